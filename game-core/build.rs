@@ -0,0 +1,50 @@
+// Generates the embedded level list from the YAML files in levels/ at the workspace root, so the
+// desktop game, the WASM build and the test runner all compile in the same levels. levels/order.txt
+// gives the play order (one file name without extension per line, # for comments); without it the
+// files go in alphabetical order. The generated file only names the files: their text is pulled in
+// with include_str!, so editing a level is enough to rebuild with it.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let levels_dir = manifest_dir.join("..").join("levels");
+    println!("cargo:rerun-if-changed={}", levels_dir.display());
+
+    let files = level_files(&levels_dir);
+    let mut generated = String::from("// Generated by game-core/build.rs from levels/; edit the YAML files, not this\n");
+    generated.push_str("pub static EMBEDDED_LEVEL_FILES: &[(&str, &str)] = &[\n");
+    for path in &files {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let name = path.file_name().unwrap().to_string_lossy();
+        generated.push_str(&format!("    ({:?}, include_str!({:?})),\n", name, path.display().to_string()));
+    }
+    generated.push_str("];\n");
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("embedded_levels.rs");
+    fs::write(out, generated).expect("failed to write the embedded level list");
+}
+
+fn level_files(dir: &Path) -> Vec<PathBuf> {
+    let order = dir.join("order.txt");
+    println!("cargo:rerun-if-changed={}", order.display());
+    if let Ok(content) = fs::read_to_string(&order) {
+        let names = content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+        return names
+            .map(|name| {
+                let path = dir.join(format!("{}.yaml", name));
+                assert!(path.exists(), "levels/order.txt names '{}', but there's no {}", name, path.display());
+                path
+            })
+            .collect();
+    }
+
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    files.retain(|path| path.extension().is_some_and(|ext| ext == "yaml" || ext == "yml"));
+    files.sort();
+    files
+}
@@ -0,0 +1,180 @@
+// Code quality metrics for a finished program, shown with the level result: how many robot
+// actions it makes, its lines of code, a rough cyclomatic complexity and runs of the same commands
+// written out one after another where a loop would do.
+
+use crate::ast::parse_program;
+use proc_macro2::{Spacing, TokenStream, TokenTree};
+use syn::spanned::Spanned;
+use syn::{Block, Expr, Stmt};
+
+// A command or sequence written out this many times in a row is worth a loop
+const MIN_REPEATS: usize = 3;
+// Longest sequence of commands looked for
+const MAX_SEQUENCE: usize = 4;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CodeMetrics {
+    pub robot_calls: usize, // Robot actions main() makes, loops unrolled
+    pub lines: usize,       // Lines with code, not counting blank lines and comments
+    pub complexity: usize,  // 1 + branches and loops
+    pub repeats: Vec<Repeat>,
+}
+
+/// Statements written out several times in a row
+#[derive(Clone, Debug, PartialEq)]
+pub struct Repeat {
+    pub commands: Vec<String>, // The repeated statements as written, e.g. move_bot(right)
+    pub times: usize,
+    pub line: usize, // Where the run starts
+}
+
+/// Metrics for `code`; None when it doesn't parse
+pub fn code_metrics(code: &str) -> Option<CodeMetrics> {
+    let file = syn::parse_file(code).ok()?;
+    let robot_calls = parse_program(code).map_or(0, |program| program.calls.len());
+    let lines = code.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        .count();
+    let complexity = 1 + code.parse::<TokenStream>().map_or(0, branch_count);
+
+    let mut repeats = Vec::new();
+    for item in &file.items {
+        if let syn::Item::Fn(function) = item {
+            find_repeats(code, &function.block, &mut repeats);
+        }
+    }
+    repeats.sort_by_key(|repeat| repeat.line);
+    Some(CodeMetrics { robot_calls, lines, complexity, repeats })
+}
+
+/// `if`, `while`, `for` and `loop`, plus every match arm after a match's first
+fn branch_count(stream: TokenStream) -> usize {
+    let (mut branches, mut matches, mut arms) = (0, 0, 0);
+    count_branches(stream, &mut branches, &mut matches, &mut arms);
+    branches + arms.saturating_sub(matches)
+}
+
+fn count_branches(stream: TokenStream, branches: &mut usize, matches: &mut usize, arms: &mut usize) {
+    let mut tokens = stream.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) => count_branches(group.stream(), branches, matches, arms),
+            TokenTree::Ident(ident) => match ident.to_string().as_str() {
+                "if" | "while" | "for" | "loop" => *branches += 1,
+                "match" => *matches += 1,
+                _ => {}
+            },
+            TokenTree::Punct(punct) if punct.as_char() == '=' && punct.spacing() == Spacing::Joint => {
+                if matches!(tokens.peek(), Some(TokenTree::Punct(next)) if next.as_char() == '>') {
+                    *arms += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Runs of repeated statements in `block` and every block inside it
+fn find_repeats(code: &str, block: &Block, repeats: &mut Vec<Repeat>) {
+    let statements: Vec<(String, usize)> = block.stmts.iter()
+        .map(|stmt| (statement_text(code, stmt), stmt.span().start().line))
+        .collect();
+
+    let mut i = 0;
+    while i < statements.len() {
+        // The sequence length at `i` that covers the most statements, shortest first on a tie
+        let best = (1..=MAX_SEQUENCE)
+            .map(|length| (length, repetitions(&statements, i, length)))
+            .filter(|&(_, times)| times >= MIN_REPEATS)
+            .max_by_key(|&(length, times)| (length * times, std::cmp::Reverse(length)));
+        match best {
+            Some((length, times)) => {
+                repeats.push(Repeat {
+                    commands: statements[i..i + length].iter().map(|(text, _)| text.clone()).collect(),
+                    times,
+                    line: statements[i].1,
+                });
+                i += length * times;
+            }
+            None => i += 1,
+        }
+    }
+
+    for stmt in &block.stmts {
+        let mut inner = Vec::new();
+        match stmt {
+            Stmt::Local(local) => {
+                if let Some(init) = &local.init {
+                    crate::lint::nested_blocks(&init.expr, &mut inner);
+                }
+            }
+            Stmt::Expr(expr, _) => crate::lint::nested_blocks(expr, &mut inner),
+            _ => {}
+        }
+        for inner in inner {
+            find_repeats(code, inner, repeats);
+        }
+    }
+}
+
+/// How many times the `length` statements at `start` come one after another. Only plain
+/// commands count: a `let` or a block in the sequence means it isn't a simple repeat.
+fn repetitions(statements: &[(String, usize)], start: usize, length: usize) -> usize {
+    let Some(pattern) = statements.get(start..start + length) else { return 0 };
+    if pattern.iter().any(|(text, _)| text.is_empty()) {
+        return 0;
+    }
+    let mut times = 1;
+    while statements.get(start + times * length..start + (times + 1) * length)
+        .is_some_and(|next| next.iter().zip(pattern).all(|((a, _), (b, _))| a == b))
+    {
+        times += 1;
+    }
+    times
+}
+
+/// A call statement as written without whitespace or the trailing `;`, empty for anything else
+fn statement_text(code: &str, stmt: &Stmt) -> String {
+    let is_call = matches!(stmt, Stmt::Expr(Expr::Call(_) | Expr::MethodCall(_) | Expr::Macro(_), _) | Stmt::Macro(_));
+    if !is_call {
+        return String::new();
+    }
+    let (start, end) = (stmt.span().start(), stmt.span().end());
+    let text: String = code.lines()
+        .enumerate()
+        .skip(start.line - 1)
+        .take(end.line + 1 - start.line)
+        .map(|(index, line)| {
+            let from = if index + 1 == start.line { start.column } else { 0 };
+            let to = if index + 1 == end.line { end.column } else { line.chars().count() };
+            line.chars().skip(from).take(to.saturating_sub(from)).collect::<String>()
+        })
+        .collect();
+    text.chars().filter(|c| !c.is_whitespace()).collect::<String>().trim_end_matches(';').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_calls_lines_and_branches() {
+        let code = "// Walk and look around\nfn main() {\n\n    for _ in 0..3 {\n        move_bot(\"right\");\n    }\n    if true { grab(); } else { scan(\"up\"); }\n    match 1 {\n        1 => grab(),\n        _ => {}\n    }\n}\n";
+        let metrics = code_metrics(code).unwrap();
+        assert_eq!(metrics.robot_calls, 5);
+        assert_eq!(metrics.lines, 10);
+        assert_eq!(metrics.complexity, 4);
+        assert!(metrics.repeats.is_empty());
+    }
+
+    #[test]
+    fn finds_repeated_commands_and_sequences() {
+        let code = "fn main() {\n    move_bot(\"right\");\n    move_bot(\"right\");\n    move_bot(\"right\");\n    grab();\n    move_bot(\"down\"); scan(\"up\");\n    move_bot(\"down\"); scan(\"up\");\n    move_bot(\"down\"); scan(\"up\");\n}";
+        let repeats = code_metrics(code).unwrap().repeats;
+        assert_eq!(repeats, vec![
+            Repeat { commands: vec!["move_bot(\"right\")".to_string()], times: 3, line: 2 },
+            Repeat { commands: vec!["move_bot(\"down\")".to_string(), "scan(\"up\")".to_string()], times: 3, line: 6 },
+        ]);
+    }
+}
@@ -0,0 +1,1186 @@
+// Syntax tree front-end for robot programs. The program is parsed with syn and main() is walked
+// statement by statement: calls into the player's own functions are followed, loops with known
+// bounds are unrolled, and integer, bool and string variables are tracked so conditions and loop
+// ranges that don't depend on the game can be worked out ahead of time.
+//
+// Anything whose outcome depends on what happens in the game (a condition on a scan result, a loop
+// over a sensor reading) can't be known before the robot moves. Those are walked once down their
+// first path and reported as diagnostics so the player knows what the robot will actually do.
+//
+// Async code runs on a simulated clock: every robot action takes ACTION_MS, sleeps and timers move
+// the clock forward, spawned tasks start at the spawner's time and awaiting or joining them waits
+// for them to finish. The calls of all tasks are then put in time order, and spawned tasks still
+// running when main() finishes are dropped like a real runtime would.
+
+use crate::interpreter::{FunctionCall, RustFunction};
+use crate::random::SeededRandom;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{BinOp, Block, Expr, Lit, Pat, RangeLimits, Stmt, UnOp};
+
+// Limits that keep a runaway program from hanging the parser
+const MAX_LOOP_ITERATIONS: usize = 1000;
+const MAX_STEPS: usize = 100_000;
+
+// Simulated milliseconds one robot action takes in async code
+const ACTION_MS: u64 = 100;
+
+/// Something in the program the robot can't run the way the code reads. Lines and columns start at 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub(crate) fn at(span: proc_macro2::Span, message: impl Into<String>) -> Self {
+        let start = span.start();
+        Self { line: start.line, column: start.column + 1, message: message.into() }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// What a robot program does when main() runs
+#[derive(Clone, Debug, Default)]
+pub struct Program {
+    pub calls: Vec<FunctionCall>,
+    pub outputs: Vec<String>, // "stdout: ...", "stderr: ..." and "panic: ..." in the order they happen
+    pub diagnostics: Vec<Diagnostic>,
+    pub environments: Vec<Environment>, // The variables in scope when each of `calls` runs
+    pub call_positions: Vec<(usize, usize)>, // Line and column (from 1) each of `calls` is made at
+}
+
+/// The variables of the function a robot action is called from, as they are at that point
+#[derive(Clone, Debug, Default)]
+pub struct Environment {
+    pub function: String,
+    names: Vec<String>, // In the order they were bound
+    vars: HashMap<String, Value>,
+}
+
+impl Environment {
+    /// Each variable with its value, or "?" when the value depends on the game
+    pub fn bindings(&self) -> Vec<(String, String)> {
+        self.names.iter()
+            .map(|name| (name.clone(), self.vars.get(name).map_or_else(|| "?".to_string(), Value::render)))
+            .collect()
+    }
+
+    /// Work out `expression` with these variables, like `steps * 2` or `direction == "up"`
+    pub fn evaluate(&self, expression: &str) -> Result<String, String> {
+        let expr: Expr = syn::parse_str(expression).map_err(|e| e.to_string())?;
+        let functions = HashMap::new();
+        let mut walker = Walker::new(&functions, None);
+        walker.vars = self.vars.clone();
+        walker.eval(&expr).map(|value| value.render()).ok_or_else(|| "?".to_string())
+    }
+}
+
+/// Parse `code` and walk its main(). Fails with the syntax error, or when there is no main().
+/// random_int() isn't known without a seed; see `parse_program_with_seed`.
+pub fn parse_program(code: &str) -> Result<Program, Diagnostic> {
+    parse(code, None)
+}
+
+/// Like `parse_program`, with random_int() drawing from the level seed `seed`
+pub fn parse_program_with_seed(code: &str, seed: u64) -> Result<Program, Diagnostic> {
+    parse(code, Some(SeededRandom::new(seed)))
+}
+
+fn parse(code: &str, random: Option<SeededRandom>) -> Result<Program, Diagnostic> {
+    let file = syn::parse_file(code).map_err(|e| Diagnostic::at(e.span(), e.to_string()))?;
+    let functions: HashMap<String, &syn::ItemFn> = file.items.iter()
+        .filter_map(|item| match item {
+            syn::Item::Fn(function) => Some((function.sig.ident.to_string(), function)),
+            _ => None,
+        })
+        .collect();
+    let main = functions.get("main").ok_or(Diagnostic { line: 1, column: 1, message: "No `fn main()` found".to_string() })?;
+
+    let mut walker = Walker::new(&functions, random);
+    walker.stack.push("main".to_string());
+    walker.walk_block(&main.block);
+    Ok(walker.finish())
+}
+
+/// Top-level structs in `code` that derive both Serialize and Deserialize and have no generic
+/// parameters, in the order they're declared. Code that doesn't parse has none.
+pub fn serde_structs(code: &str) -> Vec<String> {
+    let Ok(file) = syn::parse_file(code) else { return Vec::new() };
+    file.items.iter()
+        .filter_map(|item| match item {
+            syn::Item::Struct(item) if item.generics.params.is_empty() => Some(item),
+            _ => None,
+        })
+        .filter(|item| {
+            let derived: Vec<String> = item.attrs.iter()
+                .filter(|attr| attr.path().is_ident("derive"))
+                .filter_map(|attr| attr.parse_args_with(Punctuated::<syn::Path, syn::Token![,]>::parse_terminated).ok())
+                .flatten()
+                .filter_map(|path| path.segments.last().map(|segment| segment.ident.to_string()))
+                .collect();
+            derived.iter().any(|name| name == "Serialize") && derived.iter().any(|name| name == "Deserialize")
+        })
+        .map(|item| item.ident.to_string())
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Int(i64),
+    Bool(bool),
+    Str(String),
+    Task(usize), // Handle of a spawned async task
+}
+
+impl Value {
+    /// Like Display, with strings quoted the way they're written in code
+    fn render(&self) -> String {
+        match self {
+            Value::Str(s) => format!("{:?}", s),
+            value => value.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Str(s) => write!(f, "{}", s),
+            Value::Task(id) => write!(f, "task {}", id),
+        }
+    }
+}
+
+/// How a statement left the block it's in
+#[derive(Clone, Copy, PartialEq)]
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return,
+    Halt, // panic!, or the step limit was hit
+}
+
+struct Walker<'a> {
+    functions: &'a HashMap<String, &'a syn::ItemFn>,
+    program: Program,
+    stack: Vec<String>, // Functions being walked, to catch recursion
+    names: Vec<String>, // Variables the current function has bound so far
+    vars: HashMap<String, Value>, // Variables of the current function whose values are known
+    random: Option<RefCell<SeededRandom>>, // Numbers for random_int(); each evaluation draws the next one
+    steps: usize,
+    clock: u64, // Simulated time of the task being walked, in ms
+    task: usize, // Task being walked; 0 is main()
+    task_ends: Vec<u64>, // When each spawned task finishes; task n is at n - 1
+    spawned: HashMap<(usize, usize), usize>, // Task last spawned by each spawn() call, by line and column
+    call_times: Vec<(u64, usize)>, // Time and task of each of `program.calls`
+    output_times: Vec<(u64, usize)>, // Time and task of each of `program.outputs`
+}
+
+impl<'a> Walker<'a> {
+    fn new(functions: &'a HashMap<String, &'a syn::ItemFn>, random: Option<SeededRandom>) -> Self {
+        Self {
+            functions,
+            program: Program::default(),
+            stack: Vec::new(),
+            names: Vec::new(),
+            vars: HashMap::new(),
+            random: random.map(RefCell::new),
+            steps: 0,
+            clock: 0,
+            task: 0,
+            task_ends: Vec::new(),
+            spawned: HashMap::new(),
+            call_times: Vec::new(),
+            output_times: Vec::new(),
+        }
+    }
+
+    /// The walked program, with the calls and output of async tasks in time order
+    fn finish(mut self) -> Program {
+        if self.task_ends.is_empty() {
+            return self.program;
+        }
+        let main_end = self.clock;
+        let keep = |(time, task): (u64, usize)| task == 0 || time < main_end;
+
+        let mut calls: Vec<_> = std::mem::take(&mut self.program.calls).into_iter()
+            .zip(std::mem::take(&mut self.program.environments))
+            .zip(std::mem::take(&mut self.program.call_positions))
+            .zip(self.call_times)
+            .filter(|(_, time)| keep(*time))
+            .collect();
+        calls.sort_by_key(|(_, (time, _))| *time);
+        let (calls, positions): (Vec<_>, Vec<_>) = calls.into_iter().map(|(call, _)| call).unzip();
+        (self.program.calls, self.program.environments) = calls.into_iter().unzip();
+        self.program.call_positions = positions;
+
+        let mut outputs: Vec<_> = std::mem::take(&mut self.program.outputs).into_iter()
+            .zip(self.output_times)
+            .filter(|(_, time)| keep(*time))
+            .collect();
+        outputs.sort_by_key(|(_, (time, _))| *time);
+        self.program.outputs = outputs.into_iter().map(|(output, _)| output).collect();
+        self.program
+    }
+
+    fn diagnose(&mut self, span: proc_macro2::Span, message: impl Into<String>) {
+        let diagnostic = Diagnostic::at(span, message);
+        // Loop bodies are walked many times; report each problem once
+        if !self.program.diagnostics.contains(&diagnostic) {
+            self.program.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Give variable `name` a value, or forget it when the value depends on the game
+    fn bind(&mut self, name: String, value: Option<Value>) {
+        if !self.names.contains(&name) {
+            self.names.push(name.clone());
+        }
+        match value {
+            Some(value) => self.vars.insert(name, value),
+            None => self.vars.remove(&name),
+        };
+    }
+
+    fn walk_block(&mut self, block: &Block) -> Flow {
+        for stmt in &block.stmts {
+            self.steps += 1;
+            if self.steps > MAX_STEPS {
+                self.diagnose(stmt.span(), format!("The program runs more than {} steps; the rest is ignored", MAX_STEPS));
+                return Flow::Halt;
+            }
+            let flow = match stmt {
+                Stmt::Local(local) => {
+                    let Some(init) = &local.init else { continue };
+                    let flow = self.walk_expr(&init.expr);
+                    if let Some(name) = pattern_name(&local.pat) {
+                        let value = self.eval(&init.expr);
+                        self.bind(name, value);
+                    }
+                    flow
+                }
+                Stmt::Expr(expr, _) => self.walk_expr(expr),
+                Stmt::Macro(stmt) => self.walk_macro(&stmt.mac),
+                Stmt::Item(_) => Flow::Next,
+            };
+            if flow != Flow::Next {
+                return flow;
+            }
+        }
+        Flow::Next
+    }
+
+    fn walk_exprs<'e>(&mut self, exprs: impl IntoIterator<Item = &'e Expr>) -> Flow {
+        for expr in exprs {
+            let flow = self.walk_expr(expr);
+            if flow != Flow::Next {
+                return flow;
+            }
+        }
+        Flow::Next
+    }
+
+    fn walk_expr(&mut self, expr: &Expr) -> Flow {
+        match expr {
+            Expr::Call(call) => {
+                let Expr::Path(path) = &*call.func else { return self.walk_exprs(&call.args) };
+                let name = path_name(&path.path);
+                if let Some(function) = robot_function(&name) {
+                    self.push_call(function, &name, &call.args, None, None, expr.span());
+                    Flow::Next
+                } else if let Some(function) = self.functions.get(name.as_str()).copied() {
+                    let flow = self.walk_exprs(&call.args);
+                    if flow != Flow::Next {
+                        return flow;
+                    }
+                    self.call_function(function, &call.args, expr.span())
+                } else if let Some(flow) = self.walk_runtime_call(&name, &call.args, expr.span()) {
+                    flow
+                } else {
+                    self.walk_exprs(&call.args)
+                }
+            }
+            Expr::MethodCall(call) => {
+                // robot.action() is the same as action(); robot(n).action() only applies to robot n
+                if let Some(robot) = robot_receiver(&call.receiver) {
+                    let name = call.method.unraw().to_string();
+                    if let Some((function, direction)) = robot_method(&name) {
+                        self.push_call(function, &name, &call.args, robot, direction, expr.span());
+                        return Flow::Next;
+                    }
+                }
+                let flow = self.walk_expr(&call.receiver);
+                if flow != Flow::Next {
+                    return flow;
+                }
+                // executor.spawn(future)
+                if call.method == "spawn" && call.args.len() == 1 {
+                    return self.spawn(&call.args[0], expr.span());
+                }
+                self.walk_exprs(&call.args)
+            }
+            Expr::Macro(mac) => self.walk_macro(&mac.mac),
+            Expr::Block(block) => self.walk_block(&block.block),
+            Expr::Unsafe(block) => self.walk_block(&block.block),
+            Expr::If(if_expr) => {
+                let flow = self.walk_expr(&if_expr.cond);
+                if flow != Flow::Next {
+                    return flow;
+                }
+                match self.eval(&if_expr.cond) {
+                    Some(Value::Bool(true)) => self.walk_block(&if_expr.then_branch),
+                    Some(Value::Bool(false)) => match &if_expr.else_branch {
+                        Some((_, else_expr)) => self.walk_expr(else_expr),
+                        None => Flow::Next,
+                    },
+                    _ => {
+                        self.diagnose(if_expr.cond.span(), "This condition depends on the game, so the robot always takes the first branch");
+                        self.walk_block(&if_expr.then_branch)
+                    }
+                }
+            }
+            Expr::ForLoop(for_loop) => {
+                let flow = self.walk_expr(&for_loop.expr);
+                if flow != Flow::Next {
+                    return flow;
+                }
+                let Some((start, end)) = self.eval_range(&for_loop.expr) else {
+                    self.diagnose(for_loop.expr.span(), "Only loops over number ranges like 0..3 are repeated; this body runs once");
+                    return loop_flow(self.walk_block(&for_loop.body));
+                };
+                let name = pattern_name(&for_loop.pat);
+                if end - start > MAX_LOOP_ITERATIONS as i64 {
+                    self.diagnose(for_loop.expr.span(), format!("Loops are cut off after {} iterations", MAX_LOOP_ITERATIONS));
+                }
+                for i in (start..end).take(MAX_LOOP_ITERATIONS) {
+                    if let Some(name) = &name {
+                        self.bind(name.clone(), Some(Value::Int(i)));
+                    }
+                    match self.walk_block(&for_loop.body) {
+                        Flow::Break => break,
+                        Flow::Next | Flow::Continue => {}
+                        flow => return flow,
+                    }
+                }
+                Flow::Next
+            }
+            Expr::While(while_loop) => {
+                for iteration in 0.. {
+                    let flow = self.walk_expr(&while_loop.cond);
+                    if flow != Flow::Next {
+                        return flow;
+                    }
+                    match self.eval(&while_loop.cond) {
+                        Some(Value::Bool(false)) => break,
+                        Some(Value::Bool(true)) => {}
+                        _ => {
+                            self.diagnose(while_loop.cond.span(), "This loop condition depends on the game, so the body runs once");
+                            return loop_flow(self.walk_block(&while_loop.body));
+                        }
+                    }
+                    if iteration == MAX_LOOP_ITERATIONS {
+                        self.diagnose(while_loop.cond.span(), format!("Loops are cut off after {} iterations", MAX_LOOP_ITERATIONS));
+                        break;
+                    }
+                    match self.walk_block(&while_loop.body) {
+                        Flow::Break => break,
+                        Flow::Next | Flow::Continue => {}
+                        flow => return flow,
+                    }
+                }
+                Flow::Next
+            }
+            Expr::Loop(loop_expr) => {
+                for _ in 0..MAX_LOOP_ITERATIONS {
+                    match self.walk_block(&loop_expr.body) {
+                        Flow::Break => return Flow::Next,
+                        Flow::Next | Flow::Continue => {}
+                        flow => return flow,
+                    }
+                }
+                // Background tasks loop until main() finishes; that's only a problem in main()
+                if self.task == 0 {
+                    self.diagnose(expr.span(), format!("This loop never reaches a break and is cut off after {} iterations", MAX_LOOP_ITERATIONS));
+                }
+                Flow::Next
+            }
+            Expr::Match(match_expr) => {
+                let flow = self.walk_expr(&match_expr.expr);
+                if flow != Flow::Next {
+                    return flow;
+                }
+                let value = self.eval(&match_expr.expr);
+                let arm = match &value {
+                    Some(value) => match_expr.arms.iter().find(|arm| arm.guard.is_none() && pattern_matches(&arm.pat, value)),
+                    None => None,
+                };
+                let arm = match arm {
+                    Some(arm) => arm,
+                    None => {
+                        self.diagnose(match_expr.expr.span(), "This match depends on the game, so the robot always takes the first arm");
+                        let Some(arm) = match_expr.arms.first() else { return Flow::Next };
+                        arm
+                    }
+                };
+                if let Pat::Ident(binding) = &arm.pat {
+                    self.bind(binding.ident.to_string(), value);
+                }
+                self.walk_expr(&arm.body)
+            }
+            Expr::Assign(assign) => {
+                let flow = self.walk_expr(&assign.right);
+                if let Some(name) = expr_name(&assign.left) {
+                    let value = self.eval(&assign.right);
+                    self.bind(name, value);
+                }
+                flow
+            }
+            Expr::Binary(binary) => {
+                let flow = self.walk_exprs([&*binary.left, &*binary.right]);
+                if let (Some(name), Some(op)) = (expr_name(&binary.left), compound_op(&binary.op)) {
+                    let value = match (self.vars.get(&name), self.eval(&binary.right)) {
+                        (Some(Value::Int(a)), Some(Value::Int(b))) => apply_int(op, *a, b),
+                        _ => None,
+                    };
+                    self.bind(name, value.map(Value::Int));
+                }
+                flow
+            }
+            Expr::Return(ret) => {
+                let flow = ret.expr.as_deref().map_or(Flow::Next, |expr| self.walk_expr(expr));
+                if flow == Flow::Halt { flow } else { Flow::Return }
+            }
+            Expr::Break(brk) => {
+                let flow = brk.expr.as_deref().map_or(Flow::Next, |expr| self.walk_expr(expr));
+                if flow == Flow::Halt { flow } else { Flow::Break }
+            }
+            Expr::Continue(_) => Flow::Continue,
+            Expr::Paren(paren) => self.walk_expr(&paren.expr),
+            Expr::Group(group) => self.walk_expr(&group.expr),
+            Expr::Reference(reference) => self.walk_expr(&reference.expr),
+            Expr::Unary(unary) => self.walk_expr(&unary.expr),
+            Expr::Field(field) => self.walk_expr(&field.base),
+            Expr::Try(try_expr) => self.walk_expr(&try_expr.expr),
+            // Futures are walked where they're created; awaiting a task waits until it's done
+            Expr::Async(async_block) => match self.walk_block(&async_block.block) {
+                Flow::Halt => Flow::Halt,
+                _ => Flow::Next,
+            },
+            Expr::Await(await_expr) => {
+                let flow = self.walk_expr(&await_expr.base);
+                if let Some(Value::Task(id)) = self.eval(&await_expr.base) {
+                    self.clock = self.clock.max(self.task_ends[id - 1]);
+                }
+                flow
+            }
+            Expr::Let(let_expr) => self.walk_expr(&let_expr.expr),
+            Expr::Index(index) => self.walk_exprs([&*index.expr, &*index.index]),
+            Expr::Tuple(tuple) => self.walk_exprs(&tuple.elems),
+            Expr::Array(array) => self.walk_exprs(&array.elems),
+            Expr::Closure(closure) => {
+                self.diagnose(closure.span(), "Closures aren't run by the robot; calls inside them are skipped");
+                Flow::Next
+            }
+            _ => Flow::Next,
+        }
+    }
+
+    /// Walk the body of one of the player's own functions with its parameters bound to the
+    /// argument values that are known
+    fn call_function(&mut self, function: &syn::ItemFn, args: &Punctuated<Expr, syn::Token![,]>, span: proc_macro2::Span) -> Flow {
+        let name = function.sig.ident.to_string();
+        if self.stack.contains(&name) {
+            self.diagnose(span, format!("Recursive calls to {}() aren't followed", name));
+            return Flow::Next;
+        }
+        let mut names = Vec::new();
+        let mut vars = HashMap::new();
+        for (input, arg) in function.sig.inputs.iter().zip(args) {
+            if let syn::FnArg::Typed(typed) = input
+                && let Some(param) = pattern_name(&typed.pat)
+            {
+                if let Some(value) = self.eval(arg) {
+                    vars.insert(param.clone(), value);
+                }
+                names.push(param);
+            }
+        }
+        let caller_names = std::mem::replace(&mut self.names, names);
+        let caller_vars = std::mem::replace(&mut self.vars, vars);
+        self.stack.push(name);
+        let flow = self.walk_block(&function.block);
+        self.stack.pop();
+        self.names = caller_names;
+        self.vars = caller_vars;
+        if flow == Flow::Halt { flow } else { Flow::Next }
+    }
+
+    /// Async runtime functions: spawning tasks, sleeping and joining futures. None for any other call.
+    fn walk_runtime_call(&mut self, name: &str, args: &Punctuated<Expr, syn::Token![,]>, span: proc_macro2::Span) -> Option<Flow> {
+        let function = name.rsplit("::").next().unwrap_or(name);
+        Some(match function {
+            "spawn" | "spawn_local" if args.len() == 1 => self.spawn(&args[0], span),
+            "sleep" | "after" if name == "sleep" || name.ends_with("::sleep") || name.ends_with("Timer::after") => {
+                let flow = self.walk_exprs(args);
+                match args.first().and_then(|arg| self.eval_duration(arg)) {
+                    Some(ms) => self.clock += ms,
+                    None => self.diagnose(span, "The length of this wait isn't known before the program runs, so it takes no time"),
+                }
+                flow
+            }
+            "join_all" | "try_join_all" if args.len() == 1 => match &args[0] {
+                Expr::Macro(mac) if mac.mac.path.is_ident("vec") => {
+                    let futures = mac.mac.parse_body_with(Punctuated::<Expr, syn::Token![,]>::parse_terminated).unwrap_or_default();
+                    self.join(futures.iter())
+                }
+                Expr::Array(array) => self.join(array.elems.iter()),
+                _ => return None,
+            },
+            "zip" | "join" | "try_zip" | "try_join" if name.contains("future") => self.join(args.iter()),
+            _ => return None,
+        })
+    }
+
+    /// Start `future` as a new task at the current time. The spawner carries on right away.
+    fn spawn(&mut self, future: &Expr, span: proc_macro2::Span) -> Flow {
+        let (spawner, start) = (self.task, self.clock);
+        self.task_ends.push(start);
+        let id = self.task_ends.len();
+        self.task = id;
+        let flow = self.walk_expr(future);
+        self.task_ends[id - 1] = self.clock;
+        (self.task, self.clock) = (spawner, start);
+        self.spawned.insert(span_key(span), id);
+        if flow == Flow::Halt { flow } else { Flow::Next }
+    }
+
+    /// Run `futures` side by side from the current time and wait for the slowest
+    fn join<'e>(&mut self, futures: impl IntoIterator<Item = &'e Expr>) -> Flow {
+        let start = self.clock;
+        let mut end = start;
+        for future in futures {
+            self.clock = start;
+            let flow = self.walk_expr(future);
+            if flow == Flow::Halt {
+                return flow;
+            }
+            if let Some(Value::Task(id)) = self.eval(future) {
+                self.clock = self.clock.max(self.task_ends[id - 1]);
+            }
+            end = end.max(self.clock);
+        }
+        self.clock = end;
+        Flow::Next
+    }
+
+    /// Milliseconds in `Duration::from_millis(n)`, `Duration::from_secs(n)` and the like
+    fn eval_duration(&self, expr: &Expr) -> Option<u64> {
+        let Expr::Call(call) = expr else { return None };
+        let Expr::Path(path) = &*call.func else { return None };
+        let arg = call.args.first()?;
+        let float = || match arg {
+            Expr::Lit(syn::ExprLit { lit: Lit::Float(f), .. }) => f.base10_parse::<f64>().ok(),
+            _ => self.eval_int(arg).map(|n| n as f64),
+        };
+        let ms = match path.path.segments.last()?.ident.to_string().as_str() {
+            "from_secs" => self.eval_int(arg)?.checked_mul(1000)?,
+            "from_millis" => self.eval_int(arg)?,
+            "from_micros" => self.eval_int(arg)? / 1000,
+            "from_nanos" => self.eval_int(arg)? / 1_000_000,
+            "from_secs_f32" | "from_secs_f64" => (float()? * 1000.0) as i64,
+            _ => return None,
+        };
+        u64::try_from(ms).ok()
+    }
+
+    fn walk_macro(&mut self, mac: &syn::Macro) -> Flow {
+        let Some(name) = mac.path.segments.last().map(|segment| segment.ident.to_string()) else { return Flow::Next };
+        if matches!(name.as_str(), "join" | "try_join") {
+            let futures = mac.parse_body_with(Punctuated::<Expr, syn::Token![,]>::parse_terminated).unwrap_or_default();
+            return self.join(futures.iter());
+        }
+        let prefix = match name.as_str() {
+            "println" => "stdout",
+            "eprintln" => "stderr",
+            "panic" => "panic",
+            _ => return Flow::Next,
+        };
+        let args = mac.parse_body_with(Punctuated::<Expr, syn::Token![,]>::parse_terminated).unwrap_or_default();
+        let flow = self.walk_exprs(args.iter().skip(1));
+        if flow != Flow::Next {
+            return flow;
+        }
+        let message = match args.first() {
+            Some(Expr::Lit(syn::ExprLit { lit: Lit::Str(format), .. })) => self.format_message(&format.value(), &args),
+            _ => String::new(),
+        };
+        self.program.outputs.push(format!("{}: {}", prefix, message));
+        self.output_times.push((self.clock, self.task));
+        if prefix == "panic" { Flow::Halt } else { Flow::Next }
+    }
+
+    /// Fill in the `{}` placeholders whose values are known; the others are left as written
+    fn format_message(&self, format: &str, args: &Punctuated<Expr, syn::Token![,]>) -> String {
+        let mut message = String::new();
+        let mut next_arg = 1;
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    message.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    message.push('}');
+                }
+                '{' => {
+                    let placeholder: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                    let name = placeholder.split(':').next().unwrap_or("").trim();
+                    let value = if name.is_empty() {
+                        next_arg += 1;
+                        args.iter().nth(next_arg - 1).and_then(|arg| self.eval(arg))
+                    } else if let Ok(index) = name.parse::<usize>() {
+                        args.iter().nth(index + 1).and_then(|arg| self.eval(arg))
+                    } else {
+                        self.vars.get(name).cloned()
+                    };
+                    match value {
+                        Some(value) => message.push_str(&value.to_string()),
+                        None => message.push_str(&format!("{{{}}}", placeholder)),
+                    }
+                }
+                _ => message.push(c),
+            }
+        }
+        message
+    }
+
+    /// Add a call to `function`. `direction` is set for methods that name their direction, like
+    /// robot.move_up(); those take no arguments.
+    fn push_call(&mut self, function: RustFunction, name: &str, args: &Punctuated<Expr, syn::Token![,]>, robot: Option<usize>, direction: Option<(i32, i32)>, span: proc_macro2::Span) {
+        let args: Vec<&Expr> = args.iter().collect();
+        let mut call = FunctionCall {
+            function,
+            direction: None,
+            coordinates: None,
+            level_number: None,
+            boolean_param: None,
+            message: None,
+            robot,
+        };
+        let valid = match function {
+            _ if direction.is_some() => {
+                call.direction = direction;
+                args.is_empty()
+            }
+            RustFunction::Move | RustFunction::LaserDirection => {
+                call.direction = args.first().and_then(|arg| self.eval_direction(arg)).filter(|d| *d != (0, 0));
+                call.direction.is_some()
+            }
+            // scan() without a direction scans the way the robot is facing
+            RustFunction::Scan => {
+                call.direction = args.first().and_then(|arg| self.eval_direction(arg));
+                args.is_empty() || call.direction.is_some()
+            }
+            RustFunction::Teleport | RustFunction::LaserTile => {
+                call.coordinates = self.eval_coordinates(&args);
+                call.coordinates.is_some()
+            }
+            RustFunction::Rewind | RustFunction::Emp => {
+                call.level_number = args.first().and_then(|arg| self.eval_int(arg)).and_then(|n| usize::try_from(n).ok());
+                call.level_number.is_some()
+            }
+            RustFunction::SelectRobot => {
+                call.robot = args.first().and_then(|arg| self.eval_int(arg)).and_then(|n| usize::try_from(n).ok());
+                call.robot.is_some()
+            }
+            RustFunction::OpenDoor | RustFunction::SetAutoGrab => {
+                call.boolean_param = match args.first().and_then(|arg| self.eval(arg)) {
+                    Some(Value::Bool(open)) => Some(open),
+                    _ => None,
+                };
+                call.boolean_param.is_some()
+            }
+            RustFunction::Say => {
+                call.message = args.first().and_then(|arg| self.eval_text(arg));
+                call.message.is_some()
+            }
+            _ => true,
+        };
+        if valid {
+            self.call_times.push((self.clock, self.task));
+            self.clock += ACTION_MS;
+            self.program.calls.push(call);
+            self.program.environments.push(Environment {
+                function: self.stack.last().cloned().unwrap_or_default(),
+                names: self.names.clone(),
+                vars: self.vars.clone(),
+            });
+            let (line, column) = span_key(span);
+            self.program.call_positions.push((line, column + 1));
+        } else {
+            self.diagnose(span, format!("{}() was skipped: {}", name, argument_hint(function)));
+        }
+    }
+
+    /// Text passed to say(): a string, `&format!(...)` or anything else with a known value
+    fn eval_text(&self, expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Reference(reference) => self.eval_text(&reference.expr),
+            Expr::Paren(paren) => self.eval_text(&paren.expr),
+            Expr::Macro(mac) if mac.mac.path.is_ident("format") => {
+                let args = mac.mac.parse_body_with(Punctuated::<Expr, syn::Token![,]>::parse_terminated).ok()?;
+                match args.first()? {
+                    Expr::Lit(syn::ExprLit { lit: Lit::Str(format), .. }) => Some(self.format_message(&format.value(), &args)),
+                    _ => None,
+                }
+            }
+            _ => self.eval(expr).map(|value| value.to_string()),
+        }
+    }
+
+    fn eval_direction(&self, expr: &Expr) -> Option<(i32, i32)> {
+        // A variable holding a direction name, a string literal, or a bare `up` / `Direction::Up`
+        let name = match (self.eval(expr), expr) {
+            (Some(Value::Str(name)), _) => name,
+            (None, Expr::Path(path)) => path.path.segments.last()?.ident.to_string(),
+            _ => return None,
+        };
+        match name.to_lowercase().as_str() {
+            "up" => Some((0, -1)),
+            "down" => Some((0, 1)),
+            "left" => Some((-1, 0)),
+            "right" => Some((1, 0)),
+            "current" => Some((0, 0)),
+            _ => None,
+        }
+    }
+
+    /// `(x, y)` from two arguments or from one tuple argument
+    fn eval_coordinates(&self, args: &[&Expr]) -> Option<(i32, i32)> {
+        let (x, y) = match args {
+            [x, y] => (*x, *y),
+            [Expr::Tuple(tuple)] if tuple.elems.len() == 2 => (&tuple.elems[0], &tuple.elems[1]),
+            [Expr::Paren(paren)] => return self.eval_coordinates(&[&paren.expr]),
+            _ => return None,
+        };
+        Some((i32::try_from(self.eval_int(x)?).ok()?, i32::try_from(self.eval_int(y)?).ok()?))
+    }
+
+    fn eval_int(&self, expr: &Expr) -> Option<i64> {
+        match self.eval(expr)? {
+            Value::Int(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// `start..end` or `start..=end` as a half-open integer range
+    fn eval_range(&self, expr: &Expr) -> Option<(i64, i64)> {
+        match expr {
+            Expr::Range(range) => {
+                let start = self.eval_int(range.start.as_deref()?)?;
+                let end = self.eval_int(range.end.as_deref()?)?;
+                match range.limits {
+                    RangeLimits::HalfOpen(_) => Some((start, end.max(start))),
+                    RangeLimits::Closed(_) => Some((start, (end + 1).max(start))),
+                }
+            }
+            Expr::Paren(paren) => self.eval_range(&paren.expr),
+            _ => None,
+        }
+    }
+
+    /// The value of `expr` if it can be worked out without running the game
+    fn eval(&self, expr: &Expr) -> Option<Value> {
+        match expr {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Int(n) => n.base10_parse().ok().map(Value::Int),
+                Lit::Bool(b) => Some(Value::Bool(b.value)),
+                Lit::Str(s) => Some(Value::Str(s.value())),
+                _ => None,
+            },
+            Expr::Path(_) => self.vars.get(&expr_name(expr)?).cloned(),
+            Expr::Paren(paren) => self.eval(&paren.expr),
+            Expr::Group(group) => self.eval(&group.expr),
+            Expr::Reference(reference) => self.eval(&reference.expr),
+            Expr::Unary(unary) => match (&unary.op, self.eval(&unary.expr)?) {
+                (UnOp::Neg(_), Value::Int(n)) => n.checked_neg().map(Value::Int),
+                (UnOp::Not(_), Value::Bool(b)) => Some(Value::Bool(!b)),
+                _ => None,
+            },
+            Expr::Binary(binary) => {
+                let (left, right) = (self.eval(&binary.left)?, self.eval(&binary.right)?);
+                match (left, right) {
+                    (Value::Int(a), Value::Int(b)) => match binary.op {
+                        BinOp::Eq(_) => Some(Value::Bool(a == b)),
+                        BinOp::Ne(_) => Some(Value::Bool(a != b)),
+                        BinOp::Lt(_) => Some(Value::Bool(a < b)),
+                        BinOp::Le(_) => Some(Value::Bool(a <= b)),
+                        BinOp::Gt(_) => Some(Value::Bool(a > b)),
+                        BinOp::Ge(_) => Some(Value::Bool(a >= b)),
+                        op => apply_int(op, a, b).map(Value::Int),
+                    },
+                    (Value::Bool(a), Value::Bool(b)) => match binary.op {
+                        BinOp::And(_) => Some(Value::Bool(a && b)),
+                        BinOp::Or(_) => Some(Value::Bool(a || b)),
+                        BinOp::Eq(_) => Some(Value::Bool(a == b)),
+                        BinOp::Ne(_) => Some(Value::Bool(a != b)),
+                        _ => None,
+                    },
+                    (Value::Str(a), Value::Str(b)) => match binary.op {
+                        BinOp::Eq(_) => Some(Value::Bool(a == b)),
+                        BinOp::Ne(_) => Some(Value::Bool(a != b)),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            }
+            Expr::Call(_) | Expr::MethodCall(_) if self.spawned.contains_key(&span_key(expr.span())) => {
+                self.spawned.get(&span_key(expr.span())).copied().map(Value::Task)
+            }
+            Expr::Call(call) if matches!(&*call.func, Expr::Path(path) if path.path.is_ident("random_int")) => {
+                let [min, max] = [call.args.first()?, call.args.get(1)?].map(|arg| self.eval_int(arg));
+                self.random.as_ref()?.borrow_mut().int_between(min?, max?).map(Value::Int)
+            }
+            Expr::MethodCall(call) if call.args.is_empty() => match (call.method.to_string().as_str(), self.eval(&call.receiver)?) {
+                ("to_string" | "to_owned" | "clone", value) => Some(value),
+                ("len", Value::Str(s)) => Some(Value::Int(s.len() as i64)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Break and continue don't leave the loop they're in
+fn loop_flow(flow: Flow) -> Flow {
+    match flow {
+        Flow::Break | Flow::Continue => Flow::Next,
+        flow => flow,
+    }
+}
+
+fn apply_int(op: BinOp, a: i64, b: i64) -> Option<i64> {
+    match op {
+        BinOp::Add(_) => a.checked_add(b),
+        BinOp::Sub(_) => a.checked_sub(b),
+        BinOp::Mul(_) => a.checked_mul(b),
+        BinOp::Div(_) => a.checked_div(b),
+        BinOp::Rem(_) => a.checked_rem(b),
+        _ => None,
+    }
+}
+
+/// The plain operator behind `+=`, `-=` and friends
+fn compound_op(op: &BinOp) -> Option<BinOp> {
+    match op {
+        BinOp::AddAssign(_) => Some(BinOp::Add(Default::default())),
+        BinOp::SubAssign(_) => Some(BinOp::Sub(Default::default())),
+        BinOp::MulAssign(_) => Some(BinOp::Mul(Default::default())),
+        BinOp::DivAssign(_) => Some(BinOp::Div(Default::default())),
+        BinOp::RemAssign(_) => Some(BinOp::Rem(Default::default())),
+        _ => None,
+    }
+}
+
+/// Line and column where `span` starts, to recognize the same expression on a later visit
+fn span_key(span: proc_macro2::Span) -> (usize, usize) {
+    let start = span.start();
+    (start.line, start.column)
+}
+
+fn path_name(path: &syn::Path) -> String {
+    path.segments.iter().map(|segment| segment.ident.unraw().to_string()).collect::<Vec<_>>().join("::")
+}
+
+/// The variable a plain `name` expression refers to
+fn expr_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(path) => path.path.get_ident().map(|ident| ident.to_string()),
+        _ => None,
+    }
+}
+
+/// The variable a `let` or `for` pattern binds, when it binds exactly one
+fn pattern_name(pat: &Pat) -> Option<String> {
+    match pat {
+        Pat::Ident(binding) if binding.subpat.is_none() => Some(binding.ident.to_string()),
+        Pat::Type(typed) => pattern_name(&typed.pat),
+        _ => None,
+    }
+}
+
+fn pattern_matches(pat: &Pat, value: &Value) -> bool {
+    match pat {
+        Pat::Wild(_) | Pat::Ident(_) => true,
+        Pat::Lit(lit) => match (&lit.lit, value) {
+            (Lit::Int(n), Value::Int(v)) => n.base10_parse::<i64>().is_ok_and(|n| n == *v),
+            (Lit::Bool(b), Value::Bool(v)) => b.value == *v,
+            (Lit::Str(s), Value::Str(v)) => s.value() == *v,
+            _ => false,
+        },
+        Pat::Or(or) => or.cases.iter().any(|case| pattern_matches(case, value)),
+        _ => false,
+    }
+}
+
+/// `robot` in `robot.action()` (Some(None)) or `robot(n)` in `robot(n).action()` (Some(Some(n)))
+fn robot_receiver(expr: &Expr) -> Option<Option<usize>> {
+    match expr {
+        Expr::Path(path) if path.path.is_ident("robot") => Some(None),
+        Expr::Call(call) => {
+            let Expr::Path(path) = &*call.func else { return None };
+            if !path.path.is_ident("robot") || call.args.len() != 1 {
+                return None;
+            }
+            match &call.args[0] {
+                Expr::Lit(syn::ExprLit { lit: Lit::Int(n), .. }) => n.base10_parse().ok().map(Some),
+                _ => None,
+            }
+        }
+        Expr::Paren(paren) => robot_receiver(&paren.expr),
+        _ => None,
+    }
+}
+
+/// Methods of the object-style API: every robot function, plus move_<direction>() and
+/// scan_<direction>() which name their direction
+fn robot_method(name: &str) -> Option<(RustFunction, Option<(i32, i32)>)> {
+    let direction = |name: &str| match name {
+        "up" => Some((0, -1)),
+        "down" => Some((0, 1)),
+        "left" => Some((-1, 0)),
+        "right" => Some((1, 0)),
+        "current" => Some((0, 0)),
+        _ => None,
+    };
+    if let Some(d) = name.strip_prefix("move_").and_then(direction).filter(|d| *d != (0, 0)) {
+        return Some((RustFunction::Move, Some(d)));
+    }
+    if let Some(d) = name.strip_prefix("scan_").and_then(direction) {
+        return Some((RustFunction::Scan, Some(d)));
+    }
+    // Paths like laser::direction aren't methods
+    robot_function(name).filter(|_| !name.contains("::")).map(|function| (function, None))
+}
+
+fn robot_function(name: &str) -> Option<RustFunction> {
+    Some(match name {
+        "move_bot" | "move" => RustFunction::Move,
+        "move_forward" => RustFunction::MoveForward,
+        "rotate_left" => RustFunction::RotateLeft,
+        "rotate_right" => RustFunction::RotateRight,
+        "grab" => RustFunction::Grab,
+        "scan" => RustFunction::Scan,
+        "laser::direction" => RustFunction::LaserDirection,
+        "laser::tile" => RustFunction::LaserTile,
+        "open_door" => RustFunction::OpenDoor,
+        "set_auto_grab" => RustFunction::SetAutoGrab,
+        "say" => RustFunction::Say,
+        "print_map" => RustFunction::PrintMap,
+        "teleport" => RustFunction::Teleport,
+        "rewind" => RustFunction::Rewind,
+        "emp" => RustFunction::Emp,
+        "select_robot" => RustFunction::SelectRobot,
+        _ => return None,
+    })
+}
+
+fn argument_hint(function: RustFunction) -> &'static str {
+    match function {
+        RustFunction::Move | RustFunction::LaserDirection => "it needs a direction: up, down, left or right",
+        RustFunction::Scan => "it needs a direction: up, down, left, right or current",
+        RustFunction::Teleport | RustFunction::LaserTile => "it needs two whole-number coordinates",
+        RustFunction::Rewind => "it needs a number of turns",
+        RustFunction::Emp => "it needs a radius in tiles, e.g. emp(2)",
+        RustFunction::SelectRobot => "it needs a robot number",
+        RustFunction::OpenDoor | RustFunction::SetAutoGrab => "it needs true or false",
+        RustFunction::Say => "it needs text, e.g. say(\"hi\") or say(&format!(\"{} left\", n))",
+        _ => "its arguments aren't known before the program runs",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn functions(program: &Program) -> Vec<RustFunction> {
+        program.calls.iter().map(|call| call.function).collect()
+    }
+
+    #[test]
+    fn follows_helper_functions_and_unrolls_ranges() {
+        let program = parse_program(r#"
+            fn step(direction: &str, times: i32) {
+                for _ in 0..times {
+                    move_bot(direction);
+                }
+            }
+            fn main() {
+                let text = "move_bot(up) is just text";
+                step("right", 2);
+                robot(1).grab();
+                println!("done after {} steps: {text}", 2);
+            }
+        "#).unwrap();
+        assert_eq!(functions(&program), vec![RustFunction::Move, RustFunction::Move, RustFunction::Grab]);
+        assert_eq!(program.calls[0].direction, Some((1, 0)));
+        assert_eq!(program.calls[2].robot, Some(1));
+        assert_eq!(program.outputs, vec!["stdout: done after 2 steps: move_bot(up) is just text"]);
+        assert!(program.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn known_conditions_pick_a_branch_and_unknown_ones_are_reported() {
+        let program = parse_program(r#"
+            fn main() {
+                let mut count = 0;
+                while count < 3 {
+                    count += 1;
+                }
+                if count == 3 { grab(); } else { scan(up); }
+                if scan(right) == "wall" { move_bot(down); }
+                panic!("stop");
+                grab();
+            }
+        "#).unwrap();
+        assert_eq!(functions(&program), vec![RustFunction::Grab, RustFunction::Scan, RustFunction::Move]);
+        assert_eq!(program.outputs, vec!["panic: stop"]);
+        assert_eq!(program.diagnostics.len(), 1);
+        assert_eq!(program.diagnostics[0].line, 8);
+    }
+
+    #[test]
+    fn object_style_calls_match_function_calls() {
+        let objects = parse_program("fn main() { robot.move_up(); robot.scan_left(); robot.scan(); robot.grab(); robot(2).move_right(); }").unwrap();
+        let functions_style = parse_program("fn main() { move_bot(up); scan(left); scan(); grab(); robot(2).move_bot(right); }").unwrap();
+        let summary = |program: &Program| program.calls.iter().map(|call| (call.function, call.direction, call.robot)).collect::<Vec<_>>();
+        assert_eq!(summary(&objects), summary(&functions_style));
+        assert!(parse_program("fn main() { robot.move_up(1); }").unwrap().calls.is_empty());
+    }
+
+    #[test]
+    fn each_call_records_the_variables_in_scope() {
+        let program = parse_program(r#"
+            fn main() {
+                let mut steps = 0;
+                let direction = "right";
+                let seen = scan(up);
+                for i in 0..2 {
+                    steps += 1;
+                    move_bot(direction);
+                }
+            }
+        "#).unwrap();
+        let last = program.environments.last().unwrap();
+        assert_eq!(program.environments.len(), program.calls.len());
+        assert_eq!(last.function, "main");
+        assert_eq!(last.bindings(), vec![
+            ("steps".to_string(), "2".to_string()),
+            ("direction".to_string(), "\"right\"".to_string()),
+            ("seen".to_string(), "?".to_string()),
+            ("i".to_string(), "1".to_string()),
+        ]);
+        assert_eq!(program.environments[1].evaluate("steps * 10 + i"), Ok("10".to_string()));
+        assert_eq!(last.evaluate("seen == \"wall\""), Err("?".to_string()));
+    }
+
+    #[test]
+    fn random_int_follows_the_seed() {
+        let code = r#"
+            fn main() {
+                let roll = random_int(1, 6);
+                if roll > 3 { grab(); } else { scan(up); }
+                println!("rolled {}", roll);
+            }
+        "#;
+        let mut random = SeededRandom::new(99);
+        let roll = random.int_between(1, 6).unwrap();
+        let program = parse_program_with_seed(code, 99).unwrap();
+        assert_eq!(functions(&program), vec![if roll > 3 { RustFunction::Grab } else { RustFunction::Scan }]);
+        assert_eq!(program.outputs, vec![format!("stdout: rolled {}", roll)]);
+        assert!(program.diagnostics.is_empty());
+        assert_eq!(parse_program(code).unwrap().diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn say_takes_strings_and_format() {
+        let code = r#"
+            fn main() {
+                let left = 3;
+                say("hi");
+                say(&format!("{} items left, {left} to go", left));
+                say(&left.to_string());
+            }
+        "#;
+        let program = parse_program(code).unwrap();
+        let messages: Vec<_> = program.calls.iter().map(|call| call.message.as_deref().unwrap()).collect();
+        assert_eq!(messages, vec!["hi", "3 items left, 3 to go", "3"]);
+    }
+
+    #[test]
+    fn async_tasks_interleave_on_the_simulated_clock() {
+        let program = parse_program(r#"
+            async fn patrol() {
+                move_bot(right);
+                smol::Timer::after(Duration::from_millis(250)).await;
+                move_bot(right);
+            }
+            fn main() {
+                smol::block_on(async {
+                    let background = smol::spawn(async {
+                        loop {
+                            scan(up);
+                            smol::Timer::after(Duration::from_millis(500)).await;
+                        }
+                    });
+                    let task = smol::spawn(patrol());
+                    grab();
+                    task.await;
+                    println!("patrol done");
+                })
+            }
+        "#).unwrap();
+        // patrol: right at 0 and 350; main: grab at 0, waits until 450; background: scans at 0 and 600
+        assert_eq!(functions(&program), vec![RustFunction::Scan, RustFunction::Move, RustFunction::Grab, RustFunction::Move]);
+        assert_eq!(program.environments.len(), program.calls.len());
+        assert_eq!(program.outputs, vec!["stdout: patrol done"]);
+        assert!(program.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn finds_structs_that_derive_both_serde_traits() {
+        let code = r#"
+            use serde::{Deserialize, Serialize};
+            #[derive(Debug, Serialize, Deserialize)]
+            struct RobotState { energy: u32 }
+            #[derive(serde::Serialize)]
+            struct Report { lines: Vec<String> }
+            #[derive(Serialize, Deserialize)]
+            struct Wrapper<T> { inner: T }
+            fn main() {}
+        "#;
+        assert_eq!(serde_structs(code), vec!["RobotState".to_string()]);
+    }
+
+    #[test]
+    fn syntax_errors_and_missing_main_fail() {
+        assert!(parse_program("fn main() { move_bot(up) ").is_err());
+        assert!(parse_program("fn helper() {}").is_err());
+    }
+}
@@ -0,0 +1,81 @@
+// Execution budget: how much a robot program may do before it's stopped. The parser already cuts
+// runaway loops off, but a program can still unroll into a very long list of actions; the game and
+// the headless runner count each one against this budget, along with the wall-clock time spent
+// running them, and abort the run with a message saying which limit it hit. The game can also
+// cancel a run from the UI, which ends it the same way.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const MAX_ACTIONS: usize = 10_000;
+pub const MAX_WALL_SECS: f64 = 30.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionBudget {
+    pub max_actions: usize,
+    pub max_wall_secs: f64, // Time spent carrying out actions, not animating them
+}
+
+impl Default for ExecutionBudget {
+    fn default() -> Self {
+        Self { max_actions: MAX_ACTIONS, max_wall_secs: MAX_WALL_SECS }
+    }
+}
+
+impl ExecutionBudget {
+    /// Whether the action about to run, after `actions` have and `elapsed_secs` went by, is still allowed
+    pub fn check(&self, actions: usize, elapsed_secs: f64) -> Result<(), Abort> {
+        if actions >= self.max_actions {
+            Err(Abort::TooManyActions(self.max_actions))
+        } else if elapsed_secs >= self.max_wall_secs {
+            Err(Abort::TimedOut(self.max_wall_secs))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Why a run was stopped before the program finished
+#[derive(Clone, Debug, PartialEq)]
+pub enum Abort {
+    TooManyActions(usize),
+    TimedOut(f64),
+    Cancelled,
+}
+
+impl fmt::Display for Abort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Abort::TooManyActions(max) => write!(f, "Execution aborted: exceeded {} actions", thousands(*max)),
+            Abort::TimedOut(secs) => write!(f, "Execution aborted: ran longer than {} seconds", secs),
+            Abort::Cancelled => write!(f, "Execution aborted: cancelled"),
+        }
+    }
+}
+
+/// 10000 -> "10,000"
+fn thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_stops_at_the_first_limit_reached() {
+        let budget = ExecutionBudget::default();
+        assert_eq!(budget.check(9_999, 1.0), Ok(()));
+        let abort = budget.check(10_000, 1.0).unwrap_err();
+        assert_eq!(abort.to_string(), "Execution aborted: exceeded 10,000 actions");
+        assert_eq!(budget.check(5, 30.0), Err(Abort::TimedOut(30.0)));
+    }
+}
@@ -0,0 +1,170 @@
+// Which robot functions a program may call. Every robot function is registered here once with the
+// name programs call it by and what unlocks it. A level can narrow the base set with
+// `allowed_functions` in its YAML; upgrades the robot has bought or picked up add theirs back on top.
+
+use crate::interpreter::{FunctionCall, RustFunction};
+use std::collections::HashSet;
+
+/// What makes a function callable
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unlock {
+    Always,                // Developer commands; levels can't take them away
+    Base,                  // Every level unless its allowed_functions leaves it out
+    Upgrade(&'static str), // Base, and brought back by the named upgrade where a level leaves it out
+    Ability(&'static str), // Only once the robot has the ability, e.g. from a teleport beacon
+    MultiRobot,            // Only on levels with more than one robot
+}
+
+pub struct Capability {
+    pub function: RustFunction,
+    pub name: &'static str, // As called in programs and listed in allowed_functions
+    pub unlock: Unlock,
+    pub in_gui: bool, // Listed in the function reference; developer commands aren't
+}
+
+/// Every robot function, in the order the function reference lists them
+pub const CAPABILITIES: [Capability; 18] = [
+    Capability { function: RustFunction::Move, name: "move_bot", unlock: Unlock::Base, in_gui: true },
+    Capability { function: RustFunction::MoveForward, name: "move_forward", unlock: Unlock::Base, in_gui: true },
+    Capability { function: RustFunction::RotateLeft, name: "rotate_left", unlock: Unlock::Base, in_gui: true },
+    Capability { function: RustFunction::RotateRight, name: "rotate_right", unlock: Unlock::Base, in_gui: true },
+    Capability { function: RustFunction::Scan, name: "scan", unlock: Unlock::Upgrade("scanner"), in_gui: true },
+    Capability { function: RustFunction::Grab, name: "grab", unlock: Unlock::Base, in_gui: true },
+    Capability { function: RustFunction::LaserDirection, name: "laser::direction", unlock: Unlock::Upgrade("attack"), in_gui: true },
+    Capability { function: RustFunction::LaserTile, name: "laser::tile", unlock: Unlock::Upgrade("attack"), in_gui: true },
+    Capability { function: RustFunction::OpenDoor, name: "open_door", unlock: Unlock::Base, in_gui: true },
+    Capability { function: RustFunction::SetAutoGrab, name: "set_auto_grab", unlock: Unlock::Base, in_gui: true },
+    Capability { function: RustFunction::PrintMap, name: "print_map", unlock: Unlock::Base, in_gui: true },
+    Capability { function: RustFunction::Say, name: "say", unlock: Unlock::Base, in_gui: true },
+    Capability { function: RustFunction::SkipLevel, name: "skip_level", unlock: Unlock::Always, in_gui: false },
+    Capability { function: RustFunction::GotoLevel, name: "goto_level", unlock: Unlock::Always, in_gui: false },
+    Capability { function: RustFunction::Teleport, name: "teleport", unlock: Unlock::Ability("teleport"), in_gui: true },
+    Capability { function: RustFunction::Rewind, name: "rewind", unlock: Unlock::Ability("rewind"), in_gui: true },
+    Capability { function: RustFunction::Emp, name: "emp", unlock: Unlock::Ability("emp"), in_gui: true },
+    Capability { function: RustFunction::SelectRobot, name: "select_robot", unlock: Unlock::MultiRobot, in_gui: true },
+];
+
+pub fn capability(function: RustFunction) -> Option<&'static Capability> {
+    CAPABILITIES.iter().find(|capability| capability.function == function)
+}
+
+/// Name programs call `function` by; println!/eprintln!/panic! aren't robot functions
+pub fn function_name(function: RustFunction) -> Option<&'static str> {
+    capability(function).map(|capability| capability.name)
+}
+
+/// A call as it would be written in a program, e.g. `move_bot("up")`
+pub fn call_label(call: &FunctionCall) -> String {
+    let name = function_name(call.function).unwrap_or("println!");
+    let argument = if let Some(direction) = call.direction {
+        let direction = match direction {
+            (0, -1) => "up",
+            (0, 1) => "down",
+            (-1, 0) => "left",
+            (1, 0) => "right",
+            _ => "current",
+        };
+        format!("\"{}\"", direction)
+    } else if let Some((x, y)) = call.coordinates {
+        format!("{}, {}", x, y)
+    } else if let Some(open) = call.boolean_param {
+        open.to_string()
+    } else if let Some(number) = call.level_number {
+        number.to_string()
+    } else {
+        String::new()
+    };
+    format!("{}({})", name, argument)
+}
+
+/// Everything that decides what the current level allows
+pub struct CapabilityContext<'a> {
+    pub allowed_functions: Option<&'a [String]>, // The level's list; None allows the whole base set
+    pub upgrades: &'a HashSet<String>,           // Bought upgrades and picked up abilities
+    pub multi_robot: bool,
+    pub unlock_all: bool, // Sandbox: everything, whatever the level says
+}
+
+impl CapabilityContext<'_> {
+    pub fn allows(&self, capability: &Capability) -> bool {
+        if self.unlock_all {
+            return true;
+        }
+        let listed = self.allowed_functions.is_none_or(|names| names.iter().any(|name| name.trim() == capability.name));
+        match capability.unlock {
+            Unlock::Always => true,
+            Unlock::Base => listed,
+            Unlock::Upgrade(upgrade) => listed || self.upgrades.contains(upgrade),
+            Unlock::Ability(ability) => self.upgrades.contains(ability),
+            Unlock::MultiRobot => self.multi_robot,
+        }
+    }
+
+    /// Functions the level allows, in registry order; `gui_only` leaves out developer commands
+    pub fn available(&self, gui_only: bool) -> Vec<RustFunction> {
+        CAPABILITIES.iter()
+            .filter(|capability| !gui_only || capability.in_gui)
+            .filter(|capability| self.allows(capability))
+            .map(|capability| capability.function)
+            .collect()
+    }
+
+    /// Names of the functions `calls` makes that the level's allowed_functions rules out, each
+    /// once. Abilities aren't included: the program may still pick one up before it gets there.
+    pub fn forbidden_calls(&self, calls: &[FunctionCall]) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        for capability in calls.iter().filter_map(|call| capability(call.function)) {
+            let can_be_forbidden = matches!(capability.unlock, Unlock::Base | Unlock::Upgrade(_));
+            if can_be_forbidden && !self.allows(capability) && !names.contains(&capability.name) {
+                names.push(capability.name);
+            }
+        }
+        names
+    }
+}
+
+/// Entries of an allowed_functions list that aren't robot functions
+pub fn unknown_function_names(names: &[String]) -> Vec<String> {
+    names.iter()
+        .filter(|name| !CAPABILITIES.iter().any(|capability| capability.name == name.trim()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_list_narrows_the_base_set_and_upgrades_add_back() {
+        let allowed = vec!["move_bot".to_string(), "grab".to_string()];
+        let mut upgrades = HashSet::new();
+        let context = CapabilityContext { allowed_functions: Some(&allowed), upgrades: &upgrades, multi_robot: false, unlock_all: false };
+        let available = context.available(false);
+        assert_eq!(available, vec![RustFunction::Move, RustFunction::Grab, RustFunction::SkipLevel, RustFunction::GotoLevel]);
+
+        upgrades.insert("scanner".to_string());
+        upgrades.insert("teleport".to_string());
+        let context = CapabilityContext { allowed_functions: Some(&allowed), upgrades: &upgrades, multi_robot: false, unlock_all: false };
+        let available = context.available(true);
+        assert_eq!(available, vec![RustFunction::Move, RustFunction::Scan, RustFunction::Grab, RustFunction::Teleport]);
+
+        let none = HashSet::new();
+        let open = CapabilityContext { allowed_functions: None, upgrades: &none, multi_robot: true, unlock_all: false };
+        assert!(open.available(true).contains(&RustFunction::SelectRobot));
+        assert!(!open.available(true).contains(&RustFunction::Rewind));
+    }
+
+    #[test]
+    fn forbidden_calls_and_unknown_names_are_reported_once() {
+        let allowed = vec!["move_bot".to_string()];
+        let upgrades = HashSet::new();
+        let context = CapabilityContext { allowed_functions: Some(&allowed), upgrades: &upgrades, multi_robot: false, unlock_all: false };
+        let code = "fn main() { scan(\"up\"); move_bot(\"up\"); teleport(1, 1); scan(\"left\"); }";
+        let calls = crate::interpreter::parse_rust_code_from_main(code, 0);
+        assert_eq!(context.forbidden_calls(&calls), vec!["scan"]);
+        assert_eq!(call_label(&calls[0]), "scan(\"up\")");
+        assert_eq!(call_label(&calls[2]), "teleport(1, 1)");
+        assert_eq!(unknown_function_names(&["scan".to_string(), "fly".to_string()]), vec!["fly".to_string()]);
+    }
+}
@@ -0,0 +1,111 @@
+// Combat log: what happened on each turn, e.g. "enemy 2 moved to (4, 5)" or "laser destroyed enemy 1".
+// The game's turn scheduler records it for the Logs tab and the test runner returns it in its
+// TestResult, so headless tests can assert on the same events a player reads. Enemies are named by
+// their index at the time, which shifts down when an enemy before them is destroyed.
+
+use crate::item::Pos;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// How many entries the log keeps before dropping the oldest
+pub const MAX_COMBAT_LOG: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Weapon {
+    Laser,
+    Emp,
+    Shield,
+}
+
+impl fmt::Display for Weapon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Weapon::Laser => "laser",
+            Weapon::Emp => "EMP",
+            Weapon::Shield => "shield",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CombatEvent {
+    EnemyMoved { enemy: usize, to: Pos },
+    EnemySpawned { enemy: usize, at: Pos },
+    EnemyHit { enemy: usize, weapon: Weapon, damage: u32, hp_left: u32 },
+    EnemyStunned { enemy: usize, weapon: Weapon, turns: u8 },
+    EnemyDestroyed { enemy: usize, weapon: Weapon },
+    RobotCaught { robot: usize, at: Pos },
+}
+
+impl fmt::Display for CombatEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CombatEvent::EnemyMoved { enemy, to } => write!(f, "enemy {} moved to ({}, {})", enemy, to.x, to.y),
+            CombatEvent::EnemySpawned { enemy, at } => write!(f, "enemy {} spawned at ({}, {})", enemy, at.x, at.y),
+            CombatEvent::EnemyHit { enemy, weapon, damage, hp_left } => write!(f, "{} hit enemy {} for {} damage, {} HP left", weapon, enemy, damage, hp_left),
+            CombatEvent::EnemyStunned { enemy, weapon, turns } => write!(f, "{} stunned enemy {} for {} turns", weapon, enemy, turns),
+            CombatEvent::EnemyDestroyed { enemy, weapon } => write!(f, "{} destroyed enemy {}", weapon, enemy),
+            CombatEvent::RobotCaught { robot, at } => write!(f, "an enemy caught robot {} at ({}, {})", robot, at.x, at.y),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CombatLogEntry {
+    pub turn: usize,
+    pub event: CombatEvent,
+}
+
+impl fmt::Display for CombatLogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "turn {}: {}", self.turn, self.event)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CombatLog {
+    pub entries: Vec<CombatLogEntry>, // Oldest first, at most MAX_COMBAT_LOG
+}
+
+impl CombatLog {
+    pub fn record(&mut self, turn: usize, events: impl IntoIterator<Item = CombatEvent>) {
+        self.entries.extend(events.into_iter().map(|event| CombatLogEntry { turn, event }));
+        let excess = self.entries.len().saturating_sub(MAX_COMBAT_LOG);
+        self.entries.drain(..excess);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Moves and spawns between the enemy positions before and after the enemies' step. Spawned
+/// enemies are added at the end, so they're the ones past the end of `before`.
+pub fn enemy_steps(before: &[Pos], after: &[Pos]) -> Vec<CombatEvent> {
+    after.iter().enumerate().filter_map(|(enemy, &pos)| match before.get(enemy) {
+        Some(&old) if old == pos => None,
+        Some(_) => Some(CombatEvent::EnemyMoved { enemy, to: pos }),
+        None => Some(CombatEvent::EnemySpawned { enemy, at: pos }),
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enemy_steps_name_moves_and_spawns_in_order() {
+        let before = [Pos { x: 1, y: 1 }, Pos { x: 3, y: 3 }];
+        let after = [Pos { x: 1, y: 1 }, Pos { x: 4, y: 3 }, Pos { x: 0, y: 5 }];
+
+        let mut log = CombatLog::default();
+        log.record(2, enemy_steps(&before, &after));
+        log.record(2, [CombatEvent::EnemyDestroyed { enemy: 1, weapon: Weapon::Laser }]);
+        let lines: Vec<String> = log.entries.iter().map(ToString::to_string).collect();
+        assert_eq!(lines, vec![
+            "turn 2: enemy 1 moved to (4, 3)",
+            "turn 2: enemy 2 spawned at (0, 5)",
+            "turn 2: laser destroyed enemy 1",
+        ]);
+    }
+}
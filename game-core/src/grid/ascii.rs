@@ -0,0 +1,232 @@
+// Text and JSON dumps of the grid for test output, failure diagnostics and print_map()
+//
+// ASCII legend (one character per tile, rows top to bottom):
+//   ?  unknown tile          .  known floor          #  obstacle
+//   D  closed door           d  open door            C  crate
+//   _  pressure plate        x  spike trap           > < ^ v  conveyor
+//   E  enemy                 B  boss tile            i  item
+//   / \  mirrors             R  robot               L  laser emitter
+//   m  mud                   ~  ice
+
+use super::{Enemy, Grid};
+use crate::item::Pos;
+use crate::level::{EnemyDirection, Terrain};
+use std::collections::HashMap;
+
+/// A grid parsed by Grid::from_ascii together with the robot and items it marks
+#[allow(dead_code)] // Only used to set up tests
+pub struct AsciiMap {
+    pub grid: Grid,
+    pub robot: Option<Pos>,
+    pub items: Vec<Pos>,
+}
+
+impl Grid {
+    /// Render the grid one character per tile. Unknown tiles are drawn as `?` unless
+    /// `show_unknown` is set, in which case everything is shown.
+    pub fn to_ascii(&self, robots: &[Pos], items: &[Pos], show_unknown: bool) -> String {
+        let mut out = String::with_capacity(((self.width + 1) * self.height) as usize);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pos = Pos { x, y };
+                if !show_unknown && !self.known.contains(&pos) {
+                    out.push('?');
+                } else {
+                    out.push(self.tile_char(pos, robots, items));
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn tile_char(&self, pos: Pos, robots: &[Pos], items: &[Pos]) -> char {
+        if robots.contains(&pos) {
+            return 'R';
+        }
+        if let Some(enemy) = self.enemies.iter().find(|enemy| enemy.occupies(pos)) {
+            return if enemy.is_boss() { 'B' } else { 'E' };
+        }
+        if items.contains(&pos) {
+            return 'i';
+        }
+        if self.crates.contains(&pos) {
+            return 'C';
+        }
+        if self.blockers.contains(&pos) {
+            return '#';
+        }
+        if let Some(mirror) = self.mirrors.get(&pos) {
+            return mirror.symbol();
+        }
+        if self.emitter_at(pos).is_some() {
+            return 'L';
+        }
+        if self.doors.contains(&pos) {
+            return if self.open_doors.contains(&pos) { 'd' } else { 'D' };
+        }
+        if self.plate_at(pos).is_some() {
+            return '_';
+        }
+        if self.spikes.contains_key(&pos) {
+            return 'x';
+        }
+        match self.terrain_at(pos) {
+            Some(Terrain::Mud) => return 'm',
+            Some(Terrain::Ice) => return '~',
+            None => {}
+        }
+        match self.conveyor_at(pos) {
+            Some((1, 0)) => '>',
+            Some((-1, 0)) => '<',
+            Some((0, -1)) => '^',
+            Some(_) => 'v',
+            None => '.',
+        }
+    }
+
+    /// Machine-readable snapshot of the grid for diagnostics
+    pub fn to_json(&self, robots: &[Pos], items: &[Pos]) -> serde_json::Value {
+        let point = |pos: &Pos| serde_json::json!([pos.x, pos.y]);
+        let mut known: Vec<&Pos> = self.known.iter().collect();
+        known.sort_by_key(|pos| (pos.y, pos.x));
+        let mut blockers: Vec<&Pos> = self.blockers.iter().collect();
+        blockers.sort_by_key(|pos| (pos.y, pos.x));
+        let mut doors: Vec<&Pos> = self.doors.iter().collect();
+        doors.sort_by_key(|pos| (pos.y, pos.x));
+        let mut crates: Vec<&Pos> = self.crates.iter().collect();
+        crates.sort_by_key(|pos| (pos.y, pos.x));
+
+        serde_json::json!({
+            "width": self.width,
+            "height": self.height,
+            "robots": robots.iter().map(point).collect::<Vec<_>>(),
+            "known": known.into_iter().map(point).collect::<Vec<_>>(),
+            "blockers": blockers.into_iter().map(point).collect::<Vec<_>>(),
+            "doors": doors.into_iter().map(|pos| serde_json::json!({
+                "position": [pos.x, pos.y],
+                "open": self.open_doors.contains(pos),
+                "locked": self.locked_door_color(*pos).map(|color| color.name()),
+            })).collect::<Vec<_>>(),
+            "crates": crates.into_iter().map(point).collect::<Vec<_>>(),
+            "switches": self.switches.iter().map(|switch| serde_json::json!({
+                "id": switch.id,
+                "position": [switch.pos.x, switch.pos.y],
+                "on": switch.on,
+                "targets": switch.targets.iter().map(point).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+            "laser_emitters": self.emitters.iter().map(|emitter| serde_json::json!({
+                "position": [emitter.pos.x, emitter.pos.y],
+                "direction": [emitter.direction.0, emitter.direction.1],
+                "on_turns": emitter.on_turns,
+                "off_turns": emitter.off_turns,
+            })).collect::<Vec<_>>(),
+            "enemies": self.enemies.iter().map(|enemy| serde_json::json!({
+                "position": [enemy.pos.x, enemy.pos.y],
+                "size": enemy.size,
+                "hp": enemy.hp,
+            })).collect::<Vec<_>>(),
+            "items": items.iter().map(point).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Build a grid from the to_ascii() format, e.g. for setting up a test scenario.
+    /// Every tile except `?` starts out known.
+    #[allow(dead_code)] // Only used to set up tests
+    pub fn from_ascii(map: &str) -> Result<AsciiMap, String> {
+        let rows: Vec<&str> = map.lines().map(str::trim_end).filter(|row| !row.is_empty()).collect();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if width == 0 {
+            return Err("ASCII map is empty".to_string());
+        }
+        if let Some((y, row)) = rows.iter().enumerate().find(|(_, row)| row.chars().count() != width) {
+            return Err(format!("Row {} is {} tiles wide, expected {}", y, row.chars().count(), width));
+        }
+
+        let mut grid = Grid::new(width as i32, rows.len() as i32);
+        let mut robot = None;
+        let mut items = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let pos = Pos { x: x as i32, y: y as i32 };
+                if c != '?' {
+                    grid.known.insert(pos);
+                }
+                match c {
+                    '?' | '.' => {}
+                    '#' => { grid.blockers.insert(pos); }
+                    'D' => { grid.doors.insert(pos); }
+                    'd' => { grid.doors.insert(pos); grid.open_doors.insert(pos); }
+                    'C' => { grid.crates.insert(pos); }
+                    '/' => { grid.mirrors.insert(pos, crate::level::Mirror::Slash); }
+                    '\\' => { grid.mirrors.insert(pos, crate::level::Mirror::Backslash); }
+                    '_' => grid.plates.push(super::PressurePlate { pos, doors: Vec::new(), pressed: false }),
+                    'x' => { grid.spikes.insert(pos, 0); }
+                    '>' => { grid.conveyors.insert(pos, (1, 0)); }
+                    '<' => { grid.conveyors.insert(pos, (-1, 0)); }
+                    '^' => { grid.conveyors.insert(pos, (0, -1)); }
+                    'v' => { grid.conveyors.insert(pos, (0, 1)); }
+                    'm' => { grid.terrain.insert(pos, Terrain::Mud); }
+                    '~' => { grid.terrain.insert(pos, Terrain::Ice); }
+                    'E' => grid.enemies.push(Enemy {
+                        pos,
+                        direction: EnemyDirection::Horizontal,
+                        moving_positive: true,
+                        movement_pattern: None,
+                        movement_data: HashMap::new(),
+                        hp: None,
+                        max_hp: 0,
+                        size: 1,
+                        boss_phases: Vec::new(),
+                    }),
+                    'i' => items.push(pos),
+                    'R' => {
+                        if robot.replace(pos).is_some() {
+                            return Err(format!("More than one robot in the map (second at ({}, {}))", x, y));
+                        }
+                    }
+                    other => return Err(format!("Unknown map character '{}' at ({}, {})", other, x, y)),
+                }
+            }
+        }
+        Ok(AsciiMap { grid, robot, items })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "
+R..#
+.C.D
+E.i>
+";
+
+    #[test]
+    fn ascii_round_trip() {
+        let map = Grid::from_ascii(MAP).unwrap();
+        assert_eq!((map.grid.width, map.grid.height), (4, 3));
+        assert_eq!(map.robot, Some(Pos { x: 0, y: 0 }));
+        assert_eq!(map.items, vec![Pos { x: 2, y: 2 }]);
+        assert!(map.grid.is_crate(Pos { x: 1, y: 1 }));
+        assert!(map.grid.check_enemy_collision((0, 2)));
+
+        let robots = [map.robot.unwrap()];
+        assert_eq!(map.grid.to_ascii(&robots, &map.items, false), MAP.trim_start());
+    }
+
+    #[test]
+    fn unknown_tiles_are_hidden() {
+        let map = Grid::from_ascii("R?\n.#\n").unwrap();
+        let robots = [map.robot.unwrap()];
+        assert_eq!(map.grid.to_ascii(&robots, &[], false), "R?\n.#\n");
+        assert_eq!(map.grid.to_ascii(&robots, &[], true), "R.\n.#\n");
+    }
+
+    #[test]
+    fn rejects_ragged_rows_and_unknown_characters() {
+        assert!(Grid::from_ascii("...\n..\n").is_err());
+        assert!(Grid::from_ascii("..%\n").is_err());
+    }
+}
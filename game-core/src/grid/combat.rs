@@ -0,0 +1,93 @@
+// Stuns: enemies hit by a laser or caught in an EMP blast skip their moves for a few turns. Timers
+// are kept per enemy index, so removing an enemy shifts the timers after it down. The EMP reaches
+// every enemy within a Manhattan radius of the robot; bosses shrug it off.
+
+use super::Grid;
+use crate::item::Pos;
+use crate::movement_patterns::pathfinding::manhattan;
+
+pub const LASER_STUN_TURNS: u8 = 5;
+pub const EMP_STUN_TURNS: u8 = 3;
+pub const MAX_EMP_RADIUS: u32 = 4;
+
+impl Grid {
+    /// Stun an enemy; a longer stun it's already under is kept
+    pub fn stun(&mut self, index: usize, turns: u8) {
+        let left = self.stunned.entry(index).or_insert(0);
+        *left = (*left).max(turns);
+    }
+
+    /// Turns left on the enemy's stun, if it's stunned
+    pub fn stun_turns(&self, index: usize) -> Option<u8> {
+        self.stunned.get(&index).copied()
+    }
+
+    /// Count every stun down by a turn
+    pub fn tick_stuns(&mut self) {
+        self.stunned.retain(|_, turns| {
+            *turns -= 1;
+            *turns > 0
+        });
+    }
+
+    /// Remove a destroyed enemy along with its stun
+    pub fn remove_enemy(&mut self, index: usize) {
+        self.enemies.remove(index);
+        self.stunned = self.stunned.drain()
+            .filter(|(i, _)| *i != index)
+            .map(|(i, turns)| if i > index { (i - 1, turns) } else { (i, turns) })
+            .collect();
+    }
+
+    /// Stun every enemy but bosses within `radius` steps of `center`; the indices of those it stunned
+    pub fn emp(&mut self, center: Pos, radius: u32, turns: u8) -> Vec<usize> {
+        let hit: Vec<usize> = (0..self.enemies.len())
+            .filter(|&i| !self.enemies[i].is_boss() && manhattan(self.enemies[i].pos, center) <= radius)
+            .collect();
+        for &index in &hit {
+            self.stun(index, turns);
+        }
+        hit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Enemy;
+    use crate::level::EnemyDirection;
+    use std::collections::HashMap;
+
+    fn enemy(x: i32, y: i32, size: i32) -> Enemy {
+        Enemy {
+            pos: Pos { x, y },
+            direction: EnemyDirection::Horizontal,
+            moving_positive: true,
+            movement_pattern: None,
+            movement_data: HashMap::new(),
+            hp: None,
+            max_hp: 0,
+            size,
+            boss_phases: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn emp_stuns_enemies_in_reach_and_stuns_follow_removed_enemies() {
+        let mut grid = Grid::new(9, 9);
+        // In reach, a boss in reach, out of reach (diagonal distance 4), in reach
+        grid.enemies = vec![enemy(4, 2, 1), enemy(3, 4, 2), enemy(6, 6, 1), enemy(5, 5, 1)];
+        let hit = grid.emp(Pos { x: 4, y: 4 }, 2, EMP_STUN_TURNS);
+        assert_eq!(hit, vec![0, 3]);
+
+        grid.remove_enemy(1);
+        assert_eq!((grid.stun_turns(0), grid.stun_turns(1), grid.stun_turns(2)), (Some(3), None, Some(3)));
+
+        grid.stun(2, 1);
+        grid.tick_stuns();
+        grid.tick_stuns();
+        assert_eq!(grid.stun_turns(2), Some(1));
+        grid.tick_stuns();
+        assert!(grid.stunned.is_empty());
+    }
+}
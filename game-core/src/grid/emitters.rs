@@ -0,0 +1,101 @@
+// Laser emitters: wall-mounted hazards that fire a beam along their row or column, on for
+// `on_turns` turns and then off for `off_turns`. The beam runs until the first blocked tile, so a
+// closed door or a crate cuts it short. While it fires it can't be walked into, and it kills a
+// robot caught standing in it. Switches wired to an emitter shut it off while they power it.
+
+use super::Grid;
+use crate::item::Pos;
+
+#[derive(Clone, Debug)]
+pub struct LaserEmitter {
+    pub pos: Pos,
+    pub direction: (i32, i32), // Where the beam goes
+    pub on_turns: u32,
+    pub off_turns: u32,
+    pub phase: u32, // How far into its cycle the emitter is on turn 0
+}
+
+impl LaserEmitter {
+    /// Whether the emitter's cycle has it firing on `turn`, switches aside
+    pub fn cycle_on(&self, turn: usize) -> bool {
+        let cycle = (self.on_turns + self.off_turns) as usize;
+        cycle == 0 || (turn + self.phase as usize) % cycle < self.on_turns as usize
+    }
+}
+
+impl Grid {
+    pub fn emitter_at(&self, pos: Pos) -> Option<&LaserEmitter> {
+        self.emitters.iter().find(|emitter| emitter.pos == pos)
+    }
+
+    /// Whether `emitter` fires on `turn`: its cycle has it on and no switch wired to it is shutting it off
+    pub fn emitter_firing(&self, emitter: &LaserEmitter, turn: usize) -> bool {
+        let wired = self.switches.iter().any(|switch| switch.targets.contains(&emitter.pos));
+        emitter.cycle_on(turn) && !(wired && self.target_powered(emitter.pos))
+    }
+
+    /// Tiles the beam of `emitter` crosses when it fires, up to the first blocked tile or the edge
+    pub fn beam_tiles(&self, emitter: &LaserEmitter) -> Vec<Pos> {
+        let mut tiles = Vec::new();
+        let mut pos = emitter.pos;
+        loop {
+            pos = Pos { x: pos.x + emitter.direction.0, y: pos.y + emitter.direction.1 };
+            if !self.in_bounds(pos) || self.is_blocked(pos) {
+                return tiles;
+            }
+            tiles.push(pos);
+        }
+    }
+
+    /// Every tile a beam crosses on `turn`, with the direction of that beam
+    pub fn active_beams(&self, turn: usize) -> Vec<(Pos, (i32, i32))> {
+        self.emitters.iter()
+            .filter(|emitter| self.emitter_firing(emitter, turn))
+            .flat_map(|emitter| self.beam_tiles(emitter).into_iter().map(|tile| (tile, emitter.direction)))
+            .collect()
+    }
+
+    /// Whether a live beam crosses `pos` on `turn`
+    pub fn laser_beam_at(&self, pos: Pos, turn: usize) -> bool {
+        self.emitters.iter().any(|emitter| self.emitter_firing(emitter, turn) && self.beam_tiles(emitter).contains(&pos))
+    }
+
+    /// How scans report an emitter, e.g. "laser emitter (right) at (0, 2), firing next turn"
+    pub fn describe_emitter(&self, pos: Pos, turn: usize) -> Option<String> {
+        let emitter = self.emitter_at(pos)?;
+        let direction = match emitter.direction {
+            (0, -1) => "up",
+            (0, 1) => "down",
+            (-1, 0) => "left",
+            _ => "right",
+        };
+        let state = if self.emitter_firing(emitter, turn + 1) { "firing next turn" } else { "off next turn" };
+        Some(format!("laser emitter ({}) at ({}, {}), {}", direction, pos.x, pos.y, state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Switch;
+
+    #[test]
+    fn beam_follows_the_cycle_and_stops_at_walls() {
+        let mut grid = Grid::new(6, 2);
+        let emitter = LaserEmitter { pos: Pos { x: 0, y: 0 }, direction: (1, 0), on_turns: 2, off_turns: 1, phase: 0 };
+        grid.emitters.push(emitter.clone());
+        grid.blockers.insert(Pos { x: 4, y: 0 });
+
+        assert_eq!(grid.beam_tiles(&emitter), vec![Pos { x: 1, y: 0 }, Pos { x: 2, y: 0 }, Pos { x: 3, y: 0 }]);
+        assert!(grid.laser_beam_at(Pos { x: 2, y: 0 }, 0) && grid.laser_beam_at(Pos { x: 2, y: 0 }, 1));
+        assert!(!grid.laser_beam_at(Pos { x: 2, y: 0 }, 2) && grid.laser_beam_at(Pos { x: 2, y: 0 }, 3));
+        assert!(!grid.laser_beam_at(Pos { x: 5, y: 0 }, 0));
+        assert!(grid.is_blocked(emitter.pos));
+        assert_eq!(grid.describe_emitter(emitter.pos, 1).as_deref(), Some("laser emitter (right) at (0, 0), off next turn"));
+
+        // A switch wired to the emitter shuts it off while it's on
+        grid.switches.push(Switch { id: "a".to_string(), pos: Pos { x: 0, y: 1 }, on: false, targets: vec![emitter.pos] });
+        grid.flip_switch(Pos { x: 0, y: 1 });
+        assert!(!grid.laser_beam_at(Pos { x: 2, y: 0 }, 0));
+    }
+}
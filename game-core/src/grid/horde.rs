@@ -0,0 +1,134 @@
+// Enemy hordes: spawner tiles that add an enemy every `every` turns until they've spawned `max`, and
+// flanking, where chase enemies spread out around the robot instead of queueing up on one path. The
+// first chaser goes straight for the robot, the next for the side of the robot facing away from it
+// and the two after that for the sides across; any others, and chasers already close, go straight in.
+
+use super::{Enemy, Grid};
+use crate::item::Pos;
+use crate::movement_patterns::pathfinding::manhattan;
+
+// How far from the robot a flanking chaser lines up before closing in
+pub const FLANK_DISTANCE: i32 = 2;
+
+#[derive(Clone, Debug)]
+pub struct Spawner {
+    pub pos: Pos,
+    pub every: u32,
+    pub max: u32,
+    pub spawned: u32,
+    pub turns_left: u32, // Until the next enemy is due; it waits at 0 while the tile is taken
+    pub enemy: Enemy, // What it spawns, moved onto `pos` each time
+}
+
+impl Spawner {
+    pub fn new(pos: Pos, every: u32, max: u32, enemy: Enemy) -> Self {
+        Self { pos, every: every.max(1), max, spawned: 0, turns_left: every.max(1), enemy }
+    }
+
+    /// Count a turn down; true once an enemy is due and the spawner hasn't reached its cap
+    pub fn tick(&mut self) -> bool {
+        if self.spawned >= self.max {
+            return false;
+        }
+        self.turns_left = self.turns_left.saturating_sub(1);
+        self.turns_left == 0
+    }
+
+    /// The enemy it spawns now, starting the countdown to the next one
+    pub fn spawn(&mut self) -> Enemy {
+        self.spawned += 1;
+        self.turns_left = self.every;
+        Enemy { pos: self.pos, ..self.enemy.clone() }
+    }
+}
+
+impl Grid {
+    pub fn spawner_at(&self, pos: Pos) -> Option<&Spawner> {
+        self.spawners.iter().find(|spawner| spawner.pos == pos)
+    }
+
+    /// e.g. "enemy spawner, next enemy in 3 turn(s), 1 of 3 spawned", for the tile inspector
+    pub fn describe_spawner(&self, pos: Pos) -> Option<String> {
+        let spawner = self.spawner_at(pos)?;
+        Some(if spawner.spawned >= spawner.max {
+            format!("enemy spawner, used up ({} of {} spawned)", spawner.spawned, spawner.max)
+        } else {
+            format!("enemy spawner, next enemy in {} turn(s), {} of {} spawned", spawner.turns_left.max(1), spawner.spawned, spawner.max)
+        })
+    }
+
+    /// Spawn the enemies that are due; a spawner whose tile holds the robot, an enemy or an
+    /// obstacle tries again next turn
+    pub fn tick_spawners(&mut self, robot: Option<Pos>) {
+        for index in 0..self.spawners.len() {
+            if !self.spawners[index].tick() {
+                continue;
+            }
+            let pos = self.spawners[index].pos;
+            if robot == Some(pos) || self.is_blocked(pos) || self.enemies.iter().any(|enemy| enemy.occupies(pos)) {
+                continue;
+            }
+            let enemy = self.spawners[index].spawn();
+            self.add_enemy(enemy);
+        }
+    }
+
+    /// Tile each chaser heads for this turn, in the order of `chasers`
+    pub fn flank_targets(&self, robot: Pos, chasers: &[Pos]) -> Vec<Pos> {
+        let Some(lead) = chasers.first() else { return Vec::new() };
+        let (dx, dy) = (robot.x - lead.x, robot.y - lead.y);
+        let away = if dx.abs() >= dy.abs() { (dx.signum(), 0) } else { (0, dy.signum()) };
+        let sides = [away, (away.1, away.0), (-away.1, -away.0)];
+
+        chasers.iter().enumerate().map(|(k, &pos)| {
+            let Some(&(sx, sy)) = k.checked_sub(1).and_then(|side| sides.get(side)) else { return robot };
+            let side = Pos { x: robot.x + sx * FLANK_DISTANCE, y: robot.y + sy * FLANK_DISTANCE };
+            let close = manhattan(pos, robot) <= FLANK_DISTANCE as u32;
+            if close || !self.in_bounds(side) || self.is_blocked(side) { robot } else { side }
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::EnemyDirection;
+    use std::collections::HashMap;
+
+    fn chaser(pos: Pos) -> Enemy {
+        Enemy {
+            pos,
+            direction: EnemyDirection::Horizontal,
+            moving_positive: true,
+            movement_pattern: Some("chase".to_string()),
+            movement_data: HashMap::new(),
+            hp: None,
+            max_hp: 0,
+            size: 1,
+            boss_phases: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn spawners_add_enemies_on_their_interval_up_to_the_cap() {
+        let mut grid = Grid::new(9, 9);
+        let tile = Pos { x: 0, y: 0 };
+        grid.spawners.push(Spawner::new(tile, 2, 2, chaser(tile)));
+
+        let mut counts = Vec::new();
+        for _ in 0..8 {
+            grid.tick_spawners(None);
+            counts.push(grid.enemies.len());
+            // Clear the tile so the next one has room
+            for (n, enemy) in grid.enemies.iter_mut().enumerate() {
+                enemy.pos = Pos { x: 5, y: n as i32 };
+            }
+        }
+        assert_eq!(counts, vec![0, 1, 1, 2, 2, 2, 2, 2]);
+
+        // The lead chaser comes from the left, so the next one goes round to the robot's right
+        let robot = Pos { x: 4, y: 4 };
+        let targets = grid.flank_targets(robot, &[Pos { x: 0, y: 4 }, Pos { x: 4, y: 0 }, Pos { x: 3, y: 4 }]);
+        assert_eq!(targets, vec![robot, Pos { x: 6, y: 4 }, robot]);
+    }
+}
@@ -0,0 +1,726 @@
+use crate::level::{LevelSpec, EnemyDirection, EnemySpec, BossPhase, Gate, KeyColor, Mirror, Terrain};
+use crate::item::Pos;
+use crate::movement_patterns::MovementPatternRegistry;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::collections::{HashSet, HashMap};
+
+pub mod ascii;
+pub mod combat;
+pub mod emitters;
+pub mod horde;
+pub mod switches;
+
+pub use emitters::LaserEmitter;
+pub use horde::Spawner;
+pub use switches::Switch;
+
+#[derive(Clone, Debug)]
+pub struct Enemy {
+    pub pos: Pos,
+    pub direction: EnemyDirection,
+    pub moving_positive: bool, // true = right/down, false = left/up
+    pub movement_pattern: Option<String>, // For custom movement patterns
+    pub movement_data: HashMap<String, serde_yaml::Value>, // Data for custom movement patterns
+    pub hp: Option<u32>, // None = can't be destroyed, lasers only stun it
+    pub max_hp: u32,
+    pub size: i32, // Bosses cover size x size tiles with `pos` as the top-left tile
+    pub boss_phases: Vec<BossPhase>,
+}
+
+impl Enemy {
+    /// The enemy `spec` describes; smart chasers search for a new path at most every `recompute_every` turns
+    pub fn from_spec(spec: &EnemySpec, recompute_every: u32) -> Self {
+        let mut movement_data = HashMap::new();
+        if let Some(radius) = spec.aggro_radius {
+            movement_data.insert("aggro_radius".to_string(), serde_yaml::Value::Number(radius.into()));
+        }
+        movement_data.insert("recompute_every".to_string(), serde_yaml::Value::Number(recompute_every.into()));
+        Self {
+            pos: Pos { x: spec.pos.0, y: spec.pos.1 },
+            direction: spec.direction,
+            moving_positive: spec.moving_positive,
+            movement_pattern: spec.movement_pattern.clone(),
+            movement_data,
+            hp: spec.hp,
+            max_hp: spec.hp.unwrap_or(0),
+            size: spec.size.max(1) as i32,
+            boss_phases: spec.boss_phases.clone(),
+        }
+    }
+
+    pub fn is_boss(&self) -> bool {
+        self.size > 1 || !self.boss_phases.is_empty()
+    }
+
+    pub fn occupies(&self, pos: Pos) -> bool {
+        pos.x >= self.pos.x && pos.y >= self.pos.y && pos.x < self.pos.x + self.size && pos.y < self.pos.y + self.size
+    }
+
+    /// Tiles covered by the enemy if its top-left tile were at `origin`
+    pub fn tiles_at(&self, origin: Pos) -> Vec<Pos> {
+        (0..self.size)
+            .flat_map(|dy| (0..self.size).map(move |dx| Pos { x: origin.x + dx, y: origin.y + dy }))
+            .collect()
+    }
+
+    /// Apply laser damage and return true once the enemy is destroyed. Bosses move on to the
+    /// phase for their remaining hit points.
+    pub fn take_damage(&mut self, amount: u32) -> bool {
+        let Some(hp) = self.hp.as_mut() else { return false };
+        *hp = hp.saturating_sub(amount);
+        let remaining = *hp;
+
+        if let Some(phase) = self.boss_phases.iter()
+            .filter(|phase| remaining < phase.hp_below)
+            .min_by_key(|phase| phase.hp_below)
+        {
+            match phase.movement_pattern.as_str() {
+                "horizontal" => { self.direction = EnemyDirection::Horizontal; self.movement_pattern = None; }
+                "vertical" => { self.direction = EnemyDirection::Vertical; self.movement_pattern = None; }
+                pattern => self.movement_pattern = Some(pattern.to_string()),
+            }
+        }
+        remaining == 0
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PressurePlate {
+    pub pos: Pos,
+    pub doors: Vec<Pos>, // Held open while a crate or the robot is on the plate
+    pub pressed: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct Grid {
+    pub width: i32,
+    pub height: i32,
+    pub known: HashSet<Pos>,
+    pub visited: HashSet<Pos>,
+    pub blockers: HashSet<Pos>,
+    pub doors: HashSet<Pos>,  // Door positions
+    pub open_doors: HashSet<Pos>,  // Currently open doors
+    pub locked_doors: HashMap<Pos, KeyColor>, // Doors that stay shut until a key of their color unlocks them
+    pub crates: HashSet<Pos>,  // Pushable crates
+    pub plates: Vec<PressurePlate>,
+    pub switches: Vec<Switch>,
+    pub gates: HashMap<Pos, Gate>, // Switch targets with a gate other than xor
+    pub conveyors: HashMap<Pos, (i32, i32)>, // Belt position -> direction it pushes
+    pub spikes: HashMap<Pos, u32>, // Trap position -> phase (deadly when turn + phase is even)
+    pub emitters: Vec<LaserEmitter>,
+    pub terrain: HashMap<Pos, Terrain>, // Mud and ice; everything else is plain floor
+    pub mirrors: HashMap<Pos, Mirror>, // Block movement like obstacles but reflect lasers
+    pub enemies: Vec<Enemy>,
+    pub stunned: HashMap<usize, u8>, // Enemy index -> turns it still skips (see combat)
+    pub spawners: Vec<Spawner>,
+    pub flanking: bool, // Chase enemies spread out around the robot (see horde)
+    pub fog_of_war: bool,
+    pub income_per_square: u32,
+    pub movement_registry: MovementPatternRegistry,
+}
+
+impl Grid {
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            known: HashSet::new(),
+            visited: HashSet::new(),
+            blockers: HashSet::new(),
+            doors: HashSet::new(),
+            open_doors: HashSet::new(),
+            locked_doors: HashMap::new(),
+            crates: HashSet::new(),
+            plates: Vec::new(),
+            switches: Vec::new(),
+            gates: HashMap::new(),
+            conveyors: HashMap::new(),
+            spikes: HashMap::new(),
+            emitters: Vec::new(),
+            terrain: HashMap::new(),
+            mirrors: HashMap::new(),
+            enemies: Vec::new(),
+            stunned: HashMap::new(),
+            spawners: Vec::new(),
+            flanking: false,
+            fog_of_war: true,
+            income_per_square: 1,
+            movement_registry: MovementPatternRegistry::new(),
+        }
+    }
+
+    pub fn from_level_spec(spec: &LevelSpec, rng: &mut StdRng, _robot_carries_scanner: bool) -> Self {
+        let mut grid = Self::new(spec.width as i32, spec.height as i32);
+        grid.fog_of_war = spec.fog_of_war;
+        grid.income_per_square = spec.income_per_square;
+        
+        // Register additional built-in patterns
+        grid.movement_registry.register("random", Box::new(crate::movement_patterns::RandomMovement));
+        grid.movement_registry.register("diagonal", Box::new(crate::movement_patterns::DiagonalMovement { moving_positive: true }));
+        grid.movement_registry.register("circular", Box::new(crate::movement_patterns::CircularMovement::new()));
+        grid.movement_registry.register("chase", Box::new(crate::movement_patterns::ChaseMovement));
+        grid.movement_registry.register("chase_smart", Box::new(crate::movement_patterns::SmartChaseMovement::new()));
+
+        // Add specified blockers
+        for (x, y) in &spec.blockers {
+            grid.blockers.insert(Pos { x: *x as i32, y: *y as i32 });
+        }
+        
+        // Add specified doors
+        for (x, y) in &spec.doors {
+            grid.doors.insert(Pos { x: *x as i32, y: *y as i32 });
+        }
+        for door in &spec.locked_doors {
+            let pos = Pos { x: door.pos.0 as i32, y: door.pos.1 as i32 };
+            grid.doors.insert(pos);
+            grid.locked_doors.insert(pos, door.color);
+        }
+
+        for (x, y) in &spec.crates {
+            grid.crates.insert(Pos { x: *x as i32, y: *y as i32 });
+        }
+
+        // Doors linked to a plate don't need to be listed separately
+        for plate in &spec.plates {
+            let doors: Vec<Pos> = plate.doors.iter().map(|(x, y)| Pos { x: *x as i32, y: *y as i32 }).collect();
+            grid.doors.extend(doors.iter().copied());
+            grid.plates.push(PressurePlate {
+                pos: Pos { x: plate.pos.0 as i32, y: plate.pos.1 as i32 },
+                doors,
+                pressed: false,
+            });
+        }
+
+        for switch in &spec.switches {
+            grid.switches.push(Switch {
+                id: switch.id.clone(),
+                pos: Pos { x: switch.pos.0 as i32, y: switch.pos.1 as i32 },
+                on: switch.on,
+                targets: switch.targets.iter().map(|(x, y)| Pos { x: *x as i32, y: *y as i32 }).collect(),
+            });
+        }
+        for ((x, y), gate) in &spec.gates {
+            grid.gates.insert(Pos { x: *x as i32, y: *y as i32 }, *gate);
+        }
+        // Doors start open where the switches wired to them already power them
+        let targets: Vec<Pos> = grid.switches.iter().flat_map(|switch| switch.targets.iter().copied()).collect();
+        let start = Pos { x: spec.start.0 as i32, y: spec.start.1 as i32 };
+        grid.update_switch_targets(&targets, start);
+
+        for conveyor in &spec.conveyors {
+            grid.conveyors.insert(Pos { x: conveyor.pos.0 as i32, y: conveyor.pos.1 as i32 }, conveyor.direction);
+        }
+        for spike in &spec.spikes {
+            grid.spikes.insert(Pos { x: spike.pos.0 as i32, y: spike.pos.1 as i32 }, spike.phase);
+        }
+        for emitter in &spec.emitters {
+            grid.emitters.push(LaserEmitter {
+                pos: Pos { x: emitter.pos.0 as i32, y: emitter.pos.1 as i32 },
+                direction: emitter.direction,
+                on_turns: emitter.on_turns,
+                off_turns: emitter.off_turns,
+                phase: emitter.phase,
+            });
+        }
+        for ((x, y), terrain) in &spec.terrain {
+            grid.terrain.insert(Pos { x: *x as i32, y: *y as i32 }, *terrain);
+        }
+        for mirror in &spec.mirrors {
+            grid.mirrors.insert(Pos { x: mirror.pos.0 as i32, y: mirror.pos.1 as i32 }, mirror.kind);
+        }
+
+        // Add enemies
+        // Smart chasers on harder levels look for a new path to the robot more often
+        let recompute_every: u32 = match spec.difficulty.as_deref() {
+            Some("advanced") => 1,
+            Some("intermediate") => 2,
+            _ => 3,
+        };
+        for enemy_spec in &spec.enemies {
+            grid.add_enemy(Enemy::from_spec(enemy_spec, recompute_every));
+        }
+        for spawner in &spec.spawners {
+            let pos = Pos { x: spawner.pos.0 as i32, y: spawner.pos.1 as i32 };
+            grid.spawners.push(Spawner::new(pos, spawner.every, spawner.max, Enemy::from_spec(&spawner.enemy, recompute_every)));
+        }
+        grid.flanking = spec.flanking;
+
+        // Generate additional random obstacles for certain levels
+        if spec.name.contains("Level 3") && spec.blockers.is_empty() {
+            let n = (grid.width * grid.height) / 8;
+            for _ in 0..n {
+                let p = Pos { 
+                    x: rng.gen_range(0..grid.width), 
+                    y: rng.gen_range(0..grid.height) 
+                };
+                if p != (Pos { x: spec.start.0 as i32, y: spec.start.1 as i32 }) {
+                    grid.blockers.insert(p);
+                }
+            }
+        } else if spec.name.contains("Level 4") && spec.blockers.is_empty() {
+            // Generate some obstacles for Level 4
+            let obstacle_count = (grid.width * grid.height) / 12;
+            for _ in 0..obstacle_count {
+                let p = Pos { 
+                    x: rng.gen_range(0..grid.width), 
+                    y: rng.gen_range(0..grid.height) 
+                };
+                if p != (Pos { x: spec.start.0 as i32, y: spec.start.1 as i32 }) {
+                    grid.blockers.insert(p);
+                }
+            }
+            
+            // Generate enemies for Level 4 if not specified
+            if spec.enemies.is_empty() {
+                let enemy_count = 3;
+                for _ in 0..enemy_count {
+                    loop {
+                        let pos = Pos { 
+                            x: rng.gen_range(2..grid.width-2), 
+                            y: rng.gen_range(2..grid.height-2) 
+                        };
+                        let start_pos = Pos { x: spec.start.0 as i32, y: spec.start.1 as i32 };
+                        if pos != start_pos && !grid.blockers.contains(&pos) && 
+                           manhattan_distance(pos, start_pos) > 3 {
+                            let direction = if rng.gen_bool(0.5) { 
+                                EnemyDirection::Horizontal 
+                            } else { 
+                                EnemyDirection::Vertical 
+                            };
+                            let moving_positive = rng.gen_bool(0.5);
+                            grid.enemies.push(Enemy { 
+                                pos, 
+                                direction, 
+                                moving_positive,
+                                movement_pattern: None,
+                                movement_data: HashMap::new(),
+                                hp: None,
+                                max_hp: 0,
+                                size: 1,
+                                boss_phases: Vec::new(),
+                            });
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        grid
+    }
+
+    pub fn in_bounds(&self, pos: Pos) -> bool {
+        pos.x >= 0 && pos.y >= 0 && pos.x < self.width && pos.y < self.height
+    }
+
+    pub fn reveal(&mut self, pos: Pos) -> bool {
+        if self.in_bounds(pos) && !self.known.contains(&pos) {
+            self.known.insert(pos);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn reveal_adjacent(&mut self, center: (i32, i32)) -> usize {
+        let center_pos = Pos { x: center.0, y: center.1 };
+        let mut revealed = 0;
+        
+        if self.reveal(center_pos) {
+            revealed += 1;
+        }
+        
+        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            let adjacent_pos = Pos { x: center.0 + dx, y: center.1 + dy };
+            if self.reveal(adjacent_pos) {
+                revealed += 1;
+            }
+        }
+        
+        revealed
+    }
+
+    /// Add an enemy, loading its movement pattern first if it comes from a file
+    pub fn add_enemy(&mut self, mut enemy: Enemy) {
+        if let Some(file_path) = enemy.movement_pattern.as_deref().and_then(|pattern| pattern.strip_prefix("file:")) {
+            let pattern_name = format!("custom_{}", self.enemies.len());
+            match self.movement_registry.load_from_file(&pattern_name, file_path) {
+                Ok(()) => {
+                    if let Some(pattern) = self.movement_registry.get(&pattern_name) {
+                        for (key, value) in pattern.initialize() {
+                            enemy.movement_data.entry(key).or_insert(value);
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Failed to load movement pattern from {}: {}", file_path, e),
+            }
+        }
+        self.enemies.push(enemy);
+    }
+
+    pub fn move_enemies(&mut self, player_pos: Option<(i32, i32)>) {
+        let mut new_enemies = self.enemies.clone();
+
+        // Flanking chasers each get a tile of their own to head for
+        if self.flanking && let Some((px, py)) = player_pos {
+            let chasers: Vec<usize> = (0..self.enemies.len())
+                .filter(|&i| self.enemies[i].movement_pattern.as_deref().is_some_and(|pattern| crate::level::CHASE_PATTERNS.contains(&pattern)))
+                .collect();
+            let positions: Vec<Pos> = chasers.iter().map(|&i| self.enemies[i].pos).collect();
+            let robot = Pos { x: px, y: py };
+            for (i, target) in chasers.into_iter().zip(self.flank_targets(robot, &positions)) {
+                let data = &mut new_enemies[i].movement_data;
+                if target == robot {
+                    data.remove("flank_x");
+                    data.remove("flank_y");
+                } else {
+                    data.insert("flank_x".to_string(), serde_yaml::Value::Number(target.x.into()));
+                    data.insert("flank_y".to_string(), serde_yaml::Value::Number(target.y.into()));
+                }
+            }
+        }
+        
+        for (i, enemy) in new_enemies.iter_mut().enumerate() {
+            // Skip stunned enemies
+            if self.stunned.contains_key(&i) {
+                continue;
+            }
+            
+            // Check if enemy uses a custom movement pattern
+            if let Some(ref pattern_str) = enemy.movement_pattern {
+                // Chasing patterns, built in or loaded from a file, follow the player
+                if let Some((px, py)) = player_pos {
+                    enemy.movement_data.insert("player_x".to_string(), serde_yaml::Value::Number(serde_yaml::Number::from(px)));
+                    enemy.movement_data.insert("player_y".to_string(), serde_yaml::Value::Number(serde_yaml::Number::from(py)));
+                }
+
+                if pattern_str.starts_with("file:") {
+                    let pattern_name = format!("custom_{}", i);
+                    if let Some(pattern) = self.movement_registry.get(&pattern_name) {
+                        if let Some(new_pos) = pattern.next_move(enemy.pos, self, &mut enemy.movement_data) {
+                            enemy.pos = new_pos;
+                        }
+                        continue;
+                    }
+                } else if pattern_str == "random" {
+                    if let Some(pattern) = self.movement_registry.get("random") {
+                        if let Some(new_pos) = pattern.next_move(enemy.pos, self, &mut enemy.movement_data) {
+                            enemy.pos = new_pos;
+                        }
+                        continue;
+                    }
+                } else if pattern_str == "diagonal" {
+                    if let Some(pattern) = self.movement_registry.get("diagonal") {
+                        if let Some(new_pos) = pattern.next_move(enemy.pos, self, &mut enemy.movement_data) {
+                            enemy.pos = new_pos;
+                        }
+                        continue;
+                    }
+                } else if pattern_str == "circular" {
+                    if let Some(pattern) = self.movement_registry.get("circular") {
+                        if let Some(new_pos) = pattern.next_move(enemy.pos, self, &mut enemy.movement_data) {
+                            enemy.pos = new_pos;
+                        }
+                        continue;
+                    }
+                } else if crate::level::CHASE_PATTERNS.contains(&pattern_str.as_str())
+                    && let Some(pattern) = self.movement_registry.get(pattern_str)
+                {
+                    if let Some(new_pos) = pattern.next_move(enemy.pos, self, &mut enemy.movement_data) {
+                        enemy.pos = new_pos;
+                    }
+                    continue;
+                }
+            }
+            
+            // Fall back to built-in movement patterns
+            let step = |_pos: Pos, dir: EnemyDirection, pos_dir: bool| -> (i32, i32) {
+                match dir {
+                    EnemyDirection::Horizontal => if pos_dir { (1, 0) } else { (-1, 0) },
+                    EnemyDirection::Vertical   => if pos_dir { (0, 1) } else { (0, -1) },
+                }
+            };
+
+            // First attempt in current direction
+            let (dx, dy) = step(enemy.pos, enemy.direction, enemy.moving_positive);
+            let mut next = Pos { x: enemy.pos.x + dx, y: enemy.pos.y + dy };
+
+            let mut can_move = if enemy.size > 1 {
+                self.enemy_fits(i, next)
+            } else {
+                self.in_bounds(next)
+                    && !self.blockers.contains(&next)
+                    && !self.mirrors.contains_key(&next)
+                    && !self.crates.contains(&next)
+                    && !self.enemies.iter().any(|other| other.occupies(next))
+            };
+
+            if !can_move {
+                // Reverse and try once more this tick
+                enemy.moving_positive = !enemy.moving_positive;
+                let (dx2, dy2) = step(enemy.pos, enemy.direction, enemy.moving_positive);
+                next = Pos { x: enemy.pos.x + dx2, y: enemy.pos.y + dy2 };
+
+                can_move = if enemy.size > 1 {
+                    self.enemy_fits(i, next)
+                } else {
+                    self.in_bounds(next)
+                        && !self.blockers.contains(&next)
+                        && !self.mirrors.contains_key(&next)
+                        && !self.crates.contains(&next)
+                        && !self.enemies.iter().any(|other| other.occupies(next))
+                };
+
+                if !can_move {
+                    continue; // stuck this turn
+                }
+            }
+
+            enemy.pos = next;
+        }
+
+        // Pattern-driven moves only check the top-left tile, so bosses that no longer fit stay put
+        for (i, enemy) in new_enemies.iter_mut().enumerate() {
+            if enemy.size > 1 && enemy.pos != self.enemies[i].pos && !self.enemy_fits(i, enemy.pos) {
+                enemy.pos = self.enemies[i].pos;
+            }
+        }
+        self.enemies = new_enemies;
+        self.tick_spawners(player_pos.map(|(x, y)| Pos { x, y }));
+    }
+
+    pub fn check_enemy_collision(&self, robot_pos: (i32, i32)) -> bool {
+        let robot_pos = Pos { x: robot_pos.0, y: robot_pos.1 };
+        self.enemies.iter().any(|enemy| enemy.occupies(robot_pos))
+    }
+
+    /// Whether the enemy at `index` fits with its top-left tile at `origin`
+    fn enemy_fits(&self, index: usize, origin: Pos) -> bool {
+        self.enemies[index].tiles_at(origin).into_iter().all(|tile| {
+            self.in_bounds(tile)
+                && !self.blockers.contains(&tile)
+                && !self.mirrors.contains_key(&tile)
+                && !self.crates.contains(&tile)
+                && self.emitter_at(tile).is_none()
+                && !self.enemies.iter().enumerate().any(|(j, other)| j != index && other.occupies(tile))
+        })
+    }
+
+    pub fn is_blocked(&self, pos: Pos) -> bool {
+        self.blockers.contains(&pos)
+            || self.mirrors.contains_key(&pos)
+            || self.crates.contains(&pos)
+            || self.emitter_at(pos).is_some()
+            || (self.doors.contains(&pos) && !self.open_doors.contains(&pos))
+    }
+
+    pub fn conveyor_at(&self, pos: Pos) -> Option<(i32, i32)> {
+        self.conveyors.get(&pos).copied()
+    }
+
+    pub fn terrain_at(&self, pos: Pos) -> Option<Terrain> {
+        self.terrain.get(&pos).copied()
+    }
+
+    /// Whether a spike trap at `pos` is deadly on the given turn (false if there is no trap)
+    pub fn spikes_active(&self, pos: Pos, turn: usize) -> bool {
+        self.spikes.get(&pos).is_some_and(|phase| (turn + *phase as usize).is_multiple_of(2))
+    }
+
+    /// Short description of a hazard on a tile, used by scan() results
+    pub fn describe_hazard(&self, pos: Pos, turn: usize) -> Option<String> {
+        if let Some(direction) = self.conveyor_at(pos) {
+            let name = match direction {
+                (0, -1) => "up",
+                (0, 1) => "down",
+                (-1, 0) => "left",
+                _ => "right",
+            };
+            return Some(format!("conveyor ({}) at ({}, {})", name, pos.x, pos.y));
+        }
+        if self.spikes.contains_key(&pos) {
+            let state = if self.spikes_active(pos, turn + 1) { "deadly next turn" } else { "safe next turn" };
+            return Some(format!("spikes at ({}, {}), {}", pos.x, pos.y, state));
+        }
+        if self.emitters.iter().any(|emitter| self.beam_tiles(emitter).contains(&pos)) {
+            let state = if self.laser_beam_at(pos, turn + 1) { "live next turn" } else { "off next turn" };
+            return Some(format!("laser beam at ({}, {}), {}", pos.x, pos.y, state));
+        }
+        match self.terrain_at(pos)? {
+            Terrain::Mud => Some(format!("mud at ({}, {}), moving onto it takes 2 turns", pos.x, pos.y)),
+            Terrain::Ice => Some(format!("ice at ({}, {}), the robot slides across", pos.x, pos.y)),
+        }
+    }
+
+    /// How scans name a locked door, e.g. "locked_door_red at (3, 4)"
+    pub fn describe_locked_door(&self, pos: Pos) -> Option<String> {
+        let color = self.locked_door_color(pos)?;
+        Some(format!("locked_door_{} at ({}, {})", color.name(), pos.x, pos.y))
+    }
+
+    pub fn is_crate(&self, pos: Pos) -> bool {
+        self.crates.contains(&pos)
+    }
+
+    pub fn plate_at(&self, pos: Pos) -> Option<&PressurePlate> {
+        self.plates.iter().find(|plate| plate.pos == pos)
+    }
+
+    /// Push the crate at `crate_pos` one tile along (dx, dy). Fails if the tile behind it is
+    /// outside the grid, blocked (including by another crate) or occupied by an enemy.
+    pub fn push_crate(&mut self, crate_pos: Pos, dx: i32, dy: i32) -> bool {
+        let target = Pos { x: crate_pos.x + dx, y: crate_pos.y + dy };
+        if !self.crates.contains(&crate_pos)
+            || !self.in_bounds(target)
+            || self.is_blocked(target)
+            || self.enemies.iter().any(|enemy| enemy.occupies(target))
+        {
+            return false;
+        }
+        self.crates.remove(&crate_pos);
+        self.crates.insert(target);
+        self.known.insert(target);
+        true
+    }
+
+    /// Open doors whose plate is weighted by a crate or the robot and close the rest.
+    /// A door never closes on something standing in it.
+    pub fn update_plates(&mut self, robot_pos: Pos) {
+        let mut changed_doors = Vec::new();
+        for plate in &mut self.plates {
+            let pressed = self.crates.contains(&plate.pos) || robot_pos == plate.pos;
+            if pressed != plate.pressed {
+                plate.pressed = pressed;
+                changed_doors.extend(plate.doors.iter().copied());
+            }
+        }
+
+        for door in &changed_doors {
+            // A door shared by several plates stays open while any of them is pressed
+            let held_open = self.plates.iter().any(|plate| plate.pressed && plate.doors.contains(door));
+            if held_open {
+                self.open_doors.insert(*door);
+            } else if !self.door_occupied(*door, robot_pos) {
+                self.open_doors.remove(door);
+            }
+        }
+    }
+
+    /// Whether the robot, a crate or an enemy stands in `door`, so it can't close
+    fn door_occupied(&self, door: Pos, robot_pos: Pos) -> bool {
+        door == robot_pos
+            || self.crates.contains(&door)
+            || self.enemies.iter().any(|enemy| enemy.occupies(door))
+    }
+    
+    pub fn is_door(&self, pos: Pos) -> bool {
+        self.doors.contains(&pos)
+    }
+    
+    pub fn is_door_open(&self, pos: Pos) -> bool {
+        self.doors.contains(&pos) && self.open_doors.contains(&pos)
+    }
+    
+    pub fn open_door(&mut self, pos: Pos) -> bool {
+        if self.doors.contains(&pos) {
+            self.open_doors.insert(pos);
+            true
+        } else {
+            false
+        }
+    }
+    
+    pub fn close_door(&mut self, pos: Pos) -> bool {
+        if self.doors.contains(&pos) {
+            self.open_doors.remove(&pos);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Color of the key the door at `pos` needs, while it is still locked
+    pub fn locked_door_color(&self, pos: Pos) -> Option<KeyColor> {
+        self.locked_doors.get(&pos).copied()
+    }
+
+    /// The door open_door() works on: the robot's own tile, or else the one it faces
+    pub fn door_in_reach(&self, robot: Pos, facing: (i32, i32)) -> Pos {
+        let ahead = Pos { x: robot.x + facing.0, y: robot.y + facing.1 };
+        if !self.is_door(robot) && self.is_door(ahead) { ahead } else { robot }
+    }
+
+    /// open_door(bool) on the door at `pos`, returning the message shown to the player.
+    /// `keys` are the colors of the keys the robot carries; the first one that fits unlocks the door for good.
+    pub fn use_door(&mut self, pos: Pos, open: bool, keys: &[KeyColor]) -> String {
+        if !self.is_door(pos) {
+            return "Robot must be on or facing a door to open/close it.".to_string();
+        }
+        if open && let Some(color) = self.locked_door_color(pos) {
+            if !keys.contains(&color) {
+                return format!("This door is locked. Collect the {} key to open it.", color.name());
+            }
+            self.locked_doors.remove(&pos);
+        }
+        match (open, self.is_door_open(pos)) {
+            (true, true) => "Door is already open.".to_string(),
+            (true, false) => {
+                self.open_door(pos);
+                "Door opened successfully!".to_string()
+            }
+            (false, false) => "Door is already closed.".to_string(),
+            (false, true) => {
+                self.close_door(pos);
+                "Door closed successfully!".to_string()
+            }
+        }
+    }
+
+    pub fn is_blocked_with_temp_removal(&self, pos: Pos, temp_removed: &std::collections::HashMap<(i32, i32), u8>) -> bool {
+        // Check if temporarily removed
+        if temp_removed.contains_key(&(pos.x, pos.y)) {
+            return false;
+        }
+        self.blockers.contains(&pos)
+    }
+
+    pub fn visit(&mut self, pos: Pos) {
+        if self.in_bounds(pos) {
+            self.visited.insert(pos);
+        }
+    }
+
+    pub fn get_enemies_at_position(&self, pos: Pos) -> Vec<&Enemy> {
+        self.enemies.iter().filter(|enemy| enemy.occupies(pos)).collect()
+    }
+}
+
+pub fn manhattan_distance(a: Pos, b: Pos) -> i32 {
+    (a.x - b.x).abs() + (a.y - b.y).abs()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::YamlLevelConfig;
+    use rand::SeedableRng;
+
+    #[test]
+    fn locked_doors_open_only_with_a_key_of_their_color() {
+        let yaml = "name: Keys\ngrid_size: \"5x1\"\nstart_position: [0, 0]\ndoors: [[1, 0], {pos: [3, 0], color: red}]\nkeys: [{pos: [2, 0], color: red}]\n";
+        let config: YamlLevelConfig = serde_yaml::from_str(yaml).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let spec = config.to_level_spec(&mut rng).unwrap();
+        assert_eq!(spec.doors.len(), 2);
+        assert_eq!(spec.items[0].name, "red_key");
+
+        let mut grid = Grid::from_level_spec(&spec, &mut rng, false);
+        let door = Pos { x: 3, y: 0 };
+        assert_eq!(grid.describe_locked_door(door).as_deref(), Some("locked_door_red at (3, 0)"));
+        assert_eq!(grid.door_in_reach(Pos { x: 2, y: 0 }, (1, 0)), door);
+        assert!(grid.use_door(door, true, &[KeyColor::Blue]).contains("red key"));
+        assert!(!grid.is_door_open(door));
+        grid.use_door(door, true, &[KeyColor::Red]);
+        assert!(grid.is_door_open(door) && grid.locked_door_color(door).is_none());
+    }
+}
@@ -0,0 +1,83 @@
+// Switches: tiles that flip on or off every time the robot steps onto them. Each switch is wired to
+// target doors or laser emitters, and a target follows every switch wired to it, combined through
+// its gate (xor unless the level says otherwise), so a lone switch simply toggles its doors.
+// A powered door is open; a powered emitter is shut off (see emitters).
+
+use super::Grid;
+use crate::item::Pos;
+
+#[derive(Clone, Debug)]
+pub struct Switch {
+    pub id: String,
+    pub pos: Pos,
+    pub on: bool,
+    pub targets: Vec<Pos>,
+}
+
+impl Grid {
+    pub fn switch_at(&self, pos: Pos) -> Option<&Switch> {
+        self.switches.iter().find(|switch| switch.pos == pos)
+    }
+
+    /// Flip the switch the robot just stepped onto at `robot_pos`, if there is one.
+    /// Returns the switch's new state.
+    pub fn flip_switch(&mut self, robot_pos: Pos) -> Option<bool> {
+        let switch = self.switches.iter_mut().find(|switch| switch.pos == robot_pos)?;
+        switch.on = !switch.on;
+        let (on, targets) = (switch.on, switch.targets.clone());
+        self.update_switch_targets(&targets, robot_pos);
+        Some(on)
+    }
+
+    /// Whether the switches wired to `target` power it through its gate
+    pub fn target_powered(&self, target: Pos) -> bool {
+        let inputs = self.switches.iter().filter(|switch| switch.targets.contains(&target)).map(|switch| switch.on);
+        self.gates.get(&target).copied().unwrap_or_default().apply(inputs)
+    }
+
+    /// Open powered doors among `targets` and close the rest, never on something standing in them
+    pub(super) fn update_switch_targets(&mut self, targets: &[Pos], robot_pos: Pos) {
+        for &target in targets {
+            if !self.doors.contains(&target) {
+                continue;
+            }
+            if self.target_powered(target) {
+                self.open_doors.insert(target);
+            } else if !self.door_occupied(target, robot_pos) {
+                self.open_doors.remove(&target);
+            }
+        }
+    }
+
+    /// How scans report a switch, e.g. "switch a (on) at (2, 3)"
+    pub fn describe_switch(&self, pos: Pos) -> Option<String> {
+        let switch = self.switch_at(pos)?;
+        Some(format!("switch {} ({}) at ({}, {})", switch.id, if switch.on { "on" } else { "off" }, pos.x, pos.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::Gate;
+
+    #[test]
+    fn switches_drive_doors_through_their_gate() {
+        let mut grid = Grid::new(5, 1);
+        let (toggled, gated) = (Pos { x: 3, y: 0 }, Pos { x: 4, y: 0 });
+        grid.doors.extend([toggled, gated]);
+        grid.gates.insert(gated, Gate::And);
+        grid.switches.push(Switch { id: "a".to_string(), pos: Pos { x: 0, y: 0 }, on: false, targets: vec![toggled, gated] });
+        grid.switches.push(Switch { id: "b".to_string(), pos: Pos { x: 1, y: 0 }, on: false, targets: vec![gated] });
+
+        assert_eq!(grid.flip_switch(Pos { x: 0, y: 0 }), Some(true));
+        assert!(grid.is_door_open(toggled) && !grid.is_door_open(gated));
+        assert_eq!(grid.flip_switch(Pos { x: 1, y: 0 }), Some(true));
+        assert!(grid.is_door_open(gated));
+        assert_eq!(grid.describe_switch(Pos { x: 1, y: 0 }).as_deref(), Some("switch b (on) at (1, 0)"));
+
+        assert_eq!(grid.flip_switch(Pos { x: 0, y: 0 }), Some(false));
+        assert!(!grid.is_door_open(toggled) && !grid.is_door_open(gated));
+        assert_eq!(grid.flip_switch(Pos { x: 2, y: 0 }), None);
+    }
+}
@@ -0,0 +1,128 @@
+// Robot program interpreter shared by the game, the WASM build and the external test runner.
+// Turns a robot program into the list of robot function calls its main() makes; the parsing itself
+// happens in the ast module.
+
+use serde::{Deserialize, Serialize};
+
+// Turns teleport() costs; enemies get to move once for each
+pub const TELEPORT_TURN_COST: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RustFunction {
+    Move,
+    MoveForward, // Move one tile in the facing direction
+    RotateLeft,
+    RotateRight,
+    Grab,
+    Scan,
+    LaserDirection,
+    LaserTile,
+    OpenDoor,
+    SetAutoGrab, // Grab on every move or only on grab(); takes no turn
+    PrintMap, // Prints the known tiles as ASCII
+    Say, // Speech bubble over the robot; takes no turn
+    Teleport, // Unlocked by collecting a teleport beacon
+    Rewind, // Unlocked by collecting a rewind item
+    Emp, // Stuns nearby enemies; unlocked by collecting an EMP emitter
+    SelectRobot, // Multi-robot levels only
+    SkipLevel,
+    GotoLevel,
+    Println,
+    Eprintln, // Error messages
+    Panic,    // Critical errors
+}
+
+#[derive(Clone, Debug)]
+pub struct FunctionCall {
+    pub function: RustFunction,
+    pub direction: Option<(i32, i32)>, // for move, scan, and laser direction
+    pub coordinates: Option<(i32, i32)>, // for laser tile targeting
+    pub level_number: Option<usize>, // for goto_level, rewind turns and the emp radius
+    pub boolean_param: Option<bool>, // for open_door and set_auto_grab
+    pub message: Option<String>, // for println and say
+    pub robot: Option<usize>, // robot(n).action() on multi-robot levels; select_robot(n) target
+}
+
+/// What happened when a robot function call ran
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ExecutionOutcome {
+    Moved { message: String }, // The robot moved (or pushed a crate)
+    Blocked { reason: String }, // A wall or stuck crate is in the way; the program stops
+    Scanned { summary: String },
+    Acted { message: String }, // Any other action that went through: grab, laser, doors, ...
+    Collision { message: String }, // An enemy caught a robot and the level was reset
+    OutOfEnergy { message: String }, // The action cost more energy than was left; the program stops
+    LevelComplete { message: String },
+    Error { message: String }, // The call couldn't run: missing argument, function not unlocked, ...
+}
+
+impl ExecutionOutcome {
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Blocked { reason: message }
+            | Self::Scanned { summary: message }
+            | Self::Moved { message }
+            | Self::Acted { message }
+            | Self::Collision { message }
+            | Self::OutOfEnergy { message }
+            | Self::LevelComplete { message }
+            | Self::Error { message } => message,
+        }
+    }
+
+    /// Add what the world did in response, like hazard or enemy events
+    pub fn append(&mut self, events: &str) {
+        let message = match self {
+            Self::Blocked { reason: message }
+            | Self::Scanned { summary: message }
+            | Self::Moved { message }
+            | Self::Acted { message }
+            | Self::Collision { message }
+            | Self::OutOfEnergy { message }
+            | Self::LevelComplete { message }
+            | Self::Error { message } => message,
+        };
+        *message = if message.is_empty() { events.to_string() } else { format!("{}. {}", message, events) };
+    }
+
+    /// Plain moves and actions that say nothing beyond "done" aren't worth a line in the results
+    pub fn is_routine(&self) -> bool {
+        matches!(self, Self::Moved { message } | Self::Acted { message } if message.is_empty() || message.ends_with("executed"))
+    }
+
+    /// Why the rest of the program doesn't run after this outcome, if it stops the program
+    pub fn halt_message(&self) -> Option<&'static str> {
+        match self {
+            Self::Blocked { .. } => Some("EXECUTION HALTED! Rewrite your program to avoid obstacles."),
+            Self::OutOfEnergy { .. } => Some("EXECUTION HALTED! Plan a shorter route or use cheaper actions."),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ExecutionOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// The robot function calls main() makes, following calls into the program's own functions, with
+/// random_int() drawing from the level seed `seed`. A program that doesn't parse makes no calls.
+pub fn parse_rust_code_from_main(code: &str, seed: u64) -> Vec<FunctionCall> {
+    match crate::ast::parse_program_with_seed(code, seed) {
+        Ok(program) => {
+            log::trace!("🔍 [PARSE] Found {} function calls", program.calls.len());
+            program.calls
+        }
+        Err(e) => {
+            log::debug!("🔍 [PARSE] Program doesn't parse: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Output of the println!, eprintln! and panic! calls main() makes, as "stdout: ...",
+/// "stderr: ..." and "panic: ..." lines
+pub fn extract_print_statements_from_main(code: &str, seed: u64) -> Vec<String> {
+    crate::ast::parse_program_with_seed(code, seed).map(|program| program.outputs).unwrap_or_default()
+}
@@ -0,0 +1,386 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct Pos {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Item {
+    pub name: String,
+    pub pos: Pos,
+    pub capabilities: ItemCapabilities,
+    pub collected: bool,
+}
+
+// Abilities an item can grant the robot when picked up
+pub const KNOWN_ABILITIES: [&str; 4] = ["teleport", "shield", "rewind", "emp"];
+
+// Keys accepted by `// CAPABILITY:` lines and capability constants
+const CAPABILITY_KEYS: [&str; 8] = [
+    "scanner_range", "grabber_boost", "credits_value", "time_slow_duration",
+    "shield_hits", "on_pickup", "description", "abilities",
+];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ItemCapabilities {
+    pub scanner_range: Option<u32>,
+    pub grabber_boost: Option<u32>,
+    pub credits_value: Option<u32>,
+    pub time_slow_duration: Option<u32>, // Milliseconds between actions
+    pub special_functions: Vec<String>,
+    #[serde(skip)]
+    pub rust_code: Option<String>, // Raw Rust code for advanced items
+    pub description: Option<String>, // First comment line of the item file, shown in tooltips
+    pub abilities: Vec<String>, // Granted on pickup, one of KNOWN_ABILITIES
+    pub shield_hits: Option<u32>, // Enemy hits absorbed when the item grants a shield
+    pub on_pickup: Option<String>, // Message shown when the item is collected
+}
+
+impl ItemCapabilities {
+    /// Human readable lines describing what the item does, for the inventory tooltip
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(description) = &self.description {
+            lines.push(description.clone());
+        }
+        if let Some(credits) = self.credits_value {
+            lines.push(format!("Worth {} credits", credits));
+        }
+        if let Some(boost) = self.grabber_boost {
+            lines.push(format!("Grabber range +{}", boost));
+        }
+        if let Some(range) = self.scanner_range {
+            lines.push(format!("Scanner range {}", range));
+        }
+        if let Some(duration) = self.time_slow_duration {
+            lines.push(format!("Slows execution to one action every {}ms", duration));
+        }
+        for ability in &self.abilities {
+            match ability.as_str() {
+                "shield" => lines.push(format!("Shield: absorbs {} enemy hit(s)", self.shield_hits.unwrap_or(1))),
+                "teleport" => lines.push("Unlocks teleport(x, y)".to_string()),
+                "emp" => lines.push("Unlocks emp(radius)".to_string()),
+                other => lines.push(format!("Ability: {}", other)),
+            }
+        }
+        if !self.special_functions.is_empty() {
+            lines.push(format!("Provides: {}", self.special_functions.iter().map(|f| format!("{}()", f)).collect::<Vec<_>>().join(", ")));
+        }
+        lines
+    }
+}
+
+impl Default for ItemCapabilities {
+    fn default() -> Self {
+        Self {
+            scanner_range: None,
+            grabber_boost: None,
+            credits_value: Some(1), // Default credit value
+            time_slow_duration: None,
+            special_functions: Vec::new(),
+            rust_code: None,
+            description: None,
+            abilities: Vec::new(),
+            shield_hits: None,
+            on_pickup: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ItemManager {
+    pub items: Vec<Item>,
+    pub collected_items: HashSet<String>,
+}
+
+impl ItemManager {
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            collected_items: HashSet::new(),
+        }
+    }
+
+    pub fn add_item(&mut self, name: String, pos: Pos, capabilities: ItemCapabilities) {
+        let item = Item {
+            name: name.clone(),
+            pos,
+            capabilities,
+            collected: false,
+        };
+
+        self.items.push(item);
+    }
+
+    pub fn collect_item(&mut self, pos: Pos) -> Option<Item> {
+        if let Some(index) = self.items.iter().position(|item| item.pos == pos && !item.collected) {
+            let mut item = self.items[index].clone();
+            item.collected = true;
+            self.items[index].collected = true;
+            self.collected_items.insert(item.name.clone());
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_item_at_position(&self, pos: Pos) -> Option<&Item> {
+        self.items.iter().find(|item| item.pos == pos && !item.collected)
+    }
+
+    pub fn has_collected(&self, item_name: &str) -> bool {
+        self.collected_items.contains(item_name)
+    }
+
+    pub fn get_collected_items(&self) -> Vec<&Item> {
+        self.items.iter().filter(|item| item.collected).collect()
+    }
+
+    pub fn get_active_items(&self) -> Vec<&Item> {
+        self.items.iter().filter(|item| !item.collected).collect()
+    }
+    
+    pub fn add_dummy_item(&mut self, name: &str) {
+        // Add a dummy collected item for tracking purposes (like tutorial shown flags)
+        self.collected_items.insert(name.to_string());
+    }
+}
+
+/// Parse an item definition file. `.yaml`/`.yml` files are structured; anything else is read as
+/// a Rust file using `// CAPABILITY: key = value`, `// ABILITY: name`, `// ON_PICKUP: text`
+/// comments or top-level `const KEY: T = value;` items.
+pub fn load_item_file(file_path: &str) -> Result<ItemCapabilities, String> {
+    let content = fs::read_to_string(file_path)
+        .map_err(|e| format!("Failed to read item file '{}': {}", file_path, e))?;
+
+    let is_yaml = Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("yaml") || e.eq_ignore_ascii_case("yml"));
+
+    let capabilities = if is_yaml {
+        serde_yaml::from_str::<ItemCapabilities>(&content)
+            .map_err(|e| format!("Invalid item file '{}': {}", file_path, e))?
+    } else {
+        parse_rust_item(&content).map_err(|e| format!("Invalid item file '{}': {}", file_path, e))?
+    };
+
+    validate_capabilities(&capabilities).map_err(|e| format!("Invalid item file '{}': {}", file_path, e))?;
+    Ok(capabilities)
+}
+
+/// Load an item file for a level, falling back to defaults (with a warning) if it can't be used
+pub fn load_item_capabilities(file_path: &str) -> ItemCapabilities {
+    if !Path::new(file_path).exists() {
+        return ItemCapabilities::default();
+    }
+    load_item_file(file_path).unwrap_or_else(|e| {
+        log::warn!("{}", e);
+        ItemCapabilities::default()
+    })
+}
+
+fn validate_capabilities(capabilities: &ItemCapabilities) -> Result<(), String> {
+    for ability in &capabilities.abilities {
+        if !KNOWN_ABILITIES.contains(&ability.as_str()) {
+            return Err(format!("unknown ability '{}' (expected one of: {})", ability, KNOWN_ABILITIES.join(", ")));
+        }
+    }
+    if capabilities.shield_hits.is_some() && !capabilities.abilities.iter().any(|a| a == "shield") {
+        return Err("shield_hits is set but the item doesn't grant the 'shield' ability".to_string());
+    }
+    Ok(())
+}
+
+fn parse_rust_item(content: &str) -> Result<ItemCapabilities, String> {
+    let mut capabilities = ItemCapabilities::default();
+
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        let at_line = |e: String| format!("line {}: {}", line_no + 1, e);
+
+        if let Some(capability) = line.strip_prefix("// CAPABILITY:") {
+            // Trailing `# note` comments are allowed after the value
+            let capability = capability.split(" #").next().unwrap_or("");
+            let (key, value) = capability.split_once('=')
+                .ok_or_else(|| at_line(format!("expected 'key = value', got '{}'", capability.trim())))?;
+            apply_capability(&mut capabilities, key.trim(), value.trim()).map_err(at_line)?;
+        } else if let Some(ability) = line.strip_prefix("// ABILITY:") {
+            apply_capability(&mut capabilities, "abilities", ability.trim()).map_err(at_line)?;
+        } else if let Some(message) = line.strip_prefix("// ON_PICKUP:") {
+            apply_capability(&mut capabilities, "on_pickup", message.trim()).map_err(at_line)?;
+        } else if let Some(declaration) = line.strip_prefix("pub const ").or_else(|| line.strip_prefix("const ")) {
+            // const CREDITS_VALUE: u32 = 20; other constants are ordinary Rust and left alone
+            let Some((name_and_type, value)) = declaration.split_once('=') else { continue };
+            let name = name_and_type.split(':').next().unwrap_or("").trim().to_lowercase();
+            if CAPABILITY_KEYS.contains(&name.as_str()) {
+                let value = value.trim().trim_end_matches(';').trim();
+                apply_capability(&mut capabilities, &name, value).map_err(at_line)?;
+            }
+        } else if let Some(comment) = line.strip_prefix("//") {
+            // The first plain comment describes the item
+            let comment = comment.trim();
+            if capabilities.description.is_none() && !comment.is_empty() {
+                capabilities.description = Some(comment.to_string());
+            }
+        } else if (line.starts_with("pub fn ") || line.starts_with("fn "))
+            && let Some(func_name) = extract_function_name(line)
+        {
+            capabilities.special_functions.push(func_name);
+        }
+    }
+
+    capabilities.rust_code = Some(content.to_string());
+    Ok(capabilities)
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches('"')
+}
+
+fn parse_number(key: &str, value: &str) -> Result<u32, String> {
+    value.trim().parse::<u32>().map_err(|_| format!("{} must be a whole number, got '{}'", key, value.trim()))
+}
+
+fn apply_capability(capabilities: &mut ItemCapabilities, key: &str, value: &str) -> Result<(), String> {
+    match key.to_lowercase().as_str() {
+        "scanner_range" => capabilities.scanner_range = Some(parse_number(key, value)?),
+        "grabber_boost" => capabilities.grabber_boost = Some(parse_number(key, value)?),
+        "credits_value" => capabilities.credits_value = Some(parse_number(key, value)?),
+        "time_slow_duration" => capabilities.time_slow_duration = Some(parse_number(key, value)?),
+        "shield_hits" => capabilities.shield_hits = Some(parse_number(key, value)?),
+        "on_pickup" => capabilities.on_pickup = Some(unquote(value).to_string()),
+        "description" => capabilities.description = Some(unquote(value).to_string()),
+        "ability" | "abilities" => {
+            // Either a single name or an array literal like ["teleport", "shield"]
+            let list = value.trim().trim_start_matches('[').trim_end_matches(']');
+            for ability in list.split(',').map(unquote).filter(|a| !a.is_empty()) {
+                if !capabilities.abilities.iter().any(|a| a == ability) {
+                    capabilities.abilities.push(ability.to_string());
+                }
+            }
+        }
+        _ => return Err(format!("unknown capability '{}'", key)),
+    }
+    Ok(())
+}
+
+fn extract_function_name(line: &str) -> Option<String> {
+    // Extract function name from "fn name(" or "pub fn name("
+    let line = line.trim_start_matches("pub ");
+    if let Some(fn_start) = line.find("fn ") {
+        let after_fn = &line[fn_start + 3..];
+        if let Some(paren_pos) = after_fn.find('(') {
+            let func_name = after_fn[..paren_pos].trim();
+            return Some(func_name.to_string());
+        }
+    }
+    None
+}
+
+// Standard item types
+pub fn create_scanner_item(pos: Pos) -> Item {
+    Item {
+        name: "scanner".to_string(),
+        pos,
+        capabilities: ItemCapabilities {
+            scanner_range: Some(1),
+            grabber_boost: None,
+            credits_value: Some(5),
+            time_slow_duration: None,
+            special_functions: vec!["scan".to_string()],
+            rust_code: None,
+            description: None,
+            abilities: Vec::new(),
+            shield_hits: None,
+            on_pickup: None,
+        },
+        collected: false,
+    }
+}
+
+pub fn create_grabber_upgrade(pos: Pos) -> Item {
+    Item {
+        name: "grabber_upgrade".to_string(),
+        pos,
+        capabilities: ItemCapabilities {
+            scanner_range: None,
+            grabber_boost: Some(1),
+            credits_value: Some(3),
+            time_slow_duration: None,
+            special_functions: Vec::new(),
+            rust_code: None,
+            description: None,
+            abilities: Vec::new(),
+            shield_hits: None,
+            on_pickup: None,
+        },
+        collected: false,
+    }
+}
+
+pub fn create_credit_gem(pos: Pos, value: u32) -> Item {
+    Item {
+        name: format!("credit_gem_{}", value),
+        pos,
+        capabilities: ItemCapabilities {
+            scanner_range: None,
+            grabber_boost: None,
+            credits_value: Some(value),
+            time_slow_duration: None,
+            special_functions: Vec::new(),
+            rust_code: None,
+            description: None,
+            abilities: Vec::new(),
+            shield_hits: None,
+            on_pickup: None,
+        },
+        collected: false,
+    }
+}
+
+pub fn create_time_slow_item(pos: Pos, duration_ms: u32) -> Item {
+    Item {
+        name: "time_slow".to_string(),
+        pos,
+        capabilities: ItemCapabilities {
+            scanner_range: None,
+            grabber_boost: None,
+            credits_value: Some(25),
+            time_slow_duration: Some(duration_ms),
+            special_functions: vec!["time_slow".to_string()],
+            rust_code: None,
+            description: None,
+            abilities: Vec::new(),
+            shield_hits: None,
+            on_pickup: None,
+        },
+        collected: false,
+    }
+}
+
+pub fn create_door_key_item(pos: Pos) -> Item {
+    Item {
+        name: "door_key".to_string(),
+        pos,
+        capabilities: ItemCapabilities {
+            scanner_range: None,
+            grabber_boost: None,
+            credits_value: Some(10),
+            time_slow_duration: None,
+            special_functions: vec!["open_door".to_string()],
+            rust_code: None,
+            description: None,
+            abilities: Vec::new(),
+            shield_hits: None,
+            on_pickup: None,
+        },
+        collected: false,
+    }
+}
\ No newline at end of file
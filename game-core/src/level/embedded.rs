@@ -0,0 +1,56 @@
+// The levels compiled into the game: the YAML files in levels/ at the workspace root, listed by
+// build.rs. Everything that plays the built-in levels (the desktop game, the WASM build and the test
+// runner) goes through here, so they can't drift apart.
+
+use super::{LevelFormat, LevelSpec, YamlLevelConfig};
+use rand::{rngs::StdRng, SeedableRng};
+
+include!(concat!(env!("OUT_DIR"), "/embedded_levels.rs"));
+
+// Places the random obstacles of the built-in levels the same way every time
+pub const EMBEDDED_LEVEL_SEED: u64 = 0xC0FFEE;
+
+/// The built-in levels in play order; a file that doesn't parse is logged and left out
+pub fn embedded_level_configs() -> Vec<YamlLevelConfig> {
+    EMBEDDED_LEVEL_FILES.iter()
+        .filter_map(|(name, content)| match LevelFormat::Yaml.parse(content) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                log::error!("Built-in level {} doesn't parse: {}", name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// The built-in levels, built with the fixed seed
+pub fn embedded_level_specs() -> Vec<LevelSpec> {
+    let mut rng = StdRng::seed_from_u64(EMBEDDED_LEVEL_SEED);
+    embedded_level_configs().into_iter()
+        .filter_map(|config| match config.to_level_spec(&mut rng) {
+            Ok(spec) => Some(spec),
+            Err(e) => {
+                log::error!("Built-in level '{}' can't be built: {}", config.name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_embedded_level_parses_and_builds() {
+        assert!(!EMBEDDED_LEVEL_FILES.is_empty());
+        assert_eq!(embedded_level_configs().len(), EMBEDDED_LEVEL_FILES.len());
+        assert_eq!(embedded_level_specs().len(), EMBEDDED_LEVEL_FILES.len());
+    }
+
+    #[test]
+    fn an_embedded_level_offers_the_ownership_view() {
+        assert!(embedded_level_configs().iter().any(|config| config.ownership_view == Some(true)));
+        assert!(embedded_level_specs().iter().any(|spec| spec.ownership_view));
+    }
+}
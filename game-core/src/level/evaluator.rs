@@ -0,0 +1,85 @@
+// Task evaluators from outside the binary, as a level's `task_file:` names them. Only an explicit
+// scheme makes a task_file an evaluator, and only the extensions below are accepted, so a typo in a
+// level never ends up running some other program:
+//
+//   script:courses/warehouse/count_crates.py   run with an interpreter (.py, .sh or .js)
+//   plugin:courses/warehouse/count_crates.so   loaded as a dynamic library (.so, .dll or .dylib)
+//
+// The paths are relative to the game directory and can't leave it.
+
+use std::path::{Component, Path};
+
+pub const SCRIPT_SCHEME: &str = "script:";
+pub const PLUGIN_SCHEME: &str = "plugin:";
+
+pub const LIBRARY_EXTENSIONS: [&str; 3] = ["so", "dll", "dylib"];
+
+/// An evaluator named by a task_file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskEvaluator<'a> {
+    Script { path: &'a Path, interpreter: &'static str },
+    Library(&'a Path),
+}
+
+impl<'a> TaskEvaluator<'a> {
+    /// The evaluator `task_file` names, `None` when it doesn't use a scheme, or why it can't be run
+    pub fn parse(task_file: &'a str) -> Option<Result<Self, String>> {
+        let (path, is_script) = if let Some(path) = task_file.strip_prefix(SCRIPT_SCHEME) {
+            (Path::new(path), true)
+        } else {
+            (Path::new(task_file.strip_prefix(PLUGIN_SCHEME)?), false)
+        };
+        Some(Self::checked(path, is_script).map_err(|problem| format!("'{}' {}", task_file, problem)))
+    }
+
+    fn checked(path: &'a Path, is_script: bool) -> Result<Self, &'static str> {
+        if !path.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir)) {
+            return Err("must be a relative path inside the game directory");
+        }
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        if is_script {
+            script_interpreter(extension)
+                .map(|interpreter| Self::Script { path, interpreter })
+                .ok_or("must be a .py, .sh or .js script")
+        } else if LIBRARY_EXTENSIONS.contains(&extension) {
+            Ok(Self::Library(path))
+        } else {
+            Err("must be a .so, .dll or .dylib library")
+        }
+    }
+
+    pub fn path(&self) -> &'a Path {
+        match self {
+            Self::Script { path, .. } | Self::Library(path) => path,
+        }
+    }
+}
+
+fn script_interpreter(extension: &str) -> Option<&'static str> {
+    match extension {
+        "py" => Some(if cfg!(windows) { "python" } else { "python3" }),
+        "sh" => Some("sh"),
+        "js" => Some("node"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_schemed_allow_listed_relative_paths_are_evaluators() {
+        assert_eq!(TaskEvaluator::parse("learning_tests::level1_task1_hello::tests"), None);
+        assert_eq!(TaskEvaluator::parse("courses/check"), None);
+        assert_eq!(
+            TaskEvaluator::parse("script:courses/check.sh"),
+            Some(Ok(TaskEvaluator::Script { path: Path::new("courses/check.sh"), interpreter: "sh" }))
+        );
+        assert_eq!(TaskEvaluator::parse("plugin:courses/check.so"), Some(Ok(TaskEvaluator::Library(Path::new("courses/check.so")))));
+
+        for rejected in ["script:courses/check.exe", "script:courses/check", "plugin:courses/check.py", "script:../check.py", "script:/tmp/check.py"] {
+            assert!(matches!(TaskEvaluator::parse(rejected), Some(Err(_))), "{} should be rejected", rejected);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+// Level file formats: levels can be written in YAML, JSON or TOML, picked by the file extension, and
+// all of them deserialize into YamlLevelConfig. Parse errors are reported the same way whatever the
+// format: the line and column first, then the parser's message, then a hint in plain words about
+// what the field takes, e.g. "line 3, column 12: obstacles: invalid type: string \"many\", expected
+// u32 (hint: `obstacles` takes a whole number of 0 or more, e.g. obstacles: 5)".
+
+use super::YamlLevelConfig;
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+
+// Extensions of the files a level directory is searched for, in the order order.txt tries them
+pub const LEVEL_EXTENSIONS: [&str; 4] = ["yaml", "yml", "json", "toml"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LevelFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl LevelFormat {
+    /// The format a file's extension stands for; None for anything that isn't a level file
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "yaml" | "yml" => Some(LevelFormat::Yaml),
+            "json" => Some(LevelFormat::Json),
+            "toml" => Some(LevelFormat::Toml),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LevelFormat::Yaml => "YAML",
+            LevelFormat::Json => "JSON",
+            LevelFormat::Toml => "TOML",
+        }
+    }
+
+    pub fn parse(self, content: &str) -> Result<YamlLevelConfig, String> {
+        self.parse_as(content)
+    }
+
+    /// Parse into any type, e.g. a serde_json::Value to merge levels before reading them as one
+    pub fn parse_as<T: DeserializeOwned>(self, content: &str) -> Result<T, String> {
+        match self {
+            LevelFormat::Yaml => serde_yaml::from_str(content).map_err(|e| describe_yaml_error(&e)),
+            LevelFormat::Json => serde_json::from_str(content).map_err(|e| describe_json_error(content, &e)),
+            LevelFormat::Toml => toml::from_str(content).map_err(|e| describe_toml_error(content, &e)),
+        }
+    }
+}
+
+/// The level file `stem` names in `dir`, whichever of the level extensions it has
+pub fn find_level_file(dir: &Path, stem: &str) -> Option<PathBuf> {
+    LEVEL_EXTENSIONS.iter().map(|ext| dir.join(format!("{}.{}", stem, ext))).find(|path| path.exists())
+}
+
+pub fn describe_yaml_error(error: &serde_yaml::Error) -> String {
+    let message = error.to_string();
+    let message = match (message.rfind(" at line "), error.location()) {
+        (Some(end), Some(_)) => message[..end].to_string(),
+        _ => message,
+    };
+
+    // Paths look like "enemies[0].start_location"; syntax errors have none
+    let (field, problem) = match message.split_once(": ") {
+        Some((path, problem)) if !path.contains(' ') => (Some(path), problem),
+        _ => (None, message.as_str()),
+    };
+    describe(error.location().map(|l| (l.line(), l.column())), &message, field, problem)
+}
+
+pub fn describe_json_error(content: &str, error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    let message = match message.rfind(" at line ") {
+        Some(end) if error.line() > 0 => message[..end].to_string(),
+        _ => message,
+    };
+    // serde_json doesn't say which field; the key on the line it stopped at is the best guess
+    let field = (error.line() > 0).then(|| line_key(content, error.line(), error.column(), LevelFormat::Json)).flatten();
+    let location = (error.line() > 0).then(|| (error.line(), error.column()));
+    describe(location, &message, field.as_deref(), &message)
+}
+
+pub fn describe_toml_error(content: &str, error: &toml::de::Error) -> String {
+    let message = error.message().trim().to_string();
+    let location = error.span().map(|span| {
+        let before = &content[..span.start.min(content.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+        (line, column)
+    });
+    let field = location.and_then(|(line, column)| line_key(content, line, column, LevelFormat::Toml));
+    describe(location, &message, field.as_deref(), &message)
+}
+
+fn describe(location: Option<(usize, usize)>, message: &str, field: Option<&str>, problem: &str) -> String {
+    // A missing field is reported at the start of the document, which doesn't help finding it
+    let mut text = match location {
+        Some((line, column)) if !problem.starts_with("missing field") => format!("line {}, column {}: {}", line, column, message),
+        _ => message.to_string(),
+    };
+    if let Some(hint) = hint(field, problem) {
+        text.push_str(&format!(" (hint: {})", hint));
+    }
+    text
+}
+
+/// The key being set on a line of a JSON or TOML file, up to `column`: "obstacles" for
+/// `"obstacles": "many"` or `obstacles = "many"`
+fn line_key(content: &str, line: usize, column: usize, format: LevelFormat) -> Option<String> {
+    let text = content.lines().nth(line.checked_sub(1)?)?;
+    let before: String = text.chars().take(column).collect();
+    let key = match format {
+        LevelFormat::Json => {
+            let end = before.rfind("\":")?;
+            &before[before[..end].rfind('"')? + 1..end]
+        }
+        _ => before.split_once('=')?.0.trim().trim_matches('"'),
+    };
+    (!key.is_empty()).then(|| key.to_string())
+}
+
+fn hint(field: Option<&str>, problem: &str) -> Option<String> {
+    if let Some(missing) = problem.strip_prefix("missing field `").and_then(|rest| rest.split('`').next()) {
+        let example = match missing {
+            "name" => "name: \"My Level\"",
+            "grid_size" => "grid_size: \"16x10\"",
+            _ => return Some(format!("add the `{}` field where the error points", missing)),
+        };
+        return Some(format!("every level needs `{}`, e.g. {}", missing, example));
+    }
+    if problem.contains("cannot start any token") {
+        return Some("YAML indents with spaces, not tabs; text starting with `@`, `` ` `` or `%` needs quotes".to_string());
+    }
+    if problem.starts_with("mapping values are not allowed") {
+        return Some("check the indentation of this line against the one above; text containing `: ` needs quotes".to_string());
+    }
+    if problem.starts_with("did not find expected") || problem.starts_with("expected") || problem.starts_with("trailing") {
+        return Some("check the quotes, brackets, commas and indentation around this line".to_string());
+    }
+
+    let field = field?;
+    // The last part of "enemies[0].start_location", without any index
+    let key = field.rsplit('.').next().unwrap_or(field);
+    let key = key.split('[').next().unwrap_or(key);
+    let expected = problem.rsplit("expected ").next().unwrap_or_default();
+    let takes = match expected {
+        "u8" | "u16" | "u32" | "u64" | "usize" => format!("a whole number of 0 or more, e.g. {}: 5", key),
+        "i8" | "i16" | "i32" | "i64" | "isize" => format!("a whole number, e.g. {}: 5", key),
+        "f32" | "f64" => format!("a number, e.g. {}: 1.5", key),
+        "a boolean" => format!("true or false, e.g. {}: true", key),
+        "a string" => format!("text, e.g. {}: \"some text\"", key),
+        "a sequence" => format!("a list, e.g. {}: [a, b]", key),
+        "a tuple of size 2" => format!("an [x, y] position, e.g. {}: [3, 4]", key),
+        _ if problem.starts_with("unknown variant") => format!("one of the values listed after \"expected\"; check the spelling of `{}`", key),
+        _ => return None,
+    };
+    Some(format!("`{}` takes {}", field, takes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_name_the_position_and_what_the_field_takes() {
+        let error = LevelFormat::Yaml.parse("name: Maze\ngrid_size: 8x8\nobstacles: many\n").unwrap_err();
+        assert_eq!(
+            error,
+            "line 3, column 12: obstacles: invalid type: string \"many\", expected u32 (hint: `obstacles` takes a whole number of 0 or more, e.g. obstacles: 5)"
+        );
+
+        let error = LevelFormat::Yaml.parse("name: Maze\n").unwrap_err();
+        assert_eq!(error, "missing field `grid_size` (hint: every level needs `grid_size`, e.g. grid_size: \"16x10\")");
+    }
+
+    #[test]
+    fn json_and_toml_levels_parse_and_report_like_yaml() {
+        let json = LevelFormat::Json.parse("{\"name\": \"Maze\", \"grid_size\": \"8x8\", \"start_position\": [1, 2]}").unwrap();
+        let toml = LevelFormat::Toml.parse("name = \"Maze\"\ngrid_size = \"8x8\"\nstart_position = [1, 2]\n").unwrap();
+        assert_eq!((json.name, json.start_position), (toml.name, toml.start_position));
+
+        let error = LevelFormat::Json.parse("{\n  \"name\": \"Maze\",\n  \"grid_size\": \"8x8\",\n  \"obstacles\": \"many\"\n}").unwrap_err();
+        assert!(error.starts_with("line 4, column 21: invalid type: string \"many\", expected u32 (hint: `obstacles` takes"), "{}", error);
+        let error = LevelFormat::Toml.parse("name = \"Maze\"\ngrid_size = \"8x8\"\nobstacles = \"many\"\n").unwrap_err();
+        assert!(error.starts_with("line 3, column 13: invalid type: string \"many\", expected u32 (hint: `obstacles` takes"), "{}", error);
+    }
+}
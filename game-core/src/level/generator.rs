@@ -0,0 +1,361 @@
+// Procedural level generator used by Endless Mode and the generate-level command.
+// Every generated level is solvable: walls are only kept if the start can still reach
+// every open tile, so all items are reachable by construction. Sizes are clamped to what
+// validate-level accepts.
+
+use super::validation::MAX_GRID_SIDE;
+use super::{EnemyConfig, ItemConfig, YamlLevelConfig};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashSet, VecDeque};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LevelTheme {
+    Maze,   // Carved corridors one tile wide
+    Cavern, // Irregular walls scattered across open ground
+    Open,   // A few pillars, mostly open space
+}
+
+impl LevelTheme {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "maze" => Some(LevelTheme::Maze),
+            "cavern" | "cave" => Some(LevelTheme::Cavern),
+            "open" => Some(LevelTheme::Open),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LevelTheme::Maze => "Maze",
+            LevelTheme::Cavern => "Cavern",
+            LevelTheme::Open => "Open Field",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GeneratorParams {
+    pub width: usize,
+    pub height: usize,
+    pub obstacle_density: f32, // Fraction of tiles that are walls (0.0 - 0.6)
+    pub enemy_count: usize,
+    pub item_count: usize,
+    pub theme: LevelTheme,
+}
+
+impl Default for GeneratorParams {
+    fn default() -> Self {
+        Self {
+            width: 12,
+            height: 8,
+            obstacle_density: 0.25,
+            enemy_count: 1,
+            item_count: 3,
+            theme: LevelTheme::Maze,
+        }
+    }
+}
+
+impl GeneratorParams {
+    /// Difficulty curve for Endless Mode: bigger grids, more walls, items and enemies each round
+    pub fn for_endless_depth(depth: usize) -> Self {
+        let themes = [LevelTheme::Open, LevelTheme::Cavern, LevelTheme::Maze];
+        Self {
+            width: (10 + depth).min(20),
+            height: (8 + depth / 2).min(14),
+            obstacle_density: (0.15 + 0.03 * depth as f32).min(0.4),
+            enemy_count: (depth / 2).min(5),
+            item_count: (2 + depth / 2).min(6),
+            theme: themes[depth % themes.len()],
+        }
+    }
+}
+
+const ITEM_FILES: [&str; 3] = ["items/credit_gem.rs", "items/silver_coin.rs", "items/golden_gem.rs"];
+
+type Tile = (usize, usize);
+
+fn neighbors(tile: Tile, width: usize, height: usize) -> Vec<Tile> {
+    let (x, y) = tile;
+    let mut result = Vec::with_capacity(4);
+    if x > 0 { result.push((x - 1, y)); }
+    if y > 0 { result.push((x, y - 1)); }
+    if x + 1 < width { result.push((x + 1, y)); }
+    if y + 1 < height { result.push((x, y + 1)); }
+    result
+}
+
+fn reachable_from(start: Tile, walls: &HashSet<Tile>, width: usize, height: usize) -> HashSet<Tile> {
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+    seen.insert(start);
+    queue.push_back(start);
+    while let Some(tile) = queue.pop_front() {
+        for next in neighbors(tile, width, height) {
+            if !walls.contains(&next) && seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    seen
+}
+
+/// Whether walling off `tile` keeps the open tiles around it connected without a search: its open
+/// neighbours have to be joined through the ring of eight tiles surrounding it. This turns down a
+/// few walls a full search would allow, but never one that cuts the level in two.
+fn keeps_open_connected(tile: Tile, walls: &HashSet<Tile>, width: usize, height: usize) -> bool {
+    // Clockwise from the top left corner; the odd entries are the four neighbours
+    const RING: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0)];
+    let open: Vec<bool> = RING
+        .iter()
+        .map(|&(dx, dy)| {
+            let (x, y) = (tile.0 as isize + dx, tile.1 as isize + dy);
+            x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height && !walls.contains(&(x as usize, y as usize))
+        })
+        .collect();
+
+    // Count the runs of open ring tiles that hold a neighbour; a ring with no gap is one run
+    let mut runs_with_neighbour = 0;
+    for start in (0..RING.len()).filter(|&i| open[i] && !open[(i + RING.len() - 1) % RING.len()]) {
+        let mut run = (start..start + RING.len()).take_while(|&i| open[i % RING.len()]);
+        if run.any(|i| i % 2 == 1) {
+            runs_with_neighbour += 1;
+        }
+    }
+    runs_with_neighbour <= 1
+}
+
+/// Carve a maze with a randomized depth-first search; walls sit between odd-coordinate cells
+fn carve_maze<R: Rng>(width: usize, height: usize, start: Tile, rng: &mut R) -> HashSet<Tile> {
+    let mut walls: HashSet<Tile> = (0..width)
+        .flat_map(|x| (0..height).map(move |y| (x, y)))
+        .collect();
+
+    walls.remove(&start);
+    let mut stack = vec![start];
+    while let Some(&(x, y)) = stack.last() {
+        let mut options: Vec<(Tile, Tile)> = Vec::new();
+        if x >= 2 && walls.contains(&(x - 2, y)) { options.push(((x - 2, y), (x - 1, y))); }
+        if y >= 2 && walls.contains(&(x, y - 2)) { options.push(((x, y - 2), (x, y - 1))); }
+        if x + 2 < width && walls.contains(&(x + 2, y)) { options.push(((x + 2, y), (x + 1, y))); }
+        if y + 2 < height && walls.contains(&(x, y + 2)) { options.push(((x, y + 2), (x, y + 1))); }
+
+        match options.choose(rng) {
+            Some(&(cell, between)) => {
+                walls.remove(&between);
+                walls.remove(&cell);
+                stack.push(cell);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+
+    // Cells the carver can't reach (even-sized edges) stay walls; drop any the start can't reach
+    let reachable = reachable_from(start, &walls, width, height);
+    for x in 0..width {
+        for y in 0..height {
+            if !reachable.contains(&(x, y)) {
+                walls.insert((x, y));
+            }
+        }
+    }
+    walls
+}
+
+/// Build the wall layout for the given theme, guaranteeing every open tile is reachable
+fn generate_walls<R: Rng>(params: &GeneratorParams, start: Tile, rng: &mut R) -> HashSet<Tile> {
+    let (width, height) = (params.width, params.height);
+    let total = width * height;
+    let density = match params.theme {
+        LevelTheme::Open => params.obstacle_density.min(0.15),
+        _ => params.obstacle_density,
+    };
+    let target = ((total as f32) * density.clamp(0.0, 0.6)) as usize;
+
+    match params.theme {
+        LevelTheme::Maze => {
+            let mut walls = carve_maze(width, height, start, rng);
+            // Open up loops until we're down to the requested density; a removed wall always
+            // touches an open tile so the level stays connected
+            let mut candidates: Vec<Tile> = walls.iter().copied().collect();
+            candidates.sort();
+            candidates.shuffle(rng);
+            for wall in candidates {
+                if walls.len() <= target {
+                    break;
+                }
+                if neighbors(wall, width, height).iter().any(|n| !walls.contains(n)) {
+                    walls.remove(&wall);
+                }
+            }
+            walls
+        }
+        LevelTheme::Cavern | LevelTheme::Open => {
+            let mut walls = HashSet::new();
+            let mut candidates: Vec<Tile> = (0..width)
+                .flat_map(|x| (0..height).map(move |y| (x, y)))
+                .filter(|&tile| tile != start)
+                .collect();
+            candidates.shuffle(rng);
+
+            for tile in candidates {
+                if walls.len() >= target {
+                    break;
+                }
+                // Pillars in open fields shouldn't touch each other
+                if params.theme == LevelTheme::Open
+                    && neighbors(tile, width, height).iter().any(|n| walls.contains(n))
+                {
+                    continue;
+                }
+                if keeps_open_connected(tile, &walls, width, height) {
+                    walls.insert(tile);
+                }
+            }
+            walls
+        }
+    }
+}
+
+/// Generate a random but solvable level
+pub fn generate_level<R: Rng>(params: &GeneratorParams, rng: &mut R) -> YamlLevelConfig {
+    let params = GeneratorParams {
+        width: params.width.clamp(4, MAX_GRID_SIDE),
+        height: params.height.clamp(4, MAX_GRID_SIDE),
+        ..params.clone()
+    };
+    let start: Tile = (1, 1);
+    let walls = generate_walls(&params, start, rng);
+
+    let mut open_tiles: Vec<Tile> = reachable_from(start, &walls, params.width, params.height)
+        .into_iter()
+        .filter(|&tile| tile != start)
+        .collect();
+    open_tiles.sort();
+    open_tiles.shuffle(rng);
+
+    // Items first so they get the pick of the reachable tiles
+    let mut items = Vec::new();
+    for i in 0..params.item_count {
+        let Some((x, y)) = open_tiles.pop() else { break };
+        let is_goal = i + 1 == params.item_count;
+        items.push(ItemConfig {
+            name: if is_goal { "goal".to_string() } else { format!("gem_{}", i + 1) },
+            item_file: if is_goal { "items/goal.rs".to_string() } else { ITEM_FILES[i % ITEM_FILES.len()].to_string() },
+            spawn_randomly: Some(false),
+            location: Some((x as u32, y as u32)),
+        });
+    }
+
+    // Keep enemies a few steps away from the start so the first move is never fatal
+    let mut enemies = Vec::new();
+    open_tiles.retain(|&(x, y)| x.abs_diff(start.0) + y.abs_diff(start.1) >= 3);
+    for _ in 0..params.enemy_count {
+        let Some((x, y)) = open_tiles.pop() else { break };
+        let horizontal = rng.gen_bool(0.5);
+        enemies.push(EnemyConfig {
+            start_location: (x as u32, y as u32),
+            movement_pattern: if horizontal { "horizontal" } else { "vertical" }.to_string(),
+            moving_positive: Some(rng.gen_bool(0.5)),
+            hp: None,
+            boss: None,
+            aggro_radius: None,
+        });
+    }
+
+    let mut wall_list: Vec<(u32, u32)> = walls.iter().map(|&(x, y)| (x as u32, y as u32)).collect();
+    wall_list.sort();
+
+    YamlLevelConfig {
+        name: format!("Generated {} {}x{}", params.theme.name(), params.width, params.height),
+        grid_size: format!("{}x{}", params.width, params.height),
+        obstacles: None,
+        walls: Some(wall_list),
+        doors: None,
+        keys: None,
+        switches: None,
+        gates: None,
+        real_time_limit_secs: None,
+        win_condition: None,
+        terrain: None,
+        spawners: None,
+        flanking: None,
+        laser_emitters: None,
+        enemies: if enemies.is_empty() { None } else { Some(enemies) },
+        items: Some(items),
+        tasks: None,
+        income_per_square: Some(1),
+        start_position: Some((start.0 as u32, start.1 as u32)),
+        max_turns: Some(0),
+        fog_of_war: Some(params.theme != LevelTheme::Open),
+        message: Some(format!(
+            "A procedurally generated {} level.\n\nCollect every item to finish it. Use move_bot(), scan() and grab() to find your way.",
+            params.theme.name().to_lowercase()
+        )),
+        hint_message: Some("Every item is reachable from the start - scan() ahead if the fog hides the way.".to_string()),
+        rust_docs_url: None,
+        starting_code: None,
+        completion_condition: None,
+        completion_flag: None,
+        achievement_message: Some("Level cleared! Another one is waiting.".to_string()),
+        next_level_hint: None,
+        completion_message: Some("Collect all items on the grid to complete this level.".to_string()),
+        energy: None,
+        crates: None,
+        plates: None,
+        conveyors: None,
+        spikes: None,
+        robots: None,
+        mirrors: None,
+        message_i18n: None,
+        ownership_view: None,
+        seed: None,
+        allowed_functions: None,
+        auto_grab: None,
+        tags: Vec::new(),
+        difficulty: None,
+        author: None,
+        version: None,
+        requires: Vec::new(),
+    }
+}
+
+/// Serialize a level as YAML, leaving out unset fields so the file stays readable
+pub fn level_to_yaml(config: &YamlLevelConfig) -> Result<String, String> {
+    let mut value = serde_yaml::to_value(config).map_err(|e| e.to_string())?;
+    if let serde_yaml::Value::Mapping(map) = &mut value {
+        map.retain(|_, v| !v.is_null());
+    }
+    serde_yaml::to_string(&value).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::path::Path;
+
+    #[test]
+    fn generated_levels_pass_validation() {
+        for theme in [LevelTheme::Maze, LevelTheme::Cavern, LevelTheme::Open] {
+            for seed in [1, 7, 42, 1337] {
+                let params = GeneratorParams { width: 16, height: 12, obstacle_density: 0.45, enemy_count: 3, theme, ..GeneratorParams::default() };
+                let config = generate_level(&params, &mut StdRng::seed_from_u64(seed));
+                // Item files live in the game's items/ folder at the workspace root
+                let problems = super::super::validation::validate_level_config(&config, Path::new(".."));
+                assert!(problems.is_empty(), "{:?} seed {}: {:?}", theme, seed, problems);
+            }
+        }
+    }
+
+    #[test]
+    fn oversized_requests_are_clamped() {
+        let params = GeneratorParams { width: 500, height: 500, theme: LevelTheme::Cavern, ..GeneratorParams::default() };
+        let config = generate_level(&params, &mut StdRng::seed_from_u64(3));
+        assert_eq!(config.grid_size, format!("{}x{}", MAX_GRID_SIDE, MAX_GRID_SIDE));
+    }
+}
@@ -0,0 +1,102 @@
+// Level inheritance: a level file with `extends: base_level.yaml` starts from that base level and
+// only writes the fields it changes. Bases can extend other bases. Fields merge key by key, so a
+// level can change one entry of a base's `energy:` or `task_message_i18n:`; anything else (lists
+// like `items:`, numbers, text) is replaced whole, and `~` / null clears what the base set.
+// Base files are named relative to the level and have to stay inside its directory.
+
+use super::pack::sandboxed_path;
+use super::format::describe_json_error;
+use super::{LevelFormat, YamlLevelConfig};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const EXTENDS_KEY: &str = "extends";
+
+/// The level in `path`, with the levels it extends merged under it
+pub fn load_level(path: &Path) -> Result<YamlLevelConfig, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let format = LevelFormat::from_path(path).unwrap_or(LevelFormat::Yaml);
+    let document: Value = format.parse_as(&content)?;
+    if document.get(EXTENDS_KEY).is_none() {
+        // Reading the text itself keeps the line numbers in any error
+        return format.parse(&content);
+    }
+
+    let mut chain = vec![canonical(path)?];
+    let merged = resolve(path, document, &mut chain)?;
+    serde_json::from_value(merged).map_err(|e| format!("{} (once merged with the levels it extends)", describe_json_error("", &e)))
+}
+
+/// `document` with its base merged under it, all the way down; `chain` holds the files on the way
+fn resolve(path: &Path, mut document: Value, chain: &mut Vec<PathBuf>) -> Result<Value, String> {
+    let Some(base) = document.as_object_mut().and_then(|fields| fields.remove(EXTENDS_KEY)) else { return Ok(document) };
+    let Some(base) = base.as_str() else {
+        return Err("`extends` has to be a file name, e.g. extends: base_level.yaml".to_string());
+    };
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let base_path = sandboxed_path(dir, base).ok_or_else(|| format!("`extends: {}` has to name a file inside {}", base, dir.display()))?;
+    let base_canonical = canonical(&base_path)?;
+
+    if let Some(start) = chain.iter().position(|seen| *seen == base_canonical) {
+        let names: Vec<String> = chain[start..].iter().chain([&base_canonical]).map(|seen| file_name(seen)).collect();
+        return Err(format!("`extends` goes round in a cycle: {}", names.join(" -> ")));
+    }
+
+    let content = fs::read_to_string(&base_path).map_err(|e| format!("Failed to read {}: {}", base_path.display(), e))?;
+    let format = LevelFormat::from_path(&base_path).unwrap_or(LevelFormat::Yaml);
+    let base_document: Value = format.parse_as(&content).map_err(|e| format!("{}: {}", file_name(&base_path), e))?;
+
+    chain.push(base_canonical);
+    let base_document = resolve(&base_path, base_document, chain)?;
+    chain.pop();
+    Ok(merge(base_document, document))
+}
+
+/// `over` on top of `base`: mappings merge key by key, anything else in `over` wins
+fn merge(base: Value, over: Value) -> Value {
+    match (base, over) {
+        (Value::Object(mut base), Value::Object(over)) => {
+            for (key, value) in over {
+                let merged = match base.remove(&key) {
+                    Some(base_value) => merge(base_value, value),
+                    None => value,
+                };
+                base.insert(key, merged);
+            }
+            Value::Object(base)
+        }
+        (_, over) => over,
+    }
+}
+
+fn canonical(path: &Path) -> Result<PathBuf, String> {
+    path.canonicalize().map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_override_their_base_and_cycles_are_refused() {
+        let dir = std::env::temp_dir().join(format!("level-extends-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("base.yaml"), "name: Base\ngrid_size: 8x8\nmessage: Hello\nenergy:\n  capacity: 40\n  move_cost: 2\n").unwrap();
+        fs::write(dir.join("child.toml"), "extends = \"base.yaml\"\nname = \"Child\"\n[energy]\ncapacity = 10\n").unwrap();
+        fs::write(dir.join("a.yaml"), "extends: b.yaml\nname: A\n").unwrap();
+        fs::write(dir.join("b.yaml"), "extends: a.yaml\ngrid_size: 4x4\n").unwrap();
+
+        let child = load_level(&dir.join("child.toml")).unwrap();
+        assert_eq!((child.name.as_str(), child.grid_size.as_str(), child.message.as_deref()), ("Child", "8x8", Some("Hello")));
+        let energy = child.energy.unwrap();
+        assert_eq!((energy.capacity, energy.move_cost), (10, 2));
+
+        assert_eq!(load_level(&dir.join("a.yaml")).unwrap_err(), "`extends` goes round in a cycle: a.yaml -> b.yaml -> a.yaml");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,1016 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::fs;
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use crate::interpreter::{RustFunction, TELEPORT_TURN_COST};
+use crate::item::{load_item_capabilities, ItemCapabilities};
+use crate::win_conditions::WinCondition;
+
+pub mod validation;
+pub mod generator;
+pub mod sanitize;
+pub mod pack;
+pub mod format;
+pub mod inherit;
+pub mod embedded;
+pub mod evaluator;
+
+pub use validation::validate_level_file;
+pub use format::{describe_yaml_error, find_level_file, LevelFormat, LEVEL_EXTENSIONS};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct YamlLevelConfig {
+    pub name: String,
+    pub grid_size: String, // Format: "WxH" like "16x10"
+    pub obstacles: Option<u32>, // Number of random obstacles to place
+    #[serde(default)]
+    pub walls: Option<Vec<(u32, u32)>>, // Fixed obstacle positions (used by generated levels)
+    pub doors: Option<Vec<DoorConfig>>, // Door positions, [x, y] or {pos: [x, y], color: red} for a locked door
+    #[serde(default)]
+    pub keys: Option<Vec<KeyConfig>>, // Keys that unlock the doors of their color
+    #[serde(default)]
+    pub switches: Option<Vec<SwitchConfig>>, // Tiles that flip when the robot steps on them, wired to doors
+    #[serde(default)]
+    pub gates: Option<Vec<GateConfig>>, // How a target wired to several switches combines them
+    pub enemies: Option<Vec<EnemyConfig>>,
+    pub items: Option<Vec<ItemConfig>>,
+    pub tasks: Option<Vec<TaskConfig>>, // Multiple tasks for sequential completion
+    pub income_per_square: Option<u32>,
+    pub start_position: Option<(u32, u32)>,
+    pub max_turns: Option<u32>,
+    pub fog_of_war: Option<bool>,
+    pub message: Option<String>, // Popup message shown at level start
+    pub hint_message: Option<String>, // Hint message shown when hint button is pressed
+    pub rust_docs_url: Option<String>, // URL to relevant Rust documentation
+    pub starting_code: Option<String>, // Initial code to show in editor
+    pub completion_condition: Option<String>, // Special completion conditions: "println", "error", "panic", etc.
+    pub completion_flag: Option<String>, // Detailed completion requirements (e.g., "println:Hello, Rust!")
+    pub achievement_message: Option<String>, // Message shown when level is completed
+    pub next_level_hint: Option<String>, // Hint about what the next level will teach
+    pub completion_message: Option<String>, // Instructions on how to complete the level (Ctrl+Shift+C)
+    #[serde(default)]
+    pub energy: Option<EnergyConfig>, // Optional energy budget for efficiency-focused levels
+    #[serde(default)]
+    pub crates: Option<Vec<(u32, u32)>>, // Pushable crate positions
+    #[serde(default)]
+    pub plates: Option<Vec<PlateConfig>>, // Pressure plates and the doors they hold open
+    #[serde(default)]
+    pub conveyors: Option<Vec<ConveyorConfig>>, // Belts that shift the robot at the end of each turn
+    #[serde(default)]
+    pub spikes: Option<Vec<SpikeConfig>>, // Traps that are deadly every other turn
+    #[serde(default)]
+    pub laser_emitters: Option<Vec<EmitterConfig>>, // Wall-mounted lasers that fire on an on/off turn cycle
+    #[serde(default)]
+    pub terrain: Option<Vec<TerrainConfig>>, // Mud and ice tiles that change how moves play out
+    #[serde(default)]
+    pub spawners: Option<Vec<SpawnerConfig>>, // Tiles that add an enemy every few turns, up to a cap
+    #[serde(default)]
+    pub flanking: Option<bool>, // Chase enemies head for different sides of the robot instead of one path
+    #[serde(default, with = "serde_yaml::with::singleton_map_recursive")]
+    pub win_condition: Option<WinCondition>, // Replaces "collect every item" as what wins the level
+    #[serde(default)]
+    pub real_time_limit_secs: Option<u32>, // Wall-clock seconds to finish the level in; resets it when they run out
+    #[serde(default)]
+    pub robots: Option<Vec<(u32, u32)>>, // Extra robots; robot 0 always starts at start_position
+    #[serde(default)]
+    pub mirrors: Option<Vec<MirrorConfig>>, // Obstacles that reflect laser beams
+    #[serde(default)]
+    pub message_i18n: Option<HashMap<String, String>>, // Translations of `message`, keyed by language code ("es", "de")
+    #[serde(default)]
+    pub ownership_view: Option<bool>, // Offer the ownership timeline tab (borrow/ownership teaching levels)
+    #[serde(default)]
+    pub seed: Option<u64>, // Pins random placement, enemy movement and random_int() to the same values every play
+    #[serde(default)]
+    pub allowed_functions: Option<Vec<String>>, // Robot functions programs may call here (e.g. [move_bot, scan]); all when missing
+    #[serde(default)]
+    pub auto_grab: Option<bool>, // Whether moving onto an item picks it up; the player's setting when missing
+    #[serde(default)]
+    pub tags: Vec<String>, // Topics the level teaches, e.g. [traits, serde], for searching the level list
+    #[serde(default)]
+    pub difficulty: Option<String>, // e.g. "beginner", shown and filtered on in the level list
+    #[serde(default)]
+    pub author: Option<String>, // Who made the level, shown in the level list
+    #[serde(default)]
+    pub version: Option<String>, // e.g. "1.2", so players can tell an updated level from the old one
+    #[serde(default)]
+    pub requires: Vec<String>, // Names of the levels a campaign needs completed before this one
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConveyorConfig {
+    pub position: (u32, u32),
+    pub direction: String, // "up", "down", "left" or "right"
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpikeConfig {
+    pub position: (u32, u32),
+    #[serde(default)]
+    pub phase: u32, // 0 = deadly on even turns, 1 = deadly on odd turns
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EmitterConfig {
+    pub position: (u32, u32),
+    pub direction: String, // Where the beam goes: "up", "down", "left" or "right"
+    #[serde(default = "default_emitter_turns")]
+    pub on_turns: u32, // Turns the beam fires for
+    #[serde(default = "default_emitter_turns")]
+    pub off_turns: u32, // Turns it then stays off for
+    #[serde(default)]
+    pub phase: u32, // Turns into the cycle the emitter starts
+}
+
+fn default_emitter_turns() -> u32 {
+    1
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpawnerConfig {
+    pub position: (u32, u32),
+    #[serde(default = "default_spawn_every")]
+    pub every: u32, // Turns between spawns; the first enemy comes after this many turns too
+    #[serde(default = "default_spawn_max")]
+    pub max: u32, // Enemies it spawns over the whole level
+    #[serde(default = "default_spawn_pattern")]
+    pub movement_pattern: String, // For the enemies it spawns, as for `enemies`
+    #[serde(default)]
+    pub hp: Option<u32>,
+    #[serde(default)]
+    pub aggro_radius: Option<u32>,
+}
+
+fn default_spawn_every() -> u32 { 5 }
+fn default_spawn_max() -> u32 { 3 }
+fn default_spawn_pattern() -> String { "chase".to_string() }
+
+impl SpawnerConfig {
+    /// The enemy it spawns, as if it were listed under `enemies` on the spawner's tile
+    pub fn enemy_config(&self) -> EnemyConfig {
+        EnemyConfig {
+            start_location: self.position,
+            movement_pattern: self.movement_pattern.clone(),
+            moving_positive: None,
+            hp: self.hp,
+            boss: None,
+            aggro_radius: self.aggro_radius,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TerrainConfig {
+    pub position: (u32, u32),
+    pub kind: Terrain,
+}
+
+/// Ground that changes how the robot moves over it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Terrain {
+    Mud, // Moving onto it takes 2 turns
+    Ice, // The robot slides on until something stops it
+}
+
+impl Terrain {
+    pub fn name(self) -> &'static str {
+        match self {
+            Terrain::Mud => "mud",
+            Terrain::Ice => "ice",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MirrorConfig {
+    pub position: (u32, u32),
+    pub kind: Mirror,
+}
+
+/// Mirror orientation as drawn on screen, written "/" or "\\" in level files
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mirror {
+    #[serde(rename = "/")]
+    Slash,
+    #[serde(rename = "\\")]
+    Backslash,
+}
+
+impl Mirror {
+    /// Direction a beam travelling along (dx, dy) leaves the mirror in (y grows downwards)
+    pub fn reflect(self, (dx, dy): (i32, i32)) -> (i32, i32) {
+        match self {
+            Mirror::Slash => (-dy, -dx),
+            Mirror::Backslash => (dy, dx),
+        }
+    }
+
+    pub fn symbol(self) -> char {
+        match self {
+            Mirror::Slash => '/',
+            Mirror::Backslash => '\\',
+        }
+    }
+}
+
+/// Color of a locked door and of the key that opens it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyColor {
+    Red,
+    Blue,
+    Green,
+    Yellow,
+}
+
+impl KeyColor {
+    pub const ALL: [KeyColor; 4] = [KeyColor::Red, KeyColor::Blue, KeyColor::Green, KeyColor::Yellow];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyColor::Red => "red",
+            KeyColor::Blue => "blue",
+            KeyColor::Green => "green",
+            KeyColor::Yellow => "yellow",
+        }
+    }
+
+    /// Name of the item that opens doors of this color, e.g. "red_key"
+    pub fn key_item(self) -> String {
+        format!("{}_key", self.name())
+    }
+
+    /// The color a key item opens, for items named like "red_key"
+    pub fn of_key_item(name: &str) -> Option<KeyColor> {
+        KeyColor::ALL.into_iter().find(|color| name == color.key_item())
+    }
+}
+
+/// A door in a level file: a bare [x, y], or {pos: [x, y], color: red} for one a key has to unlock
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DoorConfig {
+    Plain((u32, u32)),
+    Locked {
+        #[serde(alias = "position")]
+        pos: (u32, u32),
+        color: KeyColor,
+    },
+}
+
+impl DoorConfig {
+    pub fn position(&self) -> (u32, u32) {
+        match self {
+            DoorConfig::Plain(pos) | DoorConfig::Locked { pos, .. } => *pos,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KeyConfig {
+    #[serde(alias = "position")]
+    pub pos: (u32, u32),
+    pub color: KeyColor,
+}
+
+/// Parse a direction name used in level files into a (dx, dy) step
+pub fn parse_direction(direction: &str) -> Option<(i32, i32)> {
+    match direction.trim().to_lowercase().as_str() {
+        "up" => Some((0, -1)),
+        "down" => Some((0, 1)),
+        "left" => Some((-1, 0)),
+        "right" => Some((1, 0)),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SwitchConfig {
+    pub id: String, // Name scans report the switch by
+    pub position: (u32, u32),
+    pub targets: Vec<(u32, u32)>, // Doors the switch drives
+    #[serde(default)]
+    pub on: bool, // State the level starts in
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GateConfig {
+    pub target: (u32, u32),
+    pub gate: Gate,
+}
+
+/// How the switches wired to one target combine; a target with a single switch follows it either way
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Gate {
+    #[default]
+    Xor, // Every flip of any of its switches toggles the target
+    And,
+    Or,
+}
+
+impl Gate {
+    /// Whether switches in the states `inputs` power the target
+    pub fn apply(self, inputs: impl IntoIterator<Item = bool>) -> bool {
+        let mut inputs = inputs.into_iter();
+        match self {
+            Gate::Xor => inputs.fold(false, |powered, on| powered ^ on),
+            Gate::And => inputs.all(|on| on),
+            Gate::Or => inputs.any(|on| on),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlateConfig {
+    pub position: (u32, u32),
+    pub doors: Vec<(u32, u32)>, // Doors that stay open while the plate is weighted
+}
+
+/// Energy budget for a level; every robot action spends some and running out ends the run
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EnergyConfig {
+    pub capacity: u32,
+    #[serde(default = "default_move_cost")]
+    pub move_cost: u32,
+    #[serde(default = "default_scan_cost")]
+    pub scan_cost: u32,
+    #[serde(default = "default_laser_cost")]
+    pub laser_cost: u32,
+    #[serde(default = "default_emp_cost")]
+    pub emp_cost: u32,
+}
+
+impl EnergyConfig {
+    /// A budget of `capacity` with the default cost of every action
+    pub fn with_capacity(capacity: u32) -> Self {
+        Self {
+            capacity,
+            move_cost: default_move_cost(),
+            scan_cost: default_scan_cost(),
+            laser_cost: default_laser_cost(),
+            emp_cost: default_emp_cost(),
+        }
+    }
+
+    /// What one call of `function` costs; actions not listed here are free
+    pub fn cost(&self, function: RustFunction) -> u32 {
+        match function {
+            RustFunction::Move | RustFunction::MoveForward => self.move_cost,
+            RustFunction::Scan => self.scan_cost,
+            RustFunction::LaserDirection | RustFunction::LaserTile => self.laser_cost,
+            RustFunction::Emp => self.emp_cost,
+            RustFunction::Teleport => self.move_cost * TELEPORT_TURN_COST as u32,
+            _ => 0,
+        }
+    }
+
+    /// Pay for `function` out of `remaining`, or report that the robot ran out of energy
+    pub fn spend(&self, remaining: &mut u32, function: RustFunction) -> Result<(), String> {
+        let cost = self.cost(function);
+        if *remaining < cost {
+            return Err(format!("⚡ OUT OF ENERGY! {:?} needs {} energy but only {} is left.", function, cost, remaining));
+        }
+        *remaining -= cost;
+        Ok(())
+    }
+}
+
+fn default_move_cost() -> u32 { 1 }
+fn default_scan_cost() -> u32 { 2 }
+fn default_laser_cost() -> u32 { 5 }
+fn default_emp_cost() -> u32 { 8 }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnemyConfig {
+    pub start_location: (u32, u32),
+    pub movement_pattern: String, // "horizontal", "vertical", or "file:path/to/pattern.rs"
+    pub moving_positive: Option<bool>, // true = right/down, false = left/up
+    #[serde(default)]
+    pub hp: Option<u32>, // Hit points; enemies without hp can only be stunned
+    #[serde(default)]
+    pub boss: Option<BossConfig>,
+    #[serde(default)]
+    pub aggro_radius: Option<u32>, // chase and chase_smart enemies only go after a robot this close
+}
+
+impl EnemyConfig {
+    pub fn to_spec(&self) -> EnemySpec {
+        let (direction, movement_pattern) = if self.movement_pattern.starts_with("file:") {
+            // Custom movement pattern from file
+            (EnemyDirection::Horizontal, Some(self.movement_pattern.clone()))
+        } else {
+            // Built-in movement pattern or special custom patterns
+            let dir = match self.movement_pattern.as_str() {
+                "horizontal" => EnemyDirection::Horizontal,
+                "vertical" => EnemyDirection::Vertical,
+                _ => EnemyDirection::Horizontal, // Default
+            };
+
+            // Check for special custom patterns (Level 6 robot fleet patterns)
+            let pattern = match self.movement_pattern.as_str() {
+                "ownership_demo" | "borrowing_demo" | "clone_demo" => {
+                    println!("🤖 Loading Level 6 robot: {} at position ({}, {})",
+                        self.movement_pattern, self.start_location.0, self.start_location.1);
+                    Some(self.movement_pattern.clone())
+                },
+                "horizontal" | "vertical" => None,
+                pattern if BOSS_PHASE_PATTERNS.contains(&pattern) => Some(pattern.to_string()),
+                _ => None
+            };
+
+            (dir, pattern)
+        };
+        
+        EnemySpec {
+            pos: (self.start_location.0 as i32, self.start_location.1 as i32),
+            direction,
+            moving_positive: self.moving_positive.unwrap_or(true),
+            movement_pattern,
+            hp: self.hp,
+            size: self.boss.as_ref().map_or(1, |boss| boss.size.max(1)),
+            boss_phases: self.boss.as_ref().map(|boss| boss.phases.clone()).unwrap_or_default(),
+            aggro_radius: self.aggro_radius,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BossConfig {
+    #[serde(default = "default_boss_size")]
+    pub size: u32, // The boss covers size x size tiles, start_location is its top-left tile
+    #[serde(default)]
+    pub phases: Vec<BossPhase>,
+}
+
+/// Once the boss is down to `hp_below` hit points it switches to `movement_pattern`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BossPhase {
+    pub hp_below: u32,
+    pub movement_pattern: String, // horizontal, vertical, random, diagonal, circular, chase or chase_smart
+}
+
+fn default_boss_size() -> u32 { 2 }
+
+/// Movement patterns a boss phase may switch to
+pub const BOSS_PHASE_PATTERNS: [&str; 7] = ["horizontal", "vertical", "random", "diagonal", "circular", "chase", "chase_smart"];
+
+/// Movement patterns that follow the robot and so can have an `aggro_radius`
+pub const CHASE_PATTERNS: [&str; 2] = ["chase", "chase_smart"];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemConfig {
+    pub name: String,
+    pub item_file: String, // Path to rust file with item capabilities
+    pub spawn_randomly: Option<bool>, // If true, spawned randomly; if false, placed at specific location
+    pub location: Option<(u32, u32)>, // Specific location if spawn_randomly is false
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaskConfig {
+    pub name: String,
+    pub task_file: Option<String>, // Path to rust test file
+    pub task_message: Option<String>, // Instructions in markdown
+    pub completion_message: Option<String>, // Message shown when task is completed
+    pub start_task_message: Option<String>, // Optional message shown when task starts
+    pub required_conditions: Option<Vec<TaskCondition>>, // Game state conditions to check
+    #[serde(default)]
+    pub task_message_i18n: Option<HashMap<String, String>>, // Translations of `task_message`, keyed by language code
+    #[serde(default)]
+    pub hint_actions: Option<Vec<String>>, // Robot calls the tutorial ghost acts out, e.g. move_bot("right")
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaskCondition {
+    pub condition_type: String, // "objects_destroyed", "grids_scanned", "enemies_destroyed", etc.
+    pub target_value: TaskTarget, // Target value or "all"
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TaskTarget {
+    Number(u32),
+    String(String), // For "all" or other string conditions
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LevelSpec {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub start: (usize, usize),
+    pub scanner_at: Option<(usize, usize)>,
+    pub blockers: Vec<(usize, usize)>,
+    pub doors: Vec<(usize, usize)>, // Door positions
+    #[serde(default)]
+    pub locked_doors: Vec<LockedDoorSpec>, // Doors among `doors` that need a key of their color
+    #[serde(default)]
+    pub switches: Vec<SwitchSpec>,
+    #[serde(default)]
+    pub gates: Vec<((usize, usize), Gate)>, // Targets that don't use the default xor gate
+    pub enemies: Vec<EnemySpec>,
+    pub items: Vec<ItemSpec>,
+    pub tasks: Vec<TaskSpec>, // Sequential tasks for completion
+    pub fog_of_war: bool,
+    pub max_turns: usize,
+    pub income_per_square: u32,
+    pub message: Option<String>, // Popup message shown at level start
+    pub hint_message: Option<String>, // Hint message shown when hint button is pressed
+    pub rust_docs_url: Option<String>, // URL to relevant Rust documentation
+    pub starting_code: Option<String>, // Initial code to show in editor
+    pub completion_condition: Option<String>, // Special completion conditions: "println", "error", "panic", etc.
+    pub completion_flag: Option<String>, // Detailed completion requirements (e.g., "println:Hello, Rust!")
+    pub achievement_message: Option<String>, // Message shown when level is completed
+    pub next_level_hint: Option<String>, // Hint about what the next level will teach
+    pub completion_message: Option<String>, // Instructions on how to complete the level (Ctrl+Shift+C)
+    #[serde(default)]
+    pub energy: Option<EnergyConfig>,
+    #[serde(default)]
+    pub crates: Vec<(usize, usize)>,
+    #[serde(default)]
+    pub plates: Vec<PlateSpec>,
+    #[serde(default)]
+    pub conveyors: Vec<ConveyorSpec>,
+    #[serde(default)]
+    pub spikes: Vec<SpikeSpec>,
+    #[serde(default)]
+    pub emitters: Vec<EmitterSpec>,
+    #[serde(default)]
+    pub terrain: Vec<((usize, usize), Terrain)>,
+    #[serde(default)]
+    pub spawners: Vec<SpawnerSpec>,
+    #[serde(default)]
+    pub flanking: bool,
+    #[serde(default)]
+    pub win_condition: Option<WinCondition>,
+    #[serde(default)]
+    pub real_time_limit_secs: Option<u32>,
+    #[serde(default)]
+    pub robots: Vec<(usize, usize)>, // Start positions of robots 1.. (robot 0 uses `start`)
+    #[serde(default)]
+    pub mirrors: Vec<MirrorSpec>,
+    #[serde(default)]
+    pub message_i18n: HashMap<String, String>, // Language code -> translated `message`
+    #[serde(default)]
+    pub ownership_view: bool,
+    #[serde(default)]
+    pub seed: Option<u64>, // Level seed pinned in YAML; unpinned levels get a fresh one each load
+    #[serde(default)]
+    pub allowed_functions: Option<Vec<String>>, // Narrows the base function set (see capabilities)
+    #[serde(default)]
+    pub auto_grab: Option<bool>, // Pins auto-grab on or off, e.g. on levels that teach grab()
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub difficulty: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub requires: Vec<String>,
+}
+
+impl LevelSpec {
+    /// Level start message in the language with code `language`, falling back to `message`
+    pub fn localized_message(&self, language: &str) -> Option<&str> {
+        self.message_i18n.get(language).or(self.message.as_ref()).map(String::as_str)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MirrorSpec {
+    pub pos: (usize, usize),
+    pub kind: Mirror,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConveyorSpec {
+    pub pos: (usize, usize),
+    pub direction: (i32, i32),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpikeSpec {
+    pub pos: (usize, usize),
+    pub phase: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpawnerSpec {
+    pub pos: (usize, usize),
+    pub every: u32,
+    pub max: u32,
+    pub enemy: EnemySpec, // What it spawns; `pos` is the spawner's tile
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EmitterSpec {
+    pub pos: (usize, usize),
+    pub direction: (i32, i32),
+    pub on_turns: u32,
+    pub off_turns: u32,
+    pub phase: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SwitchSpec {
+    pub id: String,
+    pub pos: (usize, usize),
+    pub targets: Vec<(usize, usize)>,
+    pub on: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LockedDoorSpec {
+    pub pos: (usize, usize),
+    pub color: KeyColor,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlateSpec {
+    pub pos: (usize, usize),
+    pub doors: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnemySpec {
+    pub pos: (i32, i32),
+    pub direction: EnemyDirection,
+    pub moving_positive: bool,
+    pub movement_pattern: Option<String>, // For custom movement patterns
+    #[serde(default)]
+    pub hp: Option<u32>,
+    #[serde(default = "default_enemy_size")]
+    pub size: u32, // 1 for normal enemies
+    #[serde(default)]
+    pub boss_phases: Vec<BossPhase>, // Empty unless the enemy is a boss
+    #[serde(default)]
+    pub aggro_radius: Option<u32>,
+}
+
+fn default_enemy_size() -> u32 { 1 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum EnemyDirection {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemSpec {
+    pub name: String,
+    pub pos: Option<(i32, i32)>,
+    pub capabilities: ItemCapabilities,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaskSpec {
+    pub name: String,
+    pub task_file: Option<String>, // Path to rust test file
+    pub task_message: Option<String>, // Instructions in markdown
+    pub completion_message: Option<String>, // Message shown when task is completed
+    pub start_task_message: Option<String>, // Optional message shown when task starts
+    pub required_conditions: Vec<TaskCondition>, // Game state conditions to check
+    pub completed: bool, // Track if task is completed
+    #[serde(default)]
+    pub task_message_i18n: HashMap<String, String>, // Language code -> translated `task_message`
+    #[serde(default)]
+    pub hint_actions: Vec<String>, // Suggested next robot calls for the tutorial ghost
+}
+
+impl TaskSpec {
+    /// Task instructions in the language with code `language`, falling back to `task_message`
+    pub fn localized_task_message(&self, language: &str) -> Option<&str> {
+        self.task_message_i18n.get(language).or(self.task_message.as_ref()).map(String::as_str)
+    }
+}
+
+impl YamlLevelConfig {
+    pub fn from_yaml_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let config: YamlLevelConfig = serde_yaml::from_str(&content).map_err(|e| describe_yaml_error(&e))?;
+        Ok(config)
+    }
+
+    /// Load a level in the format its extension names (.yaml, .yml, .json or .toml; anything else is
+    /// read as YAML), merged over the level it `extends` if it names one
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(inherit::load_level(path.as_ref())?)
+    }
+
+    pub fn to_level_spec<R: Rng>(&self, rng: &mut R) -> Result<LevelSpec, Box<dyn std::error::Error>> {
+        // A pinned seed places random obstacles and items the same way every time
+        let mut pinned = self.seed.map(StdRng::seed_from_u64);
+        let rng: &mut dyn RngCore = match &mut pinned {
+            Some(pinned) => pinned,
+            None => rng,
+        };
+
+        // Parse grid size
+        let parts: Vec<&str> = self.grid_size.split('x').collect();
+        if parts.len() != 2 {
+            return Err("Grid size must be in format 'WxH' (e.g., '16x10')".into());
+        }
+        
+        let width: usize = parts[0].parse()?;
+        let height: usize = parts[1].parse()?;
+        
+        // Set default start position or use specified one
+        let start = self.start_position
+            .map(|(x, y)| (x as usize, y as usize))
+            .unwrap_or((1, 1));
+        
+        // Fixed walls first, then random obstacles around them
+        let mut blockers: Vec<(usize, usize)> = self.walls.iter()
+            .flatten()
+            .map(|(x, y)| (*x as usize, *y as usize))
+            .filter(|pos| *pos != start)
+            .collect();
+        if let Some(obstacle_count) = self.obstacles {
+            for _ in 0..obstacle_count {
+                loop {
+                    let x = rng.gen_range(0..width);
+                    let y = rng.gen_range(0..height);
+                    let pos = (x, y);
+                    
+                    // Don't place obstacles on start position or existing obstacles
+                    if pos != start && !blockers.contains(&pos) {
+                        blockers.push(pos);
+                        break;
+                    }
+                }
+            }
+        }
+        
+        // Convert enemies
+        for enemy in self.enemies.iter().flatten() {
+            for phase in enemy.boss.iter().flat_map(|boss| &boss.phases) {
+                if !BOSS_PHASE_PATTERNS.contains(&phase.movement_pattern.as_str()) {
+                    return Err(format!("Boss at {:?} has unknown movement pattern '{}'", enemy.start_location, phase.movement_pattern).into());
+                }
+            }
+        }
+
+        let enemies = self.enemies.iter().flatten().map(EnemyConfig::to_spec).collect();
+        let spawners = self.spawners.iter().flatten().map(|spawner| SpawnerSpec {
+            pos: (spawner.position.0 as usize, spawner.position.1 as usize),
+            every: spawner.every,
+            max: spawner.max,
+            enemy: spawner.enemy_config().to_spec(),
+        }).collect();
+        
+        // Convert items
+        let mut items: Vec<ItemSpec> = self.items.as_ref()
+            .map(|items| {
+                items.iter().map(|item| {
+                    let pos = if item.spawn_randomly.unwrap_or(false) {
+                        // Generate random position
+                        loop {
+                            let x = rng.gen_range(0..width as i32);
+                            let y = rng.gen_range(0..height as i32);
+                            let pos = Some((x, y));
+                            
+                            // Don't place items on start position
+                            if pos != Some((start.0 as i32, start.1 as i32)) {
+                                break pos;
+                            }
+                        }
+                    } else {
+                        item.location.map(|(x, y)| (x as i32, y as i32))
+                    };
+                    
+                    // Load item capabilities from file
+                    let capabilities = load_item_capabilities(&item.item_file);
+                    
+                    ItemSpec {
+                        name: item.name.clone(),
+                        pos,
+                        capabilities,
+                    }
+                }).collect()
+            })
+            .unwrap_or_default();
+        
+        // Handle scanner placement - if there's an item named "scanner", use it
+        let scanner_at = items.iter()
+            .find(|item| item.name.to_lowercase() == "scanner")
+            .and_then(|scanner| scanner.pos)
+            .map(|(x, y)| (x as usize, y as usize));
+        
+        // Convert tasks
+        let tasks = self.tasks.as_ref()
+            .map(|tasks| {
+                tasks.iter().map(|task| {
+                    let required_conditions = task.required_conditions.clone().unwrap_or_default();
+                    
+                    TaskSpec {
+                        name: task.name.clone(),
+                        task_file: task.task_file.clone(),
+                        task_message: task.task_message.clone(),
+                        completion_message: task.completion_message.clone(),
+                        start_task_message: task.start_task_message.clone(),
+                        required_conditions,
+                        completed: false, // Initially not completed
+                        task_message_i18n: task.task_message_i18n.clone().unwrap_or_default(),
+                        hint_actions: task.hint_actions.clone().unwrap_or_default(),
+                    }
+                }).collect()
+            })
+            .unwrap_or_default();
+        
+        let mut conveyors = Vec::new();
+        for conveyor in self.conveyors.iter().flatten() {
+            let direction = parse_direction(&conveyor.direction).ok_or_else(|| {
+                format!("Conveyor at {:?} has unknown direction '{}' (use up, down, left or right)", conveyor.position, conveyor.direction)
+            })?;
+            conveyors.push(ConveyorSpec {
+                pos: (conveyor.position.0 as usize, conveyor.position.1 as usize),
+                direction,
+            });
+        }
+        let mut emitters = Vec::new();
+        for emitter in self.laser_emitters.iter().flatten() {
+            let direction = parse_direction(&emitter.direction).ok_or_else(|| {
+                format!("Laser emitter at {:?} has unknown direction '{}' (use up, down, left or right)", emitter.position, emitter.direction)
+            })?;
+            emitters.push(EmitterSpec {
+                pos: (emitter.position.0 as usize, emitter.position.1 as usize),
+                direction,
+                on_turns: emitter.on_turns,
+                off_turns: emitter.off_turns,
+                phase: emitter.phase,
+            });
+        }
+
+        // Convert doors
+        let doors = self.doors.iter().flatten()
+            .map(|door| (door.position().0 as usize, door.position().1 as usize))
+            .collect();
+        let locked_doors = self.doors.iter().flatten()
+            .filter_map(|door| match door {
+                DoorConfig::Locked { pos, color } => Some(LockedDoorSpec { pos: (pos.0 as usize, pos.1 as usize), color: *color }),
+                DoorConfig::Plain(_) => None,
+            })
+            .collect();
+
+        let switches = self.switches.iter().flatten().map(|switch| SwitchSpec {
+            id: switch.id.clone(),
+            pos: (switch.position.0 as usize, switch.position.1 as usize),
+            targets: switch.targets.iter().map(|(x, y)| (*x as usize, *y as usize)).collect(),
+            on: switch.on,
+        }).collect();
+        let gates = self.gates.iter().flatten()
+            .map(|gate| ((gate.target.0 as usize, gate.target.1 as usize), gate.gate))
+            .collect();
+
+        // Keys are items named after their color, so they show up and are collected like any other
+        items.extend(self.keys.iter().flatten().map(|key| ItemSpec {
+            name: key.color.key_item(),
+            pos: Some((key.pos.0 as i32, key.pos.1 as i32)),
+            capabilities: ItemCapabilities {
+                description: Some(format!("Unlocks {} doors", key.color.name())),
+                ..ItemCapabilities::default()
+            },
+        }));
+        
+        Ok(LevelSpec {
+            name: self.name.clone(),
+            width,
+            height,
+            start,
+            scanner_at,
+            blockers,
+            doors,
+            locked_doors,
+            switches,
+            gates,
+            enemies,
+            items,
+            tasks,
+            fog_of_war: self.fog_of_war.unwrap_or(true),
+            max_turns: self.max_turns.unwrap_or(0) as usize,
+            income_per_square: self.income_per_square.unwrap_or(1),
+            message: self.message.clone(),
+            hint_message: self.hint_message.clone(),
+            rust_docs_url: self.rust_docs_url.clone(),
+            starting_code: self.starting_code.clone(),
+            completion_condition: self.completion_condition.clone(),
+            completion_flag: self.completion_flag.clone(),
+            achievement_message: self.achievement_message.clone(),
+            next_level_hint: self.next_level_hint.clone(),
+            completion_message: self.completion_message.clone(),
+            energy: self.energy,
+            crates: self.crates.iter().flatten().map(|(x, y)| (*x as usize, *y as usize)).collect(),
+            plates: self.plates.iter().flatten().map(|plate| PlateSpec {
+                pos: (plate.position.0 as usize, plate.position.1 as usize),
+                doors: plate.doors.iter().map(|(x, y)| (*x as usize, *y as usize)).collect(),
+            }).collect(),
+            conveyors,
+            spikes: self.spikes.iter().flatten().map(|spike| SpikeSpec {
+                pos: (spike.position.0 as usize, spike.position.1 as usize),
+                phase: spike.phase % 2,
+            }).collect(),
+            emitters,
+            terrain: self.terrain.iter().flatten()
+                .map(|tile| ((tile.position.0 as usize, tile.position.1 as usize), tile.kind))
+                .collect(),
+            spawners,
+            flanking: self.flanking.unwrap_or(false),
+            win_condition: self.win_condition.clone(),
+            real_time_limit_secs: self.real_time_limit_secs,
+            robots: self.robots.iter().flatten().map(|(x, y)| (*x as usize, *y as usize)).collect(),
+            mirrors: self.mirrors.iter().flatten().map(|mirror| MirrorSpec {
+                pos: (mirror.position.0 as usize, mirror.position.1 as usize),
+                kind: mirror.kind,
+            }).collect(),
+            message_i18n: self.message_i18n.clone().unwrap_or_default(),
+            ownership_view: self.ownership_view.unwrap_or(false),
+            seed: self.seed,
+            allowed_functions: self.allowed_functions.clone(),
+            auto_grab: self.auto_grab,
+            tags: self.tags.clone(),
+            difficulty: self.difficulty.clone(),
+            author: self.author.clone(),
+            version: self.version.clone(),
+            requires: self.requires.clone(),
+        })
+    }
+}
+
+pub fn load_yaml_levels_from_directory<P: AsRef<Path>>(dir: P) -> Vec<YamlLevelConfig> {
+    let dir_path = dir.as_ref();
+    let order_file = dir_path.join("order.txt");
+    
+    // Try to load ordered list first
+    if let Ok(order_content) = fs::read_to_string(&order_file) {
+        let mut levels = Vec::new();
+        
+        for line in order_content.lines() {
+            let line = line.trim();
+            // Skip empty lines and comments
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            
+            // Try to load the specified file, in whichever format it's written
+            let Some(level_path) = find_level_file(dir_path, line) else {
+                log::warn!("Skipping '{}' from {}: no level file by that name", line, order_file.display());
+                continue;
+            };
+            match YamlLevelConfig::from_file(&level_path) {
+                Ok(level) => levels.push(level),
+                Err(e) => log::warn!("Skipping {}: {}", level_path.display(), e),
+            }
+        }
+        
+        // If we found ordered levels, return them
+        if !levels.is_empty() {
+            return levels;
+        }
+    }
+    
+    // Fallback: load all level files in directory order (alphabetical)
+    let mut levels = Vec::new();
+    let mut paths = Vec::new();
+    
+    if let Ok(entries) = fs::read_dir(dir_path) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if LevelFormat::from_path(&path).is_some() {
+                paths.push(path);
+            }
+        }
+    }
+    
+    // Sort paths alphabetically
+    paths.sort();
+    
+    for path in paths {
+        match YamlLevelConfig::from_file(&path) {
+            Ok(level) => levels.push(level),
+            Err(e) => log::warn!("Skipping {}: {}", path.display(), e),
+        }
+    }
+    
+    levels
+}
+/// Collect the level files named directly or found inside the given directories
+pub fn collect_level_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut dir_files: Vec<PathBuf> = fs::read_dir(path)
+                .map(|entries| entries.flatten().map(|e| e.path()).collect())
+                .unwrap_or_default();
+            dir_files.retain(|p| LevelFormat::from_path(p).is_some() || p.extension().is_some_and(|ext| ext == pack::PACK_EXTENSION));
+            dir_files.sort();
+            files.extend(dir_files);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    files
+}
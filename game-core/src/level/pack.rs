@@ -0,0 +1,216 @@
+// Level packs: a .rlgpack file is a zip holding level.yaml together with the files it uses (item
+// capability files, movement pattern scripts, markdown docs), so a level and everything it needs
+// can be shared as one file.
+//
+// Packs come from other players, so extraction is sandboxed: every entry has to resolve to a plain
+// relative path inside the pack's directory. Absolute paths, `..`, symlinks and unexpected file
+// types reject the whole pack instead of being skipped.
+
+use super::YamlLevelConfig;
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+pub const PACK_EXTENSION: &str = "rlgpack";
+pub const PACK_LEVEL_FILE: &str = "level.yaml";
+
+// Extensions a pack may contain: the level, item and movement pattern files, and docs
+const PACK_FILE_TYPES: [&str; 5] = ["yaml", "yml", "rs", "md", "txt"];
+const MAX_PACK_ENTRIES: usize = 256;
+const MAX_PACK_BYTES: u64 = 8 * 1024 * 1024;
+
+/// An extracted pack, with the level's file references pointing into `dir`
+#[derive(Clone, Debug)]
+pub struct LevelPack {
+    pub dir: PathBuf,
+    pub config: YamlLevelConfig,
+    pub docs: Vec<PathBuf>, // Markdown files shipped with the level
+}
+
+/// Where zip entry `name` goes inside `root`, or None if it could end up anywhere else
+pub fn sandboxed_path(root: &Path, name: &str) -> Option<PathBuf> {
+    // Zips made on Windows may use backslashes
+    let name = name.replace('\\', "/");
+    let relative = Path::new(&name);
+    let mut path = root.to_path_buf();
+    let mut depth = 0;
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => {
+                path.push(part);
+                depth += 1;
+            }
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (depth > 0).then_some(path)
+}
+
+fn is_pack_file_type(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| PACK_FILE_TYPES.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Unpack `pack_file` into `dest`/<pack name>/, replacing an earlier extraction of the same pack,
+/// and load its level
+pub fn extract_level_pack(pack_file: &Path, dest: &Path) -> Result<LevelPack, String> {
+    let name = pack_file.file_stem().and_then(|stem| stem.to_str()).ok_or("Pack has no file name")?;
+    let dir = sandboxed_path(dest, name).ok_or_else(|| format!("Invalid pack name '{}'", name))?;
+
+    let file = fs::File::open(pack_file).map_err(|e| format!("Failed to open {}: {}", pack_file.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("{} is not a valid pack: {}", pack_file.display(), e))?;
+    if archive.len() > MAX_PACK_ENTRIES {
+        return Err(format!("Pack has {} files (limit {})", archive.len(), MAX_PACK_ENTRIES));
+    }
+
+    // Check every entry before writing anything so a bad pack leaves nothing behind
+    let mut total = 0u64;
+    for index in 0..archive.len() {
+        let entry = archive.by_index(index).map_err(|e| e.to_string())?;
+        let target = sandboxed_path(&dir, entry.name()).ok_or_else(|| format!("Pack entry '{}' points outside the pack", entry.name()))?;
+        if entry.is_symlink() {
+            return Err(format!("Pack entry '{}' is a symlink", entry.name()));
+        }
+        if entry.is_file() && !is_pack_file_type(&target) {
+            return Err(format!("Pack entry '{}' isn't a level, Rust or markdown file", entry.name()));
+        }
+        total += entry.size();
+    }
+    if total > MAX_PACK_BYTES {
+        return Err(format!("Pack unpacks to {} KB (limit {} KB)", total / 1024, MAX_PACK_BYTES / 1024));
+    }
+    if archive.by_name(PACK_LEVEL_FILE).is_err() {
+        return Err(format!("Pack has no {}", PACK_LEVEL_FILE));
+    }
+
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| format!("Failed to replace {}: {}", dir.display(), e))?;
+    }
+    // Whatever goes wrong from here on, don't leave half a pack behind
+    let extracted = write_entries(&mut archive, &dir).and_then(|docs| {
+        let mut config = YamlLevelConfig::from_yaml_file(dir.join(PACK_LEVEL_FILE)).map_err(|e| format!("Failed to parse {}: {}", PACK_LEVEL_FILE, e))?;
+        rebase_files(&mut config, &dir);
+        Ok((config, docs))
+    });
+    match extracted {
+        Ok((config, docs)) => Ok(LevelPack { dir, config, docs }),
+        Err(e) => {
+            let _ = fs::remove_dir_all(&dir);
+            Err(e)
+        }
+    }
+}
+
+/// Write every entry of a checked archive into `dir` and return the markdown files among them
+fn write_entries(archive: &mut zip::ZipArchive<fs::File>, dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut docs = Vec::new();
+    // The sizes in the headers can lie, so the limit is enforced on what's actually read, across all entries
+    let mut written = 0u64;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| e.to_string())?;
+        let Some(target) = sandboxed_path(dir, entry.name()) else { continue };
+        if entry.is_dir() {
+            fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut contents = Vec::new();
+        (&mut entry).take(MAX_PACK_BYTES - written + 1).read_to_end(&mut contents).map_err(|e| e.to_string())?;
+        written += contents.len() as u64;
+        if written > MAX_PACK_BYTES {
+            return Err(format!("Pack unpacks to more than {} KB", MAX_PACK_BYTES / 1024));
+        }
+        fs::write(&target, contents).map_err(|e| e.to_string())?;
+        if target.extension().is_some_and(|ext| ext == "md") {
+            docs.push(target);
+        }
+    }
+    docs.sort();
+    Ok(docs)
+}
+
+/// Point item, movement pattern and task files at the copies in the pack. References to files the
+/// pack doesn't contain (like the game's own items/) are left alone.
+fn rebase_files(config: &mut YamlLevelConfig, dir: &Path) {
+    let rebase = |file: &mut String| {
+        if let Some(path) = sandboxed_path(dir, file).filter(|path| path.is_file()) {
+            *file = path.to_string_lossy().replace('\\', "/");
+        }
+    };
+    for item in config.items.iter_mut().flatten() {
+        rebase(&mut item.item_file);
+    }
+    for enemy in config.enemies.iter_mut().flatten() {
+        if let Some(pattern_file) = enemy.movement_pattern.strip_prefix("file:") {
+            let mut file = pattern_file.to_string();
+            rebase(&mut file);
+            enemy.movement_pattern = format!("file:{}", file);
+        }
+    }
+    for task in config.tasks.iter_mut().flatten() {
+        if let Some(task_file) = &mut task.task_file {
+            rebase(task_file);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sandboxed_path_stays_inside_the_pack() {
+        let root = Path::new("packs/demo");
+        assert_eq!(sandboxed_path(root, "items/key.rs"), Some(root.join("items").join("key.rs")));
+        assert_eq!(sandboxed_path(root, "./level.yaml"), Some(root.join("level.yaml")));
+        assert_eq!(sandboxed_path(root, "../escape.rs"), None);
+        assert_eq!(sandboxed_path(root, "items/../../escape.rs"), None);
+        assert_eq!(sandboxed_path(root, "..\\escape.rs"), None);
+        assert_eq!(sandboxed_path(root, "/etc/passwd"), None);
+        assert_eq!(sandboxed_path(root, ""), None);
+    }
+
+    /// A pack with level.yaml and two 5 MB text files whose headers both claim 1 byte
+    fn lying_pack(path: &Path) {
+        use std::io::Write;
+        let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        let stored = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        writer.start_file(PACK_LEVEL_FILE, stored).unwrap();
+        writer.write_all(b"name: Liar\ngrid_size: 6x6\nstart_position: [1, 1]\n").unwrap();
+        for name in ["a.txt", "b.txt"] {
+            writer.start_file(name, stored).unwrap();
+            writer.write_all(&vec![b'x'; 5 * 1024 * 1024]).unwrap();
+        }
+        writer.finish().unwrap();
+
+        // Rewrite the uncompressed size in the local and central headers of the big entries
+        let mut bytes = fs::read(path).unwrap();
+        let real = (5u32 * 1024 * 1024).to_le_bytes();
+        for (signature, offset) in [([0x50, 0x4b, 0x03, 0x04], 22), ([0x50, 0x4b, 0x01, 0x02], 24)] {
+            let headers: Vec<usize> = bytes.windows(4).enumerate().filter(|(_, w)| *w == signature).map(|(i, _)| i).collect();
+            for header in headers {
+                if bytes[header + offset..header + offset + 4] == real {
+                    bytes[header + offset..header + offset + 4].copy_from_slice(&1u32.to_le_bytes());
+                }
+            }
+        }
+        fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn packs_that_lie_about_their_size_are_cut_off_and_removed() {
+        let root = std::env::temp_dir().join(format!("rlgpack-test-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let pack = root.join("liar.rlgpack");
+        lying_pack(&pack);
+
+        let error = extract_level_pack(&pack, &root.join("levels")).unwrap_err();
+        assert!(error.contains("more than"), "{}", error);
+        assert!(!root.join("levels").join("liar").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,145 @@
+// Limits for the text in community level files. Anything a level author writes ends up in a
+// popup or the task panel, so before a community level is shown its texts are cut to a sane
+// length, stripped of control and bidi override characters, and optionally have offensive words
+// masked. The validator reports the same problems so authors can fix them before sharing.
+
+use super::YamlLevelConfig;
+
+pub const MAX_NAME_CHARS: usize = 80;
+pub const MAX_MESSAGE_CHARS: usize = 2000;
+pub const MAX_CODE_CHARS: usize = 10_000;
+
+// Whole words only, so names like "Scunthorpe" or "Dickens" aren't caught
+const FILTERED_WORDS: [&str; 16] = [
+    "arse", "arsehole", "asshole", "bastard", "bitch", "bullshit", "cock", "cunt",
+    "dick", "fag", "faggot", "fuck", "fucking", "nigger", "shit", "slut",
+];
+
+/// `text` cut to `max_chars` (ending in "…" when cut) without control characters other than
+/// newlines and tabs, and without the bidi overrides that can make text read differently than it's
+/// stored. With `filter_words` listed offensive words are replaced by asterisks.
+pub fn sanitize_text(text: &str, max_chars: usize, filter_words: bool) -> String {
+    let mut clean: String = text.chars().filter(|c| !is_unsafe_char(*c)).collect();
+    if filter_words {
+        clean = mask_filtered_words(&clean);
+    }
+    if clean.chars().count() > max_chars {
+        clean = clean.chars().take(max_chars.saturating_sub(1)).collect();
+        clean.push('…');
+    }
+    clean
+}
+
+fn is_unsafe_char(c: char) -> bool {
+    (c.is_control() && c != '\n' && c != '\t')
+        || matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+fn is_filtered(word: &str) -> bool {
+    FILTERED_WORDS.contains(&word.to_lowercase().as_str())
+}
+
+fn mask_filtered_words(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut word = String::new();
+    let flush = |word: &mut String, result: &mut String| {
+        if is_filtered(word) {
+            result.push_str(&"*".repeat(word.chars().count()));
+        } else {
+            result.push_str(word);
+        }
+        word.clear();
+    };
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut result);
+            result.push(c);
+        }
+    }
+    flush(&mut word, &mut result);
+    result
+}
+
+/// Call `f` with a label, the text and its length limit for every player-visible text in the
+/// level. Prose fields get `true`; code, which the word filter must not touch, gets `false`.
+fn for_each_text(config: &mut YamlLevelConfig, mut f: impl FnMut(&str, &mut String, usize, bool)) {
+    f("name", &mut config.name, MAX_NAME_CHARS, true);
+    let messages = [
+        ("message", &mut config.message),
+        ("hint_message", &mut config.hint_message),
+        ("achievement_message", &mut config.achievement_message),
+        ("next_level_hint", &mut config.next_level_hint),
+        ("completion_message", &mut config.completion_message),
+    ];
+    for (label, text) in messages {
+        if let Some(text) = text {
+            f(label, text, MAX_MESSAGE_CHARS, true);
+        }
+    }
+    for (language, text) in config.message_i18n.iter_mut().flatten() {
+        f(&format!("message_i18n.{}", language), text, MAX_MESSAGE_CHARS, true);
+    }
+    if let Some(code) = &mut config.starting_code {
+        f("starting_code", code, MAX_CODE_CHARS, false);
+    }
+    for (index, task) in config.tasks.iter_mut().flatten().enumerate() {
+        let prefix = format!("Task {}", index + 1);
+        f(&format!("{} name", prefix), &mut task.name, MAX_NAME_CHARS, true);
+        let messages = [
+            ("task_message", &mut task.task_message),
+            ("completion_message", &mut task.completion_message),
+            ("start_task_message", &mut task.start_task_message),
+        ];
+        for (label, text) in messages {
+            if let Some(text) = text {
+                f(&format!("{} {}", prefix, label), text, MAX_MESSAGE_CHARS, true);
+            }
+        }
+        for (language, text) in task.task_message_i18n.iter_mut().flatten() {
+            f(&format!("{} task_message_i18n.{}", prefix, language), text, MAX_MESSAGE_CHARS, true);
+        }
+    }
+}
+
+impl YamlLevelConfig {
+    /// Apply the community text limits to every text in the level, see sanitize_text
+    pub fn sanitize_text(&mut self, filter_words: bool) {
+        for_each_text(self, |_, text, max_chars, prose| {
+            *text = sanitize_text(text, max_chars, filter_words && prose);
+        });
+    }
+}
+
+pub(super) fn check_text(config: &YamlLevelConfig, problems: &mut Vec<String>) {
+    for_each_text(&mut config.clone(), |label, text, max_chars, prose| {
+        let length = text.chars().count();
+        if length > max_chars {
+            problems.push(format!("{} is {} characters long (limit {}) and will be cut off", label, length, max_chars));
+        }
+        if text.chars().any(is_unsafe_char) {
+            problems.push(format!("{} contains control or text direction characters, which are removed", label));
+        }
+        if prose && mask_filtered_words(text) != *text {
+            problems.push(format!("{} contains words the community text filter masks", label));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_control_characters_and_truncates() {
+        assert_eq!(sanitize_text("a\u{7}b\u{202E}c\nd\te", 100, false), "abc\nd\te");
+        assert_eq!(sanitize_text("abcdef", 4, false), "abc…");
+    }
+
+    #[test]
+    fn masks_whole_words_only() {
+        assert_eq!(sanitize_text("Oh SHIT, Scunthorpe", 100, true), "Oh ****, Scunthorpe");
+        assert_eq!(sanitize_text("Oh shit", 100, false), "Oh shit");
+    }
+}
@@ -0,0 +1,602 @@
+// Static analysis of YAML level files for the validate-level command.
+// Levels with random obstacles are generated with several fixed seeds so that a layout
+// which can wall off the start, an item or a door is reported reliably.
+
+use super::evaluator::TaskEvaluator;
+use super::{DoorConfig, LevelSpec, YamlLevelConfig};
+use crate::item::load_item_file;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+
+// Seeds used to sample random obstacle layouts (the first one is the game's own seed)
+const VALIDATION_SEEDS: [u64; 8] = [0xC0FFEE, 0xDEADBEEF, 0x7E57, 1, 2, 3, 42, 1337];
+
+// Values `difficulty:` may take, easiest first
+pub const DIFFICULTIES: [&str; 3] = ["beginner", "intermediate", "advanced"];
+
+// Largest grid side a level may have; bigger grids don't fit on screen and make the layout checks crawl
+pub const MAX_GRID_SIDE: usize = 64;
+
+/// Load a level file or pack and report every problem found; an empty list means the level is valid
+pub fn validate_level_file(path: &Path) -> Vec<String> {
+    if path.extension().is_some_and(|ext| ext == super::pack::PACK_EXTENSION) {
+        return validate_level_pack(path);
+    }
+
+    let format = super::LevelFormat::from_path(path).unwrap_or(super::LevelFormat::Yaml);
+    let config = match YamlLevelConfig::from_file(path) {
+        Ok(config) => config,
+        Err(e) => return vec![format!("Failed to parse {}: {}", format.name(), e)],
+    };
+
+    let level_dir = path.parent().unwrap_or(Path::new("."));
+    validate_level_config(&config, level_dir)
+}
+
+/// Run every check against an already parsed level
+pub fn validate_level_config(config: &YamlLevelConfig, level_dir: &Path) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let Some((width, height)) = parse_grid_size(&config.grid_size) else {
+        problems.push(format!("Grid size '{}' must be in format 'WxH' with non-zero sizes (e.g. '16x10')", config.grid_size));
+        return problems;
+    };
+    if width > MAX_GRID_SIDE || height > MAX_GRID_SIDE {
+        problems.push(format!("Grid size {}x{} is larger than the {}x{} limit", width, height, MAX_GRID_SIDE, MAX_GRID_SIDE));
+        return problems;
+    }
+
+    let buildable = check_bounds(config, width, height, &mut problems);
+    check_files(config, level_dir, &mut problems);
+    check_completion(config, &mut problems);
+    check_allowed_functions(config, &mut problems);
+    check_metadata(config, &mut problems);
+    super::sanitize::check_text(config, &mut problems);
+
+    let cells = (width * height) as u32;
+    let walls = config.walls.as_ref().map_or(0, |w| w.len() as u32);
+    if config.obstacles.unwrap_or(0) + walls >= cells {
+        problems.push(format!("{} obstacles and walls don't fit on a {}x{} grid", config.obstacles.unwrap_or(0) + walls, width, height));
+        // Generating this level would loop forever looking for a free cell
+        return problems;
+    }
+
+    // Layout checks only make sense once everything is inside the grid and the level can be built
+    if !buildable {
+        return problems;
+    }
+
+    let mut reported = HashSet::new();
+    for seed in VALIDATION_SEEDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        match config.to_level_spec(&mut rng) {
+            Ok(spec) => {
+                for problem in check_layout(&spec) {
+                    // Same problem on several seeds is only worth one line
+                    if reported.insert(problem.clone()) {
+                        problems.push(format!("{} (obstacle seed {:#x})", problem, seed));
+                    }
+                }
+            }
+            Err(e) => {
+                problems.push(format!("Failed to build level: {}", e));
+                break;
+            }
+        }
+    }
+
+    problems
+}
+
+/// Unpack into a scratch directory so the level is checked against the files the pack carries
+fn validate_level_pack(path: &Path) -> Vec<String> {
+    let scratch = std::env::temp_dir().join(format!("rlgpack-validate-{}", std::process::id()));
+    let problems = match super::pack::extract_level_pack(path, &scratch) {
+        Ok(pack) => validate_level_config(&pack.config, &pack.dir),
+        Err(e) => vec![e],
+    };
+    let _ = std::fs::remove_dir_all(&scratch);
+    problems
+}
+
+fn parse_grid_size(grid_size: &str) -> Option<(usize, usize)> {
+    let (w, h) = grid_size.trim().split_once('x')?;
+    let width: usize = w.trim().parse().ok()?;
+    let height: usize = h.trim().parse().ok()?;
+    if width == 0 || height == 0 {
+        return None;
+    }
+    Some((width, height))
+}
+
+/// Returns false when something is off the grid or has an unknown direction or movement pattern,
+/// which means the level can't be built
+fn check_bounds(config: &YamlLevelConfig, width: usize, height: usize, problems: &mut Vec<String>) -> bool {
+    let outside = |(x, y): (u32, u32)| x as usize >= width || y as usize >= height;
+    let grid = format!("{}x{} grid", width, height);
+    let mut buildable = true;
+
+    if let Some(start) = config.start_position
+        && outside(start)
+    {
+        problems.push(format!("Start position {:?} is outside the {}", start, grid));
+        buildable = false;
+    }
+
+    for wall in config.walls.iter().flatten() {
+        if outside(*wall) {
+            problems.push(format!("Wall at {:?} is outside the {}", wall, grid));
+            buildable = false;
+        }
+    }
+
+    for door in config.doors.iter().flatten() {
+        if outside(door.position()) {
+            problems.push(format!("Door at {:?} is outside the {}", door.position(), grid));
+            buildable = false;
+        }
+        if let DoorConfig::Locked { pos, color } = door {
+            let has_key = config.keys.iter().flatten().any(|key| key.color == *color)
+                || config.items.iter().flatten().any(|item| item.name == color.key_item());
+            if !has_key {
+                problems.push(format!("Locked {} door at {:?} has no {} key in the level", color.name(), pos, color.name()));
+            }
+        }
+    }
+
+    for key in config.keys.iter().flatten() {
+        if outside(key.pos) {
+            problems.push(format!("{} key at {:?} is outside the {}", key.color.name(), key.pos, grid));
+            buildable = false;
+        }
+    }
+
+    for crate_pos in config.crates.iter().flatten() {
+        if outside(*crate_pos) {
+            problems.push(format!("Crate at {:?} is outside the {}", crate_pos, grid));
+            buildable = false;
+        }
+    }
+
+    for robot in config.robots.iter().flatten() {
+        if outside(*robot) {
+            problems.push(format!("Robot at {:?} is outside the {}", robot, grid));
+            buildable = false;
+        }
+    }
+
+    for plate in config.plates.iter().flatten() {
+        if outside(plate.position) {
+            problems.push(format!("Pressure plate at {:?} is outside the {}", plate.position, grid));
+            buildable = false;
+        }
+        if plate.doors.is_empty() {
+            problems.push(format!("Pressure plate at {:?} isn't linked to any door", plate.position));
+        }
+        for door in &plate.doors {
+            if outside(*door) {
+                problems.push(format!("Door {:?} linked to the plate at {:?} is outside the {}", door, plate.position, grid));
+                buildable = false;
+            }
+        }
+    }
+
+    let doors: Vec<(u32, u32)> = config.doors.iter().flatten().map(DoorConfig::position).collect();
+    let emitters: Vec<(u32, u32)> = config.laser_emitters.iter().flatten().map(|emitter| emitter.position).collect();
+    let mut switch_ids = HashSet::new();
+    for switch in config.switches.iter().flatten() {
+        if outside(switch.position) {
+            problems.push(format!("Switch '{}' at {:?} is outside the {}", switch.id, switch.position, grid));
+            buildable = false;
+        }
+        if !switch_ids.insert(switch.id.as_str()) {
+            problems.push(format!("Switch id '{}' is used more than once", switch.id));
+        }
+        if switch.targets.is_empty() {
+            problems.push(format!("Switch '{}' isn't wired to anything", switch.id));
+        }
+        for target in &switch.targets {
+            if !doors.contains(target) && !emitters.contains(target) {
+                problems.push(format!("Switch '{}' is wired to {:?}, which isn't a door or laser emitter", switch.id, target));
+            }
+            if config.plates.iter().flatten().any(|plate| plate.doors.contains(target)) {
+                problems.push(format!("Door {:?} is wired to both switch '{}' and a pressure plate", target, switch.id));
+            }
+        }
+    }
+    for gate in config.gates.iter().flatten() {
+        if !config.switches.iter().flatten().any(|switch| switch.targets.contains(&gate.target)) {
+            problems.push(format!("Gate at {:?} has no switch wired to it", gate.target));
+        }
+    }
+
+    for conveyor in config.conveyors.iter().flatten() {
+        if outside(conveyor.position) {
+            problems.push(format!("Conveyor at {:?} is outside the {}", conveyor.position, grid));
+            buildable = false;
+        }
+        if super::parse_direction(&conveyor.direction).is_none() {
+            problems.push(format!("Conveyor at {:?} has unknown direction '{}' (use up, down, left or right)", conveyor.position, conveyor.direction));
+            buildable = false;
+        }
+    }
+
+    for emitter in config.laser_emitters.iter().flatten() {
+        if outside(emitter.position) {
+            problems.push(format!("Laser emitter at {:?} is outside the {}", emitter.position, grid));
+            buildable = false;
+        }
+        if super::parse_direction(&emitter.direction).is_none() {
+            problems.push(format!("Laser emitter at {:?} has unknown direction '{}' (use up, down, left or right)", emitter.position, emitter.direction));
+            buildable = false;
+        }
+        if emitter.on_turns == 0 {
+            problems.push(format!("Laser emitter at {:?} has on_turns: 0, so it never fires", emitter.position));
+        }
+    }
+
+    for tile in config.terrain.iter().flatten() {
+        if outside(tile.position) {
+            problems.push(format!("{} at {:?} is outside the {}", tile.kind.name(), tile.position, grid));
+            buildable = false;
+        }
+    }
+
+    for mirror in config.mirrors.iter().flatten() {
+        if outside(mirror.position) {
+            problems.push(format!("Mirror at {:?} is outside the {}", mirror.position, grid));
+            buildable = false;
+        }
+    }
+
+    for spike in config.spikes.iter().flatten() {
+        if outside(spike.position) {
+            problems.push(format!("Spike trap at {:?} is outside the {}", spike.position, grid));
+            buildable = false;
+        }
+    }
+
+    for enemy in config.enemies.iter().flatten() {
+        if outside(enemy.start_location) {
+            problems.push(format!("Enemy at {:?} is outside the {}", enemy.start_location, grid));
+            buildable = false;
+        }
+        if enemy.hp == Some(0) {
+            problems.push(format!("Enemy at {:?} has 0 hp", enemy.start_location));
+        }
+        if let Some(radius) = enemy.aggro_radius {
+            let chases = super::CHASE_PATTERNS.contains(&enemy.movement_pattern.as_str())
+                || enemy.movement_pattern.starts_with("file:")
+                || enemy.boss.iter().flat_map(|boss| &boss.phases).any(|phase| super::CHASE_PATTERNS.contains(&phase.movement_pattern.as_str()));
+            if !chases {
+                problems.push(format!("Enemy at {:?} has an aggro_radius but never chases (use {})", enemy.start_location, super::CHASE_PATTERNS.join(" or ")));
+            } else if radius == 0 {
+                problems.push(format!("Enemy at {:?} has aggro_radius 0, so it never notices the robot", enemy.start_location));
+            }
+        }
+        if let Some(boss) = &enemy.boss {
+            let (x, y) = enemy.start_location;
+            let far_corner = (x + boss.size.max(1) - 1, y + boss.size.max(1) - 1);
+            if !outside(enemy.start_location) && outside(far_corner) {
+                problems.push(format!("Boss at {:?} (size {}) extends outside the {}", enemy.start_location, boss.size, grid));
+                buildable = false;
+            }
+            if enemy.hp.is_none() && !boss.phases.is_empty() {
+                problems.push(format!("Boss at {:?} has phases but no hp, so it can never change phase", enemy.start_location));
+            }
+            for phase in &boss.phases {
+                if !super::BOSS_PHASE_PATTERNS.contains(&phase.movement_pattern.as_str()) {
+                    problems.push(format!("Boss at {:?} has unknown movement pattern '{}' (use {})", enemy.start_location, phase.movement_pattern, super::BOSS_PHASE_PATTERNS.join(", ")));
+                    buildable = false;
+                }
+            }
+        }
+    }
+
+    for spawner in config.spawners.iter().flatten() {
+        if outside(spawner.position) {
+            problems.push(format!("Spawner at {:?} is outside the {}", spawner.position, grid));
+            buildable = false;
+        }
+        if spawner.max == 0 {
+            problems.push(format!("Spawner at {:?} has max 0, so it never spawns anything", spawner.position));
+        }
+        let pattern = spawner.movement_pattern.as_str();
+        if !super::BOSS_PHASE_PATTERNS.contains(&pattern) && !pattern.starts_with("file:") {
+            problems.push(format!("Spawner at {:?} has unknown movement pattern '{}' (use {} or file:...)", spawner.position, pattern, super::BOSS_PHASE_PATTERNS.join(", ")));
+            buildable = false;
+        }
+        if spawner.hp == Some(0) {
+            problems.push(format!("Spawner at {:?} spawns enemies with 0 hp", spawner.position));
+        }
+    }
+    if config.flanking == Some(true) {
+        let chases = |pattern: &String| super::CHASE_PATTERNS.contains(&pattern.as_str());
+        let chasers = config.enemies.iter().flatten().filter(|enemy| chases(&enemy.movement_pattern)).count() as u32
+            + config.spawners.iter().flatten().filter(|spawner| chases(&spawner.movement_pattern)).map(|spawner| spawner.max).sum::<u32>();
+        if chasers < 2 {
+            problems.push(format!("flanking is on but the level has {} chase enemies; it takes at least 2", chasers));
+        }
+    }
+
+    for item in config.items.iter().flatten() {
+        if item.spawn_randomly.unwrap_or(false) {
+            continue;
+        }
+        match item.location {
+            Some(location) if outside(location) => {
+                problems.push(format!("Item '{}' at {:?} is outside the {}", item.name, location, grid));
+                buildable = false;
+            }
+            Some(_) => {}
+            None => problems.push(format!("Item '{}' has no location and spawn_randomly is not set, so it is never placed", item.name)),
+        }
+    }
+
+    buildable
+}
+
+fn file_exists(file: &str, level_dir: &Path) -> bool {
+    Path::new(file).exists() || level_dir.join(file).exists()
+}
+
+fn check_files(config: &YamlLevelConfig, level_dir: &Path, problems: &mut Vec<String>) {
+    for item in config.items.iter().flatten() {
+        if !file_exists(&item.item_file, level_dir) {
+            problems.push(format!("Item '{}' references missing file '{}'", item.name, item.item_file));
+            continue;
+        }
+
+        let path = if Path::new(&item.item_file).exists() {
+            Path::new(&item.item_file).to_path_buf()
+        } else {
+            level_dir.join(&item.item_file)
+        };
+        if let Err(e) = load_item_file(&path.to_string_lossy()) {
+            problems.push(format!("Item '{}': {}", item.name, e));
+        }
+    }
+
+    for enemy in config.enemies.iter().flatten().cloned().chain(config.spawners.iter().flatten().map(|spawner| spawner.enemy_config())) {
+        if let Some(pattern_file) = enemy.movement_pattern.strip_prefix("file:")
+            && !file_exists(pattern_file, level_dir)
+        {
+            problems.push(format!("Enemy at {:?} references missing movement pattern '{}'", enemy.start_location, pattern_file));
+        }
+    }
+
+    for task in config.tasks.iter().flatten() {
+        let Some(task_file) = &task.task_file else { continue };
+
+        // Built-in tests are referenced by module path, e.g. learning_tests::level1_task1_hello::tests
+        let exists = if let Some(module_path) = task_file.strip_prefix("learning_tests::") {
+            let module = module_path.split("::").next().unwrap_or("");
+            Path::new("src/learning_tests").join(format!("{}.rs", module)).exists()
+        } else if let Some(evaluator) = TaskEvaluator::parse(task_file) {
+            match evaluator {
+                Ok(evaluator) => evaluator.path().exists(),
+                Err(problem) => {
+                    problems.push(format!("Task '{}' evaluator {}", task.name, problem));
+                    continue;
+                }
+            }
+        } else if task_file.ends_with(".rs") {
+            file_exists(task_file, level_dir)
+        } else {
+            problems.push(format!(
+                "Task '{}' task_file '{}' is neither a learning test, a .rs test nor a script: or plugin: evaluator",
+                task.name, task_file
+            ));
+            continue;
+        };
+
+        if !exists {
+            problems.push(format!("Task '{}' references missing test '{}'", task.name, task_file));
+        }
+    }
+}
+
+fn check_completion(config: &YamlLevelConfig, problems: &mut Vec<String>) {
+    let has_tasks = config.tasks.as_ref().is_some_and(|t| !t.is_empty());
+    let has_items = config.items.as_ref().is_some_and(|i| !i.is_empty());
+    let has_condition = config.completion_condition.as_ref().is_some_and(|c| !c.trim().is_empty())
+        || config.completion_flag.as_ref().is_some_and(|f| !f.trim().is_empty());
+
+    if !has_tasks && !has_items && !has_condition {
+        problems.push("Level has no completion condition: add tasks, items to collect, completion_condition or completion_flag".to_string());
+    }
+}
+
+/// The author, version, difficulty, tags and requires a level describes itself with. Whether the
+/// levels it requires exist is up to whoever loads the whole level list.
+pub fn check_metadata(config: &YamlLevelConfig, problems: &mut Vec<String>) {
+    if config.author.as_ref().is_some_and(|author| author.trim().is_empty()) {
+        problems.push("author is empty".to_string());
+    }
+    if let Some(version) = &config.version
+        && !version.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+    {
+        problems.push(format!("version '{}' must be numbers separated by dots, e.g. '1.2'", version));
+    }
+    if let Some(difficulty) = &config.difficulty
+        && !DIFFICULTIES.contains(&difficulty.as_str())
+    {
+        problems.push(format!("difficulty '{}' must be one of {}", difficulty, DIFFICULTIES.join(", ")));
+    }
+
+    let mut seen = HashSet::new();
+    for tag in &config.tags {
+        if tag.trim().is_empty() {
+            problems.push("tags has an empty tag".to_string());
+        } else if !seen.insert(tag) {
+            problems.push(format!("tags lists '{}' twice", tag));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for required in &config.requires {
+        if *required == config.name {
+            problems.push("requires lists the level itself".to_string());
+        } else if !seen.insert(required) {
+            problems.push(format!("requires lists '{}' twice", required));
+        }
+    }
+}
+
+fn check_allowed_functions(config: &YamlLevelConfig, problems: &mut Vec<String>) {
+    let Some(allowed) = &config.allowed_functions else { return };
+    for name in crate::capabilities::unknown_function_names(allowed) {
+        problems.push(format!("allowed_functions lists '{}', which isn't a robot function", name));
+    }
+
+    // Starting code that the level itself would refuse to run
+    if let Some(code) = &config.starting_code {
+        let upgrades = HashSet::new();
+        let context = crate::capabilities::CapabilityContext { allowed_functions: Some(allowed), upgrades: &upgrades, multi_robot: false, unlock_all: false };
+        for name in context.forbidden_calls(&crate::interpreter::parse_rust_code_from_main(code, 0)) {
+            problems.push(format!("starting_code calls {}(), which allowed_functions leaves out", name));
+        }
+    }
+}
+
+/// Tiles reachable from the start; doors count as passable since they can be opened
+fn reachable_tiles(spec: &LevelSpec) -> HashSet<(usize, usize)> {
+    let blockers: HashSet<(usize, usize)> = spec.blockers.iter().copied()
+        .chain(spec.mirrors.iter().map(|mirror| mirror.pos))
+        .chain(spec.emitters.iter().map(|emitter| emitter.pos))
+        .collect();
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+
+    if blockers.contains(&spec.start) {
+        return seen;
+    }
+    seen.insert(spec.start);
+    queue.push_back(spec.start);
+
+    while let Some((x, y)) = queue.pop_front() {
+        let neighbors = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+        for next in neighbors {
+            if next.0 < spec.width && next.1 < spec.height && !blockers.contains(&next) && seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    seen
+}
+
+fn check_layout(spec: &LevelSpec) -> Vec<String> {
+    let mut problems = Vec::new();
+    let blockers: HashSet<(usize, usize)> = spec.blockers.iter().copied().collect();
+
+    if blockers.contains(&spec.start) {
+        problems.push(format!("Start position {:?} is on a blocker", spec.start));
+        return problems;
+    }
+
+    let reachable = reachable_tiles(spec);
+
+    for item in &spec.items {
+        let Some((x, y)) = item.pos else { continue };
+        let pos = (x as usize, y as usize);
+        if blockers.contains(&pos) {
+            problems.push(format!("Item '{}' at {:?} is on an obstacle", item.name, pos));
+        } else if !reachable.contains(&pos) {
+            problems.push(format!("Item '{}' at {:?} is unreachable from the start", item.name, pos));
+        }
+    }
+
+    for door in &spec.doors {
+        if blockers.contains(door) {
+            problems.push(format!("Door at {:?} is on an obstacle", door));
+        } else if !reachable.contains(door) {
+            problems.push(format!("Door at {:?} is unreachable from the start", door));
+        }
+    }
+
+    for switch in &spec.switches {
+        if blockers.contains(&switch.pos) {
+            problems.push(format!("Switch '{}' at {:?} is on an obstacle", switch.id, switch.pos));
+        } else if !reachable.contains(&switch.pos) {
+            problems.push(format!("Switch '{}' at {:?} is unreachable from the start", switch.id, switch.pos));
+        }
+    }
+
+    if let Some(condition) = &spec.win_condition {
+        for tile in crate::win_conditions::WinConditionEvaluator::new(condition).objective_tiles() {
+            let pos = (tile.x as usize, tile.y as usize);
+            if pos.0 >= spec.width || pos.1 >= spec.height {
+                problems.push(format!("Win condition position {:?} is outside the {}x{} grid", pos, spec.width, spec.height));
+            } else if blockers.contains(&pos) {
+                problems.push(format!("Win condition position {:?} is on an obstacle", pos));
+            } else if !reachable.contains(&pos) {
+                problems.push(format!("Win condition position {:?} is unreachable from the start", pos));
+            }
+        }
+    }
+
+    for crate_pos in &spec.crates {
+        if blockers.contains(crate_pos) {
+            problems.push(format!("Crate at {:?} is on an obstacle", crate_pos));
+        } else if *crate_pos == spec.start {
+            problems.push(format!("Crate at {:?} is on the start position", crate_pos));
+        }
+    }
+
+    for (i, robot) in spec.robots.iter().enumerate() {
+        if blockers.contains(robot) {
+            problems.push(format!("Robot {} at {:?} is on an obstacle", i + 1, robot));
+        } else if *robot == spec.start || spec.robots[..i].contains(robot) {
+            problems.push(format!("Robot {} at {:?} shares a tile with another robot", i + 1, robot));
+        }
+    }
+
+    for mirror in &spec.mirrors {
+        if mirror.pos == spec.start {
+            problems.push(format!("Mirror at {:?} is on the start position", mirror.pos));
+        }
+    }
+
+    for plate in &spec.plates {
+        if blockers.contains(&plate.pos) {
+            problems.push(format!("Pressure plate at {:?} is on an obstacle", plate.pos));
+        }
+    }
+
+    for enemy in &spec.enemies {
+        let pos = (enemy.pos.0 as usize, enemy.pos.1 as usize);
+        if blockers.contains(&pos) {
+            problems.push(format!("Enemy spawns on an obstacle at {:?}", pos));
+        } else if pos == spec.start {
+            problems.push(format!("Enemy spawns on the start position {:?}", pos));
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_problems_are_reported() {
+        let config: YamlLevelConfig = serde_yaml::from_str(
+            "name: Doors\ngrid_size: 4x4\nauthor: Ann\nversion: '1.x'\ndifficulty: hard\ntags: [loops, loops]\nrequires: [Doors, Keys]\n",
+        ).unwrap();
+        let mut problems = Vec::new();
+        check_metadata(&config, &mut problems);
+        assert_eq!(problems, [
+            "version '1.x' must be numbers separated by dots, e.g. '1.2'",
+            "difficulty 'hard' must be one of beginner, intermediate, advanced",
+            "tags lists 'loops' twice",
+            "requires lists the level itself",
+        ]);
+    }
+}
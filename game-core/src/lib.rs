@@ -0,0 +1,21 @@
+//! Game rules shared by the desktop game, the WASM frontend and `rust-game-test-runner`.
+//!
+//! Nothing in here draws or reads input, so every frontend plays out a robot program
+//! with exactly the same grid, robot, level and parsing logic.
+
+pub mod analysis;
+pub mod ast;
+pub mod budget;
+pub mod capabilities;
+pub mod combat_log;
+pub mod lint;
+pub mod ownership;
+pub mod grid;
+pub mod interpreter;
+pub mod item;
+pub mod level;
+pub mod movement_patterns;
+pub mod random;
+pub mod robot;
+pub mod scheduler;
+pub mod win_conditions;
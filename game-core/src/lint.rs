@@ -0,0 +1,286 @@
+// Game-specific lint pass over robot programs, run as the player types. It flags code that
+// compiles fine but almost certainly doesn't do what was meant: a move straight into a wall the
+// player has already seen, a scan repeated without the robot moving in between, statements after
+// a panic!, and let bindings that are never used.
+//
+// Moves are followed from the robot's current tile along the calls parse_program works out, and
+// only as long as the robot's position is certain: a laser, teleport, conveyor or other robot ends
+// the wall check.
+
+use crate::ast::{parse_program, Diagnostic};
+use crate::interpreter::{FunctionCall, RustFunction};
+use proc_macro2::{TokenStream, TokenTree};
+use std::collections::HashSet;
+use syn::spanned::Spanned;
+use syn::{Block, Expr, Pat, Stmt};
+
+/// What the player knows about the level, for the wall check
+pub struct LintWorld {
+    pub width: i32,
+    pub height: i32,
+    pub start: (i32, i32),
+    pub facing: (i32, i32),
+    pub walls: HashSet<(i32, i32)>, // Known tiles a move can't enter
+    pub moving_tiles: HashSet<(i32, i32)>, // Conveyors and ice: the robot's position isn't certain after them
+}
+
+/// Every lint finding in `code`, in line order. Code that doesn't parse has none; the syntax check
+/// reports it.
+pub fn lint_program(code: &str, world: Option<&LintWorld>) -> Vec<Diagnostic> {
+    let Ok(file) = syn::parse_file(code) else { return Vec::new() };
+    let mut diagnostics = Vec::new();
+
+    let functions: Vec<&syn::ItemFn> = file.items.iter()
+        .filter_map(|item| match item {
+            syn::Item::Fn(function) => Some(function),
+            _ => None,
+        })
+        .collect();
+    let tokens = code.parse::<TokenStream>().map(flatten).unwrap_or_default();
+    for function in &functions {
+        unreachable_after_panic(&function.block, &mut diagnostics);
+        unused_bindings(function, &tokens, &mut diagnostics);
+    }
+
+    if let Ok(program) = parse_program(code) {
+        let calls: Vec<_> = program.calls.iter().zip(&program.call_positions).collect();
+        repeated_scans(&calls, world.map_or((0, -1), |world| world.facing), &mut diagnostics);
+        if let Some(world) = world {
+            moves_into_walls(&calls, world, &mut diagnostics);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    diagnostics.retain(|diagnostic| seen.insert((diagnostic.line, diagnostic.column, diagnostic.message.clone())));
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    diagnostics
+}
+
+fn diagnostic((line, column): (usize, usize), message: String) -> Diagnostic {
+    Diagnostic { line, column, message }
+}
+
+fn moves_into_walls(calls: &[(&FunctionCall, &(usize, usize))], world: &LintWorld, diagnostics: &mut Vec<Diagnostic>) {
+    let (mut x, mut y) = world.start;
+    let mut facing = world.facing;
+    for &(call, &position) in calls {
+        if call.robot.is_some() {
+            return;
+        }
+        match call.function {
+            RustFunction::Move | RustFunction::MoveForward => {
+                let (dx, dy) = if call.function == RustFunction::MoveForward { facing } else { call.direction.unwrap_or(facing) };
+                facing = (dx, dy);
+                let target = (x + dx, y + dy);
+                let label = crate::capabilities::call_label(call);
+                if target.0 < 0 || target.1 < 0 || target.0 >= world.width || target.1 >= world.height {
+                    diagnostics.push(diagnostic(position, format!("{} walks off the edge of the grid; the program stops here", label)));
+                    return;
+                }
+                if world.walls.contains(&target) {
+                    diagnostics.push(diagnostic(position, format!("{} walks into the wall at ({}, {}); the program stops here", label, target.0, target.1)));
+                    return;
+                }
+                (x, y) = target;
+                if world.moving_tiles.contains(&target) {
+                    return;
+                }
+            }
+            RustFunction::RotateLeft => facing = (facing.1, -facing.0),
+            RustFunction::RotateRight => facing = (-facing.1, facing.0),
+            RustFunction::Scan | RustFunction::Grab | RustFunction::PrintMap | RustFunction::OpenDoor | RustFunction::SetAutoGrab | RustFunction::Say => {}
+            RustFunction::Println | RustFunction::Eprintln | RustFunction::Panic => {}
+            _ => return,
+        }
+    }
+}
+
+fn repeated_scans(calls: &[(&FunctionCall, &(usize, usize))], start_facing: (i32, i32), diagnostics: &mut Vec<Diagnostic>) {
+    let mut facing = start_facing;
+    let mut last_scan = None;
+    for &(call, &position) in calls {
+        match call.function {
+            RustFunction::Scan => {
+                let direction = call.direction.unwrap_or(facing);
+                if last_scan == Some((call.robot, direction)) {
+                    let label = crate::capabilities::call_label(call);
+                    diagnostics.push(diagnostic(position, format!("{} repeats the last scan; the robot hasn't moved since", label)));
+                }
+                last_scan = Some((call.robot, direction));
+            }
+            RustFunction::RotateLeft => facing = (facing.1, -facing.0),
+            RustFunction::RotateRight => facing = (-facing.1, facing.0),
+            RustFunction::Grab | RustFunction::PrintMap | RustFunction::SetAutoGrab | RustFunction::Say | RustFunction::Println | RustFunction::Eprintln => {}
+            // Moving, or anything else that changes the world, makes the next scan worth it
+            _ => last_scan = None,
+        }
+    }
+}
+
+fn is_panic(mac: &syn::Macro) -> bool {
+    mac.path.segments.last().is_some_and(|segment| matches!(segment.ident.to_string().as_str(), "panic" | "unreachable" | "todo" | "unimplemented"))
+}
+
+/// The first statement after a panic! in `block` and every block inside it
+fn unreachable_after_panic(block: &Block, diagnostics: &mut Vec<Diagnostic>) {
+    let mut reported = false;
+    for (i, stmt) in block.stmts.iter().enumerate() {
+        let mut inner = Vec::new();
+        match stmt {
+            Stmt::Local(local) => {
+                if let Some(init) = &local.init {
+                    nested_blocks(&init.expr, &mut inner);
+                }
+            }
+            Stmt::Expr(expr, _) => nested_blocks(expr, &mut inner),
+            _ => {}
+        }
+        for inner in inner {
+            unreachable_after_panic(inner, diagnostics);
+        }
+
+        let panics = match stmt {
+            Stmt::Macro(stmt) => is_panic(&stmt.mac),
+            Stmt::Expr(Expr::Macro(expr), _) => is_panic(&expr.mac),
+            _ => false,
+        };
+        if panics && !reported && let Some(next) = block.stmts.get(i + 1) {
+            diagnostics.push(Diagnostic::at(next.span(), "Unreachable: the panic! above stops the program before this runs"));
+            reported = true;
+        }
+    }
+}
+
+/// Blocks directly inside `expr`: branches, loop bodies, match arms and closures
+pub(crate) fn nested_blocks<'a>(expr: &'a Expr, blocks: &mut Vec<&'a Block>) {
+    match expr {
+        Expr::Block(expr) => blocks.push(&expr.block),
+        Expr::If(expr) => {
+            blocks.push(&expr.then_branch);
+            if let Some((_, otherwise)) = &expr.else_branch {
+                nested_blocks(otherwise, blocks);
+            }
+        }
+        Expr::Loop(expr) => blocks.push(&expr.body),
+        Expr::While(expr) => blocks.push(&expr.body),
+        Expr::ForLoop(expr) => blocks.push(&expr.body),
+        Expr::Unsafe(expr) => blocks.push(&expr.block),
+        Expr::Async(expr) => blocks.push(&expr.block),
+        Expr::Match(expr) => {
+            for arm in &expr.arms {
+                nested_blocks(&arm.body, blocks);
+            }
+        }
+        Expr::Closure(expr) => nested_blocks(&expr.body, blocks),
+        _ => {}
+    }
+}
+
+/// An identifier or literal of the program, with where it starts
+enum Token {
+    Ident(String, (usize, usize)),
+    Literal(String, (usize, usize)),
+}
+
+fn flatten(stream: TokenStream) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    for tree in stream {
+        let start = tree.span().start();
+        let at = (start.line, start.column);
+        match tree {
+            TokenTree::Group(group) => tokens.extend(flatten(group.stream())),
+            TokenTree::Ident(ident) => tokens.push(Token::Ident(ident.to_string(), at)),
+            TokenTree::Literal(literal) => tokens.push(Token::Literal(literal.to_string(), at)),
+            TokenTree::Punct(_) => {}
+        }
+    }
+    tokens
+}
+
+/// `let` bindings of `function` that nothing else in it mentions, format strings included
+fn unused_bindings(function: &syn::ItemFn, tokens: &[Token], diagnostics: &mut Vec<Diagnostic>) {
+    let mut bindings = Vec::new();
+    collect_bindings(&function.block, &mut bindings);
+
+    let (start, end) = (function.span().start(), function.span().end());
+    let inside = |at: &(usize, usize)| *at >= (start.line, start.column) && *at <= (end.line, end.column);
+    for binding in bindings {
+        let name = binding.to_string();
+        if name.starts_with('_') {
+            continue;
+        }
+        let declared = (binding.span().start().line, binding.span().start().column);
+        let used = tokens.iter().any(|token| match token {
+            Token::Ident(ident, at) => *ident == name && *at != declared && inside(at),
+            Token::Literal(literal, at) => inside(at) && (literal.contains(&format!("{{{}}}", name)) || literal.contains(&format!("{{{}:", name))),
+        });
+        if !used {
+            diagnostics.push(Diagnostic::at(binding.span(), format!("`{}` is never used; use it or rename it to `_{}`", name, name)));
+        }
+    }
+}
+
+fn collect_bindings<'a>(block: &'a Block, bindings: &mut Vec<&'a syn::Ident>) {
+    for stmt in &block.stmts {
+        let mut inner = Vec::new();
+        match stmt {
+            Stmt::Local(local) => {
+                if let Some(ident) = binding_ident(&local.pat) {
+                    bindings.push(ident);
+                }
+                if let Some(init) = &local.init {
+                    nested_blocks(&init.expr, &mut inner);
+                }
+            }
+            Stmt::Expr(expr, _) => nested_blocks(expr, &mut inner),
+            _ => {}
+        }
+        for inner in inner {
+            collect_bindings(inner, bindings);
+        }
+    }
+}
+
+fn binding_ident(pat: &Pat) -> Option<&syn::Ident> {
+    match pat {
+        Pat::Ident(binding) if binding.subpat.is_none() => Some(&binding.ident),
+        Pat::Type(typed) => binding_ident(&typed.pat),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(code: &str, world: Option<&LintWorld>) -> Vec<String> {
+        lint_program(code, world).iter().map(|diagnostic| diagnostic.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_walls_repeated_scans_and_code_after_panic() {
+        let world = LintWorld {
+            width: 5,
+            height: 5,
+            start: (1, 1),
+            facing: (0, -1),
+            walls: HashSet::from([(3, 1)]),
+            moving_tiles: HashSet::new(),
+        };
+        let code = "fn main() {\n    scan(\"up\");\n    scan(\"up\");\n    move_bot(\"right\");\n    move_bot(\"right\");\n    panic!(\"done\");\n    grab();\n}";
+        assert_eq!(messages(code, Some(&world)), vec![
+            "line 3:5: scan(\"up\") repeats the last scan; the robot hasn't moved since",
+            "line 5:5: move_bot(\"right\") walks into the wall at (3, 1); the program stops here",
+            "line 7:5: Unreachable: the panic! above stops the program before this runs",
+        ]);
+
+        // Without the level only the checks that don't need it run
+        assert_eq!(messages(code, None).len(), 2);
+    }
+
+    #[test]
+    fn flags_bindings_that_are_never_used() {
+        let code = "fn main() {\n    let steps = 3;\n    let name = \"bot\";\n    let _ignored = 1;\n    let mut unused = 0;\n    for _ in 0..steps { println!(\"{name}\"); }\n}";
+        assert_eq!(messages(code, None), vec!["line 5:13: `unused` is never used; use it or rename it to `_unused`"]);
+    }
+}
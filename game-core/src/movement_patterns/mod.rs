@@ -4,6 +4,8 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+pub mod pathfinding;
+
 /// Trait for custom enemy movement patterns
 pub trait MovementPattern: Send + Sync + std::fmt::Debug {
     /// Calculate the next position for an enemy
@@ -147,6 +149,8 @@ impl MovementPatternRegistry {
             self.register(pattern_name, Box::new(CircularMovement::new()));
         } else if content.contains("// MOVEMENT_PATTERN: spiral") {
             self.register(pattern_name, Box::new(SpiralMovement));
+        } else if content.contains("// MOVEMENT_PATTERN: chase_smart") {
+            self.register(pattern_name, Box::new(SmartChaseMovement::new()));
         } else if content.contains("// MOVEMENT_PATTERN: chase") {
             self.register(pattern_name, Box::new(ChaseMovement));
         } else if content.contains("// MOVEMENT_PATTERN: guard") {
@@ -359,6 +363,12 @@ impl MovementPattern for ChaseMovement {
         } else {
             Pos { x: 1, y: 1 } // fallback
         };
+
+        if !in_aggro_range(current_pos, player_pos, enemy_data) {
+            enemy_data.insert("is_chasing".to_string(), serde_yaml::Value::Bool(false));
+            return None;
+        }
+        let player_pos = chase_target(player_pos, enemy_data);
         
         // Calculate direction to player
         let dx = if player_pos.x > current_pos.x {
@@ -425,6 +435,99 @@ impl MovementPattern for ChaseMovement {
     }
 }
 
+/// Whether the player is within the enemy's "aggro_radius" (Manhattan distance); no radius means always
+fn in_aggro_range(current_pos: Pos, player_pos: Pos, enemy_data: &HashMap<String, serde_yaml::Value>) -> bool {
+    enemy_data.get("aggro_radius")
+        .and_then(|v| v.as_u64())
+        .is_none_or(|radius| u64::from(pathfinding::manhattan(current_pos, player_pos)) <= radius)
+}
+
+/// Where a chaser heads: the tile flanking assigned it ("flank_x"/"flank_y"), otherwise the player
+fn chase_target(player_pos: Pos, enemy_data: &HashMap<String, serde_yaml::Value>) -> Pos {
+    let flank_x = enemy_data.get("flank_x").and_then(|v| v.as_i64());
+    let flank_y = enemy_data.get("flank_y").and_then(|v| v.as_i64());
+    match (flank_x, flank_y) {
+        (Some(x), Some(y)) => Pos { x: x as i32, y: y as i32 },
+        _ => player_pos,
+    }
+}
+
+/// Chase movement that follows an A* path around obstacles. The path is kept in the enemy data and
+/// only searched again when it's used up or blocked, or when its target has moved and at least
+/// "recompute_every" turns have passed since the last search, so each enemy has a budget of searches.
+#[derive(Debug)]
+pub struct SmartChaseMovement {
+    pub node_budget: usize, // Tiles one search may look at
+    pub recompute_every: u64,
+}
+
+impl SmartChaseMovement {
+    pub fn new() -> Self {
+        Self { node_budget: 400, recompute_every: 2 }
+    }
+}
+
+impl Default for SmartChaseMovement {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MovementPattern for SmartChaseMovement {
+    fn next_move(&self, current_pos: Pos, grid: &Grid, enemy_data: &mut HashMap<String, serde_yaml::Value>) -> Option<Pos> {
+        let player_x = enemy_data.get("player_x").and_then(|v| v.as_i64())?;
+        let player_y = enemy_data.get("player_y").and_then(|v| v.as_i64())?;
+        let player_pos = Pos { x: player_x as i32, y: player_y as i32 };
+
+        if !in_aggro_range(current_pos, player_pos, enemy_data) {
+            enemy_data.remove("path");
+            enemy_data.insert("is_chasing".to_string(), serde_yaml::Value::Bool(false));
+            return None;
+        }
+        let target = chase_target(player_pos, enemy_data);
+
+        let mut path: Vec<Pos> = enemy_data.get("path")
+            .and_then(|v| v.as_sequence())
+            .map(|steps| steps.chunks(2).filter_map(|xy| Some(Pos { x: xy[0].as_i64()? as i32, y: xy.get(1)?.as_i64()? as i32 })).collect())
+            .unwrap_or_default();
+        let target_x = enemy_data.get("target_x").and_then(|v| v.as_i64());
+        let target_y = enemy_data.get("target_y").and_then(|v| v.as_i64());
+        let target_moved = target_x != Some(target.x as i64) || target_y != Some(target.y as i64);
+        let turns_since_search = enemy_data.get("turns_since_search").and_then(|v| v.as_u64()).unwrap_or(u64::MAX);
+        let recompute_every = enemy_data.get("recompute_every").and_then(|v| v.as_u64()).unwrap_or(self.recompute_every);
+
+        let stale = path.is_empty()
+            || pathfinding::manhattan(current_pos, path[0]) != 1
+            || grid.is_blocked(path[0])
+            || (target_moved && turns_since_search >= recompute_every);
+        let turns_since_search = if stale {
+            let (found, reached) = pathfinding::find_path(grid, current_pos, target, self.node_budget);
+            path = found;
+            enemy_data.insert("target_x".to_string(), serde_yaml::Value::Number(target.x.into()));
+            enemy_data.insert("target_y".to_string(), serde_yaml::Value::Number(target.y.into()));
+            // Chasing while the path reaches the player, searching while it only gets closer
+            enemy_data.insert("is_chasing".to_string(), serde_yaml::Value::Bool(reached));
+            0
+        } else {
+            turns_since_search.saturating_add(1)
+        };
+        enemy_data.insert("turns_since_search".to_string(), serde_yaml::Value::Number(turns_since_search.into()));
+
+        // A blocked first step stays in the path, which makes it stale next turn
+        let next = path.first().copied().filter(|next| !grid.is_blocked(*next));
+        if next.is_some() {
+            path.remove(0);
+        }
+        let steps = path.iter().flat_map(|pos| [pos.x, pos.y]).map(|n| serde_yaml::Value::Number(n.into())).collect();
+        enemy_data.insert("path".to_string(), serde_yaml::Value::Sequence(steps));
+        next
+    }
+
+    fn description(&self) -> &'static str {
+        "Chases the player along the shortest path around obstacles"
+    }
+}
+
 /// Guard movement pattern
 #[derive(Debug)]
 pub struct GuardMovement;
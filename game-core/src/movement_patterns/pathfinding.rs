@@ -0,0 +1,99 @@
+// A* pathfinding for enemies that chase the robot around obstacles. Enemies step in the four grid
+// directions, so the Manhattan distance is the heuristic. The search looks at no more than `budget`
+// tiles; when it runs out first, the path leads to the tile it found closest to the target (other
+// than the start), so a big maze costs a bounded amount of work per search and the enemy still moves.
+
+use crate::grid::Grid;
+use crate::item::Pos;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+const STEPS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const VERTICAL_FIRST: [(i32, i32); 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+
+pub fn manhattan(a: Pos, b: Pos) -> u32 {
+    a.x.abs_diff(b.x) + a.y.abs_diff(b.y)
+}
+
+/// The tiles from `from` (not included) towards `to`, and whether they reach it
+pub fn find_path(grid: &Grid, from: Pos, to: Pos, budget: usize) -> (Vec<Pos>, bool) {
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<Pos, Pos> = HashMap::new();
+    let mut cost: HashMap<Pos, u32> = HashMap::from([(from, 0)]);
+    let mut closest: Option<(u32, Pos)> = None;
+    let mut expanded = 0;
+    // Ties on f go to the tile nearer the target, and then to the order of the steps via the counter.
+    // Trying the axis with further to go first keeps a chaser from zigzagging after a moving target.
+    let steps = if from.x.abs_diff(to.x) >= from.y.abs_diff(to.y) { STEPS } else { VERTICAL_FIRST };
+    let mut counter = 0u32;
+    open.push(Reverse((manhattan(from, to), manhattan(from, to), counter, from.x, from.y)));
+
+    while let Some(Reverse((_, h, _, x, y))) = open.pop() {
+        let pos = Pos { x, y };
+        if pos == to {
+            return (walk_back(&came_from, from, to), true);
+        }
+        if pos != from && closest.is_none_or(|(best, _)| h < best) {
+            closest = Some((h, pos));
+        }
+        expanded += 1;
+        if expanded >= budget {
+            break;
+        }
+
+        let g = cost[&pos];
+        for (dx, dy) in steps {
+            let next = Pos { x: pos.x + dx, y: pos.y + dy };
+            // The target is always enterable: the robot stands there, not an obstacle
+            if next != to && (!grid.in_bounds(next) || grid.is_blocked(next)) {
+                continue;
+            }
+            if cost.get(&next).is_some_and(|&known| known <= g + 1) {
+                continue;
+            }
+            cost.insert(next, g + 1);
+            came_from.insert(next, pos);
+            counter += 1;
+            let h = manhattan(next, to);
+            open.push(Reverse((g + 1 + h, h, counter, next.x, next.y)));
+        }
+    }
+    (closest.map(|(_, pos)| walk_back(&came_from, from, pos)).unwrap_or_default(), false)
+}
+
+fn walk_back(came_from: &HashMap<Pos, Pos>, from: Pos, to: Pos) -> Vec<Pos> {
+    let mut path = Vec::new();
+    let mut pos = to;
+    while pos != from {
+        path.push(pos);
+        pos = came_from[&pos];
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_lead_out_of_a_cup_that_traps_greedy_chasing() {
+        // The enemy sits in a cup opening upwards, the robot is straight below it
+        let mut grid = Grid::new(7, 7);
+        for pos in [(2, 1), (2, 2), (2, 3), (3, 3), (4, 3), (4, 2), (4, 1)] {
+            grid.blockers.insert(Pos { x: pos.0, y: pos.1 });
+        }
+        let (enemy, robot) = (Pos { x: 3, y: 2 }, Pos { x: 3, y: 5 });
+
+        let (path, reached) = find_path(&grid, enemy, robot, 400);
+        assert!(reached);
+        assert_eq!(path.len(), 11);
+        assert_eq!(path.first(), Some(&Pos { x: 3, y: 1 }));
+        assert!(path.iter().all(|pos| !grid.is_blocked(*pos)));
+
+        // Out of budget it still moves, towards the tile nearest the robot it found
+        let (partial, reached) = find_path(&grid, enemy, robot, 3);
+        assert!(!reached);
+        assert_eq!(partial, vec![Pos { x: 3, y: 1 }]);
+    }
+}
@@ -0,0 +1,532 @@
+// Ownership analysis for the borrow/ownership teaching levels. Every function in the program is
+// read top to bottom and each local variable gets a timeline: where it's declared, borrowed,
+// mutably borrowed, moved away, used and dropped at the end of its scope.
+//
+// This is a teaching view, not the borrow checker. Types are only known from literals, annotations
+// and the return types of the player's own functions; anything else is treated as an owned value
+// that moves. Loop bodies are read once, in source order.
+
+use crate::ast::Diagnostic;
+use std::collections::HashMap;
+use syn::spanned::Spanned;
+use syn::{BinOp, Block, Expr, Lit, Pat, Stmt, Type, UnOp};
+
+/// What happened to a variable at one point in the program
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EventKind {
+    Declared,
+    Used,
+    Borrowed,
+    BorrowedMut,
+    Assigned, // A new value, which also makes a moved-out variable usable again
+    Moved,
+    UsedAfterMove, // The compiler would reject this (E0382)
+    Dropped,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnershipEvent {
+    pub line: usize,
+    pub kind: EventKind,
+    pub detail: String, // "moved into `plan`", "borrowed by println!", ...
+}
+
+/// One variable binding from declaration to the end of its scope. Shadowing starts a new timeline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariableTimeline {
+    pub name: String,
+    pub function: String,
+    pub copy: bool, // Copy values are copied instead of moved
+    pub events: Vec<OwnershipEvent>, // In source order, starting with Declared
+}
+
+impl VariableTimeline {
+    pub fn first_line(&self) -> usize {
+        self.events.first().map_or(0, |event| event.line)
+    }
+
+    pub fn last_line(&self) -> usize {
+        self.events.iter().map(|event| event.line).max().unwrap_or(0)
+    }
+
+    /// Whether the variable's value has been moved away and not replaced since
+    pub fn is_moved(&self) -> bool {
+        self.events.iter().rev()
+            .find(|event| matches!(event.kind, EventKind::Moved | EventKind::Assigned | EventKind::Declared))
+            .is_some_and(|event| event.kind == EventKind::Moved)
+    }
+}
+
+/// Timelines of every local variable in `code`, in the order they're declared
+pub fn analyze_ownership(code: &str) -> Result<Vec<VariableTimeline>, Diagnostic> {
+    let file = syn::parse_file(code).map_err(|e| Diagnostic::at(e.span(), e.to_string()))?;
+    let functions: Vec<&syn::ItemFn> = file.items.iter()
+        .filter_map(|item| match item {
+            syn::Item::Fn(function) => Some(function),
+            _ => None,
+        })
+        .collect();
+    // Whether each of the player's functions returns a Copy value
+    let returns_copy: HashMap<String, bool> = functions.iter()
+        .map(|function| {
+            let copy = match &function.sig.output {
+                syn::ReturnType::Default => true,
+                syn::ReturnType::Type(_, ty) => is_copy_type(ty),
+            };
+            (function.sig.ident.to_string(), copy)
+        })
+        .collect();
+
+    let mut analyzer = Analyzer { returns_copy, function: String::new(), timelines: Vec::new(), scopes: Vec::new() };
+    for function in functions {
+        analyzer.function = function.sig.ident.to_string();
+        analyzer.scopes.push(Vec::new());
+        let line = function.sig.ident.span().start().line;
+        for input in &function.sig.inputs {
+            if let syn::FnArg::Typed(arg) = input {
+                let copy = is_copy_type(&arg.ty);
+                for name in pattern_names(&arg.pat) {
+                    analyzer.declare(&name, copy, line, "parameter");
+                }
+            }
+        }
+        analyzer.walk_block(&function.block);
+        analyzer.close_scope(function.block.brace_token.span.close().start().line);
+    }
+    Ok(analyzer.timelines)
+}
+
+/// How the value of an expression is taken
+#[derive(Clone)]
+enum Context {
+    Use,
+    Move(String), // Owned values move into this target
+}
+
+struct Analyzer {
+    returns_copy: HashMap<String, bool>,
+    function: String,
+    timelines: Vec<VariableTimeline>,
+    scopes: Vec<Vec<usize>>, // Timelines declared in each open block, innermost last
+}
+
+impl Analyzer {
+    fn declare(&mut self, name: &str, copy: bool, line: usize, detail: &str) {
+        self.timelines.push(VariableTimeline {
+            name: name.to_string(),
+            function: self.function.clone(),
+            copy,
+            events: vec![OwnershipEvent { line, kind: EventKind::Declared, detail: detail.to_string() }],
+        });
+        let index = self.timelines.len() - 1;
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(index);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<usize> {
+        self.scopes.iter().rev()
+            .flat_map(|scope| scope.iter().rev())
+            .copied()
+            .find(|index| self.timelines[*index].name == name)
+    }
+
+    fn is_copy_var(&self, name: &str) -> bool {
+        self.lookup(name).is_some_and(|index| self.timelines[index].copy)
+    }
+
+    /// Record `kind` for variable `name` if it's one of ours; touching a moved value is flagged
+    fn record(&mut self, name: &str, line: usize, kind: EventKind, detail: impl Into<String>) {
+        let Some(index) = self.lookup(name) else { return };
+        let timeline = &mut self.timelines[index];
+        let kind = match kind {
+            EventKind::Assigned | EventKind::Declared | EventKind::Dropped => kind,
+            _ if timeline.is_moved() => EventKind::UsedAfterMove,
+            _ => kind,
+        };
+        timeline.events.push(OwnershipEvent { line, kind, detail: detail.into() });
+    }
+
+    fn close_scope(&mut self, line: usize) {
+        let Some(scope) = self.scopes.pop() else { return };
+        for index in scope {
+            if !self.timelines[index].is_moved() {
+                self.timelines[index].events.push(OwnershipEvent { line, kind: EventKind::Dropped, detail: "goes out of scope".to_string() });
+            }
+        }
+    }
+
+    fn walk_block(&mut self, block: &Block) {
+        for stmt in &block.stmts {
+            match stmt {
+                Stmt::Local(local) => {
+                    let names = pattern_names(&local.pat);
+                    let line = local.span().start().line;
+                    let copy = match (&local.pat, &local.init) {
+                        (Pat::Type(typed), _) => is_copy_type(&typed.ty),
+                        (_, Some(init)) => self.is_copy_expr(&init.expr),
+                        _ => false,
+                    };
+                    if let Some(init) = &local.init {
+                        let target = match names.as_slice() {
+                            [name] => format!("moved into `{}`", name),
+                            _ => "moved into a pattern".to_string(),
+                        };
+                        self.walk_expr(&init.expr, Context::Move(target));
+                        if let Some((_, diverge)) = &init.diverge {
+                            self.walk_expr(diverge, Context::Use);
+                        }
+                    }
+                    for name in names {
+                        self.declare(&name, copy, line, if local.init.is_some() { "declared" } else { "declared without a value" });
+                    }
+                }
+                Stmt::Expr(expr, _) => self.walk_expr(expr, Context::Use),
+                Stmt::Macro(mac) => self.walk_macro(&mac.mac),
+                Stmt::Item(_) => {}
+            }
+        }
+    }
+
+    /// Walk `block` in a scope of its own, with `bindings` (name, copy) declared at its start
+    fn walk_scoped_block(&mut self, block: &Block, bindings: &[(String, bool)], line: usize) {
+        self.scopes.push(Vec::new());
+        for (name, copy) in bindings {
+            self.declare(name, *copy, line, "bound by the loop");
+        }
+        self.walk_block(block);
+        self.close_scope(block.brace_token.span.close().start().line);
+    }
+
+    fn walk_expr(&mut self, expr: &Expr, context: Context) {
+        let line = expr.span().start().line;
+        match expr {
+            Expr::Path(path) => {
+                let Some(name) = path.path.get_ident().map(|ident| ident.to_string()) else { return };
+                match context {
+                    Context::Move(target) if !self.is_copy_var(&name) => self.record(&name, line, EventKind::Moved, target),
+                    _ => self.record(&name, line, EventKind::Used, "used"),
+                }
+            }
+            Expr::Reference(reference) => {
+                if let Some(name) = expr_name(&reference.expr) {
+                    let (kind, detail) = match reference.mutability {
+                        Some(_) => (EventKind::BorrowedMut, "borrowed mutably"),
+                        None => (EventKind::Borrowed, "borrowed"),
+                    };
+                    self.record(&name, line, kind, detail);
+                } else {
+                    self.walk_expr(&reference.expr, Context::Use);
+                }
+            }
+            Expr::Call(call) => {
+                let target = match &*call.func {
+                    Expr::Path(path) => format!("moved into {}()", path_name(&path.path)),
+                    func => {
+                        self.walk_expr(func, Context::Use);
+                        "moved into a call".to_string()
+                    }
+                };
+                for arg in &call.args {
+                    self.walk_expr(arg, Context::Move(target.clone()));
+                }
+            }
+            Expr::MethodCall(call) => {
+                let method = call.method.to_string();
+                if let Some(name) = expr_name(&call.receiver) {
+                    let (kind, detail) = if CONSUMING_METHODS.contains(&method.as_str()) && !self.is_copy_var(&name) {
+                        (EventKind::Moved, format!("consumed by .{}()", method))
+                    } else if MUTATING_METHODS.contains(&method.as_str()) {
+                        (EventKind::BorrowedMut, format!("borrowed mutably by .{}()", method))
+                    } else {
+                        (EventKind::Borrowed, format!("borrowed by .{}()", method))
+                    };
+                    self.record(&name, line, kind, detail);
+                } else {
+                    self.walk_expr(&call.receiver, Context::Use);
+                }
+                for arg in &call.args {
+                    self.walk_expr(arg, Context::Move(format!("moved into .{}()", method)));
+                }
+            }
+            Expr::Macro(mac) => self.walk_macro(&mac.mac),
+            Expr::Block(block) => self.walk_scoped_block(&block.block, &[], line),
+            Expr::Unsafe(block) => self.walk_scoped_block(&block.block, &[], line),
+            Expr::If(if_expr) => {
+                self.walk_expr(&if_expr.cond, Context::Use);
+                self.walk_scoped_block(&if_expr.then_branch, &[], line);
+                if let Some((_, else_branch)) = &if_expr.else_branch {
+                    self.walk_expr(else_branch, Context::Use);
+                }
+            }
+            Expr::ForLoop(for_loop) => {
+                self.walk_expr(&for_loop.expr, Context::Move("moved into the for loop".to_string()));
+                let copy = matches!(&*for_loop.expr, Expr::Range(_) | Expr::Reference(_));
+                let bindings: Vec<(String, bool)> = pattern_names(&for_loop.pat).into_iter().map(|name| (name, copy)).collect();
+                self.walk_scoped_block(&for_loop.body, &bindings, line);
+            }
+            Expr::While(while_loop) => {
+                self.walk_expr(&while_loop.cond, Context::Use);
+                self.walk_scoped_block(&while_loop.body, &[], line);
+            }
+            Expr::Loop(loop_expr) => self.walk_scoped_block(&loop_expr.body, &[], line),
+            Expr::Match(match_expr) => {
+                self.walk_expr(&match_expr.expr, Context::Use);
+                for arm in &match_expr.arms {
+                    self.scopes.push(Vec::new());
+                    let arm_line = arm.pat.span().start().line;
+                    for name in pattern_names(&arm.pat) {
+                        self.declare(&name, false, arm_line, "bound by the match");
+                    }
+                    if let Some((_, guard)) = &arm.guard {
+                        self.walk_expr(guard, Context::Use);
+                    }
+                    self.walk_expr(&arm.body, context.clone());
+                    self.close_scope(arm.body.span().end().line);
+                }
+            }
+            Expr::Let(let_expr) => self.walk_expr(&let_expr.expr, Context::Use),
+            Expr::Assign(assign) => {
+                let target = match expr_name(&assign.left) {
+                    Some(name) => {
+                        self.walk_expr(&assign.right, Context::Move(format!("moved into `{}`", name)));
+                        self.record(&name, line, EventKind::Assigned, "assigned a new value");
+                        return;
+                    }
+                    None => "moved into a field".to_string(),
+                };
+                self.walk_expr(&assign.right, Context::Move(target));
+                self.walk_expr(&assign.left, Context::Use);
+            }
+            Expr::Binary(binary) => {
+                // `a + &b` on Strings moves `a`; compound assignments change the left side in place
+                match binary.op {
+                    BinOp::Add(_) if !self.is_copy_expr(&binary.left) => self.walk_expr(&binary.left, Context::Move("moved into `+`".to_string())),
+                    BinOp::AddAssign(_) | BinOp::SubAssign(_) | BinOp::MulAssign(_) | BinOp::DivAssign(_) | BinOp::RemAssign(_) => {
+                        match expr_name(&binary.left) {
+                            Some(name) => self.record(&name, line, EventKind::BorrowedMut, "changed in place"),
+                            None => self.walk_expr(&binary.left, Context::Use),
+                        }
+                    }
+                    _ => self.walk_expr(&binary.left, Context::Use),
+                }
+                self.walk_expr(&binary.right, Context::Use);
+            }
+            Expr::Return(ret) => {
+                if let Some(value) = &ret.expr {
+                    self.walk_expr(value, Context::Move("returned".to_string()));
+                }
+            }
+            Expr::Break(brk) => {
+                if let Some(value) = &brk.expr {
+                    self.walk_expr(value, Context::Move("moved out of the loop".to_string()));
+                }
+            }
+            Expr::Closure(closure) => {
+                let context = match closure.capture {
+                    Some(_) => Context::Move("moved into a closure".to_string()),
+                    None => Context::Use,
+                };
+                self.walk_expr(&closure.body, context);
+            }
+            Expr::Struct(init) => {
+                let target = format!("moved into a {}", path_name(&init.path));
+                for field in &init.fields {
+                    self.walk_expr(&field.expr, Context::Move(target.clone()));
+                }
+            }
+            Expr::Tuple(tuple) => tuple.elems.iter().for_each(|elem| self.walk_expr(elem, context.clone())),
+            Expr::Array(array) => array.elems.iter().for_each(|elem| self.walk_expr(elem, context.clone())),
+            Expr::Paren(paren) => self.walk_expr(&paren.expr, context),
+            Expr::Group(group) => self.walk_expr(&group.expr, context),
+            Expr::Try(try_expr) => self.walk_expr(&try_expr.expr, context),
+            Expr::Unary(unary) => self.walk_expr(&unary.expr, Context::Use),
+            Expr::Cast(cast) => self.walk_expr(&cast.expr, Context::Use),
+            Expr::Field(field) => self.walk_expr(&field.base, Context::Use),
+            Expr::Index(index) => {
+                self.walk_expr(&index.expr, Context::Use);
+                self.walk_expr(&index.index, Context::Use);
+            }
+            Expr::Range(range) => {
+                for end in [&range.start, &range.end].into_iter().flatten() {
+                    self.walk_expr(end, Context::Use);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Formatting macros borrow their arguments, including `{name}` captured in the format string;
+    /// vec! takes ownership of its elements
+    fn walk_macro(&mut self, mac: &syn::Macro) {
+        let name = path_name(&mac.path);
+        let Ok(args) = mac.parse_body_with(syn::punctuated::Punctuated::<Expr, syn::Token![,]>::parse_terminated) else { return };
+        let line = mac.path.span().start().line;
+        if name == "vec" {
+            for arg in &args {
+                self.walk_expr(arg, Context::Move("moved into vec!".to_string()));
+            }
+            return;
+        }
+        let detail = format!("borrowed by {}!", name);
+        for (index, arg) in args.iter().enumerate() {
+            match arg {
+                Expr::Lit(syn::ExprLit { lit: Lit::Str(format), .. }) if index == 0 => {
+                    for captured in captured_names(&format.value()) {
+                        self.record(&captured, line, EventKind::Borrowed, detail.clone());
+                    }
+                }
+                _ => match expr_name(arg) {
+                    Some(name) => self.record(&name, line, EventKind::Borrowed, detail.clone()),
+                    None => self.walk_expr(arg, Context::Use),
+                },
+            }
+        }
+    }
+
+    fn is_copy_expr(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Lit(lit) => !matches!(lit.lit, Lit::ByteStr(_) | Lit::CStr(_)),
+            Expr::Reference(reference) => reference.mutability.is_none(),
+            Expr::Path(path) => path.path.get_ident().is_some_and(|ident| self.is_copy_var(&ident.to_string())),
+            Expr::Unary(unary) => matches!(unary.op, UnOp::Neg(_) | UnOp::Not(_)) && self.is_copy_expr(&unary.expr),
+            Expr::Binary(binary) => self.is_copy_expr(&binary.left) || matches!(binary.op, BinOp::Eq(_) | BinOp::Ne(_) | BinOp::Lt(_) | BinOp::Le(_) | BinOp::Gt(_) | BinOp::Ge(_) | BinOp::And(_) | BinOp::Or(_)),
+            Expr::Cast(_) => true,
+            Expr::Paren(paren) => self.is_copy_expr(&paren.expr),
+            Expr::Tuple(tuple) => tuple.elems.iter().all(|elem| self.is_copy_expr(elem)),
+            Expr::Call(call) => match &*call.func {
+                Expr::Path(path) => {
+                    let name = path_name(&path.path);
+                    self.returns_copy.get(&name).copied().unwrap_or(COPY_GAME_FUNCTIONS.contains(&name.as_str()))
+                }
+                _ => false,
+            },
+            Expr::MethodCall(call) => match call.method.to_string().as_str() {
+                "len" | "count" | "is_empty" | "contains" | "starts_with" | "ends_with" | "abs" | "min" | "max" => true,
+                "clone" | "copied" => self.is_copy_expr(&call.receiver),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+// Methods that take `self` by value, so calling them moves the receiver
+const CONSUMING_METHODS: [&str; 8] = ["into_iter", "into", "unwrap", "expect", "unwrap_or", "unwrap_or_default", "into_bytes", "into_inner"];
+// Methods that need `&mut self`
+const MUTATING_METHODS: [&str; 12] = ["push", "push_str", "pop", "insert", "remove", "clear", "extend", "sort", "truncate", "retain", "dedup", "reverse"];
+// Game functions that return numbers, bools or positions
+const COPY_GAME_FUNCTIONS: [&str; 6] = ["check_position", "get_health", "get_energy", "remaining_energy", "is_blocked", "random_int"];
+
+fn is_copy_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.get_ident().is_some_and(|ident| {
+            matches!(ident.to_string().as_str(),
+                "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "f32" | "f64" | "bool" | "char")
+        }),
+        Type::Reference(reference) => reference.mutability.is_none(),
+        Type::Tuple(tuple) => tuple.elems.iter().all(is_copy_type),
+        Type::Array(array) => is_copy_type(&array.elem),
+        Type::Paren(paren) => is_copy_type(&paren.elem),
+        _ => false,
+    }
+}
+
+fn path_name(path: &syn::Path) -> String {
+    path.segments.iter().map(|segment| segment.ident.to_string()).collect::<Vec<_>>().join("::")
+}
+
+fn expr_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(path) => path.path.get_ident().map(|ident| ident.to_string()),
+        Expr::Paren(paren) => expr_name(&paren.expr),
+        _ => None,
+    }
+}
+
+/// Every variable name a pattern binds
+fn pattern_names(pat: &Pat) -> Vec<String> {
+    match pat {
+        Pat::Ident(ident) => {
+            let mut names = vec![ident.ident.to_string()];
+            if let Some((_, sub)) = &ident.subpat {
+                names.extend(pattern_names(sub));
+            }
+            names
+        }
+        Pat::Type(typed) => pattern_names(&typed.pat),
+        Pat::Tuple(tuple) => tuple.elems.iter().flat_map(pattern_names).collect(),
+        Pat::TupleStruct(tuple) => tuple.elems.iter().flat_map(pattern_names).collect(),
+        Pat::Struct(pat) => pat.fields.iter().flat_map(|field| pattern_names(&field.pat)).collect(),
+        Pat::Slice(slice) => slice.elems.iter().flat_map(pattern_names).collect(),
+        Pat::Reference(reference) => pattern_names(&reference.pat),
+        Pat::Paren(paren) => pattern_names(&paren.pat),
+        Pat::Or(or) => or.cases.first().map(pattern_names).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Names captured inline in a format string, like `name` in "{name}" or "{name:?}"
+fn captured_names(format: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = format;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        if let Some(escaped) = rest.strip_prefix('{') {
+            rest = escaped;
+            continue;
+        }
+        let end = rest.find('}').unwrap_or(rest.len());
+        let name = rest[..end].split(':').next().unwrap_or_default().trim();
+        if name.starts_with(|c: char| c.is_alphabetic() || c == '_') && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            names.push(name.to_string());
+        }
+        rest = &rest[end..];
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(timelines: &[VariableTimeline], name: &str) -> Vec<EventKind> {
+        timelines.iter().find(|timeline| timeline.name == name).unwrap().events.iter().map(|event| event.kind).collect()
+    }
+
+    #[test]
+    fn tracks_moves_borrows_and_drops() {
+        let code = r#"
+            fn main() {
+                let mut path = Vec::new();
+                path.push("up");
+                let steps = path.len();
+                let plan = path;
+                println!("{} {steps}", &plan[0]);
+                println!("{:?}", path);
+            }
+        "#;
+        let timelines = analyze_ownership(code).unwrap();
+        assert_eq!(kinds(&timelines, "path"), vec![EventKind::Declared, EventKind::BorrowedMut, EventKind::Borrowed, EventKind::Moved, EventKind::UsedAfterMove]);
+        assert_eq!(kinds(&timelines, "steps"), vec![EventKind::Declared, EventKind::Borrowed, EventKind::Dropped]);
+        assert!(timelines.iter().find(|timeline| timeline.name == "steps").unwrap().copy);
+        assert_eq!(kinds(&timelines, "plan"), vec![EventKind::Declared, EventKind::Used, EventKind::Dropped]);
+    }
+
+    #[test]
+    fn copy_values_and_reassignment_dont_stay_moved() {
+        let code = r#"
+            fn main() {
+                let x = 3;
+                teleport(x, x);
+                let mut s = String::from("up");
+                move_bot(s);
+                s = String::from("down");
+                move_bot(&s);
+            }
+        "#;
+        let timelines = analyze_ownership(code).unwrap();
+        assert_eq!(kinds(&timelines, "x"), vec![EventKind::Declared, EventKind::Used, EventKind::Used, EventKind::Dropped]);
+        assert_eq!(kinds(&timelines, "s"), vec![EventKind::Declared, EventKind::Moved, EventKind::Assigned, EventKind::Borrowed, EventKind::Dropped]);
+    }
+}
@@ -0,0 +1,70 @@
+// random_int() for robot programs. A program's robot calls are worked out before it runs, and the
+// desktop game also compiles it to capture what it prints, so both have to draw the same numbers
+// from the level's seed. The generator is SplitMix64: small enough to paste into the compiled
+// program as source, and the same on every platform.
+
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The numbers random_int() returns for one run of a program
+#[derive(Clone, Debug)]
+pub struct SeededRandom {
+    state: u64,
+}
+
+impl SeededRandom {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A whole number from `min` to `max`, both included. None when `min` is larger than `max`
+    /// or either doesn't fit in an i32, like random_int() in the player's code.
+    pub fn int_between(&mut self, min: i64, max: i64) -> Option<i64> {
+        if min > max || i32::try_from(min).is_err() || i32::try_from(max).is_err() {
+            return None;
+        }
+        let span = (max - min + 1) as u64;
+        Some(min + (self.next_u64() % span) as i64)
+    }
+}
+
+/// random_int() as Rust source for programs that are compiled and run, drawing the same numbers
+/// as `SeededRandom::new(seed)`
+pub fn random_int_source(seed: u64) -> String {
+    format!(r#"fn random_int(min: i32, max: i32) -> i32 {{
+    use std::sync::atomic::{{AtomicU64, Ordering}};
+    static STATE: AtomicU64 = AtomicU64::new({seed});
+    assert!(min <= max, "random_int(min, max) needs min <= max");
+    let mut z = STATE.fetch_add({gamma}, Ordering::Relaxed).wrapping_add({gamma});
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    let span = (max as i64 - min as i64 + 1) as u64;
+    (min as i64 + (z % span) as i64) as i32
+}}"#, seed = seed, gamma = GOLDEN_GAMMA)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_draws_the_same_numbers_within_bounds() {
+        let draw = |seed| {
+            let mut random = SeededRandom::new(seed);
+            (0..20).map(|_| random.int_between(1, 6).unwrap()).collect::<Vec<_>>()
+        };
+        assert_eq!(draw(42), draw(42));
+        assert_ne!(draw(42), draw(43));
+        assert!(draw(42).iter().all(|n| (1..=6).contains(n)));
+        assert_eq!(SeededRandom::new(7).int_between(3, 3), Some(3));
+        assert_eq!(SeededRandom::new(7).int_between(4, 3), None);
+    }
+}
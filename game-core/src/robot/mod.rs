@@ -16,6 +16,9 @@ pub struct Robot {
     pub upgrades: Upgrades,
     pub inventory: HashSet<String>, // item names
     pub auto_grab_enabled: bool,
+    pub abilities: HashSet<String>, // granted by picked up items, e.g. "teleport"
+    pub shield_charges: u32, // enemy hits absorbed before a collision counts
+    pub facing: (i32, i32), // unit direction the robot faces; up at level start
 }
 
 impl Robot {
@@ -30,6 +33,9 @@ impl Robot {
             },
             inventory: HashSet::new(),
             auto_grab_enabled: false,
+            abilities: HashSet::new(),
+            shield_charges: 0,
+            facing: (0, -1),
         }
     }
 
@@ -95,6 +101,44 @@ impl Robot {
 
     pub fn clear_inventory(&mut self) {
         self.inventory.clear();
+        self.abilities.clear();
+        self.shield_charges = 0;
+    }
+
+    pub fn grant_ability(&mut self, ability: &str) {
+        self.abilities.insert(ability.to_string());
+    }
+
+    pub fn has_ability(&self, ability: &str) -> bool {
+        self.abilities.contains(ability)
+    }
+
+    /// Use up one shield charge, returning false if the robot has none
+    pub fn absorb_hit(&mut self) -> bool {
+        if self.shield_charges == 0 {
+            return false;
+        }
+        self.shield_charges -= 1;
+        true
+    }
+
+    /// Turn 90 degrees counter-clockwise (screen coordinates, y grows downwards)
+    pub fn rotate_left(&mut self) {
+        self.facing = (self.facing.1, -self.facing.0);
+    }
+
+    /// Turn 90 degrees clockwise
+    pub fn rotate_right(&mut self) {
+        self.facing = (-self.facing.1, self.facing.0);
+    }
+
+    pub fn facing_name(&self) -> &'static str {
+        match self.facing {
+            (0, -1) => "up",
+            (0, 1) => "down",
+            (-1, 0) => "left",
+            _ => "right",
+        }
     }
 
     // Calculate Manhattan distance from robot to target
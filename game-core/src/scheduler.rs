@@ -0,0 +1,112 @@
+// Turn resolution shared by the desktop game (GUI, headless test mode and replays) and the
+// external test runner. Once a robot action has taken a turn, TurnScheduler::resolve_turn advances
+// the world in one fixed order, so a program plays out the same way everywhere:
+//
+//   player action → hazards → enemies → collisions → win check
+//
+// Each frontend implements TurnPhases for its own state; the order only lives here.
+
+/// A frontend's version of each phase of a turn
+pub trait TurnPhases {
+    /// Conveyors, spikes and laser beams under the robots. `Ok(Some)` describes something that
+    /// happened, `Err` a hazard that caught a robot and ended the run.
+    fn hazards(&mut self) -> Result<Option<String>, String>;
+    /// Every enemy takes its step
+    fn enemies(&mut self);
+    /// Why the run ended if an enemy caught a robot
+    fn collisions(&mut self) -> Option<String>;
+    /// Whether the level is won now that the turn is over
+    fn win_check(&mut self) -> bool;
+}
+
+/// How a turn ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TurnEnd {
+    /// The program carries on
+    Continue,
+    /// A hazard or an enemy caught a robot; the message says which
+    Caught(String),
+    /// The win check passed
+    Won,
+}
+
+/// What happened during a turn once the robots had acted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TurnReport {
+    /// Things worth telling the player, such as a conveyor moving the robot
+    pub events: Vec<String>,
+    pub end: TurnEnd,
+}
+
+pub struct TurnScheduler;
+
+impl TurnScheduler {
+    /// Resolve one turn after the robots have acted. A robot getting caught ends the turn early.
+    pub fn resolve_turn(world: &mut impl TurnPhases) -> TurnReport {
+        let mut events = Vec::new();
+
+        match world.hazards() {
+            Ok(Some(event)) => events.push(event),
+            Ok(None) => {}
+            Err(message) => return TurnReport { events, end: TurnEnd::Caught(message) },
+        }
+
+        world.enemies();
+
+        if let Some(message) = world.collisions() {
+            return TurnReport { events, end: TurnEnd::Caught(message) };
+        }
+
+        let end = if world.win_check() { TurnEnd::Won } else { TurnEnd::Continue };
+        TurnReport { events, end }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        phases: Vec<&'static str>,
+        hazard: Option<Result<String, String>>,
+        caught_by_enemy: bool,
+    }
+
+    impl TurnPhases for Recorder {
+        fn hazards(&mut self) -> Result<Option<String>, String> {
+            self.phases.push("hazards");
+            self.hazard.clone().transpose()
+        }
+
+        fn enemies(&mut self) {
+            self.phases.push("enemies");
+        }
+
+        fn collisions(&mut self) -> Option<String> {
+            self.phases.push("collisions");
+            self.caught_by_enemy.then(|| "caught".to_string())
+        }
+
+        fn win_check(&mut self) -> bool {
+            self.phases.push("win");
+            true
+        }
+    }
+
+    #[test]
+    fn phases_run_in_order_and_stop_when_a_robot_is_caught() {
+        let mut world = Recorder { hazard: Some(Ok("Conveyor moved the robot".to_string())), ..Default::default() };
+        let report = TurnScheduler::resolve_turn(&mut world);
+        assert_eq!(world.phases, ["hazards", "enemies", "collisions", "win"]);
+        assert_eq!(report, TurnReport { events: vec!["Conveyor moved the robot".to_string()], end: TurnEnd::Won });
+
+        let mut world = Recorder { hazard: Some(Err("SPIKE TRAP!".to_string())), ..Default::default() };
+        assert_eq!(TurnScheduler::resolve_turn(&mut world).end, TurnEnd::Caught("SPIKE TRAP!".to_string()));
+        assert_eq!(world.phases, ["hazards"]);
+
+        let mut world = Recorder { caught_by_enemy: true, ..Default::default() };
+        assert_eq!(TurnScheduler::resolve_turn(&mut world).end, TurnEnd::Caught("caught".to_string()));
+        assert_eq!(world.phases, ["hazards", "enemies", "collisions"]);
+    }
+}
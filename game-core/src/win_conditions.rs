@@ -0,0 +1,124 @@
+// Win conditions a level can declare with `win_condition` in its YAML instead of the frontend's
+// default (collect every item on desktop, discover a third of the tiles in the browser). The
+// desktop game, the WASM build and the test runner all check them with the same
+// WinConditionEvaluator after every turn, so a level is won at the same moment everywhere.
+//
+//   win_condition: collect_all_items
+//   win_condition: {reach_position: [7, 3]}
+//   win_condition: {all: [destroy_all_enemies, {any: [{reach_position: [0, 0]}, {survive_n_turns: 30}]}]}
+
+use crate::item::Pos;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WinCondition {
+    ReachPosition((u32, u32)), // A robot stands on the tile
+    CollectAllItems,
+    DestroyAllEnemies,
+    SurviveNTurns(u32), // The robot has lasted this many turns without the level resetting
+    All(Vec<WinCondition>),
+    Any(Vec<WinCondition>),
+    Not(Box<WinCondition>),
+}
+
+/// What a win condition is checked against once a turn is over
+pub struct WinState<'a> {
+    pub robots: &'a [Pos],
+    pub items_left: usize,
+    pub enemies_left: usize,
+    pub turns: usize,
+}
+
+pub struct WinConditionEvaluator<'a> {
+    condition: &'a WinCondition,
+}
+
+impl<'a> WinConditionEvaluator<'a> {
+    pub fn new(condition: &'a WinCondition) -> Self {
+        Self { condition }
+    }
+
+    pub fn is_met(&self, state: &WinState) -> bool {
+        met(self.condition, state)
+    }
+
+    /// Tiles the level wants a robot on, for objective markers. Positions under a `not` are
+    /// tiles to stay away from, so they aren't included.
+    pub fn objective_tiles(&self) -> Vec<Pos> {
+        let mut tiles = Vec::new();
+        collect_tiles(self.condition, &mut tiles);
+        tiles
+    }
+
+    /// The objective in words, e.g. "reach (7, 3) and destroy every enemy"
+    pub fn describe(&self) -> String {
+        describe(self.condition)
+    }
+}
+
+fn met(condition: &WinCondition, state: &WinState) -> bool {
+    match condition {
+        WinCondition::ReachPosition((x, y)) => state.robots.contains(&Pos { x: *x as i32, y: *y as i32 }),
+        WinCondition::CollectAllItems => state.items_left == 0,
+        WinCondition::DestroyAllEnemies => state.enemies_left == 0,
+        WinCondition::SurviveNTurns(turns) => state.turns >= *turns as usize,
+        WinCondition::All(conditions) => conditions.iter().all(|condition| met(condition, state)),
+        WinCondition::Any(conditions) => conditions.iter().any(|condition| met(condition, state)),
+        WinCondition::Not(condition) => !met(condition, state),
+    }
+}
+
+fn collect_tiles(condition: &WinCondition, tiles: &mut Vec<Pos>) {
+    match condition {
+        WinCondition::ReachPosition((x, y)) => tiles.push(Pos { x: *x as i32, y: *y as i32 }),
+        WinCondition::All(conditions) | WinCondition::Any(conditions) => {
+            for condition in conditions {
+                collect_tiles(condition, tiles);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn describe(condition: &WinCondition) -> String {
+    let join = |conditions: &[WinCondition], word: &str| {
+        let parts: Vec<String> = conditions.iter()
+            .map(|condition| match condition {
+                WinCondition::All(_) | WinCondition::Any(_) => format!("({})", describe(condition)),
+                _ => describe(condition),
+            })
+            .collect();
+        parts.join(word)
+    };
+    match condition {
+        WinCondition::ReachPosition((x, y)) => format!("reach ({}, {})", x, y),
+        WinCondition::CollectAllItems => "collect every item".to_string(),
+        WinCondition::DestroyAllEnemies => "destroy every enemy".to_string(),
+        WinCondition::SurviveNTurns(turns) => format!("survive {} turns", turns),
+        WinCondition::All(conditions) => join(conditions, " and "),
+        WinCondition::Any(conditions) => join(conditions, " or "),
+        WinCondition::Not(condition) => format!("not {}", describe(condition)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combined_conditions_parse_and_evaluate() {
+        let yaml = "{all: [destroy_all_enemies, {any: [{reach_position: [2, 1]}, {survive_n_turns: 5}]}]}";
+        let condition: WinCondition = serde_yaml::with::singleton_map_recursive::deserialize(serde_yaml::Deserializer::from_str(yaml)).unwrap();
+        let evaluator = WinConditionEvaluator::new(&condition);
+        assert_eq!(evaluator.describe(), "destroy every enemy and (reach (2, 1) or survive 5 turns)");
+        assert_eq!(evaluator.objective_tiles(), vec![Pos { x: 2, y: 1 }]);
+
+        let robots = [Pos { x: 2, y: 1 }];
+        let state = WinState { robots: &robots, items_left: 3, enemies_left: 0, turns: 1 };
+        assert!(evaluator.is_met(&state));
+        assert!(!evaluator.is_met(&WinState { enemies_left: 1, ..state }));
+        assert!(evaluator.is_met(&WinState { robots: &[], turns: 5, ..state }));
+        assert!(!evaluator.is_met(&WinState { robots: &[], turns: 4, ..state }));
+    }
+}
@@ -0,0 +1,7 @@
+// EMP emitter - unlocks emp(radius) for the rest of the level
+// ABILITY: emp
+// ON_PICKUP: EMP charged! Call emp(radius) to stun every enemy within that many steps for 3 turns.
+
+pub fn max_emp_radius() -> u32 {
+    4 // Furthest the pulse reaches, counting steps up, down, left and right
+}
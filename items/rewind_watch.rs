@@ -0,0 +1,7 @@
+// Rewind watch - unlocks rewind(turns) for the rest of the level
+// ABILITY: rewind
+// ON_PICKUP: Time is on your side! Call rewind(n) to undo the last n turns (up to 50).
+
+pub fn max_rewind() -> usize {
+    50 // Turns of history the watch remembers
+}
@@ -0,0 +1,9 @@
+// Shield charm - absorbs enemy hits for the rest of the level
+// ABILITY: shield
+// CAPABILITY: shield_hits = 2
+// CAPABILITY: credits_value = 5
+// ON_PICKUP: The charm hums - the next two enemy hits will be absorbed.
+
+pub fn shield_hits() -> u32 {
+    2 // Each absorbed hit destroys the enemy that caused it
+}
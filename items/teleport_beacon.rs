@@ -0,0 +1,7 @@
+// Teleport beacon - unlocks teleport(x, y) for the rest of the level
+// ABILITY: teleport
+// ON_PICKUP: Beacon linked! Call teleport(x, y) to jump to any explored, unblocked tile. Each jump costs 3 turns.
+
+pub fn teleport_cost() -> u32 {
+    3 // Turns spent per teleport - enemies keep moving meanwhile
+}
@@ -0,0 +1,18 @@
+// MOVEMENT_PATTERN: chase_smart
+// This movement pattern makes enemies chase the player along the shortest path around obstacles
+//
+// To use this pattern in your YAML file:
+// enemies:
+//   - start_location: [15, 8]
+//     movement_pattern: "file:movement_patterns/chase_smart.rs"
+//     aggro_radius: 6   # optional: only chase a player within 6 steps
+//
+// The same behavior is built in as movement_pattern: "chase_smart".
+//
+// Smart chase movement behavior:
+// - Enemy finds a path to the player with A*, going around walls, closed doors and crates
+// - It follows that path and only searches again when the path is blocked or used up, or when
+//   the player has moved and a few turns have passed (fewer on harder levels)
+// - A search looks at a limited number of tiles; in a big maze the enemy heads for the closest
+//   tile it found, so it keeps moving while it works its way around
+// - Outside the aggro radius the enemy waits where it is
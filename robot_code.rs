@@ -1,7 +1,9 @@
 // Write your robot control code here
 // Available functions:
 // robot.move_up(), robot.move_down(), robot.move_left(), robot.move_right()
-// robot.scan() -> returns what's at the robot's current position
+// robot.scan_current() -> returns what's at the robot's current position
+// robot.grab() -> picks up the item under the robot
+// The same actions also work as plain calls: move_bot("up"), scan("current"), grab()
 // println!() for debugging output
 fn scan_level() {
     for y in 0..6 {
@@ -0,0 +1,385 @@
+//! Command line front-end for the Rust Game Test Runner.
+//!
+//! Usage:
+//!   test-runner --test-code FILE [--verbose] [--seed N]
+//!   test-runner --test-dir DIR [--verbose] [--seed N]
+//!   test-runner --test-many FILE... [--verbose] [--seed N]
+//!
+//! Add --level LEVEL.yaml to test on a level's layout (or --level N for the game's built-in level N),
+//! and --watch to re-run whenever a solution or the level changes.
+
+use rust_game_test_runner::{BatchTestResult, GameConfig, TestResult, TestRunner};
+use std::path::{Path, PathBuf};
+
+fn print_usage() {
+    println!("Usage:");
+    println!("  test-runner --test-code FILE       Test a single solution");
+    println!("  test-runner --test-dir DIR         Test every .rs file in DIR concurrently");
+    println!("  test-runner --test-many FILE...    Test the given files concurrently");
+    println!();
+    println!("Options:");
+    println!("  --verbose                          Show messages for every solution");
+    println!("  --seed N                           Seed for enemy placement, movement and random_int()");
+    println!("  --level FILE                       Test on the grid, walls and hazards of a level YAML");
+    println!("  --level N                          Test on built-in level N of the game, counting from 1");
+    println!("  --watch                            Re-run whenever a solution or the level file changes");
+}
+
+fn print_result(result: &TestResult, verbose: bool) {
+    if result.success {
+        println!("✅ Execution successful");
+    } else {
+        println!("❌ Execution failed");
+    }
+    println!("📍 Final Position: ({}, {})", result.final_position.x, result.final_position.y);
+    println!("🔄 Turns taken: {}", result.turns_taken);
+    println!("🎲 Seed: {}", result.seed);
+    println!("📋 Messages: {} popups would be displayed", result.messages.len());
+
+    if verbose {
+        for message in &result.messages {
+            println!("Message Popup: {} - {}", message.title, message.content);
+        }
+    }
+    if let Some(error) = &result.error {
+        println!("Error: {}", error);
+    }
+}
+
+fn rust_files_in(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn run_batch(runner: &TestRunner, files: &[PathBuf], verbose: bool) -> bool {
+    let mut sources = Vec::with_capacity(files.len());
+    for file in files {
+        match std::fs::read_to_string(file) {
+            Ok(code) => sources.push(code),
+            Err(e) => {
+                eprintln!("Failed to read {}: {}", file.display(), e);
+                return false;
+            }
+        }
+    }
+
+    let codes: Vec<&str> = sources.iter().map(|s| s.as_str()).collect();
+    let start = std::time::Instant::now();
+    let results: Vec<BatchTestResult> = runner.test_many(&codes);
+    let total = start.elapsed();
+
+    let mut passed = 0;
+    for batch_result in &results {
+        let file = &files[batch_result.index];
+        println!();
+        println!("=== {} ({}ms) ===", file.display(), batch_result.duration_ms);
+        match &batch_result.result {
+            Ok(result) => {
+                if result.success {
+                    passed += 1;
+                }
+                print_result(result, verbose);
+            }
+            Err(e) => println!("❌ Could not test solution: {}", e),
+        }
+    }
+
+    println!();
+    println!("=== Batch Summary ===");
+    println!("{}/{} solutions passed in {:.2}s", passed, results.len(), total.as_secs_f64());
+    passed == results.len()
+}
+
+/// What to test, worked out once from the arguments
+enum Target {
+    Code(PathBuf),
+    Dir(PathBuf),
+    Many(Vec<PathBuf>),
+}
+
+impl Target {
+    /// The solutions to test right now; a directory is listed again on every run
+    fn files(&self) -> std::io::Result<Vec<PathBuf>> {
+        match self {
+            Target::Code(file) => Ok(vec![file.clone()]),
+            Target::Dir(dir) => rust_files_in(dir),
+            Target::Many(files) => Ok(files.clone()),
+        }
+    }
+}
+
+/// A --level that's a number rather than a file names one of the game's built-in levels
+fn builtin_level_number(level: &Path) -> Option<usize> {
+    if level.exists() {
+        return None;
+    }
+    level.to_str()?.parse().ok().filter(|number| *number > 0)
+}
+
+/// Build the configuration for a run; the level file is read again every time
+fn load_config(level: Option<&Path>, seed: Option<u64>, verbose: bool) -> Result<GameConfig, String> {
+    let mut config = match level {
+        Some(path) => match builtin_level_number(path) {
+            Some(number) => GameConfig::from_embedded_level(number - 1)?,
+            None => GameConfig::from_level_file(path)
+                .map_err(|e| format!("Failed to load level {}: {}", path.display(), e))?,
+        },
+        None => GameConfig::new(),
+    };
+    if let Some(seed) = seed {
+        config = config.with_seed(seed);
+    }
+    Ok(config.with_logging(verbose))
+}
+
+fn run(target: &Target, config: GameConfig, verbose: bool) -> bool {
+    let runner = TestRunner::new(config);
+    match target {
+        Target::Code(file) => {
+            println!("Testing code from file: {}", file.display());
+            let code = match std::fs::read_to_string(file) {
+                Ok(code) => code,
+                Err(e) => {
+                    eprintln!("Failed to read {}: {}", file.display(), e);
+                    return false;
+                }
+            };
+            match runner.test_code_blocking(&code) {
+                Ok(result) => {
+                    println!();
+                    println!("=== Test Results ===");
+                    print_result(&result, verbose);
+                    result.success
+                }
+                Err(e) => {
+                    eprintln!("❌ Could not test solution: {}", e);
+                    false
+                }
+            }
+        }
+        Target::Dir(dir) => match rust_files_in(dir) {
+            Ok(files) => {
+                println!("Testing {} files from directory: {}", files.len(), dir.display());
+                run_batch(&runner, &files, verbose)
+            }
+            Err(e) => {
+                eprintln!("Failed to read directory {}: {}", dir.display(), e);
+                false
+            }
+        },
+        Target::Many(files) => {
+            println!("Testing {} files", files.len());
+            run_batch(&runner, files, verbose)
+        }
+    }
+}
+
+/// Colour `text` for the terminal unless NO_COLOR is set
+fn paint(text: &str, color: &str) -> String {
+    if std::env::var_os("NO_COLOR").is_some() {
+        text.to_string()
+    } else {
+        format!("\x1b[{}m{}\x1b[0m", color, text)
+    }
+}
+
+const GREEN: &str = "32";
+const RED: &str = "31";
+const DIM: &str = "2";
+
+/// One line per solution and a total, for re-runs in watch mode
+fn run_summary(target: &Target, config: GameConfig) -> bool {
+    let files = match target.files() {
+        Ok(files) => files,
+        Err(e) => {
+            println!("{} {}", paint("ERROR", RED), e);
+            return false;
+        }
+    };
+    let mut sources = Vec::with_capacity(files.len());
+    for file in &files {
+        match std::fs::read_to_string(file) {
+            Ok(code) => sources.push(code),
+            Err(e) => {
+                println!("{} {}: {}", paint("ERROR", RED), file.display(), e);
+                return false;
+            }
+        }
+    }
+
+    let codes: Vec<&str> = sources.iter().map(|s| s.as_str()).collect();
+    let results = TestRunner::new(config).test_many(&codes);
+    let mut passed = 0;
+    for batch_result in &results {
+        let file = files[batch_result.index].display();
+        match &batch_result.result {
+            Ok(result) if result.success => {
+                passed += 1;
+                println!("{} {} {}", paint("PASS", GREEN), file,
+                    paint(&format!("({} turns, {}ms)", result.turns_taken, batch_result.duration_ms), DIM));
+            }
+            Ok(result) => println!("{} {} {}", paint("FAIL", RED), file,
+                result.error.as_deref().unwrap_or("the robot didn't finish")),
+            Err(e) => println!("{} {} {}", paint("FAIL", RED), file, e),
+        }
+    }
+
+    let summary = format!("{}/{} passed", passed, results.len());
+    let all_passed = passed == results.len();
+    println!("{}", paint(&summary, if all_passed { GREEN } else { RED }));
+    all_passed
+}
+
+/// Re-run the tests whenever a solution or the level file changes, until the process is killed.
+/// Parent directories are watched rather than the files, so editors that save by replacing the
+/// file are still noticed.
+fn watch(target: &Target, level: Option<&Path>, seed: Option<u64>) -> notify::Result<()> {
+    use notify::{RecursiveMode, Watcher};
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+
+    let mut directories: Vec<PathBuf> = Vec::new();
+    let mut watched_files: Vec<PathBuf> = Vec::new();
+    let files = match target {
+        Target::Dir(dir) => {
+            directories.push(dir.clone());
+            Vec::new()
+        }
+        Target::Code(file) => vec![file.clone()],
+        Target::Many(files) => files.clone(),
+    };
+    for file in files.iter().map(PathBuf::as_path).chain(level) {
+        let parent = file.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let parent = parent.canonicalize().unwrap_or_else(|_| parent.to_path_buf());
+        if let Some(name) = file.file_name() {
+            watched_files.push(parent.join(name));
+        }
+        directories.push(parent);
+    }
+    directories.sort();
+    directories.dedup();
+    for directory in &directories {
+        watcher.watch(directory, RecursiveMode::NonRecursive)?;
+    }
+    let dir_target = match target {
+        Target::Dir(dir) => dir.canonicalize().ok(),
+        _ => None,
+    };
+    let relevant = |path: &Path| {
+        let canonical = path.parent()
+            .and_then(|parent| parent.canonicalize().ok())
+            .zip(path.file_name())
+            .map(|(parent, name)| parent.join(name));
+        let Some(canonical) = canonical else { return false };
+        watched_files.contains(&canonical)
+            || dir_target.as_ref().is_some_and(|dir| {
+                canonical.parent() == Some(dir.as_path()) && canonical.extension().is_some_and(|ext| ext == "rs")
+            })
+    };
+
+    let run_once = || match load_config(level, seed, false) {
+        Ok(config) => {
+            run_summary(target, config);
+        }
+        Err(e) => println!("{} {}", paint("ERROR", RED), e),
+    };
+
+    run_once();
+    println!("{}", paint("Watching for changes (Ctrl+C to stop)...", DIM));
+    while let Ok(event) = rx.recv() {
+        let changed = event.map(|event| event.paths.iter().any(|path| relevant(path))).unwrap_or(false);
+        if !changed {
+            continue;
+        }
+        // A save often arrives as several events; wait for them to settle before re-running
+        while rx.recv_timeout(std::time::Duration::from_millis(200)).is_ok() {}
+        println!();
+        println!("{}", paint("Change detected, re-running...", DIM));
+        run_once();
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let verbose = args.iter().any(|a| a == "--verbose" || a == "-v");
+    let watch_mode = args.iter().any(|a| a == "--watch");
+
+    let mut seed = None;
+    if let Some(pos) = args.iter().position(|a| a == "--seed") {
+        match args.get(pos + 1).and_then(|s| s.parse::<u64>().ok()) {
+            Some(value) => seed = Some(value),
+            None => {
+                eprintln!("--seed requires a numeric value");
+                std::process::exit(2);
+            }
+        }
+    }
+    let mut level = None;
+    if let Some(pos) = args.iter().position(|a| a == "--level") {
+        match args.get(pos + 1) {
+            Some(path) => level = Some(PathBuf::from(path)),
+            None => {
+                eprintln!("--level requires a level YAML path or a built-in level number");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let target = if let Some(pos) = args.iter().position(|a| a == "--test-code") {
+        let Some(file) = args.get(pos + 1) else {
+            eprintln!("--test-code requires a file path");
+            std::process::exit(2);
+        };
+        Target::Code(PathBuf::from(file))
+    } else if let Some(pos) = args.iter().position(|a| a == "--test-dir") {
+        let Some(dir) = args.get(pos + 1) else {
+            eprintln!("--test-dir requires a directory path");
+            std::process::exit(2);
+        };
+        Target::Dir(PathBuf::from(dir))
+    } else if let Some(pos) = args.iter().position(|a| a == "--test-many") {
+        let files: Vec<PathBuf> = args[pos + 1..]
+            .iter()
+            .take_while(|a| !a.starts_with("--"))
+            .map(PathBuf::from)
+            .collect();
+        if files.is_empty() {
+            eprintln!("--test-many requires at least one file path");
+            std::process::exit(2);
+        }
+        Target::Many(files)
+    } else {
+        print_usage();
+        std::process::exit(2);
+    };
+
+    println!("=== RUST GAME TEST RUNNER ===");
+
+    if watch_mode {
+        if let Err(e) = watch(&target, level.as_deref(), seed) {
+            eprintln!("❌ Failed to watch for changes: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let success = match load_config(level.as_deref(), seed, verbose) {
+        Ok(config) => run(&target, config, verbose),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            false
+        }
+    };
+
+    println!();
+    println!("=== Test Complete ===");
+    if !success {
+        std::process::exit(1);
+    }
+}
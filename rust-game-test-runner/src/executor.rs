@@ -0,0 +1,103 @@
+//! Executes parsed robot calls against a [`GameState`](crate::GameState)
+
+use crate::{GameState, Position};
+use game_core::interpreter::{ExecutionOutcome, FunctionCall, RustFunction};
+use game_core::item::Pos;
+
+/// Runs robot function calls, returning the same outcomes the game reports for them
+#[derive(Debug, Default)]
+pub struct CodeExecutor;
+
+impl CodeExecutor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Execute one call, advancing `state.turns` by the turns it takes in the game
+    pub fn execute_function(&mut self, state: &mut GameState, call: FunctionCall) -> ExecutionOutcome {
+        let error = |message: &str| ExecutionOutcome::Error { message: message.to_string() };
+        match call.function {
+            RustFunction::Move => {
+                let Some((dx, dy)) = call.direction else {
+                    return error("Direction required for move");
+                };
+                state.turns += 1;
+                let next = Position::new(state.robot_position.x + dx, state.robot_position.y + dy);
+                let pos = Pos { x: next.x, y: next.y };
+                if !state.grid.in_bounds(pos) {
+                    error("Move blocked")
+                } else if state.grid.is_blocked(pos) {
+                    ExecutionOutcome::Blocked { reason: "Unknown Object Blocking Function".to_string() }
+                } else if state.grid.laser_beam_at(pos, state.turns as usize) {
+                    error("Move blocked by a laser beam")
+                } else {
+                    state.robot_position = next;
+                    state.grid.flip_switch(pos);
+                    ExecutionOutcome::Moved { message: "Move executed".to_string() }
+                }
+            }
+            // Grabbing and scanning take no turn, like in the game
+            RustFunction::Grab => {
+                let before = state.items.len();
+                state.items.retain(|item| *item != state.robot_position);
+                let message = if state.items.len() < before { "Grabbed items!" } else { "Nothing to grab." };
+                ExecutionOutcome::Acted { message: message.to_string() }
+            }
+            RustFunction::Scan => {
+                let summary = match call.direction {
+                    Some((0, 0)) | None => Self::scan_area(state),
+                    Some(dir) => Self::scan_direction(state, dir),
+                };
+                ExecutionOutcome::Scanned { summary }
+            }
+            RustFunction::OpenDoor => {
+                let Some(open) = call.boolean_param else {
+                    return error("Boolean parameter required for open_door (true or false)");
+                };
+                state.turns += 1;
+                let pos = Pos { x: state.robot_position.x, y: state.robot_position.y };
+                // The runner doesn't track which items were picked up, so locked doors stay locked
+                ExecutionOutcome::Acted { message: state.grid.use_door(pos, open, &[]) }
+            }
+            RustFunction::Println | RustFunction::Eprintln | RustFunction::Panic => {
+                ExecutionOutcome::Acted { message: String::new() }
+            }
+            other => ExecutionOutcome::Error { message: format!("{:?} is not supported by the test runner", other) },
+        }
+    }
+
+    /// scan("current"): the four tiles next to the robot
+    fn scan_area(state: &GameState) -> String {
+        let robot = state.robot_position;
+        let neighbors: Vec<Position> = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .iter()
+            .map(|(dx, dy)| Position::new(robot.x + dx, robot.y + dy))
+            .filter(|pos| state.grid.in_bounds(Pos { x: pos.x, y: pos.y }))
+            .filter(|pos| !state.grid.is_blocked(Pos { x: pos.x, y: pos.y }))
+            .collect();
+
+        let items = neighbors.iter().filter(|pos| state.items.contains(pos)).count();
+        let enemies = neighbors.iter().filter(|pos| state.enemies.contains(pos)).count();
+        if items == 0 && enemies == 0 {
+            format!("All {} accessible tiles are empty", neighbors.len())
+        } else {
+            format!("Found {} items and {} enemies next to the robot", items, enemies)
+        }
+    }
+
+    /// scan(direction): every tile up to the first wall or the edge of the grid
+    fn scan_direction(state: &GameState, (dx, dy): (i32, i32)) -> String {
+        let mut pos = state.robot_position;
+        let (mut items, mut enemies) = (0, 0);
+        loop {
+            pos = Position::new(pos.x + dx, pos.y + dy);
+            let tile = Pos { x: pos.x, y: pos.y };
+            if !state.grid.in_bounds(tile) || state.grid.is_blocked(tile) {
+                break;
+            }
+            items += state.items.iter().filter(|item| **item == pos).count();
+            enemies += state.enemies.iter().filter(|enemy| **enemy == pos).count();
+        }
+        format!("Scan complete. Found {} items and {} enemies in that direction.", items, enemies)
+    }
+}
@@ -0,0 +1,7 @@
+//! The runner plays on the game's own grid, so walls and doors behave exactly like in the game
+
+pub use game_core::grid::Grid;
+pub use game_core::item::Pos;
+
+/// Grid of a [`GameState`](crate::GameState)
+pub type TestGrid = Grid;
@@ -3,7 +3,13 @@
 //! A testing framework for editors and basic game engines to verify code functionality
 //! without GUI interaction. Originally developed for the Rust Learning Game.
 
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
+use game_core::combat_log::{enemy_steps, CombatLog};
+use game_core::level::EnergyConfig;
+use game_core::scheduler::{TurnEnd, TurnPhases, TurnScheduler};
+use game_core::win_conditions::{WinConditionEvaluator, WinState};
 
 pub mod parser;
 pub mod executor;
@@ -14,6 +20,15 @@ pub use parser::*;
 pub use executor::*;
 pub use grid::*;
 pub use robot::*;
+pub use game_core::win_conditions::WinCondition;
+pub use game_core::budget::{Abort, ExecutionBudget};
+pub use game_core::combat_log::{CombatEvent, CombatLogEntry};
+
+/// Seed used when no explicit seed is configured (matches the game's headless test mode)
+pub const DEFAULT_SEED: u64 = 0xDEADBEEF;
+
+/// Tiles a chasing enemy's path search may look at each turn, like the game's chase_smart enemies
+pub const CHASE_SEARCH_BUDGET: usize = 400;
 
 /// Configuration for game testing environment
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +38,130 @@ pub struct GameConfig {
     pub robot_start_x: i32,
     pub robot_start_y: i32,
     pub enable_logging: bool,
+    /// Seed for every random decision (enemy placement, item spawns, enemy movement, `random_int()`)
+    #[serde(default = "default_seed")]
+    pub seed: u64,
+    /// Number of enemies placed randomly on the grid
+    #[serde(default)]
+    pub enemy_count: usize,
+    /// Number of items spawned randomly on the grid
+    #[serde(default)]
+    pub item_count: usize,
+    /// Starting energy; `None` means the robot has unlimited energy
+    #[serde(default)]
+    pub energy: Option<u32>,
+    /// Energy a move costs when `energy` is set (teleport costs three moves)
+    #[serde(default = "default_move_cost")]
+    pub move_cost: u32,
+    /// Energy a scan costs when `energy` is set
+    #[serde(default = "default_scan_cost")]
+    pub scan_cost: u32,
+    /// Energy a laser shot costs when `energy` is set
+    #[serde(default = "default_laser_cost")]
+    pub laser_cost: u32,
+    /// Energy an EMP costs when `energy` is set
+    #[serde(default = "default_emp_cost")]
+    pub emp_cost: u32,
+    /// Belts that shift the robot one tile at the end of each turn
+    #[serde(default)]
+    pub conveyors: Vec<Conveyor>,
+    /// Traps that are deadly every other turn
+    #[serde(default)]
+    pub spikes: Vec<SpikeTrap>,
+    /// Wall-mounted lasers whose beam blocks and kills on an on/off cycle
+    #[serde(default)]
+    pub laser_emitters: Vec<LaserEmitter>,
+    /// Closed doors the robot has to open with `open_door(true)` to walk through
+    #[serde(default)]
+    pub doors: Vec<Position>,
+    /// Wall tiles the robot can't enter
+    #[serde(default)]
+    pub walls: Vec<Position>,
+    /// Tiles that add an enemy every few turns, up to a cap
+    #[serde(default)]
+    pub spawners: Vec<EnemySpawner>,
+    /// Chasing enemies spread out around the robot instead of following each other
+    #[serde(default)]
+    pub flanking: bool,
+    /// What wins the level; the run stops once it is met
+    #[serde(default)]
+    pub win_condition: Option<WinCondition>,
+    /// Wall-clock seconds the level allows, played out on the simulated clock below
+    #[serde(default)]
+    pub real_time_limit_secs: Option<u32>,
+    /// Seconds of the simulated clock each turn takes. The default of 0 leaves the clock
+    /// standing, so a time limit never runs out unless a test sets this.
+    #[serde(default)]
+    pub seconds_per_turn: f32,
+    /// Most actions and wall-clock seconds a run may take before it's aborted, like in the game
+    #[serde(default)]
+    pub budget: ExecutionBudget,
+}
+
+/// Conveyor tile, pushing the robot by `direction` at the end of a turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conveyor {
+    pub position: Position,
+    pub direction: (i32, i32),
+}
+
+/// Spike trap, deadly on turns where `turn + phase` is even (same rule as the game)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpikeTrap {
+    pub position: Position,
+    pub phase: u32,
+}
+
+/// Laser emitter at `position` firing along `direction`: on for `on_turns` turns, then off for
+/// `off_turns`, starting `phase` turns into the cycle (same rule as the game)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaserEmitter {
+    pub position: Position,
+    pub direction: (i32, i32),
+    pub on_turns: u32,
+    pub off_turns: u32,
+    #[serde(default)]
+    pub phase: u32,
+}
+
+/// Spawner tile adding an enemy every `every` turns until it has spawned `max`, waiting while its
+/// tile is taken (same rule as the game). `chases` makes its enemies chase the robot along the
+/// shortest path and catch it, instead of wandering like the randomly placed enemies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnemySpawner {
+    pub position: Position,
+    pub every: u32,
+    pub max: u32,
+    #[serde(default)]
+    pub chases: bool,
+}
+
+/// A spawner during a run
+#[derive(Debug, Clone, Copy)]
+pub struct SpawnerState {
+    pub spawner: EnemySpawner,
+    pub turns_left: u32,
+    pub spawned: u32,
+}
+
+fn default_seed() -> u64 {
+    DEFAULT_SEED
+}
+
+fn default_move_cost() -> u32 {
+    EnergyConfig::with_capacity(0).move_cost
+}
+
+fn default_scan_cost() -> u32 {
+    EnergyConfig::with_capacity(0).scan_cost
+}
+
+fn default_laser_cost() -> u32 {
+    EnergyConfig::with_capacity(0).laser_cost
+}
+
+fn default_emp_cost() -> u32 {
+    EnergyConfig::with_capacity(0).emp_cost
 }
 
 impl GameConfig {
@@ -34,9 +173,93 @@ impl GameConfig {
             robot_start_x: 1,
             robot_start_y: 1,
             enable_logging: false,
+            seed: DEFAULT_SEED,
+            enemy_count: 0,
+            item_count: 0,
+            energy: None,
+            move_cost: default_move_cost(),
+            scan_cost: default_scan_cost(),
+            laser_cost: default_laser_cost(),
+            emp_cost: default_emp_cost(),
+            conveyors: Vec::new(),
+            spikes: Vec::new(),
+            laser_emitters: Vec::new(),
+            doors: Vec::new(),
+            walls: Vec::new(),
+            spawners: Vec::new(),
+            flanking: false,
+            win_condition: None,
+            real_time_limit_secs: None,
+            seconds_per_turn: 0.0,
+            budget: ExecutionBudget::default(),
         }
     }
 
+    /// Configuration matching a level file (YAML, JSON or TOML): its grid, walls, doors, conveyors, spikes, laser
+    /// emitters, spawners, win condition and energy, and as many randomly placed enemies and items as the level has
+    pub fn from_level_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let level = game_core::level::YamlLevelConfig::from_file(path)?;
+        let spec = level.to_level_spec(&mut StdRng::seed_from_u64(DEFAULT_SEED))?;
+        Ok(Self::from_level_spec(&spec))
+    }
+
+    /// Configuration matching one of the levels built into the game (0-indexed), laid out the way the
+    /// game lays it out
+    pub fn from_embedded_level(index: usize) -> Result<Self, String> {
+        let specs = game_core::level::embedded::embedded_level_specs();
+        let count = specs.len();
+        specs.get(index)
+            .map(Self::from_level_spec)
+            .ok_or_else(|| format!("There's no built-in level {} (there are {})", index + 1, count))
+    }
+
+    /// Configuration matching an already built level
+    pub fn from_level_spec(spec: &game_core::level::LevelSpec) -> Self {
+        let position = |(x, y): (usize, usize)| Position::new(x as i32, y as i32);
+
+        let mut config = Self::new()
+            .with_grid_size(spec.width, spec.height)
+            .with_robot_start_position(spec.start.0 as i32, spec.start.1 as i32)
+            .with_enemies(spec.enemies.len())
+            .with_random_items(spec.items.len());
+        if let Some(seed) = spec.seed {
+            config = config.with_seed(seed);
+        }
+        if let Some(energy) = spec.energy {
+            config = config.with_energy_costs(energy);
+        }
+        config.walls = spec.blockers.iter().copied().map(position).collect();
+        config.doors = spec.doors.iter().copied().map(position).collect();
+        config.conveyors = spec.conveyors.iter()
+            .map(|conveyor| Conveyor { position: position(conveyor.pos), direction: conveyor.direction })
+            .collect();
+        config.spikes = spec.spikes.iter()
+            .map(|spike| SpikeTrap { position: position(spike.pos), phase: spike.phase % 2 })
+            .collect();
+        config.spawners = spec.spawners.iter()
+            .map(|spawner| EnemySpawner {
+                position: position(spawner.pos),
+                every: spawner.every.max(1),
+                max: spawner.max,
+                chases: spawner.enemy.movement_pattern.as_deref()
+                    .is_some_and(|pattern| game_core::level::CHASE_PATTERNS.contains(&pattern)),
+            })
+            .collect();
+        config.flanking = spec.flanking;
+        config.win_condition = spec.win_condition.clone();
+        config.real_time_limit_secs = spec.real_time_limit_secs;
+        config.laser_emitters = spec.emitters.iter()
+            .map(|emitter| LaserEmitter {
+                position: position(emitter.pos),
+                direction: emitter.direction,
+                on_turns: emitter.on_turns,
+                off_turns: emitter.off_turns,
+                phase: emitter.phase,
+            })
+            .collect();
+        config
+    }
+
     /// Set the grid size
     pub fn with_grid_size(mut self, width: usize, height: usize) -> Self {
         self.grid_width = width;
@@ -56,6 +279,104 @@ impl GameConfig {
         self.enable_logging = enabled;
         self
     }
+
+    /// Set the random seed so runs with the same code produce the same result
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Place the given number of enemies at random positions
+    pub fn with_enemies(mut self, count: usize) -> Self {
+        self.enemy_count = count;
+        self
+    }
+
+    /// Spawn the given number of items at random positions
+    pub fn with_random_items(mut self, count: usize) -> Self {
+        self.item_count = count;
+        self
+    }
+
+    /// Add a conveyor that pushes the robot by (dx, dy) at the end of each turn
+    pub fn with_conveyor(mut self, x: i32, y: i32, dx: i32, dy: i32) -> Self {
+        self.conveyors.push(Conveyor { position: Position::new(x, y), direction: (dx, dy) });
+        self
+    }
+
+    /// Add a spike trap; phase 0 is deadly on even turns, 1 on odd turns
+    pub fn with_spikes(mut self, x: i32, y: i32, phase: u32) -> Self {
+        self.spikes.push(SpikeTrap { position: Position::new(x, y), phase: phase % 2 });
+        self
+    }
+
+    /// Add a laser emitter at (x, y) firing along (dx, dy) for `on_turns` turns out of every
+    /// `on_turns + off_turns`
+    pub fn with_laser_emitter(mut self, x: i32, y: i32, (dx, dy): (i32, i32), on_turns: u32, off_turns: u32) -> Self {
+        self.laser_emitters.push(LaserEmitter { position: Position::new(x, y), direction: (dx, dy), on_turns, off_turns, phase: 0 });
+        self
+    }
+
+    /// Add a spawner at (x, y) whose enemies chase the robot: one every `every` turns, `max` in all
+    pub fn with_spawner(mut self, x: i32, y: i32, every: u32, max: u32) -> Self {
+        self.spawners.push(EnemySpawner { position: Position::new(x, y), every: every.max(1), max, chases: true });
+        self
+    }
+
+    /// Have chasing enemies flank the robot from different sides
+    pub fn with_flanking(mut self, enabled: bool) -> Self {
+        self.flanking = enabled;
+        self
+    }
+
+    /// Stop the run, and count the level as won, once `condition` is met
+    pub fn with_win_condition(mut self, condition: WinCondition) -> Self {
+        self.win_condition = Some(condition);
+        self
+    }
+
+    /// Give the level a real-time limit of `seconds`, with every turn taking `seconds_per_turn`
+    /// of the simulated clock
+    pub fn with_time_limit(mut self, seconds: u32, seconds_per_turn: f32) -> Self {
+        self.real_time_limit_secs = Some(seconds);
+        self.seconds_per_turn = seconds_per_turn;
+        self
+    }
+
+    /// Add a closed door at (x, y)
+    pub fn with_door(mut self, x: i32, y: i32) -> Self {
+        self.doors.push(Position::new(x, y));
+        self
+    }
+
+    /// Give the robot an energy budget, spending `move_cost` per move and the game's default
+    /// cost for every other action
+    pub fn with_energy(mut self, energy: u32, move_cost: u32) -> Self {
+        self.energy = Some(energy);
+        self.move_cost = move_cost;
+        self
+    }
+
+    /// Give the robot a level's energy budget and per-action costs
+    pub fn with_energy_costs(mut self, energy: EnergyConfig) -> Self {
+        self.energy = Some(energy.capacity);
+        self.move_cost = energy.move_cost;
+        self.scan_cost = energy.scan_cost;
+        self.laser_cost = energy.laser_cost;
+        self.emp_cost = energy.emp_cost;
+        self
+    }
+
+    /// The energy budget and what each action costs, or `None` when energy is unlimited
+    pub fn energy_config(&self) -> Option<EnergyConfig> {
+        self.energy.map(|capacity| EnergyConfig {
+            capacity,
+            move_cost: self.move_cost,
+            scan_cost: self.scan_cost,
+            laser_cost: self.laser_cost,
+            emp_cost: self.emp_cost,
+        })
+    }
 }
 
 impl Default for GameConfig {
@@ -65,7 +386,7 @@ impl Default for GameConfig {
 }
 
 /// Position on the game grid
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
@@ -141,6 +462,46 @@ pub struct TestResult {
     pub execution_output: String,
     /// Error message if execution failed
     pub error: Option<String>,
+    /// Seed the run used; testing again with the same seed gives the same `random_int()` values
+    #[serde(default)]
+    pub seed: u64,
+    /// Final state of the grid, used for snapshot testing
+    pub snapshot: StateSnapshot,
+    /// Energy left at the end of the run, if the config set an energy budget
+    #[serde(default)]
+    pub energy_remaining: Option<u32>,
+    /// What each robot call did, in order. Ends early when a call halted the program.
+    #[serde(default)]
+    pub outcomes: Vec<ExecutionOutcome>,
+    /// Whether the config's win condition was met; always false without one
+    #[serde(default)]
+    pub level_complete: bool,
+    /// Each turn's enemy moves, spawns and catches, as the game's combat log lists them
+    #[serde(default)]
+    pub combat_log: Vec<CombatLogEntry>,
+}
+
+impl TestResult {
+    /// Canonical JSON dump of the final grid state.
+    ///
+    /// Field order is fixed and position lists are sorted, so two runs with the
+    /// same seed and code produce byte-identical output.
+    pub fn state_snapshot(&self) -> String {
+        serde_json::to_string_pretty(&self.snapshot)
+            .expect("state snapshot only contains plain data and always serializes")
+    }
+}
+
+/// Serializable view of the game state after execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub seed: u64,
+    pub grid_width: usize,
+    pub grid_height: usize,
+    pub robot_position: Position,
+    pub turns: u32,
+    pub enemies: Vec<Position>,
+    pub items: Vec<Position>,
 }
 
 /// Main test runner for game code
@@ -156,14 +517,19 @@ impl TestRunner {
 
     /// Test the given Rust code and return results
     pub async fn test_code(&self, code: &str) -> Result<TestResult, Box<dyn std::error::Error>> {
+        self.test_code_blocking(code)
+    }
+
+    /// Synchronous version of [`TestRunner::test_code`] for callers without an async runtime
+    pub fn test_code_blocking(&self, code: &str) -> Result<TestResult, Box<dyn std::error::Error>> {
         let mut game_state = GameState::new(&self.config);
         let mut executor = CodeExecutor::new();
 
         // Parse the code into function calls
-        let function_calls = parse_rust_code(code)?;
+        let function_calls = parse_rust_code(code, self.config.seed)?;
         
         // Extract print statements
-        let print_outputs = extract_print_statements(code);
+        let print_outputs = extract_print_statements(code, self.config.seed);
         
         // Process print outputs first
         let mut messages = Vec::new();
@@ -174,64 +540,508 @@ impl TestRunner {
         }
 
         // Execute robot function calls
-        let mut robot_results = Vec::new();
-        for call in function_calls {
-            let result = executor.execute_function(&mut game_state, call);
-            robot_results.push(result.clone());
+        let mut outcomes = Vec::new();
+        let mut halt_error = None;
+        let mut level_complete = false;
+        let energy = self.config.energy_config();
+        let mut energy_remaining = energy.map(|energy| energy.capacity);
+        let started = std::time::Instant::now();
+        for (actions, call) in function_calls.iter().cloned().enumerate() {
+            if let Err(abort) = self.config.budget.check(actions, started.elapsed().as_secs_f64()) {
+                halt_error = Some(abort.to_string());
+                break;
+            }
+
+            // Each action is paid for before it runs; running out stops the program, like in the game
+            let paid = match (energy, energy_remaining.as_mut()) {
+                (Some(energy), Some(remaining)) => energy.spend(remaining, call.function),
+                _ => Ok(()),
+            };
+            if let Err(message) = paid {
+                let outcome = ExecutionOutcome::OutOfEnergy { message };
+                halt_error = Some(format!("{} {}", outcome, outcome.halt_message().unwrap_or_default()));
+                outcomes.push(outcome);
+                break;
+            }
+            let turns_before = game_state.turns;
+            let mut outcome = executor.execute_function(&mut game_state, call);
             
             if self.config.enable_logging {
-                log::info!("Executed function: {}", result);
+                log::info!("Executed function: {}", outcome);
+            }
+
+            // Every turn the action took resolves through game-core's TurnScheduler, exactly like in
+            // the game. The game also checks for a win on every frame, so an action that takes no turn
+            // can still complete the level.
+            let turns_taken = game_state.turns.saturating_sub(turns_before);
+            let mut events = Vec::new();
+            let mut end = TurnEnd::Continue;
+            game_state.turns = turns_before;
+            for _ in 0..turns_taken {
+                game_state.turns += 1;
+                let report = TurnScheduler::resolve_turn(&mut game_state);
+                events.extend(report.events);
+                end = report.end;
+                if matches!(end, TurnEnd::Caught(_)) {
+                    break;
+                }
+            }
+            if turns_taken == 0 && game_state.win_check() {
+                end = TurnEnd::Won;
+            }
+            if !events.is_empty() {
+                let events = events.join(". ");
+                if outcome.is_routine() {
+                    outcome = ExecutionOutcome::Acted { message: events };
+                } else {
+                    outcome.append(&events);
+                }
+            }
+            if let TurnEnd::Caught(message) = end {
+                halt_error = Some(message.clone());
+                outcomes.push(outcome);
+                outcomes.push(ExecutionOutcome::Collision { message });
+                break;
+            }
+
+            // The simulated clock runs out like the game's countdown, resetting the level
+            let elapsed = game_state.turns as f32 * self.config.seconds_per_turn;
+            if let Some(limit) = self.config.real_time_limit_secs.filter(|limit| elapsed >= *limit as f32) {
+                let message = format!("TIME'S UP! The {}s time limit ran out on turn {}", limit, game_state.turns);
+                halt_error = Some(message.clone());
+                outcomes.push(ExecutionOutcome::Error { message });
+                break;
+            }
+
+            // Walls stop the program, like in the game
+            if let Some(halt) = outcome.halt_message() {
+                halt_error = Some(format!("{} {}", outcome, halt));
+                outcomes.push(outcome);
+                break;
+            }
+            outcomes.push(outcome);
+
+            if end == TurnEnd::Won {
+                level_complete = true;
+                break;
             }
         }
 
         // Add robot action messages if any
-        if !robot_results.is_empty() {
-            let meaningful_results: Vec<String> = robot_results
-                .into_iter()
-                .filter(|r| !r.is_empty() && !r.contains("executed"))
-                .collect();
-            
-            if !meaningful_results.is_empty() {
-                messages.push(GameMessage::robot_action(meaningful_results.join("\n")));
-            }
+        let meaningful_results: Vec<String> = outcomes
+            .iter()
+            .filter(|outcome| !outcome.is_routine())
+            .map(|outcome| outcome.to_string())
+            .collect();
+        if !meaningful_results.is_empty() {
+            messages.push(GameMessage::robot_action(meaningful_results.join("\n")));
         }
 
         Ok(TestResult {
-            success: true,
+            success: halt_error.is_none(),
             final_position: game_state.robot_position,
             turns_taken: game_state.turns,
             messages,
             execution_output: format!("{:?}", function_calls),
-            error: None,
+            error: halt_error,
+            seed: self.config.seed,
+            snapshot: game_state.snapshot(),
+            energy_remaining,
+            outcomes,
+            level_complete,
+            combat_log: game_state.combat_log.entries,
         })
     }
 }
 
+/// Result of one solution from a batch run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchTestResult {
+    /// Index of the solution in the input slice
+    pub index: usize,
+    /// Wall-clock time spent testing this solution
+    pub duration_ms: u128,
+    /// Test result, or the error message if the code could not be tested
+    pub result: Result<TestResult, String>,
+}
+
+impl TestRunner {
+    /// Test many solutions concurrently, one worker thread per available CPU.
+    ///
+    /// Results are returned in the same order as `codes`. Every solution gets
+    /// its own game state built from the same config, so seeded runs stay
+    /// reproducible regardless of scheduling.
+    pub fn test_many(&self, codes: &[&str]) -> Vec<BatchTestResult> {
+        let workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(codes.len().max(1));
+        let next = std::sync::atomic::AtomicUsize::new(0);
+        let results = std::sync::Mutex::new(Vec::with_capacity(codes.len()));
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    let Some(code) = codes.get(index) else { break };
+
+                    let start = std::time::Instant::now();
+                    let result = self.test_code_blocking(code).map_err(|e| e.to_string());
+                    let batch_result = BatchTestResult {
+                        index,
+                        duration_ms: start.elapsed().as_millis(),
+                        result,
+                    };
+
+                    if self.config.enable_logging {
+                        log::info!("Solution {} tested in {}ms", index, batch_result.duration_ms);
+                    }
+
+                    results.lock().unwrap_or_else(|e| e.into_inner()).push(batch_result);
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+        results.sort_by_key(|r| r.index);
+        results
+    }
+}
+
 /// Internal game state for testing
 #[derive(Debug)]
 pub struct GameState {
     pub robot_position: Position,
     pub turns: u32,
     pub grid: TestGrid,
+    pub enemies: Vec<Position>,
+    /// Whether each enemy chases the robot (spawned chasers) rather than wandering
+    pub chasing: Vec<bool>,
+    pub items: Vec<Position>,
+    pub seed: u64,
+    pub grid_width: usize,
+    pub grid_height: usize,
+    /// All randomness must come from this generator to keep runs reproducible
+    pub rng: StdRng,
+    pub conveyors: Vec<Conveyor>,
+    pub spikes: Vec<SpikeTrap>,
+    pub spawners: Vec<SpawnerState>,
+    pub flanking: bool,
+    pub combat_log: CombatLog,
+    /// What wins the level, copied from the config
+    pub win_condition: Option<WinCondition>,
 }
 
 impl GameState {
     pub fn new(config: &GameConfig) -> Self {
-        Self {
+        let mut state = Self {
             robot_position: Position::new(config.robot_start_x, config.robot_start_y),
             turns: 0,
-            grid: TestGrid::new(config.grid_width, config.grid_height),
+            grid: TestGrid::new(config.grid_width as i32, config.grid_height as i32),
+            enemies: Vec::new(),
+            chasing: Vec::new(),
+            items: Vec::new(),
+            seed: config.seed,
+            grid_width: config.grid_width,
+            grid_height: config.grid_height,
+            rng: StdRng::seed_from_u64(config.seed),
+            conveyors: config.conveyors.clone(),
+            spikes: config.spikes.clone(),
+            spawners: config.spawners.iter()
+                .map(|spawner| SpawnerState { spawner: *spawner, turns_left: spawner.every.max(1), spawned: 0 })
+                .collect(),
+            flanking: config.flanking,
+            combat_log: CombatLog::default(),
+            win_condition: config.win_condition.clone(),
+        };
+
+        for door in &config.doors {
+            state.grid.doors.insert(Pos { x: door.x, y: door.y });
+        }
+        for wall in &config.walls {
+            state.grid.blockers.insert(Pos { x: wall.x, y: wall.y });
+        }
+        for emitter in &config.laser_emitters {
+            state.grid.emitters.push(game_core::grid::LaserEmitter {
+                pos: Pos { x: emitter.position.x, y: emitter.position.y },
+                direction: emitter.direction,
+                on_turns: emitter.on_turns,
+                off_turns: emitter.off_turns,
+                phase: emitter.phase,
+            });
+        }
+
+        // Enemies are placed before items so the draw order from the RNG never changes
+        for _ in 0..config.enemy_count {
+            if let Some(pos) = state.random_free_position() {
+                state.enemies.push(pos);
+                state.chasing.push(false);
+            }
+        }
+        for _ in 0..config.item_count {
+            if let Some(pos) = state.random_free_position() {
+                state.items.push(pos);
+            }
+        }
+
+        state
+    }
+
+    /// Pick a random empty tile that isn't a wall, the robot, an enemy or an item
+    pub fn random_free_position(&mut self) -> Option<Position> {
+        if self.grid_width == 0 || self.grid_height == 0 {
+            return None;
+        }
+
+        // Bounded attempts so a full grid can't loop forever
+        for _ in 0..(self.grid_width * self.grid_height * 4) {
+            let pos = Position::new(
+                self.rng.gen_range(0..self.grid_width as i32),
+                self.rng.gen_range(0..self.grid_height as i32),
+            );
+            let wall = self.grid.blockers.contains(&Pos { x: pos.x, y: pos.y });
+            if !wall && pos != self.robot_position && !self.enemies.contains(&pos) && !self.items.contains(&pos) {
+                return Some(pos);
+            }
+        }
+        None
+    }
+
+    /// Step every enemy one tile, staying inside the grid and out of walls: chasers along the shortest
+    /// path to the robot (or to their side of it when flanking), the others in a random direction.
+    /// Spawners due this turn then add their enemies.
+    pub fn move_enemies(&mut self) {
+        const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+        let before = self.enemy_positions();
+
+        let robot = Pos { x: self.robot_position.x, y: self.robot_position.y };
+        let chasers: Vec<usize> = (0..self.enemies.len()).filter(|&i| self.chasing[i]).collect();
+        let targets = if self.flanking {
+            let positions: Vec<Pos> = chasers.iter().map(|&i| Pos { x: self.enemies[i].x, y: self.enemies[i].y }).collect();
+            self.grid.flank_targets(robot, &positions)
+        } else {
+            vec![robot; chasers.len()]
+        };
+
+        for i in 0..self.enemies.len() {
+            let current = self.enemies[i];
+            let next = match chasers.iter().position(|&chaser| chaser == i) {
+                Some(k) => {
+                    let from = Pos { x: current.x, y: current.y };
+                    let (path, _) = game_core::movement_patterns::pathfinding::find_path(&self.grid, from, targets[k], CHASE_SEARCH_BUDGET);
+                    path.first().map_or(current, |step| Position::new(step.x, step.y))
+                }
+                None => {
+                    let (dx, dy) = DIRECTIONS[self.rng.gen_range(0..DIRECTIONS.len())];
+                    Position::new(current.x + dx, current.y + dy)
+                }
+            };
+            let in_bounds = next.x >= 0
+                && next.y >= 0
+                && next.x < self.grid_width as i32
+                && next.y < self.grid_height as i32;
+            let wall = self.grid.blockers.contains(&Pos { x: next.x, y: next.y });
+            if in_bounds && !wall && !self.enemies.contains(&next) {
+                self.enemies[i] = next;
+            }
+        }
+        self.tick_spawners();
+        let steps = enemy_steps(&before, &self.enemy_positions());
+        self.combat_log.record(self.turns as usize, steps);
+    }
+
+    fn enemy_positions(&self) -> Vec<Pos> {
+        self.enemies.iter().map(|enemy| Pos { x: enemy.x, y: enemy.y }).collect()
+    }
+
+    fn tick_spawners(&mut self) {
+        for index in 0..self.spawners.len() {
+            let state = &mut self.spawners[index];
+            if state.spawned >= state.spawner.max {
+                continue;
+            }
+            state.turns_left = state.turns_left.saturating_sub(1);
+            let (pos, chases) = (state.spawner.position, state.spawner.chases);
+            let wall = self.grid.is_blocked(Pos { x: pos.x, y: pos.y });
+            if state.turns_left > 0 || wall || pos == self.robot_position || self.enemies.contains(&pos) {
+                continue;
+            }
+            state.spawned += 1;
+            state.turns_left = state.spawner.every;
+            self.enemies.push(pos);
+            self.chasing.push(chases);
+        }
+    }
+
+    /// What happened once a chasing enemy has caught the robot; wandering enemies never do
+    pub fn enemy_collision(&mut self) -> Option<String> {
+        let caught = self.enemies.iter().zip(&self.chasing).any(|(enemy, chasing)| *chasing && *enemy == self.robot_position);
+        if caught {
+            let at = Pos { x: self.robot_position.x, y: self.robot_position.y };
+            self.combat_log.record(self.turns as usize, [CombatEvent::RobotCaught { robot: 0, at }]);
+        }
+        caught.then(|| format!(
+            "CAUGHT! A chasing enemy reached the robot at ({}, {}) on turn {}",
+            self.robot_position.x, self.robot_position.y, self.turns
+        ))
+    }
+
+    /// End-of-turn hazards: a conveyor under the robot shifts it one tile if that tile is inside
+    /// the grid and free of enemies, then live spikes or a laser beam under the robot end the run
+    /// with `Err`.
+    pub fn apply_turn_hazards(&mut self) -> Result<Option<String>, String> {
+        let mut message = None;
+
+        if let Some(conveyor) = self.conveyors.iter().find(|c| c.position == self.robot_position) {
+            let next = Position::new(
+                self.robot_position.x + conveyor.direction.0,
+                self.robot_position.y + conveyor.direction.1,
+            );
+            let in_bounds = next.x >= 0
+                && next.y >= 0
+                && next.x < self.grid_width as i32
+                && next.y < self.grid_height as i32;
+            if in_bounds && !self.enemies.contains(&next) {
+                self.robot_position = next;
+                message = Some(format!("Conveyor moved the robot to ({}, {})", next.x, next.y));
+            }
+        }
+
+        let turn = self.turns;
+        let on_live_spikes = self.spikes.iter().any(|spike| {
+            spike.position == self.robot_position && (turn + spike.phase).is_multiple_of(2)
+        });
+        if on_live_spikes {
+            return Err(format!(
+                "SPIKE TRAP! The spikes at ({}, {}) were up on turn {}",
+                self.robot_position.x, self.robot_position.y, turn
+            ));
+        }
+        let pos = Pos { x: self.robot_position.x, y: self.robot_position.y };
+        if self.grid.laser_beam_at(pos, turn as usize) {
+            return Err(format!("LASER! The robot was caught in a laser beam at ({}, {}) on turn {}", pos.x, pos.y, turn));
+        }
+
+        Ok(message)
+    }
+
+    /// Whether the state meets `condition`, checked the same way as in the game
+    pub fn meets(&self, condition: &WinCondition) -> bool {
+        WinConditionEvaluator::new(condition).is_met(&WinState {
+            robots: &[Pos { x: self.robot_position.x, y: self.robot_position.y }],
+            items_left: self.items.len(),
+            enemies_left: self.enemies.len(),
+            turns: self.turns as usize,
+        })
+    }
+
+    /// Capture the current state with positions in canonical (sorted) order
+    pub fn snapshot(&self) -> StateSnapshot {
+        let mut enemies = self.enemies.clone();
+        enemies.sort();
+        let mut items = self.items.clone();
+        items.sort();
+
+        StateSnapshot {
+            seed: self.seed,
+            grid_width: self.grid_width,
+            grid_height: self.grid_height,
+            robot_position: self.robot_position,
+            turns: self.turns,
+            enemies,
+            items,
         }
     }
 }
 
+/// The runner's phases of a turn, run in order by game-core's TurnScheduler
+impl TurnPhases for GameState {
+    fn hazards(&mut self) -> Result<Option<String>, String> {
+        self.apply_turn_hazards()
+    }
+
+    fn enemies(&mut self) {
+        self.move_enemies();
+    }
+
+    fn collisions(&mut self) -> Option<String> {
+        self.enemy_collision()
+    }
+
+    fn win_check(&mut self) -> bool {
+        self.win_condition.as_ref().is_some_and(|condition| self.meets(condition))
+    }
+}
+
 /// Parse print output into a message
 fn parse_print_output(output: &str) -> Option<GameMessage> {
     if let Some(content) = output.strip_prefix("stdout: ") {
         Some(GameMessage::stdout(content.to_string()))
-    } else if let Some(content) = output.strip_prefix("stderr: ") {
-        Some(GameMessage::stderr(content.to_string()))
     } else {
-        None
+        output
+            .strip_prefix("stderr: ")
+            .map(|content| GameMessage::stderr(content.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn energy_runs_out_mid_program_at_the_action_that_cannot_be_paid() {
+        // A move costs 1 and a scan 2: the second scan can't be paid, so the last move never runs
+        let config = GameConfig::new().with_energy(4, 1);
+        let code = "fn main() {\n    move_bot(\"right\");\n    scan(\"current\");\n    scan(\"current\");\n    move_bot(\"right\");\n}";
+        let result = TestRunner::new(config).test_code_blocking(code).unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.as_deref().is_some_and(|error| error.contains("OUT OF ENERGY")));
+        assert_eq!(result.energy_remaining, Some(1));
+        assert_eq!(result.final_position, Position::new(2, 1));
+        assert!(matches!(result.outcomes.last(), Some(ExecutionOutcome::OutOfEnergy { .. })));
+    }
+
+    fn moves_right(steps: usize) -> String {
+        format!("fn main() {{\n{}}}", "    move_bot(\"right\");\n".repeat(steps))
+    }
+
+    #[test]
+    fn the_same_seed_reproduces_a_run_and_another_seed_changes_it() {
+        let config = GameConfig::new().with_enemies(3).with_random_items(4);
+        let code = "fn main() {\n    move_bot(\"down\");\n    scan(\"current\");\n    move_bot(\"right\");\n}";
+        let run = |seed| TestRunner::new(config.clone().with_seed(seed)).test_code_blocking(code).unwrap();
+
+        let first = run(7);
+        let again = run(7);
+        assert_eq!(serde_json::to_string(&first).unwrap(), serde_json::to_string(&again).unwrap());
+
+        let other = run(8);
+        assert_eq!(other.seed, 8);
+        assert_ne!(first.snapshot, other.snapshot);
+    }
+
+    #[test]
+    fn state_snapshots_round_trip_through_their_json() {
+        let config = GameConfig::new().with_seed(42).with_enemies(2).with_random_items(3);
+        let result = TestRunner::new(config).test_code_blocking(&moves_right(2)).unwrap();
+
+        let parsed: StateSnapshot = serde_json::from_str(&result.state_snapshot()).unwrap();
+        assert_eq!(parsed, result.snapshot);
+        assert_eq!(parsed.robot_position, result.final_position);
+    }
+
+    #[test]
+    fn batch_results_come_back_in_input_order() {
+        let codes: Vec<String> = (0..12).map(|i| moves_right(i % 4)).collect();
+        let codes: Vec<&str> = codes.iter().map(String::as_str).collect();
+        let results = TestRunner::new(GameConfig::new()).test_many(&codes);
+
+        assert_eq!(results.len(), codes.len());
+        for (i, batch) in results.iter().enumerate() {
+            assert_eq!(batch.index, i);
+            let result = batch.result.as_ref().unwrap();
+            assert_eq!(result.final_position, Position::new(1 + (i % 4) as i32, 1), "solution {}", i);
+        }
     }
 }
@@ -0,0 +1,18 @@
+//! Robot program parsing. The runner uses the game's own interpreter so both always agree on
+//! which calls a program makes.
+
+pub use game_core::interpreter::{ExecutionOutcome, FunctionCall, RustFunction};
+
+/// Parse the robot function calls made from `main()`, with `random_int()` drawing from `seed`
+pub fn parse_rust_code(code: &str, seed: u64) -> Result<Vec<FunctionCall>, Box<dyn std::error::Error>> {
+    if !code.contains("fn main(") {
+        return Err("No `fn main()` found in the robot program".into());
+    }
+    Ok(game_core::interpreter::parse_rust_code_from_main(code, seed))
+}
+
+/// Output of the `println!`, `eprintln!` and `panic!` calls in `main()`, as
+/// `"stdout: ..."`, `"stderr: ..."` and `"panic: ..."` lines
+pub fn extract_print_statements(code: &str, seed: u64) -> Vec<String> {
+    game_core::interpreter::extract_print_statements_from_main(code, seed)
+}
@@ -0,0 +1,3 @@
+//! Robot model shared with the game
+
+pub use game_core::robot::Robot;
@@ -591,6 +591,169 @@ pub fn laser_tile(x: i32, y: i32) -> bool {
 pub fn open_door() -> bool {
     unimplemented!()
 }
+
+/// Teleport to an explored tile (needs a teleport beacon, costs 3 turns)
+pub fn teleport(x: i32, y: i32) -> bool {
+    unimplemented!()
+}
+
+/// Energy left on levels with an energy budget
+pub fn remaining_energy() -> u32 {
+    unimplemented!()
+}
+
+/// Random whole number from min to max (both included), drawn from the level's seed
+pub fn random_int(min: i32, max: i32) -> i32 {
+    unimplemented!()
+}
+
+/// Move one tile in the direction the robot is facing
+pub fn move_forward() -> bool {
+    unimplemented!()
+}
+
+/// Turn the robot 90 degrees counter-clockwise
+pub fn rotate_left() -> bool {
+    unimplemented!()
+}
+
+/// Turn the robot 90 degrees clockwise
+pub fn rotate_right() -> bool {
+    unimplemented!()
+}
+
+/// Print the explored part of the map as ASCII
+pub fn print_map() {
+    unimplemented!()
+}
+
+/// Undo the last turns (needs a rewind item)
+pub fn rewind(turns: usize) -> bool {
+    unimplemented!()
+}
+
+/// Stun the enemies within `radius` steps of the robot (needs an EMP emitter)
+pub fn emp(radius: u32) -> bool {
+    unimplemented!()
+}
+
+/// Turn grabbing items on every move on or off
+pub fn set_auto_grab(enabled: bool) -> bool {
+    unimplemented!()
+}
+
+/// Show `text` in a speech bubble over the robot and in the message log
+pub fn say(text: &str) -> bool {
+    unimplemented!()
+}
+
+/// Handle returned by robot(index) on multi-robot levels
+pub struct RobotHandle;
+
+/// Object-style robot API: robot.move_up() controls the current robot, robot(1).move_up() robot 1
+pub trait RobotApi {
+    fn move_bot(&self, direction: &str) -> bool {
+        unimplemented!()
+    }
+
+    fn move_up(&self) -> bool {
+        unimplemented!()
+    }
+
+    fn move_down(&self) -> bool {
+        unimplemented!()
+    }
+
+    fn move_left(&self) -> bool {
+        unimplemented!()
+    }
+
+    fn move_right(&self) -> bool {
+        unimplemented!()
+    }
+
+    fn scan(&self, direction: &str) -> String {
+        unimplemented!()
+    }
+
+    fn scan_up(&self) -> String {
+        unimplemented!()
+    }
+
+    fn scan_down(&self) -> String {
+        unimplemented!()
+    }
+
+    fn scan_left(&self) -> String {
+        unimplemented!()
+    }
+
+    fn scan_right(&self) -> String {
+        unimplemented!()
+    }
+
+    fn scan_current(&self) -> String {
+        unimplemented!()
+    }
+
+    fn grab(&self) -> bool {
+        unimplemented!()
+    }
+
+    fn open_door(&self, open: bool) -> bool {
+        unimplemented!()
+    }
+
+    fn teleport(&self, x: i32, y: i32) -> bool {
+        unimplemented!()
+    }
+
+    fn move_forward(&self) -> bool {
+        unimplemented!()
+    }
+
+    fn rotate_left(&self) -> bool {
+        unimplemented!()
+    }
+
+    fn rotate_right(&self) -> bool {
+        unimplemented!()
+    }
+
+    fn print_map(&self) {
+        unimplemented!()
+    }
+
+    fn rewind(&self, turns: usize) -> bool {
+        unimplemented!()
+    }
+
+    fn emp(&self, radius: u32) -> bool {
+        unimplemented!()
+    }
+
+    fn set_auto_grab(&self, enabled: bool) -> bool {
+        unimplemented!()
+    }
+
+    fn say(&self, text: &str) -> bool {
+        unimplemented!()
+    }
+}
+
+impl RobotApi for RobotHandle {}
+
+impl<F: Fn(usize) -> RobotHandle> RobotApi for F {}
+
+/// Control one robot for a single call: robot(1).move_bot("up")
+pub fn robot(index: usize) -> RobotHandle {
+    unimplemented!()
+}
+
+/// Choose which robot the following commands control
+pub fn select_robot(index: usize) -> bool {
+    unimplemented!()
+}
 "#)?;
         }
 
@@ -0,0 +1,125 @@
+// Periodic snapshots of the editor buffer, so a crash never costs more than a few seconds of typing.
+//
+// Snapshots are plain .rs files in the cache directory, named after the time they were taken.
+// Only the newest MAX_SNAPSHOTS are kept. Whether the last session ended cleanly is tracked by
+// crash_protection; after an unclean shutdown the game offers to restore the newest snapshot.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::text_buffer::TextBuffer;
+
+pub const AUTOSAVE_INTERVAL_SECS: f64 = 30.0;
+pub const MAX_SNAPSHOTS: usize = 10;
+
+const SNAPSHOT_PREFIX: &str = "robot_code-";
+const SNAPSHOT_EXTENSION: &str = "rs";
+
+/// Directory the snapshots are written to
+pub fn autosave_dir() -> PathBuf {
+    crate::cache::cache_dir().join("autosave")
+}
+
+#[derive(Debug)]
+pub struct Autosave {
+    dir: PathBuf,
+    last_saved_code: Option<String>,
+    last_save_time: f64,
+}
+
+impl Autosave {
+    pub fn new() -> Self {
+        Self::in_dir(autosave_dir())
+    }
+
+    pub fn in_dir(dir: PathBuf) -> Self {
+        Self { dir, last_saved_code: None, last_save_time: 0.0 }
+    }
+
+    /// Called every frame. Takes a snapshot once the interval has passed, if the code changed.
+    pub fn update(&mut self, code: &TextBuffer, now: f64) {
+        if self.last_saved_code.is_none() {
+            // Whatever was loaded at startup is already on disk
+            self.last_saved_code = Some(code.to_string());
+            self.last_save_time = now;
+            return;
+        }
+        if now - self.last_save_time < AUTOSAVE_INTERVAL_SECS {
+            return;
+        }
+        self.last_save_time = now;
+        if let Err(e) = self.snapshot(code) {
+            log::warn!("Autosave failed: {}", e);
+        }
+    }
+
+    /// Write a snapshot right away (unless the code hasn't changed since the last one) and
+    /// drop the oldest snapshots past MAX_SNAPSHOTS
+    pub fn snapshot(&mut self, code: &str) -> Result<(), String> {
+        if self.last_saved_code.as_deref() == Some(code) {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.dir).map_err(|e| format!("Failed to create {}: {}", self.dir.display(), e))?;
+
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let path = self.dir.join(format!("{}{}.{}", SNAPSHOT_PREFIX, millis, SNAPSHOT_EXTENSION));
+        fs::write(&path, code).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        self.last_saved_code = Some(code.to_string());
+
+        let snapshots = self.snapshots();
+        let excess = snapshots.len().saturating_sub(MAX_SNAPSHOTS);
+        for old in &snapshots[..excess] {
+            let _ = fs::remove_file(old);
+        }
+        Ok(())
+    }
+
+    /// Snapshot files, oldest first
+    pub fn snapshots(&self) -> Vec<PathBuf> {
+        let mut snapshots: Vec<(u128, PathBuf)> = fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter_map(|path| snapshot_time(&path).map(|time| (time, path)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        snapshots.sort();
+        snapshots.into_iter().map(|(_, path)| path).collect()
+    }
+
+    /// Contents of the newest snapshot
+    pub fn newest_snapshot(&self) -> Option<String> {
+        self.snapshots().last().and_then(|path| fs::read_to_string(path).ok())
+    }
+}
+
+fn snapshot_time(path: &Path) -> Option<u128> {
+    if path.extension()? != SNAPSHOT_EXTENSION {
+        return None;
+    }
+    path.file_stem()?.to_str()?.strip_prefix(SNAPSHOT_PREFIX)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_the_newest_snapshots() {
+        let dir = std::env::temp_dir().join(format!("autosave-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut autosave = Autosave::in_dir(dir.clone());
+
+        for i in 0..MAX_SNAPSHOTS + 3 {
+            autosave.snapshot(&format!("move_bot(\"right\"); // {}", i)).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        // Unchanged code doesn't add a snapshot
+        autosave.snapshot(&format!("move_bot(\"right\"); // {}", MAX_SNAPSHOTS + 2)).unwrap();
+
+        assert_eq!(autosave.snapshots().len(), MAX_SNAPSHOTS);
+        assert_eq!(autosave.newest_snapshot().as_deref(), Some(format!("move_bot(\"right\"); // {}", MAX_SNAPSHOTS + 2).as_str()));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
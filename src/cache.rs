@@ -1,7 +1,15 @@
+// Game cache: compiled levels, font metrics, startup data and the last window settings, kept in
+// rust_game_cache.json between sessions. Levels, font metrics and assets share a size budget and
+// the least recently used of them are evicted once it's exceeded. A cache file that doesn't parse,
+// or entries in it that don't check out, are thrown away and regenerated rather than trusted.
+// The file itself is written and recovered through save_file.
+
 use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
 use serde::{Serialize, Deserialize};
 use crate::level::LevelSpec;
+use crate::save_file::LoadError;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct GameCache {
@@ -11,6 +19,12 @@ pub struct GameCache {
     pub precompiled_assets: HashMap<String, CachedAsset>,
     pub game_settings: Option<CachedGameSettings>,
     pub startup_data: Option<StartupData>,
+    #[serde(default)]
+    pub access_clock: u64, // Ticks on every lookup and insert; entries remember the tick they were last used at
+    #[serde(default)]
+    pub evictions: u64, // Entries evicted since the cache was last cleared
+    #[serde(skip)]
+    pub load_problem: Option<String>, // What was wrong with the cache file this session started from
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -18,6 +32,8 @@ pub struct CachedLevel {
     pub spec: LevelSpec,
     pub checksum: String,
     pub compiled_at: u64, // Unix timestamp
+    #[serde(default)]
+    pub last_used: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -26,6 +42,8 @@ pub struct FontMetrics {
     pub char_width: f32,
     pub line_height: f32,
     pub cached_at: u64,
+    #[serde(default)]
+    pub last_used: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -34,6 +52,8 @@ pub struct CachedAsset {
     pub data: Vec<u8>,
     pub checksum: String,
     pub cached_at: u64,
+    #[serde(default)]
+    pub last_used: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -55,43 +75,122 @@ pub struct StartupData {
     pub cached_at: u64,
 }
 
-const CACHE_VERSION: u32 = 2;
+const CACHE_VERSION: u32 = 3;
 const CACHE_FILE: &str = "rust_game_cache.json";
+const CACHE_DIR: &str = "rust_game_cache";
+
+// Serialized size levels, font metrics and assets may take up together before eviction starts
+pub const MAX_CACHE_BYTES: usize = 2 * 1024 * 1024;
+
+/// Which map of the cache an entry lives in
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EntryKind {
+    Level,
+    FontMetrics,
+    Asset,
+}
+
+/// Entry counts and sizes for the cache page of the settings
+#[derive(Clone, Debug, Default)]
+pub struct CacheStats {
+    pub levels: (usize, usize), // Entries and bytes
+    pub font_metrics: (usize, usize),
+    pub assets: (usize, usize),
+    pub max_bytes: usize,
+    pub file_bytes: Option<u64>, // Size of rust_game_cache.json, None before it's written
+    pub evictions: u64,
+    pub load_problem: Option<String>,
+}
+
+impl CacheStats {
+    pub fn total_bytes(&self) -> usize {
+        self.levels.1 + self.font_metrics.1 + self.assets.1
+    }
+}
+
+/// 1536 -> "1.5 KB"
+pub fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn serialized_size<T: Serialize>(value: &T) -> usize {
+    serde_json::to_vec(value).map(|bytes| bytes.len()).unwrap_or(0)
+}
+
+/// Directory for caches that don't fit in the cache file, like the syntax checker's build output
+pub fn cache_dir() -> PathBuf {
+    PathBuf::from(CACHE_DIR)
+}
 
 impl GameCache {
     pub fn load() -> Self {
-        if let Ok(cache_data) = fs::read_to_string(CACHE_FILE) {
-            if let Ok(mut cache) = serde_json::from_str::<GameCache>(&cache_data) {
-                // Check cache version compatibility
-                if cache.cache_version == CACHE_VERSION {
-                    log::info!("Loaded game cache with {} compiled levels", cache.compiled_levels.len());
-                    return cache;
-                } else {
-                    log::warn!("Cache version mismatch, clearing cache");
-                    cache.clear();
-                }
-            } else {
-                log::warn!("Failed to parse cache file, starting fresh");
+        let fresh = || Self { cache_version: CACHE_VERSION, ..Default::default() };
+        // save_file restores the last good copy of a damaged file; this is when there was none
+        let mut cache = match crate::save_file::load_json::<GameCache>(Path::new(CACHE_FILE), CACHE_VERSION) {
+            Ok(Some(cache)) => cache,
+            Ok(None) => {
+                log::info!("No cache file found, starting fresh");
+                return fresh();
             }
-        } else {
-            log::info!("No cache file found, starting fresh");
+            // A cache from an older build is stale, not damaged: start over and let the next save replace it
+            Err(LoadError::Outdated(problem)) => {
+                log::info!("{}; starting fresh", problem);
+                return fresh();
+            }
+            Err(LoadError::Damaged(problem)) => return Self::regenerate(fresh(), format!("{}; the cache has been rebuilt and the old file is {}.corrupt", problem, CACHE_FILE)),
+        };
+        if cache.cache_version != CACHE_VERSION {
+            log::warn!("Cache version mismatch, clearing cache");
+            return fresh();
         }
-        
-        Self {
-            cache_version: CACHE_VERSION,
-            ..Default::default()
+
+        let dropped = cache.drop_invalid_entries();
+        if dropped > 0 {
+            return Self::regenerate(cache, format!("Dropped {} damaged entries from {}", dropped, CACHE_FILE));
         }
+        cache.evict_to_fit();
+        log::info!("Loaded game cache with {} compiled levels", cache.compiled_levels.len());
+        cache
     }
-    
+
+    /// Write `cache` over a cache file that couldn't be used as it was
+    fn regenerate(mut cache: Self, problem: String) -> Self {
+        log::warn!("{}", problem);
+        cache.load_problem = Some(problem);
+        cache.save();
+        cache
+    }
+
+    /// Remove entries that can't have come from the game, and return how many there were
+    fn drop_invalid_entries(&mut self) -> usize {
+        let before = self.compiled_levels.len() + self.font_metrics.len() + self.precompiled_assets.len();
+        self.compiled_levels.retain(|_, level| {
+            let spec = &level.spec;
+            spec.width > 0 && spec.height > 0 && spec.start.0 < spec.width && spec.start.1 < spec.height
+        });
+        self.font_metrics.retain(|_, metrics| {
+            [metrics.font_size, metrics.char_width, metrics.line_height].iter().all(|value| value.is_finite() && *value > 0.0)
+        });
+        self.precompiled_assets.retain(|_, asset| asset.checksum == Self::calculate_checksum(&String::from_utf8_lossy(&asset.data)));
+        before - (self.compiled_levels.len() + self.font_metrics.len() + self.precompiled_assets.len())
+    }
+
     pub fn save(&self) {
-        if let Ok(cache_data) = serde_json::to_string_pretty(self) {
-            if let Err(e) = fs::write(CACHE_FILE, cache_data) {
-                log::error!("Failed to save cache: {}", e);
-            } else {
-                log::debug!("Cache saved successfully");
-            }
+        if let Err(e) = crate::save_file::save_json(Path::new(CACHE_FILE), CACHE_VERSION, self) {
+            log::error!("Failed to save cache: {}", e);
         } else {
-            log::error!("Failed to serialize cache");
+            log::debug!("Cache saved successfully");
         }
     }
     
@@ -101,34 +200,89 @@ impl GameCache {
         self.precompiled_assets.clear();
         self.game_settings = None;
         self.startup_data = None;
+        self.evictions = 0;
+        self.load_problem = None;
         log::info!("Cache cleared");
     }
+
+    /// Entry counts, sizes and what happened to the cache file, for the settings
+    pub fn stats(&self) -> CacheStats {
+        let sized = |entries: Vec<usize>| (entries.len(), entries.iter().sum());
+        CacheStats {
+            levels: sized(self.compiled_levels.values().map(serialized_size).collect()),
+            font_metrics: sized(self.font_metrics.values().map(serialized_size).collect()),
+            assets: sized(self.precompiled_assets.values().map(serialized_size).collect()),
+            max_bytes: MAX_CACHE_BYTES,
+            file_bytes: fs::metadata(Path::new(CACHE_FILE)).ok().map(|metadata| metadata.len()),
+            evictions: self.evictions,
+            load_problem: self.load_problem.clone(),
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.access_clock += 1;
+        self.access_clock
+    }
+
+    /// Evict the least recently used levels, font metrics and assets until they fit MAX_CACHE_BYTES
+    fn evict_to_fit(&mut self) {
+        let mut entries: Vec<(u64, EntryKind, String, usize)> = Vec::new();
+        entries.extend(self.compiled_levels.iter().map(|(key, e)| (e.last_used, EntryKind::Level, key.clone(), serialized_size(e))));
+        entries.extend(self.font_metrics.iter().map(|(key, e)| (e.last_used, EntryKind::FontMetrics, key.clone(), serialized_size(e))));
+        entries.extend(self.precompiled_assets.iter().map(|(key, e)| (e.last_used, EntryKind::Asset, key.clone(), serialized_size(e))));
+        let mut total: usize = entries.iter().map(|(_, _, _, size)| size).sum();
+        if total <= MAX_CACHE_BYTES {
+            return;
+        }
+
+        entries.sort_by_key(|(last_used, _, _, _)| *last_used);
+        for (_, kind, key, size) in entries {
+            if total <= MAX_CACHE_BYTES {
+                break;
+            }
+            match kind {
+                EntryKind::Level => self.compiled_levels.remove(&key).map(|_| ()),
+                EntryKind::FontMetrics => self.font_metrics.remove(&key).map(|_| ()),
+                EntryKind::Asset => self.precompiled_assets.remove(&key).map(|_| ()),
+            };
+            total -= size;
+            self.evictions += 1;
+            log::debug!("Evicted {:?} {} from the cache", kind, key);
+        }
+    }
     
-    pub fn get_cached_level(&self, key: &str) -> Option<&CachedLevel> {
-        self.compiled_levels.get(key)
+    pub fn get_cached_level(&mut self, key: &str) -> Option<&CachedLevel> {
+        let tick = self.tick();
+        let level = self.compiled_levels.get_mut(key)?;
+        level.last_used = tick;
+        Some(level)
     }
     
     pub fn cache_level(&mut self, key: String, level_spec: LevelSpec, checksum: String) {
         let cached_level = CachedLevel {
             spec: level_spec,
             checksum,
-            compiled_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs(),
+            compiled_at: now_secs(),
+            last_used: self.tick(),
         };
         
         log::debug!("Cached level: {}", key);
         self.compiled_levels.insert(key, cached_level);
+        self.evict_to_fit();
     }
     
-    pub fn cache_font_metrics(&mut self, key: String, metrics: FontMetrics) {
+    pub fn cache_font_metrics(&mut self, key: String, mut metrics: FontMetrics) {
         log::debug!("Cached font metrics: {}", key);
+        metrics.last_used = self.tick();
         self.font_metrics.insert(key, metrics);
+        self.evict_to_fit();
     }
     
-    pub fn get_font_metrics(&self, key: &str) -> Option<&FontMetrics> {
-        self.font_metrics.get(key)
+    pub fn get_font_metrics(&mut self, key: &str) -> Option<&FontMetrics> {
+        let tick = self.tick();
+        let metrics = self.font_metrics.get_mut(key)?;
+        metrics.last_used = tick;
+        Some(metrics)
     }
     
     // Calculate a simple checksum for level content
@@ -143,12 +297,8 @@ impl GameCache {
     
     // Check if cache entry is still valid (not too old)
     pub fn is_cache_fresh(&self, cached_level: &CachedLevel, max_age_seconds: u64) -> bool {
-        let current_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-        
-        current_time - cached_level.compiled_at < max_age_seconds
+        // A timestamp from the future (a changed clock, a hand-edited file) counts as fresh, not an underflow
+        now_secs().saturating_sub(cached_level.compiled_at) < max_age_seconds
     }
 
     // Asset caching methods
@@ -158,18 +308,20 @@ impl GameCache {
             asset_type,
             data,
             checksum,
-            cached_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs(),
+            cached_at: now_secs(),
+            last_used: self.tick(),
         };
         
         log::debug!("Cached asset: {}", key);
         self.precompiled_assets.insert(key, cached_asset);
+        self.evict_to_fit();
     }
 
-    pub fn get_cached_asset(&self, key: &str) -> Option<&CachedAsset> {
-        self.precompiled_assets.get(key)
+    pub fn get_cached_asset(&mut self, key: &str) -> Option<&CachedAsset> {
+        let tick = self.tick();
+        let asset = self.precompiled_assets.get_mut(key)?;
+        asset.last_used = tick;
+        Some(asset)
     }
 
     // Game settings caching
@@ -202,12 +354,7 @@ impl GameCache {
     // Check if cached data is still valid
     pub fn is_startup_data_fresh(&self, max_age_seconds: u64) -> bool {
         if let Some(data) = &self.startup_data {
-            let current_time = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs();
-            
-            current_time - data.cached_at < max_age_seconds
+            now_secs().saturating_sub(data.cached_at) < max_age_seconds
         } else {
             false
         }
@@ -228,7 +375,7 @@ impl GameCache {
         // Cache commonly used text patterns for faster parsing
         let common_patterns = [
             ("rust_keywords", "fn main use let mut if else for while loop match"),
-            ("game_commands", "move_bot grab scan laser::direction laser::tile open_door"),
+            ("game_commands", "move_bot grab scan laser::direction laser::tile open_door teleport remaining_energy random_int select_robot robot move_forward rotate_left rotate_right print_map rewind emp set_auto_grab say"),
             ("print_patterns", "println! eprintln! panic! format!"),
         ];
 
@@ -237,4 +384,46 @@ impl GameCache {
             self.cache_asset(key.to_string(), "text_pattern".to_string(), pattern_data);
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(font_size: f32) -> FontMetrics {
+        FontMetrics { font_size, char_width: 8.0, line_height: 18.0, cached_at: 0, last_used: 0 }
+    }
+
+    #[test]
+    fn the_least_recently_used_entry_is_evicted_first() {
+        let mut cache = GameCache::default();
+        // Zeros serialize as "0," so each of these takes up about 1 MB of the 2 MB budget
+        let asset = || vec![0u8; 500_000];
+        cache.cache_asset("oldest".to_string(), "blob".to_string(), asset());
+        cache.cache_asset("touched".to_string(), "blob".to_string(), asset());
+        cache.cache_font_metrics("font".to_string(), metrics(16.0));
+        assert!(cache.get_cached_asset("oldest").is_some());
+        assert_eq!(cache.evictions, 0);
+
+        cache.cache_asset("newest".to_string(), "blob".to_string(), asset());
+        assert!(cache.get_cached_asset("touched").is_none());
+        assert!(cache.get_cached_asset("oldest").is_some());
+        assert!(cache.get_cached_asset("newest").is_some());
+        assert!(cache.get_font_metrics("font").is_some());
+        assert_eq!(cache.evictions, 1);
+        assert!(cache.stats().total_bytes() <= MAX_CACHE_BYTES);
+    }
+
+    #[test]
+    fn entries_that_cant_have_come_from_the_game_are_dropped() {
+        let mut cache = GameCache::default();
+        cache.cache_font_metrics("good".to_string(), metrics(16.0));
+        cache.cache_font_metrics("nan".to_string(), metrics(f32::NAN));
+        cache.cache_asset("intact".to_string(), "text_pattern".to_string(), b"fn main".to_vec());
+        cache.cache_asset("edited".to_string(), "text_pattern".to_string(), b"fn main".to_vec());
+        cache.precompiled_assets.get_mut("edited").unwrap().data = b"fn evil".to_vec();
+
+        assert_eq!(cache.drop_invalid_entries(), 2);
+        assert!(cache.font_metrics.contains_key("good") && cache.precompiled_assets.contains_key("intact"));
+        assert!(!cache.font_metrics.contains_key("nan") && !cache.precompiled_assets.contains_key("edited"));
+    }
+}
@@ -0,0 +1,200 @@
+// Optional campaign graph loaded from campaign.yaml
+// When present it replaces the flat level list: each node points at a level and lists the
+// nodes that must be completed before it unlocks. Optional side levels never block progress.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+pub const CAMPAIGN_FILE: &str = "campaign.yaml";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CampaignNode {
+    pub id: String,
+    pub level: usize, // Index into the game's level list (0-indexed)
+    pub title: Option<String>,
+    #[serde(default)]
+    pub requires: Vec<String>, // Node ids that must all be completed first
+    #[serde(default)]
+    pub optional: bool, // Side level: shown on the map but never required
+    pub position: Option<(f32, f32)>, // Map position in 0.0 - 1.0 screen space
+}
+
+impl CampaignNode {
+    pub fn display_name(&self) -> String {
+        self.title.clone().unwrap_or_else(|| format!("Level {}", self.level + 1))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Campaign {
+    pub name: String,
+    pub nodes: Vec<CampaignNode>,
+    #[serde(skip)]
+    depths: HashMap<String, usize>, // Prerequisite depth of each node, worked out once on load
+}
+
+impl Campaign {
+    /// Load the campaign from campaign.yaml if the file exists
+    pub fn load_default() -> Option<Self> {
+        if !Path::new(CAMPAIGN_FILE).exists() {
+            return None;
+        }
+        match Self::load(Path::new(CAMPAIGN_FILE)) {
+            Ok(campaign) => Some(campaign),
+            Err(e) => {
+                log::warn!("Ignoring {}: {}", CAMPAIGN_FILE, e);
+                None
+            }
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_yaml(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    pub fn from_yaml(content: &str) -> Result<Self, String> {
+        let mut campaign: Campaign = serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+        campaign.validate()?;
+        campaign.depths = campaign.prerequisite_depths()?;
+        Ok(campaign)
+    }
+
+    /// Reject duplicate ids, unknown prerequisites and prerequisite cycles
+    pub fn validate(&self) -> Result<(), String> {
+        let mut ids = HashSet::new();
+        for node in &self.nodes {
+            if !ids.insert(node.id.as_str()) {
+                return Err(format!("Duplicate campaign node '{}'", node.id));
+            }
+        }
+        for node in &self.nodes {
+            for required in &node.requires {
+                if !ids.contains(required.as_str()) {
+                    return Err(format!("Node '{}' requires unknown node '{}'", node.id, required));
+                }
+            }
+        }
+
+        self.prerequisite_depths().map(|_| ())
+    }
+
+    /// How many prerequisites deep each node is. Nodes are resolved once all their prerequisites
+    /// are, so each depth is worked out once; anything left over is part of a cycle.
+    fn prerequisite_depths(&self) -> Result<HashMap<String, usize>, String> {
+        let mut depths: HashMap<String, usize> = HashMap::new();
+        loop {
+            let before = depths.len();
+            for node in &self.nodes {
+                if depths.contains_key(&node.id) {
+                    continue;
+                }
+                let required: Option<Vec<usize>> = node.requires.iter().map(|r| depths.get(r).copied()).collect();
+                if let Some(required) = required {
+                    depths.insert(node.id.clone(), required.iter().map(|depth| depth + 1).max().unwrap_or(0));
+                }
+            }
+            if depths.len() == before {
+                break;
+            }
+        }
+        match self.nodes.iter().find(|n| !depths.contains_key(&n.id)) {
+            Some(node) => Err(format!("Node '{}' is part of a prerequisite cycle", node.id)),
+            None => Ok(depths),
+        }
+    }
+
+    pub fn node(&self, id: &str) -> Option<&CampaignNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    /// Ids of the nodes that play the given level
+    pub fn nodes_for_level(&self, level: usize) -> Vec<&str> {
+        self.nodes.iter().filter(|n| n.level == level).map(|n| n.id.as_str()).collect()
+    }
+
+    /// A node is unlocked once every prerequisite is completed (root nodes always are)
+    pub fn is_unlocked(&self, id: &str, completed: &[String]) -> bool {
+        self.node(id).is_some_and(|node| {
+            node.requires.iter().all(|r| completed.iter().any(|c| c == r))
+        })
+    }
+
+    /// Mark every node for `level` completed and return the ids of newly unlocked nodes
+    pub fn complete_level(&self, level: usize, completed: &mut Vec<String>, unlocked: &mut Vec<String>) -> Vec<String> {
+        for id in self.nodes_for_level(level) {
+            if !completed.iter().any(|c| c == id) {
+                completed.push(id.to_string());
+            }
+        }
+        self.unlock_available(completed, unlocked)
+    }
+
+    /// Whether every node that isn't a side level is completed
+    pub fn is_finished(&self, completed: &[String]) -> bool {
+        self.nodes.iter().filter(|n| !n.optional).all(|n| completed.contains(&n.id))
+    }
+
+    /// Add every node whose prerequisites are met to `unlocked`, returning the new ones
+    pub fn unlock_available(&self, completed: &[String], unlocked: &mut Vec<String>) -> Vec<String> {
+        let mut newly_unlocked = Vec::new();
+        for node in &self.nodes {
+            if self.is_unlocked(&node.id, completed) && !unlocked.contains(&node.id) {
+                unlocked.push(node.id.clone());
+                newly_unlocked.push(node.id.clone());
+            }
+        }
+        newly_unlocked
+    }
+
+    /// Map position of a node, falling back to a column per prerequisite depth
+    pub fn layout_position(&self, id: &str) -> (f32, f32) {
+        let Some(node) = self.node(id) else { return (0.5, 0.5) };
+        if let Some(position) = node.position {
+            return position;
+        }
+
+        let depth = self.depth(id);
+        let max_depth = self.nodes.iter().map(|n| self.depth(&n.id)).max().unwrap_or(0);
+        let column: Vec<&CampaignNode> = self.nodes.iter().filter(|n| self.depth(&n.id) == depth).collect();
+        let row = column.iter().position(|n| n.id == id).unwrap_or(0);
+
+        let x = 0.1 + 0.8 * (depth as f32 + 0.5) / (max_depth as f32 + 1.0);
+        let y = 0.25 + 0.6 * (row as f32 + 0.5) / column.len() as f32;
+        (x, y)
+    }
+
+    fn depth(&self, id: &str) -> usize {
+        self.depths.get(id).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depths_are_worked_out_once_for_shared_prerequisites() {
+        // 40 layers of two nodes that each need both nodes of the layer before: walking every
+        // path to the root would take 2^40 steps
+        let mut yaml = String::from("name: Ladder\nnodes:\n- {id: root, level: 0}\n");
+        for layer in 1..=40 {
+            let requires = if layer == 1 { "[root]".to_string() } else { format!("[a{0}, b{0}]", layer - 1) };
+            for side in ["a", "b"] {
+                yaml.push_str(&format!("- {{id: {}{}, level: {}, requires: {}}}\n", side, layer, layer, requires));
+            }
+        }
+        let campaign = Campaign::from_yaml(&yaml).unwrap();
+        assert_eq!(campaign.depth("root"), 0);
+        assert_eq!(campaign.depth("b40"), 40);
+        let (a, b) = (campaign.layout_position("a40"), campaign.layout_position("b40"));
+        assert_eq!(a.0, b.0);
+        assert!(a.1 < b.1);
+
+        let cycle = "name: Loop\nnodes:\n- {id: x, level: 0, requires: [y]}\n- {id: y, level: 1, requires: [x]}\n";
+        assert!(Campaign::from_yaml(cycle).unwrap_err().contains("cycle"));
+    }
+}
@@ -0,0 +1,366 @@
+// Command line interface for the game and its headless tools
+// Subcommands are the preferred interface; the old flat flags (--test-code, --debug, ...)
+// are still accepted and mapped onto the matching subcommand.
+
+use clap::{Parser, Subcommand};
+use log::{info, warn};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "robo_grid_explorer_gui",
+    version,
+    about = "Rust Steam Game - learn Rust by programming a robot on a grid",
+    after_help = "Running without a subcommand starts the game (same as `play`)."
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Log filter, e.g. `debug` or `info,keys=debug,exec=debug` (defaults to RUST_LOG, then info)
+    #[arg(long = "log", global = true, value_name = "SPEC")]
+    pub log: Option<String>,
+
+    /// Same as --log debug
+    #[arg(long, global = true, hide = true)]
+    pub all_logs: bool,
+
+    // Legacy flags kept for backwards compatibility, hidden from --help
+    #[arg(long, hide = true, value_name = "FILE")]
+    test_code: Option<String>,
+    #[arg(long, hide = true)]
+    debug: bool,
+    #[arg(long, hide = true)]
+    editor_test: bool,
+    #[arg(long, hide = true)]
+    command_test: bool,
+    #[arg(long, hide = true)]
+    test_learning_levels: bool,
+    #[arg(long, hide = true)]
+    test_error_system: bool,
+    #[arg(long, hide = true, value_name = "CODE")]
+    check_code: Option<String>,
+    #[arg(long, hide = true, value_name = "N")]
+    start_at_level: Option<usize>,
+    #[arg(long, hide = true, value_name = "N")]
+    start_level: Option<usize>,
+    #[arg(long, hide = true, value_name = "N")]
+    max_levels: Option<usize>,
+    #[arg(long, hide = true, value_parser = ["junit", "json", "tap"])]
+    report_format: Option<String>,
+    #[arg(long, hide = true, value_name = "PATH")]
+    report_out: Option<String>,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Start the game (default)
+    Play {
+        /// Start directly at level N (0-indexed), skipping the menu
+        #[arg(long, value_name = "N")]
+        start_at_level: Option<usize>,
+    },
+    /// Run robot code headlessly on the first level and print what would happen
+    Test {
+        /// Rust file containing a `fn main()` robot program
+        file: String,
+    },
+    /// Check YAML level files for problems; exits nonzero if any are found
+    ValidateLevel {
+        /// Level files or directories of level files
+        #[arg(default_value = "community_levels")]
+        paths: Vec<PathBuf>,
+    },
+    /// Generate a random but solvable level and print it as YAML
+    GenerateLevel {
+        #[arg(long, default_value_t = 12)]
+        width: usize,
+        #[arg(long, default_value_t = 8)]
+        height: usize,
+        /// Fraction of tiles that are walls (0.0 - 0.6)
+        #[arg(long, default_value_t = 0.25)]
+        density: f32,
+        #[arg(long, default_value_t = 1)]
+        enemies: usize,
+        #[arg(long, default_value_t = 3)]
+        items: usize,
+        #[arg(long, default_value = "maze", value_parser = ["maze", "cavern", "open"])]
+        theme: String,
+        /// Seed for reproducible output (random if omitted)
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Write the YAML to PATH instead of stdout
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
+    },
+    /// Run the automated learning level task tests in the real game UI
+    RunLearningTests {
+        /// Start testing from level N (0-indexed)
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        start_level: usize,
+        /// Test at most N levels
+        #[arg(long, value_name = "N", default_value_t = 4)]
+        max_levels: usize,
+        /// Write machine-readable results for CI
+        #[arg(long, value_parser = ["junit", "json", "tap"])]
+        report_format: Option<String>,
+        /// Write the report to PATH instead of stdout (defaults to junit)
+        #[arg(long, value_name = "PATH")]
+        report_out: Option<String>,
+    },
+    /// Run robot code on a level and export every step as a JSON replay
+    ExportReplay {
+        /// Rust file containing a `fn main()` robot program
+        file: String,
+        /// Level to run the code on (0-indexed)
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        level: usize,
+        /// Output path (defaults to stdout)
+        #[arg(long, value_name = "PATH")]
+        out: Option<String>,
+    },
+    /// Run robot code on a level with many obstacle and enemy seeds and report turn statistics
+    Bench {
+        /// Rust file containing a `fn main()` robot program
+        file: String,
+        /// Built-in level to run on (0-indexed)
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        level: usize,
+        /// Run on this level YAML instead of a built-in level
+        #[arg(long, value_name = "PATH")]
+        level_file: Option<PathBuf>,
+        /// Number of runs, each with its own seed
+        #[arg(long, default_value_t = 100)]
+        runs: u64,
+        /// Seed of the first run; run i uses seed + i
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Serve the web (WASM) build over HTTP for local play
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Directory containing index.html and the .wasm build
+        #[arg(long, default_value = ".")]
+        dir: PathBuf,
+    },
+    /// Publish a level pack to the Steam Workshop (Steam must be running)
+    #[cfg(feature = "steam")]
+    WorkshopUpload {
+        /// The .rlgpack file to publish
+        pack: PathBuf,
+        #[arg(long)]
+        title: String,
+        #[arg(long, default_value = "")]
+        description: String,
+    },
+    /// Test every learning level with its built-in solution
+    DebugLevels,
+    /// Run editor functionality tests
+    EditorTest,
+    /// Play scripted editor scenarios without a window; exits nonzero if any fail
+    EditorScenario {
+        /// Scenario files or directories of scenario files
+        #[arg(default_value = "tests/editor_scenarios")]
+        paths: Vec<PathBuf>,
+    },
+    /// Run robot command tests
+    #[command(name = "command-test")]
+    RobotCommandTest,
+    /// Test the enhanced error detection system
+    TestErrorSystem,
+    /// Check Rust code for syntax errors
+    CheckCode {
+        /// Code to check, e.g. "fn main() { println!(\"hi\"); }"
+        code: String,
+    },
+}
+
+impl Cli {
+    /// The log filter to start with: --log, then --all-logs, then RUST_LOG, then info
+    pub fn log_spec(&self) -> String {
+        match &self.log {
+            Some(spec) => spec.clone(),
+            None if self.all_logs => "debug".to_string(),
+            None => std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
+        }
+    }
+
+    /// Resolve the command to run, translating legacy flags when no subcommand was given
+    pub fn into_command(self) -> Command {
+        if let Some(command) = self.command {
+            return command;
+        }
+
+        // Same precedence the old flag parser used
+        if self.editor_test {
+            Command::EditorTest
+        } else if self.command_test {
+            Command::RobotCommandTest
+        } else if self.test_error_system {
+            Command::TestErrorSystem
+        } else if let Some(code) = self.check_code {
+            Command::CheckCode { code }
+        } else if self.test_learning_levels {
+            Command::RunLearningTests {
+                start_level: self.start_level.unwrap_or(0),
+                max_levels: self.max_levels.unwrap_or(4),
+                report_format: self.report_format,
+                report_out: self.report_out,
+            }
+        } else if let Some(file) = self.test_code {
+            Command::Test { file }
+        } else if self.debug {
+            Command::DebugLevels
+        } else {
+            Command::Play { start_at_level: self.start_at_level }
+        }
+    }
+}
+
+fn content_type_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "html" => "text/html; charset=utf-8",
+        "js" => "application/javascript",
+        "wasm" => "application/wasm",
+        "css" => "text/css",
+        "json" => "application/json",
+        "png" => "image/png",
+        "ttf" => "font/ttf",
+        "yaml" | "yml" | "txt" | "rs" => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// The file under `root` a request path names, or `None` if it could reach outside of `root`. The
+/// path is percent-decoded first, and both / and \ count as separators so `..\`, drive letters
+/// and absolute paths are caught on every platform.
+fn served_path(root: &Path, request_path: &str) -> Option<PathBuf> {
+    let decoded = percent_decode(request_path)?;
+    let relative = decoded.replace('\\', "/");
+    let relative = relative.trim_start_matches('/');
+    if relative.is_empty() {
+        return Some(root.join("index.html"));
+    }
+
+    let mut path = root.to_path_buf();
+    for component in Path::new(relative).components() {
+        match component {
+            std::path::Component::Normal(segment) if !segment.to_string_lossy().contains(':') => path.push(segment),
+            _ => return None,
+        }
+    }
+    Some(path)
+}
+
+/// Decode %XX escapes; `None` for a broken escape, a NUL byte or text that isn't UTF-8
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    if decoded.contains(&0) {
+        return None;
+    }
+    String::from_utf8(decoded).ok()
+}
+
+fn handle_connection(mut stream: TcpStream, root: &Path) -> std::io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let raw_path = parts.next().unwrap_or("/");
+    let path = raw_path.split('?').next().unwrap_or("/");
+
+    if method != "GET" {
+        return stream.write_all(b"HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\n\r\n");
+    }
+
+    // Refuse anything that tries to climb out of the served directory
+    let Some(mut file_path) = served_path(root, path) else {
+        warn!("GET {} -> 403", path);
+        return stream.write_all(b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\n\r\n");
+    };
+    if file_path.is_dir() {
+        file_path = file_path.join("index.html");
+    }
+
+    match std::fs::read(&file_path) {
+        Ok(body) => {
+            info!("GET {} -> 200", path);
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\n\r\n",
+                content_type_for(&file_path),
+                body.len()
+            );
+            stream.write_all(header.as_bytes())?;
+            stream.write_all(&body)
+        }
+        Err(_) => {
+            warn!("GET {} -> 404", path);
+            let body = format!("Not found: {}", path);
+            let header = format!(
+                "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes())?;
+            stream.write_all(body.as_bytes())
+        }
+    }
+}
+
+/// Minimal static file server for the WASM build (blocks until the process is killed)
+pub fn serve_directory(dir: &Path, port: u16) -> std::io::Result<()> {
+    if !dir.join("index.html").exists() {
+        warn!("No index.html in {} - build the web version first (build-wasm.sh)", dir.display());
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    println!("Serving {} at http://127.0.0.1:{}/ (Ctrl+C to stop)", dir.display(), port);
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = handle_connection(stream, dir) {
+                    warn!("Connection error: {}", e);
+                }
+            }
+            Err(e) => warn!("Failed to accept connection: {}", e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn served_paths_stay_inside_the_root() {
+        let root = Path::new("dist");
+        assert_eq!(served_path(root, "/"), Some(root.join("index.html")));
+        assert_eq!(served_path(root, "/pkg/game%20core.wasm"), Some(root.join("pkg").join("game core.wasm")));
+
+        for escape in ["/../secret", "/pkg/../../secret", "/..\\secret", "/pkg\\..\\..\\secret", "/C:\\Windows\\win.ini",
+                       "/C:/Windows/win.ini", "/%2e%2e/secret", "/%2E%2E%2Fsecret", "/%2e%2e%5csecret", "//etc/passwd/..", "/a%00b", "/%zz"] {
+            assert_eq!(served_path(root, escape), None, "{} should be refused", escape);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+// Side-by-side comparison of the player's code with a reference solution, shown after a level is
+// completed.
+//
+// Lines are matched on their tokens rather than their exact text, so formatting differences
+// (spacing, indentation, trailing comments) don't show up as changes and the diff lines up the
+// statements that actually differ.
+
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RowKind {
+    Same,
+    Changed, // Both sides have a line, but they differ
+    Removed, // Only in the player's code
+    Added,   // Only in the reference solution
+}
+
+/// One row of the side-by-side view. Sides hold (line number, text), None where one side has no line.
+#[derive(Clone, Debug)]
+pub struct DiffRow {
+    pub kind: RowKind,
+    pub left: Option<(usize, String)>,
+    pub right: Option<(usize, String)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SolutionComparison {
+    pub title: String,
+    pub rows: Vec<DiffRow>,
+    pub scroll: usize, // First row shown
+}
+
+impl SolutionComparison {
+    pub fn new(title: String, attempt: &str, reference: &str) -> Self {
+        Self { title, rows: side_by_side(attempt, reference), scroll: 0 }
+    }
+
+    pub fn changed_rows(&self) -> usize {
+        self.rows.iter().filter(|row| row.kind != RowKind::Same).count()
+    }
+
+    pub fn scroll_by(&mut self, delta: isize, visible_rows: usize) {
+        let max_scroll = self.rows.len().saturating_sub(visible_rows);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max_scroll);
+    }
+}
+
+/// Match the lines of `old` and `new` into side-by-side rows
+pub fn side_by_side(old: &str, new: &str) -> Vec<DiffRow> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let old_keys: Vec<String> = old_lines.iter().map(|line| line_key(line)).collect();
+    let new_keys: Vec<String> = new_lines.iter().map(|line| line_key(line)).collect();
+
+    let left = |i: usize| Some((i + 1, old_lines[i].to_string()));
+    let right = |i: usize| Some((i + 1, new_lines[i].to_string()));
+
+    let mut rows = Vec::new();
+    for op in capture_diff_slices(Algorithm::Patience, &old_keys, &new_keys) {
+        match op {
+            DiffOp::Equal { old_index, new_index, len } => {
+                for i in 0..len {
+                    rows.push(DiffRow { kind: RowKind::Same, left: left(old_index + i), right: right(new_index + i) });
+                }
+            }
+            DiffOp::Delete { old_index, old_len, .. } => {
+                for i in old_index..old_index + old_len {
+                    rows.push(DiffRow { kind: RowKind::Removed, left: left(i), right: None });
+                }
+            }
+            DiffOp::Insert { new_index, new_len, .. } => {
+                for i in new_index..new_index + new_len {
+                    rows.push(DiffRow { kind: RowKind::Added, left: None, right: right(i) });
+                }
+            }
+            DiffOp::Replace { old_index, old_len, new_index, new_len } => {
+                // Pair the replaced lines up; whatever is left over on the longer side stands alone
+                for i in 0..old_len.max(new_len) {
+                    let (l, r) = (i < old_len, i < new_len);
+                    let kind = match (l, r) {
+                        (true, true) => RowKind::Changed,
+                        (true, false) => RowKind::Removed,
+                        _ => RowKind::Added,
+                    };
+                    rows.push(DiffRow {
+                        kind,
+                        left: if l { left(old_index + i) } else { None },
+                        right: if r { right(new_index + i) } else { None },
+                    });
+                }
+            }
+        }
+    }
+    rows
+}
+
+/// What a line is compared on: its tokens separated by single spaces, without comments
+fn line_key(line: &str) -> String {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == '/' && chars.peek() == Some(&'/') {
+            break; // Rest of the line is a comment
+        }
+        if c == '"' {
+            // Strings are one token, escapes included
+            let mut token = String::from(c);
+            while let Some(s) = chars.next() {
+                token.push(s);
+                if s == '\\' {
+                    if let Some(escaped) = chars.next() {
+                        token.push(escaped);
+                    }
+                } else if s == '"' {
+                    break;
+                }
+            }
+            tokens.push(token);
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut token = String::from(c);
+            while let Some(&next) = chars.peek() {
+                if !(next.is_alphanumeric() || next == '_') {
+                    break;
+                }
+                token.push(next);
+                chars.next();
+            }
+            tokens.push(token);
+        } else {
+            tokens.push(c.to_string());
+        }
+    }
+    tokens.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting_differences_are_not_changes() {
+        assert_eq!(line_key("    let x=5; // five"), line_key("let x = 5;"));
+        assert_ne!(line_key("println!(\"a b\");"), line_key("println!(\"a  b\");"));
+    }
+
+    #[test]
+    fn rows_pair_up_changed_lines() {
+        let attempt = "fn main() {\n    let x=1;\n    move_bot(\"right\");\n}";
+        let reference = "fn main() {\n    let x = 1;\n    move_bot(\"down\");\n    grab();\n}";
+        let kinds: Vec<RowKind> = side_by_side(attempt, reference).iter().map(|row| row.kind).collect();
+        assert_eq!(kinds, [RowKind::Same, RowKind::Same, RowKind::Changed, RowKind::Added, RowKind::Same]);
+    }
+}
@@ -1,10 +1,294 @@
 use std::process::{Command, Stdio};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::io::Write;
 
+/// Stand-ins for the smol and futures_lite APIs the async levels use, so that code compiles
+/// without the crates. Futures complete as soon as they're polled, timers don't wait and spawned
+/// tasks run when they're awaited; the simulated timing lives in game_core::ast.
+pub const ASYNC_RUNTIME_STUBS: &str = r#"mod smol {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::time::Duration;
+
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    pub fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    pub struct Task<T>(Pin<Box<dyn Future<Output = T>>>);
+    impl<T> Task<T> {
+        pub fn detach(self) {}
+    }
+    impl<T> Future for Task<T> {
+        type Output = T;
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+            self.get_mut().0.as_mut().poll(cx)
+        }
+    }
+    pub fn spawn<F: Future + 'static>(future: F) -> Task<F::Output> {
+        Task(Box::pin(future))
+    }
+
+    pub struct Timer;
+    impl Timer {
+        pub fn after(duration: Duration) -> std::future::Ready<()> {
+            std::future::ready(())
+        }
+    }
+}
+mod futures_lite {
+    pub mod future {
+        use std::future::Future;
+        pub use crate::smol::block_on;
+        pub async fn zip<A: Future, B: Future>(a: A, b: B) -> (A::Output, B::Output) {
+            (a.await, b.await)
+        }
+        pub async fn join_all<F: Future>(futures: Vec<F>) -> Vec<F::Output> {
+            let mut outputs = Vec::new();
+            for future in futures {
+                outputs.push(future.await);
+            }
+            outputs
+        }
+    }
+}"#;
+
+/// Crates the serde levels use. Code that names them is built in a small cargo project instead
+/// of with plain rustc; the first build needs network access to fetch them.
+pub const SERDE_DEPENDENCIES: &str = r#"serde = { version = "1", features = ["derive"] }
+serde_json = "1"
+serde_yaml = "0.9"
+"#;
+
+/// The log crate, for the logging levels. env_logger is stood in for by LOG_CAPTURE_STUBS.
+pub const LOG_DEPENDENCIES: &str = "log = \"0.4\"\n";
+
+/// Start of the stderr lines LOG_CAPTURE_STUBS writes, followed by level, target and message
+pub const LOG_RECORD_PREFIX: &str = "GAME_LOG ";
+
+/// env_logger for programs using the log crate. Records go to stderr tagged with LOG_RECORD_PREFIX
+/// so the game can show them with their level and target. Unlike the real env_logger every level
+/// is shown unless RUST_LOG or filter_level() says otherwise.
+pub const LOG_CAPTURE_STUBS: &str = r#"mod env_logger {
+    struct GameLogger;
+
+    impl log::Log for GameLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::max_level()
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                eprintln!("GAME_LOG {} {} {}", record.level(), record.target().replace(' ', "_"), record.args().to_string().replace('\n', " "));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    pub struct Builder {
+        level: log::LevelFilter,
+    }
+
+    impl Default for Builder {
+        fn default() -> Self {
+            Builder { level: log::LevelFilter::Trace }
+        }
+    }
+
+    impl Builder {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn from_default_env() -> Self {
+            let mut builder = Self::default();
+            if let Ok(filters) = std::env::var("RUST_LOG") {
+                builder.parse_filters(&filters);
+            }
+            builder
+        }
+
+        pub fn from_env<E>(_env: E) -> Self {
+            Self::from_default_env()
+        }
+
+        pub fn filter_level(&mut self, level: log::LevelFilter) -> &mut Self {
+            self.level = level;
+            self
+        }
+
+        // Per-module filters aren't kept apart; the level applies to everything
+        pub fn filter_module(&mut self, _module: &str, level: log::LevelFilter) -> &mut Self {
+            self.filter_level(level)
+        }
+
+        pub fn filter(&mut self, _module: Option<&str>, level: log::LevelFilter) -> &mut Self {
+            self.filter_level(level)
+        }
+
+        pub fn parse_filters(&mut self, filters: &str) -> &mut Self {
+            for directive in filters.split(',') {
+                if let Ok(level) = directive.rsplit('=').next().unwrap_or("").trim().parse() {
+                    self.level = level;
+                }
+            }
+            self
+        }
+
+        pub fn try_init(&mut self) -> Result<(), log::SetLoggerError> {
+            log::set_logger(&GameLogger)?;
+            log::set_max_level(self.level);
+            Ok(())
+        }
+
+        pub fn init(&mut self) {
+            self.try_init().expect("env_logger::init should not be called after logger initialized");
+        }
+    }
+
+    pub fn builder() -> Builder {
+        Builder::from_default_env()
+    }
+
+    pub fn try_init() -> Result<(), log::SetLoggerError> {
+        builder().try_init()
+    }
+
+    pub fn init() {
+        builder().init()
+    }
+}
+"#;
+
+// Appended to the player's code for a round-trip check; the generated main() calls round_trip()
+// once per serde struct and the results come back as ROUND_TRIP lines on stdout
+const ROUND_TRIP_HARNESS: &str = r#"
+fn round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(name: &str, document: &serde_json::Value) {
+    // The whole game state first, then every object and list nested in it
+    let mut parts = vec![("state".to_string(), document.clone())];
+    let mut next = 0;
+    while next < parts.len() {
+        let (path, value) = parts[next].clone();
+        match value {
+            serde_json::Value::Object(fields) => {
+                for (key, child) in fields {
+                    parts.push((format!("{}.{}", path, key), child));
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for (index, child) in items.into_iter().enumerate() {
+                    parts.push((format!("{}[{}]", path, index), child));
+                }
+            }
+            _ => {}
+        }
+        next += 1;
+    }
+
+    // Serde skips fields a struct doesn't have, so prefer a part that has exactly the struct's fields
+    let fits = |part: &serde_json::Value| serde_json::from_value::<T>(part.clone()).ok();
+    let exact = parts.iter().find(|(_, part)| fits(part).and_then(|value| serde_json::to_value(&value).ok()).as_ref() == Some(part));
+    if let Some((path, part)) = exact.or_else(|| parts.iter().find(|(_, part)| fits(part).is_some())) {
+        let value = fits(part).unwrap();
+        let expected = serde_json::to_value(&value).map_err(|e| e.to_string());
+        let from_json = serde_json::to_string(&value).map_err(|e| e.to_string())
+            .and_then(|text| serde_json::from_str::<T>(&text).map_err(|e| e.to_string()));
+        let from_yaml = serde_yaml::to_string(&value).map_err(|e| e.to_string())
+            .and_then(|text| serde_yaml::from_str::<T>(&text).map_err(|e| e.to_string()));
+        match (expected, from_json, from_yaml) {
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => println!("ROUND_TRIP {} fail {}", name, e),
+            (Ok(expected), Ok(json), Ok(yaml)) => {
+                if serde_json::to_value(&json).ok().as_ref() == Some(&expected) && serde_json::to_value(&yaml).ok().as_ref() == Some(&expected) {
+                    println!("ROUND_TRIP {} ok {}", name, path);
+                } else {
+                    println!("ROUND_TRIP {} fail its values change when written out and read back", name);
+                }
+            }
+        }
+    } else {
+        println!("ROUND_TRIP {} fail no part of the game state has the fields it needs", name);
+    }
+}
+"#;
+
+/// Result of feeding one of the player's serde structs the game state
+#[derive(Debug, Clone)]
+pub struct RoundTrip {
+    pub name: String,
+    pub result: Result<String, String>, // Where in the game state it was read from, or why it failed
+}
+
+/// Whether `code` needs the crates in SERDE_DEPENDENCIES
+pub fn uses_serde(code: &str) -> bool {
+    ["serde::", "serde_json", "serde_yaml", "Serialize", "Deserialize"].iter().any(|name| code.contains(name))
+}
+
+/// Whether `code` logs through the log crate
+pub fn uses_log(code: &str) -> bool {
+    ["log::", "env_logger", "info!", "warn!", "error!", "debug!", "trace!"].iter().any(|name| code.contains(name))
+}
+
+/// `[dependencies]` lines `code` needs; empty for code plain rustc can build
+pub fn crate_dependencies(code: &str) -> String {
+    let mut dependencies = String::new();
+    if uses_serde(code) {
+        dependencies.push_str(SERDE_DEPENDENCIES);
+    }
+    if uses_log(code) {
+        dependencies.push_str(LOG_DEPENDENCIES);
+    }
+    dependencies
+}
+
+/// Stand-ins for library crates put next to the game function stubs: the async runtime always,
+/// env_logger when the code logs
+pub fn library_stubs(code: &str) -> String {
+    if uses_log(code) {
+        format!("{}\n{}", ASYNC_RUNTIME_STUBS, LOG_CAPTURE_STUBS)
+    } else {
+        ASYNC_RUNTIME_STUBS.to_string()
+    }
+}
+
+/// One record the player's code logged
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub level: String, // "ERROR", "WARN", "INFO", "DEBUG" or "TRACE"
+    pub target: String,
+    pub message: String,
+}
+
+impl LogRecord {
+    /// Read a stderr line written by LOG_CAPTURE_STUBS
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.strip_prefix(LOG_RECORD_PREFIX)?.splitn(3, ' ');
+        Some(Self {
+            level: parts.next()?.to_string(),
+            target: parts.next()?.to_string(),
+            message: parts.next().unwrap_or("").to_string(),
+        })
+    }
+}
+
 pub struct CodeExecutor {
     temp_dir: PathBuf,
+    remaining_energy: Option<u32>, // Value returned by remaining_energy() in user code
+    random_seed: u64, // Level seed random_int() draws from
 }
 
 impl CodeExecutor {
@@ -14,13 +298,27 @@ impl CodeExecutor {
         fs::create_dir_all(&temp_dir)
             .map_err(|e| format!("Failed to create temp directory: {}", e))?;
 
-        Ok(Self { temp_dir })
+        Ok(Self { temp_dir, remaining_energy: None, random_seed: 0 })
+    }
+
+    /// Energy the robot has when the program starts; levels without a budget report u32::MAX
+    pub fn set_remaining_energy(&mut self, energy: Option<u32>) {
+        self.remaining_energy = energy;
+    }
+
+    /// Level seed, so random_int() prints the same numbers the robot acts on
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.random_seed = seed;
     }
 
     /// Execute user's Rust code and capture output
     pub fn execute_code(&self, user_code: &str) -> Result<ExecutionResult, String> {
         // Use the same wrapper system as the syntax checker to provide game function stubs
         let code = self.wrap_user_code_for_execution(user_code);
+        let dependencies = crate_dependencies(user_code);
+        if !dependencies.is_empty() {
+            return self.execute_with_crates(&code, &dependencies);
+        }
 
         // Write code to a temporary .rs file
         let source_path = self.temp_dir.join("user_code.rs");
@@ -48,8 +346,42 @@ impl CodeExecutor {
             });
         }
 
-        // Run the compiled executable and capture output
-        let run_output = Command::new(&exe_path)
+        Self::run_executable(&exe_path)
+    }
+
+    /// Build wrapped code in a cargo project with `dependencies` and run it
+    fn execute_with_crates(&self, code: &str, dependencies: &str) -> Result<ExecutionResult, String> {
+        let project = self.temp_dir.join("crates_project");
+        fs::create_dir_all(project.join("src"))
+            .map_err(|e| format!("Failed to create project directory: {}", e))?;
+        let cargo_toml = format!("[workspace]\n\n[package]\nname = \"user_code\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n{}", dependencies);
+        fs::write(project.join("Cargo.toml"), cargo_toml)
+            .map_err(|e| format!("Failed to write Cargo.toml: {}", e))?;
+        fs::write(project.join("src").join("main.rs"), code)
+            .map_err(|e| format!("Failed to write source file: {}", e))?;
+
+        let build_output = Command::new("cargo")
+            .args(["build", "--quiet"])
+            .current_dir(&project)
+            .env("CARGO_TARGET_DIR", project.join("target"))
+            .output()
+            .map_err(|e| format!("Failed to run cargo: {}. Make sure Rust is installed.", e))?;
+
+        if !build_output.status.success() {
+            return Ok(ExecutionResult {
+                stdout: String::new(),
+                stderr: String::from_utf8_lossy(&build_output.stderr).to_string(),
+                success: false,
+                is_compilation_error: true,
+            });
+        }
+
+        Self::run_executable(&project.join("target").join("debug").join(format!("user_code{}", std::env::consts::EXE_SUFFIX)))
+    }
+
+    /// Run a compiled program and capture its output
+    fn run_executable(exe_path: &Path) -> Result<ExecutionResult, String> {
+        let run_output = Command::new(exe_path)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .output()
@@ -66,10 +398,48 @@ impl CodeExecutor {
         })
     }
 
+    /// Feed every struct in `user_code` that derives Serialize and Deserialize the part of
+    /// `game_state` whose fields match it, then check it comes back unchanged after being written
+    /// to JSON and YAML and read again. Fails when the code doesn't build.
+    pub fn check_serde_round_trip(&self, user_code: &str, game_state: &serde_json::Value) -> Result<Vec<RoundTrip>, String> {
+        let structs = game_core::ast::serde_structs(user_code);
+        if structs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // The player's main() is kept under another name so the check gets to run its own
+        let checks: String = structs.iter()
+            .map(|name| format!("    round_trip::<{0}>(\"{0}\", &document);\n", name))
+            .collect();
+        let program = format!(
+            "{}\n{}\nconst GAME_STATE: &str = r###\"{}\"###;\n\nfn main() {{\n    let document: serde_json::Value = serde_json::from_str(GAME_STATE).unwrap();\n{}}}\n",
+            user_code.replacen("fn main(", "fn user_main(", 1),
+            ROUND_TRIP_HARNESS,
+            game_state,
+            checks,
+        );
+
+        let result = self.execute_with_crates(&self.wrap_user_code_for_execution(&program), &crate_dependencies(&program))?;
+        if result.is_compilation_error {
+            return Err(result.stderr);
+        }
+        Ok(result.stdout.lines()
+            .filter_map(|line| line.strip_prefix("ROUND_TRIP "))
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, ' ');
+                let (name, status, detail) = (parts.next()?, parts.next()?, parts.next().unwrap_or(""));
+                let result = if status == "ok" { Ok(detail.to_string()) } else { Err(detail.to_string()) };
+                Some(RoundTrip { name: name.to_string(), result })
+            })
+            .collect())
+    }
+
     /// Wrap user code with game function stubs for execution
     fn wrap_user_code_for_execution(&self, user_code: &str) -> String {
         // Check if the user code already contains fn main()
         let has_main = user_code.contains("fn main()") || user_code.contains("fn main (");
+        let energy = self.remaining_energy.unwrap_or(u32::MAX);
+        let random_int = game_core::random::random_int_source(self.random_seed);
 
         if has_main {
             // If user code already has fn main(), just add the necessary stubs
@@ -81,10 +451,52 @@ fn scan() -> String {{ String::new() }}
 fn grab() -> String {{ String::new() }}
 fn search() -> String {{ String::new() }}
 fn move_bot(direction: &str) -> String {{ String::new() }}
+fn teleport(x: i32, y: i32) -> String {{ String::new() }}
+struct RobotHandle;
+// robot.move_up() and robot(1).move_up(): the trait is implemented for robot() itself too
+trait RobotApi {{
+    fn move_bot(&self, direction: &str) -> String {{ String::new() }}
+    fn move_up(&self) -> String {{ String::new() }}
+    fn move_down(&self) -> String {{ String::new() }}
+    fn move_left(&self) -> String {{ String::new() }}
+    fn move_right(&self) -> String {{ String::new() }}
+    fn scan(&self, direction: &str) -> String {{ String::new() }}
+    fn scan_up(&self) -> String {{ String::new() }}
+    fn scan_down(&self) -> String {{ String::new() }}
+    fn scan_left(&self) -> String {{ String::new() }}
+    fn scan_right(&self) -> String {{ String::new() }}
+    fn scan_current(&self) -> String {{ String::new() }}
+    fn grab(&self) -> String {{ String::new() }}
+    fn open_door(&self, open: bool) -> String {{ String::new() }}
+    fn teleport(&self, x: i32, y: i32) -> String {{ String::new() }}
+    fn move_forward(&self) -> String {{ String::new() }}
+    fn rotate_left(&self) -> String {{ String::new() }}
+    fn rotate_right(&self) -> String {{ String::new() }}
+    fn print_map(&self) {{}}
+    fn rewind(&self, turns: usize) -> String {{ String::new() }}
+    fn emp(&self, radius: u32) -> String {{ String::new() }}
+    fn set_auto_grab(&self, enabled: bool) -> String {{ String::new() }}
+    fn say(&self, text: &str) -> String {{ String::new() }}
+}}
+impl RobotApi for RobotHandle {{}}
+impl<F: Fn(usize) -> RobotHandle> RobotApi for F {{}}
+fn robot(index: usize) -> RobotHandle {{ RobotHandle }}
+fn select_robot(index: usize) -> String {{ String::new() }}
+fn remaining_energy() -> u32 {{ {} }}
+fn move_forward() -> String {{ String::new() }}
+fn rotate_left() -> String {{ String::new() }}
+fn rotate_right() -> String {{ String::new() }}
+fn print_map() {{}}
+fn rewind(turns: usize) -> String {{ String::new() }}
+fn emp(radius: u32) -> String {{ String::new() }}
+fn set_auto_grab(enabled: bool) -> String {{ String::new() }}
+fn say(text: &str) -> String {{ String::new() }}
+{}
+{}
 
 // User code with its own main function
 {}
-"#, user_code)
+"#, energy, random_int, library_stubs(user_code), user_code)
         } else {
             // If no main function, wrap it
             format!(r#"#![allow(unused_variables, dead_code, unused_imports, unused_mut, unused_parens)]
@@ -95,11 +507,53 @@ fn scan() -> String {{ String::new() }}
 fn grab() -> String {{ String::new() }}
 fn search() -> String {{ String::new() }}
 fn move_bot(direction: &str) -> String {{ String::new() }}
+fn teleport(x: i32, y: i32) -> String {{ String::new() }}
+struct RobotHandle;
+// robot.move_up() and robot(1).move_up(): the trait is implemented for robot() itself too
+trait RobotApi {{
+    fn move_bot(&self, direction: &str) -> String {{ String::new() }}
+    fn move_up(&self) -> String {{ String::new() }}
+    fn move_down(&self) -> String {{ String::new() }}
+    fn move_left(&self) -> String {{ String::new() }}
+    fn move_right(&self) -> String {{ String::new() }}
+    fn scan(&self, direction: &str) -> String {{ String::new() }}
+    fn scan_up(&self) -> String {{ String::new() }}
+    fn scan_down(&self) -> String {{ String::new() }}
+    fn scan_left(&self) -> String {{ String::new() }}
+    fn scan_right(&self) -> String {{ String::new() }}
+    fn scan_current(&self) -> String {{ String::new() }}
+    fn grab(&self) -> String {{ String::new() }}
+    fn open_door(&self, open: bool) -> String {{ String::new() }}
+    fn teleport(&self, x: i32, y: i32) -> String {{ String::new() }}
+    fn move_forward(&self) -> String {{ String::new() }}
+    fn rotate_left(&self) -> String {{ String::new() }}
+    fn rotate_right(&self) -> String {{ String::new() }}
+    fn print_map(&self) {{}}
+    fn rewind(&self, turns: usize) -> String {{ String::new() }}
+    fn emp(&self, radius: u32) -> String {{ String::new() }}
+    fn set_auto_grab(&self, enabled: bool) -> String {{ String::new() }}
+    fn say(&self, text: &str) -> String {{ String::new() }}
+}}
+impl RobotApi for RobotHandle {{}}
+impl<F: Fn(usize) -> RobotHandle> RobotApi for F {{}}
+fn robot(index: usize) -> RobotHandle {{ RobotHandle }}
+fn select_robot(index: usize) -> String {{ String::new() }}
+fn remaining_energy() -> u32 {{ {} }}
+fn move_forward() -> String {{ String::new() }}
+fn rotate_left() -> String {{ String::new() }}
+fn rotate_right() -> String {{ String::new() }}
+fn print_map() {{}}
+fn rewind(turns: usize) -> String {{ String::new() }}
+fn emp(radius: u32) -> String {{ String::new() }}
+fn set_auto_grab(enabled: bool) -> String {{ String::new() }}
+fn say(text: &str) -> String {{ String::new() }}
+{}
+{}
 
 fn main() {{
     {}
 }}
-"#, user_code)
+"#, energy, random_int, library_stubs(user_code), user_code)
         }
     }
 
@@ -0,0 +1,224 @@
+// Robot code history: every successful run is committed to a small local git repository, so
+// players can look back at earlier attempts, diff them against the editor and restore them.
+//
+// The repository is bare and lives in the cache directory; it only ever holds robot_code.rs
+// and never touches a git repository the player's project might be in.
+
+use git2::{DiffOptions, Oid, Patch, Repository, Signature};
+use std::path::PathBuf;
+
+const CODE_FILE: &str = "robot_code.rs";
+const AUTHOR: &str = "Robot Code History";
+const AUTHOR_EMAIL: &str = "player@localhost";
+// Entries shown in the history panel
+pub const HISTORY_LIMIT: usize = 50;
+
+pub fn history_dir() -> PathBuf {
+    crate::cache::cache_dir().join("history.git")
+}
+
+#[derive(Clone, Debug)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub message: String,
+    pub time: i64, // Unix seconds
+}
+
+impl HistoryEntry {
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(7)]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiffKind {
+    Hunk,    // "@@ -1,3 +1,4 @@" header
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Clone, Debug)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+pub struct CodeHistory {
+    repo: Repository,
+}
+
+impl std::fmt::Debug for CodeHistory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CodeHistory").field("path", &self.repo.path()).finish()
+    }
+}
+
+impl CodeHistory {
+    /// Open the history repository, creating it on first use
+    pub fn open() -> Result<Self, String> {
+        let dir = history_dir();
+        let repo = match Repository::open_bare(&dir) {
+            Ok(repo) => repo,
+            Err(_) => Repository::init_bare(&dir).map_err(|e| format!("Failed to create code history: {}", e))?,
+        };
+        Ok(Self { repo })
+    }
+
+    /// Commit `code` with `message`. Returns false (and commits nothing) if the code is the same
+    /// as in the last commit.
+    pub fn commit(&self, code: &str, message: &str) -> Result<bool, String> {
+        let blob = self.repo.blob(code.as_bytes()).map_err(|e| e.to_string())?;
+        let mut builder = self.repo.treebuilder(None).map_err(|e| e.to_string())?;
+        builder.insert(CODE_FILE, blob, 0o100644).map_err(|e| e.to_string())?;
+        let tree_id = builder.write().map_err(|e| e.to_string())?;
+
+        let parent = self.repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        if parent.as_ref().is_some_and(|parent| parent.tree_id() == tree_id) {
+            return Ok(false);
+        }
+
+        let tree = self.repo.find_tree(tree_id).map_err(|e| e.to_string())?;
+        let signature = Signature::now(AUTHOR, AUTHOR_EMAIL).map_err(|e| e.to_string())?;
+        let parents: Vec<_> = parent.iter().collect();
+        self.repo
+            .commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+            .map_err(|e| format!("Failed to record code history: {}", e))?;
+        Ok(true)
+    }
+
+    /// Newest commits first
+    pub fn entries(&self, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+        if self.repo.head().is_err() {
+            return Ok(Vec::new()); // Nothing committed yet
+        }
+        let mut walk = self.repo.revwalk().map_err(|e| e.to_string())?;
+        walk.push_head().map_err(|e| e.to_string())?;
+
+        let mut entries = Vec::new();
+        for oid in walk.take(limit) {
+            let oid = oid.map_err(|e| e.to_string())?;
+            let commit = self.repo.find_commit(oid).map_err(|e| e.to_string())?;
+            entries.push(HistoryEntry {
+                id: oid.to_string(),
+                message: commit.summary().unwrap_or("").to_string(),
+                time: commit.time().seconds(),
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Robot code as it was in commit `id`
+    pub fn code_at(&self, id: &str) -> Result<String, String> {
+        let oid = Oid::from_str(id).map_err(|e| e.to_string())?;
+        let commit = self.repo.find_commit(oid).map_err(|e| format!("Unknown history entry {}: {}", id, e))?;
+        let tree = commit.tree().map_err(|e| e.to_string())?;
+        let entry = tree.get_name(CODE_FILE).ok_or_else(|| format!("{} missing from history entry {}", CODE_FILE, id))?;
+        let blob = entry.to_object(&self.repo).and_then(|object| object.peel_to_blob()).map_err(|e| e.to_string())?;
+        Ok(String::from_utf8_lossy(blob.content()).into_owned())
+    }
+}
+
+/// Unified diff from `old` to `new`, three lines of context around each change
+pub fn diff_lines(old: &str, new: &str) -> Result<Vec<DiffLine>, String> {
+    let mut options = DiffOptions::new();
+    options.context_lines(3);
+    let patch = Patch::from_buffers(old.as_bytes(), None, new.as_bytes(), None, Some(&mut options))
+        .map_err(|e| e.to_string())?;
+
+    let mut lines = Vec::new();
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_idx).map_err(|e| e.to_string())?;
+        lines.push(DiffLine {
+            kind: DiffKind::Hunk,
+            text: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+        });
+        for line_idx in 0..line_count {
+            let line = patch.line_in_hunk(hunk_idx, line_idx).map_err(|e| e.to_string())?;
+            let kind = match line.origin() {
+                '+' => DiffKind::Added,
+                '-' => DiffKind::Removed,
+                ' ' => DiffKind::Context,
+                _ => continue, // "\ No newline at end of file"
+            };
+            let text = String::from_utf8_lossy(line.content()).trim_end_matches(['\r', '\n']).to_string();
+            lines.push(DiffLine { kind, text });
+        }
+    }
+    Ok(lines)
+}
+
+/// What the history panel in the sidebar is showing
+#[derive(Debug)]
+pub struct HistoryPanel {
+    pub history: CodeHistory,
+    pub entries: Vec<HistoryEntry>,
+    pub selected: usize,
+    pub diff: Option<Vec<DiffLine>>, // Selected entry compared to the editor, while open
+    pub status: Option<String>,      // Last error or confirmation
+}
+
+impl HistoryPanel {
+    pub fn open() -> Result<Self, String> {
+        let history = CodeHistory::open()?;
+        let entries = history.entries(HISTORY_LIMIT)?;
+        Ok(Self { history, entries, selected: 0, diff: None, status: None })
+    }
+
+    /// Commit `code` and reload the entry list
+    pub fn record(&mut self, code: &str, message: &str) -> Result<(), String> {
+        if self.history.commit(code, message)? {
+            self.entries = self.history.entries(HISTORY_LIMIT)?;
+            self.selected = 0;
+            self.diff = None;
+        }
+        Ok(())
+    }
+
+    pub fn selected_entry(&self) -> Option<&HistoryEntry> {
+        self.entries.get(self.selected)
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+        self.diff = None;
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+        self.diff = None;
+    }
+
+    /// Show or hide the diff between the selected entry and `current_code`
+    pub fn toggle_diff(&mut self, current_code: &str) {
+        if self.diff.take().is_some() {
+            return;
+        }
+        let Some(entry) = self.selected_entry() else { return };
+        match self.history.code_at(&entry.id).and_then(|old| diff_lines(&old, current_code)) {
+            Ok(diff) => self.diff = Some(diff),
+            Err(e) => self.status = Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_marks_added_and_removed_lines() {
+        let old = "fn main() {\n    move_bot(\"right\");\n}\n";
+        let new = "fn main() {\n    move_bot(\"down\");\n    grab();\n}\n";
+        let diff = diff_lines(old, new).unwrap();
+
+        assert_eq!(diff[0].kind, DiffKind::Hunk);
+        let removed: Vec<_> = diff.iter().filter(|l| l.kind == DiffKind::Removed).map(|l| l.text.as_str()).collect();
+        let added: Vec<_> = diff.iter().filter(|l| l.kind == DiffKind::Added).map(|l| l.text.as_str()).collect();
+        assert_eq!(removed, ["    move_bot(\"right\");"]);
+        assert_eq!(added, ["    move_bot(\"down\");", "    grab();"]);
+        assert!(diff_lines(old, old).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+// In-game browser for community levels. The index is a JSON manifest at a configurable URL
+// (community_index_url in game_settings.json) listing each level's metadata and where to download
+// its YAML. Downloads go into community_levels/ only after the level validator accepts them, so a
+// broken or unsafe level never ends up next to the working ones.
+//
+// Network requests run on a background thread; the menu polls for their results every frame.
+
+use crate::i18n::tr_with;
+use crate::progressive_loader::COMMUNITY_LEVELS_DIR;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+
+pub const DEFAULT_INDEX_URL: &str = "https://raw.githubusercontent.com/hastur-dev/rust-learning-game/main/community_index.json";
+
+// Index and level files are small; anything bigger than this is refused
+const MAX_DOWNLOAD_BYTES: u64 = 1024 * 1024;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CommunityIndex {
+    #[serde(default)]
+    pub levels: Vec<CommunityLevelEntry>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CommunityLevelEntry {
+    pub id: String, // File name in community_levels/ (letters, digits, '-' and '_')
+    pub name: String,
+    pub url: String, // Where to download the level YAML
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub difficulty: Option<String>,
+    #[serde(default)]
+    pub rating: Option<f32>, // Average rating out of 5
+    #[serde(default)]
+    pub ratings: u32, // Number of ratings behind `rating`
+    #[serde(default)]
+    pub downloads: u32,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl CommunityLevelEntry {
+    /// Ids become file names, so only plain names are accepted
+    pub fn has_valid_id(&self) -> bool {
+        !self.id.is_empty() && self.id.len() <= 64 && self.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    fn sanitize(&mut self, filter_words: bool) {
+        use game_core::level::sanitize::{sanitize_text, MAX_MESSAGE_CHARS, MAX_NAME_CHARS};
+        self.name = sanitize_text(&self.name, MAX_NAME_CHARS, filter_words);
+        for text in [&mut self.author, &mut self.difficulty].into_iter().flatten() {
+            *text = sanitize_text(text, MAX_NAME_CHARS, filter_words);
+        }
+        if let Some(description) = &mut self.description {
+            *description = sanitize_text(description, MAX_MESSAGE_CHARS, filter_words);
+        }
+        for tag in &mut self.tags {
+            *tag = sanitize_text(tag, MAX_NAME_CHARS, filter_words);
+        }
+    }
+
+    pub fn file_path(&self) -> PathBuf {
+        Path::new(COMMUNITY_LEVELS_DIR).join(format!("{}.yaml", self.id))
+    }
+
+    pub fn is_installed(&self) -> bool {
+        self.file_path().exists()
+    }
+
+    /// "★ 4.5 (12)  ·  340 downloads  ·  by someone" with whatever the index provides
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(rating) = self.rating {
+            parts.push(format!("★ {:.1} ({})", rating, self.ratings));
+        }
+        parts.push(format!("{} downloads", self.downloads));
+        if let Some(difficulty) = &self.difficulty {
+            parts.push(difficulty.clone());
+        }
+        if let Some(author) = &self.author {
+            parts.push(format!("by {}", author));
+        }
+        parts.join("  ·  ")
+    }
+}
+
+#[derive(Debug)]
+enum BrowserEvent {
+    Index(Result<CommunityIndex, String>),
+    Installed(usize, Result<PathBuf, Vec<String>>),
+}
+
+/// State of the community browser screen
+#[derive(Clone, Debug)]
+pub struct CommunityBrowser {
+    pub entries: Vec<CommunityLevelEntry>,
+    pub status: String,
+    pub busy: bool,
+    sender: mpsc::Sender<BrowserEvent>,
+    receiver: Arc<Mutex<mpsc::Receiver<BrowserEvent>>>,
+}
+
+impl CommunityBrowser {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self { entries: Vec::new(), status: String::new(), busy: false, sender, receiver: Arc::new(Mutex::new(receiver)) }
+    }
+
+    /// Fetch the index in the background. Its texts go through the same community sanitizer as
+    /// level files.
+    pub fn refresh(&mut self, index_url: &str, filter_words: bool) {
+        self.busy = true;
+        self.status = tr_with("community.loading", &[("url", index_url)]);
+        let sender = self.sender.clone();
+        let url = index_url.to_string();
+        spawn(move || {
+            let index = fetch_text(&url).and_then(|text| {
+                serde_json::from_str::<CommunityIndex>(&text).map_err(|e| format!("Invalid index: {}", e))
+            }).map(|mut index| {
+                index.levels.iter_mut().for_each(|entry| entry.sanitize(filter_words));
+                index
+            });
+            let _ = sender.send(BrowserEvent::Index(index));
+        });
+    }
+
+    /// Download entry `index`, validate it and move it into community_levels/
+    pub fn install(&mut self, index: usize) {
+        let Some(entry) = self.entries.get(index).cloned() else { return };
+        if !entry.has_valid_id() {
+            self.status = tr_with("community.bad_id", &[("name", &entry.name)]);
+            return;
+        }
+        self.busy = true;
+        self.status = tr_with("community.downloading", &[("name", &entry.name)]);
+        let sender = self.sender.clone();
+        spawn(move || {
+            let result = fetch_text(&entry.url).map_err(|e| vec![e]).and_then(|yaml| install_level(&entry, &yaml));
+            let _ = sender.send(BrowserEvent::Installed(index, result));
+        });
+    }
+
+    /// Apply finished requests. Returns the files installed since the last poll so the caller can
+    /// add them to the level list, and whether anything changed on screen.
+    pub fn poll(&mut self) -> (Vec<PathBuf>, bool) {
+        let mut installed = Vec::new();
+        let mut changed = false;
+        let events: Vec<BrowserEvent> = match self.receiver.lock() {
+            Ok(receiver) => receiver.try_iter().collect(),
+            Err(_) => Vec::new(),
+        };
+        for event in events {
+            changed = true;
+            self.busy = false;
+            match event {
+                BrowserEvent::Index(Ok(index)) => {
+                    self.status = tr_with("community.count", &[("count", &index.levels.len().to_string())]);
+                    self.entries = index.levels;
+                }
+                BrowserEvent::Index(Err(e)) => self.status = tr_with("community.index_failed", &[("error", &e)]),
+                BrowserEvent::Installed(index, Ok(path)) => {
+                    let name = self.entries.get(index).map_or("Level", |e| e.name.as_str());
+                    self.status = tr_with("community.installed_as", &[("name", name)]);
+                    installed.push(path);
+                }
+                BrowserEvent::Installed(index, Err(problems)) => {
+                    let name = self.entries.get(index).map_or("Level", |e| e.name.as_str());
+                    self.status = tr_with("community.failed", &[("name", name), ("problems", &problems.join("; "))]);
+                }
+            }
+        }
+        (installed, changed)
+    }
+}
+
+impl Default for CommunityBrowser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn(job: impl FnOnce() + Send + 'static) {
+    std::thread::spawn(job);
+}
+
+#[cfg(target_arch = "wasm32")]
+fn spawn(job: impl FnOnce() + Send + 'static) {
+    job();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn fetch_text(url: &str) -> Result<String, String> {
+    use std::io::Read;
+
+    let response = ureq::get(url)
+        .timeout(std::time::Duration::from_secs(15))
+        .call()
+        .map_err(|e| e.to_string())?;
+    let mut text = String::new();
+    response
+        .into_reader()
+        .take(MAX_DOWNLOAD_BYTES + 1)
+        .read_to_string(&mut text)
+        .map_err(|e| e.to_string())?;
+    if text.len() as u64 > MAX_DOWNLOAD_BYTES {
+        return Err(format!("{} is larger than {} KB", url, MAX_DOWNLOAD_BYTES / 1024));
+    }
+    Ok(text)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn fetch_text(_url: &str) -> Result<String, String> {
+    Err("Community downloads aren't available in the web version".to_string())
+}
+
+/// Write the level next to the installed ones under a temporary name, run the validator on it and
+/// only keep it if no problems are found
+fn install_level(entry: &CommunityLevelEntry, yaml: &str) -> Result<PathBuf, Vec<String>> {
+    let io_error = |e: std::io::Error| vec![e.to_string()];
+    std::fs::create_dir_all(COMMUNITY_LEVELS_DIR).map_err(io_error)?;
+    let pending = Path::new(COMMUNITY_LEVELS_DIR).join(format!("{}.yaml.download", entry.id));
+    std::fs::write(&pending, yaml).map_err(io_error)?;
+
+    let problems = game_core::level::validate_level_file(&pending);
+    if !problems.is_empty() {
+        let _ = std::fs::remove_file(&pending);
+        return Err(problems);
+    }
+    let path = entry.file_path();
+    std::fs::rename(&pending, &path).map_err(io_error)?;
+    Ok(path)
+}
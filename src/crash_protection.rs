@@ -13,7 +13,7 @@ const EXCEPTION_CONTINUE_EXECUTION: i32 = -1;
 #[cfg(windows)]  
 const EXCEPTION_CONTINUE_SEARCH: i32 = 0;
 use log::{error, info, warn};
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
 
 // Global crash tracking
 static CRASH_COUNT: AtomicUsize = AtomicUsize::new(0);
@@ -24,6 +24,22 @@ static mut BLACKLISTED_ADDRESSES: Vec<usize> = Vec::new();
 static PERMANENT_CRASH_PROTECTION: AtomicBool = AtomicBool::new(false);
 static EMERGENCY_SHUTDOWN_MODE: AtomicBool = AtomicBool::new(false);
 
+// Runs without a window (editor scenarios, benchmarks) have no macroquad context to ask, so they
+// use a fixed viewport and every other system operation returns its default
+static HEADLESS: AtomicBool = AtomicBool::new(false);
+static HEADLESS_WIDTH: AtomicU32 = AtomicU32::new(0);
+static HEADLESS_HEIGHT: AtomicU32 = AtomicU32::new(0);
+
+pub fn set_headless_viewport(width: f32, height: f32) {
+    HEADLESS_WIDTH.store(width.to_bits(), Ordering::SeqCst);
+    HEADLESS_HEIGHT.store(height.to_bits(), Ordering::SeqCst);
+    HEADLESS.store(true, Ordering::SeqCst);
+}
+
+pub fn is_headless() -> bool {
+    HEADLESS.load(Ordering::SeqCst)
+}
+
 pub fn is_system_crash_active() -> bool {
     SYSTEM_CRASH_ACTIVE.load(Ordering::SeqCst)
 }
@@ -47,6 +63,33 @@ pub fn is_emergency_shutdown_active() -> bool {
     EMERGENCY_SHUTDOWN_MODE.load(Ordering::SeqCst)
 }
 
+// Session marker: created at startup and removed on a clean exit, so finding it at startup
+// means the previous session crashed or was killed
+fn session_marker_path() -> std::path::PathBuf {
+    crate::cache::cache_dir().join("session.lock")
+}
+
+/// Mark this session as running. Returns true if the previous session didn't shut down cleanly.
+pub fn begin_session() -> bool {
+    let marker = session_marker_path();
+    let unclean = marker.exists();
+    if unclean {
+        warn!("Previous session did not shut down cleanly");
+    }
+    if let Some(dir) = marker.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Err(e) = std::fs::write(&marker, std::process::id().to_string()) {
+        warn!("Failed to write session marker: {}", e);
+    }
+    unclean
+}
+
+/// Mark the session as cleanly shut down
+pub fn end_session() {
+    let _ = std::fs::remove_file(session_marker_path());
+}
+
 #[cfg(windows)]
 unsafe extern "system" fn unhandled_exception_filter(
     exception_info: PEXCEPTION_POINTERS,
@@ -146,6 +189,10 @@ where
     F: FnOnce() -> R,
     R: Clone,
 {
+    if is_headless() {
+        return default_result;
+    }
+
     // In permanent protection mode, be extra cautious with risky operations
     if is_permanent_protection_active() {
         // Skip potentially dangerous operations entirely
@@ -202,10 +249,16 @@ pub fn safe_is_key_down(key: KeyCode) -> bool {
 }
 
 pub fn safe_screen_width() -> f32 {
+    if is_headless() {
+        return f32::from_bits(HEADLESS_WIDTH.load(Ordering::SeqCst));
+    }
     safe_system_operation(|| macroquad::prelude::screen_width(), "screen_width", 800.0)
 }
 
 pub fn safe_screen_height() -> f32 {
+    if is_headless() {
+        return f32::from_bits(HEADLESS_HEIGHT.load(Ordering::SeqCst));
+    }
     safe_system_operation(|| macroquad::prelude::screen_height(), "screen_height", 600.0)
 }
 
@@ -0,0 +1,212 @@
+// Camera over the grid: mouse-wheel zoom, drag to pan and an optional follow-robot mode, so
+// levels bigger than the game area can still be played.
+//
+// The grid is drawn in world space (tile (0, 0) at the origin, TILE pixels per tile) under a
+// Camera2D that maps it into the game area left of the sidebar. GridView converts between screen
+// and world positions, and turns the mouse into a tile for anything that picks tiles.
+
+use macroquad::prelude::*;
+use crate::gamestate::Game;
+use crate::item::Pos;
+use crate::font_scaling::*;
+use super::game_drawing::{robot_draw_position, TILE};
+
+pub const MIN_ZOOM: f32 = 0.25;
+pub const MAX_ZOOM: f32 = 3.0;
+// Zoom factor per mouse-wheel notch and per button click
+pub const ZOOM_STEP: f32 = 1.15;
+
+/// Screen area the grid is drawn in: left of the tabbed sidebar, below the header
+pub fn grid_viewport() -> Rect {
+    let grid = super::layout::screen_layout().grid;
+    Rect::new(grid.x, grid.y, grid.w.max(1.0), grid.h.max(1.0))
+}
+
+#[derive(Debug, Clone)]
+pub struct GridCamera {
+    pub zoom: f32,            // 1.0 draws tiles at TILE pixels
+    pub follow_robot: bool,
+    center: Option<Vec2>,     // World point at the middle of the view; None keeps the grid centered
+    drag: Option<(Vec2, Vec2)>, // Mouse position and view center when the drag started
+}
+
+impl Default for GridCamera {
+    fn default() -> Self {
+        Self { zoom: 1.0, follow_robot: false, center: None, drag: None }
+    }
+}
+
+/// Where the camera is looking this frame
+#[derive(Debug, Clone, Copy)]
+pub struct GridView {
+    pub viewport: Rect,
+    pub center: Vec2,
+    pub zoom: f32,
+}
+
+impl GridView {
+    pub fn screen_to_world(&self, screen: Vec2) -> Vec2 {
+        self.center + (screen - self.viewport.center()) / self.zoom
+    }
+
+    pub fn world_to_screen(&self, world: Vec2) -> Vec2 {
+        self.viewport.center() + (world - self.center) * self.zoom
+    }
+
+    /// Tile under a screen position, if it's inside the game area and on the grid
+    pub fn screen_to_tile(&self, screen: Vec2, game: &Game) -> Option<Pos> {
+        if !self.viewport.contains(screen) {
+            return None;
+        }
+        let world = self.screen_to_world(screen) / TILE;
+        let p = Pos { x: world.x.floor() as i32, y: world.y.floor() as i32 };
+        game.grid.in_bounds(p).then_some(p)
+    }
+
+    /// Camera that draws world space into the viewport
+    pub fn camera(&self) -> Camera2D {
+        let (sw, sh) = (crate::crash_protection::safe_screen_width(), crate::crash_protection::safe_screen_height());
+        let top_left = self.screen_to_world(Vec2::ZERO);
+        Camera2D::from_display_rect(Rect::new(top_left.x, top_left.y, sw / self.zoom, sh / self.zoom))
+    }
+}
+
+impl GridCamera {
+    pub fn with_zoom(zoom: f32) -> Self {
+        Self { zoom: zoom.clamp(MIN_ZOOM, MAX_ZOOM), ..Self::default() }
+    }
+
+    /// Back to the centered grid, e.g. when a level is loaded. Zoom and follow mode are kept.
+    pub fn reset(&mut self) {
+        self.center = None;
+        self.drag = None;
+    }
+
+    pub fn view(&self, game: &Game) -> GridView {
+        let viewport = grid_viewport();
+        let grid = vec2(game.grid.width as f32, game.grid.height as f32) * TILE;
+        let wanted = if self.follow_robot {
+            let (x, y) = robot_draw_position(game);
+            (vec2(x, y) + 0.5) * TILE
+        } else {
+            self.center.unwrap_or(grid * 0.5)
+        };
+        let visible = vec2(viewport.w, viewport.h) / self.zoom;
+        let center = vec2(clamp_axis(wanted.x, grid.x, visible.x), clamp_axis(wanted.y, grid.y, visible.y));
+        GridView { viewport, center, zoom: self.zoom }
+    }
+
+    /// Zoom by `factor`, keeping the world point under `anchor` (a screen position) in place.
+    /// Returns true if the zoom changed.
+    pub fn zoom_by(&mut self, game: &Game, factor: f32, anchor: Vec2) -> bool {
+        let view = self.view(game);
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        if zoom == self.zoom {
+            return false;
+        }
+        let world = view.screen_to_world(anchor);
+        self.center = Some(world - (anchor - view.viewport.center()) / zoom);
+        self.zoom = zoom;
+        true
+    }
+
+    /// Start, continue or end a drag that pans the view. Dragging stops following the robot.
+    pub fn drag(&mut self, game: &Game, mouse: Vec2, down: bool) {
+        if !down {
+            self.drag = None;
+            return;
+        }
+        match self.drag {
+            None => {
+                let view = self.view(game);
+                if view.viewport.contains(mouse) {
+                    self.drag = Some((mouse, view.center));
+                }
+            }
+            Some((start, start_center)) => {
+                if mouse != start {
+                    self.follow_robot = false;
+                }
+                if !self.follow_robot {
+                    self.center = Some(start_center - (mouse - start) / self.zoom);
+                }
+            }
+        }
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    pub fn toggle_follow(&mut self, game: &Game) {
+        // Stay where the robot was when following is turned off
+        self.center = Some(self.view(game).center);
+        self.follow_robot = !self.follow_robot;
+    }
+}
+
+/// Keep the view on the grid. A grid smaller than the view is centered instead.
+fn clamp_axis(center: f32, grid: f32, visible: f32) -> f32 {
+    if visible >= grid {
+        grid * 0.5
+    } else {
+        center.clamp(visible * 0.5, grid - visible * 0.5)
+    }
+}
+
+/// Zoom out, zoom level (click to reset to 100%), zoom in and follow-robot buttons in the bottom
+/// left corner of the game area
+pub struct CameraControls {
+    pub zoom_out: Rect,
+    pub reset: Rect,
+    pub zoom_in: Rect,
+    pub follow: Rect,
+}
+
+pub fn camera_controls() -> CameraControls {
+    let viewport = grid_viewport();
+    let (h, gap) = (scale_size(22.0), scale_size(4.0));
+    let y = viewport.bottom() - h - gap;
+    let mut x = viewport.x + gap;
+    let mut next = |w: f32| {
+        let rect = Rect::new(x, y, scale_size(w), h);
+        x += rect.w + gap;
+        rect
+    };
+    CameraControls { zoom_out: next(24.0), reset: next(52.0), zoom_in: next(24.0), follow: next(64.0) }
+}
+
+pub fn draw_camera_controls(game: &Game) {
+    let controls = camera_controls();
+    let (mouse_x, mouse_y) = crate::crash_protection::safe_mouse_position();
+    let mouse = vec2(mouse_x, mouse_y);
+    let zoom_label = format!("{:.0}%", game.grid_camera.zoom * 100.0);
+    let buttons = [
+        (controls.zoom_out, "-", false),
+        (controls.reset, zoom_label.as_str(), false),
+        (controls.zoom_in, "+", false),
+        (controls.follow, "Follow", game.grid_camera.follow_robot),
+    ];
+    for (rect, label, active) in buttons {
+        let fill = if active {
+            Color::new(0.2, 0.45, 0.7, 0.9)
+        } else if rect.contains(mouse) {
+            Color::new(0.3, 0.3, 0.35, 0.9)
+        } else {
+            Color::new(0.15, 0.15, 0.18, 0.8)
+        };
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, fill);
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, GRAY);
+        let dim = measure_scaled_text(label, 14.0);
+        draw_scaled_text(label, rect.x + (rect.w - dim.width) * 0.5, rect.y + (rect.h + dim.height) * 0.5, 14.0, WHITE);
+    }
+}
+
+/// Restrict drawing to `rect` (screen coordinates), or lift the restriction with None
+pub fn set_clip(rect: Option<Rect>) {
+    let dpi = screen_dpi_scale();
+    let clip = rect.map(|r| ((r.x * dpi) as i32, (r.y * dpi) as i32, (r.w * dpi) as i32, (r.h * dpi) as i32));
+    unsafe {
+        get_internal_gl().quad_gl.scissor(clip);
+    }
+}
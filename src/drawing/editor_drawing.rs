@@ -1,25 +1,15 @@
 use macroquad::prelude::*;
 use crate::gamestate::Game;
 use crate::font_scaling::*;
-
-/// Helper function to convert line/column position to absolute character position
-fn get_absolute_position(line_index: usize, col: usize, lines: &[&str]) -> usize {
-    let mut pos = 0;
-    for i in 0..line_index.min(lines.len()) {
-        pos += lines[i].len();
-        // Only add newline if there's actually a next line (not at the end of file)
-        if i < lines.len() - 1 {
-            pos += 1; // Add newline character
-        }
-    }
-    pos + col
-}
+use crate::text_buffer::TextBuffer;
+use std::collections::HashMap;
 
 /// Convert mouse coordinates to grid position (row, col)
 pub fn mouse_to_grid_position(
     mouse_x: f32, 
     mouse_y: f32, 
     editor_bounds: (f32, f32, f32, f32),
+    line_number_width: f32,
     char_width: f32,
     line_height: f32,
     scroll_offset: usize
@@ -27,7 +17,6 @@ pub fn mouse_to_grid_position(
     use crate::font_scaling::scale_size;
 
     let (editor_x, editor_y, editor_width, _editor_height) = editor_bounds;
-    let line_number_width = scale_size(28.0); // Match the actual line number width from drawing code
     let grid_start_x = editor_x + line_number_width + scale_size(4.0); // Match text_x calculation
     let grid_start_y = editor_y + scale_size(50.0) + scale_size(10.0); // Match exact drawing coordinates
     
@@ -53,20 +42,42 @@ pub fn mouse_to_grid_position(
     Some((actual_line, col))
 }
 
-/// Convert grid position to absolute cursor position in text
-pub fn grid_to_cursor_position(row: usize, col: usize, lines: &[&str]) -> usize {
-    if row >= lines.len() {
-        // Position at end of last line
-        let mut pos = 0;
-        for line in lines {
-            pos += line.len() + 1; // +1 for newline
-        }
-        return pos.saturating_sub(1); // Remove extra newline
+/// Spinner while a background job or the syntax check runs, then the check's result for the code on screen
+#[cfg(not(target_arch = "wasm32"))]
+fn draw_syntax_check_status(game: &mut Game, right_x: f32, y: f32) {
+    const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+    let frame = (get_time() * 8.0) as usize % SPINNER.len();
+    if let Some((label, running)) = game.jobs.as_ref().and_then(|jobs| jobs.status()) {
+        let status = if running > 1 { format!("{} {} (+{})", SPINNER[frame], label, running - 1) } else { format!("{} {}", SPINNER[frame], label) };
+        let width = measure_scaled_text(&status, 12.0).width;
+        draw_scaled_text(&status, right_x - width, y, 12.0, LIGHTGRAY);
+        return;
     }
-    
-    let line = lines[row];
-    let clamped_col = col.min(line.len());
-    get_absolute_position(row, clamped_col, lines)
+
+    let Some(checker) = game.rust_checker.as_mut() else { return };
+    if game.current_code.is_empty() {
+        return;
+    }
+
+    let (status, color) = if checker.is_checking() {
+        (format!("{} checking", SPINNER[frame]), LIGHTGRAY)
+    } else {
+        match checker.result_for(&game.current_code) {
+            Some(Ok(errors)) => {
+                let error_count = errors.iter().filter(|e| e.severity == crate::rust_checker::ErrorSeverity::Error).count();
+                if error_count == 0 {
+                    ("compiles".to_string(), GREEN)
+                } else {
+                    (format!("{} error{}", error_count, if error_count == 1 { "" } else { "s" }), RED)
+                }
+            }
+            Some(Err(_)) => ("check unavailable".to_string(), GRAY),
+            None => return, // Waiting for typing to pause
+        }
+    };
+
+    let width = measure_scaled_text(&status, 12.0).width;
+    draw_scaled_text(&status, right_x - width, y, 12.0, color);
 }
 
 pub fn draw_code_editor(game: &mut Game) {
@@ -88,10 +99,21 @@ pub fn draw_code_editor(game: &mut Game) {
     
     let title = "ROBOT CODE EDITOR";
     draw_scaled_text(title, editor_x, editor_y, 20.0, YELLOW);
+    #[cfg(not(target_arch = "wasm32"))]
+    draw_syntax_check_status(game, editor_x + editor_width, editor_y);
+    let markers = diagnostic_markers(game);
+    let lint_message = game.cursor_lint_message();
     
     draw_scaled_text(&format!("File: {}", game.robot_code_path), editor_x, editor_y + scale.line_height, 12.0, LIGHTGRAY);
     if game.robot_code_modified {
         draw_scaled_text("File modified externally! Changes loaded.", editor_x, editor_y + scale_size(35.0), 12.0, YELLOW);
+    } else if let Some(vim) = game.vim.as_ref() {
+        // Vim mode shows which mode the keys act in instead of the usual hint
+        let color = if vim.mode == crate::gamestate::vim::VimMode::Insert { GREEN } else { SKYBLUE };
+        draw_scaled_text(&format!("{} | Esc for normal mode | Ctrl+Shift+Enter to run", vim.status()), editor_x, editor_y + scale_size(35.0), 11.0, color);
+    } else if let Some(message) = lint_message {
+        // A lint finding on the cursor's line takes the place of the hint
+        draw_scaled_text(&format!("⚠ {}", message), editor_x, editor_y + scale_size(35.0), 11.0, YELLOW);
     } else {
         draw_scaled_text("Click to position cursor | Arrow keys navigate | Ctrl+Shift+Enter to run", editor_x, editor_y + scale_size(35.0), 11.0, GRAY);
     }
@@ -108,54 +130,79 @@ pub fn draw_code_editor(game: &mut Game) {
     draw_rectangle_lines(editor_x, input_y, editor_width, text_area_height, scale_size(1.0), WHITE);
     
     // Show current code from game state
+    let placeholder;
     let code_to_display = if game.current_code.is_empty() {
-        "// Start typing your Rust code here...\n".to_string()
+        placeholder = TextBuffer::from("// Start typing your Rust code here...\n");
+        &placeholder
     } else {
-        game.current_code.clone()
+        &game.current_code
     };
     
-    let lines: Vec<&str> = code_to_display.lines().collect();
+    // Only the visible lines are read from the buffer, so long files draw as fast as short ones
+    let line_count = code_to_display.line_count();
     let max_visible_lines = 30; // Always show 30 lines
     let chars_per_line = ((editor_width - scale_size(60.0)) / sample_char_width) as usize; // Account for line numbers
     
     // Calculate which lines to show based on scroll offset
     let start_line = game.code_scroll_offset;
-    let _end_line = (start_line + max_visible_lines).min(lines.len().max(max_visible_lines));
-    
-    // Draw line numbers
-    let line_number_width = scale_size(35.0);
-    draw_rectangle(editor_x, input_y, line_number_width, text_area_height, Color::new(0.15, 0.15, 0.2, 1.0));
-    draw_line(editor_x + line_number_width, input_y, editor_x + line_number_width, input_y + text_area_height, scale_size(1.0), DARKGRAY);
-    
-    for i in 0..max_visible_lines {
-        let line_num = start_line + i + 1;
-        let y = input_y + scale_size(12.0) + (i as f32 * line_height);
-        let color = if line_num <= lines.len() { DARKGRAY } else { Color::new(0.3, 0.3, 0.3, 1.0) };
-        draw_scaled_text(&format!("{:2}", line_num), editor_x + scale_size(3.0), y, 11.0, color);
-    }
+    let _end_line = (start_line + max_visible_lines).min(line_count.max(max_visible_lines));
     
-    // Grid-based character rendering - each character in its own cell
+    // Grid-based character rendering - each character (grapheme) in its own cell
+    let line_number_width = game.line_number_width();
     let text_x = editor_x + line_number_width + scale_size(5.0);
-    let char_width = game.get_cached_char_width();
+    let char_width = sample_char_width;
     let char_height = line_height;
     
-    // Calculate grid dimensions
+    // Calculate grid dimensions; the rows depend on how many columns fit when lines wrap
     let max_cols = ((editor_width - line_number_width - scale_size(20.0)) / char_width) as usize;
+    game.code_columns_visible = max_cols.max(1);
+    let rows = game.visible_rows(max_visible_lines);
     let grid_start_x = text_x;
     let grid_start_y = input_y + scale_size(12.0);
+
+    // Draw line numbers, unless turned off in the editor settings; wrapped rows go unnumbered.
+    // The cursor's line number is brighter and lines with syntax check reports get a dot.
+    let cursor_line = game.current_code.line_of(game.cursor_position);
+    if line_number_width > 0.0 {
+        draw_rectangle(editor_x, input_y, line_number_width, text_area_height, Color::new(0.15, 0.15, 0.2, 1.0));
+        draw_line(editor_x + line_number_width, input_y, editor_x + line_number_width, input_y + text_area_height, scale_size(1.0), DARKGRAY);
+
+        for (i, (line_index, first_col)) in rows.iter().enumerate() {
+            if game.wraps_lines() && *first_col > 0 {
+                continue;
+            }
+            let line_num = line_index + 1;
+            let y = input_y + scale_size(12.0) + (i as f32 * line_height);
+            let color = if game.code_editor_active && *line_index == cursor_line {
+                LIGHTGRAY
+            } else if line_num <= line_count {
+                DARKGRAY
+            } else {
+                Color::new(0.3, 0.3, 0.3, 1.0)
+            };
+            draw_scaled_text(&format!("{:2}", line_num), editor_x + scale_size(3.0), y, 11.0, color);
+            if let Some(marker) = markers.get(line_index) {
+                draw_circle(editor_x + line_number_width - scale_size(5.0), y - scale_size(4.0), scale_size(3.0), *marker);
+            }
+        }
+    }
     
     // Draw character grid
-    for row in 0..max_visible_lines {
-        let line_index = start_line + row;
+    for (row, &(line_index, first_col)) in rows.iter().enumerate() {
         let grid_y = grid_start_y + (row as f32 * char_height);
         
-        if line_index < lines.len() {
-            let line = lines[line_index];
-            let chars: Vec<char> = line.chars().collect();
+        if line_index < line_count {
+            // Highlight current line if cursor is on it
+            if game.code_editor_active && line_index == cursor_line {
+                draw_rectangle(grid_start_x, grid_y - scale_size(10.0), max_cols as f32 * char_width, char_height, Color::new(0.2, 0.2, 0.3, 0.3));
+            }
+
+            let graphemes = code_to_display.line_graphemes(line_index);
             
             // Draw each character in its own grid cell
-            for col in 0..max_cols {
-                let grid_x = grid_start_x + (col as f32 * char_width);
+            for cell in 0..max_cols {
+                let col = first_col + cell;
+                let grid_x = grid_start_x + (cell as f32 * char_width);
                 let char_rect = macroquad::prelude::Rect {
                     x: grid_x,
                     y: grid_y - scale_size(10.0),
@@ -164,7 +211,7 @@ pub fn draw_code_editor(game: &mut Game) {
                 };
                 
                 // Check if this position is selected
-                let absolute_pos = get_absolute_position(line_index, col, &lines);
+                let absolute_pos = code_to_display.byte_at(line_index, col);
 
                 // Enhanced selection detection - check raw selection values too
                 let is_selected = if let Some((sel_start, sel_end)) = game.get_selection_bounds() {
@@ -199,10 +246,8 @@ pub fn draw_code_editor(game: &mut Game) {
                                  Color::new(0.3, 0.5, 1.0, 0.8)); // Brighter blue with higher opacity
                 }
                 
-                // Draw character if it exists
-                if col < chars.len() {
-                    let ch = chars[col];
-                    let char_str = ch.to_string();
+                // Draw character if it exists; a tab takes one blank cell
+                if let Some(char_str) = graphemes.get(col).filter(|g| **g != "\t") {
                     let text_color = if game.code_editor_active {
                         if is_selected { WHITE } else { WHITE }
                     } else {
@@ -213,23 +258,20 @@ pub fn draw_code_editor(game: &mut Game) {
                     let char_x = char_rect.x + (char_rect.w - char_width) * 0.5;
                     let char_y = char_rect.y + char_rect.h - scale_size(3.0);
                     
-                    draw_scaled_text(&char_str, char_x, char_y, 12.0, text_color);
+                    draw_scaled_text(char_str, char_x, char_y, 12.0, text_color);
                 }
             }
         }
     }
 
     // Calculate cursor position for both cursor drawing and autocomplete
-    let cursor_line = game.current_code[..game.cursor_position].matches('\n').count();
-    let line_start = game.current_code[..game.cursor_position].rfind('\n').map(|i| i + 1).unwrap_or(0);
-    let cursor_col = game.cursor_position - line_start;
+    let cursor_cell = game.cursor_cell(&rows);
 
     // Draw cursor when active - now grid-based
     if game.code_editor_active {
         // Show cursor if it's in the visible area
-        if cursor_line >= start_line && cursor_line < start_line + max_visible_lines {
-            let visible_row = cursor_line - start_line;
-            let cursor_x = grid_start_x + (cursor_col as f32 * char_width);
+        if let Some((visible_row, visible_col)) = cursor_cell {
+            let cursor_x = grid_start_x + (visible_col as f32 * char_width);
             let cursor_y = grid_start_y + (visible_row as f32 * char_height);
 
             // Draw blinking cursor as a vertical line in the grid cell
@@ -248,15 +290,14 @@ pub fn draw_code_editor(game: &mut Game) {
     }
 
     // Draw autocomplete suggestion
-    draw_autocomplete_suggestion(game, cursor_line, cursor_col, start_line, max_visible_lines,
-                                grid_start_x, grid_start_y, char_width, char_height);
+    draw_autocomplete_suggestion(game, cursor_cell, grid_start_x, grid_start_y, char_width, char_height);
 
     // Draw scroll indicator if there are more lines than visible
-    if lines.len() > max_visible_lines {
+    if line_count > max_visible_lines {
         let scroll_bar_x = editor_x + editor_width - 12.0;
         let scroll_bar_height = text_area_height - 4.0;
-        let thumb_height = (max_visible_lines as f32 / lines.len() as f32 * scroll_bar_height).max(10.0);
-        let thumb_y = input_y + 2.0 + (start_line as f32 / lines.len() as f32 * scroll_bar_height);
+        let thumb_height = (max_visible_lines as f32 / line_count as f32 * scroll_bar_height).max(10.0);
+        let thumb_y = input_y + 2.0 + (start_line as f32 / line_count as f32 * scroll_bar_height);
         
         // Scroll bar background
         draw_rectangle(scroll_bar_x, input_y + 2.0, 8.0, scroll_bar_height, Color::new(0.2, 0.2, 0.2, 0.8));
@@ -264,7 +305,12 @@ pub fn draw_code_editor(game: &mut Game) {
         draw_rectangle(scroll_bar_x, thumb_y, 8.0, thumb_height, Color::new(0.6, 0.6, 0.6, 0.9));
         draw_rectangle_lines(scroll_bar_x, thumb_y, 8.0, thumb_height, 1.0, GRAY);
     }
+
+    draw_horizontal_scrollbar(game, &rows, grid_start_x, input_y + text_area_height - 10.0, max_cols as f32 * char_width);
     
+    draw_clipboard_picker(game, editor_x, input_y, editor_width);
+    draw_goto_line_prompt(game, editor_x, input_y, editor_width);
+
     // Draw buttons at the bottom
     draw_editor_buttons(editor_x, input_y + text_area_height + 10.0);
     
@@ -272,6 +318,103 @@ pub fn draw_code_editor(game: &mut Game) {
     draw_execution_results(game, editor_x, input_y + text_area_height + 50.0);
 }
 
+/// Bar under the text while a visible line is wider than the text area and word wrap is off,
+/// its thumb showing which columns are on screen
+pub fn draw_horizontal_scrollbar(game: &Game, rows: &[(usize, usize)], x: f32, y: f32, width: f32) {
+    if game.wraps_lines() {
+        return;
+    }
+    let widest = rows.iter().map(|(line, _)| game.current_code.line_len(*line)).max().unwrap_or(0);
+    let columns = game.code_columns_visible;
+    if widest <= columns && game.code_scroll_column == 0 {
+        return;
+    }
+    let total = widest.max(game.code_scroll_column + columns) as f32;
+    let thumb_width = (columns as f32 / total * width).max(10.0);
+    let thumb_x = x + game.code_scroll_column as f32 / total * width;
+
+    draw_rectangle(x, y, width, 6.0, Color::new(0.2, 0.2, 0.2, 0.8));
+    draw_rectangle(thumb_x, y, thumb_width, 6.0, Color::new(0.6, 0.6, 0.6, 0.9));
+    draw_rectangle_lines(thumb_x, y, thumb_width, 6.0, 1.0, GRAY);
+}
+
+/// Lines the last syntax check and lint pass of the code on screen reported on, counted from 0,
+/// each with the color of its worst report: red for errors, yellow for warnings and lint findings
+/// and blue for help
+pub fn diagnostic_markers(game: &mut Game) -> HashMap<usize, Color> {
+    let mut markers: HashMap<usize, Color> = game.lint_diagnostics().iter()
+        .map(|diagnostic| (diagnostic.line.saturating_sub(1), YELLOW))
+        .collect();
+    syntax_check_markers(game, &mut markers);
+    markers
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn syntax_check_markers(game: &mut Game, markers: &mut HashMap<usize, Color>) {
+    use crate::rust_checker::ErrorSeverity;
+
+    let Some(checker) = game.rust_checker.as_mut() else { return };
+    let Some(Ok(errors)) = checker.result_for(&game.current_code) else { return };
+    for error in errors {
+        let line = error.line.saturating_sub(1);
+        let color = match error.severity {
+            ErrorSeverity::Error => RED,
+            ErrorSeverity::Warning => YELLOW,
+            ErrorSeverity::Help => SKYBLUE,
+        };
+        // An error on the line wins over warnings and help
+        if error.severity == ErrorSeverity::Error || !markers.contains_key(&line) {
+            markers.insert(line, color);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn syntax_check_markers(_game: &mut Game, _markers: &mut HashMap<usize, Color>) {
+    // No background syntax check in the web version
+}
+
+/// The Ctrl+G prompt, over the top of the text area
+pub fn draw_goto_line_prompt(game: &Game, editor_x: f32, top_y: f32, editor_width: f32) {
+    let Some(input) = game.goto_line_input.as_ref() else { return };
+    let height = scale_size(28.0);
+    let caret = if (crate::crash_protection::safe_get_time() * 2.0) as i64 % 2 == 0 { "_" } else { " " };
+
+    draw_rectangle(editor_x, top_y, editor_width, height, Color::new(0.08, 0.08, 0.14, 0.97));
+    draw_rectangle_lines(editor_x, top_y, editor_width, height, scale_size(2.0), SKYBLUE);
+    draw_scaled_text(&format!("Go to line (1-{}): {}{}  Enter to jump, Esc to cancel", game.current_code.line_count(), input, caret),
+                     editor_x + scale_size(8.0), top_y + height * 0.7, 12.0, SKYBLUE);
+}
+
+/// The Ctrl+Shift+V list of recent cuts and copies, over the top of the text area
+pub fn draw_clipboard_picker(game: &Game, editor_x: f32, top_y: f32, editor_width: f32) {
+    let Some(selected) = game.clipboard_history.picker else { return };
+    let row_height = scale_size(22.0);
+    let entries: Vec<&String> = game.clipboard_history.entries().collect();
+    let height = row_height * (entries.len() as f32 + 1.5);
+    let max_chars = ((editor_width - scale_size(60.0)) / scale_size(7.0)).max(10.0) as usize;
+
+    draw_rectangle(editor_x, top_y, editor_width, height, Color::new(0.08, 0.08, 0.14, 0.97));
+    draw_rectangle_lines(editor_x, top_y, editor_width, height, scale_size(2.0), SKYBLUE);
+    draw_scaled_text("CLIPBOARD HISTORY - Up/Down, Enter or 1-0 to paste, Esc to close",
+                     editor_x + scale_size(8.0), top_y + row_height * 0.8, 12.0, SKYBLUE);
+
+    for (i, entry) in entries.iter().enumerate() {
+        let y = top_y + row_height * (i as f32 + 1.0);
+        if i == selected {
+            draw_rectangle(editor_x + scale_size(2.0), y + scale_size(4.0), editor_width - scale_size(4.0), row_height, Color::new(0.2, 0.3, 0.5, 0.9));
+        }
+        // One line per entry: its first line, and how many more there are
+        let lines = entry.lines().count();
+        let mut preview: String = entry.lines().next().unwrap_or("").trim().chars().take(max_chars).collect();
+        if lines > 1 {
+            preview.push_str(&format!("  (+{} lines)", lines - 1));
+        }
+        draw_scaled_text(&format!("{}  {}", (i + 1) % 10, preview), editor_x + scale_size(8.0), y + row_height * 0.8, 12.0,
+                         if i == selected { WHITE } else { LIGHTGRAY });
+    }
+}
+
 fn draw_editor_buttons(editor_x: f32, button_y: f32) {
     let button_width = scale_size(140.0);
     let button_height = scale_size(30.0);
@@ -321,10 +464,7 @@ fn draw_execution_results(game: &Game, editor_x: f32, result_y: f32) {
 
 fn draw_autocomplete_suggestion(
     game: &Game,
-    cursor_line: usize,
-    cursor_col: usize,
-    start_line: usize,
-    max_visible_lines: usize,
+    cursor_cell: Option<(usize, usize)>, // Row and column of the cursor on screen
     grid_start_x: f32,
     grid_start_y: f32,
     char_width: f32,
@@ -337,8 +477,7 @@ fn draw_autocomplete_suggestion(
 
     if let Some(suggestion) = game.get_autocomplete_suggestion() {
         // Check if cursor is in visible area
-        if cursor_line >= start_line && cursor_line < start_line + max_visible_lines {
-            let visible_row = cursor_line - start_line;
+        if let Some((visible_row, visible_col)) = cursor_cell {
 
             // Get the current word at cursor to know what to replace
             let current_word = get_current_word_at_cursor_position(&game.current_code, game.cursor_position);
@@ -351,7 +490,7 @@ fn draw_autocomplete_suggestion(
             };
 
             if !completion_text.is_empty() {
-                let suggestion_x = grid_start_x + (cursor_col as f32 * char_width);
+                let suggestion_x = grid_start_x + (visible_col as f32 * char_width);
                 let suggestion_y = grid_start_y + (visible_row as f32 * char_height);
 
                 // Draw subtle background for the suggestion text (50% opacity)
@@ -394,25 +533,18 @@ fn draw_autocomplete_suggestion(
     }
 }
 
-fn get_current_word_at_cursor_position(code: &str, cursor_position: usize) -> String {
-    let chars: Vec<char> = code.chars().collect();
-    let mut start = cursor_position;
-
-    // Find start of current word
-    while start > 0 {
-        let prev_char = chars[start - 1];
-        if prev_char.is_alphanumeric() || prev_char == '_' {
-            start -= 1;
-        } else {
-            break;
-        }
-    }
-
-    if start < cursor_position {
-        chars[start..cursor_position].iter().collect()
-    } else {
-        String::new()
-    }
+fn get_current_word_at_cursor_position(code: &TextBuffer, cursor_position: usize) -> String {
+    // Words don't span lines, so only the cursor's line is read
+    let cursor = code.floor_char_boundary(cursor_position);
+    let line = code.line_of(cursor);
+    let text = code.line_text(line);
+    let before_cursor = &text[..(cursor - code.byte_at(line, 0)).min(text.len())];
+    let word: Vec<char> = before_cursor
+        .chars()
+        .rev()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+    word.into_iter().rev().collect()
 }
 
 
@@ -1,10 +1,15 @@
 use macroquad::prelude::*;
 use crate::gamestate::Game;
+use crate::gamestate::sandbox::SandboxTool;
 use crate::item::Pos;
 use crate::level::EnemyDirection;
 use crate::font_scaling::*;
+use crate::menu::ExecutionSpeed;
+use super::camera::{set_clip, GridView};
+use std::cell::RefCell;
+use std::collections::HashSet;
 
-const TILE: f32 = 42.0;
+pub const TILE: f32 = 42.0;
 
 // Helper function to wrap text with dynamic font sizing and scrolling support
 fn calculate_wrapped_text_dimensions(text: &str, initial_font_size: f32, max_width: f32, max_height: f32) -> (Vec<String>, f32, f32, f32) {
@@ -74,53 +79,77 @@ fn calculate_wrapped_text_dimensions(text: &str, initial_font_size: f32, max_wid
     }
 }
 
+/// Screen position of the grid's top-left corner, wherever the camera has put it
 pub fn grid_origin(g: &Game) -> (f32, f32) {
-    let gw = g.grid.width as f32 * TILE;
-    let gh = g.grid.height as f32 * TILE;
-    
-    // Calculate available width dynamically based on single sidebar layout
-    // We now have only the tabbed sidebar (Commands/Logs/Tasks/Editor)
-    let sidebar_width = crate::crash_protection::safe_screen_width() * 0.25; // Tabbed sidebar on the right
-    let padding = scale_size(10.0);
-    
-    // Available width is screen minus sidebar with padding
-    let available_width = crate::crash_protection::safe_screen_width() - sidebar_width - (padding * 2.0);
-    
-    // Center the grid in the available space (left side of screen)
-    let ox = (available_width - gw) * 0.5 + padding;
-    
-    // Center the grid vertically with some space for header
-    let header_height = scale_size(100.0); // Space for game info at top
-    let available_height = crate::crash_protection::safe_screen_height() - header_height - padding;
-    let oy = header_height + (available_height - gh) * 0.5; // Center vertically
-    
-    (ox, oy)
+    let origin = g.grid_camera.view(g).world_to_screen(Vec2::ZERO);
+    (origin.x, origin.y)
 }
 
 fn tile_rect(ox: f32, oy: f32, p: Pos) -> Rect {
     Rect { x: ox + p.x as f32 * TILE, y: oy + p.y as f32 * TILE, w: TILE - 1.0, h: TILE - 1.0 }
 }
 
-pub fn draw_game(game: &Game) {
-    let (ox, oy) = grid_origin(game);
-
+/// Everything that only changes when the player acts: floor, fog, walls, doors, crates,
+/// hazards and items
+fn draw_static_tiles(game: &Game, ox: f32, oy: f32) {
+    let colors = game.menu.settings.palette.grid_colors();
+    // Beams shown are the ones live once the next action ends, like the spikes
+    let beams = game.grid.active_beams(game.turns + 1);
     for y in 0..game.grid.height {
         for x in 0..game.grid.width {
             let p = Pos { x, y };
             let r = tile_rect(ox, oy, p);
 
-            draw_rectangle(r.x, r.y, r.w, r.h, BLACK);
+            draw_rectangle(r.x, r.y, r.w, r.h, colors.unexplored);
 
             let known = game.grid.known.contains(&p);
             if known {
-                draw_rectangle(r.x+2.0, r.y+2.0, r.w-4.0, r.h-4.0, GREEN);
+                draw_rectangle(r.x+2.0, r.y+2.0, r.w-4.0, r.h-4.0, colors.floor);
+                if let Some(terrain) = game.grid.terrain_at(p) {
+                    draw_terrain(r, terrain);
+                }
+
+                // Pressure plates sit flat on the floor and light up while weighted
+                if let Some(plate) = game.grid.plate_at(p) {
+                    let color = if plate.pressed { GOLD } else { DARKGRAY };
+                    draw_rectangle(r.x + r.w * 0.2, r.y + r.h * 0.2, r.w * 0.6, r.h * 0.6, color);
+                    draw_rectangle_lines(r.x + r.w * 0.2, r.y + r.h * 0.2, r.w * 0.6, r.h * 0.6, 2.0, BLACK);
+                }
+
+                if let Some(switch) = game.grid.switch_at(p) {
+                    draw_switch(r, switch.on);
+                }
+                if let Some(direction) = game.grid.conveyor_at(p) {
+                    draw_conveyor(r, direction);
+                }
+                if let Some(mirror) = game.grid.mirrors.get(&p) {
+                    draw_mirror(r, *mirror);
+                }
+                if game.grid.spikes.contains_key(&p) {
+                    // Spikes shown raised are deadly once the next action ends
+                    draw_spikes(r, game.grid.spikes_active(p, game.turns + 1));
+                }
+                for (_, direction) in beams.iter().filter(|(tile, _)| *tile == p) {
+                    draw_laser_beam(r, *direction);
+                }
+                if let Some(spawner) = game.grid.spawner_at(p) {
+                    draw_spawner(r, spawner.spawned < spawner.max);
+                }
             }
 
-            if game.grid.is_blocked(p) && known {
+            if game.grid.is_crate(p) && known {
+                let inset = r.w * 0.12;
+                draw_rectangle(r.x + inset, r.y + inset, r.w - inset * 2.0, r.h - inset * 2.0, BROWN);
+                draw_rectangle_lines(r.x + inset, r.y + inset, r.w - inset * 2.0, r.h - inset * 2.0, 2.0, DARKBROWN);
+                draw_line(r.x + inset, r.y + inset, r.x + r.w - inset, r.y + r.h - inset, 2.0, DARKBROWN);
+                draw_line(r.x + r.w - inset, r.y + inset, r.x + inset, r.y + r.h - inset, 2.0, DARKBROWN);
+            } else if game.grid.is_blocked(p) && known {
                 // Check if it's a door
                 if game.grid.is_door(p) {
                     let (txt, color) = if game.grid.is_door_open(p) {
                         ("|", GREEN)  // Open door - green vertical line
+                    } else if let Some(lock) = game.grid.locked_door_color(p) {
+                        ("█", key_color(lock))  // Locked door - block in the color of its key
                     } else {
                         ("█", BROWN)  // Closed door - brown block
                     };
@@ -134,6 +163,8 @@ pub fn draw_game(game: &Game) {
                         font_size,
                         color,
                     );
+                } else if let Some(emitter) = game.grid.emitter_at(p) {
+                    draw_emitter(r, emitter.direction, game.grid.emitter_firing(emitter, game.turns + 1));
                 } else {
                     // Regular obstacle
                     let txt = "?";
@@ -145,63 +176,682 @@ pub fn draw_game(game: &Game) {
                         r.x + (r.w - dim.width) * 0.5,
                         r.y + (r.h + dim.height) * 0.5 - scale_size(6.0),
                         font_size,
-                        WHITE,
+                        colors.obstacle,
                     );
                 }
             }
 
             // Draw items
-            if known {
-                if let Some(_item) = game.item_manager.get_item_at_position(p) {
-                    let txt = "!";
-                    let font_size = 28.0;
-                    let scaled_font_size = scale_font_size(font_size);
-                    let dim = measure_text(txt, None, scaled_font_size as u16, 1.0);
-                    draw_scaled_text(
-                        txt,
-                        r.x + (r.w - dim.width) * 0.5,
-                        r.y + (r.h + dim.height) * 0.5 - scale_size(6.0),
-                        font_size,
-                        WHITE,
-                    );
+            if known && let Some(item) = game.item_manager.get_item_at_position(p) {
+                if let Some(color) = crate::level::KeyColor::of_key_item(&item.name) {
+                    draw_key(r, key_color(color));
+                    continue;
                 }
+                let txt = "!";
+                let font_size = 28.0;
+                let scaled_font_size = scale_font_size(font_size);
+                let dim = measure_text(txt, None, scaled_font_size as u16, 1.0);
+                draw_scaled_text(
+                    txt,
+                    r.x + (r.w - dim.width) * 0.5,
+                    r.y + (r.h + dim.height) * 0.5 - scale_size(6.0),
+                    font_size,
+                    colors.item,
+                );
             }
+        }
+    }
+}
 
-            // Draw enemies (including special robots for learning levels)
-            let should_show_enemies = known || game.should_show_special_robots_at(p);
-
-            if should_show_enemies {
-                for enemy in &game.grid.enemies {
-                    if enemy.pos == p {
-                        // Special rendering based on learning level and robot type
-                        let (txt, font_size) = game.get_robot_symbol_for_level(enemy);
-
-                        let scaled_font_size = scale_font_size(font_size);
-                        let dim = measure_text(txt, None, scaled_font_size as u16, 1.0);
-
-                        // Determine enemy color based on level and robot type
-                        let enemy_color = game.get_robot_color_for_level(enemy);
-
-                        draw_scaled_text(
-                            txt,
-                            r.x + (r.w - dim.width) * 0.5,
-                            r.y + (r.h + dim.height) * 0.5 - scale_size(6.0),
-                            font_size,
-                            enemy_color,
-                        );
-                        break;
-                    }
+/// What the cached grid layer was drawn from. Static tiles only change when the player acts
+/// (turns, reveals, robot moves, doors) or the level is (re)loaded.
+#[derive(PartialEq)]
+struct GridLayerKey {
+    level_loads: u64,
+    size: (i32, i32),
+    turns: usize,
+    known: usize,
+    robots: Vec<Pos>,
+    open_doors: usize,
+    blockers: usize, // Sandbox walls come and go between turns
+    items_left: usize,
+    font_size: u32,
+    palette: crate::theme::Palette,
+}
+
+impl GridLayerKey {
+    fn of(game: &Game) -> Self {
+        Self {
+            level_loads: game.level_loads,
+            size: (game.grid.width, game.grid.height),
+            turns: game.turns,
+            known: game.grid.known.len(),
+            robots: game.robot_positions(),
+            open_doors: game.grid.open_doors.len(),
+            blockers: game.grid.blockers.len(),
+            items_left: game.item_manager.items.iter().filter(|item| !item.collected).count(),
+            font_size: scale_font_size(28.0).to_bits(),
+            palette: game.menu.settings.palette,
+        }
+    }
+}
+
+struct GridLayer {
+    key: GridLayerKey,
+    target: RenderTarget,
+}
+
+thread_local! {
+    static GRID_LAYER: RefCell<Option<GridLayer>> = const { RefCell::new(None) };
+}
+
+/// Draw the static tiles from a render target, redrawing it only when they changed. Big grids
+/// would otherwise cost several draw calls per tile every frame.
+fn draw_grid_layer(game: &Game, view: &GridView) {
+    let key = GridLayerKey::of(game);
+    let (w, h) = (game.grid.width as f32 * TILE, game.grid.height as f32 * TILE);
+
+    GRID_LAYER.with(|layer| {
+        let mut layer = layer.borrow_mut();
+        if layer.as_ref().is_none_or(|cached| cached.key != key) {
+            let target = match layer.take() {
+                Some(cached) if cached.key.size == key.size => cached.target,
+                _ => {
+                    let target = render_target(w as u32, h as u32);
+                    target.texture.set_filter(FilterMode::Nearest);
+                    target
                 }
+            };
+            let mut camera = Camera2D::from_display_rect(Rect::new(0.0, 0.0, w, h));
+            camera.render_target = Some(target.clone());
+            set_clip(None);
+            set_camera(&camera);
+            clear_background(BLANK);
+            draw_static_tiles(game, 0.0, 0.0);
+            set_camera(&view.camera());
+            set_clip(Some(view.viewport));
+            *layer = Some(GridLayer { key, target });
+        }
+
+        if let Some(cached) = layer.as_ref() {
+            draw_texture_ex(&cached.target.texture, 0.0, 0.0, WHITE, DrawTextureParams {
+                dest_size: Some(vec2(w, h)),
+                flip_y: true, // Render targets come out upside down
+                ..Default::default()
+            });
+        }
+    });
+}
+
+pub fn draw_game(game: &Game) {
+    // Everything on the grid is drawn in world space; the camera puts it on screen
+    let view = game.grid_camera.view(game);
+    set_camera(&view.camera());
+    set_clip(Some(view.viewport));
+    let (ox, oy) = (0.0, 0.0);
+    draw_grid_layer(game, &view);
+
+    // Enemies (including special robots for learning levels), one per tile
+    let mut drawn = HashSet::new();
+    for (index, enemy) in game.grid.enemies.iter().enumerate().filter(|(_, enemy)| !enemy.is_boss()) {
+        let p = enemy.pos;
+        if !(game.grid.known.contains(&p) || game.should_show_special_robots_at(p)) || !drawn.insert(p) {
+            continue;
+        }
+        let r = tile_rect(ox, oy, p);
+
+        // Special rendering based on learning level and robot type
+        let (txt, font_size) = game.get_robot_symbol_for_level(enemy);
+
+        let scaled_font_size = scale_font_size(font_size);
+        let dim = measure_text(txt, None, scaled_font_size as u16, 1.0);
+
+        // Determine enemy color based on level and robot type
+        let enemy_color = game.get_robot_color_for_level(enemy);
+
+        draw_scaled_text(
+            txt,
+            r.x + (r.w - dim.width) * 0.5,
+            r.y + (r.h + dim.height) * 0.5 - scale_size(6.0),
+            font_size,
+            enemy_color,
+        );
+        draw_health_bar(r, enemy);
+        if game.grid.stun_turns(index).is_some() {
+            draw_stun_sparks(r);
+        }
+    }
+
+    // Bosses span several tiles, so they're drawn over the finished grid
+    for enemy in game.grid.enemies.iter().filter(|enemy| enemy.is_boss()) {
+        if enemy.tiles_at(enemy.pos).iter().any(|tile| game.grid.known.contains(tile)) {
+            draw_boss(tile_rect(ox, oy, enemy.pos), enemy);
+        }
+    }
+
+    // Idle robots on multi-robot levels, labelled with their index
+    let radius = (TILE * 0.35).min(16.0);
+    draw_objective_markers(game, ox, oy);
+
+    if game.is_multi_robot() {
+        for (index, pos) in game.robot_positions().into_iter().enumerate() {
+            if index == game.active_robot {
+                continue;
             }
+            let r = tile_rect(ox, oy, pos);
+            let (cx, cy) = (r.x + r.w * 0.5, r.y + r.h * 0.5);
+            draw_circle(cx, cy, radius, ROBOT_COLORS[index % ROBOT_COLORS.len()]);
+            draw_facing(cx, cy, radius, game.robots[index].facing);
+            draw_text(&index.to_string(), cx - 4.0, cy + 5.0, 16.0, BLACK);
         }
     }
 
-    // Robot circle
+    draw_race_ghost(game, ox, oy, radius);
+
+    // Robot circle, part way between tiles while a move is animated
+    let (tx, ty) = robot_draw_position(game);
+    let cx = ox + tx * TILE + (TILE - 1.0) * 0.5;
+    let cy = oy + ty * TILE + (TILE - 1.0) * 0.5;
+    draw_circle(cx, cy, radius, game.menu.settings.palette.grid_colors().robot);
+    draw_facing(cx, cy, radius, game.robot.facing);
+    if game.is_multi_robot() {
+        draw_circle_lines(cx, cy, radius + 3.0, 2.0, WHITE);
+        draw_text(&game.active_robot.to_string(), cx - 4.0, cy + 5.0, 16.0, BLACK);
+    }
+
+    draw_teleport_effect(game, ox, oy);
+    draw_emp_effect(game, ox, oy);
+    draw_laser_path(game, ox, oy);
+    draw_tutorial_ghost(game, ox, oy);
+    draw_speech_bubble(game, ox, oy, radius);
+
+    if let Some(p) = hovered_tile(game, &view) {
+        let r = tile_rect(ox, oy, p);
+        draw_rectangle_lines(r.x, r.y, r.w, r.h, 2.0, WHITE);
+    }
+
+    set_default_camera();
+    set_clip(None);
+}
+
+/// Tile under the mouse, unless the view is being dragged or something else has the screen
+fn hovered_tile(game: &Game, view: &GridView) -> Option<Pos> {
+    if game.grid_camera.is_dragging() || game.popup_system.is_showing() || game.solution_comparison.is_some() {
+        return None;
+    }
+    let (mouse_x, mouse_y) = crate::crash_protection::safe_mouse_position();
+    view.screen_to_tile(vec2(mouse_x, mouse_y), game)
+}
+
+/// What the hover inspector says about a tile. Unexplored tiles give nothing away.
+fn describe_tile(game: &Game, p: Pos) -> Vec<String> {
+    let mut lines = vec![format!("Tile ({}, {})", p.x, p.y)];
+    let known = game.grid.known.contains(&p);
+    lines.push(match (known, game.grid.visited.contains(&p)) {
+        (false, _) => "Unexplored".to_string(),
+        (true, true) => "Explored, visited".to_string(),
+        (true, false) => "Explored, not visited".to_string(),
+    });
+
+    for (index, pos) in game.robot_positions().into_iter().enumerate() {
+        if pos == p {
+            lines.push(if game.is_multi_robot() { format!("Robot {}", index) } else { "Robot".to_string() });
+        }
+    }
+    if !known && !game.should_show_special_robots_at(p) {
+        return lines;
+    }
+
+    if known {
+        if game.grid.is_crate(p) {
+            lines.push("Crate (pushable)".to_string());
+        } else if game.grid.is_door(p) {
+            lines.push(match game.grid.locked_door_color(p) {
+                Some(color) => format!("Door (locked, needs the {} key)", color.name()),
+                None => if game.grid.is_door_open(p) { "Door (open)" } else { "Door (closed)" }.to_string(),
+            });
+        } else if game.grid.mirrors.contains_key(&p) {
+            lines.push("Mirror (blocks movement, reflects lasers)".to_string());
+        } else if let Some(emitter) = game.grid.describe_emitter(p, game.turns) {
+            lines.push(format!("Wall-mounted {}", emitter));
+        } else if game.grid.is_blocked(p) {
+            lines.push("Obstacle".to_string());
+        }
+        if let Some(plate) = game.grid.plate_at(p) {
+            let state = if plate.pressed { "pressed" } else { "not pressed" };
+            lines.push(format!("Pressure plate, {} ({} door(s))", state, plate.doors.len()));
+        }
+        if let Some(switch) = game.grid.switch_at(p) {
+            let state = if switch.on { "on" } else { "off" };
+            lines.push(format!("Switch '{}', {} ({} door(s))", switch.id, state, switch.targets.len()));
+        }
+        if let Some(hazard) = game.grid.describe_hazard(p, game.turns) {
+            lines.push(format!("Hazard: {}", hazard));
+        }
+        if let Some(spawner) = game.grid.describe_spawner(p) {
+            lines.push(format!("Hazard: {}", spawner));
+        }
+        if let Some(item) = game.item_manager.get_item_at_position(p) {
+            lines.push(format!("Item: {}", item.name));
+        }
+    }
+
+    for (index, enemy) in game.grid.enemies.iter().enumerate().filter(|(_, enemy)| enemy.occupies(p)) {
+        let kind = if enemy.is_boss() { "Boss" } else { "Enemy" };
+        lines.push(format!("{} #{}: {}", kind, index, enemy_movement(enemy)));
+        if let Some(hp) = enemy.hp {
+            lines.push(format!("  HP {}/{}", hp, enemy.max_hp));
+        }
+        if let Some(turns) = game.grid.stun_turns(index) {
+            lines.push(format!("  Stunned for {} more turn(s)", turns));
+        }
+    }
+    lines
+}
+
+/// Movement pattern of an enemy and which way it's heading
+fn enemy_movement(enemy: &crate::grid::Enemy) -> String {
+    if let Some(pattern) = &enemy.movement_pattern {
+        return format!("{} movement", pattern);
+    }
+    let heading = match (&enemy.direction, enemy.moving_positive) {
+        (EnemyDirection::Horizontal, true) => "right",
+        (EnemyDirection::Horizontal, false) => "left",
+        (EnemyDirection::Vertical, true) => "down",
+        (EnemyDirection::Vertical, false) => "up",
+    };
+    let axis = match enemy.direction {
+        EnemyDirection::Horizontal => "horizontal",
+        EnemyDirection::Vertical => "vertical",
+    };
+    format!("{} patrol, moving {}", axis, heading)
+}
+
+/// Tooltip next to the mouse describing the tile under it
+pub fn draw_tile_inspector(game: &Game) {
+    let view = game.grid_camera.view(game);
+    let Some(p) = hovered_tile(game, &view) else { return };
+    let lines = describe_tile(game, p);
+
+    let font_size = 14.0;
+    let line_height = scale_size(18.0);
+    let padding = scale_size(8.0);
+    let width = lines.iter().map(|line| measure_scaled_text(line, font_size).width).fold(0.0, f32::max) + padding * 2.0;
+    let height = lines.len() as f32 * line_height + padding * 2.0;
+
+    // Below and right of the cursor, flipped to stay on screen
+    let (mouse_x, mouse_y) = crate::crash_protection::safe_mouse_position();
+    let (screen_w, screen_h) = (crate::crash_protection::safe_screen_width(), crate::crash_protection::safe_screen_height());
+    let mut x = mouse_x + scale_size(16.0);
+    let mut y = mouse_y + scale_size(16.0);
+    if x + width > screen_w {
+        x = (mouse_x - width - scale_size(8.0)).max(0.0);
+    }
+    if y + height > screen_h {
+        y = (mouse_y - height - scale_size(8.0)).max(0.0);
+    }
+
+    draw_rectangle(x, y, width, height, Color::new(0.05, 0.05, 0.08, 0.92));
+    draw_rectangle_lines(x, y, width, height, 1.0, GRAY);
+    for (i, line) in lines.iter().enumerate() {
+        let color = if i == 0 { YELLOW } else { WHITE };
+        draw_scaled_text(line, x + padding, y + padding + line_height * (i as f32 + 0.75), font_size, color);
+    }
+}
+
+/// Tile coordinates the robot is drawn at, fractional while a move is animated
+pub fn robot_draw_position(game: &Game) -> (f32, f32) {
     let robot_pos = game.robot.get_pos();
-    let rr = tile_rect(ox, oy, robot_pos);
-    let cx = rr.x + rr.w * 0.5;
-    let cy = rr.y + rr.h * 0.5;
-    draw_circle(cx, cy, (TILE * 0.35).min(16.0), SKYBLUE);
+    match game.robot_animation {
+        Some(animation) if animation.to == robot_pos => animation.position(crate::crash_protection::safe_get_time()),
+        _ => (robot_pos.x as f32, robot_pos.y as f32),
+    }
+}
+
+/// Translucent robot acting out the current task's suggested next calls once the player has been
+/// idle, with the tile each call reaches outlined and the call written above it
+fn draw_tutorial_ghost(game: &Game, ox: f32, oy: f32) {
+    let Some((index, progress)) = game.ghost_progress(crate::crash_protection::safe_get_time()) else { return };
+    let steps = game.ghost_steps();
+    let Some(step) = steps.get(index) else { return };
+
+    let (from, to) = (tile_rect(ox, oy, step.from), tile_rect(ox, oy, step.to));
+    let pulse = 0.5 + 0.5 * (progress * std::f32::consts::PI).sin();
+    draw_rectangle_lines(to.x, to.y, to.w, to.h, 3.0, Color::new(0.6, 0.9, 1.0, 0.4 + 0.5 * pulse));
+
+    let center = from.center().lerp(to.center(), progress.min(1.0));
+    let radius = (TILE * 0.35).min(16.0);
+    draw_circle(center.x, center.y, radius, Color::new(0.7, 0.9, 1.0, 0.35));
+    draw_circle_lines(center.x, center.y, radius, 2.0, Color::new(0.8, 0.95, 1.0, 0.8));
+
+    let label = format!("{};", step.call);
+    let dim = measure_text(&label, None, 16, 1.0);
+    let (lx, ly) = (center.x - dim.width * 0.5, center.y - radius - 8.0);
+    draw_rectangle(lx - 4.0, ly - dim.height - 4.0, dim.width + 8.0, dim.height + 8.0, Color::new(0.0, 0.0, 0.0, 0.7));
+    draw_text(&label, lx, ly, 16.0, Color::new(0.8, 0.95, 1.0, 1.0));
+}
+
+/// What the robot said with say(), in a bubble pointing down at it
+fn draw_speech_bubble(game: &Game, ox: f32, oy: f32, radius: f32) {
+    let Some(speech) = game.current_speech() else { return };
+    let (tx, ty) = if speech.robot == game.active_robot {
+        robot_draw_position(game)
+    } else {
+        let Some(pos) = game.robot_positions().get(speech.robot).copied() else { return };
+        (pos.x as f32, pos.y as f32)
+    };
+    let (cx, cy) = (ox + tx * TILE + (TILE - 1.0) * 0.5, oy + ty * TILE + (TILE - 1.0) * 0.5);
+
+    let dim = measure_text(&speech.text, None, 16, 1.0);
+    let (w, h) = (dim.width + 12.0, 24.0);
+    let (x, y) = (cx - w * 0.5, cy - radius - 10.0 - h);
+    draw_rectangle(x, y, w, h, WHITE);
+    draw_rectangle_lines(x, y, w, h, 2.0, DARKGRAY);
+    draw_triangle(vec2(cx - 6.0, y + h), vec2(cx + 6.0, y + h), vec2(cx, y + h + 8.0), WHITE);
+    draw_text(&speech.text, x + 6.0, y + 17.0, 16.0, BLACK);
+}
+
+/// The best run so far racing the player: faint dots where it has been and a translucent robot
+/// where it stood at the current turn
+fn draw_race_ghost(game: &Game, ox: f32, oy: f32, radius: f32) {
+    let Some((&ghost, trail)) = game.ghost_race_trail().split_last() else { return };
+    for &pos in trail {
+        let center = tile_rect(ox, oy, pos).center();
+        draw_circle(center.x, center.y, radius * 0.25, Color::new(0.85, 0.85, 1.0, 0.25));
+    }
+    let center = tile_rect(ox, oy, ghost).center();
+    draw_circle(center.x, center.y, radius, Color::new(0.85, 0.85, 1.0, 0.3));
+    draw_circle_lines(center.x, center.y, radius, 2.0, Color::new(0.9, 0.9, 1.0, 0.6));
+}
+
+/// Sandbox tool buttons along the top left of the game area
+pub fn sandbox_palette() -> Vec<(Rect, SandboxTool)> {
+    let viewport = super::camera::grid_viewport();
+    let (w, h, gap) = (scale_size(64.0), scale_size(22.0), scale_size(4.0));
+    SandboxTool::ALL.iter().enumerate()
+        .map(|(i, &tool)| (Rect::new(viewport.x + gap + i as f32 * (w + gap), viewport.y + gap, w, h), tool))
+        .collect()
+}
+
+pub fn draw_sandbox_palette(game: &Game) {
+    let Some(selected) = game.sandbox_tool else { return };
+    let (mouse_x, mouse_y) = crate::crash_protection::safe_mouse_position();
+    let mouse = vec2(mouse_x, mouse_y);
+    for (rect, tool) in sandbox_palette() {
+        let fill = if tool == selected {
+            Color::new(0.2, 0.45, 0.7, 0.9)
+        } else if rect.contains(mouse) {
+            Color::new(0.3, 0.3, 0.35, 0.9)
+        } else {
+            Color::new(0.15, 0.15, 0.18, 0.8)
+        };
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, fill);
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, GRAY);
+        let label = tool.label();
+        let dim = measure_scaled_text(&label, 14.0);
+        draw_scaled_text(&label, rect.x + (rect.w - dim.width) * 0.5, rect.y + (rect.h + dim.height) * 0.5, 14.0, WHITE);
+    }
+}
+
+/// Triangle on the rim of a robot pointing the way it faces
+fn draw_facing(cx: f32, cy: f32, radius: f32, (dx, dy): (i32, i32)) {
+    let (fx, fy) = (dx as f32, dy as f32);
+    let (px, py) = (-fy, fx);
+    let tip = vec2(cx + fx * (radius + 6.0), cy + fy * (radius + 6.0));
+    let base = vec2(cx + fx * (radius - 2.0), cy + fy * (radius - 2.0));
+    draw_triangle(
+        tip,
+        vec2(base.x + px * 6.0, base.y + py * 6.0),
+        vec2(base.x - px * 6.0, base.y - py * 6.0),
+        YELLOW,
+    );
+}
+
+fn draw_boss(top_left: Rect, enemy: &crate::grid::Enemy) {
+    let size = enemy.size as f32;
+    let r = Rect::new(top_left.x, top_left.y, top_left.w * size, top_left.h * size);
+    draw_rectangle(r.x + 3.0, r.y + 3.0, r.w - 6.0, r.h - 6.0, Color::new(0.45, 0.05, 0.1, 0.9));
+    draw_rectangle_lines(r.x + 3.0, r.y + 3.0, r.w - 6.0, r.h - 6.0, 3.0, RED);
+    let dim = measure_text("BOSS", None, 20, 1.0);
+    draw_text("BOSS", r.x + (r.w - dim.width) * 0.5, r.y + (r.h + dim.height) * 0.5, 20.0, WHITE);
+    draw_health_bar(r, enemy);
+}
+
+/// Thin bar along the top of an enemy with hit points
+fn draw_health_bar(r: Rect, enemy: &crate::grid::Enemy) {
+    let Some(hp) = enemy.hp else { return };
+    if enemy.max_hp == 0 {
+        return;
+    }
+    let fraction = hp as f32 / enemy.max_hp as f32;
+    let (x, y, w, h) = (r.x + 4.0, r.y + 2.0, r.w - 8.0, 4.0);
+    draw_rectangle(x, y, w, h, Color::new(0.2, 0.2, 0.2, 0.9));
+    let color = if fraction > 0.5 { GREEN } else if fraction > 0.25 { ORANGE } else { RED };
+    draw_rectangle(x, y, w * fraction, h, color);
+}
+
+// Colors for the robots that aren't currently selected
+const ROBOT_COLORS: [Color; 4] = [
+    Color::new(0.4, 0.6, 0.9, 1.0),
+    Color::new(0.95, 0.6, 0.2, 1.0),
+    Color::new(0.7, 0.45, 0.9, 1.0),
+    Color::new(0.3, 0.85, 0.6, 1.0),
+];
+
+fn draw_conveyor(r: Rect, (dx, dy): (i32, i32)) {
+    draw_rectangle(r.x + 2.0, r.y + 2.0, r.w - 4.0, r.h - 4.0, Color::new(0.25, 0.25, 0.3, 1.0));
+
+    // Chevrons scroll along the belt direction
+    let offset = (crate::crash_protection::safe_get_time() as f32 * 0.8).fract();
+    let (cx, cy) = (r.x + r.w * 0.5, r.y + r.h * 0.5);
+    let (fx, fy) = (dx as f32, dy as f32);
+    let (px, py) = (-fy, fx); // Perpendicular to the belt
+    for i in 0..2 {
+        let t = ((i as f32 * 0.5 + offset).fract() - 0.5) * r.w * 0.8;
+        let tip = vec2(cx + fx * (t + r.w * 0.12), cy + fy * (t + r.h * 0.12));
+        let left = vec2(cx + fx * t + px * r.w * 0.2, cy + fy * t + py * r.h * 0.2);
+        let right = vec2(cx + fx * t - px * r.w * 0.2, cy + fy * t - py * r.h * 0.2);
+        draw_line(left.x, left.y, tip.x, tip.y, 3.0, YELLOW);
+        draw_line(right.x, right.y, tip.x, tip.y, 3.0, YELLOW);
+    }
+}
+
+fn draw_spikes(r: Rect, deadly: bool) {
+    let (height, color) = if deadly {
+        let pulse = 0.75 + 0.25 * (crate::crash_protection::safe_get_time() as f32 * 6.0).sin();
+        (r.h * 0.45, Color::new(0.9 * pulse, 0.15, 0.15, 1.0))
+    } else {
+        (r.h * 0.15, GRAY)
+    };
+    let base = r.y + r.h * 0.8;
+    for i in 0..3 {
+        let x = r.x + r.w * (0.2 + 0.3 * i as f32);
+        draw_triangle(
+            vec2(x - r.w * 0.12, base),
+            vec2(x + r.w * 0.12, base),
+            vec2(x, base - height),
+            color,
+        );
+    }
+}
+
+/// A ring on the floor, pulsing purple while it still has enemies to spawn
+fn draw_spawner(r: Rect, active: bool) {
+    let (cx, cy) = (r.x + r.w * 0.5, r.y + r.h * 0.5);
+    let color = if active {
+        let pulse = 0.6 + 0.4 * (crate::crash_protection::safe_get_time() as f32 * 3.0).sin();
+        Color::new(0.6 * pulse, 0.2, 0.8 * pulse, 1.0)
+    } else {
+        DARKGRAY
+    };
+    draw_circle_lines(cx, cy, r.w * 0.38, 3.0, color);
+    draw_circle_lines(cx, cy, r.w * 0.22, 2.0, color);
+}
+
+/// Mud as dark puddles, ice as a pale sheen with a couple of glints
+fn draw_terrain(r: Rect, terrain: crate::level::Terrain) {
+    match terrain {
+        crate::level::Terrain::Mud => {
+            draw_rectangle(r.x + 2.0, r.y + 2.0, r.w - 4.0, r.h - 4.0, Color::new(0.4, 0.26, 0.13, 0.6));
+            draw_circle(r.x + r.w * 0.3, r.y + r.h * 0.35, r.w * 0.12, Color::new(0.3, 0.18, 0.08, 0.8));
+            draw_circle(r.x + r.w * 0.65, r.y + r.h * 0.65, r.w * 0.16, Color::new(0.3, 0.18, 0.08, 0.8));
+        }
+        crate::level::Terrain::Ice => {
+            draw_rectangle(r.x + 2.0, r.y + 2.0, r.w - 4.0, r.h - 4.0, Color::new(0.75, 0.9, 1.0, 0.55));
+            draw_line(r.x + r.w * 0.2, r.y + r.h * 0.45, r.x + r.w * 0.45, r.y + r.h * 0.2, 2.0, WHITE);
+            draw_line(r.x + r.w * 0.55, r.y + r.h * 0.8, r.x + r.w * 0.8, r.y + r.h * 0.55, 2.0, WHITE);
+        }
+    }
+}
+
+/// A wall block with a lens on the side the beam leaves from, lit while it fires next turn
+fn draw_emitter(r: Rect, (dx, dy): (i32, i32), firing: bool) {
+    let inset = r.w * 0.1;
+    draw_rectangle(r.x + inset, r.y + inset, r.w - inset * 2.0, r.h - inset * 2.0, DARKGRAY);
+    let (cx, cy) = (r.x + r.w * 0.5, r.y + r.h * 0.5);
+    let (lx, ly) = (cx + dx as f32 * r.w * 0.3, cy + dy as f32 * r.h * 0.3);
+    draw_line(cx, cy, lx, ly, 4.0, BLACK);
+    draw_circle(lx, ly, r.w * 0.12, if firing { RED } else { MAROON });
+}
+
+/// A live beam crossing the tile along its direction
+fn draw_laser_beam(r: Rect, (dx, _): (i32, i32)) {
+    let pulse = 0.7 + 0.3 * (crate::crash_protection::safe_get_time() as f32 * 8.0).sin();
+    let color = Color::new(1.0, 0.1, 0.1, pulse);
+    let (cx, cy) = (r.x + r.w * 0.5, r.y + r.h * 0.5);
+    if dx != 0 {
+        draw_line(r.x, cy, r.x + r.w, cy, 4.0, color);
+    } else {
+        draw_line(cx, r.y, cx, r.y + r.h, 4.0, color);
+    }
+}
+
+/// A flag on every tile the level's win condition wants a robot on, shown even through fog
+fn draw_objective_markers(game: &Game, ox: f32, oy: f32) {
+    let Some(condition) = &game.levels[game.level_idx].win_condition else { return };
+    for tile in game_core::win_conditions::WinConditionEvaluator::new(condition).objective_tiles() {
+        let r = tile_rect(ox, oy, tile);
+        let pole = r.x + r.w * 0.3;
+        draw_line(pole, r.y + r.h * 0.15, pole, r.y + r.h * 0.85, 2.0, WHITE);
+        draw_triangle(
+            vec2(pole, r.y + r.h * 0.15),
+            vec2(pole, r.y + r.h * 0.45),
+            vec2(r.x + r.w * 0.75, r.y + r.h * 0.3),
+            LIME,
+        );
+    }
+}
+
+// Seconds the teleport rings stay on screen
+const TELEPORT_EFFECT_SECONDS: f64 = 0.6;
+
+fn draw_teleport_effect(game: &Game, ox: f32, oy: f32) {
+    let Some((from, to, started)) = game.teleport_effect else { return };
+    let t = ((crate::crash_protection::safe_get_time() - started) / TELEPORT_EFFECT_SECONDS) as f32;
+    if !(0.0..1.0).contains(&t) {
+        return;
+    }
+
+    let center = |p: Pos| {
+        let r = tile_rect(ox, oy, p);
+        (r.x + r.w * 0.5, r.y + r.h * 0.5)
+    };
+    let (fx, fy) = center(from);
+    let (tx, ty) = center(to);
+    let color = Color::new(0.6, 0.4, 1.0, 1.0 - t);
+
+    // Ring expands away from the old tile and collapses onto the new one
+    draw_line(fx, fy, tx, ty, 2.0, Color::new(0.6, 0.4, 1.0, (1.0 - t) * 0.5));
+    draw_circle_lines(fx, fy, TILE * (0.3 + 0.5 * t), 3.0, color);
+    draw_circle_lines(tx, ty, TILE * (0.8 - 0.5 * t), 3.0, color);
+}
+
+// Seconds the EMP pulse takes to spread out and fade
+const EMP_EFFECT_SECONDS: f64 = 0.7;
+
+/// A diamond spreading out to the pulse's radius: the tiles within that many steps of the robot
+fn draw_emp_effect(game: &Game, ox: f32, oy: f32) {
+    let Some((center, radius, started)) = game.emp_effect else { return };
+    let t = ((crate::crash_protection::safe_get_time() - started) / EMP_EFFECT_SECONDS) as f32;
+    if !(0.0..1.0).contains(&t) {
+        return;
+    }
+
+    let r = tile_rect(ox, oy, center);
+    let (cx, cy) = (r.x + r.w * 0.5, r.y + r.h * 0.5);
+    let reach = TILE * (radius as f32 + 0.5) * t;
+    let color = Color::new(0.3, 0.9, 1.0, 1.0 - t);
+    let corners = [(cx, cy - reach), (cx + reach, cy), (cx, cy + reach), (cx - reach, cy)];
+    for (i, &(x1, y1)) in corners.iter().enumerate() {
+        let (x2, y2) = corners[(i + 1) % corners.len()];
+        draw_line(x1, y1, x2, y2, 3.0, color);
+    }
+}
+
+/// Little sparks over the corners of a stunned enemy's tile
+fn draw_stun_sparks(r: Rect) {
+    let color = Color::new(0.3, 0.9, 1.0, 0.9);
+    let size = r.w * 0.12;
+    for (x, y) in [(r.x + r.w * 0.2, r.y + r.h * 0.2), (r.x + r.w * 0.8, r.y + r.h * 0.2)] {
+        draw_line(x - size, y, x + size, y, 2.0, color);
+        draw_line(x, y - size, x, y + size, 2.0, color);
+    }
+}
+
+/// A lever on a base plate, thrown right and lit while the switch is on
+fn draw_switch(r: Rect, on: bool) {
+    let base_y = r.y + r.h * 0.72;
+    draw_rectangle(r.x + r.w * 0.25, base_y, r.w * 0.5, r.h * 0.12, DARKGRAY);
+    let pivot = vec2(r.x + r.w * 0.5, base_y);
+    let tip = vec2(pivot.x + if on { r.w * 0.22 } else { -r.w * 0.22 }, r.y + r.h * 0.3);
+    draw_line(pivot.x, pivot.y, tip.x, tip.y, 3.0, LIGHTGRAY);
+    draw_circle(tip.x, tip.y, r.w * 0.08, if on { GREEN } else { MAROON });
+}
+
+fn key_color(color: crate::level::KeyColor) -> Color {
+    match color {
+        crate::level::KeyColor::Red => RED,
+        crate::level::KeyColor::Blue => SKYBLUE,
+        crate::level::KeyColor::Green => LIME,
+        crate::level::KeyColor::Yellow => YELLOW,
+    }
+}
+
+/// A key lying on the floor: ring, shaft and one tooth
+fn draw_key(r: Rect, color: Color) {
+    let (cx, cy) = (r.x + r.w * 0.32, r.y + r.h * 0.5);
+    let radius = r.w * 0.13;
+    draw_circle_lines(cx, cy, radius, 3.0, color);
+    draw_line(cx + radius, cy, r.x + r.w * 0.8, cy, 3.0, color);
+    draw_line(r.x + r.w * 0.7, cy, r.x + r.w * 0.7, cy + r.h * 0.16, 3.0, color);
+}
+
+fn draw_mirror(r: Rect, mirror: crate::level::Mirror) {
+    draw_rectangle(r.x + 2.0, r.y + 2.0, r.w - 4.0, r.h - 4.0, Color::new(0.2, 0.25, 0.35, 1.0));
+    let inset = r.w * 0.15;
+    let (y1, y2) = match mirror {
+        crate::level::Mirror::Slash => (r.y + r.h - inset, r.y + inset),
+        crate::level::Mirror::Backslash => (r.y + inset, r.y + r.h - inset),
+    };
+    draw_line(r.x + inset, y1, r.x + r.w - inset, y2, 4.0, Color::new(0.75, 0.9, 1.0, 1.0));
+}
+
+const LASER_PATH_SECONDS: f64 = 0.5;
+
+/// Fading beam along every segment of the last laser shot, including mirror bounces
+fn draw_laser_path(game: &Game, ox: f32, oy: f32) {
+    let Some((path, fired)) = &game.laser_path else { return };
+    let t = ((crate::crash_protection::safe_get_time() - fired) / LASER_PATH_SECONDS) as f32;
+    if !(0.0..1.0).contains(&t) {
+        return;
+    }
+
+    let color = Color::new(1.0, 0.2, 0.2, 1.0 - t);
+    for segment in path.windows(2) {
+        let a = tile_rect(ox, oy, segment[0]);
+        let b = tile_rect(ox, oy, segment[1]);
+        draw_line(a.x + a.w * 0.5, a.y + a.h * 0.5, b.x + b.w * 0.5, b.y + b.h * 0.5, 3.0, color);
+    }
 }
 
 pub fn draw_tutorial_overlay(game: &Game) {
@@ -299,6 +949,60 @@ pub fn draw_time_slow_indicator(game: &Game) {
     }
 }
 
+/// Clickable execution speed buttons, plus pause/resume while a program is playing
+pub struct ExecutionControls {
+    pub speeds: Vec<(ExecutionSpeed, Rect)>,
+    pub pause: Rect,
+    pub stop: Rect, // Cancels the run
+}
+
+/// Where the execution controls sit: right-aligned in the header above the grid
+pub fn execution_controls() -> ExecutionControls {
+    let layout = super::layout::screen_layout();
+    let right = layout.grid.right();
+    let y = layout.header.bottom() - scale_size(34.0);
+    let (width, height) = (scale_size(58.0), scale_size(22.0));
+    let speeds: Vec<(ExecutionSpeed, Rect)> = ExecutionSpeed::ALL.iter().enumerate()
+        .map(|(i, speed)| {
+            let x = right - (ExecutionSpeed::ALL.len() - i) as f32 * width;
+            (*speed, Rect::new(x, y, width, height))
+        })
+        .collect();
+    let pause_width = scale_size(76.0);
+    let pause = Rect::new(right - ExecutionSpeed::ALL.len() as f32 * width - scale_size(56.0) - pause_width, y, pause_width, height);
+    let stop_width = scale_size(64.0);
+    let stop = Rect::new(pause.x - scale_size(6.0) - stop_width, y, stop_width, height);
+    ExecutionControls { speeds, pause, stop }
+}
+
+pub fn draw_execution_controls(game: &Game) {
+    let controls = execution_controls();
+    let current = game.menu.settings.execution_speed;
+    if let Some((_, first)) = controls.speeds.first() {
+        draw_scaled_text("Speed:", first.x - scale_size(50.0), first.y + first.h * 0.72, 13.0, LIGHTGRAY);
+    }
+    for (speed, rect) in &controls.speeds {
+        let selected = *speed == current;
+        let background = if selected { Color::new(0.25, 0.45, 0.75, 0.95) } else { Color::new(0.12, 0.12, 0.16, 0.9) };
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, background);
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, scale_size(1.0), if selected { WHITE } else { GRAY });
+        draw_scaled_text(speed.label(), rect.x + scale_size(6.0), rect.y + rect.h * 0.72, 12.0, if selected { WHITE } else { LIGHTGRAY });
+    }
+
+    if game.program_running {
+        let rect = controls.pause;
+        let (label, color) = if game.program_paused { ("> Resume", GREEN) } else { ("|| Pause", ORANGE) };
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0.12, 0.12, 0.16, 0.9));
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, scale_size(1.0), color);
+        draw_scaled_text(label, rect.x + scale_size(8.0), rect.y + rect.h * 0.72, 12.0, color);
+
+        let rect = controls.stop;
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0.12, 0.12, 0.16, 0.9));
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, scale_size(1.0), RED);
+        draw_scaled_text("[] Stop", rect.x + scale_size(8.0), rect.y + rect.h * 0.72, 12.0, RED);
+    }
+}
+
 pub fn draw_level_complete_overlay(game: &Game) {
     if game.finished {
         let msg = "Level complete! Press N for next level.";
@@ -0,0 +1,175 @@
+// Screen layout: splits the window into regions (header, grid, sidebar, inventory, controls line)
+// from size constraints instead of fixed fractions, so the in-game screen reflows from 1024x600
+// up to 4K. Fixed sizes go through scale_size and so follow the font multiplier.
+//
+// Anything that draws or hit-tests one of these regions should ask screen_layout() for it rather
+// than working the position out again.
+
+use macroquad::prelude::*;
+use crate::font_scaling::*;
+
+/// How much space one region of a split wants
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Size {
+    Fixed(f32),
+    Percent(f32), // Fraction (0.0-1.0) of the space being split
+    Fill,         // Share of whatever the other regions leave
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Constraint {
+    pub size: Size,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Constraint {
+    pub fn fixed(size: f32) -> Self {
+        Self { size: Size::Fixed(size), min: 0.0, max: f32::INFINITY }
+    }
+
+    pub fn percent(fraction: f32) -> Self {
+        Self { size: Size::Percent(fraction), min: 0.0, max: f32::INFINITY }
+    }
+
+    pub fn fill() -> Self {
+        Self { size: Size::Fill, min: 0.0, max: f32::INFINITY }
+    }
+
+    pub fn min(self, min: f32) -> Self {
+        Self { min, ..self }
+    }
+
+    pub fn max(self, max: f32) -> Self {
+        Self { max, ..self }
+    }
+}
+
+/// Lengths for `constraints` along `total`, with `gap` between neighbours. If the minimums don't
+/// fit, every region shrinks by the same factor.
+fn split_lengths(total: f32, gap: f32, constraints: &[Constraint]) -> Vec<f32> {
+    let available = (total - gap * constraints.len().saturating_sub(1) as f32).max(0.0);
+    let mut lengths: Vec<f32> = constraints
+        .iter()
+        .map(|c| match c.size {
+            Size::Fixed(size) => size.clamp(c.min, c.max),
+            Size::Percent(fraction) => (available * fraction).clamp(c.min, c.max),
+            Size::Fill => 0.0,
+        })
+        .collect();
+
+    let fills = constraints.iter().filter(|c| c.size == Size::Fill).count();
+    if fills > 0 {
+        let share = (available - lengths.iter().sum::<f32>()).max(0.0) / fills as f32;
+        for (length, c) in lengths.iter_mut().zip(constraints) {
+            if c.size == Size::Fill {
+                *length = share.clamp(c.min, c.max);
+            }
+        }
+    }
+
+    let used: f32 = lengths.iter().sum();
+    if used > available && used > 0.0 {
+        let factor = available / used;
+        lengths.iter_mut().for_each(|length| *length *= factor);
+    }
+    lengths
+}
+
+/// Split `rect` into columns, left to right
+pub fn split_horizontal(rect: Rect, gap: f32, constraints: &[Constraint]) -> Vec<Rect> {
+    let mut x = rect.x;
+    split_lengths(rect.w, gap, constraints)
+        .into_iter()
+        .map(|w| {
+            let column = Rect::new(x, rect.y, w, rect.h);
+            x += w + gap;
+            column
+        })
+        .collect()
+}
+
+/// Split `rect` into rows, top to bottom
+pub fn split_vertical(rect: Rect, gap: f32, constraints: &[Constraint]) -> Vec<Rect> {
+    let mut y = rect.y;
+    split_lengths(rect.h, gap, constraints)
+        .into_iter()
+        .map(|h| {
+            let row = Rect::new(rect.x, y, rect.w, h);
+            y += h + gap;
+            row
+        })
+        .collect()
+}
+
+/// `rect` shrunk by `amount` on every side
+pub fn inset(rect: Rect, amount: f32) -> Rect {
+    Rect::new(rect.x + amount, rect.y + amount, (rect.w - amount * 2.0).max(0.0), (rect.h - amount * 2.0).max(0.0))
+}
+
+/// Regions of the in-game screen
+#[derive(Clone, Copy, Debug)]
+pub struct ScreenLayout {
+    pub header: Rect,    // Level info, upgrades and execution controls
+    pub grid: Rect,      // Game area the grid camera draws into
+    pub sidebar: Rect,   // Frame of the tabbed sidebar (editor, logs, history)
+    pub inventory: Rect, // Under the sidebar
+    pub footer: Rect,    // Controls line
+}
+
+impl ScreenLayout {
+    /// Inside of the sidebar frame, where its tabs draw their content
+    pub fn sidebar_content(&self) -> Rect {
+        inset(self.sidebar, scale_size(10.0))
+    }
+}
+
+pub fn screen_layout() -> ScreenLayout {
+    let screen = Rect::new(0.0, 0.0, crate::crash_protection::safe_screen_width(), crate::crash_protection::safe_screen_height());
+    let padding = scale_size(10.0);
+
+    let rows = split_vertical(screen, 0.0, &[
+        Constraint::fixed(scale_size(100.0)),
+        Constraint::fill(),
+        Constraint::fixed(scale_size(28.0)),
+    ]);
+    let (header, body, footer) = (rows[0], rows[1], rows[2]);
+
+    // The sidebar gets a fixed share of the width, but never less than the editor needs to be usable
+    let body = Rect::new(body.x + padding, body.y, (body.w - padding * 2.0).max(0.0), body.h);
+    let columns = split_horizontal(body, padding * 2.0, &[
+        Constraint::fill().min(scale_size(240.0)),
+        Constraint::percent(0.3).min(scale_size(360.0)).max(scale_size(900.0)),
+    ]);
+    let (grid, right) = (columns[0], columns[1]);
+
+    let panels = split_vertical(right, padding, &[
+        Constraint::percent(0.72).min(scale_size(220.0)),
+        Constraint::fill(),
+    ]);
+
+    ScreenLayout { header, grid, sidebar: panels[0], inventory: panels[1], footer }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_takes_what_is_left() {
+        let rects = split_horizontal(Rect::new(0.0, 0.0, 1000.0, 100.0), 10.0, &[
+            Constraint::fixed(200.0),
+            Constraint::fill(),
+            Constraint::percent(0.5).max(300.0),
+        ]);
+        let widths: Vec<f32> = rects.iter().map(|r| r.w).collect();
+        assert_eq!(widths, [200.0, 480.0, 300.0]);
+        assert_eq!(rects[2].x, 700.0);
+    }
+
+    #[test]
+    fn minimums_shrink_evenly_when_they_do_not_fit() {
+        let lengths = split_lengths(300.0, 0.0, &[Constraint::fill().min(200.0), Constraint::fixed(400.0)]);
+        assert_eq!(lengths, [100.0, 200.0]);
+    }
+}
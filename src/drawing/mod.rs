@@ -1,6 +1,8 @@
 pub mod game_drawing;
 pub mod ui_drawing;
 pub mod editor_drawing;
+pub mod camera;
+pub mod layout;
 
 pub use game_drawing::*;
 pub use ui_drawing::*;
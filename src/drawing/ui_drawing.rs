@@ -2,6 +2,8 @@ use macroquad::prelude::*;
 use crate::gamestate::{Game, RustFunction};
 use crate::gamestate::types::EditorTab;
 use crate::font_scaling::*;
+use crate::popup::PopupType;
+use crate::text_buffer::TextBuffer;
 
 
 pub fn draw_game_info(game: &Game) {
@@ -13,10 +15,19 @@ pub fn draw_game_info(game: &Game) {
         &format!("{}  (Level {}/{})", spec.name, game.level_idx + 1, game.levels.len()),
         padding, padding + 0.0, 26.0, WHITE,
     );
-    draw_scaled_text(
-        &format!("Credits: {}   Turns: {}{}", game.credits, game.turns, if game.max_turns>0 { format!("/{}", game.max_turns) } else { "".into() }),
-        padding, padding + scale.line_height, 22.0, WHITE,
-    );
+    let goal = spec.win_condition.as_ref()
+        .map(|condition| format!("   Goal: {}", game_core::win_conditions::WinConditionEvaluator::new(condition).describe()))
+        .unwrap_or_default();
+    let info = format!("Credits: {}   Turns: {}{}{}", game.credits, game.turns, if game.max_turns>0 { format!("/{}", game.max_turns) } else { "".into() }, goal);
+    draw_scaled_text(&info, padding, padding + scale.line_height, 22.0, WHITE);
+
+    // Real-time countdown after the turn counter, red for the last ten seconds
+    if let (Some(label), Some(left)) = (game.time_left_label(), game.time_left) {
+        let color = if left <= 10.0 { RED } else { YELLOW };
+        let paused = if game.popup_system.is_showing() { " (paused)" } else { "" };
+        let width = measure_text(&info, None, scale_font_size(22.0) as u16, 1.0).width;
+        draw_scaled_text(&format!("{}{}", label, paused), padding + width + scale_size(16.0), padding + scale.line_height, 22.0, color);
+    }
     let time_slow_status = if game.time_slow_active {
         format!(" | Time Slow: {}ms", game.time_slow_duration_ms)
     } else {
@@ -31,12 +42,29 @@ pub fn draw_game_info(game: &Game) {
                 time_slow_status),
         padding, padding + scale.line_height * 2.0, 20.0, WHITE,
     );
+
+    if let (Some(remaining), Some(config)) = (game.energy, spec.energy) {
+        draw_energy_bar(remaining, config.capacity, padding, padding + scale.line_height * 2.6);
+    }
+}
+
+fn draw_energy_bar(remaining: u32, capacity: u32, x: f32, y: f32) {
+    let width = scale_size(200.0);
+    let height = scale_size(10.0);
+    let fraction = if capacity == 0 { 0.0 } else { (remaining as f32 / capacity as f32).min(1.0) };
+    let color = if fraction > 0.5 { GREEN } else if fraction > 0.2 { YELLOW } else { RED };
+
+    draw_rectangle(x, y, width, height, Color::new(0.15, 0.15, 0.15, 0.9));
+    draw_rectangle(x, y, width * fraction, height, color);
+    draw_rectangle_lines(x, y, width, height, 1.0, WHITE);
+    draw_scaled_text(&format!("Energy {}/{}", remaining, capacity), x + width + scale_size(8.0), y + height, 16.0, WHITE);
 }
 
 pub fn draw_controls_text() {
     let scale = ScaledMeasurements::new();
-    let controls_text = "Controls: Click code editor to edit robot_code.rs | ENTER execute | Ctrl+Shift+C completion help | Ctrl+Shift+E IDE hint | Ctrl+Shift+B docs | Ctrl+Shift+S settings | Ctrl+Shift+N finish | Ctrl+Shift+L reload | Ctrl+Shift+M menu";
-    draw_scaled_text(controls_text, scale.padding, crate::crash_protection::safe_screen_height() - scale_size(18.0), 18.0, GRAY);
+    let controls_text = crate::i18n::tr("game.controls");
+    let footer = crate::drawing::layout::screen_layout().footer;
+    draw_scaled_text(&controls_text, scale.padding, footer.bottom() - scale_size(10.0), 18.0, GRAY);
 }
 
 fn get_function_definition(func: RustFunction) -> &'static str {
@@ -44,6 +72,18 @@ fn get_function_definition(func: RustFunction) -> &'static str {
         RustFunction::Move => r#"fn move_robot(direction: Direction) -> Result<String, String> {
     // Move robot in the specified direction
     // Returns Ok with status message or Err if blocked
+}"#,
+        RustFunction::MoveForward => r#"fn move_forward() -> String {
+    // Move one tile in the direction the robot is facing
+    // The triangle on the robot shows its facing
+}"#,
+        RustFunction::RotateLeft => r#"fn rotate_left() -> String {
+    // Turn 90 degrees counter-clockwise: up -> left -> down -> right
+    // Turning doesn't use up a turn
+}"#,
+        RustFunction::RotateRight => r#"fn rotate_right() -> String {
+    // Turn 90 degrees clockwise: up -> right -> down -> left
+    // Turning doesn't use up a turn
 }"#,
         RustFunction::Grab => r#"fn grab_items() -> String {
     // Grab all items and unknown tiles within grabber range
@@ -51,6 +91,7 @@ fn get_function_definition(func: RustFunction) -> &'static str {
 }"#,
         RustFunction::Scan => r#"fn scan_direction(direction: Direction) -> Result<String, String> {
     // Scan in a direction to reveal tiles (2-tile range)
+    // scan() with no direction scans the way the robot is facing
     // Always available in the new design
 }"#,
         RustFunction::LaserDirection => r#"fn laser_direction(direction: Direction) -> String {
@@ -73,6 +114,44 @@ fn get_function_definition(func: RustFunction) -> &'static str {
     // Open or close a door at the robot's current position
     // Pass true to open, false to close
     // Teaches about boolean literals in Rust
+}"#,
+        RustFunction::SetAutoGrab => r#"fn set_auto_grab(enabled: bool) -> String {
+    // true: moving onto an item picks it up
+    // false: items stay put until you call grab()
+    // Takes no turn
+}"#,
+        RustFunction::Say => r#"fn say(text: &str) -> String {
+    // Show text in a speech bubble over the robot for 2 turns
+    // It's also kept in the Logs tab
+    // Try say(&format!("{} items left", n)) to build the text
+    // Takes no turn
+}"#,
+        RustFunction::Rewind => r#"fn rewind(turns: usize) -> String {
+    // Undo the last `turns` turns: robot, enemies, items and the map
+    // go back to how they were (up to 50 turns of history)
+    // Unlocked by collecting a rewind item
+    // Outside the editor, Ctrl+Z rewinds one turn
+}"#,
+        RustFunction::Emp => r#"fn emp(radius: u32) -> String {
+    // Stun every enemy within `radius` steps of the robot
+    // (counting steps up, down, left and right) for 3 turns
+    // Radius 1 to 4; bosses aren't affected
+    // Unlocked by collecting an EMP emitter
+}"#,
+        RustFunction::PrintMap => r#"fn print_map() {
+    // Print the tiles the robot knows about, one character per tile:
+    // R robot, E enemy, i item, # obstacle, D/d closed/open door,
+    // C crate, . floor, ? not explored yet
+}"#,
+        RustFunction::Teleport => r#"fn teleport(x: i32, y: i32) -> String {
+    // Jump to an explored, unblocked tile
+    // Costs 3 turns - enemies keep moving while you teleport
+    // Unlocked by collecting a teleport beacon
+}"#,
+        RustFunction::SelectRobot => r#"fn select_robot(index: usize) -> String {
+    // Choose which robot the following commands control
+    // robot(index).move_bot("up") targets one robot for a single call
+    // The turn advances once every robot has acted
 }"#,
         // Print functions are available as standard Rust macros
         RustFunction::Println | RustFunction::Eprintln | RustFunction::Panic => {
@@ -114,11 +193,21 @@ fn draw_commands_content(game: &Game, def_x: f32, def_y: f32, def_width: f32, de
         
         let func_name = match func {
             RustFunction::Move => "move_bot(\"direction\")",
+            RustFunction::MoveForward => "move_forward()",
+            RustFunction::RotateLeft => "rotate_left()",
+            RustFunction::RotateRight => "rotate_right()",
             RustFunction::Grab => "grab()",
             RustFunction::Scan => "scan(direction)",
             RustFunction::LaserDirection => "laser::direction(dir)",
             RustFunction::LaserTile => "laser::tile(x,y)",
             RustFunction::OpenDoor => "open_door(true/false)",
+            RustFunction::SetAutoGrab => "set_auto_grab(true/false)",
+            RustFunction::Say => "say(text)",
+            RustFunction::PrintMap => "print_map()",
+            RustFunction::Rewind => "rewind(turns)",
+            RustFunction::Emp => "emp(radius)",
+            RustFunction::Teleport => "teleport(x, y)",
+            RustFunction::SelectRobot => "select_robot(index)",
             _ => continue, // Skip hidden functions
         };
         
@@ -201,7 +290,7 @@ fn draw_tasks_content(game: &Game, def_x: f32, def_y: f32, def_width: f32, def_h
                                14.0, text_color);
                 
                 // Task description preview
-                if let Some(message) = &task.task_message {
+                if let Some(message) = task.localized_task_message(crate::i18n::current_language().code()) {
                     let preview = message.lines().next().unwrap_or("").chars().take(50).collect::<String>();
                     let preview = if message.len() > 50 { format!("{}...", preview) } else { preview };
                     draw_scaled_text(&preview, def_x + scale.padding + scale_size(25.0), task_y + scale_size(35.0), 
@@ -228,9 +317,17 @@ fn draw_editor_content(game: &mut Game, editor_x: f32, editor_y: f32, editor_wid
     // Draw editor title and info
     draw_scaled_text("ROBOT CODE EDITOR", editor_x, editor_y, 18.0, YELLOW);
     draw_scaled_text(&format!("File: {}", game.robot_code_path), editor_x, editor_y + scale.line_height, 11.0, LIGHTGRAY);
+    let lint_message = game.cursor_lint_message();
     
     if game.robot_code_modified {
         draw_scaled_text("File modified externally! Changes loaded.", editor_x, editor_y + scale_size(32.0), 11.0, YELLOW);
+    } else if let Some(vim) = game.vim.as_ref() {
+        // Vim mode shows which mode the keys act in instead of the usual hint
+        let color = if vim.mode == crate::gamestate::vim::VimMode::Insert { GREEN } else { SKYBLUE };
+        draw_scaled_text(&format!("{} | Esc for normal mode | Ctrl+Shift+Enter to run", vim.status()), editor_x, editor_y + scale_size(32.0), 10.0, color);
+    } else if let Some(message) = lint_message {
+        // A lint finding on the cursor's line takes the place of the hint
+        draw_scaled_text(&format!("⚠ {}", message), editor_x, editor_y + scale_size(32.0), 10.0, YELLOW);
     } else {
         draw_scaled_text("Click to position cursor | Arrow keys navigate | Ctrl+Shift+Enter to run", editor_x, editor_y + scale_size(32.0), 10.0, GRAY);
     }
@@ -247,165 +344,514 @@ fn draw_editor_content(game: &mut Game, editor_x: f32, editor_y: f32, editor_wid
                         if game.code_editor_active { YELLOW } else { WHITE });
     
     // Show current code from game state
-    let code_to_display = if game.current_code.is_empty() {
-        "// Start typing your Rust code here...\n".to_string()
-    } else {
-        game.current_code.clone()
-    };
-    
-    let lines: Vec<&str> = code_to_display.lines().collect();
-    let start_line = game.code_scroll_offset;
-    
-    // Draw line numbers
-    let line_number_width = scale_size(28.0); // Slightly smaller for sidebar
-    draw_rectangle(editor_x, input_y, line_number_width, text_area_height, Color::new(0.15, 0.15, 0.2, 1.0));
-    draw_line(editor_x + line_number_width, input_y, editor_x + line_number_width, input_y + text_area_height, scale_size(1.0), DARKGRAY);
-    
-    for i in 0..max_visible_lines {
-        let line_num = start_line + i + 1;
-        let y = input_y + scale_size(10.0) + (i as f32 * line_height);
-        let color = if line_num <= lines.len() { DARKGRAY } else { Color::new(0.3, 0.3, 0.3, 1.0) };
-        draw_scaled_text(&format!("{:2}", line_num), editor_x + scale_size(2.0), y, 10.0, color);
-    }
+    // Only the visible lines are read from the buffer, as in the main editor
+    let placeholder = game.current_code.is_empty().then(|| TextBuffer::from("// Start typing your Rust code here...\n"));
+    let line_count = placeholder.as_ref().unwrap_or(&game.current_code).line_count();
+    let markers = crate::drawing::editor_drawing::diagnostic_markers(game);
+    let cursor_line = game.current_code.line_of(game.cursor_position);
     
     // Grid-based character rendering
+    let line_number_width = game.line_number_width();
     let text_x = editor_x + line_number_width + scale_size(4.0);
     let char_width = game.get_cached_char_width();
     let char_height = line_height;
     
-    // Calculate grid dimensions for sidebar width
+    // Calculate grid dimensions for sidebar width; the rows depend on how many columns fit when lines wrap
     let max_cols = ((editor_width - line_number_width - scale_size(15.0)) / char_width) as usize;
+    game.code_columns_visible = max_cols.max(1);
+    let rows = game.visible_rows(max_visible_lines);
     let grid_start_x = text_x;
     let grid_start_y = input_y + scale_size(10.0);
+
+    // Draw line numbers, unless turned off in the editor settings; wrapped rows go unnumbered.
+    // The cursor's line number is brighter and lines with syntax check reports get a dot.
+    if line_number_width > 0.0 {
+        draw_rectangle(editor_x, input_y, line_number_width, text_area_height, Color::new(0.15, 0.15, 0.2, 1.0));
+        draw_line(editor_x + line_number_width, input_y, editor_x + line_number_width, input_y + text_area_height, scale_size(1.0), DARKGRAY);
+
+        for (i, (line_index, first_col)) in rows.iter().enumerate() {
+            if game.wraps_lines() && *first_col > 0 {
+                continue;
+            }
+            let line_num = line_index + 1;
+            let y = input_y + scale_size(10.0) + (i as f32 * line_height);
+            let color = if game.code_editor_active && *line_index == cursor_line {
+                LIGHTGRAY
+            } else if line_num <= line_count {
+                DARKGRAY
+            } else {
+                Color::new(0.3, 0.3, 0.3, 1.0)
+            };
+            draw_scaled_text(&format!("{:2}", line_num), editor_x + scale_size(2.0), y, 10.0, color);
+            if let Some(marker) = markers.get(line_index) {
+                draw_circle(editor_x + line_number_width - scale_size(4.0), y - scale_size(3.0), scale_size(2.5), *marker);
+            }
+        }
+    }
     
     // Draw character grid
-    for row in 0..max_visible_lines {
-        let line_index = start_line + row;
+    let code_to_display = placeholder.as_ref().unwrap_or(&game.current_code);
+    for (row, &(line_index, first_col)) in rows.iter().enumerate() {
         let grid_y = grid_start_y + (row as f32 * char_height);
         
-        if line_index < lines.len() {
-            let line = lines[line_index];
+        if line_index < line_count {
+            let line = code_to_display.line_text(line_index);
             let chars: Vec<char> = line.chars().collect();
             
             // Highlight current line if cursor is on it
-            let cursor_line = get_cursor_line(game);
             if game.code_editor_active && line_index == cursor_line {
                 draw_rectangle(grid_start_x, grid_y - scale_size(8.0), editor_width - line_number_width - scale_size(8.0), char_height, Color::new(0.2, 0.2, 0.3, 0.3));
             }
             
-            // Draw characters
-            for col in 0..max_cols {
-                if col < chars.len() {
-                    let grid_x = grid_start_x + (col as f32 * char_width);
+            // Draw characters; a tab takes one blank cell
+            for cell in 0..max_cols {
+                let col = first_col + cell;
+                if col < chars.len() && chars[col] != '\t' {
+                    let grid_x = grid_start_x + (cell as f32 * char_width);
                     let ch = chars[col];
-                    let color = get_syntax_color(ch, col, line);
+                    let color = get_syntax_color(ch, col, &line);
                     draw_scaled_text(&ch.to_string(), grid_x, grid_y, 11.0, color);
                 }
             }
         }
     }
+
+    crate::drawing::editor_drawing::draw_horizontal_scrollbar(game, &rows, grid_start_x, input_y + text_area_height - scale_size(8.0), max_cols as f32 * char_width);
     
     // Show cursor if it's in the visible area and editor is active
-    if game.code_editor_active {
-        let cursor_line = get_cursor_line(game);
-        let cursor_col = get_cursor_col(game);
+    if game.code_editor_active && let Some((visible_row, visible_col)) = game.cursor_cell(&rows) {
+        let cursor_x = grid_start_x + (visible_col as f32 * char_width);
+        let cursor_y = grid_start_y + (visible_row as f32 * char_height);
         
-        if cursor_line >= start_line && cursor_line < start_line + max_visible_lines {
-            let visible_row = cursor_line - start_line;
-            let cursor_x = grid_start_x + (cursor_col as f32 * char_width);
-            let cursor_y = grid_start_y + (visible_row as f32 * char_height);
-            
-            // Draw blinking cursor
-            let time = crate::crash_protection::safe_get_time() as f32;
-            if (time * 2.0) % 2.0 < 1.0 {
-                draw_line(cursor_x, cursor_y - scale_size(8.0), cursor_x, cursor_y + scale_size(4.0), scale_size(2.0), YELLOW);
+        // Draw blinking cursor
+        let time = crate::crash_protection::safe_get_time() as f32;
+        if (time * 2.0) % 2.0 < 1.0 {
+            draw_line(cursor_x, cursor_y - scale_size(8.0), cursor_x, cursor_y + scale_size(4.0), scale_size(2.0), YELLOW);
+        }
+    }
+
+    crate::drawing::editor_drawing::draw_clipboard_picker(game, editor_x, input_y, editor_width);
+    crate::drawing::editor_drawing::draw_goto_line_prompt(game, editor_x, input_y, editor_width);
+}
+
+/// Variables at the selected step of the last run, then the pinned expressions and the one being typed
+fn draw_watch_content(game: &Game, def_x: f32, def_y: f32, def_width: f32, def_height: f32, scale: &ScaledMeasurements) {
+    let watch = &game.watch;
+    draw_scaled_text("WATCH", def_x, def_y, 20.0, YELLOW);
+    draw_scaled_text("Up/Down step | type + Enter pin | Backspace unpin | Ctrl+Shift+W editor", def_x, def_y + scale.line_height, 11.0, GRAY);
+
+    let content_y = def_y + scale_size(50.0);
+    let content_height = def_height - scale_size(70.0);
+    let line_height = scale_size(16.0);
+    draw_rectangle(def_x, content_y, def_width, content_height, Color::new(0.05, 0.05, 0.05, 0.9));
+    draw_rectangle_lines(def_x, content_y, def_width, content_height, scale_size(1.0), LIGHTGRAY);
+
+    let x = def_x + scale.padding;
+    let mut y = content_y + scale.padding + line_height / 2.0;
+    let bottom = content_y + content_height - scale.padding;
+    match watch.current() {
+        None => {
+            draw_scaled_text("Run your code to watch its variables.", x, y + scale_size(14.0), 14.0, GRAY);
+            y += scale_size(30.0);
+        }
+        Some(environment) => {
+            let state = if game.program_running { "running" } else { "last run" };
+            draw_scaled_text(&format!("Step {}/{} in {}() ({})", watch.step + 1, watch.environments.len(), environment.function, state), x, y, 12.0, SKYBLUE);
+            y += line_height;
+            let bindings = environment.bindings();
+            if bindings.is_empty() {
+                draw_scaled_text("No variables yet", x, y, 12.0, GRAY);
+                y += line_height;
+            }
+            for (name, value) in bindings {
+                if y > bottom - line_height * (watch.pins.len() + 3) as f32 {
+                    break;
+                }
+                draw_scaled_text(&format!("{} = {}", name, value), x, y, 12.0, if value == "?" { GRAY } else { WHITE });
+                y += line_height;
             }
         }
     }
+
+    // Pins and the input line stay at the bottom of the panel
+    y += line_height / 2.0;
+    draw_scaled_text("PINNED", x, y, 12.0, GOLD);
+    y += line_height;
+    for (pin, value) in watch.pinned_values() {
+        if y > bottom - line_height {
+            break;
+        }
+        draw_scaled_text(&format!("{} = {}", pin, value), x, y, 12.0, LIME);
+        y += line_height;
+    }
+    let cursor = if (crate::crash_protection::safe_get_time() * 2.0) as i64 % 2 == 0 { "_" } else { " " };
+    draw_scaled_text(&format!("> {}{}", watch.input, cursor), x, y, 12.0, LIGHTGRAY);
+}
+
+/// Each variable from the last run as a row: a bar from declaration to drop (or move), with a
+/// marker for every borrow, move and use on the code line where it happens
+fn draw_ownership_content(game: &Game, def_x: f32, def_y: f32, def_width: f32, def_height: f32, scale: &ScaledMeasurements) {
+    use game_core::ownership::EventKind;
+
+    draw_scaled_text("OWNERSHIP TIMELINE", def_x, def_y, 20.0, YELLOW);
+    draw_scaled_text("Updated on every run | Ctrl+Shift+O editor", def_x, def_y + scale.line_height, 11.0, GRAY);
+
+    let content_y = def_y + scale_size(50.0);
+    let content_height = def_height - scale_size(70.0);
+    let row_height = scale_size(18.0);
+    draw_rectangle(def_x, content_y, def_width, content_height, Color::new(0.05, 0.05, 0.05, 0.9));
+    draw_rectangle_lines(def_x, content_y, def_width, content_height, scale_size(1.0), LIGHTGRAY);
+
+    let timelines = &game.ownership_timelines;
+    if timelines.is_empty() {
+        draw_scaled_text("Run your code to see its variables.", def_x + scale.padding, content_y + scale_size(30.0), 14.0, GRAY);
+        draw_scaled_text("Each variable gets a line from where it's", def_x + scale.padding, content_y + scale_size(50.0), 12.0, LIGHTGRAY);
+        draw_scaled_text("declared to where it's dropped or moved.", def_x + scale.padding, content_y + scale_size(66.0), 12.0, LIGHTGRAY);
+        return;
+    }
+
+    let event_color = |kind: EventKind| match kind {
+        EventKind::Declared => LIME,
+        EventKind::Used => LIGHTGRAY,
+        EventKind::Borrowed => SKYBLUE,
+        EventKind::BorrowedMut => ORANGE,
+        EventKind::Assigned => GREEN,
+        EventKind::Moved => RED,
+        EventKind::UsedAfterMove => MAGENTA,
+        EventKind::Dropped => GRAY,
+    };
+
+    // Code lines run left to right across the space next to the names
+    let first_line = timelines.iter().map(|timeline| timeline.first_line()).min().unwrap_or(1);
+    let last_line = timelines.iter().map(|timeline| timeline.last_line()).max().unwrap_or(first_line).max(first_line + 1);
+    let label_width = def_width * 0.3;
+    let axis_x = def_x + scale.padding + label_width;
+    let axis_width = def_width - label_width - scale.padding * 3.0;
+    let line_x = |line: usize| axis_x + line.saturating_sub(first_line) as f32 / (last_line - first_line) as f32 * axis_width;
+
+    let mut y = content_y + scale.padding + row_height / 2.0;
+    draw_scaled_text(&format!("line {}", first_line), axis_x, y, 10.0, GRAY);
+    let end_label = format!("line {}", last_line);
+    draw_scaled_text(&end_label, axis_x + axis_width - measure_scaled_text(&end_label, 10.0).width, y, 10.0, GRAY);
+    y += row_height;
+
+    // Keep room for the legend and the problems list under the rows
+    let problems: Vec<String> = timelines.iter()
+        .flat_map(|timeline| timeline.events.iter()
+            .filter(|event| event.kind == EventKind::UsedAfterMove)
+            .map(move |event| format!("line {}: `{}` is used after it was moved", event.line, timeline.name)))
+        .collect();
+    let reserved_rows = 1 + problems.len().min(3);
+    let max_rows = ((content_y + content_height - y) / row_height) as usize;
+    let rows = max_rows.saturating_sub(reserved_rows);
+
+    for timeline in timelines.iter().take(rows) {
+        let label = if timeline.function == "main" { timeline.name.clone() } else { format!("{}::{}", timeline.function, timeline.name) };
+        draw_scaled_text(&label, def_x + scale.padding, y, 12.0, if timeline.copy { LIGHTGRAY } else { WHITE });
+
+        // Owned values are solid until they move; after a move the variable is only an empty name
+        let start_x = line_x(timeline.first_line());
+        let moved_at = timeline.events.iter().find(|event| event.kind == EventKind::Moved).map(|event| event.line);
+        let alive_end = line_x(moved_at.unwrap_or(timeline.last_line()));
+        let bar_y = y - row_height * 0.3;
+        draw_line(start_x, bar_y, alive_end.max(start_x + scale_size(2.0)), bar_y, scale_size(3.0), if timeline.copy { DARKGRAY } else { DARKGREEN });
+        if moved_at.is_some() {
+            draw_line(alive_end, bar_y, line_x(timeline.last_line()), bar_y, scale_size(1.0), Color::new(0.5, 0.2, 0.2, 1.0));
+        }
+        for event in &timeline.events {
+            draw_circle(line_x(event.line), bar_y, scale_size(3.5), event_color(event.kind));
+        }
+        y += row_height;
+    }
+    if timelines.len() > rows {
+        draw_scaled_text(&format!("... {} more", timelines.len() - rows), def_x + scale.padding, y, 11.0, GRAY);
+        y += row_height;
+    }
+
+    // Legend, then what the compiler would reject
+    let mut legend_x = def_x + scale.padding;
+    for (label, kind) in [("declared", EventKind::Declared), ("borrow", EventKind::Borrowed), ("&mut", EventKind::BorrowedMut), ("move", EventKind::Moved), ("dropped", EventKind::Dropped)] {
+        draw_circle(legend_x + scale_size(4.0), y - scale_size(4.0), scale_size(3.5), event_color(kind));
+        draw_scaled_text(label, legend_x + scale_size(10.0), y, 10.0, LIGHTGRAY);
+        legend_x += measure_scaled_text(label, 10.0).width + scale_size(22.0);
+    }
+    y += row_height;
+    for problem in problems.iter().take(3) {
+        draw_scaled_text(problem, def_x + scale.padding, y, 12.0, MAGENTA);
+        y += row_height;
+    }
 }
 
 fn draw_logs_content(game: &Game, def_x: f32, def_y: f32, def_width: f32, def_height: f32, scale: &ScaledMeasurements) {
-    draw_scaled_text("PROGRAM OUTPUT LOGS", def_x, def_y, 20.0, YELLOW);
-    draw_scaled_text("Output from your Rust program execution", def_x, def_y + scale.line_height, 12.0, GRAY);
-    
+    if game.log_filter == crate::popup::LogFilter::GameLog {
+        draw_game_log_content(game, def_x, def_y, def_width, def_height, scale);
+        return;
+    }
+    if game.log_filter == crate::popup::LogFilter::Combat {
+        draw_combat_log_content(game, def_x, def_y, def_width, def_height, scale);
+        return;
+    }
+    let history = &game.popup_system.history;
+    draw_scaled_text("MESSAGE LOG", def_x, def_y, 20.0, YELLOW);
+    draw_scaled_text(
+        &format!("Filter: {} | Ctrl+Shift+F filter | Ctrl+Shift+G editor | Ctrl+Shift+P last popup", game.log_filter.label()),
+        def_x, def_y + scale.line_height, 11.0, GRAY,
+    );
+
     let content_y = def_y + scale_size(50.0);
     let content_height = def_height - scale_size(70.0);
     let line_height = scale_size(16.0);
-    
+
     // Draw log area background
     draw_rectangle(def_x, content_y, def_width, content_height, Color::new(0.05, 0.05, 0.05, 0.9));
     draw_rectangle_lines(def_x, content_y, def_width, content_height, scale_size(1.0), LIGHTGRAY);
-    
-    if game.println_outputs.is_empty() && game.error_outputs.is_empty() {
-        draw_scaled_text("No program output yet.", def_x + scale.padding, content_y + scale_size(30.0), 14.0, GRAY);
-        draw_scaled_text("Run your code to see println!() and", def_x + scale.padding, content_y + scale_size(50.0), 12.0, LIGHTGRAY);
-        draw_scaled_text("eprintln!() output here.", def_x + scale.padding, content_y + scale_size(66.0), 12.0, LIGHTGRAY);
+
+    if history.filtered(game.log_filter).next().is_none() {
+        draw_scaled_text("No messages yet.", def_x + scale.padding, content_y + scale_size(30.0), 14.0, GRAY);
+        draw_scaled_text("Output, errors and robot actions from", def_x + scale.padding, content_y + scale_size(50.0), 12.0, LIGHTGRAY);
+        draw_scaled_text("every run are collected here.", def_x + scale.padding, content_y + scale_size(66.0), 12.0, LIGHTGRAY);
         return;
     }
-    
-    let mut y_position = content_y + scale.padding;
+
     let max_lines = ((content_height - scale.padding * 2.0) / line_height) as usize;
-    let mut line_count = 0;
-    
-    // Show println outputs (regular output)
-    if !game.println_outputs.is_empty() {
-        draw_scaled_text("📝 Standard Output:", def_x + scale.padding, y_position, 14.0, GREEN);
+    let text_width = def_width - scale.padding * 3.0;
+
+    // Walk backwards from the newest message until the panel is full, then draw top-down
+    let mut lines: Vec<(String, Color, bool)> = Vec::new();
+    let mut last_run = None;
+    for message in history.filtered(game.log_filter).rev() {
+        if lines.len() >= max_lines {
+            break;
+        }
+        if last_run.is_some_and(|run| run != message.run) {
+            lines.push((run_header(last_run.unwrap_or(0), history.current_run()), GRAY, false));
+        }
+        last_run = Some(message.run);
+
+        let color = log_color(&message.popup_type);
+        let mut wrapped = Vec::new();
+        // Game messages carry their meaning in the title; program output doesn't need it
+        if crate::popup::LogFilter::Game.matches(&message.popup_type) {
+            wrapped.push(format!("[{}]", message.title));
+        }
+        for text_line in message.text.lines().filter(|l| !l.trim().is_empty()) {
+            wrapped.extend(wrap_log_text(text_line, text_width, 12.0));
+        }
+        for wrapped_line in wrapped.into_iter().rev() {
+            lines.push((wrapped_line, color, true));
+        }
+    }
+    if let Some(run) = last_run {
+        lines.push((run_header(run, history.current_run()), GRAY, false));
+    }
+
+    let skipped = lines.len().saturating_sub(max_lines);
+    let mut y_position = content_y + scale.padding + line_height / 2.0;
+    for (text, color, indented) in lines.iter().rev().skip(skipped) {
+        let x = if *indented { def_x + scale.padding * 2.0 } else { def_x + scale.padding };
+        draw_scaled_text(text, x, y_position, 12.0, *color);
         y_position += line_height;
-        line_count += 1;
-        
-        for (i, output) in game.println_outputs.iter().enumerate() {
-            if line_count >= max_lines { break; }
-            
-            let text = format!("{}. {}", i + 1, output);
-            let wrapped_lines = wrap_log_text(&text, def_width - scale.padding * 2.0, 12.0);
-            
-            for wrapped_line in wrapped_lines {
-                if line_count >= max_lines { break; }
-                draw_scaled_text(&wrapped_line, def_x + scale.padding * 2.0, y_position, 12.0, WHITE);
-                y_position += line_height;
-                line_count += 1;
-            }
+    }
+}
+
+#[cfg(feature = "vcs")]
+fn draw_history_content(game: &Game, def_x: f32, def_y: f32, def_width: f32, def_height: f32, scale: &ScaledMeasurements) {
+    use crate::code_history::DiffKind;
+
+    draw_scaled_text("CODE HISTORY", def_x, def_y, 20.0, YELLOW);
+    draw_scaled_text(
+        "Up/Down select | D diff vs editor | Enter restore | Ctrl+Shift+H editor",
+        def_x, def_y + scale.line_height, 11.0, GRAY,
+    );
+
+    let content_y = def_y + scale_size(50.0);
+    let content_height = def_height - scale_size(70.0);
+    let line_height = scale_size(16.0);
+    draw_rectangle(def_x, content_y, def_width, content_height, Color::new(0.05, 0.05, 0.05, 0.9));
+    draw_rectangle_lines(def_x, content_y, def_width, content_height, scale_size(1.0), LIGHTGRAY);
+
+    let Some(panel) = game.code_history.as_ref() else {
+        draw_scaled_text("Code history is unavailable.", def_x + scale.padding, content_y + scale_size(30.0), 14.0, GRAY);
+        return;
+    };
+    if panel.entries.is_empty() {
+        draw_scaled_text("No attempts recorded yet.", def_x + scale.padding, content_y + scale_size(30.0), 14.0, GRAY);
+        draw_scaled_text("Every run that compiles and finishes", def_x + scale.padding, content_y + scale_size(50.0), 12.0, LIGHTGRAY);
+        draw_scaled_text("is saved here.", def_x + scale.padding, content_y + scale_size(66.0), 12.0, LIGHTGRAY);
+        return;
+    }
+
+    let mut max_lines = ((content_height - scale.padding * 2.0) / line_height) as usize;
+    let mut y_position = content_y + scale.padding + line_height / 2.0;
+    if let Some(status) = &panel.status {
+        draw_scaled_text(status, def_x + scale.padding, y_position, 12.0, ORANGE);
+        y_position += line_height;
+        max_lines = max_lines.saturating_sub(1);
+    }
+
+    if let Some(diff) = &panel.diff {
+        if diff.is_empty() {
+            draw_scaled_text("Same as the editor.", def_x + scale.padding, y_position, 12.0, GRAY);
+        }
+        for line in diff.iter().take(max_lines) {
+            let (prefix, color) = match line.kind {
+                DiffKind::Hunk => ("", SKYBLUE),
+                DiffKind::Context => (" ", LIGHTGRAY),
+                DiffKind::Added => ("+", LIME),
+                DiffKind::Removed => ("-", Color::new(1.0, 0.5, 0.5, 1.0)),
+            };
+            draw_scaled_text(&format!("{}{}", prefix, line.text), def_x + scale.padding, y_position, 12.0, color);
+            y_position += line_height;
         }
+        return;
     }
-    
-    // Show error outputs (error messages)
-    if !game.error_outputs.is_empty() {
-        if line_count < max_lines {
-            if !game.println_outputs.is_empty() {
-                y_position += line_height / 2.0; // Add some spacing
-                line_count += 1;
-            }
-            
-            if line_count < max_lines {
-                draw_scaled_text("🔴 Error Output:", def_x + scale.padding, y_position, 14.0, RED);
-                y_position += line_height;
-                line_count += 1;
-                
-                for (i, error) in game.error_outputs.iter().enumerate() {
-                    if line_count >= max_lines { break; }
-                    
-                    let text = format!("{}. {}", i + 1, error);
-                    let wrapped_lines = wrap_log_text(&text, def_width - scale.padding * 2.0, 12.0);
-                    
-                    for wrapped_line in wrapped_lines {
-                        if line_count >= max_lines { break; }
-                        draw_scaled_text(&wrapped_line, def_x + scale.padding * 2.0, y_position, 12.0, Color::new(1.0, 0.8, 0.8, 1.0));
-                        y_position += line_height;
-                        line_count += 1;
-                    }
-                }
-            }
+
+    // Keep the selected entry in view
+    let first = (panel.selected + 1).saturating_sub(max_lines);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    for (index, entry) in panel.entries.iter().enumerate().skip(first).take(max_lines) {
+        let selected = index == panel.selected;
+        if selected {
+            draw_rectangle(def_x + scale_size(2.0), y_position - line_height * 0.75, def_width - scale_size(4.0), line_height, Color::new(0.2, 0.3, 0.5, 0.8));
         }
+        let text = format!("{} {:>8}  {}", entry.short_id(), time_ago(now - entry.time), entry.message);
+        draw_scaled_text(&text, def_x + scale.padding, y_position, 12.0, if selected { WHITE } else { LIGHTGRAY });
+        y_position += line_height;
     }
-    
-    // Show if there are more logs than can be displayed
-    if line_count >= max_lines {
-        let remaining_println = game.println_outputs.len().saturating_sub(max_lines / 2);
-        let remaining_error = game.error_outputs.len().saturating_sub(max_lines / 2);
-        if remaining_println > 0 || remaining_error > 0 {
-            draw_scaled_text("... (more logs available)", def_x + scale.padding, content_y + content_height - scale_size(30.0), 11.0, YELLOW);
+}
+
+#[cfg(feature = "vcs")]
+fn time_ago(seconds: i64) -> String {
+    match seconds {
+        s if s < 60 => "just now".to_string(),
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s if s < 86400 => format!("{}h ago", s / 3600),
+        s => format!("{}d ago", s / 86400),
+    }
+}
+
+/// The game's own log records, newest at the bottom, narrowed down by the Game log view
+fn draw_game_log_content(game: &Game, def_x: f32, def_y: f32, def_width: f32, def_height: f32, scale: &ScaledMeasurements) {
+    draw_scaled_text("GAME LOG", def_x, def_y, 20.0, YELLOW);
+    draw_scaled_text(
+        &format!("{} | L level | M module | E export | Ctrl+Shift+F filter", game.game_log_view.label()),
+        def_x, def_y + scale.line_height, 11.0, GRAY,
+    );
+
+    let content_y = def_y + scale_size(50.0);
+    let content_height = def_height - scale_size(70.0);
+    let line_height = scale_size(16.0);
+    draw_rectangle(def_x, content_y, def_width, content_height, Color::new(0.05, 0.05, 0.05, 0.9));
+    draw_rectangle_lines(def_x, content_y, def_width, content_height, scale_size(1.0), LIGHTGRAY);
+
+    let entries = crate::game_log::entries(&game.game_log_view);
+    if entries.is_empty() {
+        draw_scaled_text("No log records.", def_x + scale.padding, content_y + scale_size(30.0), 14.0, GRAY);
+        draw_scaled_text("Start the game with --log debug (or e.g.", def_x + scale.padding, content_y + scale_size(50.0), 12.0, LIGHTGRAY);
+        draw_scaled_text("--log info,keys=debug) to record more.", def_x + scale.padding, content_y + scale_size(66.0), 12.0, LIGHTGRAY);
+        return;
+    }
+
+    let max_lines = ((content_height - scale.padding * 2.0) / line_height) as usize;
+    let text_width = def_width - scale.padding * 3.0;
+    let mut lines: Vec<(String, Color)> = Vec::new();
+    for entry in entries.iter().rev() {
+        if lines.len() >= max_lines {
+            break;
+        }
+        let color = match entry.level {
+            log::Level::Error => Color::new(1.0, 0.5, 0.5, 1.0),
+            log::Level::Warn => ORANGE,
+            log::Level::Info => WHITE,
+            _ => GRAY,
+        };
+        for wrapped_line in wrap_log_text(&entry.line(), text_width, 12.0).into_iter().rev() {
+            lines.push((wrapped_line, color));
+        }
+    }
+
+    let skipped = lines.len().saturating_sub(max_lines);
+    let mut y_position = content_y + scale.padding + line_height / 2.0;
+    for (text, color) in lines.iter().rev().skip(skipped) {
+        draw_scaled_text(text, def_x + scale.padding, y_position, 12.0, *color);
+        y_position += line_height;
+    }
+}
+
+fn draw_combat_log_content(game: &Game, def_x: f32, def_y: f32, def_width: f32, def_height: f32, scale: &ScaledMeasurements) {
+    draw_scaled_text("COMBAT LOG", def_x, def_y, 20.0, YELLOW);
+    draw_scaled_text("Up/Down scroll | End newest | Ctrl+Shift+F filter", def_x, def_y + scale.line_height, 11.0, GRAY);
+
+    let content_y = def_y + scale_size(50.0);
+    let content_height = def_height - scale_size(70.0);
+    let line_height = scale_size(16.0);
+    draw_rectangle(def_x, content_y, def_width, content_height, Color::new(0.05, 0.05, 0.05, 0.9));
+    draw_rectangle_lines(def_x, content_y, def_width, content_height, scale_size(1.0), LIGHTGRAY);
+
+    let entries = &game.combat_log.entries;
+    if entries.is_empty() {
+        draw_scaled_text("Nothing has happened yet.", def_x + scale.padding, content_y + scale_size(30.0), 14.0, GRAY);
+        draw_scaled_text("Enemy moves, laser hits and stuns from", def_x + scale.padding, content_y + scale_size(50.0), 12.0, LIGHTGRAY);
+        draw_scaled_text("the last run are listed here by turn.", def_x + scale.padding, content_y + scale_size(66.0), 12.0, LIGHTGRAY);
+        return;
+    }
+
+    // A header for every turn, then its events, oldest at the top
+    let text_width = def_width - scale.padding * 3.0;
+    let mut lines: Vec<(String, Color, bool)> = Vec::new();
+    let mut last_turn = None;
+    for entry in entries {
+        if last_turn != Some(entry.turn) {
+            lines.push((format!("-- Turn {} --", entry.turn), GRAY, false));
+            last_turn = Some(entry.turn);
+        }
+        let color = match entry.event {
+            game_core::combat_log::CombatEvent::EnemyMoved { .. } => LIGHTGRAY,
+            game_core::combat_log::CombatEvent::EnemySpawned { .. } => ORANGE,
+            game_core::combat_log::CombatEvent::RobotCaught { .. } => Color::new(1.0, 0.5, 0.5, 1.0),
+            _ => SKYBLUE,
+        };
+        for wrapped_line in wrap_log_text(&entry.event.to_string(), text_width, 12.0) {
+            lines.push((wrapped_line, color, true));
         }
     }
+
+    // Scrolled back from the newest lines at the bottom
+    let max_lines = ((content_height - scale.padding * 2.0) / line_height) as usize;
+    let end = lines.len().saturating_sub(game.combat_log_scroll).max(max_lines.min(lines.len()));
+    let mut y_position = content_y + scale.padding + line_height / 2.0;
+    for (text, color, indented) in &lines[end.saturating_sub(max_lines)..end] {
+        let x = if *indented { def_x + scale.padding * 2.0 } else { def_x + scale.padding };
+        draw_scaled_text(text, x, y_position, 12.0, *color);
+        y_position += line_height;
+    }
+}
+
+fn run_header(run: usize, current_run: usize) -> String {
+    match run {
+        0 => "-- Before first run --".to_string(),
+        _ if run == current_run => format!("-- Run #{} (latest) --", run),
+        _ => format!("-- Run #{} --", run),
+    }
+}
+
+fn log_color(popup_type: &PopupType) -> Color {
+    match popup_type {
+        PopupType::Stdout => WHITE,
+        PopupType::Stderr => Color::new(1.0, 0.8, 0.8, 1.0),
+        PopupType::Panic => RED,
+        PopupType::FunctionResults => LIME,
+        PopupType::Log(level) => match level.as_str() {
+            "ERROR" => Color::new(1.0, 0.5, 0.5, 1.0),
+            "WARN" => ORANGE,
+            "INFO" => WHITE,
+            _ => GRAY,
+        },
+        _ => SKYBLUE,
+    }
 }
 
 fn wrap_log_text(text: &str, max_width: f32, font_size: f32) -> Vec<String> {
@@ -446,53 +892,205 @@ fn wrap_log_text(text: &str, max_width: f32, font_size: f32) -> Vec<String> {
 pub fn draw_tabbed_sidebar(game: &mut Game) {
     let scale = ScaledMeasurements::new();
 
-    // Define sidebar position and dimensions (same as old function definitions area)
-    let sidebar_x = crate::crash_protection::safe_screen_width() * 0.5 + scale.padding;
-    let sidebar_y = scale.padding + scale_size(100.0);
-    let sidebar_width = crate::crash_protection::safe_screen_width() * 0.25;
-    let sidebar_height = crate::crash_protection::safe_screen_height() * 0.6;
+    // Sidebar frame and the content area inside it come from the screen layout
+    let layout = crate::drawing::layout::screen_layout();
+    let frame = layout.sidebar;
+    let content = layout.sidebar_content();
+    let (sidebar_x, sidebar_y, sidebar_width, sidebar_height) = (content.x, content.y, content.w, content.h);
 
     // Draw the main sidebar background
-    draw_rectangle(sidebar_x - scale.padding, sidebar_y - scale.padding,
-                   sidebar_width + scale.padding * 2.0, sidebar_height + scale.padding * 2.0,
-                   Color::new(0.0, 0.0, 0.0, 0.8));
-    draw_rectangle_lines(sidebar_x - scale.padding, sidebar_y - scale.padding,
-                        sidebar_width + scale.padding * 2.0, sidebar_height + scale.padding * 2.0,
-                        scale_size(2.0), WHITE);
+    draw_rectangle(frame.x, frame.y, frame.w, frame.h, Color::new(0.0, 0.0, 0.0, 0.8));
+    draw_rectangle_lines(frame.x, frame.y, frame.w, frame.h, scale_size(2.0), WHITE);
 
-    // Always draw editor content (no tabs)
-    draw_editor_content(game, sidebar_x, sidebar_y, sidebar_width, sidebar_height, &scale);
+    // Editor by default; Ctrl+Shift+G swaps in the message log
+    if let Some(playback) = &game.solution_playback {
+        draw_playback_content(playback, sidebar_x, sidebar_y, sidebar_width, sidebar_height, &scale);
+        return;
+    }
+
+    if game.editor_tab == EditorTab::Logs {
+        draw_logs_content(game, sidebar_x, sidebar_y, sidebar_width, sidebar_height, &scale);
+    } else if game.editor_tab == EditorTab::Ownership {
+        draw_ownership_content(game, sidebar_x, sidebar_y, sidebar_width, sidebar_height, &scale);
+    } else if game.editor_tab == EditorTab::Watch {
+        draw_watch_content(game, sidebar_x, sidebar_y, sidebar_width, sidebar_height, &scale);
+    } else if !game.editor_tab.shows_editor() {
+        #[cfg(feature = "vcs")]
+        draw_history_content(game, sidebar_x, sidebar_y, sidebar_width, sidebar_height, &scale);
+    } else {
+        draw_editor_content(game, sidebar_x, sidebar_y, sidebar_width, sidebar_height, &scale);
+    }
+
+    if let Some(rect) = show_me_button_rect(game) {
+        let (mouse_x, mouse_y) = crate::crash_protection::safe_mouse_position();
+        let hovered = rect.contains(vec2(mouse_x, mouse_y));
+        let background = if hovered { Color::new(0.3, 0.45, 0.7, 0.95) } else { Color::new(0.2, 0.3, 0.5, 0.9) };
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, background);
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, scale_size(1.0), WHITE);
+        draw_scaled_text("Show me", rect.x + scale_size(8.0), rect.y + rect.h * 0.72, 13.0, WHITE);
+    }
 }
 
-// Removed draw_code_editor_standalone - now integrated into tabbed interface as Editor tab
+/// Where the "Show me" button sits (just above the sidebar's top right corner), or None when it's
+/// hidden: turned off in settings, no reference solution, or playback already running
+pub fn show_me_button_rect(game: &Game) -> Option<Rect> {
+    if !game.menu.settings.show_me_enabled || game.solution_playback.is_some() {
+        return None;
+    }
+    let level_name = &game.levels.get(game.level_idx)?.name;
+    crate::learning_level_solutions::get_reference_solution_for_level(level_name)?;
 
-// Helper functions for the editor
-fn get_cursor_line(game: &Game) -> usize {
-    let lines: Vec<&str> = game.current_code.lines().collect();
-    let mut current_pos = 0;
-    for (line_idx, line) in lines.iter().enumerate() {
-        let line_len = line.len() + 1; // +1 for newline
-        if current_pos + line_len > game.cursor_position {
-            return line_idx;
-        }
-        current_pos += line_len;
+    let sidebar = crate::drawing::layout::screen_layout().sidebar;
+    let (sidebar_right, sidebar_top) = (sidebar.right(), sidebar.y);
+    let (width, height) = (scale_size(76.0), scale_size(22.0));
+    Some(Rect::new(sidebar_right - width, sidebar_top - height - scale_size(4.0), width, height))
+}
+
+fn draw_playback_content(playback: &crate::gamestate::SolutionPlayback, def_x: f32, def_y: f32, def_width: f32, def_height: f32, scale: &ScaledMeasurements) {
+    draw_scaled_text("REFERENCE SOLUTION (read-only)", def_x, def_y, 20.0, GOLD);
+    let progress = if playback.total_steps == 0 {
+        "No robot actions to play".to_string()
+    } else if playback.is_finished() {
+        format!("Finished all {} steps | ESC back to your code", playback.total_steps)
+    } else {
+        format!("Step {}/{} | ESC stop", playback.current_step(), playback.total_steps)
+    };
+    draw_scaled_text(&format!("{} | {}", playback.title, progress), def_x, def_y + scale.line_height, 11.0, GRAY);
+
+    let content_y = def_y + scale_size(50.0);
+    let content_height = def_height - scale_size(70.0);
+    let line_height = scale_size(16.0);
+    draw_rectangle(def_x, content_y, def_width, content_height, Color::new(0.08, 0.07, 0.02, 0.9));
+    draw_rectangle_lines(def_x, content_y, def_width, content_height, scale_size(1.0), GOLD);
+
+    // Keep the last action's result visible under the code
+    let max_lines = ((content_height - scale.padding * 2.0) / line_height) as usize;
+    let code_lines = if playback.last_result.is_some() { max_lines.saturating_sub(2) } else { max_lines };
+    let mut y_position = content_y + scale.padding + line_height / 2.0;
+    for (number, line) in playback.code.lines().enumerate().take(code_lines) {
+        draw_scaled_text(&format!("{:>3} {}", number + 1, line), def_x + scale.padding, y_position, 12.0, LIGHTGRAY);
+        y_position += line_height;
+    }
+    if let Some(result) = &playback.last_result {
+        let result_y = content_y + content_height - scale.padding - line_height / 2.0;
+        draw_scaled_text(&format!("> {}", result), def_x + scale.padding, result_y, 12.0, LIME);
     }
-    lines.len().max(1) - 1
 }
 
-fn get_cursor_col(game: &Game) -> usize {
-    let lines: Vec<&str> = game.current_code.lines().collect();
-    let mut current_pos = 0;
-    for line in lines.iter() {
-        let line_len = line.len() + 1; // +1 for newline
-        if current_pos + line_len > game.cursor_position {
-            return game.cursor_position - current_pos;
+/// Held items and upgrade levels, drawn under the sidebar; hovering an item shows what it does
+pub fn draw_inventory_panel(game: &Game) {
+    let scale = ScaledMeasurements::new();
+
+    // Same column as the tabbed sidebar, in the space between it and the controls line
+    let panel = crate::drawing::layout::screen_layout().inventory;
+    let (panel_x, panel_y, panel_width, panel_height) = (panel.x, panel.y, panel.w, panel.h);
+    if panel_height < scale_size(60.0) {
+        return; // Window too small to fit the panel
+    }
+
+    draw_rectangle(panel_x, panel_y, panel_width, panel_height, Color::new(0.0, 0.0, 0.0, 0.8));
+    draw_rectangle_lines(panel_x, panel_y, panel_width, panel_height, scale_size(2.0), WHITE);
+
+    let text_x = panel_x + scale.padding;
+    draw_scaled_text("INVENTORY", text_x, panel_y + scale_size(20.0), 16.0, YELLOW);
+
+    let upgrades = &game.robot.upgrades;
+    let scanner = if upgrades.scanner_level > 0 { format!("Lv {}", upgrades.scanner_level) } else { "none".to_string() };
+    let laser = if upgrades.attack_range > 0 { format!("Lv {}", upgrades.attack_range) } else { "none".to_string() };
+    draw_scaled_text(
+        &format!("Grabber Lv {}  |  Scanner {}  |  Laser {}", upgrades.grabber_level, scanner, laser),
+        text_x, panel_y + scale_size(40.0), 13.0, LIGHTGRAY,
+    );
+
+    let mut items = game.robot.get_inventory_items();
+    items.sort();
+    let row_y = panel_y + scale_size(50.0);
+    if items.is_empty() {
+        draw_scaled_text("No items held - grab() items on the grid to collect them", text_x, row_y + scale_size(18.0), 12.0, GRAY);
+        return;
+    }
+
+    // Items as icon chips that wrap onto new rows
+    let icon_size = scale_size(22.0);
+    let chip_height = scale_size(26.0);
+    let (mouse_x, mouse_y) = crate::crash_protection::safe_mouse_position();
+    let mut hovered = None;
+    let mut x = text_x;
+    let mut y = row_y;
+    for name in &items {
+        let label_width = measure_scaled_text(name, 12.0).width;
+        let chip_width = icon_size + scale_size(6.0) + label_width + scale_size(10.0);
+        if x + chip_width > panel_x + panel_width - scale.padding && x > text_x {
+            x = text_x;
+            y += chip_height + scale_size(4.0);
+        }
+        if y + chip_height > panel_y + panel_height {
+            break; // Out of room; the rest are still in the inventory
+        }
+
+        let capabilities = game.item_manager.items.iter()
+            .find(|item| item.collected && &item.name == name)
+            .map(|item| &item.capabilities);
+
+        let color = inventory_icon_color(name, capabilities);
+        draw_rectangle(x, y + scale_size(2.0), icon_size, icon_size, color);
+        let initial = name.chars().next().map(|c| c.to_ascii_uppercase().to_string()).unwrap_or_default();
+        draw_scaled_text(&initial, x + icon_size * 0.3, y + icon_size * 0.85, 14.0, BLACK);
+        draw_scaled_text(name, x + icon_size + scale_size(6.0), y + chip_height * 0.7, 12.0, WHITE);
+
+        if mouse_x >= x && mouse_x <= x + chip_width && mouse_y >= y && mouse_y <= y + chip_height {
+            draw_rectangle_lines(x - scale_size(2.0), y, chip_width, chip_height, scale_size(1.0), YELLOW);
+            hovered = Some((name.clone(), capabilities));
+        }
+        x += chip_width + scale_size(6.0);
+    }
+
+    if let Some((name, capabilities)) = hovered {
+        let mut lines = capabilities.map(|c| c.describe()).unwrap_or_default();
+        if lines.is_empty() {
+            lines.push("No special capabilities".to_string());
         }
-        current_pos += line_len;
+        draw_item_tooltip(&name, &lines, mouse_x, mouse_y);
+    }
+}
+
+fn inventory_icon_color(name: &str, capabilities: Option<&crate::item::ItemCapabilities>) -> Color {
+    if name == "goal" {
+        return GREEN;
+    }
+    match capabilities {
+        Some(c) if c.time_slow_duration.is_some() => PURPLE,
+        Some(c) if c.grabber_boost.is_some() => ORANGE,
+        Some(c) if c.scanner_range.is_some() => SKYBLUE,
+        Some(c) if !c.special_functions.is_empty() => YELLOW,
+        Some(c) if c.credits_value.is_some() => GOLD,
+        _ => LIGHTGRAY,
     }
-    0
 }
 
+fn draw_item_tooltip(title: &str, lines: &[String], mouse_x: f32, mouse_y: f32) {
+    let padding = scale_size(8.0);
+    let line_height = scale_size(16.0);
+    let width = lines.iter()
+        .map(|l| measure_scaled_text(l, 12.0).width)
+        .fold(measure_scaled_text(title, 14.0).width, f32::max) + padding * 2.0;
+    let height = line_height * (lines.len() + 1) as f32 + padding * 2.0;
+
+    // Open upwards from the cursor and keep it on screen
+    let x = (mouse_x + scale_size(12.0)).min(crate::crash_protection::safe_screen_width() - width - padding);
+    let y = (mouse_y - height - scale_size(8.0)).max(padding);
+
+    draw_rectangle(x, y, width, height, Color::new(0.1, 0.1, 0.15, 0.95));
+    draw_rectangle_lines(x, y, width, height, scale_size(1.0), YELLOW);
+    draw_scaled_text(title, x + padding, y + padding + line_height * 0.8, 14.0, YELLOW);
+    for (i, line) in lines.iter().enumerate() {
+        draw_scaled_text(line, x + padding, y + padding + line_height * (i as f32 + 1.8), 12.0, WHITE);
+    }
+}
+
+// Removed draw_code_editor_standalone - now integrated into tabbed interface as Editor tab
+
+// Helper functions for the editor
 fn get_syntax_color(ch: char, col: usize, line: &str) -> Color {
     // Simple syntax highlighting
     if line.trim_start().starts_with("//") {
@@ -537,4 +1135,76 @@ fn get_syntax_color(ch: char, col: usize, line: &str) -> Color {
 pub fn handle_shop(_game: &mut Game) {
     // Shop functionality can be implemented here if needed in the future
     // Currently using this for potential future expansion
-}
\ No newline at end of file
+}
+// Solution comparison screen layout, shared with its scroll handling
+const COMPARISON_MARGIN: f32 = 40.0;
+const COMPARISON_HEADER: f32 = 90.0;
+const COMPARISON_ROW_HEIGHT: f32 = 18.0;
+
+/// Rows of the solution comparison that fit on screen
+pub fn solution_comparison_visible_rows() -> usize {
+    let height = crate::crash_protection::safe_screen_height() - scale_size(COMPARISON_MARGIN) * 2.0 - scale_size(COMPARISON_HEADER) - scale_size(30.0);
+    (height / scale_size(COMPARISON_ROW_HEIGHT)).max(1.0) as usize
+}
+
+/// Results screen: the player's code and the reference solution side by side
+pub fn draw_solution_comparison(game: &Game) {
+    use crate::code_diff::RowKind;
+
+    let Some(comparison) = &game.solution_comparison else { return };
+    let screen_width = crate::crash_protection::safe_screen_width();
+    let screen_height = crate::crash_protection::safe_screen_height();
+    let margin = scale_size(COMPARISON_MARGIN);
+    let (x, y) = (margin, margin);
+    let (width, height) = (screen_width - margin * 2.0, screen_height - margin * 2.0);
+
+    draw_rectangle(0.0, 0.0, screen_width, screen_height, Color::new(0.0, 0.0, 0.0, 0.6));
+    draw_rectangle(x, y, width, height, Color::new(0.08, 0.08, 0.1, 0.97));
+    draw_rectangle_lines(x, y, width, height, scale_size(2.0), GOLD);
+
+    let padding = scale_size(16.0);
+    draw_scaled_text("Your Code vs. Reference Solution", x + padding, y + scale_size(30.0), 24.0, YELLOW);
+    let summary = match comparison.changed_rows() {
+        0 => "Same as the reference solution (ignoring formatting and comments)".to_string(),
+        n => format!("{} line(s) differ. There's more than one way to solve a level!", n),
+    };
+    draw_scaled_text(&format!("{} | {}", comparison.title, summary), x + padding, y + scale_size(52.0), 13.0, LIGHTGRAY);
+
+    let column_width = (width - padding * 3.0) / 2.0;
+    let left_x = x + padding;
+    let right_x = left_x + column_width + padding;
+    let header_y = y + scale_size(COMPARISON_HEADER) - scale_size(12.0);
+    draw_scaled_text("YOUR CODE", left_x, header_y, 14.0, SKYBLUE);
+    draw_scaled_text("REFERENCE SOLUTION", right_x, header_y, 14.0, GOLD);
+
+    let row_height = scale_size(COMPARISON_ROW_HEIGHT);
+    let number_width = measure_scaled_text("0000 ", 12.0).width;
+    let max_chars = ((column_width - number_width) / measure_scaled_text("M", 12.0).width.max(1.0)) as usize;
+    let mut row_y = y + scale_size(COMPARISON_HEADER);
+    for row in comparison.rows.iter().skip(comparison.scroll).take(solution_comparison_visible_rows()) {
+        let (left_bg, right_bg) = match row.kind {
+            RowKind::Same => (None, None),
+            RowKind::Changed => (Some(Color::new(0.4, 0.3, 0.1, 0.6)), Some(Color::new(0.4, 0.3, 0.1, 0.6))),
+            RowKind::Removed => (Some(Color::new(0.45, 0.12, 0.12, 0.6)), None),
+            RowKind::Added => (None, Some(Color::new(0.12, 0.4, 0.15, 0.6))),
+        };
+        for (side, column_x, background) in [(&row.left, left_x, left_bg), (&row.right, right_x, right_bg)] {
+            if let Some(color) = background {
+                draw_rectangle(column_x, row_y, column_width, row_height, color);
+            }
+            if let Some((number, text)) = side {
+                let text_y = row_y + row_height * 0.75;
+                draw_scaled_text(&format!("{:>4}", number), column_x, text_y, 12.0, GRAY);
+                let shown: String = text.chars().take(max_chars).collect();
+                let color = if row.kind == RowKind::Same { LIGHTGRAY } else { WHITE };
+                draw_scaled_text(&shown, column_x + number_width, text_y, 12.0, color);
+            }
+        }
+        row_y += row_height;
+    }
+
+    draw_scaled_text(
+        "Up/Down/PageUp/PageDown or mouse wheel to scroll | ESC to close",
+        x + padding, y + height - scale_size(12.0), 12.0, GRAY,
+    );
+}
@@ -0,0 +1,354 @@
+// Scripted editor scenarios. A scenario file is JSON listing key presses, typed text and mouse
+// clicks/drags together with the buffer, cursor, selection and autocomplete suggestion expected
+// after them. `editor-scenario` plays them against a Game without opening a window, so selection,
+// undo and autocomplete regressions show up in CI instead of in the interactive editor test mode.
+//
+// {
+//   "name": "Shift+Left selects",
+//   "code": "fn main() {}",
+//   "cursor": [0, 12],
+//   "steps": [
+//     { "event": "key", "key": "Shift+Left" },
+//     { "event": "expect", "selection": "}", "cursor": [0, 11] }
+//   ]
+// }
+//
+// Lines and columns count from 0. Mouse events address text by line and column rather than
+// pixels, so they don't depend on fonts or the window size; `columns` sets how wide the text
+// area is for word wrap and sideways scrolling.
+
+use crate::gamestate::Game;
+use macroquad::prelude::KeyCode;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+// Screen size scenarios are laid out in; `columns` narrows the text area within it
+const HEADLESS_VIEWPORT: (f32, f32) = (800.0, 600.0);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EditorScenario {
+    pub name: String,
+    pub code: String,
+    #[serde(default)]
+    pub cursor: (usize, usize),
+    #[serde(default = "autocomplete_default")]
+    pub autocomplete: bool,
+    #[serde(default)]
+    pub vim: bool, // Start in Vim mode's normal mode
+    #[serde(default)]
+    pub hotkeys: crate::hotkeys::HotkeyPreset, // "Default" or "Emacs" bindings
+    #[serde(default)]
+    pub editor: crate::menu::EditorSettings, // Tab width, indentation...; the defaults unless given
+    #[serde(default)]
+    pub columns: Option<usize>, // Width of the text area in columns, for wrapping and sideways scrolling
+    pub steps: Vec<ScenarioStep>,
+}
+
+fn autocomplete_default() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ScenarioStep {
+    /// Text arriving from the keyboard, as the editor's typed-text input delivers it
+    Type { text: String },
+    /// A key with modifiers, written like the hotkey bindings: "Tab", "Shift+Up", "Ctrl+Z"
+    Key { key: String },
+    /// Left click at a line and column
+    Click { line: usize, column: usize },
+    /// Press at `from`, move to `to`, release
+    Drag { from: (usize, usize), to: (usize, usize) },
+    /// Checks on the editor state; fields left out aren't checked
+    Expect {
+        code: Option<String>,
+        cursor: Option<(usize, usize)>,
+        selection: Option<String>,  // "" expects nothing selected
+        suggestion: Option<String>, // "" expects no autocomplete suggestion
+        #[serde(default)]
+        scroll_column: Option<usize>, // First column on screen while long lines scroll sideways
+    },
+}
+
+/// What went wrong in a scenario: the step (0-based) and why
+#[derive(Debug, Clone)]
+pub struct ScenarioFailure {
+    pub step: usize,
+    pub reason: String,
+}
+
+impl EditorScenario {
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    /// Play the scenario on a fresh editor; Err holds the first failed step
+    pub fn run(&self) -> Result<(), ScenarioFailure> {
+        let mut game = crate::editor_test_mode::create_test_game();
+        game.code_editor_active = true;
+        game.menu.settings.editor = self.editor.clone();
+        if let Some(columns) = self.columns {
+            game.code_columns_visible = columns;
+        }
+        game.autocomplete_enabled = self.autocomplete;
+        game.set_vim_mode(self.vim);
+        game.hotkey_system.apply_preset(self.hotkeys);
+        game.current_code = self.code.as_str().into();
+        game.cursor_position = game.current_code.byte_at(self.cursor.0, self.cursor.1);
+        game.clear_selection();
+        game.update_autocomplete();
+
+        let mut player = ScenarioPlayer { game, clipboard: String::new() };
+        for (index, step) in self.steps.iter().enumerate() {
+            player.play(step).map_err(|reason| ScenarioFailure { step: index, reason })?;
+        }
+        Ok(())
+    }
+}
+
+/// The editor being driven, with a clipboard of its own since the OS one needs a window
+struct ScenarioPlayer {
+    game: Game,
+    clipboard: String,
+}
+
+impl ScenarioPlayer {
+    fn play(&mut self, step: &ScenarioStep) -> Result<(), String> {
+        match step {
+            ScenarioStep::Type { text } => {
+                self.game.hotkey_system.cancel_chord();
+                if self.game.goto_line_open() {
+                    self.game.goto_line_text(text);
+                } else if self.game.vim_intercepts_typing() {
+                    self.game.vim_handle_text(text);
+                } else if self.game.insert_typed_text(text) {
+                    self.game.save_undo_state_if_needed(false);
+                }
+            }
+            ScenarioStep::Key { key } => {
+                let (code, ctrl, shift, alt) = self.game.hotkey_system.parse_input_combination(key)
+                    .ok_or_else(|| format!("unknown key '{}'", key))?;
+                self.press(code, ctrl, shift, alt);
+            }
+            ScenarioStep::Click { line, column } => {
+                // A click without movement ends the drag with no selection
+                self.game.cursor_position = self.game.current_code.byte_at(*line, *column);
+                self.game.clear_selection();
+                self.game.is_dragging = false;
+                self.game.ensure_cursor_visible();
+            }
+            ScenarioStep::Drag { from, to } => {
+                let start = self.game.current_code.byte_at(from.0, from.1);
+                let end = self.game.current_code.byte_at(to.0, to.1);
+                self.game.clear_selection();
+                self.game.selection_start = Some(start);
+                self.game.selection_end = Some(end);
+                self.game.cursor_position = end;
+                self.game.is_dragging = false;
+                self.game.ensure_cursor_visible();
+            }
+            ScenarioStep::Expect { code, cursor, selection, suggestion, scroll_column } => {
+                return self.check(code, cursor, selection, suggestion, scroll_column);
+            }
+        }
+        self.game.update_autocomplete();
+        Ok(())
+    }
+
+    /// One key press, handled the way the real editor test mode handles it
+    fn press(&mut self, key: KeyCode, ctrl: bool, shift: bool, alt: bool) {
+        let game = &mut self.game;
+        if game.clipboard_picker_open() {
+            game.clipboard_picker_key(key);
+            return;
+        }
+        if game.goto_line_open() {
+            game.goto_line_key(key);
+            return;
+        }
+        if !crate::HOTKEY_KEYS.contains(&key) {
+            game.hotkey_system.cancel_chord();
+        }
+        if key == KeyCode::Escape {
+            game.vim_escape();
+            return;
+        }
+        // Outside insert mode these keys are commands (Space moves right) rather than text
+        if !ctrl && !alt && game.vim_intercepts_typing() && matches!(key, KeyCode::Space | KeyCode::Tab | KeyCode::Backspace | KeyCode::Enter) {
+            if key == KeyCode::Space {
+                game.vim_handle_text(" ");
+            }
+            return;
+        }
+        if !ctrl && !alt {
+            match key {
+                KeyCode::Up => return game.move_cursor_up_with_selection(shift),
+                KeyCode::Down => return game.move_cursor_down_with_selection(shift),
+                KeyCode::Left => return game.move_cursor_left_with_selection(shift),
+                KeyCode::Right => return game.move_cursor_right_with_selection(shift),
+                KeyCode::Tab => {
+                    if !game.accept_autocomplete() {
+                        game.delete_selection();
+                        let indent = game.menu.settings.editor.indent_unit();
+                        game.current_code.insert_str(game.cursor_position, &indent);
+                        game.cursor_position += indent.len();
+                        game.save_undo_state_if_needed(false);
+                    }
+                    return;
+                }
+                KeyCode::Space => {
+                    game.insert_typed_text(" ");
+                    game.save_undo_state_if_needed(false);
+                    return;
+                }
+                KeyCode::Backspace => {
+                    if !game.delete_selection() && game.cursor_position > 0 {
+                        game.save_undo_state_if_needed(true);
+                        let previous = game.current_code.prev_grapheme_boundary(game.cursor_position);
+                        game.current_code.remove_range(previous..game.cursor_position);
+                        game.cursor_position = previous;
+                    }
+                    return;
+                }
+                KeyCode::Enter => {
+                    game.delete_selection();
+                    let indent = if game.menu.settings.editor.auto_indent {
+                        crate::get_auto_indentation(&game.current_code, game.cursor_position, &game.menu.settings.editor.indent_unit())
+                    } else {
+                        String::new()
+                    };
+                    game.insert_typed_text(&format!("\n{}", indent));
+                    game.save_undo_state_if_needed(false);
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        if game.handle_hotkey(key, ctrl, shift, alt) || !ctrl {
+            return;
+        }
+
+        // Fallbacks for the clipboard and undo shortcuts the hotkey system doesn't carry out
+        match key {
+            KeyCode::C | KeyCode::X => {
+                if let Some((start, end)) = game.get_selection_bounds() {
+                    self.clipboard = game.current_code[start..end].to_string();
+                    if key == KeyCode::X {
+                        game.save_undo_state();
+                        game.delete_selection();
+                    }
+                }
+            }
+            KeyCode::V => {
+                game.save_undo_state();
+                game.insert_typed_text(&self.clipboard);
+                game.clear_selection();
+            }
+            KeyCode::Z => {
+                game.undo();
+            }
+            KeyCode::Y => {
+                game.redo();
+            }
+            KeyCode::A => game.select_all(),
+            _ => {}
+        }
+    }
+
+    fn check(
+        &self,
+        code: &Option<String>,
+        cursor: &Option<(usize, usize)>,
+        selection: &Option<String>,
+        suggestion: &Option<String>,
+        scroll_column: &Option<usize>,
+    ) -> Result<(), String> {
+        let game = &self.game;
+        if let Some(code) = code {
+            let actual = game.current_code.to_string();
+            if actual != *code {
+                return Err(format!("expected code {:?}, found {:?}", code, actual));
+            }
+        }
+        if let Some(cursor) = cursor {
+            let actual = game.current_code.line_col(game.cursor_position);
+            if actual != *cursor {
+                return Err(format!("expected cursor at {:?}, found {:?}", cursor, actual));
+            }
+        }
+        if let Some(selection) = selection {
+            let actual = game.get_selection_bounds()
+                .map(|(start, end)| game.current_code[start..end].to_string())
+                .unwrap_or_default();
+            if actual != *selection {
+                return Err(format!("expected selection {:?}, found {:?}", selection, actual));
+            }
+        }
+        if let Some(suggestion) = suggestion {
+            let actual = game.get_autocomplete_suggestion()
+                .map(|s| s.text.clone())
+                .unwrap_or_default();
+            if actual != *suggestion {
+                return Err(format!("expected suggestion {:?}, found {:?}", suggestion, actual));
+            }
+        }
+        if let Some(column) = scroll_column.filter(|column| *column != game.code_scroll_column) {
+            return Err(format!("expected to scroll to column {}, found {}", column, game.code_scroll_column));
+        }
+        Ok(())
+    }
+}
+
+/// Scenario files under `paths`; directories contribute their .json files
+pub fn collect_scenario_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut found: Vec<PathBuf> = std::fs::read_dir(path)
+                .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+                .unwrap_or_default();
+            found.retain(|file| file.extension().is_some_and(|ext| ext == "json"));
+            found.sort();
+            files.extend(found);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    files
+}
+
+/// Run every scenario under `paths` and print a line per scenario; false if any failed
+pub fn run_scenarios(paths: &[PathBuf]) -> bool {
+    let files = collect_scenario_files(paths);
+    if files.is_empty() {
+        eprintln!("No editor scenarios found");
+        return false;
+    }
+
+    // There's no window to ask for the screen size, so lay the editor out in a fixed one
+    crate::crash_protection::set_headless_viewport(HEADLESS_VIEWPORT.0, HEADLESS_VIEWPORT.1);
+
+    let mut passed = 0;
+    for file in &files {
+        let result = EditorScenario::from_file(file).and_then(|scenario| {
+            match std::panic::catch_unwind(|| scenario.run()) {
+                Ok(Ok(())) => Ok(scenario.name.clone()),
+                Ok(Err(failure)) => Err(format!("{}: step {}: {}", scenario.name, failure.step, failure.reason)),
+                Err(_) => Err(format!("{}: the editor panicked", scenario.name)),
+            }
+        });
+        match result {
+            Ok(name) => {
+                passed += 1;
+                println!("✅ {} ({})", name, file.display());
+            }
+            Err(reason) => println!("❌ {} ({})", reason, file.display()),
+        }
+    }
+    println!("{}/{} editor scenarios passed", passed, files.len());
+    passed == files.len()
+}
@@ -104,7 +104,7 @@ pub async fn run_editor_test_mode() {
     print_final_hotkey_report(&hotkey_test_results);
 }
 
-fn create_test_game() -> Game {
+pub(crate) fn create_test_game() -> Game {
     use ::rand::{rngs::StdRng, SeedableRng};
 
     let test_level = crate::level::LevelSpec {
@@ -115,6 +115,15 @@ fn create_test_game() -> Game {
         scanner_at: None,
         blockers: vec![],
         doors: vec![],
+        locked_doors: vec![],
+        switches: vec![],
+        gates: vec![],
+        emitters: vec![],
+        terrain: vec![],
+        spawners: vec![],
+        flanking: false,
+        win_condition: None,
+        real_time_limit_secs: None,
         enemies: vec![],
         items: vec![],
         tasks: vec![],
@@ -137,6 +146,23 @@ fn create_test_game() -> Game {
         achievement_message: None,
         next_level_hint: None,
         completion_message: None,
+        energy: None,
+        crates: vec![],
+        plates: vec![],
+        conveyors: vec![],
+        spikes: vec![],
+        robots: vec![],
+        mirrors: vec![],
+        message_i18n: Default::default(),
+        ownership_view: false,
+        seed: None,
+        allowed_functions: None,
+        auto_grab: None,
+        tags: Vec::new(),
+        difficulty: None,
+        author: None,
+        version: None,
+        requires: Vec::new(),
     };
 
     let levels = vec![test_level];
@@ -191,7 +217,7 @@ fn test_autocomplete_behavior(game: &mut Game, results: &mut HotkeyTestResults)
     let original_cursor = game.cursor_position;
 
     // Test 1: Type partial keyword and check for suggestion
-    game.current_code = "pri".to_string();
+    game.current_code = "pri".into();
     game.cursor_position = 3;
     game.update_autocomplete();
 
@@ -202,7 +228,7 @@ fn test_autocomplete_behavior(game: &mut Game, results: &mut HotkeyTestResults)
     }
 
     // Test 2: Check if suggestion works for Rust types
-    game.current_code = "Str".to_string();
+    game.current_code = "Str".into();
     game.cursor_position = 3;
     game.update_autocomplete();
 
@@ -213,7 +239,7 @@ fn test_autocomplete_behavior(game: &mut Game, results: &mut HotkeyTestResults)
     }
 
     // Test 3: Check empty string doesn't crash
-    game.current_code = "".to_string();
+    game.current_code = "".into();
     game.cursor_position = 0;
     game.update_autocomplete();
     results.add_autocomplete_test("✅ Empty string handled safely".to_string());
@@ -229,7 +255,7 @@ fn test_tab_key_behavior(game: &mut Game, results: &mut HotkeyTestResults) {
     let original_cursor = game.cursor_position;
 
     // Test 1: Tab with autocomplete suggestion
-    game.current_code = "pri".to_string();
+    game.current_code = "pri".into();
     game.cursor_position = 3;
     game.update_autocomplete();
 
@@ -245,7 +271,7 @@ fn test_tab_key_behavior(game: &mut Game, results: &mut HotkeyTestResults) {
     }
 
     // Test 2: Tab without suggestion should not autocomplete
-    game.current_code = "xyz123".to_string();
+    game.current_code = "xyz123".into();
     game.cursor_position = 6;
     game.update_autocomplete();
 
@@ -306,8 +332,9 @@ fn test_text_editing(game: &mut Game, results: &mut HotkeyTestResults) {
 
     // Test text deletion
     if game.cursor_position > 0 {
-        game.cursor_position -= 1;
-        game.current_code.remove(game.cursor_position);
+        let previous = game.current_code.prev_grapheme_boundary(game.cursor_position);
+        game.current_code.remove_range(previous..game.cursor_position);
+        game.cursor_position = previous;
 
         if game.current_code.len() == initial_len {
             results.add_text_test("✅ Text deletion works".to_string());
@@ -357,14 +384,15 @@ fn handle_editor_input(game: &mut Game, results: &mut HotkeyTestResults) {
 
         // Insert character at cursor position
         game.current_code.insert(game.cursor_position, character);
-        game.cursor_position += 1;
+        game.cursor_position += character.len_utf8();
     }
 
     // Handle special keys
     if is_key_pressed(KeyCode::Backspace) {
         if game.cursor_position > 0 {
-            game.cursor_position -= 1;
-            game.current_code.remove(game.cursor_position);
+            let previous = game.current_code.prev_grapheme_boundary(game.cursor_position);
+            game.current_code.remove_range(previous..game.cursor_position);
+            game.cursor_position = previous;
         }
     }
 
@@ -805,7 +833,7 @@ fn test_tab_key_no_suggestion(game: &mut Game, results: &mut HotkeyTestResults)
     let original_cursor = game.cursor_position;
 
     // Test 1: Empty editor - tab should NOT autocomplete anything
-    game.current_code = String::new();
+    game.current_code = Default::default();
     game.cursor_position = 0;
     game.update_autocomplete();
 
@@ -826,7 +854,7 @@ fn test_tab_key_no_suggestion(game: &mut Game, results: &mut HotkeyTestResults)
     }
 
     // Test 2: Random text that doesn't match any autocomplete - tab should NOT autocomplete
-    game.current_code = "zxcvbnm123".to_string();
+    game.current_code = "zxcvbnm123".into();
     game.cursor_position = game.current_code.len();
     game.update_autocomplete();
 
@@ -849,7 +877,7 @@ fn test_tab_key_no_suggestion(game: &mut Game, results: &mut HotkeyTestResults)
     }
 
     // Test 3: Verify that valid autocomplete text still works
-    game.current_code = "pri".to_string();
+    game.current_code = "pri".into();
     game.cursor_position = 3;
     game.update_autocomplete();
 
@@ -868,7 +896,7 @@ fn test_tab_key_no_suggestion(game: &mut Game, results: &mut HotkeyTestResults)
     let mut all_no_suggestions = true;
 
     for test_str in test_strings {
-        game.current_code = test_str.to_string();
+        game.current_code = test_str.into();
         game.cursor_position = test_str.len();
         game.update_autocomplete();
 
@@ -908,7 +936,7 @@ fn test_click_drag_selection(game: &mut Game, results: &mut HotkeyTestResults) {
     game.clear_selection();
 
     // Set up test text
-    game.current_code = "fn main() {\n    println!(\"Hello World!\");\n}".to_string();
+    game.current_code = "fn main() {\n    println!(\"Hello World!\");\n}".into();
     game.cursor_position = 0;
 
     // Test 2: Start mouse drag
@@ -0,0 +1,179 @@
+// Beginner explanations for the compiler errors players run into most. rustc's own messages are
+// precise but assume you already know Rust; each entry here says the same thing in plain words and
+// shows how it typically happens in robot code. Codes not in the catalog just get the raw message.
+
+/// Plain-words explanation of one rustc error code
+#[derive(Debug)]
+pub struct ErrorExplanation {
+    pub code: &'static str,
+    pub title: &'static str,
+    pub explanation: &'static str,
+    pub example: &'static str, // How the error usually shows up in robot code
+}
+
+const fn entry(code: &'static str, title: &'static str, explanation: &'static str, example: &'static str) -> ErrorExplanation {
+    ErrorExplanation { code, title, explanation, example }
+}
+
+static CATALOG: [ErrorExplanation; 50] = [
+    entry("E0004", "A match doesn't cover every case",
+        "Rust makes you handle every possible value in a match. Add the missing patterns, or a `_ => ...` arm for everything else.",
+        "match steps { 0 => ..., 1 => ... } leaves every other number unhandled; add `_ => move_bot(\"right\"),`."),
+    entry("E0005", "A let pattern might not match",
+        "`let` has to work for every value. For patterns that can fail, like Some(x), use `if let` or `let ... else` instead.",
+        "let Some(item) = items.pop(); fails when the list is empty. Write if let Some(item) = items.pop() { ... }."),
+    entry("E0023", "Wrong number of fields in a pattern",
+        "A pattern has to list as many fields as the tuple or enum variant it matches.",
+        "let (x, y, z) = check_position(); but check_position() returns only (x, y)."),
+    entry("E0026", "The pattern names a field that doesn't exist",
+        "Struct patterns can only use field names the struct actually has. Check the spelling.",
+        "let Pos { x, row } = pos; when the struct's fields are x and y."),
+    entry("E0027", "The pattern is missing struct fields",
+        "Struct patterns have to mention every field. Add the missing ones, or write `..` to ignore the rest.",
+        "let Pos { x } = pos; should be let Pos { x, .. } = pos;"),
+    entry("E0046", "A trait implementation is missing methods",
+        "Implementing a trait means writing every method it requires. Add the methods the message lists.",
+        "impl Robot for Scout {} without the required fn step(&self) method."),
+    entry("E0053", "A trait method has the wrong signature",
+        "The method in your impl has to take and return exactly the types the trait declares.",
+        "The trait says fn step(&self) -> String but your impl writes fn step(&self) -> i32."),
+    entry("E0061", "Wrong number of arguments",
+        "The function was called with more or fewer arguments than it takes. Compare the call with the function's definition.",
+        "move_bot() needs a direction: move_bot(\"right\"). teleport(3) needs both coordinates: teleport(3, 4)."),
+    entry("E0063", "A struct is missing fields",
+        "When you create a struct value you have to give every field a value.",
+        "Pos { x: 1 } when Pos also has a y field; write Pos { x: 1, y: 2 }."),
+    entry("E0069", "`return;` in a function that returns a value",
+        "A function that declares a return type has to return a value of that type on every path.",
+        "fn steps_left() -> i32 { if done { return; } ... } needs return 0; or another number."),
+    entry("E0070", "Assigning to something that can't be assigned",
+        "The left side of `=` has to be a variable, field or element. A function call or a literal can't be changed.",
+        "get_energy() = 100; won't work; store the value in a variable instead: let mut energy = get_energy();"),
+    entry("E0072", "A recursive type has infinite size",
+        "A struct can't contain itself directly. Put the inner value in a Box, Vec or Option<Box<...>>.",
+        "struct Path { next: Path } should be struct Path { next: Option<Box<Path>> }."),
+    entry("E0106", "A reference is missing a lifetime",
+        "When a function returns a reference Rust needs to know which argument it borrows from. Often returning an owned String is simpler.",
+        "fn best_direction() -> &str { ... } can return String instead, or &'static str for fixed text like \"up\"."),
+    entry("E0107", "Wrong number of generic arguments",
+        "The type was given more or fewer <...> parameters than it declares.",
+        "Vec<i32, i32> should be Vec<i32>; HashMap needs two: HashMap<String, i32>."),
+    entry("E0133", "Unsafe code outside an unsafe block",
+        "Some operations can break memory safety and need an `unsafe { }` block. Robot programs never need them.",
+        "Reading a `static mut` counter of moves; keep the counter in a normal `let mut` variable instead."),
+    entry("E0201", "The same item is defined twice in an impl",
+        "Each method name can only appear once in an impl block.",
+        "Two fn step(&self) methods in impl Scout; rename or remove one."),
+    entry("E0252", "Two imports use the same name",
+        "Two `use` lines bring in items with the same name. Remove one or rename it with `as`.",
+        "use std::fmt::Result; and use std::io::Result; together; write use std::io::Result as IoResult;"),
+    entry("E0261", "Use of an undeclared lifetime",
+        "Lifetimes like 'a have to be declared after the function or type name before they can be used.",
+        "fn first(path: &'a str) -> &'a str should be fn first<'a>(path: &'a str) -> &'a str."),
+    entry("E0267", "`break` or `continue` inside a closure",
+        "A closure is its own function, so it can't break out of the loop around it. Use a plain for loop instead.",
+        "directions.iter().for_each(|d| { if is_blocked(d) { break; } }) should be a for loop."),
+    entry("E0268", "`break` or `continue` outside a loop",
+        "`break` and `continue` only work inside loop, while or for.",
+        "if is_blocked(\"right\") { break; } written directly in main, not inside a loop."),
+    entry("E0277", "A type doesn't support what you're doing with it",
+        "The type doesn't implement a trait the operation needs, like printing with {}, comparing or adding.",
+        "println!(\"{}\", check_position()); prints a tuple, which needs {:?}. Adding an i32 to a usize also lands here."),
+    entry("E0282", "Rust can't work out a type",
+        "Rust couldn't infer the type from how the value is used. Add a type annotation.",
+        "let path = Vec::new(); with nothing pushed yet; write let path: Vec<String> = Vec::new();"),
+    entry("E0283", "Rust can't decide which type to use",
+        "Several types would fit, so Rust needs you to say which one.",
+        "let steps = \"3\".parse().unwrap(); should be let steps: i32 = \"3\".parse().unwrap();"),
+    entry("E0308", "Mismatched types",
+        "A value of one type was used where a different type is expected. Check what the function takes or returns.",
+        "move_bot(1) passes a number where a direction text is expected; write move_bot(\"right\"). teleport(\"3\", \"4\") needs numbers: teleport(3, 4)."),
+    entry("E0369", "An operator doesn't work with these types",
+        "Operators like + or == only work between types that support them.",
+        "scan(\"up\") + 1 adds a number to a String; compare the scan result with == \"enemy\" instead."),
+    entry("E0373", "A closure may outlive a variable it borrows",
+        "The closure borrows a local variable but could run after the function ends. Add `move` before the closure.",
+        "thread::spawn(|| println!(\"{}\", steps)) should be thread::spawn(move || println!(\"{}\", steps))."),
+    entry("E0381", "A variable is used before it has a value",
+        "Every variable needs a value before it's read, on every path through the code.",
+        "let direction; if is_blocked(\"up\") { direction = \"down\"; } move_bot(direction); has no value when up isn't blocked."),
+    entry("E0382", "Use of a moved value",
+        "The value was moved somewhere else, so this variable no longer owns it. Borrow it with & or clone() it.",
+        "let plan = path; then path.len() uses path after its Vec moved into plan; write let plan = path.clone();"),
+    entry("E0384", "Assigning twice to an immutable variable",
+        "Variables can't change unless they're declared with `mut`.",
+        "let steps = 0; steps += 1; needs let mut steps = 0;"),
+    entry("E0412", "Unknown type name",
+        "The type isn't defined or imported here. Check the spelling or add a `use` line.",
+        "let seen: HashSet<(i32, i32)> needs use std::collections::HashSet; at the top."),
+    entry("E0423", "A type was used as a value",
+        "A struct or type name appeared where Rust expected a value. Build the struct with its fields, or call its constructor.",
+        "let p = Pos; should be let p = Pos { x: 0, y: 0 };"),
+    entry("E0425", "Unknown variable or function",
+        "Nothing with this name exists here. Check the spelling, and that the variable was declared before and in scope.",
+        "move_bot(dir) where dir was declared inside an earlier loop; or scn() instead of scan(\"up\")."),
+    entry("E0428", "The same name is defined twice",
+        "Two functions, structs or other items share a name in the same place. Rename one.",
+        "Two fn go_right() helpers in the same program."),
+    entry("E0432", "An import can't be found",
+        "The path in a `use` line doesn't point to anything. Check each part of the path.",
+        "use std::collection::HashMap; should be use std::collections::HashMap;"),
+    entry("E0433", "A path can't be resolved",
+        "A name in a path like a::b isn't known. It usually needs a `use` line or a spelling fix.",
+        "HashMap::new() without use std::collections::HashMap;"),
+    entry("E0434", "A nested function can't use outer variables",
+        "A fn defined inside another function can't see that function's local variables. Pass them as arguments, or use a closure.",
+        "fn step() { move_bot(direction); } inside main, where direction is a local of main."),
+    entry("E0435", "A constant uses a runtime value",
+        "Constants and array lengths have to be known when compiling. Use a let variable or a Vec instead.",
+        "let n = get_energy(); let path = [\"up\"; n]; should use vec![\"up\"; n as usize]."),
+    entry("E0499", "Two mutable borrows at once",
+        "Only one &mut reference to a value can be in use at a time. Finish with the first before making the second.",
+        "let a = &mut path; let b = &mut path; a.push(...); keeps both alive at once."),
+    entry("E0502", "Borrowing as mutable while it's also borrowed",
+        "You can't change a value while an immutable reference to it is still in use.",
+        "for step in &path { path.push(step.clone()); } changes path while looping over it."),
+    entry("E0505", "Moving a value while it's borrowed",
+        "A value can't be moved away while a reference to it is still used afterwards.",
+        "let first = &path[0]; let plan = path; println!(\"{}\", first);"),
+    entry("E0506", "Assigning to a borrowed value",
+        "A variable can't be changed while a reference to it is still in use.",
+        "let current = &direction; direction = \"left\"; move_bot(current);"),
+    entry("E0507", "Moving out of a borrow",
+        "You can't take ownership of something you only borrowed. Clone it, or borrow it instead.",
+        "let next = path[0]; with a Vec<String> path; write let next = path[0].clone(); or &path[0]."),
+    entry("E0515", "Returning a reference to a local value",
+        "The local value is dropped when the function ends, so a reference to it would dangle. Return the value itself.",
+        "fn plan() -> &String { let s = String::from(\"up\"); &s } should return String."),
+    entry("E0596", "Borrowing an immutable value as mutable",
+        "Changing a value through a variable needs that variable to be `mut`.",
+        "let path = Vec::new(); path.push(\"up\"); needs let mut path = Vec::new();"),
+    entry("E0597", "A borrowed value doesn't live long enough",
+        "The reference is still used after the value it points to was dropped. Keep the value around longer.",
+        "Storing &scan_result from inside a loop in a Vec declared before the loop."),
+    entry("E0599", "No such method",
+        "The type has no method with this name. Check the spelling and what type the value actually is.",
+        "robot.move_upp() instead of robot.move_up(); or calling .len() on a number."),
+    entry("E0600", "Unary operator not supported",
+        "Operators like ! or - don't work with this type.",
+        "!scan(\"up\") on a String; compare instead: scan(\"up\") != \"wall\"."),
+    entry("E0608", "This type can't be indexed",
+        "Only types like Vec, arrays and slices can be indexed with [ ].",
+        "check_position()[0] indexes a tuple; use check_position().0 instead."),
+    entry("E0609", "No such field",
+        "The value doesn't have a field with this name. Tuples use .0 and .1, structs their field names.",
+        "check_position().x should be check_position().0."),
+    entry("E0614", "This value can't be dereferenced",
+        "`*` only works on references and smart pointers, not on plain values.",
+        "let x = 3; teleport(*x, 0); where x isn't a reference; just write teleport(x, 0)."),
+];
+
+/// The catalog entry for `code` (like "E0308"), if there is one
+pub fn explain(code: &str) -> Option<&'static ErrorExplanation> {
+    CATALOG.iter().find(|entry| entry.code == code)
+}
+
+/// The full write-up of `code` in the Rust error index
+pub fn docs_url(code: &str) -> String {
+    format!("https://doc.rust-lang.org/error_codes/{}.html", code)
+}
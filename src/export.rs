@@ -0,0 +1,141 @@
+// Certificate of completion, exported from the Stats screen and automatically once the campaign is
+// finished. The certificate is a one-page PDF written by hand (fonts are the PDF base fonts, so
+// nothing has to be embedded) with the player's name, the levels completed, the date and a
+// verification code: a checksum of those details, so an edited certificate no longer matches
+// the code the game prints for them.
+
+use crate::i18n::{tr, tr_with};
+use std::path::PathBuf;
+
+pub const CERTIFICATE_DIR: &str = "certificates";
+
+// A4 landscape, in points
+const PAGE_WIDTH: f32 = 842.0;
+const PAGE_HEIGHT: f32 = 595.0;
+
+#[derive(Clone, Debug)]
+pub struct Certificate {
+    pub name: String,
+    pub levels_completed: usize,
+    pub total_levels: usize,
+    pub campaign: Option<String>, // Campaign name from campaign.yaml
+    pub date: String,             // YYYY-MM-DD
+}
+
+impl Certificate {
+    /// "XXXX-XXXX-XXXX-XXXX": FNV-1a over every detail the certificate shows
+    pub fn verification_code(&self) -> String {
+        let details = format!("{}\n{}\n{}\n{}\n{}", self.name, self.levels_completed, self.total_levels, self.campaign.as_deref().unwrap_or(""), self.date);
+        let hash = details.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3));
+        let hex = format!("{:016X}", hash);
+        [&hex[0..4], &hex[4..8], &hex[8..12], &hex[12..16]].join("-")
+    }
+
+    /// File name the certificate is saved under, e.g. certificate-2026-10-16.pdf
+    pub fn file_name(&self) -> String {
+        format!("certificate-{}.pdf", self.date)
+    }
+
+    /// The certificate as a PDF document
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let completed = tr_with("certificate.completed", &[
+            ("levels", &self.levels_completed.to_string()),
+            ("total", &self.total_levels.to_string()),
+        ]);
+        let campaign = self.campaign.clone().unwrap_or_else(|| tr("certificate.game"));
+
+        let mut content = String::new();
+        // Double border
+        content.push_str("0.2 0.3 0.5 RG 4 w 24 24 794 547 re S 1 w 36 36 770 523 re S\n");
+        let mut line = |font: &str, size: f32, y: f32, text: &str| {
+            // Courier is monospaced
+            let width = if font == "F3" { text.chars().count() as f32 * 0.6 * size } else { text_width(text, size) };
+            let x = (PAGE_WIDTH - width) / 2.0;
+            content.push_str(&format!("BT /{} {} Tf {:.1} {:.1} Td ({}) Tj ET\n", font, size, x, y, escape(text)));
+        };
+        line("F1", 36.0, 460.0, &tr("certificate.title"));
+        line("F2", 16.0, 400.0, &tr("certificate.certifies"));
+        line("F1", 30.0, 350.0, &self.name);
+        line("F2", 16.0, 300.0, &completed);
+        line("F2", 16.0, 276.0, &campaign);
+        line("F2", 12.0, 150.0, &tr_with("certificate.date", &[("date", &self.date)]));
+        line("F3", 11.0, 120.0, &tr_with("certificate.code", &[("code", &self.verification_code())]));
+
+        let stream = encode(&content);
+        let objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 4 0 R /F2 5 0 R /F3 6 0 R >> >> /Contents 7 0 R >>",
+                PAGE_WIDTH, PAGE_HEIGHT
+            ).into_bytes(),
+            base_font("Helvetica-Bold"),
+            base_font("Helvetica"),
+            base_font("Courier"),
+            [format!("<< /Length {} >>\nstream\n", stream.len()).into_bytes(), stream, b"\nendstream".to_vec()].concat(),
+        ];
+
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+            pdf.extend(object);
+            pdf.extend(b"\nendobj\n");
+        }
+        let xref = pdf.len();
+        pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+        for offset in offsets {
+            pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+        }
+        pdf.extend(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).into_bytes());
+        pdf
+    }
+
+    /// Write the certificate to the certificates directory and return where it went
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self) -> Result<PathBuf, String> {
+        std::fs::create_dir_all(CERTIFICATE_DIR).map_err(|e| format!("Couldn't create {}: {}", CERTIFICATE_DIR, e))?;
+        let path = PathBuf::from(CERTIFICATE_DIR).join(self.file_name());
+        std::fs::write(&path, self.to_pdf()).map_err(|e| format!("Couldn't write {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn save(&self) -> Result<PathBuf, String> {
+        Err("Certificates can only be saved in the desktop version".to_string())
+    }
+}
+
+fn base_font(name: &str) -> Vec<u8> {
+    format!("<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>", name).into_bytes()
+}
+
+/// Rough Helvetica advance width of `text`, for centering lines
+fn text_width(text: &str, size: f32) -> f32 {
+    let em: f32 = text.chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | 'I' | '.' | ',' | ':' | ';' | '\'' | '!' | '|' => 0.28,
+            'f' | 't' | 'r' | ' ' | '-' | '(' | ')' => 0.33,
+            'm' | 'w' | 'M' | 'W' => 0.83,
+            c if c.is_uppercase() => 0.67,
+            _ => 0.55,
+        })
+        .sum();
+    em * size
+}
+
+/// Backslash-escape the characters that end or break a PDF string
+fn escape(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            '(' | ')' | '\\' => vec!['\\', c],
+            c => vec![c],
+        })
+        .collect()
+}
+
+/// Latin-1 bytes for the WinAnsi-encoded base fonts; anything outside it becomes '?'
+fn encode(content: &str) -> Vec<u8> {
+    content.chars().map(|c| u8::try_from(c as u32).unwrap_or(b'?')).collect()
+}
@@ -0,0 +1,231 @@
+// Game log: the game's own log records (key presses, code execution, the editor, level setup...)
+// go through the log crate to this logger. It prints the records its filter lets through to stderr
+// and keeps the latest of them for the Game log view of the Logs tab, where they can be narrowed
+// down by module and level and exported to a file.
+//
+// The filter takes env_logger-style directives from --log (or RUST_LOG): a bare level sets the
+// default and `module=level` overrides it for a module and everything under it.
+//   --log debug                     everything down to debug
+//   --log info,keys=debug,exec=debug  key and execution tracing on top of the usual info
+//   --log warn,gamestate=trace      quiet, except for the game state modules
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+pub const EXPORT_FILE: &str = "game_log.txt";
+
+// Records kept for the Logs tab; older ones are dropped
+const CAPACITY: usize = 2000;
+
+// The log crate's default target is the module path, starting with the crate name
+const CRATE_NAME: &str = "robo_grid_explorer_gui";
+
+static LOGGER: OnceLock<GameLogger> = OnceLock::new();
+
+#[derive(Clone, Debug)]
+pub struct LogEntry {
+    pub time: f64, // Seconds since the logger started
+    pub level: Level,
+    pub module: String,
+    pub message: String,
+}
+
+impl LogEntry {
+    /// The entry as one line, e.g. "[  12.345 DEBUG keys] Ctrl+Shift+G"
+    pub fn line(&self) -> String {
+        format!("[{:>8.3} {:<5} {}] {}", self.time, self.level, self.module, self.message)
+    }
+}
+
+/// Per-module level filter parsed from a --log spec
+#[derive(Clone, Debug)]
+pub struct LogSpec {
+    default: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl LogSpec {
+    /// Parse "info,keys=debug,gamestate::cursor=trace"; without a bare level the default is info
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parsed = Self { default: LevelFilter::Info, modules: Vec::new() };
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => parsed.modules.push((module.trim().to_string(), parse_level(level)?)),
+                None => match parse_level(directive) {
+                    Ok(level) => parsed.default = level,
+                    // A bare module name turns everything on for it, like env_logger
+                    Err(_) => parsed.modules.push((directive.to_string(), LevelFilter::Trace)),
+                },
+            }
+        }
+        // Longest module first, so the most specific directive wins
+        parsed.modules.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+        Ok(parsed)
+    }
+
+    /// Level a module logs at; directives may name it with or without the crate prefix
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        let short = short_module(target);
+        self.modules.iter()
+            .find(|(module, _)| is_within(target, module) || is_within(short, module))
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    /// Level for modules no directive names
+    pub fn default_level(&self) -> LevelFilter {
+        self.default
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.modules.iter().map(|(_, level)| *level).fold(self.default, Ord::max)
+    }
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level.trim().parse().map_err(|_| format!("Unknown log level '{}' (use off, error, warn, info, debug or trace)", level.trim()))
+}
+
+/// Whether `target` is `module` or one of its submodules
+fn is_within(target: &str, module: &str) -> bool {
+    target.strip_prefix(module).is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// Module path without the crate name, e.g. "gamestate::cursor"; the crate root is "main"
+fn short_module(target: &str) -> &str {
+    match target.strip_prefix(CRATE_NAME) {
+        Some("") => "main",
+        Some(rest) => rest.strip_prefix("::").unwrap_or(target),
+        None => target,
+    }
+}
+
+struct GameLogger {
+    spec: LogSpec,
+    started: Instant,
+    records: Mutex<VecDeque<LogEntry>>,
+}
+
+impl Log for GameLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.spec.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let entry = LogEntry {
+            time: self.started.elapsed().as_secs_f64(),
+            level: record.level(),
+            module: short_module(record.target()).to_string(),
+            message: record.args().to_string(),
+        };
+        eprintln!("{}", entry.line());
+        if let Ok(mut records) = self.records.lock() {
+            if records.len() == CAPACITY {
+                records.pop_front();
+            }
+            records.push_back(entry);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the game logger; only the first call has any effect
+pub fn init(spec: LogSpec) {
+    let max_level = spec.max_level();
+    let logger = LOGGER.get_or_init(|| GameLogger {
+        spec,
+        started: Instant::now(),
+        records: Mutex::new(VecDeque::with_capacity(CAPACITY)),
+    });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// What the Game log view shows: records at or above a level, from one module or all of them
+#[derive(Clone, Debug)]
+pub struct LogView {
+    pub level: LevelFilter,
+    pub module: Option<String>,
+}
+
+impl Default for LogView {
+    fn default() -> Self {
+        Self { level: LevelFilter::Trace, module: None }
+    }
+}
+
+impl LogView {
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        entry.level <= self.level && self.module.as_deref().is_none_or(|module| is_within(&entry.module, module))
+    }
+
+    /// Trace -> debug -> info -> warn -> error -> trace
+    pub fn next_level(&mut self) {
+        self.level = match self.level {
+            LevelFilter::Trace => LevelFilter::Debug,
+            LevelFilter::Debug => LevelFilter::Info,
+            LevelFilter::Info => LevelFilter::Warn,
+            LevelFilter::Warn => LevelFilter::Error,
+            _ => LevelFilter::Trace,
+        };
+    }
+
+    /// All modules -> each module that has logged something -> all modules
+    pub fn next_module(&mut self) {
+        let modules = modules();
+        let next = match &self.module {
+            None => modules.first(),
+            Some(current) => modules.iter().skip_while(|module| *module != current).nth(1),
+        };
+        self.module = next.cloned();
+    }
+
+    /// e.g. "level >= debug, module keys"
+    pub fn label(&self) -> String {
+        format!("level >= {}, module {}", self.level.to_string().to_lowercase(), self.module.as_deref().unwrap_or("all"))
+    }
+}
+
+/// Captured records the view lets through, oldest first
+pub fn entries(view: &LogView) -> Vec<LogEntry> {
+    let Some(logger) = LOGGER.get() else { return Vec::new() };
+    let Ok(records) = logger.records.lock() else { return Vec::new() };
+    records.iter().filter(|entry| view.matches(entry)).cloned().collect()
+}
+
+/// Every module that has a record in the buffer, sorted
+pub fn modules() -> Vec<String> {
+    let Some(logger) = LOGGER.get() else { return Vec::new() };
+    let Ok(records) = logger.records.lock() else { return Vec::new() };
+    let mut modules: Vec<String> = records.iter().map(|entry| entry.module.clone()).collect();
+    modules.sort();
+    modules.dedup();
+    modules
+}
+
+/// Write the records the view shows to game_log.txt and return where it went
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export(view: &LogView) -> Result<PathBuf, String> {
+    let entries = entries(view);
+    let mut text = format!("# Game log ({}), {} records\n", view.label(), entries.len());
+    for entry in &entries {
+        text.push_str(&entry.line());
+        text.push('\n');
+    }
+    let path = PathBuf::from(EXPORT_FILE);
+    std::fs::write(&path, text).map_err(|e| format!("Couldn't write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn export(_view: &LogView) -> Result<PathBuf, String> {
+    Err("The game log can only be exported in the desktop version".to_string())
+}
@@ -0,0 +1,41 @@
+// Background jobs the game queues each frame: a reload whenever the file watcher reports a change to
+// robot_code.rs, and `cargo add` for crates an outside edit started using. Reads happen on the job
+// workers, so the frame only applies the code once it's there.
+
+use super::types::Game;
+use crate::jobs::{Job, JobResult};
+
+impl Game {
+    /// Queue a reload for file changes and apply the jobs that have finished. Call once a frame.
+    pub fn update_jobs(&mut self) {
+        let Some(jobs) = self.jobs.as_mut() else { return };
+        // Several events for one save only need one reload
+        let changed = self.file_watcher_receiver.as_ref().is_some_and(|receiver| receiver.try_iter().count() > 0);
+        if changed {
+            jobs.submit(Job::ReloadCode { path: self.robot_code_path.clone(), saves: self.robot_code_saves });
+        }
+        for result in jobs.poll() {
+            self.apply_job_result(result);
+        }
+    }
+
+    fn apply_job_result(&mut self, result: JobResult) {
+        match result {
+            JobResult::CodeLoaded { path, saves, code: Ok(code) } => {
+                // The file was read before the editor saved over it, or it's the editor's own save
+                if path != self.robot_code_path || saves != self.robot_code_saves || *self.current_code == *code {
+                    return;
+                }
+                self.current_code = code.into();
+                self.cursor_position = self.current_code.floor_char_boundary(self.cursor_position);
+                self.robot_code_modified = true;
+                if let Some(jobs) = self.jobs.as_mut() {
+                    jobs.submit(Job::AddCrates { path });
+                }
+            }
+            JobResult::CodeLoaded { code: Err(e), .. } => log::warn!(target: "jobs", "{}", e),
+            JobResult::CratesAdded(Some(summary)) => self.execution_result = summary,
+            JobResult::CratesAdded(None) => {}
+        }
+    }
+}
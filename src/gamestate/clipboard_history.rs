@@ -0,0 +1,89 @@
+// Clipboard history for the code editor: the last few cuts, copies and kills of this session, most
+// recent first. Ctrl+Shift+V opens a picker over the editor to paste an older one, so text cut a
+// while ago isn't lost when something else is copied.
+
+use super::types::Game;
+use macroquad::prelude::KeyCode;
+use std::collections::VecDeque;
+
+/// How many cuts and copies are kept
+pub const CLIPBOARD_HISTORY_SIZE: usize = 10;
+
+#[derive(Clone, Debug, Default)]
+pub struct ClipboardHistory {
+    entries: VecDeque<String>,
+    pub picker: Option<usize>, // Highlighted entry while the picker is open
+}
+
+impl ClipboardHistory {
+    /// Remember `text` as the newest entry; copying the same text again moves it to the front
+    pub fn push(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry != text);
+        self.entries.push_front(text.to_string());
+        self.entries.truncate(CLIPBOARD_HISTORY_SIZE);
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Game {
+    /// Open the clipboard history picker on the newest entry. Returns false when nothing was cut
+    /// or copied yet.
+    pub fn open_clipboard_picker(&mut self) -> bool {
+        if self.clipboard_history.is_empty() {
+            log::debug!(target: "editor", "Clipboard history is empty");
+            return false;
+        }
+        self.clipboard_history.picker = Some(0);
+        true
+    }
+
+    pub fn clipboard_picker_open(&self) -> bool {
+        self.clipboard_history.picker.is_some()
+    }
+
+    /// A key pressed while the picker is open: Up/Down choose, Enter or 1-9/0 paste, Escape closes.
+    /// Returns whether the code changed.
+    pub fn clipboard_picker_key(&mut self, key: KeyCode) -> bool {
+        let Some(selected) = self.clipboard_history.picker else { return false };
+        let count = self.clipboard_history.entries.len();
+        let digits = [
+            KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5,
+            KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9, KeyCode::Key0,
+        ];
+        match key {
+            KeyCode::Up => self.clipboard_history.picker = Some((selected + count - 1) % count),
+            KeyCode::Down => self.clipboard_history.picker = Some((selected + 1) % count),
+            KeyCode::Escape => self.clipboard_history.picker = None,
+            KeyCode::Enter => return self.paste_clipboard_history(selected),
+            _ => {
+                if let Some(index) = digits.iter().position(|digit| *digit == key).filter(|index| *index < count) {
+                    return self.paste_clipboard_history(index);
+                }
+            }
+        }
+        false
+    }
+
+    /// Paste entry `index` over the selection, close the picker and make the entry the newest
+    fn paste_clipboard_history(&mut self, index: usize) -> bool {
+        self.clipboard_history.picker = None;
+        let Some(text) = self.clipboard_history.entries.get(index).cloned() else { return false };
+        self.save_undo_state();
+        self.insert_typed_text(&text);
+        self.clear_selection();
+        self.clipboard_history.push(&text);
+        // Plain Ctrl+V pastes it next time too
+        crate::crash_protection::safe_clipboard_copy(&text);
+        true
+    }
+}
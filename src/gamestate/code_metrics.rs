@@ -0,0 +1,27 @@
+// Code quality metrics for the program that finished the level (see game_core::analysis), added to
+// the level complete popup with a tip for every command written out where a loop would do.
+
+use super::types::Game;
+use crate::i18n::tr_with;
+use game_core::analysis::code_metrics;
+
+impl Game {
+    pub(super) fn show_code_metrics(&mut self) {
+        let Some(metrics) = code_metrics(&self.current_code.to_string()) else { return };
+        let mut message = tr_with("metrics.summary", &[
+            ("calls", &metrics.robot_calls.to_string()),
+            ("lines", &metrics.lines.to_string()),
+            ("complexity", &metrics.complexity.to_string()),
+        ]);
+        for repeat in &metrics.repeats {
+            let times = repeat.times.to_string();
+            let tip = match repeat.commands.as_slice() {
+                [command] => tr_with("metrics.repeated", &[("command", command), ("times", &times)]),
+                commands => tr_with("metrics.repeated_sequence", &[("commands", &commands.join("; ")), ("times", &times)]),
+            };
+            message.push('\n');
+            message.push_str(&tip);
+        }
+        self.popup_system.append_to_last(&message);
+    }
+}
@@ -3,14 +3,28 @@ use macroquad::prelude::*;
 use crate::font_scaling::*;
 use log::{debug, warn, error};
 
+/// Text typed since the last frame. Space and control keys have their own handlers, so they're
+/// left out. An IME commits a composed word as several characters at once, which arrive here
+/// together and go into the code as one insertion.
+pub fn read_typed_text() -> String {
+    let mut typed = String::new();
+    while let Some(character) = get_char_pressed() {
+        if !character.is_control() && character != ' ' {
+            typed.push(character);
+        }
+    }
+    typed
+}
+
 impl Game {
     // Update window position for coordinate transformations (throttled to 1x per second, more during rapid clicking)
     pub fn update_window_coordinates(&mut self) {
         // Check window activity but don't skip entirely - just be more cautious
-        let window_active = crate::coordinate_system::CoordinateTransformer::is_game_window_active(self.enable_coordinate_logs);
+        let coordinate_logs = log::log_enabled!(target: "coordinates", log::Level::Debug);
+        let window_active = crate::coordinate_system::CoordinateTransformer::is_game_window_active(coordinate_logs);
         if !window_active {
-            if self.enable_coordinate_logs {
-                debug!("Window not fully active - proceeding with cautious coordinate update");
+            if coordinate_logs {
+                debug!(target: "coordinates", "Window not fully active - proceeding with cautious coordinate update");
             }
             // Continue with update but maybe with reduced frequency
         }
@@ -35,11 +49,11 @@ impl Game {
             match update_result {
                 Ok(_) => {
                     self.last_window_update_time = current_time;
-                    if self.enable_coordinate_logs {
+                    if coordinate_logs {
                         if let Some(window_info) = self.coordinate_transformer.get_window_info() {
-                            debug!("Updated window info: {:?}", window_info);
+                            debug!(target: "coordinates", "Updated window info: {:?}", window_info);
                         } else {
-                            debug!("Window info update failed");
+                            debug!(target: "coordinates", "Window info update failed");
                         }
                     }
                 }
@@ -71,11 +85,11 @@ impl Game {
     
     /// Grid-based cursor positioning - simpler and more accurate
     fn position_cursor_at_click_grid_based(&mut self, click_x: f32, click_y: f32, editor_bounds: (f32, f32, f32, f32)) {
-        use crate::drawing::editor_drawing::{mouse_to_grid_position, grid_to_cursor_position};
+        use crate::drawing::editor_drawing::mouse_to_grid_position;
         
         // Make sure code isn't empty
         if self.current_code.is_empty() {
-            self.current_code = "// Start typing your Rust code here...\n".to_string();
+            self.current_code = "// Start typing your Rust code here...\n".into();
         }
         
         let char_width = self.get_cached_char_width();
@@ -85,12 +99,12 @@ impl Game {
             click_x, 
             click_y, 
             editor_bounds, 
+            self.line_number_width(),
             char_width, 
             line_height, 
-            self.code_scroll_offset
+            0 // Rows from the top of the text area; wrapped and scrolled lines are mapped below
         ) {
-            let lines: Vec<&str> = self.current_code.lines().collect();
-            let new_cursor_pos = grid_to_cursor_position(row, col, &lines);
+            let new_cursor_pos = self.position_at_cell(row, col);
             
             // Clamp to valid range
             self.cursor_position = new_cursor_pos.min(self.current_code.len());
@@ -107,7 +121,7 @@ impl Game {
         // make sure it isn't empty
         if self.current_code.is_empty() {
             warn!("current_code was empty, initializing with default content");
-            self.current_code = "// Start typing your Rust code here...\n".to_string();
+            self.current_code = "// Start typing your Rust code here...\n".into();
         }
         
         debug!("Current code length: {}, cursor_position: {}", self.current_code.len(), self.cursor_position);
@@ -115,28 +129,8 @@ impl Game {
         // please god work
         if let Some((line_index, col_index)) = self.get_character_at_position(click_x, click_y, editor_bounds) {
             debug!("Character position found: line={}, col={}", line_index, col_index);
-            // change the line/column to character position
-            let lines: Vec<&str> = self.current_code.lines().collect();
-            let mut position = 0;
-            
-            // add all things from lines to the target line
-            for i in 0..line_index.min(lines.len()) {
-                position += lines[i].len();
-                // add newline character except for the last line (unless the code ends with a newline)
-                if i < lines.len() - 1 || (i == lines.len() - 1 && self.current_code.ends_with('\n')) {
-                    position += 1;
-                }
-            }
-            
-            // column offset
-            if line_index < lines.len() {
-                position += col_index.min(lines[line_index].len());
-            }
-            
-            // trailing new lines were a sin. So we gotta deal with them here hopefully
-            if line_index == lines.len() && self.current_code.ends_with('\n') {
-                position += 1;
-            }
+            // change the line/column to a position in the text
+            let position = self.current_code.byte_at(line_index, col_index);
             
             self.cursor_position = position.min(self.current_code.len());
             debug!("New cursor position: {}", self.cursor_position);
@@ -148,68 +142,46 @@ impl Game {
     }
     
     pub fn move_cursor_up(&mut self) {
-        let lines: Vec<&str> = self.current_code.lines().collect();
-        let cursor_line = self.current_code[..self.cursor_position].matches('\n').count();
+        if self.wraps_lines() {
+            return self.move_cursor_by_row(false);
+        }
+        let (cursor_line, cursor_col) = self.current_code.line_col(self.cursor_position);
         
         if cursor_line > 0 {
-            let line_start = self.current_code[..self.cursor_position].rfind('\n').map(|i| i + 1).unwrap_or(0);
-            let cursor_col = self.cursor_position - line_start;
-            
-            let prev_line_start = if cursor_line > 1 {
-                self.current_code[..line_start.saturating_sub(1)].rfind('\n').map(|i| i + 1).unwrap_or(0)
-            } else {
-                0
-            };
-            
-            let prev_line_len = if cursor_line > 0 {
-                lines[cursor_line - 1].len()
-            } else {
-                0
-            };
-            
-            let new_col = cursor_col.min(prev_line_len);
-            self.cursor_position = prev_line_start + new_col;
+            // byte_at keeps the column when the line above is long enough and clamps otherwise
+            self.cursor_position = self.current_code.byte_at(cursor_line - 1, cursor_col);
             self.ensure_cursor_visible();
         }
     }
     
     pub fn move_cursor_down(&mut self) {
-        let lines: Vec<&str> = self.current_code.lines().collect();
-        let cursor_line = self.current_code[..self.cursor_position].matches('\n').count();
+        if self.wraps_lines() {
+            return self.move_cursor_by_row(true);
+        }
+        let (cursor_line, cursor_col) = self.current_code.line_col(self.cursor_position);
         
-        if cursor_line < lines.len().saturating_sub(1) {
-            let line_start = self.current_code[..self.cursor_position].rfind('\n').map(|i| i + 1).unwrap_or(0);
-            let cursor_col = self.cursor_position - line_start;
-            
-            let next_line_start = line_start + lines[cursor_line].len() + 1; // +1 for newline
-            let next_line_len = if cursor_line + 1 < lines.len() {
-                lines[cursor_line + 1].len()
-            } else {
-                0
-            };
-            
-            let new_col = cursor_col.min(next_line_len);
-            self.cursor_position = next_line_start + new_col;
+        if cursor_line + 1 < self.current_code.line_count() {
+            self.cursor_position = self.current_code.byte_at(cursor_line + 1, cursor_col);
             self.ensure_cursor_visible();
         }
     }
     
     pub fn move_cursor_left(&mut self) {
         if self.cursor_position > 0 {
-            self.cursor_position -= 1;
+            self.cursor_position = self.current_code.prev_grapheme_boundary(self.cursor_position);
             self.ensure_cursor_visible();
         }
     }
     
     pub fn move_cursor_right(&mut self) {
         if self.cursor_position < self.current_code.len() {
-            self.cursor_position += 1;
+            self.cursor_position = self.current_code.next_grapheme_boundary(self.cursor_position);
             self.ensure_cursor_visible();
         }
     }
     
     pub fn ensure_cursor_visible(&mut self) {
-        let cursor_line = self.current_code[..self.cursor_position].matches('\n').count();
+        let (cursor_line, cursor_col) = self.current_code.line_col(self.cursor_position);
         let max_visible_lines = 30;
         
         // scroll up if cursor is above visible area if the scroll works
@@ -221,6 +193,9 @@ impl Game {
         if cursor_line >= self.code_scroll_offset + max_visible_lines {
             self.code_scroll_offset = cursor_line.saturating_sub(max_visible_lines - 1);
         }
+
+        // Wrapped rows and sideways scrolling for long lines
+        self.keep_cursor_cell_visible(cursor_line, cursor_col, max_visible_lines);
     }
     
     pub fn scroll_up(&mut self) {
@@ -230,9 +205,8 @@ impl Game {
     }
     
     pub fn scroll_down(&mut self) {
-        let lines: Vec<&str> = self.current_code.lines().collect();
         let max_visible_lines = 30;
-        if self.code_scroll_offset + max_visible_lines < lines.len() {
+        if self.code_scroll_offset + max_visible_lines < self.current_code.line_count() {
             self.code_scroll_offset += 1;
         }
     }
@@ -351,6 +325,7 @@ impl Game {
     pub fn clear_selection(&mut self) {
         self.selection_start = None;
         self.selection_end = None;
+        self.mark_active = false;
     }
     
     pub fn has_selection(&self) -> bool {
@@ -373,7 +348,7 @@ impl Game {
     
     pub fn delete_selection(&mut self) -> bool {
         if let Some((start, end)) = self.get_selection_bounds() {
-            self.current_code.drain(start..end);
+            self.current_code.remove_range(start..end);
             self.cursor_position = start;
             self.clear_selection();
             self.ensure_cursor_visible();
@@ -383,8 +358,75 @@ impl Game {
         }
     }
     
+    /// Insert typed text at the cursor, replacing the selection. Returns whether anything was typed.
+    pub fn insert_typed_text(&mut self, text: &str) -> bool {
+        if text.is_empty() {
+            return false;
+        }
+        self.delete_selection();
+        self.current_code.insert_str(self.cursor_position, text);
+        self.cursor_position += text.len();
+        self.ensure_cursor_visible();
+        true
+    }
+    
+    /// Add (or remove) "// " at the start of the selected lines, or the cursor's line. Returns
+    /// whether any line changed.
+    pub fn comment_selected_lines(&mut self, comment: bool) -> bool {
+        // Positions after the edit move with the text; ones inside a removed marker land on it
+        fn shift_position(position: usize, at: usize, delta: isize) -> usize {
+            if position < at {
+                position
+            } else if delta > 0 {
+                position + delta as usize
+            } else {
+                at.max(position.saturating_sub(delta.unsigned_abs()))
+            }
+        }
+
+        let (start, end) = self.get_selection_bounds().unwrap_or((self.cursor_position, self.cursor_position));
+        let first = self.current_code.line_of(start);
+        let mut last = self.current_code.line_of(end);
+        // A selection ending at the start of a line doesn't take that line with it
+        if last > first && self.current_code.line_col(end).1 == 0 {
+            last -= 1;
+        }
+
+        let mut changed = false;
+        // Bottom up, so the lines still to do keep their offsets
+        for line in (first..=last).rev() {
+            let text = self.current_code.line_text(line);
+            let indent = text.len() - text.trim_start().len();
+            let at = self.current_code.byte_at(line, 0) + indent;
+            let edit = if comment && !text.trim().is_empty() {
+                Some((at, "// ".len() as isize))
+            } else if !comment && text[indent..].starts_with("//") {
+                let marker = if text[indent..].starts_with("// ") { "// " } else { "//" };
+                Some((at, -(marker.len() as isize)))
+            } else {
+                None
+            };
+            let Some((at, delta)) = edit else { continue };
+
+            if !changed {
+                self.save_undo_state();
+                changed = true;
+            }
+            if delta > 0 {
+                self.current_code.insert_str(at, "// ");
+            } else {
+                self.current_code.remove_range(at..at + delta.unsigned_abs());
+            }
+            self.cursor_position = shift_position(self.cursor_position, at, delta);
+            self.selection_start = self.selection_start.map(|position| shift_position(position, at, delta));
+            self.selection_end = self.selection_end.map(|position| shift_position(position, at, delta));
+        }
+        changed
+    }
+
     // I wanted something that would let me hold shift and arrow keys to select multiple stuff here. I find out if it works soon
     pub fn move_cursor_up_with_selection(&mut self, extend_selection: bool) {
+        let extend_selection = extend_selection || self.mark_active;
         if extend_selection {
             self.start_selection();
         } else {
@@ -399,6 +441,7 @@ impl Game {
     }
     
     pub fn move_cursor_down_with_selection(&mut self, extend_selection: bool) {
+        let extend_selection = extend_selection || self.mark_active;
         if extend_selection {
             self.start_selection();
         } else {
@@ -413,6 +456,7 @@ impl Game {
     }
     
     pub fn move_cursor_left_with_selection(&mut self, extend_selection: bool) {
+        let extend_selection = extend_selection || self.mark_active;
         if extend_selection {
             self.start_selection();
         } else {
@@ -427,6 +471,7 @@ impl Game {
     }
     
     pub fn move_cursor_right_with_selection(&mut self, extend_selection: bool) {
+        let extend_selection = extend_selection || self.mark_active;
         if extend_selection {
             self.start_selection();
         } else {
@@ -439,6 +484,77 @@ impl Game {
             self.update_selection(self.cursor_position);
         }
     }
+
+    /// Jump to `position`, selecting on the way like the arrow keys with Shift
+    pub fn move_cursor_to_with_selection(&mut self, position: usize, extend_selection: bool) {
+        let extend_selection = extend_selection || self.mark_active;
+        if extend_selection {
+            self.start_selection();
+        } else {
+            self.clear_selection();
+        }
+
+        self.cursor_position = position.min(self.current_code.len());
+        self.ensure_cursor_visible();
+
+        if extend_selection {
+            self.update_selection(self.cursor_position);
+        }
+    }
+
+    /// End of the next word going forward, or start of the previous one going back
+    pub fn word_boundary(&self, forward: bool) -> usize {
+        let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+        let code: &str = &self.current_code;
+        if forward {
+            let rest = &code[self.cursor_position..];
+            let skipped: usize = rest.chars().take_while(|c| !is_word(c)).map(char::len_utf8).sum();
+            let word: usize = rest[skipped..].chars().take_while(is_word).map(char::len_utf8).sum();
+            self.cursor_position + skipped + word
+        } else {
+            let before = &code[..self.cursor_position];
+            let skipped: usize = before.chars().rev().take_while(|c| !is_word(c)).map(char::len_utf8).sum();
+            let word: usize = before[..before.len() - skipped].chars().rev().take_while(is_word).map(char::len_utf8).sum();
+            self.cursor_position - skipped - word
+        }
+    }
+
+    /// Emacs C-space: drop the mark at the cursor so moving selects from there, or let go of it
+    pub fn set_mark(&mut self) -> bool {
+        let was_active = self.mark_active;
+        self.clear_selection();
+        if !was_active {
+            self.selection_start = Some(self.cursor_position);
+            self.selection_end = Some(self.cursor_position);
+            self.mark_active = true;
+        }
+        true
+    }
+
+    /// Emacs C-k: cut the rest of the line to the clipboard, or the line break when the cursor is
+    /// already at the end of the line
+    pub fn kill_line(&mut self) -> bool {
+        let line = self.current_code.line_of(self.cursor_position);
+        let line_end = self.current_code.byte_at(line, usize::MAX);
+        let end = if self.cursor_position < line_end {
+            line_end
+        } else if self.cursor_position < self.current_code.len() {
+            self.current_code.next_grapheme_boundary(self.cursor_position)
+        } else {
+            return false;
+        };
+
+        let killed = self.current_code[self.cursor_position..end].to_string();
+        self.clipboard_history.push(&killed);
+        if !crate::crash_protection::safe_clipboard_copy(&killed) {
+            log::warn!(target: "editor", "Failed to copy killed text to OS clipboard (window may not be focused)");
+        }
+        self.save_undo_state();
+        self.clear_selection();
+        self.current_code.remove_range(self.cursor_position..end);
+        self.ensure_cursor_visible();
+        true
+    }
     // it worked so I can forget how I did this
     // font measure caching thing
     pub fn refresh_font_measurements(&mut self) {
@@ -452,13 +568,22 @@ impl Game {
         self.needs_font_refresh = false;
     }
     
+    /// Width of the line number gutter left of the code; none when line numbers are turned off
+    pub fn line_number_width(&self) -> f32 {
+        if self.menu.settings.editor.line_numbers {
+            scale_size(28.0)
+        } else {
+            0.0
+        }
+    }
+
     // figure out the position of stuff
     pub fn get_text_position(&mut self, line_index: usize, col_index: usize, editor_bounds: (f32, f32, f32, f32)) -> (f32, f32) {
         let (editor_x, editor_y, _editor_width, _editor_height) = editor_bounds;
         let input_y = editor_y + scale_size(55.0);
         let line_height = self.get_cached_line_height();
         let cached_font_size = self.get_cached_font_size(); // get cached values first
-        let line_number_width = scale_size(35.0);
+        let line_number_width = self.line_number_width();
         let text_x = editor_x + line_number_width + scale_size(5.0);
         let text_start_offset = scale_size(12.0);
         
@@ -467,13 +592,12 @@ impl Game {
         let y = input_y + text_start_offset + (display_line as f32 * line_height);
         
         // do the x position by measuring exact character width
-        let lines: Vec<&str> = self.current_code.lines().collect();
+        let line = self.current_code.line_graphemes(line_index);
         let mut x = text_x;
         
-        if line_index < lines.len() && col_index <= lines[line_index].len() {
-            let line = lines[line_index];
-            let text_before = &line[..col_index];
-            let text_dimensions = measure_text(text_before, None, cached_font_size as u16, 1.0);
+        if col_index <= line.len() {
+            let text_before = line[..col_index].concat();
+            let text_dimensions = measure_text(&text_before, None, cached_font_size as u16, 1.0);
             x += text_dimensions.width;
         }
         
@@ -490,7 +614,7 @@ impl Game {
         let input_y = editor_y + scale_size(55.0);
         let line_height = self.get_cached_line_height();
         let cached_font_size = self.get_cached_font_size(); // cache first
-        let line_number_width = scale_size(35.0);
+        let line_number_width = self.line_number_width();
         let text_x = editor_x + line_number_width + scale_size(5.0);
         let text_start_offset = scale_size(12.0);
         
@@ -504,17 +628,15 @@ impl Game {
         let clicked_line_offset = (click_offset_from_text_start / line_height).floor() as usize;
         let target_line = self.code_scroll_offset + clicked_line_offset;
         
-        let lines: Vec<&str> = self.current_code.lines().collect();
-        if target_line >= lines.len() {
+        let line_count = self.current_code.line_count();
+        if target_line >= line_count {
             // If you went to far then have it go to last line
-            if let Some(last_line) = lines.last() {
-                return Some((lines.len() - 1, last_line.len()));
-            }
-            return Some((0, 0));
+            let last_line = line_count.saturating_sub(1);
+            return Some((last_line, self.current_code.line_len(last_line)));
         }
         
         // find character position within the line using precise measurements
-        let line = lines[target_line];
+        let line = self.current_code.line_graphemes(target_line);
         let click_offset = click_x - text_x;
         
         // Found some code online that chatgpt and claude explained to me. This looks like a good idea for precision
@@ -522,8 +644,8 @@ impl Game {
         let mut best_distance = f32::INFINITY;
         
         for col in 0..=line.len() {
-            let text_before = &line[..col];
-            let text_dimensions = measure_text(text_before, None, cached_font_size as u16, 1.0);
+            let text_before = line[..col].concat();
+            let text_dimensions = measure_text(&text_before, None, cached_font_size as u16, 1.0);
             let distance = (text_dimensions.width - click_offset).abs();
             
             if distance < best_distance {
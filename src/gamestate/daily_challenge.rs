@@ -0,0 +1,91 @@
+// Daily Challenge: one generated level per day, the same for everyone since its seed comes from the
+// date. Each code run uses one of the day's attempts, and finishing the level records a score on the
+// local leaderboard (see crate::leaderboard). Like Endless Mode, the level lives in an extra slot
+// at the end of the level list.
+
+use super::types::Game;
+use crate::i18n::{tr, tr_with};
+use crate::leaderboard::{self, DailyLeaderboard, MAX_DAILY_ATTEMPTS};
+use crate::popup::PopupType;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// Generator difficulty of the daily level, as deep as a few rounds into Endless Mode
+const DAILY_DEPTH: usize = 4;
+
+impl Game {
+    pub fn start_daily_challenge(&mut self) {
+        let day = leaderboard::today();
+        let seed = leaderboard::seed_for_day(day);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let params = crate::level::generator::GeneratorParams::for_endless_depth(DAILY_DEPTH);
+        let config = crate::level::generator::generate_level(&params, &mut rng);
+
+        let mut spec = match config.to_level_spec(&mut rng) {
+            Ok(spec) => spec,
+            Err(e) => {
+                log::error!("Failed to build the daily level: {}", e);
+                return;
+            }
+        };
+        let date = leaderboard::date_label(day);
+        spec.name = tr_with("daily.level_name", &[("date", &date)]);
+        spec.seed = Some(seed);
+
+        self.levels.push(spec);
+        self.daily_challenge = Some(day);
+        self.level_idx = self.levels.len() - 1;
+        self.load_level(self.level_idx);
+
+        let attempts = DailyLeaderboard::load_or_default().attempts_left(day);
+        self.popup_system.show_message(
+            tr("daily.title"),
+            tr_with("daily.started", &[("date", &date), ("attempts", &attempts.to_string())]),
+            PopupType::Info,
+            None,
+        );
+    }
+
+    /// Leave the Daily Challenge and drop its level from the level list
+    pub fn stop_daily_challenge(&mut self) {
+        if self.daily_challenge.take().is_some() {
+            self.levels.pop();
+        }
+    }
+
+    /// Called before running code: uses up one of today's attempts, or explains why the run can't
+    /// happen once they're gone
+    pub fn use_daily_attempt(&mut self) -> Result<(), String> {
+        let Some(day) = self.daily_challenge else { return Ok(()) };
+        let mut board = DailyLeaderboard::load_or_default();
+        if !board.use_attempt(day) {
+            return Err(tr_with("daily.no_attempts", &[("max", &MAX_DAILY_ATTEMPTS.to_string())]));
+        }
+        if let Err(e) = board.save() {
+            log::warn!("Couldn't save the daily leaderboard: {}", e);
+        }
+        Ok(())
+    }
+
+    pub(super) fn finish_daily_challenge(&mut self) {
+        let Some(day) = self.daily_challenge else { return };
+        let result = leaderboard::score(day, self.turns, &self.current_code.to_string());
+        leaderboard::sync_result(&self.menu.settings.daily_challenge_url, &result);
+
+        let mut board = DailyLeaderboard::load_or_default();
+        board.results.push(result.clone());
+        if let Err(e) = board.save() {
+            log::warn!("Couldn't save the daily leaderboard: {}", e);
+        }
+
+        let best = board.results_for(day).first().map_or(result.score, |r| r.score);
+        let content = tr_with("daily.result", &[
+            ("score", &result.score.to_string()),
+            ("turns", &result.turns.to_string()),
+            ("length", &result.code_length.to_string()),
+            ("best", &best.to_string()),
+            ("attempts", &board.attempts_left(day).to_string()),
+        ]);
+        self.popup_system.show_message(tr("daily.result_title"), content, PopupType::Success, None);
+    }
+}
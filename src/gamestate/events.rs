@@ -0,0 +1,107 @@
+// Game event bus. Game mechanics publish what happened as a GameEvent instead of calling every side
+// effect inline; the subscribers (popups, the combat log, achievements, tutorial checks and the
+// "events" log target) react once the queued events are dispatched. The turn scheduler dispatches
+// at the end of every action, so a subscriber sees the events in the order they were published.
+// Events a subscriber publishes are dispatched in the same pass, after the ones already queued.
+
+use super::Game;
+use game_core::combat_log::CombatEvent;
+use std::collections::VecDeque;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    ItemCollected { name: String, on_pickup: Option<String> }, // on_pickup comes from the item file
+    Combat(CombatEvent),
+    LevelCompleted { level: usize },
+    ProgramFinished, // Every call of a run has been played out
+}
+
+pub type Subscriber = fn(&mut Game, &GameEvent);
+
+#[derive(Debug)]
+pub struct EventBus {
+    queue: VecDeque<GameEvent>,
+    subscribers: Vec<Subscriber>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    /// A bus with the game's own subscribers
+    pub fn new() -> Self {
+        let mut bus = Self { queue: VecDeque::new(), subscribers: Vec::new() };
+        bus.subscribe(log_event);
+        bus.subscribe(show_popups);
+        bus.subscribe(record_combat);
+        #[cfg(feature = "steam")]
+        bus.subscribe(unlock_achievements);
+        bus.subscribe(check_tutorial);
+        bus
+    }
+
+    pub fn subscribe(&mut self, subscriber: Subscriber) {
+        self.subscribers.push(subscriber);
+    }
+
+    pub fn publish(&mut self, event: GameEvent) {
+        self.queue.push_back(event);
+    }
+}
+
+impl Game {
+    pub fn publish(&mut self, event: GameEvent) {
+        self.events.publish(event);
+    }
+
+    /// Hand every queued event to every subscriber, oldest first
+    pub fn dispatch_events(&mut self) {
+        let subscribers = self.events.subscribers.clone();
+        while let Some(event) = self.events.queue.pop_front() {
+            for subscriber in &subscribers {
+                subscriber(self, &event);
+            }
+        }
+    }
+}
+
+fn log_event(_game: &mut Game, event: &GameEvent) {
+    log::debug!(target: "events", "{:?}", event);
+}
+
+fn show_popups(game: &mut Game, event: &GameEvent) {
+    let GameEvent::ItemCollected { name, on_pickup } = event else { return };
+    game.show_item_collected(name);
+    if let Some(message) = on_pickup {
+        game.popup_system.show_message(format!("📦 {}", name), message.clone(), crate::popup::PopupType::Info, None);
+    }
+}
+
+/// Combat events wait in combat_events until the turn scheduler logs them under their turn
+fn record_combat(game: &mut Game, event: &GameEvent) {
+    if let GameEvent::Combat(event) = event {
+        game.combat_events.push(event.clone());
+    }
+}
+
+/// Levels with an achievement message are the game's achievements; Steam builds mirror them as
+/// LEVEL_<n>, plus ALL_LEVELS once every level is done
+#[cfg(feature = "steam")]
+fn unlock_achievements(game: &mut Game, event: &GameEvent) {
+    let GameEvent::LevelCompleted { level } = *event else { return };
+    if game.levels[level].achievement_message.is_some() {
+        crate::steam::unlock_achievement(&format!("LEVEL_{}", level + 1));
+    }
+    if (0..game.levels.len()).all(|level| game.menu.progress.is_level_completed(level)) {
+        crate::steam::unlock_achievement("ALL_LEVELS");
+    }
+}
+
+fn check_tutorial(game: &mut Game, event: &GameEvent) {
+    if *event == GameEvent::ProgramFinished {
+        game.check_tutorial_progress();
+    }
+}
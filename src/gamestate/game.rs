@@ -1,29 +1,52 @@
 use super::types::*;
-use crate::level::LevelSpec;
+use crate::level::{KeyColor, LevelSpec};
 use crate::grid::Grid;
+use crate::grid::combat::{EMP_STUN_TURNS, LASER_STUN_TURNS, MAX_EMP_RADIUS};
+use super::events::GameEvent;
+use game_core::combat_log::{CombatEvent, Weapon};
 use crate::robot::Robot;
 use crate::item::ItemManager;
+use game_core::win_conditions::{WinConditionEvaluator, WinState};
 use crate::menu::Menu;
 use crate::popup::{PopupSystem, PopupAction};
 use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+pub use game_core::interpreter::TELEPORT_TURN_COST;
+
+// Mirrors a laser beam can bounce off before it fades out
+pub const MAX_LASER_BOUNCES: usize = 8;
+
+// Turns of history kept for rewind()
+pub const MAX_REWIND_TURNS: usize = 50;
 
 impl Game {
     pub fn new(levels: Vec<LevelSpec>, mut rng: StdRng) -> Self {
         let first = levels.first().expect("no levels").clone();
         let grid = Grid::from_level_spec(&first, &mut rng, false);
-        let robot = Robot::new((first.start.0 as i32, first.start.1 as i32));
+        let level_seed = first.seed.unwrap_or_else(|| rng.next_u64());
+        let mut robot = Robot::new((first.start.0 as i32, first.start.1 as i32));
+        let robot_start = robot.get_pos();
         let item_manager = ItemManager::new();
+        let menu = Menu::new();
+        robot.set_auto_grab(first.auto_grab.unwrap_or(menu.settings.auto_grab));
+        let grid_camera = crate::drawing::camera::GridCamera::with_zoom(menu.settings.grid_zoom);
 
         Self {
             level_idx: 0,
             levels,
             grid,
             robot,
+            robots: Vec::new(),
+            active_robot: 0,
+            robots_acted: std::collections::HashSet::new(),
             item_manager,
             rng,
+            level_seed,
             credits: 0,
             turns: 0,
             max_turns: first.max_turns,
+            energy: first.energy.map(|e| e.capacity),
             discovered_this_level: 0,
             finished: false,
             scan_armed: false,
@@ -34,7 +57,8 @@ impl Game {
             #[cfg(not(target_arch = "wasm32"))]
             file_watcher_receiver: None,
             robot_code_modified: false,
-            current_code: String::new(),
+            robot_code_saves: 0,
+            current_code: crate::text_buffer::TextBuffer::new(),
             cursor_position: 0,
             code_execution_requested: false,
             selection_start: None,
@@ -43,17 +67,26 @@ impl Game {
             is_dragging: false,
             code_scroll_offset: 0,
             code_lines_visible: 30, // Default number of lines visible
+            code_scroll_column: 0,
+            code_columns_visible: 40, // Until the editor is first drawn
+            goto_line_input: None,
             tutorial_scroll_offset: 0,
             enemy_step_paused: false,
             time_slow_active: false,
             time_slow_duration_ms: 500, // Default 500ms
-            menu: Menu::new(),
+            menu,
             popup_system: PopupSystem::new(),
-            stunned_enemies: std::collections::HashMap::new(),
+            teleport_effect: None,
+            emp_effect: None,
+            laser_path: None,
+            turn_history: std::collections::VecDeque::new(),
+            level_loads: 0,
             temporary_removed_obstacles: std::collections::HashMap::new(),
             println_outputs: Vec::new(),
             error_outputs: Vec::new(),
+            log_records: Vec::new(),
             panic_occurred: false,
+            serde_round_trips: Vec::new(),
             tutorial_state: TutorialState {
                 task_completed: [false; 5],
                 current_task: 0,
@@ -62,7 +95,22 @@ impl Game {
                 u32_move_used: false,
             },
             #[cfg(not(target_arch = "wasm32"))]
-            rust_checker: crate::rust_checker::RustChecker::new().ok(),
+            rust_checker: crate::rust_checker::BackgroundChecker::new().ok(),
+            #[cfg(not(target_arch = "wasm32"))]
+            jobs: crate::jobs::JobQueue::new().ok(),
+            #[cfg(not(target_arch = "wasm32"))]
+            autosave: crate::autosave::Autosave::new(),
+            #[cfg(feature = "vcs")]
+            code_history: crate::code_history::HistoryPanel::open().ok(),
+            solution_comparison: None,
+            solution_playback: None,
+            program_running: false,
+            program_paused: false,
+            cancel_requested: false,
+            robot_animation: None,
+            grid_camera,
+            announced_task: None,
+            last_activity: 0.0,
             key_backspace_held_time: 0.0,
             key_space_held_time: 0.0,
             key_char_held_time: 0.0,
@@ -78,10 +126,16 @@ impl Game {
             cached_line_height: 0.0,
             needs_font_refresh: true,      // Initially needs refresh
             editor_tab: EditorTab::Commands, // Default to Commands tab
+            log_filter: crate::popup::LogFilter::All,
+            game_log_view: crate::game_log::LogView::default(),
+            combat_log: Default::default(),
+            combat_events: Vec::new(),
+            events: super::events::EventBus::new(),
+            combat_log_scroll: 0,
+            ownership_timelines: Vec::new(),
+            watch: Default::default(),
             coordinate_transformer: crate::coordinate_system::CoordinateTransformer::new(), // Initialize coordinate transformer
             last_system_key_time: 0.0,    // Initialize system key timer
-            enable_coordinate_logs: false, // Default to disabled, enabled via --all-logs command line flag
-            enable_key_press_logs: false,  // Default to disabled, enabled via --all-logs command line flag
             last_key_log_time: 0.0,        // Initialize log rate limiting timers
             last_exec_log_time: 0.0,
             last_window_update_time: 0.0, // Initialize timer
@@ -89,35 +143,54 @@ impl Game {
             autocomplete_engine: crate::autocomplete::AutocompleteEngine::new(),
             autocomplete_enabled: true,   // Enable autocomplete by default
             hotkey_system: crate::hotkeys::HotkeySystem::new(),
+            vim: None,
+            mark_active: false,
+            clipboard_history: Default::default(),
             // Initialize undo functionality
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            endless_depth: None,
+            daily_challenge: None,
+            sandbox_tool: None,
+            dry_run: false,
+            time_left: first.real_time_limit_secs.map(|secs| secs as f32),
+            speech: None,
+            lint: None,
+            race_path: vec![robot_start],
+            best_runs: super::ghost_race::BestRuns::load_or_default(),
         }
     }
 
+    /// Robot functions programs may call on this level (see game_core::capabilities)
     pub fn get_available_functions(&self) -> Vec<RustFunction> {
-        vec![
-            RustFunction::Move,
-            RustFunction::Scan, 
-            RustFunction::Grab,
-            RustFunction::LaserDirection,
-            RustFunction::LaserTile,
-            RustFunction::OpenDoor,
-            RustFunction::SkipLevel,
-            RustFunction::GotoLevel,
-        ]
+        self.with_capabilities(|capabilities| capabilities.available(false))
     }
-    
+
     // Functions displayed in GUI (excludes skip/goto commands and print functions)
     pub fn get_gui_functions(&self) -> Vec<RustFunction> {
-        vec![
-            RustFunction::Move,
-            RustFunction::Scan, 
-            RustFunction::Grab,
-            RustFunction::LaserDirection,
-            RustFunction::LaserTile,
-            RustFunction::OpenDoor,
-        ]
+        self.with_capabilities(|capabilities| capabilities.available(true))
+    }
+
+    /// Functions `calls` makes that this level's allowed_functions rules out
+    pub fn forbidden_calls(&self, calls: &[FunctionCall]) -> Vec<&'static str> {
+        self.with_capabilities(|capabilities| capabilities.forbidden_calls(calls))
+    }
+
+    fn with_capabilities<T>(&self, f: impl FnOnce(&game_core::capabilities::CapabilityContext) -> T) -> T {
+        // Upgrades bought in the shop count alongside abilities from picked up items
+        let mut upgrades = self.robot.abilities.clone();
+        if self.robot.upgrades.scanner_level > 0 {
+            upgrades.insert("scanner".to_string());
+        }
+        if self.robot.upgrades.attack_range > 0 {
+            upgrades.insert("attack".to_string());
+        }
+        f(&game_core::capabilities::CapabilityContext {
+            allowed_functions: self.levels[self.level_idx].allowed_functions.as_deref(),
+            upgrades: &upgrades,
+            multi_robot: self.is_multi_robot(),
+            unlock_all: self.in_sandbox(),
+        })
     }
 
     pub fn finish_level(&mut self) {
@@ -125,25 +198,108 @@ impl Game {
         let reward = self.discovered_this_level as u32;
         self.credits += reward;
         
+        // Generated endless and daily levels aren't part of the saved campaign progress
+        if self.endless_depth.is_some() || self.daily_challenge.is_some() {
+            return;
+        }
+
         // Mark current level as completed and unlock next level
+        let was_complete = self.menu.campaign_complete();
         self.menu.progress.mark_level_completed(self.level_idx);
         if self.level_idx + 1 < self.levels.len() {
             self.menu.progress.unlock_level(self.level_idx + 1);
         }
+        if let Some(campaign) = &self.menu.campaign {
+            self.menu.progress.complete_campaign_level(campaign, self.level_idx);
+        }
+
+        // Finishing the campaign writes the completion certificate straight away
+        if !was_complete && self.menu.campaign_complete() {
+            let content = match self.menu.export_certificate() {
+                Ok(path) => crate::i18n::tr_with("certificate.saved", &[("path", &path.display().to_string())]),
+                Err(e) => e,
+            };
+            self.popup_system.show_message(crate::i18n::tr("certificate.saved_title"), content, crate::popup::PopupType::Success, None);
+        }
+
+        self.publish(GameEvent::LevelCompleted { level: self.level_idx });
+        self.dispatch_events();
+    }
+
+    /// The level was won rather than skipped: settle the ghost race and record the Daily Challenge
+    /// score, then finish it
+    fn complete_level(&mut self) {
+        // A dry run only needs to know the level would be won
+        if self.dry_run {
+            self.finished = true;
+            return;
+        }
+        self.finish_ghost_race();
+        self.show_code_metrics();
+        if self.daily_challenge.is_some() {
+            self.finish_daily_challenge();
+        }
+        self.finish_level();
     }
 
     pub fn next_level(&mut self) {
-        if self.level_idx + 1 < self.levels.len() {
+        if self.endless_depth.is_some() {
+            self.advance_endless_level();
+        } else if self.level_idx + 1 < self.levels.len() {
             self.level_idx += 1;
             self.load_level(self.level_idx);
         }
     }
 
+    /// Start Endless Mode with a freshly generated level
+    pub fn start_endless_mode(&mut self) {
+        self.endless_depth = Some(0);
+        self.load_endless_level();
+    }
+
+    /// Leave Endless Mode and drop the generated level from the level list
+    pub fn stop_endless_mode(&mut self) {
+        if self.endless_depth.take().is_some() {
+            self.levels.pop();
+        }
+    }
+
+    pub fn advance_endless_level(&mut self) {
+        let depth = self.endless_depth.map_or(0, |d| d + 1);
+        self.endless_depth = Some(depth);
+        self.load_endless_level();
+    }
+
+    // The generated level always lives in one extra slot at the end of the level list
+    fn load_endless_level(&mut self) {
+        let depth = self.endless_depth.unwrap_or(0);
+        let params = crate::level::generator::GeneratorParams::for_endless_depth(depth);
+        let config = crate::level::generator::generate_level(&params, &mut self.rng);
+
+        let mut spec = match config.to_level_spec(&mut self.rng) {
+            Ok(spec) => spec,
+            Err(e) => {
+                log::error!("Failed to build generated level: {}", e);
+                return;
+            }
+        };
+        spec.name = format!("Endless #{} - {}", depth + 1, spec.name);
+
+        if depth > 0 && self.level_idx + 1 == self.levels.len() {
+            let last = self.levels.len() - 1;
+            self.levels[last] = spec;
+        } else {
+            self.levels.push(spec);
+        }
+        self.level_idx = self.levels.len() - 1;
+        self.load_level(self.level_idx);
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     pub fn load_robot_code(&mut self) {
         if let Ok(code) = crate::read_robot_code(&self.robot_code_path) {
-            self.current_code = code;
-            self.cursor_position = self.cursor_position.min(self.current_code.len());
+            self.current_code = code.into();
+            self.cursor_position = self.current_code.floor_char_boundary(self.cursor_position);
         }
     }
 
@@ -154,6 +310,7 @@ impl Game {
 
     #[cfg(not(target_arch = "wasm32"))]
     pub fn save_robot_code(&mut self) {
+        self.robot_code_saves += 1;
         if let Err(e) = crate::write_robot_code(&self.robot_code_path, &self.current_code) {
             self.execution_result = format!("Save error: {}", e);
         }
@@ -164,22 +321,108 @@ impl Game {
         // WASM version - no file I/O
     }
 
+    /// After an unclean shutdown, offer to restore the newest autosave if it differs from the saved file
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn offer_autosave_restore(&mut self) {
+        let Some(snapshot) = self.autosave.newest_snapshot() else { return };
+        if *self.current_code == *snapshot {
+            return;
+        }
+        self.popup_system.show_message(
+            crate::i18n::tr("game.restore_title"),
+            crate::i18n::tr_with("game.restore_body", &[
+                ("lines", &snapshot.lines().count().to_string()),
+                ("path", &self.robot_code_path),
+                ("dir", &crate::autosave::autosave_dir().display().to_string()),
+            ]),
+            crate::popup::PopupType::RestoreAutosave,
+            None
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn restore_autosave(&mut self) {
+        if let Some(snapshot) = self.autosave.newest_snapshot() {
+            self.current_code = snapshot.into();
+            self.cursor_position = self.current_code.floor_char_boundary(self.cursor_position);
+            self.save_robot_code();
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn restore_autosave(&mut self) {
+        // WASM version - no file I/O
+    }
+
+    /// Commit the code of a successful run to the history panel, labelled with the level and task
+    #[cfg(feature = "vcs")]
+    pub fn record_code_history(&mut self, code: &str) {
+        let level = self.levels.get(self.level_idx).map(|level| level.name.clone()).unwrap_or_default();
+        let message = match self.get_max_tasks_for_level(self.level_idx) {
+            Some(max_tasks) => format!("{} - task {}", level, (self.tutorial_state.current_task + 1).min(max_tasks)),
+            None => level,
+        };
+        if let Some(panel) = self.code_history.as_mut() {
+            if let Err(e) = panel.record(code, &message) {
+                panel.status = Some(e);
+            }
+        }
+    }
+
+    /// Replace the editor contents with the entry selected in the history panel
+    #[cfg(feature = "vcs")]
+    pub fn restore_code_history(&mut self) {
+        let Some(panel) = self.code_history.as_mut() else { return };
+        let Some(entry) = panel.selected_entry().cloned() else { return };
+        match panel.history.code_at(&entry.id) {
+            Ok(code) => {
+                panel.diff = None;
+                panel.status = Some(format!("Restored {} ({})", entry.short_id(), entry.message));
+                self.current_code = code.into();
+                self.cursor_position = self.current_code.floor_char_boundary(self.cursor_position);
+                self.save_robot_code();
+            }
+            Err(e) => panel.status = Some(e),
+        }
+    }
+
+    /// Open the results screen comparing the editor with the level's reference solution
+    pub fn open_solution_comparison(&mut self) {
+        let level_name = self.levels[self.level_idx].name.clone();
+        match crate::learning_level_solutions::get_reference_solution_for_level(&level_name) {
+            Some(solution) => {
+                self.solution_comparison = Some(crate::code_diff::SolutionComparison::new(
+                    format!("{} - {}", level_name, solution.task_description),
+                    &self.current_code,
+                    solution.solution_code,
+                ));
+                self.code_editor_active = false;
+            }
+            None => self.popup_system.show_message(
+                crate::i18n::tr("game.no_solution_title"),
+                crate::i18n::tr_with("game.no_solution", &[("level", &level_name)]),
+                crate::popup::PopupType::Info,
+                Some(3.0)
+            ),
+        }
+    }
+
     // Request code execution (used by Ctrl+Shift+Enter)
     pub fn request_code_execution(&mut self) {
         // Set a flag that the main loop can check to trigger code execution
         self.code_execution_requested = true;
-        println!("🚀 Code execution requested via Ctrl+Shift+Enter");
+        log::debug!(target: "exec", "Code execution requested via Ctrl+Shift+Enter");
     }
 
     // Rate-limited logging helpers (max 1 per second to prevent spam)
     pub fn log_key_press(&mut self, message: &str) -> bool {
-        if !self.enable_key_press_logs {
+        if !log::log_enabled!(target: "keys", log::Level::Debug) {
             return false;
         }
         let current_time = crate::crash_protection::safe_get_time();
         if current_time - self.last_key_log_time >= 1.0 {
             self.last_key_log_time = current_time;
-            println!("🔍 [KEY] {}", message);
+            log::debug!(target: "keys", "{}", message);
             true
         } else {
             false
@@ -187,13 +430,13 @@ impl Game {
     }
 
     pub fn log_execution(&mut self, message: &str) -> bool {
-        if !self.enable_key_press_logs {
+        if !log::log_enabled!(target: "exec", log::Level::Debug) {
             return false;
         }
         let current_time = crate::crash_protection::safe_get_time();
         if current_time - self.last_exec_log_time >= 1.0 {
             self.last_exec_log_time = current_time;
-            println!("🔍 [EXEC] {}", message);
+            log::debug!(target: "exec", "{}", message);
             true
         } else {
             false
@@ -202,50 +445,106 @@ impl Game {
 
     // Immediate logging (no rate limiting) for important events
     pub fn log_key_immediate(&self, message: &str) {
-        if self.enable_key_press_logs {
-            println!("🔍 [KEY] {}", message);
-        }
+        log::debug!(target: "keys", "{}", message);
     }
 
     pub fn log_execution_immediate(&self, message: &str) {
-        if self.enable_key_press_logs {
-            println!("🔍 [EXEC] {}", message);
+        log::debug!(target: "exec", "{}", message);
+    }
+
+    /// Export the records the Logs tab's Game log shows to game_log.txt
+    pub fn export_game_log(&mut self) {
+        match crate::game_log::export(&self.game_log_view) {
+            Ok(path) => self.popup_system.show_message(
+                crate::i18n::tr("game_log.exported_title"),
+                crate::i18n::tr_with("game_log.exported", &[("path", &path.display().to_string())]),
+                crate::popup::PopupType::Success,
+                None,
+            ),
+            Err(e) => self.popup_system.show_message(crate::i18n::tr("game_log.export_failed_title"), e, crate::popup::PopupType::Warning, None),
         }
     }
 
     pub fn load_level(&mut self, idx: usize) {
+        // A reset during a dry run ends it; the turn counter going back to 0 is all it needs
+        if self.dry_run {
+            self.turns = 0;
+            return;
+        }
         let spec = self.levels[idx].clone();
+        // A pinned seed replays the level exactly; otherwise every load gets a fresh one
+        if let Some(seed) = spec.seed {
+            self.rng = StdRng::seed_from_u64(seed);
+        }
         let mut grid = Grid::from_level_spec(&spec, &mut self.rng, self.item_manager.has_collected("scanner"));
+        self.level_seed = spec.seed.unwrap_or_else(|| self.rng.next_u64());
         let start = (spec.start.0 as i32, spec.start.1 as i32);
         self.robot.set_position(start);
+        self.robot.facing = (0, -1);
+        self.robot_animation = None;
+        self.last_activity = crate::crash_protection::safe_get_time();
+        self.grid_camera.reset();
+        self.robot.clear_inventory(); // Held items are per level; upgrades carry over
+        self.robot.set_auto_grab(spec.auto_grab.unwrap_or(self.menu.settings.auto_grab));
+        self.serde_round_trips.clear();
+
+        // Extra robots start with the same upgrades as robot 0
+        self.robots.clear();
+        self.active_robot = 0;
+        self.robots_acted.clear();
+        if !spec.robots.is_empty() {
+            self.robots.push(self.robot.clone());
+            for &(x, y) in &spec.robots {
+                let mut extra = self.robot.clone();
+                extra.set_position((x as i32, y as i32));
+                self.robots.push(extra);
+            }
+        }
 
-        // Reveal starting tile + neighbors
+        // Reveal starting tiles + neighbors
         grid.reveal_adjacent(start);
+        for &(x, y) in &spec.robots {
+            grid.reveal_adjacent((x as i32, y as i32));
+        }
+        grid.update_plates(crate::item::Pos { x: start.0, y: start.1 });
 
         // For Level 6, also reveal robot fleet positions for ownership demonstration
         if idx == 5 { // Level 6 (0-indexed)
-            println!("🎮 Initializing Level 6 - revealing robot fleet positions (found {} enemies)", grid.enemies.len());
+            log::info!(target: "level", "Initializing Level 6 - revealing robot fleet positions (found {} enemies)", grid.enemies.len());
             for enemy in &grid.enemies {
                 if let Some(ref pattern) = enemy.movement_pattern {
                     if pattern.contains("ownership_demo") ||
                        pattern.contains("borrowing_demo") ||
                        pattern.contains("clone_demo") {
-                        println!("👁️ Revealing robot {} at position ({}, {})", pattern, enemy.pos.x, enemy.pos.y);
+                        log::debug!(target: "level", "Revealing robot {} at position ({}, {})", pattern, enemy.pos.x, enemy.pos.y);
                         grid.known.insert(crate::item::Pos { x: enemy.pos.x, y: enemy.pos.y });
                     }
                 } else {
-                    println!("⚠️ Enemy at ({}, {}) has no movement pattern", enemy.pos.x, enemy.pos.y);
+                    log::warn!(target: "level", "Enemy at ({}, {}) has no movement pattern", enemy.pos.x, enemy.pos.y);
                 }
             }
         }
 
         self.grid = grid;
+        if self.in_sandbox() {
+            self.reveal_sandbox();
+        }
+        self.level_loads += 1;
+        self.turn_history.clear();
         self.turns = 0;
+        self.race_path = vec![self.robot.get_pos()];
         self.max_turns = spec.max_turns;
+        self.energy = spec.energy.map(|e| e.capacity);
+        self.time_left = spec.real_time_limit_secs.map(|secs| secs as f32);
+        self.speech = None;
         self.discovered_this_level = 0;
         self.finished = false;
         self.scan_armed = false;
         self.enemy_step_paused = false;
+        self.ownership_timelines.clear();
+        if self.editor_tab == EditorTab::Ownership && !spec.ownership_view {
+            self.editor_tab = EditorTab::Editor;
+        }
         
         // Reset tutorial state and outputs for learning levels when starting fresh
         let should_reset_tutorial = if self.is_learning_level(idx) {
@@ -266,24 +565,26 @@ impl Game {
             };
             self.println_outputs.clear();
             self.error_outputs.clear();
+            self.log_records.clear();
             self.panic_occurred = false;
         } else if !self.is_learning_level(idx) {
             // Clear outputs for non-tutorial levels
             self.println_outputs.clear();
             self.error_outputs.clear();
+            self.log_records.clear();
             self.panic_occurred = false;
         }
         
         // Load starting code if available, otherwise ensure current_code has content
         if let Some(ref starting_code) = spec.starting_code {
-            self.current_code = starting_code.clone();
+            self.current_code = starting_code.clone().into();
             self.cursor_position = starting_code.len();
         } else {
             // Ensure current_code is not empty if no starting code is provided
             if self.current_code.is_empty() {
-                self.current_code = "// Start typing your Rust code here...\n".to_string();
+                self.current_code = "// Start typing your Rust code here...\n".into();
             }
-            self.cursor_position = self.cursor_position.min(self.current_code.len());
+            self.cursor_position = self.current_code.floor_char_boundary(self.cursor_position);
         }
 
         // Initialize item manager with level items
@@ -293,9 +594,7 @@ impl Game {
                 self.item_manager.add_item(
                     item_spec.name.clone(),
                     crate::item::Pos { x: pos.0, y: pos.1 },
-                    item_spec.capabilities.get("file_path")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string())
+                    item_spec.capabilities.clone()
                 );
             }
         }
@@ -319,8 +618,8 @@ impl Game {
         }
         
         // Then show base level message if it exists (initial information/hints)
-        if let Some(ref message) = spec.message {
-            self.popup_system.show_level_message(message.clone());
+        if let Some(message) = spec.localized_message(crate::i18n::current_language().code()) {
+            self.popup_system.show_level_message(message.to_string());
         }
     }
 
@@ -380,38 +679,26 @@ impl Game {
         }
     }
     
-    #[cfg(not(target_arch = "wasm32"))]
     pub fn open_rust_docs(&self) -> String {
         let current_level = &self.levels[self.level_idx];
         if let Some(ref url) = current_level.rust_docs_url {
-            if let Err(e) = std::process::Command::new("cmd")
-                .args(["/C", "start", url])
-                .spawn() {
-                format!("Failed to open browser: {}. Manual URL: {}", e, url)
-            } else {
-                format!("Opening Rust docs: {}", url)
+            match open_url(url) {
+                Ok(()) => format!("Opening Rust docs: {}", url),
+                Err(e) => e,
             }
         } else {
             "No documentation URL available for this level.".to_string()
         }
     }
-    
-    #[cfg(target_arch = "wasm32")]
-    pub fn open_rust_docs(&self) -> String {
-        let current_level = &self.levels[self.level_idx];
-        if let Some(ref url) = current_level.rust_docs_url {
-            // For WASM, we'll use JavaScript to open the URL
-            unsafe {
-                let js_code = format!("window.open('{}', '_blank');", url);
-                js_sys::eval(&js_code).ok();
-            }
-            format!("Opening Rust docs: {}", url)
-        } else {
-            "No documentation URL available for this level.".to_string()
-        }
+
+    /// Swap the sidebar between the code editor and `tab`
+    pub fn toggle_editor_tab(&mut self, tab: EditorTab) {
+        self.editor_tab = if self.editor_tab == tab { EditorTab::Editor } else { tab };
+        self.code_editor_active = false;
     }
 
     pub fn update_popup_system(&mut self, delta_time: f32) {
+        self.popup_system.toast_settings = self.menu.settings.toasts;
         self.popup_system.update(delta_time);
     }
 
@@ -421,13 +708,17 @@ impl Game {
         // Handle popup actions
         match action {
             PopupAction::NextLevel => {
-                if self.level_idx + 1 < self.levels.len() {
+                if self.endless_depth.is_some() {
+                    self.advance_endless_level();
+                } else if self.daily_challenge.is_some() {
+                    // One level a day; the result popup already said how it went
+                } else if self.level_idx + 1 < self.levels.len() {
                     self.load_level(self.level_idx + 1);
                 } else {
                     // Last level completed
                     self.popup_system.show_message(
-                        "🏆 Game Complete!".to_string(),
-                        "Congratulations! You've completed all levels and mastered the basics of Rust programming!".to_string(),
+                        crate::i18n::tr("game.complete_title"),
+                        crate::i18n::tr("game.complete"),
                         crate::popup::PopupType::Success,
                         None
                     );
@@ -437,6 +728,14 @@ impl Game {
                 // Player chose to stay on current level, just clear the finished flag
                 self.finished = false;
             },
+            PopupAction::RestoreAutosave => self.restore_autosave(),
+            PopupAction::CompareSolution => self.open_solution_comparison(),
+            PopupAction::LearnMore(ref code) => {
+                self.execution_result = match open_url(&crate::error_catalog::docs_url(code)) {
+                    Ok(()) => format!("Opening the explanation of {}", code),
+                    Err(e) => e,
+                };
+            }
             _ => {}
         }
         
@@ -450,33 +749,56 @@ impl Game {
     // Laser system methods
     pub fn fire_laser_direction(&mut self, direction: (i32, i32)) -> String {
         let robot_pos = self.robot.get_position();
+        let mut direction = direction;
         let mut current_pos = (robot_pos.0 + direction.0, robot_pos.1 + direction.1);
+        let mut path = vec![self.robot.get_pos()];
+        let mut bounces = 0;
         
         // Trace laser path until it hits something
-        loop {
+        let result = loop {
             let pos = crate::item::Pos { x: current_pos.0, y: current_pos.1 };
             
             // Check bounds
             if !self.grid.in_bounds(pos) {
-                return "Laser fired but hit the edge of the grid.".to_string();
+                // The beam is drawn up to the last tile inside the grid
+                path.push(crate::item::Pos { x: pos.x - direction.0, y: pos.y - direction.1 });
+                break "Laser fired but hit the edge of the grid.".to_string();
+            }
+
+            // Mirrors turn the beam 90 degrees
+            if let Some(mirror) = self.grid.mirrors.get(&pos) {
+                path.push(pos);
+                bounces += 1;
+                if bounces > MAX_LASER_BOUNCES {
+                    break format!("Laser faded out after {} bounces.", MAX_LASER_BOUNCES);
+                }
+                direction = mirror.reflect(direction);
+                current_pos = (current_pos.0 + direction.0, current_pos.1 + direction.1);
+                continue;
             }
             
             // Check for enemy hit
-            for (i, enemy) in self.grid.enemies.iter().enumerate() {
-                if enemy.pos == pos {
-                    self.stunned_enemies.insert(i, 5); // Stun for 5 turns
-                    return format!("Laser hit enemy at ({}, {})! Enemy stunned for 5 turns.", current_pos.0, current_pos.1);
-                }
+            if let Some(i) = self.grid.enemies.iter().position(|enemy| enemy.occupies(pos)) {
+                path.push(pos);
+                break self.laser_hit_enemy(i, current_pos);
             }
             
             // Check for obstacle hit
             if self.grid.is_blocked(pos) {
+                path.push(pos);
                 self.hit_obstacle_with_laser(current_pos);
-                return format!("Laser hit obstacle at ({}, {})! Obstacle destroyed for 2 turns.", current_pos.0, current_pos.1);
+                break format!("Laser hit obstacle at ({}, {})! Obstacle destroyed for 2 turns.", current_pos.0, current_pos.1);
             }
             
             // Continue laser path
             current_pos = (current_pos.0 + direction.0, current_pos.1 + direction.1);
+        };
+
+        self.laser_path = Some((path, crate::crash_protection::safe_get_time()));
+        match bounces.min(MAX_LASER_BOUNCES) {
+            0 => result,
+            1 => format!("Laser bounced off a mirror. {}", result),
+            n => format!("Laser bounced off {} mirrors. {}", n, result),
         }
     }
 
@@ -489,11 +811,8 @@ impl Game {
         }
         
         // Check for enemy at target
-        for (i, enemy) in self.grid.enemies.iter().enumerate() {
-            if enemy.pos == pos {
-                self.stunned_enemies.insert(i, 5); // Stun for 5 turns
-                return format!("Laser hit enemy at ({}, {})! Enemy stunned for 5 turns.", target.0, target.1);
-            }
+        if let Some(i) = self.grid.enemies.iter().position(|enemy| enemy.occupies(pos)) {
+            return self.laser_hit_enemy(i, target);
         }
         
         // Check for obstacle at target
@@ -505,6 +824,315 @@ impl Game {
         "Laser fired but hit nothing at target location.".to_string()
     }
 
+    /// Laser damage scales with the attack upgrade level
+    pub fn laser_damage(&self) -> u32 {
+        self.robot.upgrades.attack_range.max(1)
+    }
+
+    /// Enemies without hit points are stunned; the rest take damage and are destroyed at 0 HP.
+    /// Bosses shrug off the stun.
+    fn laser_hit_enemy(&mut self, index: usize, at: (i32, i32)) -> String {
+        let damage = self.laser_damage();
+        let enemy = &mut self.grid.enemies[index];
+        if enemy.hp.is_none() {
+            self.grid.stun(index, LASER_STUN_TURNS);
+            self.publish(GameEvent::Combat(CombatEvent::EnemyStunned { enemy: index, weapon: Weapon::Laser, turns: LASER_STUN_TURNS }));
+            return format!("Laser hit enemy at ({}, {})! Enemy stunned for {} turns.", at.0, at.1, LASER_STUN_TURNS);
+        }
+
+        let is_boss = enemy.is_boss();
+        let name = if is_boss { "boss" } else { "enemy" };
+        let pattern_before = (enemy.movement_pattern.clone(), enemy.direction);
+        if enemy.take_damage(damage) {
+            self.grid.remove_enemy(index);
+            self.publish(GameEvent::Combat(CombatEvent::EnemyDestroyed { enemy: index, weapon: Weapon::Laser }));
+            return format!("Laser hit {} at ({}, {}) for {} damage! The {} was destroyed.", name, at.0, at.1, damage, name);
+        }
+
+        let hp = enemy.hp.unwrap_or(0);
+        let new_phase = pattern_before != (enemy.movement_pattern.clone(), enemy.direction);
+        self.publish(GameEvent::Combat(CombatEvent::EnemyHit { enemy: index, weapon: Weapon::Laser, damage, hp_left: hp }));
+        let mut result = format!("Laser hit {} at ({}, {}) for {} damage! {} HP left.", name, at.0, at.1, damage, hp);
+        if is_boss {
+            if new_phase {
+                result.push_str(" The boss enters a new phase!");
+            }
+        } else {
+            self.grid.stun(index, LASER_STUN_TURNS);
+            self.publish(GameEvent::Combat(CombatEvent::EnemyStunned { enemy: index, weapon: Weapon::Laser, turns: LASER_STUN_TURNS }));
+            result.push_str(&format!(" Enemy stunned for {} turns.", LASER_STUN_TURNS));
+        }
+        result
+    }
+
+    /// Stun every enemy within `radius` steps of the robot. Only available once an EMP emitter is
+    /// collected; bosses aren't affected.
+    pub fn fire_emp(&mut self, radius: u32) -> Result<String, String> {
+        if radius == 0 || radius > MAX_EMP_RADIUS {
+            return Err(format!("emp() needs a radius from 1 to {}.", MAX_EMP_RADIUS));
+        }
+        let center = self.robot.get_pos();
+        let hit = self.grid.emp(center, radius, EMP_STUN_TURNS);
+        for enemy in hit.iter().copied() {
+            self.publish(GameEvent::Combat(CombatEvent::EnemyStunned { enemy, weapon: Weapon::Emp, turns: EMP_STUN_TURNS }));
+        }
+        self.emp_effect = Some((center, radius, crate::crash_protection::safe_get_time()));
+        Ok(match hit.len() {
+            0 => format!("EMP pulse (radius {}) hit no enemies.", radius),
+            n => format!("EMP pulse (radius {}) stunned {} enemy(s) for {} turns.", radius, n, EMP_STUN_TURNS),
+        })
+    }
+
+    /// End-of-turn hazards: a conveyor under the robot shifts it one tile (if the tile is free),
+    /// then spikes that are deadly this turn reset the level. Returns a message if anything happened.
+    pub fn apply_turn_hazards(&mut self) -> Option<String> {
+        let mut message = None;
+        let pos = self.robot.get_pos();
+
+        if let Some((dx, dy)) = self.grid.conveyor_at(pos) {
+            let next = crate::item::Pos { x: pos.x + dx, y: pos.y + dy };
+            if self.grid.in_bounds(next) && !self.grid.is_blocked(next) && self.other_robot_at(next).is_none() {
+                self.robot.set_position((next.x, next.y));
+                self.grid.visit(next);
+                self.grid.reveal_adjacent((next.x, next.y));
+                self.grid.update_plates(next);
+                self.grid.flip_switch(next);
+                message = Some(format!("Conveyor moved the robot to ({}, {})", next.x, next.y));
+
+                if self.level_idx >= 3 && self.enemy_collision_at((next.x, next.y)) {
+                    let idx = self.level_idx;
+                    self.load_level(idx);
+                    return Some(super::ENEMY_COLLISION_MESSAGE.to_string());
+                }
+            }
+        }
+
+        if self.grid.spikes_active(self.robot.get_pos(), self.turns) {
+            let idx = self.level_idx;
+            self.load_level(idx);
+            return Some("SPIKE TRAP! The spikes were up this turn. Level reset.".to_string());
+        }
+
+        if self.grid.laser_beam_at(self.robot.get_pos(), self.turns) {
+            let idx = self.level_idx;
+            self.load_level(idx);
+            return Some("LASER! The robot was caught in a laser beam. Level reset.".to_string());
+        }
+
+        message
+    }
+
+    pub fn turn_snapshot(&self) -> TurnSnapshot {
+        TurnSnapshot {
+            grid: self.grid.clone(),
+            robot: self.robot.clone(),
+            robots: self.robots.clone(),
+            active_robot: self.active_robot,
+            item_manager: self.item_manager.clone(),
+            turns: self.turns,
+            energy: self.energy,
+            credits: self.credits,
+            discovered_this_level: self.discovered_this_level,
+            temporary_removed_obstacles: self.temporary_removed_obstacles.clone(),
+        }
+    }
+
+    /// Remember the state before a turn so it can be rewound, dropping the oldest beyond the limit
+    pub fn push_turn_snapshot(&mut self, snapshot: TurnSnapshot) {
+        if self.turn_history.len() >= MAX_REWIND_TURNS {
+            self.turn_history.pop_front();
+        }
+        self.turn_history.push_back(snapshot);
+    }
+
+    pub fn restore_turn_snapshot(&mut self, snapshot: TurnSnapshot) {
+        self.grid = snapshot.grid;
+        self.robot = snapshot.robot;
+        self.robots = snapshot.robots;
+        self.active_robot = snapshot.active_robot;
+        self.item_manager = snapshot.item_manager;
+        self.turns = snapshot.turns;
+        self.energy = snapshot.energy;
+        self.credits = snapshot.credits;
+        self.discovered_this_level = snapshot.discovered_this_level;
+        self.temporary_removed_obstacles = snapshot.temporary_removed_obstacles;
+    }
+
+    /// Go back `turns` turns (or as far as the history reaches)
+    pub fn rewind(&mut self, turns: usize) -> Result<String, String> {
+        if turns == 0 {
+            return Err("rewind() needs at least 1 turn.".to_string());
+        }
+        if self.finished {
+            return Err("The level is already complete.".to_string());
+        }
+        let steps = turns.min(self.turn_history.len());
+        if steps == 0 {
+            return Err("Nothing to rewind - no turns have been taken on this level yet.".to_string());
+        }
+
+        let snapshot = self.turn_history.drain(self.turn_history.len() - steps..).next()
+            .expect("steps is at least 1");
+        self.restore_turn_snapshot(snapshot);
+        self.robots_acted.clear();
+
+        let limited = if steps < turns { format!(" (only {} turn(s) of history)", steps) } else { String::new() };
+        Ok(format!("⏪ Rewound {} turn(s) to turn {}{}", steps, self.turns, limited))
+    }
+
+    /// ASCII dump of the current level with every robot and uncollected item (see Grid::to_ascii)
+    pub fn map_ascii(&self, show_unknown: bool) -> String {
+        self.grid.to_ascii(&self.robot_positions(), &self.uncollected_item_positions(), show_unknown)
+    }
+
+    pub fn map_json(&self) -> serde_json::Value {
+        self.grid.to_json(&self.robot_positions(), &self.uncollected_item_positions())
+    }
+
+    /// The game state serde levels feed the player's structs, as JSON. Field names follow the
+    /// structs those levels ask for; the round trip check reads each struct from whichever part fits.
+    pub fn serde_state_document(&self) -> serde_json::Value {
+        let position = self.robot.get_pos();
+        let spec = &self.levels[self.level_idx];
+        serde_json::json!({
+            "level": self.level_idx + 1,
+            "turn": self.turns,
+            "current_position": [position.x, position.y],
+            "energy": self.energy.unwrap_or(100),
+            "items_collected": self.robot.get_inventory_items().len(),
+            "doors_opened": self.grid.open_doors.len(),
+            "mission_completed": self.finished,
+            "config": {
+                "id": self.active_robot + 1,
+                "name": spec.name,
+                "max_speed": 1.0,
+                "sensors_enabled": true,
+                "position": [position.x, position.y],
+            },
+            "enemies": self.grid.enemies.iter()
+                .map(|enemy| serde_json::json!({ "position": [enemy.pos.x, enemy.pos.y] }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    fn uncollected_item_positions(&self) -> Vec<crate::item::Pos> {
+        self.item_manager.items.iter().filter(|item| !item.collected).map(|item| item.pos).collect()
+    }
+
+    pub fn is_multi_robot(&self) -> bool {
+        self.robots.len() > 1
+    }
+
+    /// Positions of every robot, indexed like `robots` (just the one robot on normal levels)
+    pub fn robot_positions(&self) -> Vec<crate::item::Pos> {
+        if !self.is_multi_robot() {
+            return vec![self.robot.get_pos()];
+        }
+        self.robots.iter().enumerate()
+            .map(|(i, robot)| if i == self.active_robot { self.robot.get_pos() } else { robot.get_pos() })
+            .collect()
+    }
+
+    /// Whether a robot other than the one being controlled stands on `pos`
+    pub fn other_robot_at(&self, pos: crate::item::Pos) -> Option<usize> {
+        if !self.is_multi_robot() {
+            return None;
+        }
+        self.robots.iter().enumerate()
+            .find(|(i, robot)| *i != self.active_robot && robot.get_pos() == pos)
+            .map(|(i, _)| i)
+    }
+
+    /// Switch control to another robot. `robot` is a working copy, so it is written back first.
+    pub fn select_robot(&mut self, index: usize) -> Result<String, String> {
+        if index >= self.robots.len().max(1) {
+            return Err(format!("No robot {} on this level (robots: 0..{}).", index, self.robots.len().max(1)));
+        }
+        if index != self.active_robot {
+            self.robots[self.active_robot] = self.robot.clone();
+            self.robot = self.robots[index].clone();
+            self.active_robot = index;
+        }
+        Ok(format!("Robot {} selected", index))
+    }
+
+    /// Whether the active robot's next turn-taking action completes the round. On multi-robot
+    /// levels the turn only advances once every robot has acted; a robot acting a second time
+    /// also ends the round so nothing gets free moves.
+    pub fn completes_robot_round(&self) -> bool {
+        !self.is_multi_robot()
+            || self.robots_acted.contains(&self.active_robot)
+            || self.robots_acted.len() + 1 >= self.robots.len()
+    }
+
+    /// Conveyors and spikes for every robot at the end of a round. Stops at the first level reset.
+    pub fn apply_round_hazards(&mut self) -> Option<String> {
+        self.robots_acted.clear();
+        if !self.is_multi_robot() {
+            return self.apply_turn_hazards();
+        }
+
+        let selected = self.active_robot;
+        let mut messages = Vec::new();
+        for index in 0..self.robots.len() {
+            let _ = self.select_robot(index);
+            if let Some(message) = self.apply_turn_hazards() {
+                if self.turns == 0 {
+                    // The hazard reset the level, which also reset the robots
+                    return Some(format!("Robot {}: {}", index, message));
+                }
+                messages.push(format!("Robot {}: {}", index, message));
+            }
+        }
+        let _ = self.select_robot(selected);
+
+        if messages.is_empty() { None } else { Some(messages.join(". ")) }
+    }
+
+    /// Energy an action costs on the current level (0 when the level has no energy budget)
+    pub fn energy_cost(&self, function: RustFunction) -> u32 {
+        self.levels.get(self.level_idx)
+            .and_then(|spec| spec.energy)
+            .map_or(0, |config| config.cost(function))
+    }
+
+    /// Pay for an action, or report that the robot ran out of energy
+    pub fn spend_energy(&mut self, function: RustFunction) -> Result<(), String> {
+        let Some(config) = self.levels.get(self.level_idx).and_then(|spec| spec.energy) else { return Ok(()) };
+        let Some(remaining) = self.energy.as_mut() else { return Ok(()) };
+        config.spend(remaining, function)
+    }
+
+    /// Jump the robot to a known, free tile. Only available once a teleport beacon is collected;
+    /// the caller charges TELEPORT_TURN_COST turns for it.
+    pub fn teleport(&mut self, target: (i32, i32)) -> Result<String, String> {
+        if !self.robot.has_ability("teleport") && !self.in_sandbox() {
+            return Err("teleport() is locked - collect a teleport beacon first.".to_string());
+        }
+
+        let pos = crate::item::Pos { x: target.0, y: target.1 };
+        if !self.grid.in_bounds(pos) {
+            return Err(format!(
+                "Teleport target ({}, {}) is outside the grid (0..{}, 0..{}).",
+                target.0, target.1, self.grid.width, self.grid.height
+            ));
+        }
+        if !self.grid.known.contains(&pos) {
+            return Err(format!("Teleport target ({}, {}) hasn't been explored yet.", target.0, target.1));
+        }
+        if self.grid.is_blocked(pos) || self.other_robot_at(pos).is_some() {
+            return Err(format!("Teleport target ({}, {}) is blocked.", target.0, target.1));
+        }
+
+        let from = self.robot.get_pos();
+        self.robot.set_position(target);
+        self.grid.reveal_adjacent(target);
+        self.grid.update_plates(pos);
+        self.grid.flip_switch(pos);
+        self.teleport_effect = Some((from, pos, crate::crash_protection::safe_get_time()));
+        Ok(format!("Teleported from ({}, {}) to ({}, {})", from.x, from.y, pos.x, pos.y))
+    }
+
     pub fn skip_level(&mut self) -> String {
         if self.level_idx + 1 < self.levels.len() {
             self.level_idx += 1;
@@ -526,37 +1154,13 @@ impl Game {
     }
     
     pub fn open_door(&mut self, open: bool) -> String {
-        let robot_pos = self.robot.get_position();
-        let robot_item_pos = crate::item::Pos { x: robot_pos.0, y: robot_pos.1 };
-        
-        // Check if robot is standing on a door
-        if self.grid.is_door(robot_item_pos) {
-            if open {
-                if self.grid.is_door_open(robot_item_pos) {
-                    "Door is already open.".to_string()
-                } else {
-                    self.grid.open_door(robot_item_pos);
-                    "Door opened successfully!".to_string()
-                }
-            } else {
-                if !self.grid.is_door_open(robot_item_pos) {
-                    "Door is already closed.".to_string()
-                } else {
-                    self.grid.close_door(robot_item_pos);
-                    "Door closed successfully!".to_string()
-                }
-            }
-        } else {
-            "Robot must be standing on a door to open/close it.".to_string()
-        }
+        let door = self.grid.door_in_reach(self.robot.get_pos(), self.robot.facing);
+        let keys: Vec<KeyColor> = self.robot.inventory.iter().filter_map(|item| KeyColor::of_key_item(item)).collect();
+        self.grid.use_door(door, open, &keys)
     }
 
     pub fn update_laser_effects(&mut self) {
-        // Update stunned enemies
-        self.stunned_enemies.retain(|_, turns| {
-            *turns -= 1;
-            *turns > 0
-        });
+        self.grid.tick_stuns();
         
         // Update temporary removed obstacles
         self.temporary_removed_obstacles.retain(|_, turns| {
@@ -565,6 +1169,26 @@ impl Game {
         });
     }
 
+    /// Whether an enemy at `pos` catches the robot. A shield charge absorbs the hit and
+    /// destroys the enemy instead (bosses survive).
+    pub fn enemy_collision_at(&mut self, pos: (i32, i32)) -> bool {
+        if !self.grid.check_enemy_collision(pos) {
+            return false;
+        }
+        if !self.robot.absorb_hit() {
+            return true;
+        }
+
+        let hit = crate::item::Pos { x: pos.0, y: pos.1 };
+        // Bosses survive the shield blast
+        if let Some(index) = self.grid.enemies.iter().position(|enemy| enemy.occupies(hit) && !enemy.is_boss()) {
+            self.grid.remove_enemy(index);
+            self.publish(GameEvent::Combat(CombatEvent::EnemyDestroyed { enemy: index, weapon: Weapon::Shield }));
+        }
+        self.execution_result = format!("🛡️ Shield absorbed an enemy hit! ({} charge(s) left)", self.robot.shield_charges);
+        false
+    }
+
     fn hit_obstacle_with_laser(&mut self, pos: (i32, i32)) {
         // Temporarily remove obstacle for 2 turns
         self.temporary_removed_obstacles.insert(pos, 2);
@@ -606,6 +1230,28 @@ impl Game {
                         false
                     }
                 },
+                "robots_on" => {
+                    // Every listed tile ("x,y;x,y") must have a robot standing on it
+                    let positions = self.robot_positions();
+                    expected_value.split(';').all(|tile| {
+                        let coords: Vec<&str> = tile.split(',').collect();
+                        match (coords.first().map(|c| c.trim().parse::<i32>()), coords.get(1).map(|c| c.trim().parse::<i32>())) {
+                            (Some(Ok(x)), Some(Ok(y))) => positions.contains(&crate::item::Pos { x, y }),
+                            _ => false,
+                        }
+                    })
+                },
+                "log_level" => {
+                    // Something was logged at that level ("warn" or "WARN")
+                    self.log_records.iter().any(|record| record.level.eq_ignore_ascii_case(expected_value))
+                },
+                "log_target" => {
+                    self.log_records.iter().any(|record| record.target == expected_value)
+                },
+                "serde_round_trip" => {
+                    // That struct was read from the game state and survived the round trip
+                    self.serde_round_trips.iter().any(|check| check.name == expected_value && check.result.is_ok())
+                },
                 _ => false
             }
         } else {
@@ -613,27 +1259,35 @@ impl Game {
             match completion_flag {
                 "println" => !self.println_outputs.is_empty(),
                 "error" | "eprintln" => !self.error_outputs.is_empty(),
+                "log" => !self.log_records.is_empty(),
                 "panic" => self.panic_occurred,
                 "items_collected" => !self.robot.get_inventory_items().is_empty(),
+                "serde_round_trip" => !self.serde_round_trips.is_empty() && self.serde_round_trips.iter().all(|check| check.result.is_ok()),
                 _ => false
             }
         }
     }
 
     pub fn check_end_condition(&mut self) {
-        if self.finished { 
+        // A "show me" playback doesn't count as solving the level
+        if self.finished || self.solution_playback.is_some() {
             return; 
         }
         
         // Check for enemy collision (Level 4+)
-        if self.level_idx >= 3 && self.grid.check_enemy_collision(self.robot.get_position()) {
+        if self.level_idx >= 3 && self.enemy_collision_at(self.robot.get_position()) {
             // Reset and randomize the level when enemy catches player
             let idx = self.level_idx;
             self.load_level(idx);
-            self.execution_result = "ENEMY COLLISION! Level reset and randomized.".to_string();
+            self.execution_result = super::ENEMY_COLLISION_MESSAGE.to_string();
             return;
         }
         
+        // The sandbox has nothing to win
+        if self.in_sandbox() {
+            return;
+        }
+
         // Check special completion conditions first
         let current_level = &self.levels[self.level_idx];
         
@@ -645,15 +1299,38 @@ impl Game {
                 let level_name = current_level.name.clone();
                 let next_hint = current_level.next_level_hint.clone();
                 self.popup_system.show_congratulations(level_name, achievement, next_hint);
-                self.finish_level();
+                self.complete_level();
                 return;
             }
         }
         
-        // Fallback to basic completion condition (all items collected)
-        if self.item_manager.items.is_empty() {
+        // Levels whose tasks are all checked by evaluators are done once every task passed; other
+        // levels with tasks still need their goal or items
+        let tasks = &self.levels[self.level_idx].tasks;
+        let evaluated_by_plugins = !tasks.is_empty()
+            && tasks.iter().all(|task| task.task_file.as_deref().is_some_and(crate::task_plugins::is_plugin));
+        if evaluated_by_plugins && tasks.iter().all(|task| task.completed) {
             self.show_level_complete();
-            self.finish_level();
+            self.complete_level();
+            return;
+        }
+
+        // The level's own win condition, or by default every item collected
+        let won = match &self.levels[self.level_idx].win_condition {
+            Some(condition) => {
+                let robots = self.robot_positions();
+                WinConditionEvaluator::new(condition).is_met(&WinState {
+                    robots: &robots,
+                    items_left: self.item_manager.items.len(),
+                    enemies_left: self.grid.enemies.len(),
+                    turns: self.turns,
+                })
+            }
+            None => self.item_manager.items.is_empty(),
+        };
+        if won {
+            self.show_level_complete();
+            self.complete_level();
         }
     }
 
@@ -684,19 +1361,10 @@ impl Game {
                 let current_word = self.get_current_word_at_cursor();
                 let start_pos = self.cursor_position - current_word.len();
 
-                // Remove the current partial word
-                for _ in 0..current_word.len() {
-                    if self.cursor_position > 0 {
-                        self.cursor_position -= 1;
-                        self.current_code.remove(self.cursor_position);
-                    }
-                }
-
-                // Insert the completion
-                for (i, ch) in completion.chars().enumerate() {
-                    self.current_code.insert(self.cursor_position + i, ch);
-                }
-                self.cursor_position += completion.len();
+                // Replace the current partial word with the completion
+                self.current_code.remove_range(start_pos..self.cursor_position);
+                self.current_code.insert_str(start_pos, &completion);
+                self.cursor_position = start_pos + completion.len();
 
                 return true;
             }
@@ -712,38 +1380,122 @@ impl Game {
     }
 
     fn get_current_word_at_cursor(&self) -> String {
-        let chars: Vec<char> = self.current_code.chars().collect();
-        let mut start = self.cursor_position;
-
-        // Find start of current word
-        while start > 0 {
-            let prev_char = chars[start - 1];
-            if prev_char.is_alphanumeric() || prev_char == '_' {
-                start -= 1;
-            } else {
-                break;
+        let before_cursor = &self.current_code[..self.cursor_position.min(self.current_code.len())];
+        let word: Vec<char> = before_cursor
+            .chars()
+            .rev()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+        word.into_iter().rev().collect()
+    }
+
+    // Hotkey system integration methods
+    pub fn handle_hotkey(&mut self, key: macroquad::prelude::KeyCode, ctrl: bool, shift: bool, alt: bool) -> bool {
+        self.log_key_immediate(&format!("handle_hotkey called: {:?} (ctrl:{}, shift:{}, alt:{})", key, ctrl, shift, alt));
+
+        let context = self.hotkey_context();
+        match self.hotkey_system.process_input(key, ctrl, shift, alt, context) {
+            crate::hotkeys::HotkeyInput::Action(action) => {
+                self.log_key_immediate(&format!("Hotkey system found action: {:?}", action));
+                let result = self.execute_hotkey_action(action);
+                self.log_key_immediate(&format!("execute_hotkey_action returned: {}", result));
+                result
+            }
+            crate::hotkeys::HotkeyInput::Pending(chord) => {
+                // The key belongs to a chord, so nothing else should act on it
+                self.log_key_immediate(&format!("Waiting for the next key of chord {}", chord));
+                true
+            }
+            crate::hotkeys::HotkeyInput::Unbound => {
+                self.log_key_immediate("No action found for hotkey combination");
+                false
             }
         }
+    }
 
-        if start < self.cursor_position {
-            chars[start..self.cursor_position].iter().collect()
+    /// Which bindings apply right now: popups first, then the focused editor, otherwise the game
+    pub fn hotkey_context(&self) -> crate::hotkeys::HotkeyContext {
+        if self.popup_system.is_showing() {
+            crate::hotkeys::HotkeyContext::Popup
+        } else if self.code_editor_active {
+            crate::hotkeys::HotkeyContext::Editor
         } else {
-            String::new()
+            crate::hotkeys::HotkeyContext::Game
         }
     }
 
-    // Hotkey system integration methods
-    pub fn handle_hotkey(&mut self, key: macroquad::prelude::KeyCode, ctrl: bool, shift: bool, alt: bool) -> bool {
-        self.log_key_immediate(&format!("handle_hotkey called: {:?} (ctrl:{}, shift:{}, alt:{})", key, ctrl, shift, alt));
+    /// Keep one binding of a conflict shown on the hotkey settings page and save the result
+    pub fn resolve_hotkey_conflict(&mut self, conflict: usize, keep: usize) {
+        if let Some(conflict) = self.hotkey_system.conflicts().get(conflict) {
+            self.hotkey_system.resolve_conflict(conflict, keep);
+            if let Err(e) = self.hotkey_system.save_config() {
+                log::warn!("{}", e);
+            }
+        }
+        self.menu.hotkey_conflicts = self.hotkey_system.conflicts();
+        self.menu.setup_hotkey_settings_menu();
+    }
 
-        if let Some(action) = self.hotkey_system.get_action_for_input(key, ctrl, shift, alt) {
-            self.log_key_immediate(&format!("Hotkey system found action: {:?}", action));
-            let result = self.execute_hotkey_action(action);
-            self.log_key_immediate(&format!("execute_hotkey_action returned: {}", result));
-            result
-        } else {
-            self.log_key_immediate("No action found for hotkey combination");
-            false
+    /// Back to the bindings of the preset picked on the hotkey settings page
+    pub fn reset_hotkeys(&mut self) {
+        self.hotkey_system.apply_preset(self.menu.settings.hotkey_preset);
+        if let Err(e) = self.hotkey_system.save_config() {
+            log::warn!("{}", e);
+        }
+        self.menu.hotkey_conflicts = self.hotkey_system.conflicts();
+        self.menu.setup_hotkey_settings_menu();
+    }
+
+    /// Switch to the bindings of `preset`. Picking Emacs also imports the global-set-key lines of
+    /// ~/.emacs, ~/.emacs.d/init.el or ~/.config/emacs/init.el.
+    pub fn apply_hotkey_preset(&mut self, preset: crate::hotkeys::HotkeyPreset) {
+        self.hotkey_system.apply_preset(preset);
+        self.clear_selection();
+        if preset == crate::hotkeys::HotkeyPreset::Emacs {
+            let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(std::path::PathBuf::from);
+            let init_file = home.and_then(|home| {
+                [".emacs", ".emacs.d/init.el", ".config/emacs/init.el"].iter()
+                    .map(|file| home.join(file))
+                    .find(|path| path.exists())
+            });
+            if let Some(init_file) = init_file {
+                match self.hotkey_system.import_emacs_config(&init_file.to_string_lossy()) {
+                    Ok(imported) => log::info!("Imported {} key bindings from {}", imported, init_file.display()),
+                    Err(e) => log::warn!("{}", e),
+                }
+            }
+        }
+        if let Err(e) = self.hotkey_system.save_config() {
+            log::warn!("{}", e);
+        }
+        self.menu.hotkey_conflicts = self.hotkey_system.conflicts();
+        self.menu.setup_hotkey_settings_menu();
+    }
+
+    /// Follow the Vim mode setting. Switching it on also imports the mappings of ~/.vimrc
+    /// (_vimrc on Windows) that have an editor action.
+    pub fn apply_vim_mode(&mut self, enabled: bool, import_vimrc: bool) {
+        if enabled == self.vim.is_some() {
+            return;
+        }
+        self.set_vim_mode(enabled);
+        if !enabled || !import_vimrc {
+            return;
+        }
+        let vimrc = std::env::var_os("HOME")
+            .map(|home| std::path::PathBuf::from(home).join(".vimrc"))
+            .or_else(|| std::env::var_os("USERPROFILE").map(|home| std::path::PathBuf::from(home).join("_vimrc")));
+        let Some(vimrc) = vimrc.filter(|path| path.exists()) else { return };
+        match self.hotkey_system.import_vim_config(&vimrc.to_string_lossy()) {
+            Ok(0) => {}
+            Ok(imported) => {
+                log::info!("Imported {} mappings from {}", imported, vimrc.display());
+                if let Err(e) = self.hotkey_system.save_config() {
+                    log::warn!("{}", e);
+                }
+                self.menu.hotkey_conflicts = self.hotkey_system.conflicts();
+            }
+            Err(e) => log::warn!("{}", e),
         }
     }
 
@@ -765,6 +1517,71 @@ impl Game {
                 self.save_robot_code();
                 true
             },
+            crate::hotkeys::EditorAction::Comment => {
+                self.log_key_immediate("Executing Comment action");
+                self.comment_selected_lines(true)
+            },
+            crate::hotkeys::EditorAction::Uncomment => {
+                self.log_key_immediate("Executing Uncomment action");
+                self.comment_selected_lines(false)
+            },
+            crate::hotkeys::EditorAction::Undo => self.undo(),
+            crate::hotkeys::EditorAction::Redo => self.redo(),
+            crate::hotkeys::EditorAction::Cut => self.cut_to_clipboard(),
+            crate::hotkeys::EditorAction::Copy => {
+                // Copying ends the Emacs region like M-w does
+                let copied = self.copy_to_clipboard();
+                if copied && self.mark_active {
+                    self.clear_selection();
+                }
+                copied
+            },
+            crate::hotkeys::EditorAction::Paste | crate::hotkeys::EditorAction::Yank => self.paste_from_clipboard(),
+            crate::hotkeys::EditorAction::SelectAll => {
+                self.select_all();
+                true
+            },
+            crate::hotkeys::EditorAction::PasteFromHistory => self.open_clipboard_picker(),
+            crate::hotkeys::EditorAction::GoToLine => self.open_goto_line(),
+            crate::hotkeys::EditorAction::KillLine => self.kill_line(),
+            crate::hotkeys::EditorAction::SetMark => self.set_mark(),
+            crate::hotkeys::EditorAction::MoveCursorUp => {
+                self.move_cursor_up_with_selection(false);
+                true
+            },
+            crate::hotkeys::EditorAction::MoveCursorDown => {
+                self.move_cursor_down_with_selection(false);
+                true
+            },
+            crate::hotkeys::EditorAction::MoveCursorLeft => {
+                self.move_cursor_left_with_selection(false);
+                true
+            },
+            crate::hotkeys::EditorAction::MoveCursorRight => {
+                self.move_cursor_right_with_selection(false);
+                true
+            },
+            crate::hotkeys::EditorAction::MoveCursorWordLeft | crate::hotkeys::EditorAction::MoveCursorWordRight => {
+                let target = self.word_boundary(action == crate::hotkeys::EditorAction::MoveCursorWordRight);
+                self.move_cursor_to_with_selection(target, false);
+                true
+            },
+            crate::hotkeys::EditorAction::MoveCursorLineStart | crate::hotkeys::EditorAction::MoveCursorLineEnd => {
+                let line = self.current_code.line_of(self.cursor_position);
+                let column = if action == crate::hotkeys::EditorAction::MoveCursorLineEnd { usize::MAX } else { 0 };
+                let target = self.current_code.byte_at(line, column);
+                self.move_cursor_to_with_selection(target, false);
+                true
+            },
+            crate::hotkeys::EditorAction::MoveCursorDocStart => {
+                self.move_cursor_to_with_selection(0, false);
+                true
+            },
+            crate::hotkeys::EditorAction::MoveCursorDocEnd => {
+                let end = self.current_code.len();
+                self.move_cursor_to_with_selection(end, false);
+                true
+            },
             crate::hotkeys::EditorAction::RunCode => {
                 self.log_key_immediate("Executing RunCode action - setting code_execution_requested flag");
                 self.request_code_execution();
@@ -794,6 +1611,8 @@ impl Game {
         self.autocomplete_enabled = settings.autocomplete_enabled;
         self.autocomplete_engine.set_enabled(settings.autocomplete_enabled);
         self.autocomplete_engine.set_vscode_enabled(settings.vscode_integration_enabled);
+        self.key_repeat_initial_delay = settings.editor.key_repeat_delay;
+        self.key_repeat_interval = 1.0 / settings.editor.key_repeat_rate.max(1.0);
     }
 
     pub fn toggle_autocomplete_setting(&mut self) -> bool {
@@ -815,7 +1634,7 @@ impl Game {
     // Clipboard and Undo/Redo functionality
     pub fn save_undo_state(&mut self) {
         let undo_state = UndoState {
-            code: self.current_code.clone(),
+            code: self.current_code.to_string(),
             cursor_position: self.cursor_position,
             selection_start: self.selection_start,
             selection_end: self.selection_end,
@@ -830,7 +1649,7 @@ impl Game {
             self.undo_stack.remove(0);
         }
 
-        println!("📚 Undo state saved. Stack size: {}", self.undo_stack.len());
+        log::trace!(target: "editor", "Undo state saved. Stack size: {}", self.undo_stack.len());
     }
 
     // Smart undo state saving for typing operations
@@ -864,17 +1683,18 @@ impl Game {
     pub fn copy_to_clipboard(&mut self) -> bool {
         if let Some((start, end)) = self.get_selection_bounds() {
             let selected_text = self.current_code[start..end].to_string();
+            self.clipboard_history.push(&selected_text);
 
             // Use safe clipboard operation to prevent crashes on focus loss
             if crate::crash_protection::safe_clipboard_copy(&selected_text) {
-                println!("📋 Copied to OS clipboard: '{}'", selected_text);
+                log::debug!(target: "editor", "Copied to OS clipboard: '{}'", selected_text);
                 true
             } else {
-                println!("❌ Failed to copy to OS clipboard (window may not be focused)");
+                log::warn!(target: "editor", "Failed to copy to OS clipboard (window may not be focused)");
                 false
             }
         } else {
-            println!("📋 No text selected for copy");
+            log::debug!(target: "editor", "No text selected for copy");
             false
         }
     }
@@ -883,11 +1703,11 @@ impl Game {
         if self.copy_to_clipboard() {
             self.save_undo_state();
             if let Some((start, end)) = self.get_selection_bounds() {
-                self.current_code.drain(start..end);
+                self.current_code.remove_range(start..end);
                 self.cursor_position = start;
                 self.clear_selection();
                 self.ensure_cursor_visible();
-                println!("✂️ Cut text to clipboard");
+                log::debug!(target: "editor", "Cut text to clipboard");
                 true
             } else {
                 false
@@ -913,10 +1733,10 @@ impl Game {
             self.clear_selection();
             self.ensure_cursor_visible();
 
-            println!("📋 Pasted from OS clipboard: '{}'", clipboard_text);
+            log::debug!(target: "editor", "Pasted from OS clipboard: '{}'", clipboard_text);
             true
         } else {
-            println!("❌ Failed to paste from OS clipboard (window may not be focused or clipboard empty)");
+            log::warn!(target: "editor", "Failed to paste from OS clipboard (window may not be focused or clipboard empty)");
             false
         }
     }
@@ -925,7 +1745,7 @@ impl Game {
         if let Some(undo_state) = self.undo_stack.pop() {
             // Save current state to redo stack
             let redo_state = UndoState {
-                code: self.current_code.clone(),
+                code: self.current_code.to_string(),
                 cursor_position: self.cursor_position,
                 selection_start: self.selection_start,
                 selection_end: self.selection_end,
@@ -933,15 +1753,15 @@ impl Game {
             self.redo_stack.push(redo_state);
 
             // Restore previous state
-            self.current_code = undo_state.code;
+            self.current_code = undo_state.code.into();
             self.cursor_position = undo_state.cursor_position;
             self.selection_start = undo_state.selection_start;
             self.selection_end = undo_state.selection_end;
             self.ensure_cursor_visible();
-            println!("↶ Undo performed");
+            log::debug!(target: "editor", "Undo performed");
             true
         } else {
-            println!("↶ Nothing to undo");
+            log::debug!(target: "editor", "Nothing to undo");
             false
         }
     }
@@ -950,7 +1770,7 @@ impl Game {
         if let Some(redo_state) = self.redo_stack.pop() {
             // Save current state to undo stack
             let undo_state = UndoState {
-                code: self.current_code.clone(),
+                code: self.current_code.to_string(),
                 cursor_position: self.cursor_position,
                 selection_start: self.selection_start,
                 selection_end: self.selection_end,
@@ -958,15 +1778,15 @@ impl Game {
             self.undo_stack.push(undo_state);
 
             // Restore redo state
-            self.current_code = redo_state.code;
+            self.current_code = redo_state.code.into();
             self.cursor_position = redo_state.cursor_position;
             self.selection_start = redo_state.selection_start;
             self.selection_end = redo_state.selection_end;
             self.ensure_cursor_visible();
-            println!("↷ Redo performed");
+            log::debug!(target: "editor", "Redo performed");
             true
         } else {
-            println!("↷ Nothing to redo");
+            log::debug!(target: "editor", "Nothing to redo");
             false
         }
     }
@@ -975,7 +1795,7 @@ impl Game {
         self.selection_start = Some(0);
         self.selection_end = Some(self.current_code.len());
         self.cursor_position = self.current_code.len();
-        println!("🔲 Selected all text ({} characters)", self.current_code.len());
+        log::debug!(target: "editor", "Selected all text ({} characters)", self.current_code.len());
     }
 
     // Helper function to determine if special robots should be shown at a position
@@ -1019,49 +1839,28 @@ impl Game {
 
     // Get robot symbol and font size for current level
     pub fn get_robot_symbol_for_level(&self, enemy: &crate::grid::Enemy) -> (&'static str, f32) {
+        let palette = self.menu.settings.palette;
+        let regular = (palette.enemy_glyph(crate::theme::EnemyKind::of(enemy)), 28.0); // Standard enemy symbol
         if !self.is_learning_level(self.level_idx) {
-            return ("E", 28.0); // Standard enemy symbol for non-learning levels
+            return regular;
         }
 
         match self.level_idx {
             17 if self.is_ownership_robot(enemy) => ("⚡", 24.0), // Robot Alpha - ownership
             1 | 7 | 8 | 9 | 10 if self.is_cloning_robot(enemy) => ("◆", 24.0), // Robot Gamma - cloning
             13 | 14 | 15 if self.is_serde_robot(enemy) => ("📡", 20.0), // Scout Bot - serde
-            _ => {
-                // Regular enemies in learning levels
-                ("E", 28.0) // Standard enemy symbol
-            }
+            _ => regular, // Regular enemies in learning levels
         }
     }
 
     // Get robot color for current level
     pub fn get_robot_color_for_level(&self, enemy: &crate::grid::Enemy) -> macroquad::color::Color {
         use macroquad::color::*;
-        use crate::level::EnemyDirection;
 
+        // Standard enemy colors come from the palette picked in settings
+        let regular = self.menu.settings.palette.enemy_color(crate::theme::EnemyKind::of(enemy));
         if !self.is_learning_level(self.level_idx) {
-            // Standard enemy colors for non-learning levels
-            return if let Some(ref pattern) = enemy.movement_pattern {
-                match pattern.as_str() {
-                    "chase" => {
-                        if let Some(is_chasing) = enemy.movement_data.get("is_chasing")
-                            .and_then(|v| v.as_bool()) {
-                            if is_chasing { ORANGE } else { BLUE }
-                        } else { ORANGE }
-                    }
-                    "random" => MAGENTA,
-                    "diagonal" => YELLOW,
-                    "circular" => LIME,
-                    "spiral" => PINK,
-                    pattern if pattern.starts_with("file:") => PURPLE,
-                    _ => RED
-                }
-            } else {
-                match enemy.direction {
-                    EnemyDirection::Horizontal => GREEN,
-                    EnemyDirection::Vertical => DARKBLUE,
-                }
-            };
+            return regular;
         }
 
         // Colors for learning level robots
@@ -1069,30 +1868,26 @@ impl Game {
             17 if self.is_ownership_robot(enemy) => SKYBLUE,    // Robot Alpha - ownership
             1 | 7 | 8 | 9 | 10 if self.is_cloning_robot(enemy) => YELLOW, // Robot Gamma - cloning
             13 | 14 | 15 if self.is_serde_robot(enemy) => LIME, // Scout Bot - serde (bright green)
-            _ => {
-                // Regular enemies in learning levels
-                if let Some(ref pattern) = enemy.movement_pattern {
-                    match pattern.as_str() {
-                        "chase" => {
-                            if let Some(is_chasing) = enemy.movement_data.get("is_chasing")
-                                .and_then(|v| v.as_bool()) {
-                                if is_chasing { ORANGE } else { BLUE }
-                            } else { ORANGE }
-                        }
-                        "random" => MAGENTA,
-                        "diagonal" => YELLOW,
-                        "circular" => LIME,
-                        "spiral" => PINK,
-                        pattern if pattern.starts_with("file:") => PURPLE,
-                        _ => RED
-                    }
-                } else {
-                    match enemy.direction {
-                        EnemyDirection::Horizontal => GREEN,
-                        EnemyDirection::Vertical => DARKBLUE,
-                    }
-                }
-            }
+            _ => regular, // Regular enemies in learning levels
         }
     }
-}
\ No newline at end of file
+}
+/// Open `url` in the system browser
+#[cfg(not(target_arch = "wasm32"))]
+fn open_url(url: &str) -> Result<(), String> {
+    std::process::Command::new("cmd")
+        .args(["/C", "start", url])
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open browser: {}. Manual URL: {}", e, url))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn open_url(url: &str) -> Result<(), String> {
+    // For WASM, we'll use JavaScript to open the URL
+    unsafe {
+        let js_code = format!("window.open('{}', '_blank');", url);
+        js_sys::eval(&js_code).ok();
+    }
+    Ok(())
+}
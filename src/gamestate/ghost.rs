@@ -0,0 +1,72 @@
+// Tutorial ghost: when the player has been idle for a while, a translucent robot acts out the
+// next suggested calls of the current task (its hint_actions in the level YAML) on the grid.
+
+use super::{Game, RustFunction};
+use crate::item::Pos;
+
+// Seconds the ghost spends on each suggested call
+pub const GHOST_STEP_SECS: f64 = 0.9;
+
+/// One suggested call and where it takes the ghost
+#[derive(Clone, Debug, PartialEq)]
+pub struct GhostStep {
+    pub call: String,
+    pub from: Pos,
+    pub to: Pos,
+}
+
+impl Game {
+    /// The current task's hint actions played out from where the robot stands. Calls the ghost
+    /// can't show as movement (grab, scan, println!) leave it in place with the call as a label.
+    pub fn ghost_steps(&self) -> Vec<GhostStep> {
+        let Some(task) = self.levels[self.level_idx].tasks.get(self.tutorial_state.current_task) else {
+            return Vec::new();
+        };
+        let mut pos = self.robot.get_pos();
+        let mut facing = self.robot.facing;
+        task.hint_actions.iter().map(|call| {
+            let parsed = game_core::interpreter::parse_rust_code_from_main(&format!("fn main() {{ {}; }}", call), self.level_seed);
+            let step = match parsed.first() {
+                Some(parsed) => match parsed.function {
+                    RustFunction::Move => parsed.direction,
+                    RustFunction::MoveForward => Some(facing),
+                    RustFunction::RotateLeft => {
+                        facing = (facing.1, -facing.0);
+                        None
+                    }
+                    RustFunction::RotateRight => {
+                        facing = (-facing.1, facing.0);
+                        None
+                    }
+                    _ => None,
+                },
+                None => None,
+            };
+            let from = pos;
+            if let Some((dx, dy)) = step {
+                let next = Pos { x: pos.x + dx, y: pos.y + dy };
+                if self.grid.in_bounds(next) && !self.grid.is_blocked(next) {
+                    pos = next;
+                }
+            }
+            GhostStep { call: call.clone(), from, to: pos }
+        }).collect()
+    }
+
+    /// Which step the ghost is on at `now` and how far through it, or None while it's hidden
+    pub fn ghost_progress(&self, now: f64) -> Option<(usize, f32)> {
+        let delay = self.menu.settings.tutorial_ghost_delay as f64;
+        let idle = now - self.last_activity;
+        if delay <= 0.0 || idle < delay || self.program_running || self.solution_playback.is_some() || self.popup_system.is_showing() {
+            return None;
+        }
+        let steps = self.levels[self.level_idx].tasks.get(self.tutorial_state.current_task)?.hint_actions.len();
+        if steps == 0 {
+            return None;
+        }
+        // One extra step's worth of pause at the end before the demonstration repeats
+        let t = (idle - delay) / GHOST_STEP_SECS;
+        let index = t as usize % (steps + 1);
+        Some((index.min(steps - 1), if index == steps { 1.0 } else { t.fract() as f32 }))
+    }
+}
@@ -0,0 +1,95 @@
+// Ghost race: every run records where the robot stood after each turn, and the fastest run that
+// finished a level is kept in best_runs.json. On the next attempt that run plays back as a
+// translucent ghost turn by turn, and finishing reports whether the new run beat it.
+
+use super::types::Game;
+use crate::i18n::tr_with;
+use crate::item::Pos;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BestRun {
+    pub turns: usize,
+    pub path: Vec<Pos>, // Robot position at the start and after every turn
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BestRuns {
+    #[serde(default)]
+    pub levels: HashMap<String, BestRun>, // Keyed by level name
+}
+
+impl BestRuns {
+    const SAVE_FILE: &'static str = "best_runs.json";
+
+    pub fn load_or_default() -> Self {
+        if !Path::new(Self::SAVE_FILE).exists() {
+            return Self::default();
+        }
+        fs::read_to_string(Self::SAVE_FILE)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(Self::SAVE_FILE, json)?;
+        Ok(())
+    }
+}
+
+impl Game {
+    /// Note where the robot ended this turn. A rewind or level reset set the turn counter back,
+    /// so anything recorded past it is dropped first.
+    pub fn record_race_turn(&mut self) {
+        self.race_path.truncate(self.turns);
+        self.race_path.push(self.robot.get_pos());
+    }
+
+    /// Best run on the current level, unless it's a generated Endless level nobody plays twice
+    pub fn best_run(&self) -> Option<&BestRun> {
+        if self.endless_depth.is_some() {
+            return None;
+        }
+        self.best_runs.levels.get(&self.levels[self.level_idx].name)
+    }
+
+    /// Where the best run's robot was at the current turn and every turn before it
+    pub fn ghost_race_trail(&self) -> &[Pos] {
+        match self.best_run() {
+            Some(run) => &run.path[..run.path.len().min(self.turns + 1)],
+            None => &[],
+        }
+    }
+
+    /// Compare the finished run with the best one, keep it if it's faster and add the outcome to
+    /// the level complete popup
+    pub(super) fn finish_ghost_race(&mut self) {
+        if self.endless_depth.is_some() {
+            return;
+        }
+        let turns = self.turns.to_string();
+        let previous = self.best_run().map(|run| run.turns);
+        let message = match previous {
+            None => tr_with("race.first", &[("turns", &turns)]),
+            Some(best) if self.turns < best => tr_with("race.beat", &[("turns", &turns), ("best", &best.to_string()), ("diff", &(best - self.turns).to_string())]),
+            Some(best) if self.turns == best => tr_with("race.tie", &[("turns", &turns)]),
+            Some(best) => tr_with("race.lost", &[("turns", &turns), ("best", &best.to_string()), ("diff", &(self.turns - best).to_string())]),
+        };
+        self.popup_system.append_to_last(&message);
+
+        if previous.is_none_or(|best| self.turns < best) {
+            let name = self.levels[self.level_idx].name.clone();
+            let mut path = self.race_path.clone();
+            path.truncate(self.turns + 1);
+            self.best_runs.levels.insert(name, BestRun { turns: self.turns, path });
+            if let Err(e) = self.best_runs.save() {
+                log::warn!("Couldn't save best runs: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// Ctrl+G go-to-line prompt for the code editor. The line number is typed into a box over the
+// text area; Enter jumps there and Escape closes it without moving.
+
+use super::types::Game;
+use macroquad::prelude::KeyCode;
+
+impl Game {
+    pub fn open_goto_line(&mut self) -> bool {
+        self.goto_line_input = Some(String::new());
+        true
+    }
+
+    pub fn goto_line_open(&self) -> bool {
+        self.goto_line_input.is_some()
+    }
+
+    /// Typed text while the prompt is open; only digits are kept
+    pub fn goto_line_text(&mut self, text: &str) {
+        if let Some(input) = self.goto_line_input.as_mut() {
+            input.extend(text.chars().filter(char::is_ascii_digit));
+        }
+    }
+
+    /// A key pressed while the prompt is open: Backspace, Enter and Escape. Returns whether the
+    /// cursor moved.
+    pub fn goto_line_key(&mut self, key: KeyCode) -> bool {
+        let Some(input) = self.goto_line_input.as_mut() else { return false };
+        match key {
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Escape => self.goto_line_input = None,
+            KeyCode::Enter | KeyCode::KpEnter => {
+                let line = input.parse::<usize>().ok();
+                self.goto_line_input = None;
+                if let Some(line) = line {
+                    self.go_to_line(line);
+                    return true;
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Put the cursor at the start of `line`, counted from 1 as the gutter shows it; numbers past
+    /// the end go to the last line
+    pub fn go_to_line(&mut self, line: usize) {
+        let last = self.current_code.line_count().saturating_sub(1);
+        let target = self.current_code.byte_at(line.saturating_sub(1).min(last), 0);
+        self.move_cursor_to_with_selection(target, false);
+    }
+}
@@ -39,7 +39,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[0] = true;
                     self.tutorial_state.current_task = 1;
-                    println!("✅ Task 1 completed: Integer types!");
+                    log::info!(target: "tasks", "Task 1 completed: Integer types!");
                 }
             },
             1 => {
@@ -53,7 +53,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[1] = true;
                     self.tutorial_state.current_task = 2;
-                    println!("✅ Task 2 completed: Floating point numbers!");
+                    log::info!(target: "tasks", "Task 2 completed: Floating point numbers!");
                 }
             },
             2 => {
@@ -68,7 +68,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[2] = true;
                     self.tutorial_state.current_task = 3;
-                    println!("✅ Task 3 completed: Boolean logic!");
+                    log::info!(target: "tasks", "Task 3 completed: Boolean logic!");
                 }
             },
             3 => {
@@ -83,7 +83,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[3] = true;
                     self.tutorial_state.current_task = 4;
-                    println!("✅ Task 4 completed: Character types and Unicode!");
+                    log::info!(target: "tasks", "Task 4 completed: Character types and Unicode!");
                 }
             },
             4 => {
@@ -98,7 +98,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[4] = true;
                     self.tutorial_state.current_task = 5;
-                    println!("✅ Task 5 completed: Type inference and annotations!");
+                    log::info!(target: "tasks", "Task 5 completed: Type inference and annotations!");
                 }
             },
             _ => {}
@@ -40,7 +40,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[0] = true;
                     self.tutorial_state.current_task = 1;
-                    println!("✅ Task 1 completed: Immutable variable bindings!");
+                    log::info!(target: "tasks", "Task 1 completed: Immutable variable bindings!");
                 }
             },
             1 => {
@@ -54,7 +54,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[1] = true;
                     self.tutorial_state.current_task = 2;
-                    println!("✅ Task 2 completed: Mutable variable bindings!");
+                    log::info!(target: "tasks", "Task 2 completed: Mutable variable bindings!");
                 }
             },
             2 => {
@@ -69,7 +69,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[2] = true;
                     self.tutorial_state.current_task = 3;
-                    println!("✅ Task 3 completed: Variable shadowing!");
+                    log::info!(target: "tasks", "Task 3 completed: Variable shadowing!");
                 }
             },
             3 => {
@@ -84,7 +84,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[3] = true;
                     self.tutorial_state.current_task = 4;
-                    println!("✅ Task 4 completed: Variable scope and blocks!");
+                    log::info!(target: "tasks", "Task 4 completed: Variable scope and blocks!");
                 }
             },
             4 => {
@@ -100,7 +100,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[4] = true;
                     self.tutorial_state.current_task = 5;
-                    println!("✅ Task 5 completed: Constants and naming conventions!");
+                    log::info!(target: "tasks", "Task 5 completed: Constants and naming conventions!");
                 }
             },
             _ => {}
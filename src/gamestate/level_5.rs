@@ -43,7 +43,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[0] = true;
                     self.tutorial_state.current_task = 1;
-                    println!("✅ Task 1 completed: Explicit type casting with 'as'!");
+                    log::info!(target: "tasks", "Task 1 completed: Explicit type casting with 'as'!");
                 }
             },
             1 => {
@@ -60,7 +60,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[1] = true;
                     self.tutorial_state.current_task = 2;
-                    println!("✅ Task 2 completed: Safe conversions with From/Into!");
+                    log::info!(target: "tasks", "Task 2 completed: Safe conversions with From/Into!");
                 }
             },
             2 => {
@@ -78,7 +78,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[2] = true;
                     self.tutorial_state.current_task = 3;
-                    println!("✅ Task 3 completed: String parsing and error handling!");
+                    log::info!(target: "tasks", "Task 3 completed: String parsing and error handling!");
                 }
             },
             3 => {
@@ -95,7 +95,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[3] = true;
                     self.tutorial_state.current_task = 4;
-                    println!("✅ Task 4 completed: Custom type conversions!");
+                    log::info!(target: "tasks", "Task 4 completed: Custom type conversions!");
                 }
             },
             4 => {
@@ -113,7 +113,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[4] = true;
                     self.tutorial_state.current_task = 5;
-                    println!("✅ Task 5 completed: Type inference with conversions!");
+                    log::info!(target: "tasks", "Task 5 completed: Type inference with conversions!");
                 }
             },
             _ => {}
@@ -40,7 +40,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[0] = true;
                     self.tutorial_state.current_task = 1;
-                    println!("✅ Task 1 completed: Robot Registration and Transfer Protocol!");
+                    log::info!(target: "tasks", "Task 1 completed: Robot Registration and Transfer Protocol!");
                 }
             },
             1 => {
@@ -55,7 +55,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[1] = true;
                     self.tutorial_state.current_task = 2;
-                    println!("✅ Task 2 completed: Mission Handoff and Resource Transfer!");
+                    log::info!(target: "tasks", "Task 2 completed: Mission Handoff and Resource Transfer!");
                 }
             },
             2 => {
@@ -71,7 +71,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[2] = true;
                     self.tutorial_state.current_task = 3;
-                    println!("✅ Task 3 completed: Shared Resources and Robot Communication!");
+                    log::info!(target: "tasks", "Task 3 completed: Shared Resources and Robot Communication!");
                 }
             },
             3 => {
@@ -87,7 +87,7 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[3] = true;
                     self.tutorial_state.current_task = 4;
-                    println!("✅ Task 4 completed: Command Structure and Robot Deployment!");
+                    log::info!(target: "tasks", "Task 4 completed: Command Structure and Robot Deployment!");
                 }
             },
             4 => {
@@ -105,8 +105,8 @@ impl Game {
                 ) {
                     self.tutorial_state.task_completed[4] = true;
                     self.tutorial_state.current_task = 5;
-                    println!("✅ Task 5 completed: Advanced Fleet Management Strategies!");
-                    println!("🎉 Level 6 Complete! You've mastered Robot Ownership Systems!");
+                    log::info!(target: "tasks", "Task 5 completed: Advanced Fleet Management Strategies!");
+                    log::info!(target: "tasks", "Level 6 Complete! You've mastered Robot Ownership Systems!");
                 }
             },
             _ => {}
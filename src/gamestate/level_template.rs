@@ -126,7 +126,7 @@ STEP 7: Integration Instructions
       And:
       X => self.check_level_X_progress(),
 
-2. Create your level YAML file in learning_levels/0X_your_level.yaml following the existing format
+2. Create your level YAML file in levels/0X_your_level.yaml following the existing format and add it to levels/order.txt
 
 3. Test by running the game and navigating to your level!
 
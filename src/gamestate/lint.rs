@@ -0,0 +1,46 @@
+// Lint findings for the code in the editor (see game_core::lint), worked out again whenever the
+// code changes or the robot takes a turn, and shown as yellow marks in the line number gutter.
+
+use super::types::Game;
+use game_core::ast::Diagnostic;
+use game_core::lint::{lint_program, LintWorld};
+
+impl Game {
+    /// Lint findings for the current code against what the player knows of the level
+    pub fn lint_diagnostics(&mut self) -> &[Diagnostic] {
+        let state = (self.level_loads, self.turns);
+        let revision = self.current_code.revision();
+        let stale = self.lint.as_ref().is_none_or(|(linted_revision, linted_state, _)| *linted_state != state || *linted_revision != revision);
+        if stale {
+            let diagnostics = lint_program(&self.current_code, self.lint_world().as_ref());
+            self.lint = Some((revision, state, diagnostics));
+        }
+        self.lint.as_ref().map_or(&[], |(_, _, diagnostics)| diagnostics.as_slice())
+    }
+
+    /// The first finding on the cursor's line, for the editor's status line
+    pub fn cursor_lint_message(&mut self) -> Option<String> {
+        let line = self.current_code.line_of(self.cursor_position) + 1;
+        self.lint_diagnostics().iter().find(|diagnostic| diagnostic.line == line).map(|diagnostic| diagnostic.message.clone())
+    }
+
+    /// Walls only count once they've been seen; other robots, conveyors and ice make the robot's
+    /// path uncertain
+    fn lint_world(&self) -> Option<LintWorld> {
+        if self.is_multi_robot() {
+            return None;
+        }
+        let known = |pos: &&crate::item::Pos| self.grid.known.contains(*pos);
+        Some(LintWorld {
+            width: self.grid.width,
+            height: self.grid.height,
+            start: self.robot.get_position(),
+            facing: self.robot.facing,
+            walls: self.grid.blockers.iter().chain(self.grid.mirrors.keys()).filter(known).map(|pos| (pos.x, pos.y)).collect(),
+            moving_tiles: self.grid.conveyors.keys()
+                .chain(self.grid.terrain.iter().filter(|(_, terrain)| **terrain == game_core::level::Terrain::Ice).map(|(pos, _)| pos))
+                .map(|pos| (pos.x, pos.y))
+                .collect(),
+        })
+    }
+}
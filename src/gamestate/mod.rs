@@ -1,7 +1,25 @@
 pub mod types;
 pub mod tutorial;
 pub mod cursor;
+pub mod wrap;
+pub mod goto_line;
+pub mod lint;
+pub mod vim;
+pub mod clipboard_history;
+pub mod code_metrics;
+pub mod daily_challenge;
+pub mod sandbox;
 pub mod game;
+pub mod scheduler;
+pub mod events;
+pub mod playback;
+pub mod ghost;
+pub mod ghost_race;
+pub mod turn_budget;
+pub mod time_limit;
+pub mod speech;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod background_jobs;
 pub mod level_2;
 pub mod level_3;
 pub mod level_4;
@@ -9,4 +27,7 @@ pub mod level_5;
 pub mod level_6;
 
 pub use types::*;
-pub use game::*;
\ No newline at end of file
+pub use game::*;
+pub use scheduler::*;
+pub use events::*;
+pub use playback::*;
\ No newline at end of file
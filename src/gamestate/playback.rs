@@ -0,0 +1,100 @@
+// "Show me": plays a reference solution from learning_level_solutions on the grid, one robot
+// action at a time, while the sidebar shows the solution read-only. The player's own code is
+// left alone, and the level is reset when playback stops.
+
+use super::{FunctionCall, Game};
+use std::collections::VecDeque;
+
+// Seconds between robot actions during playback
+pub const PLAYBACK_STEP_SECS: f64 = 0.6;
+
+#[derive(Clone, Debug)]
+pub struct SolutionPlayback {
+    pub title: String,
+    pub code: String,
+    pub total_steps: usize,
+    pub last_result: Option<String>,
+    calls: VecDeque<FunctionCall>,
+    last_step_time: Option<f64>,
+}
+
+impl SolutionPlayback {
+    pub fn new(title: String, code: &str, seed: u64) -> Self {
+        let calls: VecDeque<FunctionCall> = game_core::interpreter::parse_rust_code_from_main(code, seed).into();
+        Self {
+            title,
+            code: code.to_string(),
+            total_steps: calls.len(),
+            last_result: None,
+            calls,
+            last_step_time: None,
+        }
+    }
+
+    pub fn current_step(&self) -> usize {
+        self.total_steps - self.calls.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// The next action to play, once PLAYBACK_STEP_SECS have passed since the last one
+    pub fn next_call(&mut self, now: f64) -> Option<FunctionCall> {
+        if self.last_step_time.is_some_and(|last| now - last < PLAYBACK_STEP_SECS) {
+            return None;
+        }
+        let call = self.calls.pop_front()?;
+        self.last_step_time = Some(now);
+        Some(call)
+    }
+}
+
+impl Game {
+    /// Start playing the reference solution for the current task (or level)
+    pub fn start_solution_playback(&mut self) {
+        if !self.menu.settings.show_me_enabled {
+            self.popup_system.show_message(
+                crate::i18n::tr("game.show_me_disabled_title"),
+                crate::i18n::tr("game.show_me_disabled"),
+                crate::popup::PopupType::Info,
+                Some(3.0)
+            );
+            return;
+        }
+        let level_name = self.levels[self.level_idx].name.clone();
+        let task = self.tutorial_state.current_task + 1;
+        let Some(solution) = crate::learning_level_solutions::get_reference_solution_for_task(&level_name, task) else {
+            self.popup_system.show_message(
+                crate::i18n::tr("game.no_solution_title"),
+                crate::i18n::tr_with("game.no_solution", &[("level", &level_name)]),
+                crate::popup::PopupType::Info,
+                Some(3.0)
+            );
+            return;
+        };
+
+        // Start from a fresh level so the solution plays out the way it would for the player
+        let idx = self.level_idx;
+        self.load_level(idx);
+        self.code_editor_active = false;
+        let playback = SolutionPlayback::new(solution.task_description.to_string(), solution.solution_code, self.level_seed);
+        if playback.total_steps == 0 {
+            self.popup_system.show_message(
+                crate::i18n::tr("game.nothing_to_animate_title"),
+                crate::i18n::tr("game.nothing_to_animate"),
+                crate::popup::PopupType::Info,
+                Some(4.0)
+            );
+        }
+        self.solution_playback = Some(playback);
+    }
+
+    /// Leave playback and put the level back the way it starts
+    pub fn stop_solution_playback(&mut self) {
+        if self.solution_playback.take().is_some() {
+            let idx = self.level_idx;
+            self.load_level(idx);
+        }
+    }
+}
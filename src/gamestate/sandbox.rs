@@ -0,0 +1,188 @@
+// Sandbox: free play outside the curriculum on a big empty grid (sandbox_width x sandbox_height in
+// game_settings.json) with every robot function unlocked and no win condition. A palette over the
+// grid picks what a click puts on a tile: a wall, an item or an enemy, or the eraser. Placements
+// are written into the level itself so they survive the resets an enemy collision causes. Like
+// Endless Mode, the level lives in an extra slot at the end of the level list.
+
+use super::types::Game;
+use crate::grid::Enemy;
+use crate::i18n::tr;
+use crate::item::{ItemCapabilities, Pos};
+use crate::level::{EnemyDirection, EnemySpec, ItemSpec, LevelSpec};
+use crate::popup::PopupType;
+use std::collections::HashMap;
+
+pub const SANDBOX_MIN_SIZE: usize = 5;
+pub const SANDBOX_MAX_SIZE: usize = 200;
+
+/// What a click on the grid does in the sandbox
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SandboxTool {
+    Wall,
+    Item,
+    Enemy,
+    Erase,
+}
+
+impl SandboxTool {
+    pub const ALL: [SandboxTool; 4] = [SandboxTool::Wall, SandboxTool::Item, SandboxTool::Enemy, SandboxTool::Erase];
+
+    pub fn label(self) -> String {
+        tr(match self {
+            SandboxTool::Wall => "sandbox.wall",
+            SandboxTool::Item => "sandbox.item",
+            SandboxTool::Enemy => "sandbox.enemy",
+            SandboxTool::Erase => "sandbox.erase",
+        })
+    }
+}
+
+fn sandbox_level(width: usize, height: usize) -> LevelSpec {
+    let width = width.clamp(SANDBOX_MIN_SIZE, SANDBOX_MAX_SIZE);
+    let height = height.clamp(SANDBOX_MIN_SIZE, SANDBOX_MAX_SIZE);
+    LevelSpec {
+        name: tr("sandbox.level_name"),
+        width,
+        height,
+        start: (width / 2, height / 2),
+        scanner_at: None,
+        blockers: vec![],
+        doors: vec![],
+        locked_doors: vec![],
+        switches: vec![],
+        gates: vec![],
+        emitters: vec![],
+        terrain: vec![],
+        spawners: vec![],
+        flanking: false,
+        win_condition: None,
+        real_time_limit_secs: None,
+        enemies: vec![],
+        items: vec![],
+        tasks: vec![],
+        fog_of_war: false,
+        max_turns: 0,
+        income_per_square: 0,
+        message: None,
+        hint_message: None,
+        rust_docs_url: None,
+        starting_code: None,
+        completion_condition: None,
+        completion_flag: None,
+        achievement_message: None,
+        next_level_hint: None,
+        completion_message: None,
+        energy: None,
+        crates: vec![],
+        plates: vec![],
+        conveyors: vec![],
+        spikes: vec![],
+        robots: vec![],
+        mirrors: vec![],
+        message_i18n: Default::default(),
+        ownership_view: false,
+        seed: None,
+        allowed_functions: None,
+        auto_grab: None,
+        tags: Vec::new(),
+        difficulty: None,
+        author: None,
+        version: None,
+        requires: Vec::new(),
+    }
+}
+
+fn sandbox_item_capabilities() -> ItemCapabilities {
+    ItemCapabilities { credits_value: Some(1), ..ItemCapabilities::default() }
+}
+
+impl Game {
+    pub fn in_sandbox(&self) -> bool {
+        self.sandbox_tool.is_some()
+    }
+
+    pub fn start_sandbox(&mut self) {
+        let settings = &self.menu.settings;
+        self.levels.push(sandbox_level(settings.sandbox_width, settings.sandbox_height));
+        self.sandbox_tool = Some(SandboxTool::Wall);
+        self.level_idx = self.levels.len() - 1;
+        self.load_level(self.level_idx);
+        self.popup_system.show_message(tr("sandbox.title"), tr("sandbox.help"), PopupType::Info, None);
+    }
+
+    /// Leave the sandbox and drop its level from the level list
+    pub fn stop_sandbox(&mut self) {
+        if self.sandbox_tool.take().is_some() {
+            self.levels.pop();
+        }
+    }
+
+    /// Nothing is hidden in the sandbox; called whenever its level is (re)loaded
+    pub(super) fn reveal_sandbox(&mut self) {
+        for y in 0..self.grid.height {
+            for x in 0..self.grid.width {
+                self.grid.known.insert(Pos { x, y });
+            }
+        }
+    }
+
+    /// Use the selected tool on `pos`. The robot's own tile can't be built on.
+    pub fn sandbox_place(&mut self, pos: Pos) -> bool {
+        let Some(tool) = self.sandbox_tool else { return false };
+        if !self.grid.in_bounds(pos) || self.robot_positions().contains(&pos) {
+            return false;
+        }
+        self.clear_sandbox_tile(pos);
+
+        let tile = (pos.x as usize, pos.y as usize);
+        let spec = &mut self.levels[self.level_idx];
+        match tool {
+            SandboxTool::Wall => {
+                spec.blockers.push(tile);
+                self.grid.blockers.insert(pos);
+            }
+            SandboxTool::Item => {
+                let name = tr("sandbox.item_name");
+                spec.items.push(ItemSpec { name: name.clone(), pos: Some((pos.x, pos.y)), capabilities: sandbox_item_capabilities() });
+                self.item_manager.add_item(name, pos, sandbox_item_capabilities());
+            }
+            SandboxTool::Enemy => {
+                spec.enemies.push(EnemySpec {
+                    pos: (pos.x, pos.y),
+                    direction: EnemyDirection::Horizontal,
+                    moving_positive: true,
+                    movement_pattern: None,
+                    hp: None,
+                    size: 1,
+                    boss_phases: Vec::new(),
+                    aggro_radius: None,
+                });
+                self.grid.enemies.push(Enemy {
+                    pos,
+                    direction: EnemyDirection::Horizontal,
+                    moving_positive: true,
+                    movement_pattern: None,
+                    movement_data: HashMap::new(),
+                    hp: None,
+                    max_hp: 0,
+                    size: 1,
+                    boss_phases: Vec::new(),
+                });
+            }
+            SandboxTool::Erase => {}
+        }
+        true
+    }
+
+    /// Take every wall, item and enemy off `pos`, in the level and on the grid
+    fn clear_sandbox_tile(&mut self, pos: Pos) {
+        let tile = (pos.x as usize, pos.y as usize);
+        let spec = &mut self.levels[self.level_idx];
+        spec.blockers.retain(|&blocker| blocker != tile);
+        spec.items.retain(|item| item.pos != Some((pos.x, pos.y)));
+        spec.enemies.retain(|enemy| enemy.pos != (pos.x, pos.y));
+        self.grid.blockers.remove(&pos);
+        self.item_manager.items.retain(|item| item.pos != pos);
+        self.grid.enemies.retain(|enemy| enemy.pos != pos);
+    }
+}
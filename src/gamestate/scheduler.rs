@@ -0,0 +1,172 @@
+// Turn resolution for the GUI, headless test mode and the replay exporter. Every robot action goes
+// through run_action, which handles multi-robot rounds and rewind snapshots and then resolves each
+// turn the action took with game-core's TurnScheduler, the same pipeline the external test runner
+// uses:
+//
+//   player action → hazards → enemies → collisions → win check
+//
+// What happens to enemies and robots along the way goes into the combat log, by turn.
+
+use super::{ExecutionOutcome, FunctionCall, Game, GameEvent, RustFunction};
+use game_core::combat_log::{enemy_steps, CombatEvent};
+use game_core::scheduler::{TurnEnd, TurnPhases, TurnScheduler};
+
+pub const ENEMY_COLLISION_MESSAGE: &str = "ENEMY COLLISION! Level reset and randomized.";
+
+/// Run one robot action and resolve the turn(s) it took. `action` only performs the action
+/// itself and advances `game.turns` by the number of turns it costs; everything the world does
+/// in response happens here.
+pub fn run_action(game: &mut Game, call: FunctionCall, action: impl FnOnce(&mut Game, FunctionCall) -> ExecutionOutcome) -> ExecutionOutcome {
+    // robot(n).action() temporarily takes control of robot n
+    let previous_robot = game.active_robot;
+    let targeted = call.function != RustFunction::SelectRobot && call.robot.is_some();
+    if targeted
+        && let Some(index) = call.robot
+        && let Err(message) = game.select_robot(index)
+    {
+        return ExecutionOutcome::Error { message };
+    }
+
+    // On multi-robot levels the turn only advances once every robot has acted
+    let completes_round = game.completes_robot_round();
+
+    // rewind() goes back to the state at the start of a turn (of the whole round with several robots)
+    let starts_turn = game.robots_acted.is_empty();
+    let snapshot = game.turn_snapshot();
+
+    // Level resets and rewinds set the turn counter back, so they never count as turns taken
+    let turns_before = game.turns;
+    let was_finished = game.finished;
+    let mut outcome = action(game, call);
+    let turns_taken = game.turns.saturating_sub(turns_before);
+    log_combat(game, turns_before + 1);
+    if turns_taken > 0 && starts_turn {
+        game.push_turn_snapshot(snapshot);
+    }
+
+    if turns_taken > 0 && !completes_round {
+        game.turns = turns_before;
+        game.robots_acted.insert(game.active_robot);
+    }
+    if targeted && game.is_multi_robot() {
+        let _ = game.select_robot(previous_robot);
+    }
+    if turns_taken == 0 || !completes_round {
+        return check_completion(game, was_finished, outcome);
+    }
+
+    // Multi-turn actions (teleport) resolve every turn they took, stopping at a level reset
+    game.turns = turns_before;
+    let mut events = Vec::new();
+    let mut caught = false;
+    for _ in 0..turns_taken {
+        game.turns += 1;
+        let turn = game.turns;
+        let report = TurnScheduler::resolve_turn(game);
+        events.extend(report.events);
+        if let TurnEnd::Caught(message) = report.end {
+            events.push(message);
+            caught = true;
+        }
+        log_combat(game, turn);
+        if caught {
+            break;
+        }
+    }
+
+    if !events.is_empty() {
+        // A routine action only says it ran, so the events replace it
+        let events = events.join(". ");
+        if outcome.is_routine() {
+            outcome = ExecutionOutcome::Acted { message: events };
+        } else {
+            outcome.append(&events);
+        }
+    }
+    // Getting caught resets the level
+    if caught && !matches!(outcome, ExecutionOutcome::Collision { .. }) {
+        outcome = ExecutionOutcome::Collision { message: outcome.message().to_string() };
+    }
+    check_completion(game, was_finished, outcome)
+}
+
+fn check_completion(game: &Game, was_finished: bool, outcome: ExecutionOutcome) -> ExecutionOutcome {
+    if game.finished && !was_finished {
+        let message = if outcome.is_routine() { "Level complete!".to_string() } else { outcome.message().to_string() };
+        ExecutionOutcome::LevelComplete { message }
+    } else {
+        outcome
+    }
+}
+
+/// Dispatch the events published so far and log the turn's combat events under `turn`
+fn log_combat(game: &mut Game, turn: usize) {
+    game.dispatch_events();
+    let events = std::mem::take(&mut game.combat_events);
+    game.combat_log.record(turn, events);
+}
+
+/// The game's phases of a turn, run in order by game-core's TurnScheduler. Everything that
+/// catches a robot resets the level.
+impl TurnPhases for Game {
+    /// Conveyors, spikes and laser beams under every robot; a reset sets the turn counter to 0
+    fn hazards(&mut self) -> Result<Option<String>, String> {
+        match self.apply_round_hazards() {
+            Some(message) if self.turns == 0 => Err(message),
+            hazard => Ok(hazard),
+        }
+    }
+
+    /// Stuns and lasered obstacles wear off, then every enemy takes a step
+    fn enemies(&mut self) {
+        if self.level_idx < 3 || self.enemy_step_paused {
+            return;
+        }
+        self.update_laser_effects();
+        let before: Vec<_> = self.grid.enemies.iter().map(|enemy| enemy.pos).collect();
+        self.grid.move_enemies(Some(self.robot.get_position()));
+        let after: Vec<_> = self.grid.enemies.iter().map(|enemy| enemy.pos).collect();
+        for step in enemy_steps(&before, &after) {
+            self.publish(GameEvent::Combat(step));
+        }
+    }
+
+    /// An enemy standing on any robot resets the level
+    fn collisions(&mut self) -> Option<String> {
+        if self.level_idx < 3 {
+            return None;
+        }
+        if !self.is_multi_robot() {
+            let pos = self.robot.get_position();
+            if !self.enemy_collision_at(pos) {
+                return None;
+            }
+            self.publish(GameEvent::Combat(CombatEvent::RobotCaught { robot: 0, at: self.robot.get_pos() }));
+            let idx = self.level_idx;
+            self.load_level(idx);
+            return Some(ENEMY_COLLISION_MESSAGE.to_string());
+        }
+
+        // Each robot's own shield protects it, so check them one at a time
+        let selected = self.active_robot;
+        for index in 0..self.robots.len() {
+            let _ = self.select_robot(index);
+            let pos = self.robot.get_position();
+            if self.enemy_collision_at(pos) {
+                self.publish(GameEvent::Combat(CombatEvent::RobotCaught { robot: index, at: self.robot.get_pos() }));
+                let idx = self.level_idx;
+                self.load_level(idx);
+                return Some(format!("ENEMY COLLISION with robot {}! Level reset and randomized.", index));
+            }
+        }
+        let _ = self.select_robot(selected);
+        None
+    }
+
+    /// Checked once the ghost race knows where the robot ended the turn
+    fn win_check(&mut self) -> bool {
+        self.record_race_turn();
+        self.check_end_condition();
+        self.finished
+    }
+}
@@ -0,0 +1,35 @@
+// Speech bubbles: say("text") shows the text over the robot that said it for a couple of turns and
+// keeps it in the Logs tab. It's a friendlier place than popups for early levels to print
+// formatted strings, and saying something takes no turn.
+
+use super::types::Game;
+use crate::popup::PopupType;
+
+// Turns a speech bubble stays up after say()
+const SPEECH_TURNS: usize = 2;
+
+/// Text a robot is saying, shown over it until `until_turn`
+#[derive(Clone, Debug)]
+pub struct Speech {
+    pub robot: usize,
+    pub text: String,
+    pub until_turn: usize,
+}
+
+impl Game {
+    /// Put `text` in a bubble over the robot being controlled and record it in the message log
+    pub fn say(&mut self, text: &str) -> String {
+        self.speech = Some(Speech {
+            robot: self.active_robot,
+            text: text.to_string(),
+            until_turn: self.turns + SPEECH_TURNS,
+        });
+        self.popup_system.history.push(PopupType::FunctionResults, "🤖 Robot says", text);
+        format!("Said \"{}\"", text)
+    }
+
+    /// The speech bubble still up on the current turn
+    pub fn current_speech(&self) -> Option<&Speech> {
+        self.speech.as_ref().filter(|speech| self.turns < speech.until_turn)
+    }
+}
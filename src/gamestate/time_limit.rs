@@ -0,0 +1,32 @@
+// Real-time limit: a level with real_time_limit_secs has to be finished within that many seconds
+// of wall-clock time. The countdown stands still while a popup is open, so reading the level
+// message or a hint costs nothing, and the level resets when it runs out.
+
+use super::types::Game;
+use crate::i18n::tr;
+use crate::popup::PopupType;
+
+impl Game {
+    /// Count `delta_time` seconds off the level's clock and reset the level once it runs out
+    pub fn update_time_limit(&mut self, delta_time: f32) {
+        if self.finished || self.popup_system.is_showing() {
+            return;
+        }
+        let Some(left) = self.time_left.as_mut() else { return };
+        *left -= delta_time;
+        if *left > 0.0 {
+            return;
+        }
+
+        let idx = self.level_idx;
+        self.load_level(idx);
+        self.execution_result = "TIME'S UP! The level's time limit ran out. Level reset.".to_string();
+        self.popup_system.show_message(tr("time_limit.expired_title"), tr("time_limit.expired"), PopupType::Warning, None);
+    }
+
+    /// The countdown as shown next to the turn counter, e.g. "Time 1:05"
+    pub fn time_left_label(&self) -> Option<String> {
+        let seconds = self.time_left?.max(0.0).ceil() as u32;
+        Some(format!("{} {}:{:02}", tr("time_limit.label"), seconds / 60, seconds % 60))
+    }
+}
@@ -0,0 +1,125 @@
+// Turn budget: on levels with max_turns, a program is played out once against the current state
+// before it runs for real. A program that would take more turns than the level allows gets a
+// warning with the call that goes over the limit, so it can be shortened before the run.
+// The dry run goes through the same TurnScheduler as the real one and everything it changes is
+// put back afterwards; `dry_run` keeps level resets and completion from touching anything else.
+
+use super::types::{Game, TurnSnapshot};
+use super::{ExecutionOutcome, FunctionCall, RustFunction};
+use crate::i18n::{tr, tr_with};
+use crate::popup::{PopupSystem, PopupType};
+
+/// How a program would use the turns the level allows
+#[derive(Clone, Debug)]
+pub struct TurnProjection {
+    pub turns: usize,           // Turns the program takes, up to a halt, reset or win
+    pub allowed: usize,         // Turns the level has left when the program starts
+    pub over_at: Option<usize>, // Index of the call that goes past the limit
+}
+
+/// What a dry run may change besides the TurnSnapshot
+struct SavedState {
+    snapshot: TurnSnapshot,
+    turn_history: std::collections::VecDeque<TurnSnapshot>,
+    robots_acted: std::collections::HashSet<usize>,
+    race_path: Vec<crate::item::Pos>,
+    finished: bool,
+    scan_armed: bool,
+    time_slow: (bool, u32),
+    teleport_effect: Option<(crate::item::Pos, crate::item::Pos, f64)>,
+    emp_effect: Option<(crate::item::Pos, u32, f64)>,
+    laser_path: Option<(Vec<crate::item::Pos>, f64)>,
+    combat_log: game_core::combat_log::CombatLog,
+    speech: Option<super::speech::Speech>,
+    outputs: (usize, usize),
+    popup_system: PopupSystem,
+}
+
+impl Game {
+    /// Play `calls` out with `run` and put everything back. None when the level has no turn limit.
+    pub fn project_turns(&mut self, calls: &[FunctionCall], run: impl Fn(&mut Game, FunctionCall) -> ExecutionOutcome) -> Option<TurnProjection> {
+        if self.max_turns == 0 || self.finished || self.in_sandbox() {
+            return None;
+        }
+        let turns_before = self.turns;
+        let allowed = self.max_turns.saturating_sub(turns_before);
+        let saved = self.save_for_dry_run();
+        self.dry_run = true;
+
+        let mut over_at = None;
+        // The real run is aborted once it's through the execution budget, so the dry run stops there too
+        for (i, call) in calls.iter().enumerate().take(game_core::budget::MAX_ACTIONS) {
+            // Level changes can't be played ahead
+            if matches!(call.function, RustFunction::SkipLevel | RustFunction::GotoLevel) {
+                break;
+            }
+            let outcome = run(self, call.clone());
+            if over_at.is_none() && self.turns > self.max_turns {
+                over_at = Some(i);
+            }
+            let reset = matches!(outcome, ExecutionOutcome::Collision { .. });
+            if outcome.halt_message().is_some() || reset || self.finished {
+                break;
+            }
+        }
+        let turns = self.turns.saturating_sub(turns_before);
+
+        self.dry_run = false;
+        self.restore_after_dry_run(saved);
+        Some(TurnProjection { turns, allowed, over_at })
+    }
+
+    /// Warn before the run when `calls` would go over the turn limit
+    pub fn warn_turn_budget(&mut self, calls: &[FunctionCall], functions: &[String], run: impl Fn(&mut Game, FunctionCall) -> ExecutionOutcome) {
+        let Some(projection) = self.project_turns(calls, run) else { return };
+        let Some(step) = projection.over_at else { return };
+        let function = functions.get(step).map_or("main", String::as_str);
+        let content = tr_with("turns.over", &[
+            ("turns", &projection.turns.to_string()),
+            ("allowed", &projection.allowed.to_string()),
+            ("step", &(step + 1).to_string()),
+            ("call", &game_core::capabilities::call_label(&calls[step])),
+            ("function", function),
+        ]);
+        self.popup_system.show_message(tr("turns.over_title"), content, PopupType::Warning, None);
+    }
+
+    fn save_for_dry_run(&mut self) -> SavedState {
+        SavedState {
+            snapshot: self.turn_snapshot(),
+            turn_history: self.turn_history.clone(),
+            robots_acted: self.robots_acted.clone(),
+            race_path: self.race_path.clone(),
+            finished: self.finished,
+            scan_armed: self.scan_armed,
+            time_slow: (self.time_slow_active, self.time_slow_duration_ms),
+            teleport_effect: self.teleport_effect,
+            emp_effect: self.emp_effect,
+            laser_path: self.laser_path.clone(),
+            combat_log: self.combat_log.clone(),
+            speech: self.speech.clone(),
+            outputs: (self.println_outputs.len(), self.error_outputs.len()),
+            // Popups the dry run raises go into a scratch system that is thrown away
+            popup_system: std::mem::take(&mut self.popup_system),
+        }
+    }
+
+    fn restore_after_dry_run(&mut self, saved: SavedState) {
+        self.restore_turn_snapshot(saved.snapshot);
+        self.turn_history = saved.turn_history;
+        self.robots_acted = saved.robots_acted;
+        self.race_path = saved.race_path;
+        self.finished = saved.finished;
+        self.scan_armed = saved.scan_armed;
+        (self.time_slow_active, self.time_slow_duration_ms) = saved.time_slow;
+        self.teleport_effect = saved.teleport_effect;
+        self.emp_effect = saved.emp_effect;
+        self.laser_path = saved.laser_path;
+        self.combat_log = saved.combat_log;
+        self.combat_events.clear();
+        self.speech = saved.speech;
+        self.println_outputs.truncate(saved.outputs.0);
+        self.error_outputs.truncate(saved.outputs.1);
+        self.popup_system = saved.popup_system;
+    }
+}
@@ -7,6 +7,15 @@ impl Game {
         if !self.is_learning_level(self.level_idx) {
             return String::new();
         }
+
+        // A translation from the level YAML wins over the built-in English text
+        let language = crate::i18n::current_language();
+        if language != crate::i18n::Language::English {
+            let task = self.levels[self.level_idx].tasks.get(self.tutorial_state.current_task);
+            if let Some(message) = task.and_then(|task| task.task_message_i18n.get(language.code())) {
+                return message.clone();
+            }
+        }
         
         match self.level_idx {
             0 => self.get_level_1_task_message(),
@@ -49,6 +58,81 @@ impl Game {
             }
         }
     }
+
+    /// Ask the evaluator of the level's current task (the first one not completed) whether the last
+    /// run solves it. Only tasks whose task_file names a script or library are checked here.
+    pub fn check_plugin_tasks(&mut self, code: &str) {
+        let level = &self.levels[self.level_idx];
+        let Some(index) = level.tasks.iter().position(|task| !task.completed) else { return };
+        let task = &level.tasks[index];
+        let Some(task_file) = task.task_file.clone().filter(|file| crate::task_plugins::is_plugin(file)) else { return };
+
+        let context = crate::task_plugins::TaskContext {
+            api_version: crate::task_plugins::TASK_PLUGIN_API_VERSION,
+            level: level.name.clone(),
+            task: task.name.clone(),
+            task_index: index,
+            code: code.to_string(),
+            stdout: self.println_outputs.clone(),
+            stderr: self.error_outputs.clone(),
+            game: self.serde_state_document(),
+        };
+        let completion_message = task.completion_message.clone();
+        let number = (index + 1).to_string();
+
+        match crate::task_plugins::evaluate(&task_file, &context) {
+            Ok(verdict) if verdict.passed => {
+                self.levels[self.level_idx].tasks[index].completed = true;
+                self.popup_system.show_message(
+                    crate::i18n::tr_with("game.task_complete_title", &[("number", &number)]),
+                    verdict.message.or(completion_message).unwrap_or_default(),
+                    crate::popup::PopupType::Success,
+                    Some(4.0)
+                );
+            }
+            Ok(verdict) => {
+                if let Some(message) = verdict.message {
+                    self.popup_system.show_message(
+                        crate::i18n::tr_with("game.task_not_passed_title", &[("number", &number)]),
+                        message,
+                        crate::popup::PopupType::Info,
+                        Some(4.0)
+                    );
+                }
+            }
+            Err(e) => {
+                log::warn!("Task evaluator {} failed: {}", task_file, e);
+                self.popup_system.show_message(
+                    crate::i18n::tr("game.task_evaluator_failed_title"),
+                    e,
+                    crate::popup::PopupType::Warning,
+                    None
+                );
+            }
+        }
+    }
+
+    /// In screen reader mode, read out the task message whenever the level or task changes.
+    /// Waits for popups to close so it doesn't talk over the level introduction.
+    pub fn announce_task_if_changed(&mut self) {
+        if !crate::speech::is_enabled() || self.popup_system.is_showing() {
+            return;
+        }
+        let key = (self.level_idx, self.tutorial_state.current_task);
+        if self.announced_task == Some(key) {
+            return;
+        }
+        self.announced_task = Some(key);
+        let message = if self.is_learning_level(self.level_idx) {
+            self.get_tutorial_task_message()
+        } else {
+            self.levels[self.level_idx].tasks.get(key.1)
+                .and_then(|task| task.localized_task_message(crate::i18n::current_language().code()))
+                .unwrap_or_default()
+                .to_string()
+        };
+        crate::speech::announce(&message);
+    }
     
     // Level 1 specific methods
     fn get_level_1_task_message(&self) -> String {
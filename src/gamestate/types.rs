@@ -1,29 +1,49 @@
 use crate::level::LevelSpec;
 use crate::grid::Grid;
 use crate::robot::Robot;
-use crate::item::ItemManager;
+use crate::item::{ItemManager, Pos};
 use crate::menu::Menu;
 use crate::popup::PopupSystem;
 use rand::rngs::StdRng;
+pub use game_core::interpreter::{ExecutionOutcome, FunctionCall, RustFunction};
 
 #[cfg(not(target_arch = "wasm32"))]
 use crossbeam_channel::Receiver;
 #[cfg(not(target_arch = "wasm32"))]
 use notify::Event;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum RustFunction {
-    Move,
-    Grab,
-    Scan,
-    LaserDirection,
-    LaserTile,
-    OpenDoor,
-    SkipLevel,
-    GotoLevel,
-    Println,
-    Eprintln, // Error messages
-    Panic,    // Critical errors
+/// Level state before a turn, restored by rewind()
+#[derive(Clone, Debug)]
+pub struct TurnSnapshot {
+    pub grid: Grid,
+    pub robot: Robot,
+    pub robots: Vec<Robot>,
+    pub active_robot: usize,
+    pub item_manager: ItemManager,
+    pub turns: usize,
+    pub energy: Option<u32>,
+    pub credits: u32,
+    pub discovered_this_level: usize,
+    pub temporary_removed_obstacles: std::collections::HashMap<(i32, i32), u8>,
+}
+
+/// The robot gliding between tiles while a program plays at a visible speed
+#[derive(Clone, Copy, Debug)]
+pub struct MoveAnimation {
+    pub from: crate::item::Pos,
+    pub to: crate::item::Pos,
+    pub start: f64,
+    pub duration: f64,
+}
+
+impl MoveAnimation {
+    /// Tile coordinates (fractional mid-move) at time `now`
+    pub fn position(&self, now: f64) -> (f32, f32) {
+        let t = if self.duration > 0.0 { ((now - self.start) / self.duration).clamp(0.0, 1.0) as f32 } else { 1.0 };
+        let eased = t * t * (3.0 - 2.0 * t); // Smoothstep: ease in and out of each tile
+        let lerp = |a: i32, b: i32| a as f32 + (b - a) as f32 * eased;
+        (lerp(self.from.x, self.to.x), lerp(self.from.y, self.to.y))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -34,16 +54,6 @@ pub struct UndoState {
     pub selection_end: Option<usize>,
 }
 
-#[derive(Clone, Debug)]
-pub struct FunctionCall {
-    pub function: RustFunction,
-    pub direction: Option<(i32, i32)>, // for move, scan, and laser direction
-    pub coordinates: Option<(i32, i32)>, // for laser tile targeting
-    pub level_number: Option<usize>, // for goto_level
-    pub boolean_param: Option<bool>, // for open_door
-    pub message: Option<String>, // for println
-}
-
 #[derive(Clone, Debug)]
 pub struct TutorialState {
     pub task_completed: [bool; 5], // Track completion of 5 tutorial tasks
@@ -58,12 +68,17 @@ pub struct Game {
     pub level_idx: usize,
     pub levels: Vec<LevelSpec>,
     pub grid: Grid,
-    pub robot: Robot,
+    pub robot: Robot, // The robot being controlled (a working copy of robots[active_robot] on multi-robot levels)
+    pub robots: Vec<Robot>, // Every robot on multi-robot levels, empty otherwise
+    pub active_robot: usize,
+    pub robots_acted: std::collections::HashSet<usize>, // Robots that acted since the turn last advanced
     pub item_manager: ItemManager,
     pub rng: StdRng,
+    pub level_seed: u64, // Seed random_int() draws from in the current level
     pub credits: u32,
     pub turns: usize,
     pub max_turns: usize,
+    pub energy: Option<u32>, // Remaining energy on levels with an energy budget
     pub discovered_this_level: usize,
     pub finished: bool,
     pub scan_armed: bool,
@@ -74,7 +89,8 @@ pub struct Game {
     #[cfg(not(target_arch = "wasm32"))]
     pub file_watcher_receiver: Option<Receiver<notify::Result<Event>>>,
     pub robot_code_modified: bool,
-    pub current_code: String,
+    pub robot_code_saves: u64, // Times the editor has written robot_code.rs, to tell stale reloads apart
+    pub current_code: crate::text_buffer::TextBuffer, // Editor contents
     pub cursor_position: usize,
     pub code_execution_requested: bool, // Flag to request code execution via Ctrl+Shift+Enter
     pub selection_start: Option<usize>, // Start of text selection (None = no selection)
@@ -83,20 +99,44 @@ pub struct Game {
     pub is_dragging: bool,              // Whether we're currently dragging to select text
     pub code_scroll_offset: usize, // Top line displayed in editor
     pub code_lines_visible: usize, // Number of lines visible in editor
+    pub code_scroll_column: usize, // First column displayed when long lines scroll sideways
+    pub code_columns_visible: usize, // Columns that fit in the editor's text area, measured when it's drawn
+    pub goto_line_input: Option<String>, // Digits typed into the Ctrl+G prompt; None while it's closed
     pub tutorial_scroll_offset: usize, // Top line displayed in tutorial overlay
     pub enemy_step_paused: bool,
     pub time_slow_active: bool,
     pub time_slow_duration_ms: u32,
     pub menu: Menu,
     pub popup_system: PopupSystem,
-    pub stunned_enemies: std::collections::HashMap<usize, u8>, // enemy_index -> remaining_stun_turns
+    pub teleport_effect: Option<(crate::item::Pos, crate::item::Pos, f64)>, // from, to, start time of the last teleport
+    pub emp_effect: Option<(crate::item::Pos, u32, f64)>, // center, radius, start time of the last EMP blast
+    pub laser_path: Option<(Vec<crate::item::Pos>, f64)>, // Beam corners (robot, bounces, end) and when it was fired
+    pub turn_history: std::collections::VecDeque<TurnSnapshot>, // Oldest first, at most MAX_REWIND_TURNS
+    pub level_loads: u64, // Bumped by every load_level so cached drawing knows the level changed
     pub temporary_removed_obstacles: std::collections::HashMap<(i32, i32), u8>, // position -> remaining_turns
     pub println_outputs: Vec<String>, // Track println outputs for completion conditions
     pub error_outputs: Vec<String>, // Track error/eprintln outputs for completion conditions
+    pub log_records: Vec<crate::code_executor::LogRecord>, // Records logged through the log crate, for completion conditions
     pub panic_occurred: bool, // Track if panic occurred for completion conditions
+    pub serde_round_trips: Vec<crate::code_executor::RoundTrip>, // Serde structs checked against the game state on the last run
     pub tutorial_state: TutorialState, // Tutorial system for progressive learning
     #[cfg(not(target_arch = "wasm32"))]
-    pub rust_checker: Option<crate::rust_checker::RustChecker>, // Cargo integration for syntax checking
+    pub rust_checker: Option<crate::rust_checker::BackgroundChecker>, // Cargo syntax checking on a worker thread
+    #[cfg(not(target_arch = "wasm32"))]
+    pub jobs: Option<crate::jobs::JobQueue>, // File reloads and cargo add on worker threads
+    #[cfg(not(target_arch = "wasm32"))]
+    pub autosave: crate::autosave::Autosave, // Periodic editor snapshots for crash recovery
+    #[cfg(feature = "vcs")]
+    pub code_history: Option<crate::code_history::HistoryPanel>, // Git history of successful runs
+    pub solution_comparison: Option<crate::code_diff::SolutionComparison>, // Results screen comparing the code with the reference solution
+    pub solution_playback: Option<super::SolutionPlayback>, // "Show me" reference solution playing on the grid
+    pub program_running: bool, // A program is playing out step by step (speed above Instant)
+    pub program_paused: bool,  // Playback held between steps by the pause button
+    pub cancel_requested: bool, // The Stop button or Escape ends the run before its next action
+    pub robot_animation: Option<MoveAnimation>, // Current robot move being animated
+    pub grid_camera: crate::drawing::camera::GridCamera, // Zoom and pan of the grid view
+    pub announced_task: Option<(usize, usize)>, // Level and task last read aloud in screen reader mode
+    pub last_activity: f64, // When the player last pressed a key, clicked or ran code; drives the tutorial ghost
     // Continuous key press support
     pub key_backspace_held_time: f32,
     pub key_space_held_time: f32,
@@ -116,13 +156,19 @@ pub struct Game {
     pub needs_font_refresh: bool,      // Flag to indicate font measurements need refresh
     // Editor tab system (above editor)
     pub editor_tab: EditorTab, // Current active tab above the editor
+    pub log_filter: crate::popup::LogFilter, // Message types shown in the Logs tab
+    pub game_log_view: crate::game_log::LogView, // Level and module shown in the Logs tab's Game log
+    pub combat_log: game_core::combat_log::CombatLog, // Each turn's enemy moves, hits and stuns, for the Logs tab's Combat log
+    pub combat_events: Vec<game_core::combat_log::CombatEvent>, // Caused this turn; the turn scheduler logs them once it's resolved
+    pub events: super::events::EventBus, // Published game events and the subscribers that react to them
+    pub combat_log_scroll: usize, // Lines the Combat log is scrolled back from the newest entry
+    pub ownership_timelines: Vec<game_core::ownership::VariableTimeline>, // Ownership tab contents from the last run
+    pub watch: crate::watch::WatchPanel, // Watch tab: variables at each robot action of the last run
     // Coordinate transformation system
     pub coordinate_transformer: crate::coordinate_system::CoordinateTransformer, // Global/window mouse coordinate handling
     // System key safety mechanism
     pub last_system_key_time: f64,    // Time when last system key combination was detected
     // Debug flags
-    pub enable_coordinate_logs: bool, // Enable detailed coordinate transformation logs
-    pub enable_key_press_logs: bool,  // Enable detailed key press and hotkey logs
     pub last_key_log_time: f64,       // Time of last key press log (for rate limiting)
     pub last_exec_log_time: f64,      // Time of last execution log (for rate limiting)
     // Window tracking timer
@@ -133,9 +179,29 @@ pub struct Game {
     pub autocomplete_enabled: bool,   // Global autocomplete enable/disable
     // Hotkey system
     pub hotkey_system: crate::hotkeys::HotkeySystem,
+    pub vim: Option<super::vim::VimState>, // Modal editing state while Vim mode is on
+    pub mark_active: bool, // Emacs mark set: cursor movement extends the selection
+    pub clipboard_history: super::clipboard_history::ClipboardHistory, // This session's cuts and copies
     // Undo functionality (clipboard now uses OS)
     pub undo_stack: Vec<UndoState>,
     pub redo_stack: Vec<UndoState>,
+    // Endless mode: Some(depth) while playing generated levels
+    pub endless_depth: Option<usize>,
+    // Daily Challenge: Some(day) while playing that day's generated level
+    pub daily_challenge: Option<u64>,
+    // Ghost race: this run's robot positions by turn, and the fastest finished run of each level
+    pub race_path: Vec<Pos>,
+    pub best_runs: super::ghost_race::BestRuns,
+    // Sandbox: Some(selected palette tool) while playing free-play
+    pub sandbox_tool: Option<super::sandbox::SandboxTool>,
+    // Turn budget: true while a program is played out ahead of time, see turn_budget.rs
+    pub dry_run: bool,
+    // Real-time limit: seconds left on levels with real_time_limit_secs, see time_limit.rs
+    pub time_left: Option<f32>,
+    // say(): the speech bubble over a robot, see speech.rs
+    pub speech: Option<super::speech::Speech>,
+    // Last lint pass: the code revision and (level loads, turns) it ran at, and what it found
+    pub lint: Option<(u64, (u64, usize), Vec<game_core::ast::Diagnostic>)>,
 }
 
 // Learning level configuration
@@ -259,4 +325,20 @@ pub enum EditorTab {
     Logs,
     Tasks,
     Editor,
+    Ownership, // Variable lifetimes, moves and borrows on ownership teaching levels
+    Watch,     // Variable values at each step of the last run, and pinned expressions
+    #[cfg(feature = "vcs")]
+    History,
+}
+
+impl EditorTab {
+    /// Whether the sidebar shows the code editor (and clicks there start editing)
+    pub fn shows_editor(&self) -> bool {
+        match self {
+            EditorTab::Logs | EditorTab::Ownership | EditorTab::Watch => false,
+            #[cfg(feature = "vcs")]
+            EditorTab::History => false,
+            _ => true,
+        }
+    }
 }
\ No newline at end of file
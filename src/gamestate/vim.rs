@@ -0,0 +1,522 @@
+// Vim-style modal editing for the code editor, switched on in the hotkey settings. Insert mode is
+// the normal editor; normal and visual mode read typed characters as commands: hjkl, w/b/e,
+// 0/^/$, gg/G, x, dd/yy/cc, d/y/c with a motion, p/P, u, i/a/I/A/o/O, v/V, with counts like 3j
+// or 2dd. Escape goes back to normal mode.
+
+use super::types::Game;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum VimMode {
+    #[default]
+    Normal,
+    Insert,
+    Visual,
+    VisualLine,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct VimState {
+    pub mode: VimMode,
+    pending: String,            // Count and operator typed so far, e.g. "2d"
+    register: String,           // Last yank or delete
+    register_linewise: bool,    // Whether the register holds whole lines
+    anchor: usize,              // Where visual mode started
+}
+
+impl VimState {
+    /// Shown under the editor title
+    pub fn status(&self) -> String {
+        let mode = match self.mode {
+            VimMode::Normal => "NORMAL",
+            VimMode::Insert => "INSERT",
+            VimMode::Visual => "VISUAL",
+            VimMode::VisualLine => "VISUAL LINE",
+        };
+        if self.pending.is_empty() {
+            format!("-- {} --", mode)
+        } else {
+            format!("-- {} -- {}", mode, self.pending)
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CharClass {
+    Space,
+    Word,
+    Punctuation,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Space
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+impl Game {
+    pub fn set_vim_mode(&mut self, enabled: bool) {
+        self.vim = if enabled { Some(VimState::default()) } else { None };
+    }
+
+    /// Whether typed characters are commands rather than text right now
+    pub fn vim_intercepts_typing(&self) -> bool {
+        self.vim.as_ref().is_some_and(|vim| vim.mode != VimMode::Insert)
+    }
+
+    /// Escape: leave insert or visual mode, or drop a half-typed command. Returns false when vim
+    /// had nothing to do with it.
+    pub fn vim_escape(&mut self) -> bool {
+        let Some(vim) = self.vim.as_mut() else { return false };
+        match vim.mode {
+            VimMode::Insert => {
+                vim.mode = VimMode::Normal;
+                // Like Vim, the cursor steps back onto the last inserted character
+                let (_, column) = self.current_code.line_col(self.cursor_position);
+                if column > 0 {
+                    self.cursor_position = self.current_code.prev_grapheme_boundary(self.cursor_position);
+                }
+                true
+            }
+            VimMode::Visual | VimMode::VisualLine => {
+                vim.mode = VimMode::Normal;
+                vim.pending.clear();
+                self.clear_selection();
+                true
+            }
+            VimMode::Normal => !std::mem::take(&mut vim.pending).is_empty(),
+        }
+    }
+
+    /// Run typed characters as normal or visual mode commands. Returns whether the code changed.
+    pub fn vim_handle_text(&mut self, text: &str) -> bool {
+        let mut modified = false;
+        for c in text.chars() {
+            if !self.vim_intercepts_typing() {
+                // A command like `i` switched to insert mode; the rest is text
+                modified |= self.insert_typed_text(&c.to_string());
+                continue;
+            }
+            modified |= self.vim_command_char(c);
+        }
+        modified
+    }
+
+    fn vim_command_char(&mut self, c: char) -> bool {
+        let Some(vim) = self.vim.as_mut() else { return false };
+        let pending = std::mem::take(&mut vim.pending);
+        let visual = matches!(vim.mode, VimMode::Visual | VimMode::VisualLine);
+
+        // Counts: "0" on its own is a motion
+        if c.is_ascii_digit() && (c != '0' || pending.chars().last().is_some_and(|p| p.is_ascii_digit())) {
+            vim.pending = pending + &c.to_string();
+            return false;
+        }
+        let digits: String = pending.chars().take_while(|p| p.is_ascii_digit()).collect();
+        let count_given = !digits.is_empty();
+        let mut count = digits.parse::<usize>().unwrap_or(1).max(1);
+        let mut operator = pending[digits.len()..].to_string();
+        // A count after the operator multiplies: 2d3w deletes six words
+        if let Some(split) = operator.find(|p: char| p.is_ascii_digit()) {
+            count *= operator[split..].parse::<usize>().unwrap_or(1).max(1);
+            operator.truncate(split);
+        }
+
+        let modified = match (operator.as_str(), c) {
+            ("g", 'g') => {
+                let line = if count_given { count - 1 } else { 0 };
+                self.vim_move_to(self.vim_first_non_blank(line));
+                false
+            }
+            ("d" | "y" | "c", _) if !visual => self.vim_operator(&operator, c, count),
+            ("", 'g') | ("", 'd' | 'y' | 'c') if c == 'g' || !visual => {
+                if let Some(vim) = self.vim.as_mut() {
+                    vim.pending = format!("{}{}", digits, c);
+                }
+                false
+            }
+            ("", 'd' | 'x' | 'y' | 'c') if visual => self.vim_visual_operator(c),
+            ("", 'v') => {
+                self.vim_toggle_visual(VimMode::Visual);
+                false
+            }
+            ("", 'V') => {
+                self.vim_toggle_visual(VimMode::VisualLine);
+                false
+            }
+            ("", 'G') => {
+                let line = if count_given { count - 1 } else { self.current_code.line_count().saturating_sub(1) };
+                self.vim_move_to(self.vim_first_non_blank(line));
+                false
+            }
+            ("", 'x') => {
+                let start = self.cursor_position;
+                let line = self.current_code.line_of(start);
+                let line_end = self.vim_line_end(line);
+                let mut end = start;
+                for _ in 0..count {
+                    if end < line_end {
+                        end = self.current_code.next_grapheme_boundary(end);
+                    }
+                }
+                self.vim_delete(start, end, false)
+            }
+            ("", 'D') => {
+                let line = self.current_code.line_of(self.cursor_position);
+                self.vim_delete(self.cursor_position, self.vim_line_end(line), false)
+            }
+            ("", 'C') => {
+                let line = self.current_code.line_of(self.cursor_position);
+                let modified = self.vim_delete(self.cursor_position, self.vim_line_end(line), false);
+                self.vim_set_mode(VimMode::Insert);
+                modified
+            }
+            ("", 'p') => self.vim_put(true, count),
+            ("", 'P') => self.vim_put(false, count),
+            ("", 'u') => {
+                let mut undone = false;
+                for _ in 0..count {
+                    undone |= self.undo();
+                }
+                undone
+            }
+            ("", 'i') => {
+                self.vim_set_mode(VimMode::Insert);
+                false
+            }
+            ("", 'a') => {
+                let line = self.current_code.line_of(self.cursor_position);
+                if self.cursor_position < self.vim_line_end(line) {
+                    self.cursor_position = self.current_code.next_grapheme_boundary(self.cursor_position);
+                }
+                self.vim_set_mode(VimMode::Insert);
+                false
+            }
+            ("", 'I') => {
+                let line = self.current_code.line_of(self.cursor_position);
+                self.cursor_position = self.vim_first_non_blank(line);
+                self.vim_set_mode(VimMode::Insert);
+                false
+            }
+            ("", 'A') => {
+                let line = self.current_code.line_of(self.cursor_position);
+                self.cursor_position = self.vim_line_end(line);
+                self.vim_set_mode(VimMode::Insert);
+                false
+            }
+            ("", 'o' | 'O') => {
+                self.save_undo_state();
+                let line = self.current_code.line_of(self.cursor_position);
+                let text = self.current_code.line_text(line);
+                let indent = &text[..text.len() - text.trim_start().len()];
+                if c == 'o' {
+                    let at = self.vim_line_end(line);
+                    self.current_code.insert_str(at, &format!("\n{}", indent));
+                    self.cursor_position = at + 1 + indent.len();
+                } else {
+                    let at = self.current_code.byte_at(line, 0);
+                    self.current_code.insert_str(at, &format!("{}\n", indent));
+                    self.cursor_position = at + indent.len();
+                }
+                self.vim_set_mode(VimMode::Insert);
+                true
+            }
+            ("", motion) => {
+                if let Some(target) = self.vim_motion(motion, count) {
+                    self.vim_move_to(target);
+                }
+                false
+            }
+            _ => false,
+        };
+
+        self.vim_clamp_cursor();
+        self.vim_update_visual_selection();
+        self.ensure_cursor_visible();
+        modified
+    }
+
+    fn vim_set_mode(&mut self, mode: VimMode) {
+        if let Some(vim) = self.vim.as_mut() {
+            vim.mode = mode;
+            vim.pending.clear();
+        }
+        if mode == VimMode::Insert {
+            self.clear_selection();
+        }
+    }
+
+    fn vim_toggle_visual(&mut self, mode: VimMode) {
+        let anchor = self.cursor_position;
+        if let Some(vim) = self.vim.as_mut() {
+            if vim.mode == mode {
+                vim.mode = VimMode::Normal;
+                self.clear_selection();
+            } else {
+                // Switching between v and V keeps where the selection started
+                if vim.mode == VimMode::Normal {
+                    vim.anchor = anchor;
+                }
+                vim.mode = mode;
+            }
+        }
+    }
+
+    /// Byte offset of the end of `line`, before its line break
+    fn vim_line_end(&self, line: usize) -> usize {
+        self.current_code.byte_at(line, usize::MAX)
+    }
+
+    fn vim_first_non_blank(&self, line: usize) -> usize {
+        let line = line.min(self.current_code.line_count().saturating_sub(1));
+        let text = self.current_code.line_text(line);
+        self.current_code.byte_at(line, 0) + (text.len() - text.trim_start().len())
+    }
+
+    /// Start of the next line, or the end of the text after the last line
+    fn vim_next_line_start(&self, line: usize) -> usize {
+        if line + 1 < self.current_code.line_count() {
+            self.current_code.byte_at(line + 1, 0)
+        } else {
+            self.current_code.len()
+        }
+    }
+
+    fn vim_move_to(&mut self, target: usize) {
+        self.cursor_position = target.min(self.current_code.len());
+    }
+
+    /// In normal mode the cursor sits on a character, so it can't rest past the end of a line
+    fn vim_clamp_cursor(&mut self) {
+        if self.vim.as_ref().is_some_and(|vim| vim.mode == VimMode::Normal) {
+            let line = self.current_code.line_of(self.cursor_position);
+            if self.cursor_position >= self.vim_line_end(line) && self.current_code.line_len(line) > 0 {
+                self.cursor_position = self.current_code.prev_grapheme_boundary(self.vim_line_end(line));
+            }
+        }
+    }
+
+    fn vim_update_visual_selection(&mut self) {
+        let Some(vim) = self.vim.as_ref() else { return };
+        let (start, end) = (vim.anchor.min(self.cursor_position), vim.anchor.max(self.cursor_position));
+        match vim.mode {
+            VimMode::Visual => {
+                // The character under the cursor is part of the selection
+                let end = self.current_code.next_grapheme_boundary(end).min(self.current_code.len());
+                self.selection_start = Some(start);
+                self.selection_end = Some(end);
+            }
+            VimMode::VisualLine => {
+                self.selection_start = Some(self.current_code.byte_at(self.current_code.line_of(start), 0));
+                self.selection_end = Some(self.vim_next_line_start(self.current_code.line_of(end)));
+            }
+            _ => {}
+        }
+    }
+
+    /// Where a motion from the cursor lands, or None for keys that aren't motions
+    fn vim_motion(&self, motion: char, count: usize) -> Option<usize> {
+        let code: &str = &self.current_code;
+        let mut position = self.cursor_position;
+        for _ in 0..count {
+            let (line, column) = self.current_code.line_col(position);
+            position = match motion {
+                'h' => {
+                    if column > 0 { self.current_code.prev_grapheme_boundary(position) } else { position }
+                }
+                'l' | ' ' => {
+                    if position < self.vim_line_end(line) { self.current_code.next_grapheme_boundary(position) } else { position }
+                }
+                'j' => self.current_code.byte_at((line + 1).min(self.current_code.line_count().saturating_sub(1)), column),
+                'k' => self.current_code.byte_at(line.saturating_sub(1), column),
+                '0' => self.current_code.byte_at(line, 0),
+                '^' => self.vim_first_non_blank(line),
+                '$' => self.vim_line_end(line),
+                'w' => {
+                    let mut chars = code[position..].char_indices().map(|(i, c)| (position + i, c)).peekable();
+                    let class = chars.peek().map(|(_, c)| char_class(*c));
+                    let mut next = code.len();
+                    let mut left_word = false;
+                    for (i, c) in chars {
+                        let current = char_class(c);
+                        if current != class.unwrap_or(CharClass::Space) {
+                            left_word = true;
+                        }
+                        if left_word && current != CharClass::Space {
+                            next = i;
+                            break;
+                        }
+                    }
+                    next
+                }
+                'e' => {
+                    let mut chars = code[position..].char_indices().map(|(i, c)| (position + i, c)).skip(1).peekable();
+                    while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+                    let mut end = position;
+                    if let Some((i, c)) = chars.next() {
+                        end = i;
+                        let class = char_class(c);
+                        while let Some((i, _)) = chars.next_if(|(_, c)| char_class(*c) == class) {
+                            end = i;
+                        }
+                    }
+                    end
+                }
+                'b' => {
+                    let mut chars = code[..position].char_indices().rev().peekable();
+                    while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+                    let mut start = chars.peek().map(|(i, _)| *i).unwrap_or(0);
+                    if let Some((_, c)) = chars.next() {
+                        let class = char_class(c);
+                        while let Some((i, _)) = chars.next_if(|(_, c)| char_class(*c) == class) {
+                            start = i;
+                        }
+                    }
+                    start
+                }
+                _ => return None,
+            };
+        }
+        Some(position)
+    }
+
+    /// dd/yy/cc on whole lines, or d/y/c with a motion
+    fn vim_operator(&mut self, operator: &str, key: char, count: usize) -> bool {
+        let line = self.current_code.line_of(self.cursor_position);
+        let doubled = operator.starts_with(key);
+        let linewise = doubled || matches!(key, 'j' | 'k');
+
+        let (start, end) = if doubled {
+            let last = (line + count - 1).min(self.current_code.line_count().saturating_sub(1));
+            (self.current_code.byte_at(line, 0), self.vim_next_line_start(last))
+        } else {
+            let Some(target) = self.vim_motion(key, count) else { return false };
+            if linewise {
+                let (first, last) = (line.min(self.current_code.line_of(target)), line.max(self.current_code.line_of(target)));
+                (self.current_code.byte_at(first, 0), self.vim_next_line_start(last))
+            } else if key == 'e' {
+                // e includes the character it lands on
+                let (start, end) = (self.cursor_position.min(target), self.cursor_position.max(target));
+                (start, self.current_code.next_grapheme_boundary(end).min(self.current_code.len()))
+            } else {
+                (self.cursor_position.min(target), self.cursor_position.max(target))
+            }
+        };
+
+        match operator {
+            "y" => {
+                self.vim_yank(start, end, linewise);
+                false
+            }
+            "c" if linewise => {
+                // Keep the line itself (and its indentation) to type into
+                let last = self.current_code.line_of(end.saturating_sub(1).max(start));
+                let indent_end = self.vim_first_non_blank(line);
+                let line_end = self.vim_line_end(last);
+                self.vim_yank(start, end, true);
+                self.save_undo_state();
+                self.current_code.remove_range(indent_end..line_end);
+                self.cursor_position = indent_end;
+                self.vim_set_mode(VimMode::Insert);
+                true
+            }
+            "c" => {
+                let modified = self.vim_delete(start, end, false);
+                self.vim_set_mode(VimMode::Insert);
+                modified
+            }
+            _ => self.vim_delete(start, end, linewise),
+        }
+    }
+
+    fn vim_visual_operator(&mut self, key: char) -> bool {
+        let Some((start, end)) = self.get_selection_bounds() else { return false };
+        let linewise = self.vim.as_ref().is_some_and(|vim| vim.mode == VimMode::VisualLine);
+        self.vim_set_mode(VimMode::Normal);
+        self.clear_selection();
+        match key {
+            'y' => {
+                self.vim_yank(start, end, linewise);
+                self.cursor_position = start;
+                false
+            }
+            'c' => {
+                let modified = self.vim_delete(start, end, false);
+                self.vim_set_mode(VimMode::Insert);
+                modified
+            }
+            _ => self.vim_delete(start, end, linewise),
+        }
+    }
+
+    fn vim_yank(&mut self, start: usize, end: usize, linewise: bool) {
+        let mut text = self.current_code[start..end].to_string();
+        if linewise && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        if let Some(vim) = self.vim.as_mut() {
+            vim.register = text;
+            vim.register_linewise = linewise;
+        }
+    }
+
+    /// Delete start..end into the register
+    fn vim_delete(&mut self, start: usize, end: usize, linewise: bool) -> bool {
+        if start >= end {
+            return false;
+        }
+        self.vim_yank(start, end, linewise);
+        self.save_undo_state();
+        let mut start = start;
+        // Deleting the last lines also takes the line break before them
+        if linewise && end == self.current_code.len() && start > 0 && !self.current_code[start..end].ends_with('\n') {
+            start -= 1;
+        }
+        self.current_code.remove_range(start..end);
+        self.cursor_position = start.min(self.current_code.len());
+        if linewise {
+            let line = self.current_code.line_of(self.cursor_position);
+            self.cursor_position = self.vim_first_non_blank(line);
+        }
+        true
+    }
+
+    /// p puts the register after the cursor (below the line for whole lines), P before it
+    fn vim_put(&mut self, after: bool, count: usize) -> bool {
+        let Some(vim) = self.vim.as_ref() else { return false };
+        if vim.register.is_empty() {
+            return false;
+        }
+        let text = vim.register.repeat(count);
+        let linewise = vim.register_linewise;
+        self.save_undo_state();
+
+        let line = self.current_code.line_of(self.cursor_position);
+        if linewise {
+            let at = if after { self.vim_next_line_start(line) } else { self.current_code.byte_at(line, 0) };
+            if at == self.current_code.len() && !self.current_code.ends_with('\n') {
+                // Below the last line, which has no line break to put it after
+                self.current_code.insert_str(at, &format!("\n{}", text.trim_end_matches('\n')));
+                self.cursor_position = at + 1;
+            } else {
+                self.current_code.insert_str(at, &text);
+                self.cursor_position = at;
+            }
+            let line = self.current_code.line_of(self.cursor_position);
+            self.cursor_position = self.vim_first_non_blank(line);
+        } else {
+            let at = if after && self.cursor_position < self.vim_line_end(line) {
+                self.current_code.next_grapheme_boundary(self.cursor_position)
+            } else {
+                self.cursor_position
+            };
+            self.current_code.insert_str(at, &text);
+            // The cursor ends on the last character put
+            self.cursor_position = self.current_code.prev_grapheme_boundary(at + text.len());
+        }
+        true
+    }
+}
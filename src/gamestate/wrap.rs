@@ -0,0 +1,104 @@
+// Long lines in the code editor. With word wrap on, a line wider than the text area continues on
+// the rows below it and Up/Down move by screen row; with it off, each line stays on one row and
+// the view scrolls sideways to keep the cursor in sight. Columns count graphemes like the rest of
+// the editor, and rows are the screen rows of the text area from its top.
+
+use super::types::Game;
+
+impl Game {
+    pub fn wraps_lines(&self) -> bool {
+        self.menu.settings.editor.word_wrap
+    }
+
+    fn columns(&self) -> usize {
+        self.code_columns_visible.max(1)
+    }
+
+    /// Screen rows `line` takes. A wrapped line gets one more row once it fills the last one, so
+    /// the cursor at its end has a place to go.
+    fn line_rows(&self, line: usize) -> usize {
+        if !self.wraps_lines() || line >= self.current_code.line_count() {
+            return 1;
+        }
+        self.current_code.line_len(line) / self.columns() + 1
+    }
+
+    /// The first `count` rows on screen: the line each one shows and the column it starts at.
+    /// Rows past the end of the code keep counting lines so the drawing can grey them out.
+    pub fn visible_rows(&self, count: usize) -> Vec<(usize, usize)> {
+        let columns = self.columns();
+        let mut rows = Vec::with_capacity(count);
+        let mut line = self.code_scroll_offset;
+        while rows.len() < count {
+            if self.wraps_lines() {
+                let line_rows = self.line_rows(line).min(count - rows.len());
+                rows.extend((0..line_rows).map(|row| (line, row * columns)));
+            } else {
+                rows.push((line, self.code_scroll_column));
+            }
+            line += 1;
+        }
+        rows
+    }
+
+    /// Row in `rows` and column within it where the cursor is drawn; None when it's off screen
+    pub fn cursor_cell(&self, rows: &[(usize, usize)]) -> Option<(usize, usize)> {
+        let (line, col) = self.current_code.line_col(self.cursor_position);
+        let columns = self.columns();
+        rows.iter()
+            .position(|&(row_line, first)| row_line == line && col >= first && col - first < columns)
+            .map(|row| (row, col - rows[row].1))
+    }
+
+    /// Text position at a row and column of the text area, for mouse clicks
+    pub fn position_at_cell(&self, row: usize, column: usize) -> usize {
+        let Some(&(line, first)) = self.visible_rows(row + 1).last() else { return 0 };
+        // A click right of a wrapped row's text lands on its last cell rather than the next row
+        let column = if self.wraps_lines() { column.min(self.columns() - 1) } else { column };
+        self.current_code.byte_at(line, first + column)
+    }
+
+    /// Up or Down while wrapping: one screen row, keeping the column within the row
+    pub(super) fn move_cursor_by_row(&mut self, down: bool) {
+        let columns = self.columns();
+        let (line, col) = self.current_code.line_col(self.cursor_position);
+        let (row, x) = (col / columns, col % columns);
+        let target = if down {
+            if row + 1 < self.line_rows(line) {
+                Some((line, (row + 1) * columns + x))
+            } else if line + 1 < self.current_code.line_count() {
+                Some((line + 1, x))
+            } else {
+                None
+            }
+        } else if row > 0 {
+            Some((line, (row - 1) * columns + x))
+        } else if line > 0 {
+            Some((line - 1, (self.line_rows(line - 1) - 1) * columns + x))
+        } else {
+            None
+        };
+
+        if let Some((line, col)) = target {
+            self.cursor_position = self.current_code.byte_at(line, col);
+            self.ensure_cursor_visible();
+        }
+    }
+
+    /// Finish what ensure_cursor_visible does by line: scroll down further while the cursor's
+    /// row is wrapped below the text area, or sideways to the cursor's column
+    pub(super) fn keep_cursor_cell_visible(&mut self, line: usize, col: usize, max_visible_rows: usize) {
+        let columns = self.columns();
+        if self.wraps_lines() {
+            self.code_scroll_column = 0;
+            let rows_to_cursor = |game: &Game| (game.code_scroll_offset..line).map(|l| game.line_rows(l)).sum::<usize>() + col / columns;
+            while self.code_scroll_offset < line && rows_to_cursor(self) >= max_visible_rows {
+                self.code_scroll_offset += 1;
+            }
+        } else if col < self.code_scroll_column {
+            self.code_scroll_column = col;
+        } else if col >= self.code_scroll_column + columns {
+            self.code_scroll_column = col + 1 - columns;
+        }
+    }
+}
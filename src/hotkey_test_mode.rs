@@ -172,7 +172,7 @@ impl HotkeyTestSuite {
     }
 }
 
-pub async fn run_hotkey_test_mode(enable_all_logs: bool) {
+pub async fn run_hotkey_test_mode() {
     println!("⌨️  Hotkey Test Mode Started!");
     println!("  🧪 Testing all editor hotkeys");
     println!("  📋 Press hotkeys to test their functionality");
@@ -185,10 +185,6 @@ pub async fn run_hotkey_test_mode(enable_all_logs: bool) {
     let core_levels = embedded_levels::get_embedded_level_specs();
     let mut game = Game::new(core_levels.clone(), rng);
 
-    // Enable coordinate logs if --all-logs flag is present
-    game.enable_coordinate_logs = enable_all_logs;
-    game.enable_key_press_logs = enable_all_logs;
-
     // Set up a simple level for testing
     game.level_idx = 0;
     game.load_level(0);
@@ -227,7 +223,7 @@ pub async fn run_hotkey_test_mode(enable_all_logs: bool) {
     // Ctrl+S - Save file
     // Ctrl+Shift+Enter - Run code
 }
-"#.to_string();
+"#.into();
 
     game.cursor_position = 100; // Position in the middle
 
@@ -405,18 +401,10 @@ pub async fn run_hotkey_test_mode(enable_all_logs: bool) {
             let mut code_modified = false;
 
             // Handle character input
-            while let Some(character) = get_char_pressed() {
-                if character.is_ascii() && !character.is_control() && character != ' ' {
-                    // Skip if this is a hotkey combination
-                    if !hotkey_detected {
-                        if game.delete_selection() {
-                            code_modified = true;
-                        }
-                        game.current_code.insert(game.cursor_position, character);
-                        game.cursor_position += 1;
-                        code_modified = true;
-                    }
-                }
+            let typed = crate::gamestate::cursor::read_typed_text();
+            // Skip if this is a hotkey combination
+            if !hotkey_detected && game.insert_typed_text(&typed) {
+                code_modified = true;
             }
 
             // Handle space (if not part of hotkey)
@@ -432,8 +420,9 @@ pub async fn run_hotkey_test_mode(enable_all_logs: bool) {
             // Backspace handling (if not part of hotkey)
             if is_key_pressed(KeyCode::Backspace) && !hotkey_detected {
                 if !game.delete_selection() && game.cursor_position > 0 {
-                    game.current_code.remove(game.cursor_position - 1);
-                    game.cursor_position -= 1;
+                    let previous = game.current_code.prev_grapheme_boundary(game.cursor_position);
+                    game.current_code.remove_range(previous..game.cursor_position);
+                    game.cursor_position = previous;
                     code_modified = true;
                 }
             }
@@ -1,23 +1,155 @@
 use macroquad::prelude::*;
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct KeyBinding {
-    pub key: String,
+    pub key: String,            // A whole chord like "Ctrl+K Ctrl+C" is written here with no modifiers
     pub modifiers: Vec<String>, // "ctrl", "shift", "alt"
     pub action: String,
+    #[serde(default)]
+    pub context: HotkeyContext,
 }
 
+/// Where a binding works. Global bindings work everywhere, the others only while that part of the
+/// game has the keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HotkeyContext {
+    Global,
+    #[default]
+    Editor,
+    Game,  // The grid with the editor unfocused
+    Popup, // A popup message is open
+}
+
+impl HotkeyContext {
+    pub fn label(self) -> &'static str {
+        match self {
+            HotkeyContext::Global => "everywhere",
+            HotkeyContext::Editor => "editor",
+            HotkeyContext::Game => "game",
+            HotkeyContext::Popup => "popup",
+        }
+    }
+
+    /// Whether bindings of the two contexts can be triggered by the same key press
+    fn overlaps(self, other: HotkeyContext) -> bool {
+        self == other || self == HotkeyContext::Global || other == HotkeyContext::Global
+    }
+}
+
+/// One key combination, or chord of combinations separated by spaces, and what it does
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hotkey {
+    pub combo: String,
+    pub action: EditorAction,
+    pub context: HotkeyContext,
+}
+
+impl Hotkey {
+    fn new(combo: &str, action: EditorAction, context: HotkeyContext) -> Self {
+        Self { combo: combo.to_string(), action, context }
+    }
+
+    /// Two bindings clash when the same keys do different things, or when one is the start of the
+    /// other's chord, since the shorter one fires before the chord can be finished
+    fn clashes_with(&self, other: &Hotkey) -> bool {
+        if !self.context.overlaps(other.context) {
+            return false;
+        }
+        if self.combo == other.combo {
+            return self.action != other.action;
+        }
+        is_chord_prefix(&self.combo, &other.combo) || is_chord_prefix(&other.combo, &self.combo)
+    }
+}
+
+fn is_chord_prefix(prefix: &str, chord: &str) -> bool {
+    chord.strip_prefix(prefix).is_some_and(|rest| rest.starts_with(' '))
+}
+
+/// Bindings that can't all work as configured; the player keeps one of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotkeyConflict {
+    pub combo: String,
+    pub bindings: Vec<Hotkey>,
+}
+
+/// What a key press meant to the hotkey system
+#[derive(Debug, Clone, PartialEq)]
+pub enum HotkeyInput {
+    Action(EditorAction),
+    Pending(String), // The strokes so far start a chord; waiting for the next one
+    Unbound,
+}
+
+/// A set of bindings picked on the hotkey settings page; Emacs swaps the VS Code-like defaults for
+/// C-a/C-e, C-f/C-b/C-n/C-p, C-k/C-y, C-space and friends
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum HotkeyPreset {
+    #[default]
+    Default,
+    Emacs,
+}
+
+impl HotkeyPreset {
+    pub const ALL: [HotkeyPreset; 2] = [HotkeyPreset::Default, HotkeyPreset::Emacs];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HotkeyPreset::Default => "Default",
+            HotkeyPreset::Emacs => "Emacs",
+        }
+    }
+
+    pub fn next(&self) -> HotkeyPreset {
+        let index = Self::ALL.iter().position(|preset| preset == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Bindings laid over the defaults; the defaults they clash with are dropped
+    fn overrides(&self) -> Vec<Hotkey> {
+        use EditorAction::*;
+        use HotkeyContext::Editor;
+
+        match self {
+            HotkeyPreset::Default => Vec::new(),
+            HotkeyPreset::Emacs => vec![
+                Hotkey::new("Ctrl+A", MoveCursorLineStart, Editor),
+                Hotkey::new("Ctrl+E", MoveCursorLineEnd, Editor),
+                Hotkey::new("Ctrl+F", MoveCursorRight, Editor),
+                Hotkey::new("Ctrl+B", MoveCursorLeft, Editor),
+                Hotkey::new("Ctrl+N", MoveCursorDown, Editor),
+                Hotkey::new("Ctrl+P", MoveCursorUp, Editor),
+                Hotkey::new("Alt+F", MoveCursorWordRight, Editor),
+                Hotkey::new("Alt+B", MoveCursorWordLeft, Editor),
+                Hotkey::new("Ctrl+K", KillLine, Editor),
+                Hotkey::new("Ctrl+Y", Yank, Editor),
+                Hotkey::new("Ctrl+Space", SetMark, Editor),
+                Hotkey::new("Ctrl+W", Cut, Editor),
+                Hotkey::new("Alt+W", Copy, Editor),
+                Hotkey::new("Ctrl+/", Undo, Editor),
+                Hotkey::new("Ctrl+S", Find, Editor),
+                Hotkey::new("Ctrl+X Ctrl+S", SaveFile, Editor),
+                Hotkey::new("Ctrl+X H", SelectAll, Editor),
+                Hotkey::new("Ctrl+X U", Undo, Editor),
+            ],
+        }
+    }
+}
+
+// Schema version of hotkeys_config.json
+pub const CONFIG_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HotkeyConfig {
     pub bindings: Vec<KeyBinding>,
     pub description: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum EditorAction {
     Accept,                    // Tab for autocomplete
     Undo,
@@ -25,6 +157,7 @@ pub enum EditorAction {
     Cut,
     Copy,
     Paste,
+    PasteFromHistory,          // Pick one of the last cuts and copies to paste
     SelectAll,
     Find,
     Replace,
@@ -35,6 +168,9 @@ pub enum EditorAction {
     Unindent,
     DuplicateLine,
     DeleteLine,
+    KillLine,                  // Emacs C-k: cut from the cursor to the end of the line
+    Yank,                      // Emacs C-y: paste the last cut
+    SetMark,                   // Emacs C-space: start a selection that follows the cursor
     MoveCursorUp,
     MoveCursorDown,
     MoveCursorLeft,
@@ -59,37 +195,57 @@ pub enum EditorAction {
     ToggleEditor,
 }
 
+/// Every key keycode_to_string has a name for
+const NAMED_KEYS: [KeyCode; 64] = [
+    KeyCode::Slash, KeyCode::GraveAccent, KeyCode::Tab, KeyCode::Enter, KeyCode::Escape, KeyCode::Backspace, KeyCode::Delete, KeyCode::Space,
+    KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right, KeyCode::Home, KeyCode::End,
+    KeyCode::PageUp, KeyCode::PageDown,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+];
+
 #[derive(Debug)]
 pub struct HotkeySystem {
-    bindings: HashMap<String, EditorAction>,
-    default_bindings: HashMap<String, EditorAction>,
+    bindings: Vec<Hotkey>,
+    default_bindings: Vec<Hotkey>,
     config_path: String,
+    pending_chord: Vec<String>, // Strokes of a chord typed so far
 }
 
 impl HotkeySystem {
     pub fn new() -> Self {
-        let mut default_bindings = HashMap::new();
+        use HotkeyContext::{Editor, Global};
 
         // Default VS Code-like bindings
-        default_bindings.insert("Tab".to_string(), EditorAction::Accept);
-        default_bindings.insert("Ctrl+Z".to_string(), EditorAction::Undo);
-        default_bindings.insert("Ctrl+Y".to_string(), EditorAction::Redo);
-        default_bindings.insert("Ctrl+X".to_string(), EditorAction::Cut);
-        default_bindings.insert("Ctrl+C".to_string(), EditorAction::Copy);
-        default_bindings.insert("Ctrl+V".to_string(), EditorAction::Paste);
-        default_bindings.insert("Ctrl+A".to_string(), EditorAction::SelectAll);
-        default_bindings.insert("Ctrl+F".to_string(), EditorAction::Find);
-        default_bindings.insert("Ctrl+H".to_string(), EditorAction::Replace);
-        default_bindings.insert("Ctrl+G".to_string(), EditorAction::GoToLine);
-        default_bindings.insert("Ctrl+/".to_string(), EditorAction::Comment);
-        default_bindings.insert("Tab".to_string(), EditorAction::Indent);
-        default_bindings.insert("Shift+Tab".to_string(), EditorAction::Unindent);
-        default_bindings.insert("Ctrl+D".to_string(), EditorAction::DuplicateLine);
-        default_bindings.insert("Ctrl+Shift+K".to_string(), EditorAction::DeleteLine);
-        default_bindings.insert("Ctrl+S".to_string(), EditorAction::SaveFile);
-        default_bindings.insert("Ctrl+Shift+Enter".to_string(), EditorAction::RunCode);
-        default_bindings.insert("Shift+Enter".to_string(), EditorAction::RunCode); // Add Shift+Enter as alternative
-        default_bindings.insert("Ctrl+`".to_string(), EditorAction::ToggleEditor);
+        let default_bindings = vec![
+            Hotkey::new("Tab", EditorAction::Accept, Editor), // Indents when there is no suggestion
+            Hotkey::new("Ctrl+Z", EditorAction::Undo, Editor),
+            Hotkey::new("Ctrl+Y", EditorAction::Redo, Editor),
+            Hotkey::new("Ctrl+X", EditorAction::Cut, Editor),
+            Hotkey::new("Ctrl+C", EditorAction::Copy, Editor),
+            Hotkey::new("Ctrl+V", EditorAction::Paste, Editor),
+            Hotkey::new("Ctrl+Shift+V", EditorAction::PasteFromHistory, Editor),
+            Hotkey::new("Ctrl+A", EditorAction::SelectAll, Editor),
+            Hotkey::new("Ctrl+F", EditorAction::Find, Editor),
+            Hotkey::new("Ctrl+H", EditorAction::Replace, Editor),
+            Hotkey::new("Ctrl+G", EditorAction::GoToLine, Editor),
+            Hotkey::new("Ctrl+/", EditorAction::Comment, Editor),
+            Hotkey::new("Ctrl+K Ctrl+C", EditorAction::Comment, Editor),
+            Hotkey::new("Ctrl+K Ctrl+U", EditorAction::Uncomment, Editor),
+            Hotkey::new("Shift+Tab", EditorAction::Unindent, Editor),
+            Hotkey::new("Ctrl+D", EditorAction::DuplicateLine, Editor),
+            Hotkey::new("Ctrl+Shift+K", EditorAction::DeleteLine, Editor),
+            Hotkey::new("Ctrl+S", EditorAction::SaveFile, Editor),
+            Hotkey::new("Ctrl+Shift+Enter", EditorAction::RunCode, Editor),
+            Hotkey::new("Shift+Enter", EditorAction::RunCode, Editor), // Add Shift+Enter as alternative
+            Hotkey::new("Ctrl+`", EditorAction::ToggleEditor, Global),
+        ];
 
         let config_path = "hotkeys_config.json".to_string();
         let bindings = default_bindings.clone();
@@ -98,26 +254,24 @@ impl HotkeySystem {
             bindings,
             default_bindings,
             config_path,
+            pending_chord: Vec::new(),
         }
     }
 
     pub fn load_config(&mut self) -> Result<(), String> {
-        if !Path::new(&self.config_path).exists() {
+        let Some(config) = crate::save_file::load_json::<HotkeyConfig>(Path::new(&self.config_path), CONFIG_VERSION)
+            .map_err(|e| e.to_string())? else {
             return self.save_config(); // Create default config
-        }
-
-        let content = fs::read_to_string(&self.config_path)
-            .map_err(|e| format!("Failed to read hotkey config: {}", e))?;
-
-        let config: HotkeyConfig = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse hotkey config: {}", e))?;
+        };
 
         self.bindings.clear();
+        self.pending_chord.clear();
 
+        // Duplicates are kept so conflicts() can show them instead of one silently winning
         for binding in config.bindings {
-            let key_combo = self.format_key_combination(&binding.key, &binding.modifiers);
+            let key_combo = self.normalize_combo(&self.format_key_combination(&binding.key, &binding.modifiers));
             if let Some(action) = self.parse_action(&binding.action) {
-                self.bindings.insert(key_combo, action);
+                self.bindings.push(Hotkey { combo: key_combo, action, context: binding.context });
             }
         }
 
@@ -127,12 +281,17 @@ impl HotkeySystem {
     pub fn save_config(&self) -> Result<(), String> {
         let mut bindings = Vec::new();
 
-        for (key_combo, action) in &self.bindings {
-            let (key, modifiers) = self.parse_key_combination(key_combo);
+        for hotkey in &self.bindings {
+            let (key, modifiers) = if hotkey.combo.contains(' ') {
+                (hotkey.combo.clone(), Vec::new())
+            } else {
+                self.parse_key_combination(&hotkey.combo)
+            };
             bindings.push(KeyBinding {
                 key,
                 modifiers,
-                action: self.action_to_string(action),
+                action: self.action_to_string(&hotkey.action),
+                context: hotkey.context,
             });
         }
 
@@ -141,36 +300,120 @@ impl HotkeySystem {
             description: "Custom hotkey configuration for Rust Steam Game".to_string(),
         };
 
-        let content = serde_json::to_string_pretty(&config)
-            .map_err(|e| format!("Failed to serialize hotkey config: {}", e))?;
-
-        fs::write(&self.config_path, content)
-            .map_err(|e| format!("Failed to write hotkey config: {}", e))?;
-
-        Ok(())
+        crate::save_file::save_json(Path::new(&self.config_path), CONFIG_VERSION, &config)
     }
 
+    /// The editor action bound to a single key press, ignoring chords
     pub fn get_action_for_input(&self, key: KeyCode, ctrl: bool, shift: bool, alt: bool) -> Option<EditorAction> {
         let key_combo = self.format_input_combination(key, ctrl, shift, alt);
-        self.bindings.get(&key_combo).cloned()
+        self.bindings.iter()
+            .find(|hotkey| hotkey.combo == key_combo && hotkey.context.overlaps(HotkeyContext::Editor))
+            .map(|hotkey| hotkey.action.clone())
+    }
+
+    /// Feed one key press in `context`. The first strokes of a chord are held until the chord is
+    /// finished; a stroke that doesn't continue it drops the chord and counts on its own.
+    pub fn process_input(&mut self, key: KeyCode, ctrl: bool, shift: bool, alt: bool, context: HotkeyContext) -> HotkeyInput {
+        let mut strokes = std::mem::take(&mut self.pending_chord);
+        let chord_started = !strokes.is_empty();
+        strokes.push(self.format_input_combination(key, ctrl, shift, alt));
+        let combo = strokes.join(" ");
+
+        let mut active = self.bindings.iter().filter(|hotkey| hotkey.context.overlaps(context));
+        if let Some(hotkey) = active.clone().find(|hotkey| hotkey.combo == combo) {
+            return HotkeyInput::Action(hotkey.action.clone());
+        }
+        if active.any(|hotkey| is_chord_prefix(&combo, &hotkey.combo)) {
+            self.pending_chord = strokes;
+            return HotkeyInput::Pending(combo);
+        }
+        if chord_started {
+            return self.process_input(key, ctrl, shift, alt, context);
+        }
+        HotkeyInput::Unbound
     }
 
+    /// The chord typed so far, for showing that the game waits for its next stroke
+    pub fn pending_chord(&self) -> Option<String> {
+        if self.pending_chord.is_empty() {
+            None
+        } else {
+            Some(self.pending_chord.join(" "))
+        }
+    }
+
+    /// Drop a half-typed chord, e.g. when a key the hotkey system never sees is pressed
+    pub fn cancel_chord(&mut self) {
+        self.pending_chord.clear();
+    }
+
+    /// Bind `key_combo` in the editor, replacing what it did there before
     pub fn set_binding(&mut self, key_combo: String, action: EditorAction) {
-        self.bindings.insert(key_combo, action);
+        self.set_binding_in(HotkeyContext::Editor, key_combo, action);
+    }
+
+    pub fn set_binding_in(&mut self, context: HotkeyContext, key_combo: String, action: EditorAction) {
+        let key_combo = self.normalize_combo(&key_combo);
+        self.bindings.retain(|hotkey| !(hotkey.combo == key_combo && hotkey.context == context));
+        self.bindings.push(Hotkey { combo: key_combo, action, context });
     }
 
     pub fn remove_binding(&mut self, key_combo: &str) {
-        self.bindings.remove(key_combo);
+        self.bindings.retain(|hotkey| hotkey.combo != key_combo);
     }
 
     pub fn reset_to_defaults(&mut self) {
         self.bindings = self.default_bindings.clone();
+        self.pending_chord.clear();
     }
 
-    pub fn get_all_bindings(&self) -> &HashMap<String, EditorAction> {
+    /// Replace the bindings with the defaults plus the preset's own
+    pub fn apply_preset(&mut self, preset: HotkeyPreset) {
+        self.reset_to_defaults();
+        for hotkey in preset.overrides() {
+            self.bindings.retain(|other| !other.clashes_with(&hotkey) && *other != hotkey);
+            self.bindings.push(hotkey);
+        }
+    }
+
+    pub fn get_all_bindings(&self) -> &[Hotkey] {
         &self.bindings
     }
 
+    /// Groups of bindings that get in each other's way, in binding order
+    pub fn conflicts(&self) -> Vec<HotkeyConflict> {
+        let mut conflicts: Vec<HotkeyConflict> = Vec::new();
+        for (index, hotkey) in self.bindings.iter().enumerate() {
+            if conflicts.iter().any(|conflict| conflict.bindings.contains(hotkey)) {
+                continue;
+            }
+            let clashing: Vec<Hotkey> = self.bindings.iter()
+                .enumerate()
+                .filter(|(other_index, other)| *other_index != index && hotkey.clashes_with(other))
+                .map(|(_, other)| other.clone())
+                .collect();
+            if !clashing.is_empty() {
+                let mut bindings = vec![hotkey.clone()];
+                bindings.extend(clashing);
+                conflicts.push(HotkeyConflict { combo: hotkey.combo.clone(), bindings });
+            }
+        }
+        conflicts
+    }
+
+    /// Settle a conflict by keeping `conflict.bindings[keep]` and removing the others
+    pub fn resolve_conflict(&mut self, conflict: &HotkeyConflict, keep: usize) {
+        let Some(kept) = conflict.bindings.get(keep) else { return };
+        let mut kept_once = false;
+        self.bindings.retain(|hotkey| {
+            if hotkey == kept && !kept_once {
+                kept_once = true;
+                return true;
+            }
+            !conflict.bindings.contains(hotkey)
+        });
+    }
+
     pub fn import_vscode_keybindings(&mut self, vscode_path: &str) -> Result<(), String> {
         let content = fs::read_to_string(vscode_path)
             .map_err(|e| format!("Failed to read VSCode keybindings: {}", e))?;
@@ -186,8 +429,8 @@ impl HotkeySystem {
                     binding.get("command").and_then(|c| c.as_str())
                 ) {
                     if let Some(action) = self.vscode_command_to_action(command) {
-                        let key_combo = self.vscode_key_to_combo(key);
-                        self.bindings.insert(key_combo, action);
+                        let key_combo = self.normalize_combo(&self.vscode_key_to_combo(key));
+                        self.bindings.push(Hotkey { combo: key_combo, action, context: HotkeyContext::Editor });
                     }
                 }
             }
@@ -196,43 +439,38 @@ impl HotkeySystem {
         Ok(())
     }
 
-    pub fn import_vim_config(&mut self, vim_path: &str) -> Result<(), String> {
+    /// Bring in the mappings of a .vimrc that the editor has an action for, e.g.
+    /// `nnoremap <C-s> :w<CR>` binds Ctrl+S to SaveFile. Returns how many were imported.
+    pub fn import_vim_config(&mut self, vim_path: &str) -> Result<usize, String> {
         let content = fs::read_to_string(vim_path)
             .map_err(|e| format!("Failed to read Vim config: {}", e))?;
 
-        // Basic vim key mapping parser (simplified)
+        let mut imported = 0;
         for line in content.lines() {
-            let line = line.trim();
-            if line.starts_with("map ") || line.starts_with("nmap ") {
-                // Parse basic vim mappings like "map <C-s> :w<CR>"
-                if let Some(action) = self.parse_vim_mapping(line) {
-                    // Convert vim mapping to our format (simplified)
-                    // This is a basic implementation - a full vim parser would be much more complex
-                    continue;
-                }
+            if let Some((key_combo, action)) = self.parse_vim_mapping(line.trim()) {
+                self.set_binding(key_combo, action);
+                imported += 1;
             }
         }
 
-        Ok(())
+        Ok(imported)
     }
 
-    pub fn import_emacs_config(&mut self, emacs_path: &str) -> Result<(), String> {
+    /// Bring in the `global-set-key` lines of an Emacs init file that the editor has an action
+    /// for. Returns how many were imported.
+    pub fn import_emacs_config(&mut self, emacs_path: &str) -> Result<usize, String> {
         let content = fs::read_to_string(emacs_path)
             .map_err(|e| format!("Failed to read Emacs config: {}", e))?;
 
-        // Basic emacs key binding parser (simplified)
+        let mut imported = 0;
         for line in content.lines() {
-            let line = line.trim();
-            if line.starts_with("(global-set-key") {
-                // Parse emacs key bindings like "(global-set-key (kbd \"C-s\") 'save-buffer)"
-                if let Some(action) = self.parse_emacs_binding(line) {
-                    // Convert emacs binding to our format (simplified)
-                    continue;
-                }
+            if let Some((key_combo, action)) = self.parse_emacs_binding(line.trim()) {
+                self.set_binding(key_combo, action);
+                imported += 1;
             }
         }
 
-        Ok(())
+        Ok(imported)
     }
 
     // Helper methods
@@ -264,6 +502,17 @@ impl HotkeySystem {
         (key, modifiers)
     }
 
+    /// Spell every stroke of a combo the way key presses are formatted, so "ctrl+k ctrl+c" matches
+    fn normalize_combo(&self, key_combo: &str) -> String {
+        key_combo.split_whitespace()
+            .map(|stroke| match self.parse_input_combination(stroke) {
+                Some((key, ctrl, shift, alt)) => self.format_input_combination(key, ctrl, shift, alt),
+                None => stroke.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
     fn format_input_combination(&self, key: KeyCode, ctrl: bool, shift: bool, alt: bool) -> String {
         let mut parts = Vec::new();
 
@@ -276,8 +525,29 @@ impl HotkeySystem {
         parts.join("+")
     }
 
+    /// Split "Ctrl+Shift+Left" into the key and its modifiers, the inverse of format_input_combination
+    pub fn parse_input_combination(&self, key_combo: &str) -> Option<(KeyCode, bool, bool, bool)> {
+        let (key, modifiers) = self.parse_key_combination(key_combo);
+        let key = NAMED_KEYS.iter()
+            .copied()
+            .find(|code| self.keycode_to_string(*code).eq_ignore_ascii_case(&key))?;
+
+        let (mut ctrl, mut shift, mut alt) = (false, false, false);
+        for modifier in modifiers {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => ctrl = true,
+                "shift" => shift = true,
+                "alt" => alt = true,
+                _ => return None,
+            }
+        }
+        Some((key, ctrl, shift, alt))
+    }
+
     fn keycode_to_string(&self, key: KeyCode) -> String {
         match key {
+            KeyCode::Slash => "/".to_string(),
+            KeyCode::GraveAccent => "`".to_string(),
             KeyCode::Tab => "Tab".to_string(),
             KeyCode::Enter => "Enter".to_string(),
             KeyCode::Escape => "Escape".to_string(),
@@ -352,6 +622,7 @@ impl HotkeySystem {
             "cut" => Some(EditorAction::Cut),
             "copy" => Some(EditorAction::Copy),
             "paste" => Some(EditorAction::Paste),
+            "pastefromhistory" => Some(EditorAction::PasteFromHistory),
             "selectall" => Some(EditorAction::SelectAll),
             "find" => Some(EditorAction::Find),
             "replace" => Some(EditorAction::Replace),
@@ -362,6 +633,19 @@ impl HotkeySystem {
             "unindent" => Some(EditorAction::Unindent),
             "duplicateline" => Some(EditorAction::DuplicateLine),
             "deleteline" => Some(EditorAction::DeleteLine),
+            "killline" => Some(EditorAction::KillLine),
+            "yank" => Some(EditorAction::Yank),
+            "setmark" => Some(EditorAction::SetMark),
+            "movecursorup" => Some(EditorAction::MoveCursorUp),
+            "movecursordown" => Some(EditorAction::MoveCursorDown),
+            "movecursorleft" => Some(EditorAction::MoveCursorLeft),
+            "movecursorright" => Some(EditorAction::MoveCursorRight),
+            "movecursorwordleft" => Some(EditorAction::MoveCursorWordLeft),
+            "movecursorwordright" => Some(EditorAction::MoveCursorWordRight),
+            "movecursorlinestart" => Some(EditorAction::MoveCursorLineStart),
+            "movecursorlineend" => Some(EditorAction::MoveCursorLineEnd),
+            "movecursordocstart" => Some(EditorAction::MoveCursorDocStart),
+            "movecursordocend" => Some(EditorAction::MoveCursorDocEnd),
             "runcode" => Some(EditorAction::RunCode),
             "savefile" => Some(EditorAction::SaveFile),
             "toggleeditor" => Some(EditorAction::ToggleEditor),
@@ -377,6 +661,7 @@ impl HotkeySystem {
             EditorAction::Cut => "cut".to_string(),
             EditorAction::Copy => "copy".to_string(),
             EditorAction::Paste => "paste".to_string(),
+            EditorAction::PasteFromHistory => "pastefromhistory".to_string(),
             EditorAction::SelectAll => "selectall".to_string(),
             EditorAction::Find => "find".to_string(),
             EditorAction::Replace => "replace".to_string(),
@@ -387,6 +672,19 @@ impl HotkeySystem {
             EditorAction::Unindent => "unindent".to_string(),
             EditorAction::DuplicateLine => "duplicateline".to_string(),
             EditorAction::DeleteLine => "deleteline".to_string(),
+            EditorAction::KillLine => "killline".to_string(),
+            EditorAction::Yank => "yank".to_string(),
+            EditorAction::SetMark => "setmark".to_string(),
+            EditorAction::MoveCursorUp => "movecursorup".to_string(),
+            EditorAction::MoveCursorDown => "movecursordown".to_string(),
+            EditorAction::MoveCursorLeft => "movecursorleft".to_string(),
+            EditorAction::MoveCursorRight => "movecursorright".to_string(),
+            EditorAction::MoveCursorWordLeft => "movecursorwordleft".to_string(),
+            EditorAction::MoveCursorWordRight => "movecursorwordright".to_string(),
+            EditorAction::MoveCursorLineStart => "movecursorlinestart".to_string(),
+            EditorAction::MoveCursorLineEnd => "movecursorlineend".to_string(),
+            EditorAction::MoveCursorDocStart => "movecursordocstart".to_string(),
+            EditorAction::MoveCursorDocEnd => "movecursordocend".to_string(),
             EditorAction::RunCode => "runcode".to_string(),
             EditorAction::SaveFile => "savefile".to_string(),
             EditorAction::ToggleEditor => "toggleeditor".to_string(),
@@ -424,15 +722,162 @@ impl HotkeySystem {
                   .replace("cmd", "Ctrl") // Mac cmd -> Ctrl on Windows/Linux
     }
 
-    fn parse_vim_mapping(&self, _line: &str) -> Option<EditorAction> {
-        // Simplified vim mapping parser
-        // A full implementation would be much more complex
-        None
+    /// "nnoremap <silent> <C-s> :w<CR>" -> ("Ctrl+S", SaveFile). Only mappings on keys with a
+    /// modifier or function keys count; plain keys are Vim mode's own commands.
+    fn parse_vim_mapping(&self, line: &str) -> Option<(String, EditorAction)> {
+        const MAP_COMMANDS: [&str; 12] = [
+            "map", "noremap", "nmap", "nnoremap", "imap", "inoremap",
+            "vmap", "vnoremap", "xmap", "xnoremap", "map!", "noremap!",
+        ];
+        let mut words = line.split_whitespace();
+        if !MAP_COMMANDS.contains(&words.next()?) {
+            return None;
+        }
+        let mut words = words.skip_while(|word| {
+            matches!(word.to_lowercase().as_str(), "<silent>" | "<buffer>" | "<nowait>" | "<unique>" | "<script>" | "<expr>")
+        });
+        let lhs = words.next()?;
+        let rhs = words.collect::<Vec<_>>().join(" ");
+
+        // <C-k><C-c> is a chord of two strokes
+        let mut strokes = Vec::new();
+        let mut rest = lhs;
+        while !rest.is_empty() {
+            let end = rest.find('>').filter(|_| rest.starts_with('<'))?;
+            strokes.push(self.vim_key_to_combo(&rest[1..end])?);
+            rest = &rest[end + 1..];
+        }
+        if strokes.is_empty() {
+            return None;
+        }
+
+        // Insert mode mappings usually leave insert mode first: <Esc>:w<CR>, <C-o>u
+        let mut command = rhs.trim();
+        for prefix in ["<Esc>", "<esc>", "<C-o>", "<c-o>"] {
+            command = command.strip_prefix(prefix).unwrap_or(command);
+        }
+        let command = command.trim_end_matches("<CR>").trim_end_matches("<cr>").trim();
+        let action = match command.trim_start_matches(':') {
+            "w" | "w!" | "write" | "update" | "up" => EditorAction::SaveFile,
+            "u" | "undo" => EditorAction::Undo,
+            "<C-r>" | "<c-r>" | "redo" => EditorAction::Redo,
+            "ggVG" | "gg0vG$" => EditorAction::SelectAll,
+            "dd" => EditorAction::DeleteLine,
+            "yyp" | "t." | "copy ." => EditorAction::DuplicateLine,
+            "/" => EditorAction::Find,
+            "%s/" | "%s//g" => EditorAction::Replace,
+            ">>" => EditorAction::Indent,
+            "<<" => EditorAction::Unindent,
+            "\"+y" | "\"*y" => EditorAction::Copy,
+            "\"+d" | "\"*d" | "\"+x" => EditorAction::Cut,
+            "\"+p" | "\"*p" | "\"+P" => EditorAction::Paste,
+            _ => return None,
+        };
+        Some((strokes.join(" "), action))
     }
 
-    fn parse_emacs_binding(&self, _line: &str) -> Option<EditorAction> {
-        // Simplified emacs binding parser
-        // A full implementation would be much more complex
-        None
+    /// "C-s" -> "Ctrl+S", "S-F5" -> "Shift+F5"; None for keys without a modifier
+    fn vim_key_to_combo(&self, key: &str) -> Option<String> {
+        let mut parts: Vec<&str> = key.split('-').collect();
+        let name = parts.pop()?;
+        let mut modifiers = Vec::new();
+        for modifier in parts {
+            match modifier.to_uppercase().as_str() {
+                "C" => modifiers.push("Ctrl".to_string()),
+                "S" => modifiers.push("Shift".to_string()),
+                "A" | "M" => modifiers.push("Alt".to_string()),
+                _ => return None,
+            }
+        }
+        let name = match name.to_lowercase().as_str() {
+            "cr" | "enter" | "return" => "Enter".to_string(),
+            "bs" => "Backspace".to_string(),
+            "del" => "Delete".to_string(),
+            "esc" => "Escape".to_string(),
+            "space" => "Space".to_string(),
+            "tab" => "Tab".to_string(),
+            "up" | "down" | "left" | "right" | "home" | "end" | "pageup" | "pagedown" => {
+                let mut chars = name.chars();
+                chars.next().map(|c| c.to_uppercase().collect::<String>() + chars.as_str())?
+            }
+            _ => name.to_uppercase(),
+        };
+        if modifiers.is_empty() && !(name.starts_with('F') && name.len() > 1) {
+            return None;
+        }
+        modifiers.push(name);
+        let combo = self.normalize_combo(&modifiers.join("+"));
+        self.parse_input_combination(&combo).map(|_| combo)
+    }
+
+    /// `(global-set-key (kbd "C-c r") 'save-buffer)` -> ("Ctrl+C R", SaveFile)
+    fn parse_emacs_binding(&self, line: &str) -> Option<(String, EditorAction)> {
+        let rest = line.strip_prefix("(global-set-key")
+            .or_else(|| line.strip_prefix("(define-key global-map"))?;
+        let keys_start = rest.find("(kbd \"")? + 6;
+        let keys_end = keys_start + rest[keys_start..].find('"')?;
+        let command = rest[keys_end..].split_once('\'')?.1.trim_end_matches(')').trim();
+
+        let action = match command {
+            "save-buffer" => EditorAction::SaveFile,
+            "undo" | "undo-only" => EditorAction::Undo,
+            "undo-redo" | "redo" => EditorAction::Redo,
+            "kill-region" => EditorAction::Cut,
+            "kill-ring-save" | "copy-region-as-kill" => EditorAction::Copy,
+            "yank" => EditorAction::Yank,
+            "kill-line" => EditorAction::KillLine,
+            "kill-whole-line" => EditorAction::DeleteLine,
+            "set-mark-command" => EditorAction::SetMark,
+            "mark-whole-buffer" => EditorAction::SelectAll,
+            "isearch-forward" => EditorAction::Find,
+            "query-replace" => EditorAction::Replace,
+            "goto-line" => EditorAction::GoToLine,
+            "comment-line" | "comment-dwim" | "comment-region" => EditorAction::Comment,
+            "uncomment-region" => EditorAction::Uncomment,
+            "duplicate-line" | "duplicate-dwim" => EditorAction::DuplicateLine,
+            "move-beginning-of-line" | "beginning-of-line" => EditorAction::MoveCursorLineStart,
+            "move-end-of-line" | "end-of-line" => EditorAction::MoveCursorLineEnd,
+            "forward-char" => EditorAction::MoveCursorRight,
+            "backward-char" => EditorAction::MoveCursorLeft,
+            "next-line" => EditorAction::MoveCursorDown,
+            "previous-line" => EditorAction::MoveCursorUp,
+            "forward-word" => EditorAction::MoveCursorWordRight,
+            "backward-word" => EditorAction::MoveCursorWordLeft,
+            "beginning-of-buffer" => EditorAction::MoveCursorDocStart,
+            "end-of-buffer" => EditorAction::MoveCursorDocEnd,
+            _ => return None,
+        };
+
+        let strokes = rest[keys_start..keys_end].split_whitespace()
+            .map(|stroke| self.emacs_key_to_combo(stroke))
+            .collect::<Option<Vec<_>>>()?;
+        Some((strokes.join(" "), action))
+    }
+
+    /// "C-x" -> "Ctrl+X", "M-<f5>" -> "Alt+F5", "C-SPC" -> "Ctrl+Space"
+    fn emacs_key_to_combo(&self, stroke: &str) -> Option<String> {
+        let mut parts = Vec::new();
+        let mut key = stroke;
+        while let Some((modifier, rest)) = key.split_once('-').filter(|(modifier, rest)| modifier.len() == 1 && !rest.is_empty()) {
+            parts.push(match modifier {
+                "C" => "Ctrl",
+                "S" => "Shift",
+                "M" | "A" => "Alt",
+                _ => return None,
+            });
+            key = rest;
+        }
+        let key = match key.trim_start_matches('<').trim_end_matches('>').to_lowercase().as_str() {
+            "spc" => "Space".to_string(),
+            "ret" | "return" => "Enter".to_string(),
+            "tab" => "Tab".to_string(),
+            "del" | "backspace" => "Backspace".to_string(),
+            "delete" => "Delete".to_string(),
+            "esc" => "Escape".to_string(),
+            other => other.to_uppercase(),
+        };
+        parts.push(&key);
+        let combo = self.normalize_combo(&parts.join("+"));
+        self.parse_input_combination(&combo).map(|_| combo)
     }
 }
\ No newline at end of file
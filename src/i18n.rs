@@ -0,0 +1,119 @@
+// Translations for UI text: menus, popups, task messages and error strings.
+//
+// Each language has a key/value catalog in locales/<code>.lang, compiled into the binary so the
+// WASM build has them too. Lines are `key = value`; `#` starts a comment, `\n` in a value is a
+// line break and `{name}` is filled in by tr_with. Keys missing from a catalog fall back to
+// English, and keys missing from English show up as the key itself so they're easy to spot.
+//
+// Level texts are translated in the level YAML instead (message_i18n, task_message_i18n).
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::English, Language::Spanish, Language::German];
+
+    /// Code used for catalog files and the *_i18n maps in level YAML
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+            Language::German => "de",
+        }
+    }
+
+    /// Name of the language in that language, for the picker
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+            Language::German => "Deutsch",
+        }
+    }
+
+    pub fn next(&self) -> Language {
+        let index = Language::ALL.iter().position(|l| l == self).unwrap_or(0);
+        Language::ALL[(index + 1) % Language::ALL.len()]
+    }
+
+    fn catalog_source(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en.lang"),
+            Language::Spanish => include_str!("../locales/es.lang"),
+            Language::German => include_str!("../locales/de.lang"),
+        }
+    }
+}
+
+static CATALOGS: Lazy<HashMap<Language, HashMap<String, String>>> = Lazy::new(|| {
+    Language::ALL.iter().map(|language| (*language, parse_catalog(language.catalog_source()))).collect()
+});
+
+static CURRENT_LANGUAGE: OnceLock<Mutex<Language>> = OnceLock::new();
+
+pub fn set_language(language: Language) {
+    let mutex = CURRENT_LANGUAGE.get_or_init(|| Mutex::new(Language::default()));
+    if let Ok(mut value) = mutex.lock() {
+        *value = language;
+    }
+}
+
+pub fn current_language() -> Language {
+    let mutex = CURRENT_LANGUAGE.get_or_init(|| Mutex::new(Language::default()));
+    mutex.lock().map(|value| *value).unwrap_or_default()
+}
+
+fn parse_catalog(source: &str) -> HashMap<String, String> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().replace("\\n", "\n")))
+        .collect()
+}
+
+/// Text for `key` in the current language
+pub fn tr(key: &str) -> String {
+    let lookup = |language: Language| CATALOGS.get(&language).and_then(|catalog| catalog.get(key)).cloned();
+    lookup(current_language())
+        .or_else(|| lookup(Language::English))
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Text for `key` with each `{name}` replaced by its value from `args`
+pub fn tr_with(key: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(tr(key), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_catalog_key_exists_in_english() {
+        let english = parse_catalog(Language::English.catalog_source());
+        for language in Language::ALL {
+            for key in parse_catalog(language.catalog_source()).keys() {
+                assert!(english.contains_key(key), "{} has key {} that English doesn't", language.code(), key);
+            }
+        }
+    }
+
+    #[test]
+    fn parses_escapes_and_fills_placeholders() {
+        let catalog = parse_catalog("# comment\ngreeting = Hello,\\n{name}!\n");
+        assert_eq!(catalog["greeting"], "Hello,\n{name}!");
+        assert_eq!(tr("no.such.key"), "no.such.key");
+    }
+}
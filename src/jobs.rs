@@ -0,0 +1,186 @@
+// Background jobs: slow work the main loop hands to a small pool of worker threads instead of doing
+// it between frames, so a slow disk or a cold `cargo add` never stalls rendering. Jobs go in over
+// one channel and come back as JobResults over another; the main loop applies the results once a
+// frame and the editor shows what's still running.
+//
+// Cargo check isn't a job: rust_checker::BackgroundChecker has its own worker, which kills a
+// check that newer code has made stale, where jobs here always run to completion.
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+
+// Enough that a long `cargo add` doesn't hold up a reload behind it
+const WORKERS: usize = 2;
+
+// Crates the game itself depends on, which robot code may use without anything being added
+const KNOWN_CRATES: [&str; 13] = [
+    "std", "core", "alloc", "crate", "self", "super",
+    "macroquad", "serde", "serde_json", "serde_yaml", "rand", "notify", "crossbeam_channel",
+];
+
+#[derive(Clone, Debug)]
+pub enum Job {
+    ReloadCode { path: String, saves: u64 }, // Read the robot code after it changed on disk; `saves` is the editor's save count then
+    AddCrates { path: String },              // `cargo add` the crates the robot code uses that Cargo.toml doesn't have
+}
+
+impl Job {
+    /// What the editor shows while the job runs
+    pub fn label(&self) -> String {
+        match self {
+            Job::ReloadCode { path, .. } => format!("reloading {}", file_name(path)),
+            Job::AddCrates { .. } => "adding crates".to_string(),
+        }
+    }
+
+    fn run(self) -> JobResult {
+        match self {
+            Job::ReloadCode { path, saves } => {
+                let code = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e));
+                JobResult::CodeLoaded { path, saves, code }
+            }
+            Job::AddCrates { path } => JobResult::CratesAdded(add_missing_crates(&path)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum JobResult {
+    CodeLoaded { path: String, saves: u64, code: Result<String, String> },
+    CratesAdded(Option<String>), // What `cargo add` did, or None when nothing was missing
+}
+
+/// Worker threads and the jobs they're running
+#[derive(Debug)]
+pub struct JobQueue {
+    jobs: Sender<(u64, Job)>,
+    results: Receiver<(u64, JobResult)>,
+    running: Vec<(u64, String)>, // Id and label, oldest first
+    next_id: u64,
+}
+
+impl JobQueue {
+    pub fn new() -> Result<Self, String> {
+        let (jobs, worker_jobs) = unbounded::<(u64, Job)>();
+        let (worker_results, results) = unbounded();
+        for i in 0..WORKERS {
+            let worker_jobs = worker_jobs.clone();
+            let worker_results = worker_results.clone();
+            thread::Builder::new()
+                .name(format!("job-worker-{}", i))
+                .spawn(move || {
+                    // Ends when the game drops the queue
+                    for (id, job) in worker_jobs {
+                        log::debug!(target: "jobs", "Running job {}: {}", id, job.label());
+                        if worker_results.send((id, job.run())).is_err() {
+                            return;
+                        }
+                    }
+                })
+                .map_err(|e| format!("Failed to start job worker: {}", e))?;
+        }
+
+        Ok(Self { jobs, results, running: Vec::new(), next_id: 0 })
+    }
+
+    pub fn submit(&mut self, job: Job) {
+        self.next_id += 1;
+        let label = job.label();
+        if self.jobs.send((self.next_id, job)).is_ok() {
+            self.running.push((self.next_id, label));
+        }
+    }
+
+    /// Results of the jobs that finished since the last call
+    pub fn poll(&mut self) -> Vec<JobResult> {
+        let finished: Vec<(u64, JobResult)> = self.results.try_iter().collect();
+        self.running.retain(|(id, _)| finished.iter().all(|(done, _)| done != id));
+        finished.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Label of the oldest job still running, and how many there are
+    pub fn status(&self) -> Option<(&str, usize)> {
+        self.running.first().map(|(_, label)| (label.as_str(), self.running.len()))
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    Path::new(path).file_name().and_then(|name| name.to_str()).unwrap_or(path)
+}
+
+/// `cargo add` every crate the robot code imports that the Cargo.toml next to the game lacks
+fn add_missing_crates(robot_code_path: &str) -> Option<String> {
+    // Only a source checkout has a manifest to add to
+    if !Path::new("Cargo.toml").exists() {
+        return None;
+    }
+    let code = fs::read_to_string(robot_code_path).ok()?;
+    let deps = dependencies_in("Cargo.toml");
+    let missing: Vec<String> = crates_used_by(&code).into_iter().filter(|name| !deps.contains(name)).collect();
+    if missing.is_empty() {
+        return None;
+    }
+    if !cargo_add_available() {
+        return Some("cargo add not found. Install it with: `cargo install cargo-edit`".to_string());
+    }
+
+    let mut added = Vec::new();
+    let mut failed = Vec::new();
+    for name in missing {
+        match Command::new("cargo").args(["add", &name, "--quiet"]).output() {
+            Ok(output) if output.status.success() => added.push(name),
+            Ok(output) => failed.push(format!("{} ({})", name, String::from_utf8_lossy(&output.stderr).trim())),
+            Err(e) => failed.push(format!("{} ({})", name, e)),
+        }
+    }
+    if failed.is_empty() {
+        Some(format!("Added crates: {}", added.join(", ")))
+    } else {
+        Some(format!("Added crates: {}. Failed: {}", added.join(", "), failed.join(", ")))
+    }
+}
+
+/// Crates named by the `use` and `extern crate` lines of `code`, other than the known ones
+fn crates_used_by(code: &str) -> HashSet<String> {
+    let mut crates = HashSet::new();
+    for line in code.lines().map(str::trim) {
+        let name = if let Some(rest) = line.strip_prefix("use ") {
+            rest.split([':', ' ', ',', '{', ';']).next().unwrap_or("")
+        } else if let Some(rest) = line.strip_prefix("extern crate ") {
+            rest.split([' ', ';']).next().unwrap_or("")
+        } else {
+            continue;
+        };
+        if !name.is_empty() && !KNOWN_CRATES.contains(&name) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            crates.insert(name.to_string());
+        }
+    }
+    crates
+}
+
+/// Names in the [dependencies] table of a Cargo.toml
+fn dependencies_in(cargo_toml_path: &str) -> HashSet<String> {
+    let Ok(toml) = fs::read_to_string(cargo_toml_path) else { return HashSet::new() };
+    let mut deps = HashSet::new();
+    let mut in_deps = false;
+    for line in toml.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_deps = line == "[dependencies]";
+        } else if in_deps && !line.starts_with('#') && let Some((key, _)) = line.split_once('=') {
+            deps.insert(key.trim().to_string());
+        }
+    }
+    deps
+}
+
+fn cargo_add_available() -> bool {
+    Command::new("cargo")
+        .args(["add", "--help"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
@@ -0,0 +1,137 @@
+// Daily Challenge results. Every day (UTC) gets its own generated level from a seed derived from
+// the date, a few attempts at it, and a score of turns taken plus code length, lower is better.
+// Results go into daily_leaderboard.json; when daily_challenge_url is set in game_settings.json
+// each result is also POSTed there as JSON from a background thread.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Code runs allowed per Daily Challenge
+pub const MAX_DAILY_ATTEMPTS: u32 = 3;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub day: u64, // Days since 1970-01-01 (UTC)
+    pub date: String,
+    pub score: usize,
+    pub turns: usize,
+    pub code_length: usize, // Non-whitespace characters of the solution
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DailyLeaderboard {
+    #[serde(default)]
+    pub results: Vec<DailyResult>,
+    #[serde(default)]
+    pub attempts_day: u64, // Day the attempt count below belongs to
+    #[serde(default)]
+    pub attempts_used: u32,
+}
+
+impl DailyLeaderboard {
+    const SAVE_FILE: &'static str = "daily_leaderboard.json";
+
+    pub fn load_or_default() -> Self {
+        if !Path::new(Self::SAVE_FILE).exists() {
+            return Self::default();
+        }
+        fs::read_to_string(Self::SAVE_FILE)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(Self::SAVE_FILE, json)?;
+        Ok(())
+    }
+
+    pub fn attempts_left(&self, day: u64) -> u32 {
+        if self.attempts_day == day {
+            MAX_DAILY_ATTEMPTS.saturating_sub(self.attempts_used)
+        } else {
+            MAX_DAILY_ATTEMPTS
+        }
+    }
+
+    /// Count an attempt at `day`'s challenge; false once they are all used
+    pub fn use_attempt(&mut self, day: u64) -> bool {
+        if self.attempts_left(day) == 0 {
+            return false;
+        }
+        if self.attempts_day != day {
+            self.attempts_day = day;
+            self.attempts_used = 0;
+        }
+        self.attempts_used += 1;
+        true
+    }
+
+    /// `day`'s results, best score first
+    pub fn results_for(&self, day: u64) -> Vec<&DailyResult> {
+        let mut results: Vec<_> = self.results.iter().filter(|r| r.day == day).collect();
+        results.sort_by_key(|r| r.score);
+        results
+    }
+}
+
+pub fn today() -> u64 {
+    (macroquad::miniquad::date::now() / 86_400.0) as u64
+}
+
+/// Same seed for everyone playing on `day`
+pub fn seed_for_day(day: u64) -> u64 {
+    day.wrapping_add(0x5EED).wrapping_mul(0x9E37_79B9_7F4A_7C15).rotate_left(29)
+}
+
+/// YYYY-MM-DD for a day number (proleptic Gregorian calendar)
+pub fn date_label(day: u64) -> String {
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// Result for finishing `day`'s challenge in `turns` turns with `code`
+pub fn score(day: u64, turns: usize, code: &str) -> DailyResult {
+    let code_length = code.chars().filter(|c| !c.is_whitespace()).count();
+    DailyResult {
+        day,
+        date: date_label(day),
+        score: turns + code_length,
+        turns,
+        code_length,
+    }
+}
+
+/// Send a result to the configured endpoint, if any; failures are only logged
+#[cfg(not(target_arch = "wasm32"))]
+pub fn sync_result(url: &str, result: &DailyResult) {
+    let url = url.trim().to_string();
+    if url.is_empty() {
+        return;
+    }
+    let Ok(body) = serde_json::to_string(result) else { return };
+    std::thread::spawn(move || {
+        let response = ureq::post(&url)
+            .timeout(std::time::Duration::from_secs(15))
+            .set("Content-Type", "application/json")
+            .send_string(&body);
+        if let Err(e) = response {
+            log::warn!("Couldn't send Daily Challenge result to {}: {}", url, e);
+        }
+    });
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn sync_result(_url: &str, _result: &DailyResult) {
+    // No background threads or HTTP client in the web version; results stay local
+}
@@ -1406,4 +1406,30 @@ pub fn get_task_count_for_level(level_name: &str) -> usize {
         .iter()
         .filter(|s| s.level_name == level_name)
         .count()
-}
\ No newline at end of file
+}
+
+// Reference solution for a whole level: the solution to its last task. Game levels are named a
+// little differently from the solutions ("Level 1 - Hello Rust! (Task System)"), so levels are
+// matched on their number.
+pub fn get_reference_solution_for_level(level_name: &str) -> Option<TaskSolution> {
+    let number = level_number(level_name)?;
+    get_all_task_solutions()
+        .into_iter()
+        .filter(|s| level_number(s.level_name) == Some(number))
+        .max_by_key(|s| s.task_number)
+}
+
+// Reference solution for one task of a level, falling back to the whole level's solution
+pub fn get_reference_solution_for_task(level_name: &str, task_number: usize) -> Option<TaskSolution> {
+    let number = level_number(level_name)?;
+    get_all_task_solutions()
+        .into_iter()
+        .find(|s| level_number(s.level_name) == Some(number) && s.task_number == task_number)
+        .or_else(|| get_reference_solution_for_level(level_name))
+}
+
+fn level_number(level_name: &str) -> Option<u32> {
+    let rest = level_name.strip_prefix("Level ")?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
@@ -16,6 +16,7 @@ use crate::{
 };
 
 pub struct TaskTestResult {
+    pub level_index: usize,
     pub level_name: String,
     pub task_number: usize,
     pub task_description: String,
@@ -66,12 +67,14 @@ impl LearningTaskTestRunner {
 
         // Skip menu and go directly to first learning level
         game.menu.state = MenuState::InGame;
+        // Automated runs don't need to watch each robot step
+        game.menu.settings.execution_speed = crate::menu::ExecutionSpeed::Instant;
         game.level_idx = 0;
         game.load_level(0);
         game.code_editor_active = true;
 
         // Clear initial code
-        game.current_code = String::new();
+        game.current_code = Default::default();
         game.cursor_position = 0;
 
         let all_level_configs = automated_level_testing::get_all_level_tests();
@@ -107,6 +110,8 @@ impl LearningTaskTestRunner {
 
         // Skip menu and go directly to the specified starting level
         game.menu.state = MenuState::InGame;
+        // Automated runs don't need to watch each robot step
+        game.menu.settings.execution_speed = crate::menu::ExecutionSpeed::Instant;
         game.level_idx = start_level;
 
         // Safely load the level if it exists
@@ -122,7 +127,7 @@ impl LearningTaskTestRunner {
         game.code_editor_active = true;
 
         // Clear initial code
-        game.current_code = String::new();
+        game.current_code = Default::default();
         game.cursor_position = 0;
 
         let all_level_configs = automated_level_testing::get_all_level_tests();
@@ -171,8 +176,10 @@ impl LearningTaskTestRunner {
                 }
             },
             TestState::WaitingForCompletion => {
-                if self.check_for_completion() || self.state_timer >= 3.0 {
+                if self.check_for_completion() {
                     self.complete_task();
+                } else if self.state_timer >= 3.0 {
+                    self.fail_task();
                 }
             },
             TestState::TaskComplete => {
@@ -218,13 +225,14 @@ impl LearningTaskTestRunner {
                     self.input_chars = self.current_solution.chars().collect();
 
                 // Clear the editor
-                self.game.current_code = String::new();
+                self.game.current_code = Default::default();
                 self.game.cursor_position = 0;
                 self.game.code_editor_active = true;
 
                 // Clear outputs from previous tasks
                 self.game.println_outputs.clear();
                 self.game.error_outputs.clear();
+                self.game.log_records.clear();
                 self.game.execution_result.clear();
 
                 self.state = TestState::InputtingSolution;
@@ -331,6 +339,19 @@ impl LearningTaskTestRunner {
         self.total_tasks_tested += 1;
     }
 
+    fn fail_task(&mut self) {
+        let error = if self.game.error_outputs.is_empty() {
+            format!("Completion indicators not found after execution: {}", self.game.execution_result)
+        } else {
+            self.game.error_outputs.join("\n")
+        };
+        self.record_test_failure(error);
+
+        self.state = TestState::TaskComplete;
+        self.state_timer = 0.0;
+        self.total_tasks_tested += 1;
+    }
+
     fn record_test_success(&mut self) {
         let duration = self.task_start_time.elapsed();
         let level_name = if self.current_level < self.game.levels.len() {
@@ -349,6 +370,7 @@ impl LearningTaskTestRunner {
         };
 
         self.test_results.push(TaskTestResult {
+            level_index: self.current_level,
             level_name: level_name.clone(),
             task_number: self.current_task,
             task_description: task_desc.clone(),
@@ -379,6 +401,7 @@ impl LearningTaskTestRunner {
         };
 
         self.test_results.push(TaskTestResult {
+            level_index: self.current_level,
             level_name: level_name.clone(),
             task_number: self.current_task,
             task_description: task_desc.clone(),
@@ -433,6 +456,7 @@ impl LearningTaskTestRunner {
 
             self.game.println_outputs.clear();
             self.game.error_outputs.clear();
+            self.game.log_records.clear();
 
             // Reset task completion state for new level
             self.game.tutorial_state.task_completed = [false; 5];
@@ -596,27 +620,175 @@ impl LearningTaskTestRunner {
     }
 }
 
+/// Machine-readable report formats for CI systems
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Junit,
+    Json,
+    Tap,
+}
+
+impl ReportFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "junit" | "xml" => Some(ReportFormat::Junit),
+            "json" => Some(ReportFormat::Json),
+            "tap" => Some(ReportFormat::Tap),
+            _ => None,
+        }
+    }
+}
+
+/// Where and how to write the test report (stdout when `out_path` is None)
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    pub format: ReportFormat,
+    pub out_path: Option<String>,
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Group results by level, keeping the order levels were tested in
+fn results_by_level(results: &[TaskTestResult]) -> Vec<(usize, &str, Vec<&TaskTestResult>)> {
+    let mut groups: Vec<(usize, &str, Vec<&TaskTestResult>)> = Vec::new();
+    for result in results {
+        match groups.iter_mut().find(|(idx, _, _)| *idx == result.level_index) {
+            Some((_, _, tasks)) => tasks.push(result),
+            None => groups.push((result.level_index, result.level_name.as_str(), vec![result])),
+        }
+    }
+    groups
+}
+
+pub fn render_report(results: &[TaskTestResult], format: ReportFormat, total_time: Duration) -> String {
+    match format {
+        ReportFormat::Junit => {
+            let failures = results.iter().filter(|r| !r.success).count();
+            let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+            xml.push_str(&format!(
+                "<testsuites name=\"learning-levels\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+                results.len(), failures, total_time.as_secs_f64()
+            ));
+
+            for (_, level_name, tasks) in results_by_level(results) {
+                let level_failures = tasks.iter().filter(|r| !r.success).count();
+                let level_time: f64 = tasks.iter().map(|r| r.time_taken.as_secs_f64()).sum();
+                xml.push_str(&format!(
+                    "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+                    xml_escape(level_name), tasks.len(), level_failures, level_time
+                ));
+
+                for task in tasks {
+                    xml.push_str(&format!(
+                        "    <testcase classname=\"{}\" name=\"Task {}: {}\" time=\"{:.3}\"",
+                        xml_escape(level_name), task.task_number, xml_escape(&task.task_description),
+                        task.time_taken.as_secs_f64()
+                    ));
+                    if task.success {
+                        xml.push_str("/>\n");
+                    } else {
+                        let message = task.error_message.as_deref().unwrap_or("Task failed");
+                        xml.push_str(">\n");
+                        xml.push_str(&format!(
+                            "      <failure message=\"{}\">{}</failure>\n",
+                            xml_escape(message.lines().next().unwrap_or("")), xml_escape(message)
+                        ));
+                        xml.push_str("    </testcase>\n");
+                    }
+                }
+                xml.push_str("  </testsuite>\n");
+            }
+            xml.push_str("</testsuites>\n");
+            xml
+        }
+        ReportFormat::Json => {
+            let levels: Vec<serde_json::Value> = results_by_level(results)
+                .into_iter()
+                .map(|(level_index, level_name, tasks)| {
+                    let tasks: Vec<serde_json::Value> = tasks.iter().map(|task| serde_json::json!({
+                        "task": task.task_number,
+                        "name": task.task_description,
+                        "passed": task.success,
+                        "duration_ms": task.time_taken.as_millis() as u64,
+                        "error": task.error_message,
+                    })).collect();
+                    serde_json::json!({
+                        "level_index": level_index,
+                        "level": level_name,
+                        "tasks": tasks,
+                    })
+                })
+                .collect();
+
+            let passed = results.iter().filter(|r| r.success).count();
+            let report = serde_json::json!({
+                "total": results.len(),
+                "passed": passed,
+                "failed": results.len() - passed,
+                "duration_ms": total_time.as_millis() as u64,
+                "levels": levels,
+            });
+            serde_json::to_string_pretty(&report).unwrap_or_else(|_| "{}".to_string())
+        }
+        ReportFormat::Tap => {
+            let mut tap = format!("TAP version 13\n1..{}\n", results.len());
+            for (i, result) in results.iter().enumerate() {
+                let status = if result.success { "ok" } else { "not ok" };
+                tap.push_str(&format!(
+                    "{} {} - {} - Task {}: {}\n",
+                    status, i + 1, result.level_name, result.task_number, result.task_description
+                ));
+                tap.push_str("  ---\n");
+                tap.push_str(&format!("  duration_ms: {}\n", result.time_taken.as_millis()));
+                if let Some(error) = &result.error_message {
+                    tap.push_str(&format!("  message: {:?}\n", error));
+                }
+                tap.push_str("  ...\n");
+            }
+            tap
+        }
+    }
+}
+
+fn write_report(test_runner: &LearningTaskTestRunner, options: &ReportOptions) {
+    let report = render_report(test_runner.get_results(), options.format, test_runner.test_start_time.elapsed());
+
+    match &options.out_path {
+        Some(path) => match std::fs::write(path, report) {
+            Ok(()) => info!("Wrote {:?} report to {}", options.format, path),
+            Err(e) => error!("Failed to write report to {}: {}", path, e),
+        },
+        None => println!("{}", report),
+    }
+}
+
 /// Run the automated learning level task tests
-pub async fn run_learning_level_tests() {
+pub async fn run_learning_level_tests(report: Option<ReportOptions>) {
     info!("Starting automated learning level task tests");
 
-    let mut test_runner = LearningTaskTestRunner::new();
+    let test_runner = LearningTaskTestRunner::new();
 
     // Main test loop
-    run_test_loop(test_runner).await;
+    run_test_loop(test_runner, report).await;
 }
 
 /// Run the automated learning level task tests with options
-pub async fn run_learning_level_tests_with_options(start_level: usize, max_levels: usize) {
+pub async fn run_learning_level_tests_with_options(start_level: usize, max_levels: usize, report: Option<ReportOptions>) {
     info!("Starting automated learning level task tests (start: {}, max: {})", start_level, max_levels);
 
-    let mut test_runner = LearningTaskTestRunner::new_with_options(start_level, max_levels);
+    let test_runner = LearningTaskTestRunner::new_with_options(start_level, max_levels);
 
     // Main test loop
-    run_test_loop(test_runner).await;
+    run_test_loop(test_runner, report).await;
 }
 
-async fn run_test_loop(mut test_runner: LearningTaskTestRunner) {
+async fn run_test_loop(mut test_runner: LearningTaskTestRunner, report: Option<ReportOptions>) {
 
     // Main test loop
     while !test_runner.is_complete() {
@@ -638,6 +810,15 @@ async fn run_test_loop(mut test_runner: LearningTaskTestRunner) {
     // Print test summary
     test_runner.print_summary();
 
+    if let Some(options) = &report {
+        write_report(&test_runner, options);
+        // CI runs can't press SPACE, so don't wait on the review screen
+        if test_runner.get_results().iter().any(|r| !r.success) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Keep window open for review
     info!("Tests complete. Press SPACE to exit or ESC to quit immediately.");
     loop {
@@ -650,4 +831,85 @@ async fn run_test_loop(mut test_runner: LearningTaskTestRunner) {
         }
         next_frame().await;
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(level_index: usize, level_name: &str, task_number: usize, error: Option<&str>) -> TaskTestResult {
+        TaskTestResult {
+            level_index,
+            level_name: level_name.to_string(),
+            task_number,
+            task_description: format!("Task <{}>", task_number),
+            success: error.is_none(),
+            error_message: error.map(str::to_string),
+            time_taken: Duration::from_millis(250),
+        }
+    }
+
+    fn waiting_runner() -> LearningTaskTestRunner {
+        let mut runner = LearningTaskTestRunner::new();
+        runner.current_level_config = automated_level_testing::get_level_tests(0);
+        runner.state = TestState::WaitingForCompletion;
+        runner.state_timer = 0.0;
+        runner
+    }
+
+    #[test]
+    fn tasks_without_their_completion_indicators_fail_after_three_seconds() {
+        let mut runner = waiting_runner();
+        runner.game.error_outputs.push("error[E0425]: cannot find value `x`".to_string());
+
+        futures_lite::future::block_on(runner.update(2.9));
+        assert!(matches!(runner.state, TestState::WaitingForCompletion));
+        assert!(runner.get_results().is_empty());
+
+        futures_lite::future::block_on(runner.update(0.2));
+        assert!(matches!(runner.state, TestState::TaskComplete));
+        assert_eq!(runner.total_tasks_tested, 1);
+        let failure = &runner.get_results()[0];
+        assert!(!failure.success);
+        assert_eq!(failure.error_message.as_deref(), Some("error[E0425]: cannot find value `x`"));
+
+        let mut runner = waiting_runner();
+        runner.game.execution_result = "Program finished".to_string();
+        futures_lite::future::block_on(runner.update(3.0));
+        assert_eq!(
+            runner.get_results()[0].error_message.as_deref(),
+            Some("Completion indicators not found after execution: Program finished")
+        );
+    }
+
+    #[test]
+    fn reports_group_tasks_by_level_and_escape_failures() {
+        let results = [
+            result(0, "Hello & Welcome", 1, None),
+            result(0, "Hello & Welcome", 2, Some("expected \"hi\"\nfound <nothing>")),
+            result(1, "Loops", 1, None),
+        ];
+        let total_time = Duration::from_millis(1500);
+
+        let junit = render_report(&results, ReportFormat::Junit, total_time);
+        assert!(junit.contains("<testsuites name=\"learning-levels\" tests=\"3\" failures=\"1\" time=\"1.500\">"));
+        assert!(junit.contains("<testsuite name=\"Hello &amp; Welcome\" tests=\"2\" failures=\"1\" time=\"0.500\">"));
+        assert!(junit.contains("<testsuite name=\"Loops\" tests=\"1\" failures=\"0\" time=\"0.250\">"));
+        assert!(junit.contains("name=\"Task 1: Task &lt;1&gt;\" time=\"0.250\"/>"));
+        assert!(junit.contains("<failure message=\"expected &quot;hi&quot;\">expected &quot;hi&quot;\nfound &lt;nothing&gt;</failure>"));
+
+        let json: serde_json::Value = serde_json::from_str(&render_report(&results, ReportFormat::Json, total_time)).unwrap();
+        assert_eq!((json["total"].as_u64(), json["passed"].as_u64(), json["failed"].as_u64()), (Some(3), Some(2), Some(1)));
+        assert_eq!(json["duration_ms"], 1500);
+        assert_eq!(json["levels"].as_array().map(Vec::len), Some(2));
+        assert_eq!(json["levels"][0]["level"], "Hello & Welcome");
+        assert_eq!(json["levels"][0]["tasks"][1]["passed"], false);
+        assert_eq!(json["levels"][0]["tasks"][1]["error"], "expected \"hi\"\nfound <nothing>");
+        assert_eq!(json["levels"][1]["tasks"][0]["error"], serde_json::Value::Null);
+
+        let tap = render_report(&results, ReportFormat::Tap, total_time);
+        let lines: Vec<&str> = tap.lines().collect();
+        assert_eq!(&lines[..3], ["TAP version 13", "1..3", "ok 1 - Hello & Welcome - Task 1: Task <1>"]);
+        assert!(tap.contains("not ok 2 - Hello & Welcome - Task 2: Task <2>\n  ---\n  duration_ms: 250\n  message: \"expected \\\"hi\\\"\\nfound <nothing>\"\n  ...\n"));
+        assert!(tap.ends_with("ok 3 - Loops - Task 1: Task <1>\n  ---\n  duration_ms: 250\n  ...\n"));
+    }
+}
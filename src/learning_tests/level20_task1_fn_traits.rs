@@ -284,7 +284,7 @@ mod tests {
     #[test]
     fn test_fnonce_trait() {
         let factory = RobotFactory::new();
-        let initial_config = HashMap::new();
+        let initial_config: HashMap<String, f64> = HashMap::new();
 
         // FnOnce consumes captured variables
         let config_copy = initial_config.clone();
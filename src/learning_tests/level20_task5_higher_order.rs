@@ -527,7 +527,7 @@ mod tests {
         let sum = HigherOrder::reduce(numbers.clone(), 0, |acc, x| acc + x);
         assert_eq!(sum, 15);
 
-        let found = HigherOrder::find(&numbers, |&&x| x == 3);
+        let found = HigherOrder::find(&numbers, |&x| x == 3);
         assert_eq!(found, Some(&3));
 
         let (evens, odds) = HigherOrder::partition(numbers, |&x| x % 2 == 0);
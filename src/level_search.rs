@@ -0,0 +1,126 @@
+// Level search: the search box and filters of the Load Level screen. Levels are matched on their
+// name, `author:` and the `tags:` of their YAML (e.g. tags: [traits, serde]), and can be narrowed down to one
+// topic tag, one `difficulty:`, and completed or not yet completed levels.
+
+use crate::level::LevelSpec;
+
+/// What the level list needs to know about a level to filter it
+#[derive(Clone, Debug)]
+pub struct LevelInfo {
+    pub name: String,
+    pub tags: Vec<String>,
+    pub difficulty: Option<String>,
+    pub author: Option<String>,
+    pub version: Option<String>,
+    pub requires: Vec<String>,
+}
+
+impl LevelInfo {
+    pub fn from_spec(spec: &LevelSpec) -> Self {
+        Self {
+            name: spec.name.clone(),
+            tags: spec.tags.clone(),
+            difficulty: spec.difficulty.clone(),
+            author: spec.author.clone(),
+            version: spec.version.clone(),
+            requires: spec.requires.clone(),
+        }
+    }
+
+    /// e.g. "[traits, serde] intermediate, by Ann, v1.2, after Loops"; empty for a level with none of them
+    pub fn details(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.tags.is_empty() {
+            parts.push(format!("[{}]", self.tags.join(", ")));
+        }
+        parts.extend(self.difficulty.clone());
+        let mut details = parts.join(" ");
+        let extra = self.author.iter().map(|author| format!("by {}", author))
+            .chain(self.version.iter().map(|version| format!("v{}", version)))
+            .chain((!self.requires.is_empty()).then(|| format!("after {}", self.requires.join(", "))));
+        for part in extra {
+            if !details.is_empty() {
+                details.push_str(", ");
+            }
+            details.push_str(&part);
+        }
+        details
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CompletionFilter {
+    #[default]
+    All,
+    Completed,
+    Uncompleted,
+}
+
+impl CompletionFilter {
+    /// All -> completed -> not completed -> all
+    pub fn next(self) -> Self {
+        match self {
+            CompletionFilter::All => CompletionFilter::Completed,
+            CompletionFilter::Completed => CompletionFilter::Uncompleted,
+            CompletionFilter::Uncompleted => CompletionFilter::All,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LevelSearch {
+    pub query: String,
+    pub tag: Option<String>,
+    pub difficulty: Option<String>,
+    pub completion: CompletionFilter,
+    pub page: usize,
+}
+
+impl LevelSearch {
+    pub fn matches(&self, level: &LevelInfo, completed: bool) -> bool {
+        let query = self.query.trim().to_lowercase();
+        let found = query.is_empty()
+            || level.name.to_lowercase().contains(&query)
+            || level.author.as_ref().is_some_and(|author| author.to_lowercase().contains(&query))
+            || level.tags.iter().any(|tag| tag.to_lowercase().contains(&query));
+        let completion = match self.completion {
+            CompletionFilter::All => true,
+            CompletionFilter::Completed => completed,
+            CompletionFilter::Uncompleted => !completed,
+        };
+        found
+            && completion
+            && self.tag.as_ref().is_none_or(|tag| level.tags.contains(tag))
+            && self.difficulty.as_ref().is_none_or(|difficulty| level.difficulty.as_ref() == Some(difficulty))
+    }
+
+    /// All topics -> each tag of `levels`, alphabetically -> all topics
+    pub fn next_tag(&mut self, levels: &[LevelInfo]) {
+        let mut tags: Vec<&String> = levels.iter().flat_map(|level| &level.tags).collect();
+        tags.sort();
+        tags.dedup();
+        self.tag = next_choice(&tags, self.tag.as_ref());
+        self.page = 0;
+    }
+
+    /// All difficulties -> each difficulty of `levels`, in the order the levels first use them -> all
+    pub fn next_difficulty(&mut self, levels: &[LevelInfo]) {
+        let mut difficulties: Vec<&String> = Vec::new();
+        for difficulty in levels.iter().filter_map(|level| level.difficulty.as_ref()) {
+            if !difficulties.contains(&difficulty) {
+                difficulties.push(difficulty);
+            }
+        }
+        self.difficulty = next_choice(&difficulties, self.difficulty.as_ref());
+        self.page = 0;
+    }
+}
+
+/// The choice after `current`; None stands for "all" and comes before the first one
+fn next_choice(choices: &[&String], current: Option<&String>) -> Option<String> {
+    let next = match current {
+        None => choices.first(),
+        Some(current) => choices.iter().skip_while(|choice| **choice != current).nth(1),
+    };
+    next.map(|choice| (*choice).clone())
+}
@@ -2,16 +2,17 @@
 
 use wasm_bindgen::prelude::*;
 
-mod level;
-mod item;
-mod grid;
-mod robot;
+use game_core::{grid, item, level, movement_patterns, robot};
+
+mod campaign;
 mod game_state;
 mod menu;
-mod movement_patterns;
 mod popup;
 mod embedded_levels;
 mod learning_tests;
+mod theme;
+mod i18n;
+mod speech;
 
 use level::*;
 use game_state::*;
@@ -161,7 +162,7 @@ async fn run_game() {
 
         if moved {
             game.update_laser_effects();
-            game.grid.move_enemies(Some(game.robot.get_position()), &game.stunned_enemies);
+            game.grid.move_enemies(Some(game.robot.get_position()));
             game.turns += 1;
             
             // Check for enemy collision
@@ -172,8 +173,17 @@ async fn run_game() {
                 continue;
             }
             
-            // Check win condition (simple: visited enough squares)
-            if game.discovered_this_level >= (game.grid.width * game.grid.height) as usize / 3 {
+            // The level's win condition, or by default enough squares discovered
+            let won = match &game.levels[current_level].win_condition {
+                Some(condition) => game_core::win_conditions::WinConditionEvaluator::new(condition).is_met(&game_core::win_conditions::WinState {
+                    robots: &[game.robot.get_pos()],
+                    items_left: game.item_manager.items.len(),
+                    enemies_left: game.grid.enemies.len(),
+                    turns: game.turns,
+                }),
+                None => game.discovered_this_level >= (game.grid.width * game.grid.height) as usize / 3,
+            };
+            if won {
                 game.finished = true;
                 game.show_level_complete();
             }
@@ -199,6 +209,8 @@ fn draw_game_wasm(game: &Game) {
     let tile_size = 30.0;
     let grid_start_x = 50.0;
     let grid_start_y = 80.0;
+    let palette = game.menu.settings.palette;
+    let colors = palette.grid_colors();
     
     // Draw title
     draw_text("Rust Steam Game - Web Edition", 10.0, 30.0, 24.0, WHITE);
@@ -227,10 +239,10 @@ fn draw_game_wasm(game: &Game) {
                 } else if game.grid.visited.contains(&pos) {
                     LIGHTGRAY
                 } else {
-                    GRAY
+                    colors.floor
                 }
             } else {
-                BLACK
+                colors.unexplored
             };
             
             draw_rectangle(screen_x, screen_y, tile_size - 2.0, tile_size - 2.0, color);
@@ -241,45 +253,21 @@ fn draw_game_wasm(game: &Game) {
     let robot_pos = game.robot.get_position();
     let robot_screen_x = grid_start_x + (robot_pos.0 as f32) * tile_size;
     let robot_screen_y = grid_start_y + (robot_pos.1 as f32) * tile_size;
-    draw_rectangle(robot_screen_x + 5.0, robot_screen_y + 5.0, tile_size - 10.0, tile_size - 10.0, SKYBLUE);
+    draw_rectangle(robot_screen_x + 5.0, robot_screen_y + 5.0, tile_size - 10.0, tile_size - 10.0, colors.robot);
     
     // Draw enemies
     for enemy in &game.grid.enemies {
         let enemy_screen_x = grid_start_x + (enemy.pos.x as f32) * tile_size;
         let enemy_screen_y = grid_start_y + (enemy.pos.y as f32) * tile_size;
         
-        // Determine enemy color based on movement type and state
-        let enemy_color = if let Some(ref pattern) = enemy.movement_pattern {
-            match pattern.as_str() {
-                "chase" => {
-                    // Check if actively chasing (orange) or not moving (blue)
-                    if let Some(is_chasing) = enemy.movement_data.get("is_chasing")
-                        .and_then(|v| v.as_bool()) {
-                        if is_chasing {
-                            ORANGE  // Actively chasing player
-                        } else {
-                            BLUE    // Not moving/searching
-                        }
-                    } else {
-                        ORANGE  // Default to orange for chase enemies
-                    }
-                }
-                "random" => MAGENTA,    // Random movement = magenta
-                "diagonal" => YELLOW,   // Diagonal movement = yellow
-                "circular" => LIME,     // Circular movement = lime green
-                "spiral" => PINK,       // Spiral movement = pink
-                pattern if pattern.starts_with("file:") => PURPLE, // Custom file patterns = purple
-                _ => RED                 // Unknown patterns = red
-            }
-        } else {
-            // Built-in horizontal/vertical enemies (no movement_pattern field)
-            match enemy.direction {
-                level::EnemyDirection::Horizontal => GREEN,  // Horizontal = green
-                level::EnemyDirection::Vertical => DARKBLUE, // Vertical = dark blue
-            }
-        };
+        // Color and letter for the enemy type, from the palette picked in settings
+        let kind = theme::EnemyKind::of(enemy);
+        let enemy_color = palette.enemy_color(kind);
         
         draw_rectangle(enemy_screen_x + 5.0, enemy_screen_y + 5.0, tile_size - 10.0, tile_size - 10.0, enemy_color);
+        if palette != theme::Palette::Classic {
+            draw_text(kind.glyph(), enemy_screen_x + 10.0, enemy_screen_y + 21.0, 18.0, BLACK);
+        }
     }
     
     // Draw controls
@@ -1,247 +1,45 @@
 use macroquad::prelude::*;
 use ::rand::{rngs::StdRng, SeedableRng};
-use std::collections::HashSet;
 use log::{info, warn, error, debug, trace};
-use std::env;
 use std::panic;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 mod crash_protection;
 mod code_executor;
 
-/// Parse only function calls that are reachable from main(), following proper Rust execution flow
-fn parse_rust_code_from_main(code: &str) -> Vec<FunctionCall> {
-    println!("🔍 [PARSE] parse_rust_code_from_main called with {} chars", code.len());
-    println!("🔍 [PARSE] Code preview: '{}'", &code.chars().take(150).collect::<String>());
-
-    // Extract main function body
-    let main_body = extract_main_function_body(code);
-    println!("🔍 [PARSE] Extracted main function body: {} chars", main_body.len());
-    if main_body.is_empty() {
-        println!("🔍 [PARSE] Main function body is empty, returning empty vec");
-        return Vec::new();
-    }
-
-    println!("🔍 [PARSE] Main body content: '{}'", main_body);
-
-    // Parse calls only within main
-    let result = parse_function_calls_in_body(&main_body);
-    println!("🔍 [PARSE] Found {} function calls", result.len());
-    result
-}
-
-/// Extract the body of the main() function from Rust code
-fn extract_main_function_body(code: &str) -> String {
-    println!("🔍 [PARSE] extract_main_function_body called with {} chars", code.len());
-    let lines: Vec<&str> = code.lines().collect();
-    println!("🔍 [PARSE] Code has {} lines", lines.len());
-    let mut in_main = false;
-    let mut brace_count = 0;
-    let mut main_body = Vec::new();
-    let mut found_main_start = false;
-
-    for (i, &line) in lines.iter().enumerate() {
-        let trimmed = line.trim();
-        println!("🔍 [PARSE] Line {}: '{}' (trimmed: '{}')", i, line, trimmed);
-
-        // Look for main function declaration
-        if !found_main_start && (trimmed.starts_with("fn main(") || trimmed.contains("fn main(")) {
-            println!("🔍 [PARSE] Found main function declaration at line {}", i);
-            found_main_start = true;
-            if trimmed.contains('{') {
-                in_main = true;
-                brace_count = 1;
-            }
-            continue;
-        }
-        
-        // If we found main but haven't entered the body yet, look for opening brace
-        if found_main_start && !in_main && trimmed.contains('{') {
-            in_main = true;
-            brace_count = 1;
-            continue;
-        }
-        
-        if in_main {
-            // Count braces to track when we exit main
-            for ch in trimmed.chars() {
-                match ch {
-                    '{' => brace_count += 1,
-                    '}' => {
-                        brace_count -= 1;
-                        if brace_count == 0 {
-                            // We've exited main function
-                            return main_body.join("\n");
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            
-            // Add this line to main body if we're still inside main
-            if brace_count > 0 {
-                main_body.push(line);
-            }
-        }
-    }
-
-    let result = main_body.join("\n");
-    println!("🔍 [PARSE] extract_main_function_body completed, returning {} chars: '{}'", result.len(), result);
-    result
-}
-
-/// Parse function calls within a specific function body
-fn parse_function_calls_in_body(body: &str) -> Vec<FunctionCall> {
-    let mut calls = Vec::new();
-    let lines: Vec<&str> = body.lines().collect();
-    
-    for line in lines {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with("//") {
-            continue;
-        }
-        
-        // Parse robot function calls (move_bot, scan, grab, etc.)
-        if let Some(call) = parse_single_line_for_calls(trimmed) {
-            calls.push(call);
-        }
-    }
-    
-    calls
-}
-
-/// Parse a single line for robot function calls
-fn parse_single_line_for_calls(line: &str) -> Option<FunctionCall> {
-    // Parse move_bot() calls (also support legacy move() for backward compatibility)
-    if let Some(start) = line.find("move_bot(").or_else(|| line.find("move(")) {
-        let paren_offset = if line[start..].starts_with("move_bot(") { 9 } else { 5 };
-        let after_paren = &line[start + paren_offset..];
-        if let Some(end) = after_paren.find(')') {
-            let param = after_paren[..end].trim();
-            let dir = match param {
-                "up" | "Up" | "\"up\"" | "\"Up\"" => Some((0, -1)),
-                "down" | "Down" | "\"down\"" | "\"Down\"" => Some((0, 1)),
-                "left" | "Left" | "\"left\"" | "\"Left\"" => Some((-1, 0)),
-                "right" | "Right" | "\"right\"" | "\"Right\"" => Some((1, 0)),
-                _ => None,
-            };
-            if let Some(d) = dir {
-                return Some(FunctionCall {
-                    function: RustFunction::Move,
-                    direction: Some(d),
-                    coordinates: None,
-                    level_number: None,
-                    boolean_param: None,
-                    message: None,
-                });
-            }
-        }
-    }
-    
-    // Parse scan() calls
-    if let Some(start) = line.find("scan(") {
-        let after_paren = &line[start + 5..];
-        if let Some(end) = after_paren.find(')') {
-            let param = after_paren[..end].trim();
-            let dir = match param {
-                "up" | "Up" | "\"up\"" | "\"Up\"" => Some((0, -1)),
-                "down" | "Down" | "\"down\"" | "\"Down\"" => Some((0, 1)),
-                "left" | "Left" | "\"left\"" | "\"Left\"" => Some((-1, 0)),
-                "right" | "Right" | "\"right\"" | "\"Right\"" => Some((1, 0)),
-                "current" | "Current" | "\"current\"" | "\"Current\"" => Some((0, 0)),
-                _ => None,
-            };
-            if let Some(d) = dir {
-                return Some(FunctionCall {
-                    function: RustFunction::Scan,
-                    direction: Some(d),
-                    coordinates: None,
-                    level_number: None,
-                    boolean_param: None,
-                    message: None,
-                });
-            }
-        }
-    }
-    
-    // Parse grab() calls
-    if line.contains("grab()") {
-        return Some(FunctionCall {
-            function: RustFunction::Grab,
-            direction: None,
-            coordinates: None,
-            level_number: None,
-            boolean_param: None,
-            message: None,
-        });
+/// Keys the hotkey system is asked about each frame; the later strokes of chords need to be here too
+const HOTKEY_KEYS: [KeyCode; 23] = [
+    KeyCode::Enter, KeyCode::S, KeyCode::Tab, KeyCode::Z, KeyCode::Y,
+    KeyCode::C, KeyCode::V, KeyCode::X, KeyCode::A, KeyCode::F,
+    KeyCode::H, KeyCode::G, KeyCode::Slash, KeyCode::D, KeyCode::K,
+    KeyCode::U, KeyCode::GraveAccent, KeyCode::E, KeyCode::B, KeyCode::N,
+    KeyCode::P, KeyCode::W, KeyCode::Space,
+];
+
+/// A key the hotkey system isn't asked about (arrows, letters of typed text) ends a chord in progress
+fn cancel_chord_on_other_keys(game: &mut Game) {
+    const MODIFIERS: [KeyCode; 6] = [
+        KeyCode::LeftShift, KeyCode::RightShift, KeyCode::LeftControl,
+        KeyCode::RightControl, KeyCode::LeftAlt, KeyCode::RightAlt,
+    ];
+    if get_keys_pressed().iter().any(|key| !HOTKEY_KEYS.contains(key) && !MODIFIERS.contains(key)) {
+        game.hotkey_system.cancel_chord();
     }
-    
-    None
 }
 
-/// Extract print statements only from main() and functions called by main()
-fn extract_print_statements_from_main(code: &str) -> Vec<String> {
-    println!("🔍 [PARSE] extract_print_statements_from_main called with {} chars", code.len());
-    let main_body = extract_main_function_body(code);
-    println!("🔍 [PARSE] Extracted main function body: {} chars", main_body.len());
-    if main_body.is_empty() {
-        println!("🔍 [PARSE] Main function body is empty, returning empty vec");
-        return Vec::new();
-    }
-
-    let result = extract_print_statements_from_body(&main_body);
-    println!("🔍 [PARSE] Found {} print statements", result.len());
-    result
+/// Hand this frame's key presses to the hotkey system outside the editor, which has its own loop.
+/// Returns whether one of them was bound.
+fn poll_hotkeys(game: &mut Game) -> bool {
+    let shift_held = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+    let ctrl_held = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+    let alt_held = is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt);
+    cancel_chord_on_other_keys(game);
+    HOTKEY_KEYS.iter().any(|key_code| is_key_pressed(*key_code) && game.handle_hotkey(*key_code, ctrl_held, shift_held, alt_held))
 }
 
-/// Extract print statements from a specific function body
-fn extract_print_statements_from_body(body: &str) -> Vec<String> {
-    let mut print_outputs = Vec::new();
-    let lines: Vec<&str> = body.lines().collect();
-    
-    for line in lines {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with("//") {
-            continue;
-        }
-        
-        // Extract println! statements
-        if let Some(start) = trimmed.find("println!(") {
-            let after_paren = &trimmed[start + 9..];
-            if let Some(end) = after_paren.rfind(')') {
-                let content = &after_paren[..end];
-                // Remove quotes from string literals
-                let clean_content = content.trim_matches('"');
-                print_outputs.push(format!("stdout: {}", clean_content));
-            }
-        }
-        
-        // Extract eprintln! statements
-        if let Some(start) = trimmed.find("eprintln!(") {
-            let after_paren = &trimmed[start + 10..];
-            if let Some(end) = after_paren.rfind(')') {
-                let content = &after_paren[..end];
-                let clean_content = content.trim_matches('"');
-                print_outputs.push(format!("stderr: {}", clean_content));
-            }
-        }
-        
-        // Extract panic! statements
-        if let Some(start) = trimmed.find("panic!(") {
-            let after_paren = &trimmed[start + 7..];
-            if let Some(end) = after_paren.rfind(')') {
-                let content = &after_paren[..end];
-                let clean_content = content.trim_matches('"');
-                print_outputs.push(format!("panic: {}", clean_content));
-            }
-        }
-    }
-    
-    print_outputs
-}
-
-/// Determine the indentation level for the next line based on Rust code structure
-fn get_auto_indentation(code: &str, cursor_position: usize) -> String {
+/// Determine the indentation level for the next line based on Rust code structure;
+/// `indent_unit` is one level, as set on the editor settings page
+fn get_auto_indentation(code: &str, cursor_position: usize, indent_unit: &str) -> String {
     // Find the current line
     let lines: Vec<&str> = code.lines().collect();
     let mut current_pos = 0;
@@ -289,8 +87,7 @@ fn get_auto_indentation(code: &str, cursor_position: usize) -> String {
                        (trimmed_line.contains("trait ") && trimmed_line.ends_with('{'));
     
     if should_indent {
-        // Add 4 spaces for indentation (standard Rust style)
-        return base_indent + "    ";
+        return base_indent + indent_unit;
     }
     
     // No additional indentation needed
@@ -307,33 +104,59 @@ use notify::{Watcher, RecursiveMode, Event};
 #[cfg(not(target_arch = "wasm32"))]
 use crossbeam_channel::{Receiver, Sender, unbounded};
 #[cfg(not(target_arch = "wasm32"))]
-use std::process::Command;
-
-mod level;
-mod item;
-mod grid;
-mod robot;
+use clap::Parser;
+
+use game_core::{grid, item, level, robot};
+use game_core::interpreter::{parse_rust_code_from_main, extract_print_statements_from_main};
+use game_core::combat_log::CombatEvent;
+
+mod campaign;
+mod community;
+mod leaderboard;
+mod export;
+mod settings_bundle;
+mod save_file;
+mod level_search;
+mod game_log;
+mod jobs;
 mod gamestate;
 mod menu;
-mod movement_patterns;
 mod popup;
 mod embedded_levels;
 mod drawing;
 mod rust_checker;
+mod error_catalog;
+mod watch;
+mod task_plugins;
+mod code_diff;
+#[cfg(not(target_arch = "wasm32"))]
+mod autosave;
+#[cfg(feature = "vcs")]
+mod code_history;
 mod test_enhanced_errors;
 mod font_scaling;
+mod theme;
+mod i18n;
+mod speech;
 mod cache;
 mod progressive_loader;
 mod coordinate_system;
+mod text_buffer;
 mod learning_tests;
 mod autocomplete;
 mod hotkeys;
 mod test_runner;
 mod editor_test_mode;
+#[cfg(not(target_arch = "wasm32"))]
+mod editor_scenarios;
 mod hotkey_test_mode;
 mod learning_level_solutions;
 mod learning_test_runner;
 mod automated_level_testing;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+#[cfg(feature = "steam")]
+mod steam;
 
 use level::*;
 use item::*;
@@ -343,12 +166,23 @@ use popup::PopupAction;
 use drawing::*;
 use progressive_loader::{ProgressiveLoader, LoadingProgress, LoadingStage};
 
+/// The character typed, if exactly one was. Only single key presses repeat while held.
+fn single_char(typed: &str) -> Option<char> {
+    let mut chars = typed.chars();
+    match (chars.next(), chars.next()) {
+        (Some(character), None) => Some(character),
+        _ => None,
+    }
+}
+
 // Reset robot_code.rs to default content
 fn reset_robot_code(game: &mut Game) {
     let default_code = r#"// Write your robot control code here
 // Available functions:
 // robot.move_up(), robot.move_down(), robot.move_left(), robot.move_right()
-// robot.scan() -> returns what's at the robot's current position
+// robot.scan_current() -> returns what's at the robot's current position
+// robot.grab() -> picks up the item under the robot
+// The same actions also work as plain calls: move_bot("up"), scan("current"), grab()
 // println!() for debugging output
 
 fn main() {
@@ -359,7 +193,7 @@ fn main() {
 }
 "#;
     
-    game.current_code = default_code.to_string();
+    game.current_code = default_code.into();
     game.cursor_position = default_code.len();
     
     // Save the default code to robot_code.rs file
@@ -504,165 +338,79 @@ fn main() {{
     print_outputs
 }
 
-fn old_extract_crates_from_code(code: &str) -> HashSet<String> {
-    let mut out = HashSet::new();
-    let ignore: HashSet<&'static str> = [
-        "std","core","alloc","crate","self","super",
-        "macroquad","serde","serde_json","serde_yaml","rand","notify","crossbeam_channel",
-    ].into_iter().collect();
-
-    for raw in code.lines() {
-        let line = raw.trim();
-        if line.starts_with("use ") {
-            let rest = &line[4..];
-            let rest = rest.split(" as ").next().unwrap_or(rest);
-            let rest = rest.trim_end_matches(';').trim();
-            let first = rest.split(&[':', ' ', ',', '{'][..]).next().unwrap_or("").trim();
-            if !first.is_empty() && !ignore.contains(first) && first.chars().all(|c| c.is_ascii_alphanumeric() || c=='_') {
-                out.insert(first.to_string());
-            }
-        } else if line.starts_with("extern crate ") {
-            let name = line["extern crate ".len()..].trim().trim_end_matches(';').trim();
-            if !name.is_empty() && !ignore.contains(name) {
-                out.insert(name.to_string());
-            }
-        }
+// Game mechanics
+/// Move the robot one tile if nothing is in the way. Returns true when it walked into an enemy,
+/// which resets the level.
+fn try_move(game: &mut Game, dx: i32, dy: i32) -> bool {
+    if game.finished { return false; }
+    
+    let current_pos = game.robot.get_position();
+    let next = Pos { x: current_pos.0 + dx, y: current_pos.1 + dy };
+    
+    if !game.grid.in_bounds(next) { return false; }
+    
+    // Walking into a crate pushes it if the tile behind it is free
+    if game.grid.is_crate(next) && !game.grid.push_crate(next, dx, dy) {
+        game.grid.reveal_adjacent(current_pos);
+        return false;
     }
-    out
-}
 
-#[cfg(not(target_arch = "wasm32"))]
-fn existing_deps_from_cargo_toml(cargo_toml_path: &str) -> HashSet<String> {
-    let mut deps = HashSet::new();
-    let Ok(toml) = fs::read_to_string(cargo_toml_path) else { return deps; };
-
-    let mut in_deps = false;
-    for line in toml.lines() {
-        let l = line.trim();
-        if l.starts_with('[') {
-            in_deps = l == "[dependencies]";
-            continue;
-        }
-        if !in_deps || l.is_empty() || l.starts_with('#') { continue; }
-
-        if let Some((key,_rest)) = l.split_once('=') {
-            let k = key.trim();
-            if !k.is_empty() && !k.starts_with('#') {
-                deps.insert(k.to_string());
-            }
-        }
+    if game.grid.is_blocked(next) || game.other_robot_at(next).is_some() {
+        game.grid.reveal_adjacent(current_pos);
+        return false;
     }
-    deps
-}
-
-#[cfg(not(target_arch = "wasm32"))]
-fn cargo_add_available() -> bool {
-    Command::new("cargo")
-        .arg("add")
-        .arg("--help")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-}
 
-#[cfg(not(target_arch = "wasm32"))]
-fn ensure_crates_in_cargo(new_crates: &HashSet<String>) -> String {
-    if new_crates.is_empty() {
-        return "No new libraries detected in robot_code.rs".to_string();
+    // A live laser beam can't be walked into
+    if game.grid.laser_beam_at(next, game.turns) {
+        game.grid.reveal_adjacent(current_pos);
+        return false;
     }
-
-    if !cargo_add_available() {
-        return "cargo-edit not found. Install with: `cargo install cargo-edit`".to_string();
+    
+    if enter_tile(game, next) {
+        return true;
     }
 
-    let mut added = Vec::new();
-    let mut failed = Vec::new();
-
-    for name in new_crates {
-        let out = Command::new("cargo")
-            .arg("add")
-            .arg(name)
-            .arg("--quiet")
-            .output();
-
-        match out {
-            Ok(o) if o.status.success() => added.push(name.clone()),
-            Ok(o) => {
-                let stderr = String::from_utf8_lossy(&o.stderr);
-                failed.push(format!("{name} ({})", stderr.trim()));
-            }
-            Err(e) => failed.push(format!("{name} ({e})")),
+    // On ice the robot keeps sliding until a wall, door, crate, robot or live beam stops it
+    let mut pos = next;
+    while game.grid.terrain_at(pos) == Some(Terrain::Ice) {
+        let slide = Pos { x: pos.x + dx, y: pos.y + dy };
+        let stopped = !game.grid.in_bounds(slide)
+            || game.grid.is_blocked(slide)
+            || game.other_robot_at(slide).is_some()
+            || game.grid.laser_beam_at(slide, game.turns);
+        if stopped {
+            break;
         }
+        if enter_tile(game, slide) {
+            return true;
+        }
+        pos = slide;
     }
-
-    if !failed.is_empty() {
-        format!("Added: {:?}. Failed: {:?}", added, failed)
-    } else {
-        format!("Added: {:?}", added)
-    }
-}
-
-#[cfg(not(target_arch = "wasm32"))]
-fn auto_add_crates_from_robot_code(robot_code_path: &str) -> String {
-    let Ok(code) = fs::read_to_string(robot_code_path) else {
-        return format!("Could not read {}", robot_code_path);
-    };
-
-    let mentioned = old_extract_crates_from_code(&code);
-    if mentioned.is_empty() {
-        return "No external libraries referenced in robot_code.rs".to_string();
-    }
-
-    let deps = existing_deps_from_cargo_toml("Cargo.toml");
-    let new_ones: HashSet<String> = mentioned
-        .into_iter()
-        .filter(|c| !deps.contains(c))
-        .collect();
-
-    ensure_crates_in_cargo(&new_ones)
+    false
 }
 
-// Game mechanics
-fn try_move(game: &mut Game, dx: i32, dy: i32) {
-    if game.finished { return; }
-    
-    let current_pos = game.robot.get_position();
-    let next = Pos { x: current_pos.0 + dx, y: current_pos.1 + dy };
-    
-    if !game.grid.in_bounds(next) { return; }
-    
-    if game.grid.is_blocked(next) {
-        game.grid.reveal_adjacent(current_pos);
-        return;
-    }
-    
-    // Move robot
+/// Move the robot onto `next` and set off what's there. Returns true if an enemy caught it.
+fn enter_tile(game: &mut Game, next: Pos) -> bool {
     game.robot.move_to(next);
     game.grid.visit(next);
     game.grid.reveal_adjacent((next.x, next.y));
+    game.grid.update_plates(next);
+    game.grid.flip_switch(next);
 
-    // Check for immediate collision
-    if game.level_idx >= 3 && game.grid.check_enemy_collision((next.x, next.y)) {
+    // Walking into an enemy is caught straight away, before the enemies take their step
+    if game.level_idx >= 3 && game.enemy_collision_at((next.x, next.y)) {
+        game.publish(GameEvent::Combat(CombatEvent::RobotCaught { robot: game.active_robot, at: next }));
         let idx = game.level_idx;
         game.load_level(idx);
-        game.execution_result = "ENEMY COLLISION! Level reset and randomized.".to_string();
-        return;
+        game.execution_result = ENEMY_COLLISION_MESSAGE.to_string();
+        return true;
     }
 
-    // Enemies move after player action
-    if game.level_idx >= 3 && !game.enemy_step_paused {
-        game.update_laser_effects();
-        game.grid.move_enemies(Some(game.robot.get_position()), &game.stunned_enemies);
-        if game.grid.check_enemy_collision(game.robot.get_position()) {
-            let idx = game.level_idx;
-            game.load_level(idx);
-            game.execution_result = "ENEMY COLLISION! Level reset and randomized.".to_string();
-            return;
-        }
+    // With auto-grab off, items stay where they are until the program calls grab()
+    if game.robot.auto_grab_enabled {
+        try_grab(game);
     }
-
-    // Always auto-grab behavior since grabber is always enabled
-    try_grab(game);
+    false
 }
 
 fn try_grab(game: &mut Game) -> &'static str {
@@ -676,9 +424,7 @@ fn try_grab(game: &mut Game) -> &'static str {
     for pos in grabbable_positions {
         if let Some(item) = game.item_manager.collect_item(pos) {
             items_found.push(item.name.clone());
-            
-            // Show popup for item collection
-            game.show_item_collected(&item.name);
+            game.robot.add_to_inventory(item.name.clone());
             
             // Apply item effects
             match item.name.as_str() {
@@ -706,6 +452,16 @@ fn try_grab(game: &mut Game) -> &'static str {
                     }
                 }
             }
+
+            // Abilities and pickup messages come from the item file
+            for ability in &item.capabilities.abilities {
+                game.robot.grant_ability(ability);
+                if ability == "shield" {
+                    game.robot.shield_charges += item.capabilities.shield_hits.unwrap_or(1);
+                }
+            }
+            // Popups for the pickup come from the event bus
+            game.publish(GameEvent::ItemCollected { name: item.name.clone(), on_pickup: item.capabilities.on_pickup.clone() });
         }
     }
     
@@ -726,17 +482,6 @@ fn try_grab(game: &mut Game) -> &'static str {
     
     game.credits += grabbed * game.grid.income_per_square;
 
-    // Enemies advance on any action
-    if game.level_idx >= 3 && !game.enemy_step_paused {
-        game.update_laser_effects();
-        game.grid.move_enemies(Some(game.robot.get_position()), &game.stunned_enemies);
-        if game.grid.check_enemy_collision(game.robot.get_position()) {
-            let idx = game.level_idx;
-            game.load_level(idx);
-            game.execution_result = "ENEMY COLLISION! Level reset and randomized.".to_string();
-        }
-    }
-
     // Return appropriate message
     match (items_found.len() > 0, grabbed > 0) {
         (true, true) => "Grabbed items and unknown tiles for credits!",
@@ -754,6 +499,8 @@ fn try_scan(game: &mut Game, dir: (i32, i32)) -> String {
         let mut obstacles = 0;
         let mut items = 0;
         let mut enemies = 0;
+        let mut hazards = Vec::new();
+        let mut devices = Vec::new();
         let target_reveals = 5;
         
         // Scan in the specified direction, looking for unrevealed tiles
@@ -772,6 +519,8 @@ fn try_scan(game: &mut Game, dir: (i32, i32)) -> String {
             // Check for obstacle - stop scanning if we hit one
             if game.grid.is_blocked(scan_pos) {
                 obstacles += 1;
+                devices.extend(game.grid.describe_locked_door(scan_pos));
+                hazards.extend(game.grid.describe_emitter(scan_pos, game.turns));
                 break; // Stop scanning when we hit an obstacle
             }
             
@@ -779,10 +528,15 @@ fn try_scan(game: &mut Game, dir: (i32, i32)) -> String {
             if game.item_manager.get_item_at_position(scan_pos).is_some() {
                 items += 1;
             }
+
+            devices.extend(game.grid.describe_switch(scan_pos));
+            if let Some(hazard) = game.grid.describe_hazard(scan_pos, game.turns) {
+                hazards.push(hazard);
+            }
             
             // Count enemies at this position
             for enemy in &game.grid.enemies {
-                if enemy.pos == scan_pos {
+                if enemy.occupies(scan_pos) {
                     enemies += 1;
                     break;
                 }
@@ -807,8 +561,8 @@ fn try_scan(game: &mut Game, dir: (i32, i32)) -> String {
             }
         }
         
-        return format!("Scanned and revealed {} new tiles, found {} obstacles, {} items, {} enemies", 
-                      tiles_revealed, obstacles, items, enemies);
+        return format!("Scanned and revealed {} new tiles, found {} obstacles, {} items, {} enemies{}{}", 
+                      tiles_revealed, obstacles, items, enemies, hazard_summary(&hazards), device_summary(&devices));
     }
     
     // Enhanced scan function for other levels - reveal 5 unrevealed tiles in direction
@@ -818,6 +572,8 @@ fn try_scan(game: &mut Game, dir: (i32, i32)) -> String {
     
     let robot_pos = game.robot.get_position();
     let mut tiles_revealed = 0;
+    let mut hazards = Vec::new();
+    let mut devices = Vec::new();
     let target_reveals = 5;
     
     // Scan in the specified direction, looking for unrevealed tiles
@@ -836,8 +592,15 @@ fn try_scan(game: &mut Game, dir: (i32, i32)) -> String {
         
         // Check for obstacle - stop scanning if we hit one
         if game.grid.is_blocked(scan_pos) {
+            devices.extend(game.grid.describe_locked_door(scan_pos));
+            hazards.extend(game.grid.describe_emitter(scan_pos, game.turns));
             break; // Stop scanning when we hit an obstacle
         }
+
+        devices.extend(game.grid.describe_switch(scan_pos));
+        if let Some(hazard) = game.grid.describe_hazard(scan_pos, game.turns) {
+            hazards.push(hazard);
+        }
         
         // Try to reveal the tile - only count if it was previously unrevealed
         if game.grid.reveal(scan_pos) {
@@ -858,21 +621,28 @@ fn try_scan(game: &mut Game, dir: (i32, i32)) -> String {
         }
     }
     
-    // Enemies advance on any action
-    if game.level_idx >= 3 && !game.enemy_step_paused {
-        game.update_laser_effects();
-        game.grid.move_enemies(Some(game.robot.get_position()), &game.stunned_enemies);
-        if game.grid.check_enemy_collision(game.robot.get_position()) {
-            let idx = game.level_idx;
-            game.load_level(idx);
-            game.execution_result = "ENEMY COLLISION! Level reset and randomized.".to_string();
-        }
-    }
-
     if tiles_revealed > 0 { 
-        format!("Scan complete. Revealed {} new tiles in that direction.", tiles_revealed) 
+        format!("Scan complete. Revealed {} new tiles in that direction.{}{}", tiles_revealed, hazard_summary(&hazards), device_summary(&devices)) 
     } else { 
-        "Scan complete. No new tiles to reveal in that direction.".to_string() 
+        format!("Scan complete. No new tiles to reveal in that direction.{}{}", hazard_summary(&hazards), device_summary(&devices))
+    }
+}
+
+/// " Hazards: ..." suffix for scan results, empty when nothing dangerous was seen
+fn hazard_summary(hazards: &[String]) -> String {
+    if hazards.is_empty() {
+        String::new()
+    } else {
+        format!(" Hazards: {}.", hazards.join(", "))
+    }
+}
+
+/// " Seen: ..." suffix for scan results: locked doors by the key they need, and switches with their state
+fn device_summary(devices: &[String]) -> String {
+    if devices.is_empty() {
+        String::new()
+    } else {
+        format!(" Seen: {}.", devices.join(", "))
     }
 }
 
@@ -886,6 +656,8 @@ fn try_area_scan(game: &mut Game) -> String {
     let mut walls_found = 0;
     let mut out_of_bounds = 0;
     let mut tiles_revealed = 0;
+    let mut hazards = Vec::new();
+    let mut devices = Vec::new();
 
     // First, determine the current scan radius by checking how far we've already scanned
     let cardinal_directions = [(0, -1), (1, 0), (0, 1), (-1, 0)]; // North, East, South, West
@@ -938,11 +710,17 @@ fn try_area_scan(game: &mut Game) -> String {
                 if game.grid.is_blocked(scan_pos) {
                     obstacles_found.push(format!("({}, {})", scan_pos.x, scan_pos.y));
                     walls_found += 1;
+                    devices.extend(game.grid.describe_locked_door(scan_pos));
+                    hazards.extend(game.grid.describe_emitter(scan_pos, game.turns));
                 } else if let Some(item) = game.item_manager.get_item_at_position(scan_pos) {
                     items_found.push(format!("{} at ({}, {})", item.name, scan_pos.x, scan_pos.y));
                 } else {
                     empty_count += 1;
                 }
+                devices.extend(game.grid.describe_switch(scan_pos));
+                if let Some(hazard) = game.grid.describe_hazard(scan_pos, game.turns) {
+                    hazards.push(hazard);
+                }
             }
         }
     }
@@ -969,300 +747,198 @@ fn try_area_scan(game: &mut Game) -> String {
     };
 
     if tiles_revealed > 0 {
-        format!("{}. Revealed {} new tiles.", base_message, tiles_revealed)
+        format!("{}. Revealed {} new tiles.{}{}", base_message, tiles_revealed, hazard_summary(&hazards), device_summary(&devices))
     } else {
-        format!("{}. No new tiles revealed.", base_message)
+        format!("{}. No new tiles revealed.{}{}", base_message, hazard_summary(&hazards), device_summary(&devices))
     }
 }
 
 
-// Code parsing and execution
-fn parse_rust_code(code: &str) -> Vec<FunctionCall> {
-    let mut calls = Vec::new();
-    
-    let lines: Vec<&str> = code.lines().collect();
-    
-    for line in lines {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with("//") {
-            continue;
-        }
-        
-        // Parse move_bot() calls (also support legacy move() for backward compatibility)
-        if let Some(start) = trimmed.find("move_bot(").or_else(|| trimmed.find("move(")) {
-            let paren_offset = if trimmed[start..].starts_with("move_bot(") { 9 } else { 5 };
-            let after_paren = &trimmed[start + paren_offset..];
-            if let Some(end) = after_paren.find(')') {
-                let param = after_paren[..end].trim();
-                let dir = match param {
-                    "up" | "Up" | "\"up\"" | "\"Up\"" => Some((0, -1)),
-                    "down" | "Down" | "\"down\"" | "\"Down\"" => Some((0, 1)),
-                    "left" | "Left" | "\"left\"" | "\"Left\"" => Some((-1, 0)),
-                    "right" | "Right" | "\"right\"" | "\"Right\"" => Some((1, 0)),
-                    _ => None,
-                };
-                if let Some(d) = dir {
-                    calls.push(FunctionCall {
-                        function: RustFunction::Move,
-                        direction: Some(d),
-                        coordinates: None,
-                        level_number: None,
-                        boolean_param: None,
-                        message: None,
-                    });
-                }
-            }
-        }
-        // Parse grab() calls
-        else if trimmed.contains("grab()") {
-            calls.push(FunctionCall {
-                function: RustFunction::Grab,
-                direction: None,
-                coordinates: None,
-                level_number: None,
-                boolean_param: None,
-                message: None,
-            });
-        }
-        // Parse skip_this_level_because_i_say_so() calls
-        else if trimmed.contains("skip_this_level_because_i_say_so()") {
-            calls.push(FunctionCall {
-                function: RustFunction::SkipLevel,
-                direction: None,
-                coordinates: None,
-                level_number: None,
-                boolean_param: None,
-                message: None,
-            });
-        }
-        // Parse scan() calls
-        else if let Some(start) = trimmed.find("scan(") {
-            let after_paren = &trimmed[start + 5..];
-            if let Some(end) = after_paren.find(')') {
-                let param = after_paren[..end].trim();
-                
-                let dir = match param {
-                    "up" | "Up" | "\"up\"" | "\"Up\"" => Some((0, -1)),
-                    "down" | "Down" | "\"down\"" | "\"Down\"" => Some((0, 1)),
-                    "left" | "Left" | "\"left\"" | "\"Left\"" => Some((-1, 0)),
-                    "right" | "Right" | "\"right\"" | "\"Right\"" => Some((1, 0)),
-                    "current" | "Current" | "\"current\"" | "\"Current\"" => Some((0, 0)), // Special case for area scan
-                    _ => None,
-                };
-                if let Some(d) = dir {
-                    calls.push(FunctionCall {
-                        function: RustFunction::Scan,
-                        direction: Some(d),
-                        coordinates: None,
-                        level_number: None,
-                        boolean_param: None,
-                        message: None,
-                    });
-                }
-            }
-        }
-        // Parse laser::direction() calls
-        else if let Some(start) = trimmed.find("laser::direction(") {
-            let after_paren = &trimmed[start + 16..];
-            if let Some(end) = after_paren.find(')') {
-                let param = after_paren[..end].trim();
-                let dir = match param {
-                    "up" | "Up" | "\"up\"" | "\"Up\"" => Some((0, -1)),
-                    "down" | "Down" | "\"down\"" | "\"Down\"" => Some((0, 1)),
-                    "left" | "Left" | "\"left\"" | "\"Left\"" => Some((-1, 0)),
-                    "right" | "Right" | "\"right\"" | "\"Right\"" => Some((1, 0)),
-                    _ => None,
-                };
-                if let Some(d) = dir {
-                    calls.push(FunctionCall {
-                        function: RustFunction::LaserDirection,
-                        direction: Some(d),
-                        coordinates: None,
-                        level_number: None,
-                        boolean_param: None,
-                        message: None,
-                    });
-                }
-            }
-        }
-        // Parse laser::tile() calls
-        else if let Some(start) = trimmed.find("laser::tile(") {
-            let after_paren = &trimmed[start + 12..];
-            if let Some(end) = after_paren.find(')') {
-                let param = after_paren[..end].trim();
-                // Parse coordinates like (x,y) or x,y
-                let coords_str = param.trim_matches(|c| c == '(' || c == ')');
-                let parts: Vec<&str> = coords_str.split(',').collect();
-                if parts.len() == 2 {
-                    if let (Ok(x), Ok(y)) = (parts[0].trim().parse::<i32>(), parts[1].trim().parse::<i32>()) {
-                        calls.push(FunctionCall {
-                            function: RustFunction::LaserTile,
-                            direction: None,
-                            coordinates: Some((x, y)),
-                            level_number: None,
-                            boolean_param: None,
-                            message: None,
-                        });
-                    }
-                }
-            }
-        }
-        // Parse goto_this_level_because_i_say_so() calls
-        else if let Some(start) = trimmed.find("goto_this_level_because_i_say_so(") {
-            let after_paren = &trimmed[start + 33..];
-            if let Some(end) = after_paren.find(')') {
-                let param = after_paren[..end].trim();
-                if let Ok(level_num) = param.parse::<usize>() {
-                    calls.push(FunctionCall {
-                        function: RustFunction::GotoLevel,
-                        direction: None,
-                        coordinates: None,
-                        level_number: Some(level_num),
-                        boolean_param: None,
-                        message: None,
-                    });
-                }
-            }
-        }
-        // Parse open_door() calls
-        else if let Some(start) = trimmed.find("open_door(") {
-            let after_paren = &trimmed[start + 10..];
-            if let Some(end) = after_paren.find(')') {
-                let param = after_paren[..end].trim();
-                let bool_param = match param {
-                    "true" | "True" => Some(true),
-                    "false" | "False" => Some(false),
-                    _ => None,
-                };
-                if let Some(open_val) = bool_param {
-                    calls.push(FunctionCall {
-                        function: RustFunction::OpenDoor,
-                        direction: None,
-                        coordinates: None,
-                        level_number: None,
-                        boolean_param: Some(open_val),
-                        message: None,
-                    });
-                }
-            }
-        }
-    }
-    
-    calls
+// Code execution
+fn execute_function(game: &mut Game, call: FunctionCall) -> ExecutionOutcome {
+    run_action(game, call, execute_robot_action)
 }
 
-fn execute_function(game: &mut Game, call: FunctionCall) -> String {
+/// Perform a single robot action. Enemies, hazards and the win check are left to the
+/// turn scheduler; actions only advance `game.turns` by the turns they take.
+fn execute_robot_action(game: &mut Game, call: FunctionCall) -> ExecutionOutcome {
+    let acted = |message: String| ExecutionOutcome::Acted { message };
+    let error = |message: &str| ExecutionOutcome::Error { message: message.to_string() };
+
     let available = game.get_available_functions();
     if !available.contains(&call.function) {
-        return "Function not available".to_string();
+        return error("Function not available");
+    }
+
+    // Running out of energy ends the run, just like an enemy collision
+    if let Err(message) = game.spend_energy(call.function) {
+        let idx = game.level_idx;
+        game.load_level(idx);
+        return ExecutionOutcome::OutOfEnergy { message: format!("{} Level reset.", message) };
     }
     
     match call.function {
-        RustFunction::Move => {
-            if let Some((dx, dy)) = call.direction {
+        RustFunction::Move | RustFunction::MoveForward => {
+            let direction = if call.function == RustFunction::MoveForward { Some(game.robot.facing) } else { call.direction };
+            if let Some((dx, dy)) = direction {
+                game.robot.facing = (dx, dy); // The robot turns towards where it moves
                 let old_pos = game.robot.get_position();
-                try_move(game, dx, dy);
+                let target_pos = Pos { x: old_pos.0 + dx, y: old_pos.1 + dy };
+                let pushing_crate = game.grid.is_crate(target_pos);
                 game.turns += 1;
-                if game.robot.get_position() != old_pos {
-                    "Move executed".to_string()
-                } else {
-                    let target_pos = Pos { x: old_pos.0 + dx, y: old_pos.1 + dy };
-                    if game.grid.is_blocked(target_pos) {
-                        "Unknown Object Blocking Function".to_string()
-                    } else {
-                        "Move blocked".to_string()
+                if try_move(game, dx, dy) {
+                    ExecutionOutcome::Collision { message: ENEMY_COLLISION_MESSAGE.to_string() }
+                } else if game.robot.get_position() != old_pos {
+                    let new_pos = game.robot.get_pos();
+                    // Wading into mud takes a second turn
+                    let muddy = game.grid.terrain_at(new_pos) == Some(Terrain::Mud);
+                    if muddy {
+                        game.turns += 1;
                     }
+                    let message = if new_pos != target_pos {
+                        format!("Slid across the ice to ({}, {})", new_pos.x, new_pos.y)
+                    } else if muddy {
+                        "Move executed (mud: 2 turns)".to_string()
+                    } else if pushing_crate {
+                        "Pushed a crate".to_string()
+                    } else {
+                        "Move executed".to_string()
+                    };
+                    ExecutionOutcome::Moved { message }
+                } else if pushing_crate && game.grid.is_crate(target_pos) {
+                    ExecutionOutcome::Blocked { reason: "Crate push blocked by obstacle".to_string() }
+                } else if let Some(other) = game.other_robot_at(target_pos) {
+                    error(&format!("Move blocked by robot {}", other))
+                } else if game.grid.is_blocked(target_pos) {
+                    ExecutionOutcome::Blocked { reason: "Unknown Object Blocking Function".to_string() }
+                } else if game.grid.laser_beam_at(target_pos, game.turns) {
+                    error("Move blocked by a laser beam")
+                } else {
+                    error("Move blocked")
                 }
             } else {
-                "Direction required for move".to_string()
+                error("Direction required for move")
             }
         },
+        RustFunction::RotateLeft => {
+            game.robot.rotate_left();
+            acted(format!("Now facing {}", game.robot.facing_name()))
+        },
+        RustFunction::RotateRight => {
+            game.robot.rotate_right();
+            acted(format!("Now facing {}", game.robot.facing_name()))
+        },
+        // Grabbing and scanning take no turn
         RustFunction::Grab => {
-            try_grab(game).to_string()
+            acted(try_grab(game).to_string())
         },
         RustFunction::Scan => {
-            if let Some(dir) = call.direction {
-                if dir == (0, 0) {
-                    // Special case: scan("current") - scan 3x3 area around robot
-                    try_area_scan(game)
-                } else {
-                    // Normal directional scan
-                    try_scan(game, dir).to_string()
-                }
+            // scan() without a direction scans the way the robot is facing
+            let dir = call.direction.unwrap_or(game.robot.facing);
+            let summary = if dir == (0, 0) {
+                // Special case: scan("current") - scan 3x3 area around robot
+                try_area_scan(game)
             } else {
-                "Direction required for scan".to_string()
-            }
+                // Normal directional scan
+                try_scan(game, dir).to_string()
+            };
+            ExecutionOutcome::Scanned { summary }
         },
         RustFunction::LaserDirection => {
             if let Some(dir) = call.direction {
                 let result = game.fire_laser_direction(dir);
                 game.turns += 1;
-                // Move enemies after laser
-                if game.level_idx >= 3 && !game.enemy_step_paused {
-                    game.update_laser_effects();
-                    game.grid.move_enemies(Some(game.robot.get_position()), &game.stunned_enemies);
-                    if game.grid.check_enemy_collision(game.robot.get_position()) {
-                        let idx = game.level_idx;
-                        game.load_level(idx);
-                        return "ENEMY COLLISION! Level reset and randomized.".to_string();
-                    }
-                }
-                result
+                acted(result)
             } else {
-                "Direction required for laser".to_string()
+                error("Direction required for laser")
             }
         },
         RustFunction::LaserTile => {
             if let Some(coords) = call.coordinates {
                 let result = game.fire_laser_tile(coords);
                 game.turns += 1;
-                // Move enemies after laser
-                if game.level_idx >= 3 && !game.enemy_step_paused {
-                    game.update_laser_effects();
-                    game.grid.move_enemies(Some(game.robot.get_position()), &game.stunned_enemies);
-                    if game.grid.check_enemy_collision(game.robot.get_position()) {
-                        let idx = game.level_idx;
-                        game.load_level(idx);
-                        return "ENEMY COLLISION! Level reset and randomized.".to_string();
-                    }
-                }
-                result
+                acted(result)
             } else {
-                "Coordinates required for laser tile".to_string()
+                error("Coordinates required for laser tile")
             }
         },
         RustFunction::SkipLevel => {
-            game.skip_level()
+            acted(game.skip_level())
         },
         RustFunction::GotoLevel => {
             if let Some(level) = call.level_number {
-                game.goto_level(level)
+                acted(game.goto_level(level))
             } else {
-                "Level number required for goto_level".to_string()
+                error("Level number required for goto_level")
             }
         },
         RustFunction::OpenDoor => {
             if let Some(open) = call.boolean_param {
                 let result = game.open_door(open);
                 game.turns += 1;
-                // Move enemies after door action
-                if game.level_idx >= 3 && !game.enemy_step_paused {
-                    game.update_laser_effects();
-                    game.grid.move_enemies(Some(game.robot.get_position()), &game.stunned_enemies);
-                    if game.grid.check_enemy_collision(game.robot.get_position()) {
-                        let idx = game.level_idx;
-                        game.load_level(idx);
-                        return "ENEMY COLLISION! Level reset and randomized.".to_string();
-                    }
-                }
-                result
+                acted(result)
+            } else {
+                error("Boolean parameter required for open_door (true or false)")
+            }
+        },
+        RustFunction::SetAutoGrab => {
+            // A setting, not an action: it takes no turn
+            if let Some(enabled) = call.boolean_param {
+                game.robot.set_auto_grab(enabled);
+                acted(format!("Auto-grab {}", if enabled { "on" } else { "off" }))
             } else {
-                "Boolean parameter required for open_door (true or false)".to_string()
+                error("Boolean parameter required for set_auto_grab (true or false)")
+            }
+        },
+        RustFunction::Teleport => {
+            let Some(coords) = call.coordinates else {
+                return error("Coordinates required for teleport (x, y)");
+            };
+            match game.teleport(coords) {
+                Ok(message) => {
+                    // Teleporting takes several turns and enemies keep moving meanwhile
+                    game.turns += TELEPORT_TURN_COST;
+                    ExecutionOutcome::Moved { message }
+                }
+                Err(message) => ExecutionOutcome::Error { message },
+            }
+        },
+        RustFunction::Rewind => {
+            match game.rewind(call.level_number.unwrap_or(1)) {
+                Ok(message) => acted(message),
+                Err(message) => ExecutionOutcome::Error { message },
+            }
+        },
+        RustFunction::Emp => {
+            match game.fire_emp(call.level_number.unwrap_or(1) as u32) {
+                Ok(message) => {
+                    game.turns += 1;
+                    acted(message)
+                }
+                Err(message) => ExecutionOutcome::Error { message },
+            }
+        },
+        RustFunction::PrintMap => {
+            // Shown like println! output so it lands in the output popup and the logs
+            let map = game.map_ascii(false);
+            game.popup_system.show_println_output(map.clone());
+            game.println_outputs.push(map);
+            acted("print_map executed".to_string())
+        },
+        // Talking takes no turn
+        RustFunction::Say => {
+            let text = call.message.unwrap_or_default();
+            acted(game.say(&text))
+        },
+        RustFunction::SelectRobot => {
+            match game.select_robot(call.robot.unwrap_or(0)) {
+                Ok(message) => acted(message),
+                Err(message) => ExecutionOutcome::Error { message },
             }
         },
         // Print functions are handled separately in execute_rust_code
         RustFunction::Println | RustFunction::Eprintln | RustFunction::Panic => {
-            "Print functions handled separately".to_string()
+            acted(String::new())
         },
     }
 }
@@ -1335,18 +1011,49 @@ fn setup_file_watcher(file_path: &str) -> Option<Receiver<notify::Result<Event>>
     Some(rx)
 }
 
+/// Round-trip the player's serde structs through the current game state and show how each did
+#[cfg(not(target_arch = "wasm32"))]
+fn check_serde_structs(game: &mut Game, executor: &crate::code_executor::CodeExecutor, code: &str) {
+    let document = game.serde_state_document();
+    let content = match executor.check_serde_round_trip(code, &document) {
+        Ok(checks) => {
+            let lines: Vec<String> = checks.iter()
+                .map(|check| match &check.result {
+                    Ok(path) => crate::i18n::tr_with("game.serde_check_ok", &[("name", &check.name), ("path", path)]),
+                    Err(reason) => crate::i18n::tr_with("game.serde_check_failed", &[("name", &check.name), ("reason", reason)]),
+                })
+                .collect();
+            game.serde_round_trips = checks;
+            if lines.is_empty() { crate::i18n::tr("game.serde_check_none") } else { lines.join("\n") }
+        }
+        Err(error) => {
+            game.serde_round_trips.clear();
+            crate::i18n::tr_with("game.serde_check_error", &[("error", &error)])
+        }
+    };
+    game.log_execution_immediate(&format!("Serde round trip: {}", content));
+    game.popup_system.show_message(crate::i18n::tr("game.serde_check_title"), content, crate::popup::PopupType::Info, Some(6.0));
+}
+
 async fn execute_rust_code(game: &mut Game) -> String {
     game.log_execution_immediate("Starting execute_rust_code function");
+    game.popup_system.history.start_run();
+    game.combat_log.clear();
+    game.combat_log_scroll = 0;
     game.log_execution_immediate(&format!("Current code length: {} chars", game.current_code.len()));
     game.log_execution_immediate(&format!("Code preview (first 100 chars): '{}'", &game.current_code.chars().take(100).collect::<String>()));
 
+    if let Err(message) = game.use_daily_attempt() {
+        return message;
+    }
+
     let code_to_execute = if game.current_code.is_empty() {
         game.log_execution_immediate(&format!("Current code is empty, reading from file: {}", game.robot_code_path));
         // Fallback to reading from file if current_code is empty
         match crate::read_robot_code(&game.robot_code_path) {
             Ok(code) => {
                 game.log_execution_immediate(&format!("Successfully read {} chars from file", code.len()));
-                game.current_code = code.clone();
+                game.current_code = code.clone().into();
                 code
             },
             Err(e) => {
@@ -1356,7 +1063,7 @@ async fn execute_rust_code(game: &mut Game) -> String {
         }
     } else {
         game.log_execution_immediate(&format!("Using current_code directly ({} chars)", game.current_code.len()));
-        game.current_code.clone()
+        game.current_code.to_string()
     };
     
     // First, check syntax with Cargo (desktop only)
@@ -1369,7 +1076,9 @@ async fn execute_rust_code(game: &mut Game) -> String {
         }
 
         if let Some(ref mut checker) = game.rust_checker {
-            match checker.check_syntax_enhanced(&code_to_execute) {
+            // The GUI waits for the background check before executing, so this is usually instant
+            let check_result = checker.wait_for(&code_to_execute);
+            match check_result {
                 Ok(errors) => {
                     // Extract info we need before doing any game logging
                     let error_count = errors.len();
@@ -1383,6 +1092,7 @@ async fn execute_rust_code(game: &mut Game) -> String {
 
                     if has_errors {
                         game.log_execution_immediate("EARLY RETURN: Compilation errors detected");
+                        game.popup_system.show_compiler_help(&errors);
                         return format!("🔍 ENHANCED SYNTAX CHECK:\n{}\n\n⚠️ Your code has errors that prevent execution!", syntax_result);
                     } else if !errors.is_empty() {
                         game.log_execution_immediate("Warnings detected, continuing with execution");
@@ -1423,7 +1133,9 @@ async fn execute_rust_code(game: &mut Game) -> String {
 
         // Create a code executor
         match crate::code_executor::CodeExecutor::new() {
-            Ok(executor) => {
+            Ok(mut executor) => {
+                executor.set_remaining_energy(game.energy);
+                executor.set_random_seed(game.level_seed);
                 // Execute the code and capture output
                 match executor.execute_code(&code_to_execute) {
                     Ok(result) => {
@@ -1457,7 +1169,11 @@ async fn execute_rust_code(game: &mut Game) -> String {
                         game.log_execution_immediate(&format!("📥 STDERR CONTENT: '{}'", result.stderr));
                         if !result.stderr.is_empty() {
                             for line in result.stderr.lines() {
-                                if !line.trim().is_empty() {
+                                // Records from the log crate go to the Logs tab with their level
+                                if let Some(record) = crate::code_executor::LogRecord::parse(line) {
+                                    game.popup_system.show_log_record(&record);
+                                    game.log_records.push(record);
+                                } else if !line.trim().is_empty() {
                                     game.log_execution_immediate(&format!("❤️ SHOWING RED POPUP: '{}'", line));
                                     game.popup_system.show_eprintln_output(line.to_string());
                                     game.error_outputs.push(line.to_string());
@@ -1467,6 +1183,13 @@ async fn execute_rust_code(game: &mut Game) -> String {
                             game.log_execution_immediate("📥 STDERR WAS EMPTY - NO RED POPUP");
                         }
 
+                        // Serde levels feed the player's structs the real game state
+                        let serde_level = game.levels[game.level_idx].completion_flag.as_deref()
+                            .is_some_and(|flag| flag.starts_with("serde_round_trip"));
+                        if serde_level {
+                            check_serde_structs(game, &executor, &code_to_execute);
+                        }
+
                         // Clean up temp files
                         let _ = executor.cleanup();
                     },
@@ -1486,7 +1209,7 @@ async fn execute_rust_code(game: &mut Game) -> String {
     #[cfg(target_arch = "wasm32")]
     {
         // For WASM, fall back to parsing approach
-        let print_outputs = extract_print_statements_from_main(&code_to_execute);
+        let print_outputs = extract_print_statements_from_main(&code_to_execute, game.level_seed);
 
         for output in &print_outputs {
             if output.starts_with("stdout:") {
@@ -1501,57 +1224,115 @@ async fn execute_rust_code(game: &mut Game) -> String {
         }
     }
     
-    let calls = parse_rust_code_from_main(&code_to_execute);
+    let program = match game_core::ast::parse_program_with_seed(&code_to_execute, game.level_seed) {
+        Ok(program) => program,
+        Err(e) => {
+            game.log_execution_immediate(&format!("Program doesn't parse: {}", e));
+            return format!("❌ Syntax error at {}", e);
+        }
+    };
+    let calls = program.calls;
 
     game.log_execution_immediate(&format!("Parsed {} function calls: {:?}", calls.len(), calls));
 
+    // Functions the level doesn't allow stop the program before the robot does anything
+    let forbidden = game.forbidden_calls(&calls);
+    if !forbidden.is_empty() {
+        let available: Vec<&str> = game.get_gui_functions().into_iter().filter_map(game_core::capabilities::function_name).collect();
+        return format!(
+            "❌ This level doesn't allow {}. Functions you can use here: {}",
+            forbidden.iter().map(|name| format!("{}()", name)).collect::<Vec<_>>().join(", "),
+            available.iter().map(|name| format!("{}()", name)).collect::<Vec<_>>().join(", "),
+        );
+    }
+
+    // Programs that would run past the turn limit are flagged with the call that goes over
+    let functions: Vec<String> = program.environments.iter().map(|environment| environment.function.clone()).collect();
+    game.warn_turn_budget(&calls, &functions, execute_function);
+
+    if game.levels[game.level_idx].ownership_view {
+        game.ownership_timelines = game_core::ownership::analyze_ownership(&code_to_execute).unwrap_or_default();
+    }
+    game.watch.start_run(program.environments);
+
     if calls.is_empty() && game.println_outputs.is_empty() && game.error_outputs.is_empty() {
         game.log_execution_immediate("No valid function calls or print statements found");
         return "No valid function calls found".to_string();
     }
 
-    let mut results = Vec::new();
-
-    // Handle robot function calls if any
+    // Constructs the robot can't follow exactly are listed before the results
+    let mut notes: Vec<String> = program.diagnostics.iter().map(|diagnostic| format!("⚠️ {}", diagnostic)).collect();
+    let mut outcomes = Vec::new();
+    let mut halted = None;
+    let mut aborted = None;
+
+    // Handle robot function calls if any, within the execution budget
+    let budget = game_core::budget::ExecutionBudget::default();
+    let mut busy_secs = 0.0;
+    game.program_running = true;
+    game.cancel_requested = false;
     for (i, call) in calls.iter().enumerate() {
+        let allowed = if game.cancel_requested { Err(game_core::budget::Abort::Cancelled) } else { budget.check(i, busy_secs) };
+        if let Err(abort) = allowed {
+            game.log_execution_immediate(&abort.to_string());
+            aborted = Some(abort);
+            break;
+        }
         game.log_execution_immediate(&format!("Executing function call {}/{}: {:?}", i + 1, calls.len(), call));
-        let result = execute_function(game, call.clone());
-        game.log_execution_immediate(&format!("Function result: '{}'", result));
-        results.push(result.clone());
+        game.watch.step = i;
+        let robot_before = game.robot.get_pos();
+        let action_start = crash_protection::safe_get_time();
+        let outcome = execute_function(game, call.clone());
+        busy_secs += crash_protection::safe_get_time() - action_start;
+        game.log_execution_immediate(&format!("Function result: {:?}", outcome));
         
-        // Add delay if time slow is active
+        // Play the step out at the chosen execution speed; time slow stretches it further
+        let mut step_secs = game.menu.settings.execution_speed.step_seconds();
         if game.time_slow_active {
-            let frames_to_wait = (game.time_slow_duration_ms as f32 / 16.67).round() as i32; // Assuming ~60 FPS
-            for _ in 0..frames_to_wait {
-                crash_protection::safe_next_frame().await;
-            }
+            step_secs += game.time_slow_duration_ms as f64 / 1000.0;
+        }
+        if step_secs > 0.0 {
+            play_program_step(game, robot_before, step_secs).await;
         }
         
-        // Halt execution on blocking conditions or panic
-        if result.contains("Unknown Object Blocking Function") || 
-           result.contains("blocked by obstacle") || 
-           result.contains("Search blocked") {
-            results.push("EXECUTION HALTED! Rewrite your program to avoid obstacles.".to_string());
-            break;
-        } else if result.contains("💥 PANIC:") {
-            // Panic halts all further execution
-            results.push("EXECUTION HALTED! Program panicked.".to_string());
+        // Walls and running out of energy stop the program
+        halted = outcome.halt_message();
+        outcomes.push(outcome);
+        if halted.is_some() {
             break;
         }
     }
 
-    // If we only had print statements (no robot function calls), provide feedback
-    if calls.is_empty() && (!game.println_outputs.is_empty() || !game.error_outputs.is_empty()) {
-        results.push("Print statements executed successfully!".to_string());
+    game.program_running = false;
+    game.program_paused = false;
+    game.cancel_requested = false;
+    if let Some(abort) = &aborted {
+        notes.insert(0, format!("⛔ {}", abort));
     }
 
     // Show function results in popup if we have meaningful robot function calls
     if !calls.is_empty() {
-        game.popup_system.show_function_results(results.clone());
+        game.popup_system.show_function_results(&notes, &outcomes);
+    }
+
+    // Runs that compiled and weren't halted go into the code history
+    #[cfg(feature = "vcs")]
+    if halted.is_none() && aborted.is_none() {
+        game.record_code_history(&code_to_execute);
+    }
+
+    let mut results = notes;
+    results.extend(outcomes.iter().map(|outcome| outcome.to_string()));
+    results.extend(halted.map(str::to_string));
+    // If we only had print statements (no robot function calls), provide feedback
+    if calls.is_empty() && (!game.println_outputs.is_empty() || !game.error_outputs.is_empty()) {
+        results.push("Print statements executed successfully!".to_string());
     }
     
-    // Check tutorial progress after execution
-    game.check_tutorial_progress();
+    // Tutorial checks subscribe to the end of the run
+    game.publish(GameEvent::ProgramFinished);
+    game.dispatch_events();
+    game.check_plugin_tasks(&code_to_execute);
 
     // Check for level completion after execution
     game.check_end_condition();
@@ -1632,10 +1413,15 @@ fn draw_main_game_view(game: &mut Game) {
     safe_draw_operation(|| draw_game_info(game), "draw_game_info");
     safe_draw_operation(|| draw_tutorial_overlay(game), "draw_tutorial_overlay");
     safe_draw_operation(|| draw_time_slow_indicator(game), "draw_time_slow_indicator");
+    safe_draw_operation(|| draw_execution_controls(game), "draw_execution_controls");
+    safe_draw_operation(|| drawing::camera::draw_camera_controls(game), "draw_camera_controls");
+    safe_draw_operation(|| drawing::game_drawing::draw_sandbox_palette(game), "draw_sandbox_palette");
     safe_draw_operation(|| draw_controls_text(), "draw_controls_text");
     
     // Draw tabbed sidebar (Commands/Logs/Tasks/Editor)
     safe_draw_operation(|| drawing::ui_drawing::draw_tabbed_sidebar(game), "draw_tabbed_sidebar");
+    safe_draw_operation(|| drawing::ui_drawing::draw_inventory_panel(game), "draw_inventory_panel");
+    safe_draw_operation(|| draw_tile_inspector(game), "draw_tile_inspector");
     safe_draw_operation(|| draw_level_complete_overlay(game), "draw_level_complete_overlay");
     
     // Check if crash recovery was triggered this frame
@@ -1696,7 +1482,7 @@ fn window_conf() -> Conf {
 }
 
 // Real editor test mode that uses actual game systems
-async fn run_real_editor_test_mode(enable_all_logs: bool) {
+async fn run_real_editor_test_mode() {
     println!("🎮 REAL Editor Test Mode Started!");
     println!("  🖱️  Click and DRAG to select text");
     println!("  ⌨️  Hold Shift + Arrow keys to select text");
@@ -1712,10 +1498,6 @@ async fn run_real_editor_test_mode(enable_all_logs: bool) {
     let core_levels = embedded_levels::get_embedded_level_specs();
     let mut game = Game::new(core_levels.clone(), rng);
 
-    // Enable coordinate logs if --all-logs flag is present
-    game.enable_coordinate_logs = enable_all_logs;
-    game.enable_key_press_logs = enable_all_logs;
-
     // Force the editor to be active and set up a test level
     game.code_editor_active = true;
     game.current_code = r#"fn main() {
@@ -1728,7 +1510,7 @@ async fn run_real_editor_test_mode(enable_all_logs: bool) {
     // Click and drag to select text
     // Hold Shift + arrow keys to select lines
 
-}"#.to_string();
+}"#.into();
     game.cursor_position = game.current_code.len() - 20; // Position near the comment
 
     // Set up window
@@ -1785,21 +1567,10 @@ async fn run_real_editor_test_mode(enable_all_logs: bool) {
             let mut code_modified = false;
 
             // Handle character input
-            let mut current_char_pressed = None;
-            while let Some(character) = get_char_pressed() {
-                if character.is_ascii() && !character.is_control() && character != ' ' {
-                    current_char_pressed = Some(character);
-
-                    // Delete selection first if it exists
-                    if game.delete_selection() {
-                        code_modified = true;
-                    }
-
-                    game.current_code.insert(game.cursor_position, character);
-                    game.cursor_position += 1;
-                    game.save_undo_state_if_needed(false); // Save undo state for typing
-                    code_modified = true;
-                }
+            let typed = gamestate::cursor::read_typed_text();
+            if game.insert_typed_text(&typed) {
+                game.save_undo_state_if_needed(false); // Save undo state for typing
+                code_modified = true;
             }
 
             // Handle space and other input
@@ -1816,12 +1587,13 @@ async fn run_real_editor_test_mode(enable_all_logs: bool) {
             // Tab key handling
             if is_key_pressed(KeyCode::Tab) {
                 if !game.accept_autocomplete() {
-                    // Insert 4 spaces if no autocomplete
+                    // Indent if no autocomplete
                     if game.delete_selection() {
                         code_modified = true;
                     }
-                    game.current_code.insert_str(game.cursor_position, "    ");
-                    game.cursor_position += 4;
+                    let indent = game.menu.settings.editor.indent_unit();
+                    game.current_code.insert_str(game.cursor_position, &indent);
+                    game.cursor_position += indent.len();
                     game.save_undo_state_if_needed(false); // Save undo state for tab
                     code_modified = true;
                 }
@@ -1831,8 +1603,9 @@ async fn run_real_editor_test_mode(enable_all_logs: bool) {
             if is_key_pressed(KeyCode::Backspace) {
                 if !game.delete_selection() && game.cursor_position > 0 {
                     game.save_undo_state_if_needed(true); // Save undo state before backspace
-                    game.current_code.remove(game.cursor_position - 1);
-                    game.cursor_position -= 1;
+                    let previous = game.current_code.prev_grapheme_boundary(game.cursor_position);
+                    game.current_code.remove_range(previous..game.cursor_position);
+                    game.cursor_position = previous;
                     code_modified = true;
                 }
             }
@@ -1868,18 +1641,14 @@ async fn run_real_editor_test_mode(enable_all_logs: bool) {
 
             // Centralized hotkey system - handle all configured hotkeys
             let mut hotkey_handled = false;
-            for key_code in [
-                KeyCode::Enter, KeyCode::S, KeyCode::Tab, KeyCode::Z, KeyCode::Y,
-                KeyCode::C, KeyCode::V, KeyCode::X, KeyCode::A, KeyCode::F,
-                KeyCode::H, KeyCode::G, KeyCode::Slash, KeyCode::D, KeyCode::K,
-                KeyCode::GraveAccent
-            ] {
+            cancel_chord_on_other_keys(&mut game);
+            for key_code in HOTKEY_KEYS {
                 if is_key_pressed(key_code) {
                     game.log_key_immediate(&format!("Key pressed: {:?} (ctrl:{}, shift:{}, alt:{})", key_code, ctrl_held, shift_held, alt_held));
                     if game.handle_hotkey(key_code, ctrl_held, shift_held, alt_held) {
                         game.log_key_immediate(&format!("✅ Handled hotkey via centralized system: {:?} (ctrl:{}, shift:{}, alt:{})", key_code, ctrl_held, shift_held, alt_held));
-                        // Some hotkeys might modify code (like paste, undo, etc.)
-                        if matches!(key_code, KeyCode::V | KeyCode::Z | KeyCode::Y | KeyCode::X) {
+                        // Some hotkeys might modify code (like paste, undo, comment, etc.)
+                        if matches!(key_code, KeyCode::V | KeyCode::Z | KeyCode::Y | KeyCode::X | KeyCode::Slash | KeyCode::C | KeyCode::U) {
                             code_modified = true;
                         }
                         hotkey_handled = true;
@@ -1891,7 +1660,7 @@ async fn run_real_editor_test_mode(enable_all_logs: bool) {
             }
 
             // Hotkey support for clipboard and undo operations (fallback for individual handling)
-            if ctrl_held {
+            if ctrl_held && !hotkey_handled {
                 if is_key_pressed(KeyCode::C) {
                     if game.copy_to_clipboard() {
                         println!("📋 Copied selected text to clipboard!");
@@ -1966,7 +1735,7 @@ async fn run_real_editor_test_mode(enable_all_logs: bool) {
 }
 
 // Robot command test mode with button interface
-async fn run_command_test_mode(enable_all_logs: bool) {
+async fn run_command_test_mode() {
     println!("🎮 Robot Command Test Mode Started!");
     println!("  🖱️  Click buttons to test robot commands");
     println!("  🤖 Available commands: move_bot, scan, grab, laser");
@@ -1981,10 +1750,6 @@ async fn run_command_test_mode(enable_all_logs: bool) {
     let core_levels = embedded_levels::get_embedded_level_specs();
     let mut game = Game::new(core_levels.clone(), rng);
 
-    // Enable coordinate logs if --all-logs flag is present
-    game.enable_coordinate_logs = enable_all_logs;
-    game.enable_key_press_logs = enable_all_logs;
-
     // Set up a simple level for testing
     game.level_idx = 1; // Use level 2 which has more space
     game.load_level(1);
@@ -2001,7 +1766,7 @@ async fn run_command_test_mode(enable_all_logs: bool) {
 
     println!("Hello from the robot!");
 }
-"#.to_string();
+"#.into();
     game.cursor_position = game.current_code.len() - 20;
 
     // Set up window
@@ -2060,21 +1825,10 @@ async fn run_command_test_mode(enable_all_logs: bool) {
             let mut code_modified = false;
 
             // Handle character input
-            let mut current_char_pressed = None;
-            while let Some(character) = get_char_pressed() {
-                if character.is_ascii() && !character.is_control() && character != ' ' {
-                    current_char_pressed = Some(character);
-
-                    // Delete selection first if it exists
-                    if game.delete_selection() {
-                        code_modified = true;
-                    }
-
-                    game.current_code.insert(game.cursor_position, character);
-                    game.cursor_position += 1;
-                    game.save_undo_state_if_needed(false); // Save undo state for typing
-                    code_modified = true;
-                }
+            let typed = gamestate::cursor::read_typed_text();
+            if game.insert_typed_text(&typed) {
+                game.save_undo_state_if_needed(false); // Save undo state for typing
+                code_modified = true;
             }
 
             // Handle space and other input
@@ -2091,12 +1845,13 @@ async fn run_command_test_mode(enable_all_logs: bool) {
             // Tab key handling
             if is_key_pressed(KeyCode::Tab) {
                 if !game.accept_autocomplete() {
-                    // Insert 4 spaces if no autocomplete
+                    // Indent if no autocomplete
                     if game.delete_selection() {
                         code_modified = true;
                     }
-                    game.current_code.insert_str(game.cursor_position, "    ");
-                    game.cursor_position += 4;
+                    let indent = game.menu.settings.editor.indent_unit();
+                    game.current_code.insert_str(game.cursor_position, &indent);
+                    game.cursor_position += indent.len();
                     game.save_undo_state_if_needed(false); // Save undo state for tab
                     code_modified = true;
                 }
@@ -2106,8 +1861,9 @@ async fn run_command_test_mode(enable_all_logs: bool) {
             if is_key_pressed(KeyCode::Backspace) {
                 if !game.delete_selection() && game.cursor_position > 0 {
                     game.save_undo_state_if_needed(true); // Save undo state before backspace
-                    game.current_code.remove(game.cursor_position - 1);
-                    game.cursor_position -= 1;
+                    let previous = game.current_code.prev_grapheme_boundary(game.cursor_position);
+                    game.current_code.remove_range(previous..game.cursor_position);
+                    game.cursor_position = previous;
                     code_modified = true;
                 }
             }
@@ -2143,18 +1899,14 @@ async fn run_command_test_mode(enable_all_logs: bool) {
 
             // Centralized hotkey system - handle all configured hotkeys
             let mut hotkey_handled = false;
-            for key_code in [
-                KeyCode::Enter, KeyCode::S, KeyCode::Tab, KeyCode::Z, KeyCode::Y,
-                KeyCode::C, KeyCode::V, KeyCode::X, KeyCode::A, KeyCode::F,
-                KeyCode::H, KeyCode::G, KeyCode::Slash, KeyCode::D, KeyCode::K,
-                KeyCode::GraveAccent
-            ] {
+            cancel_chord_on_other_keys(&mut game);
+            for key_code in HOTKEY_KEYS {
                 if is_key_pressed(key_code) {
                     game.log_key_immediate(&format!("Key pressed: {:?} (ctrl:{}, shift:{}, alt:{})", key_code, ctrl_held, shift_held, alt_held));
                     if game.handle_hotkey(key_code, ctrl_held, shift_held, alt_held) {
                         game.log_key_immediate(&format!("✅ Handled hotkey via centralized system: {:?} (ctrl:{}, shift:{}, alt:{})", key_code, ctrl_held, shift_held, alt_held));
-                        // Some hotkeys might modify code (like paste, undo, etc.)
-                        if matches!(key_code, KeyCode::V | KeyCode::Z | KeyCode::Y | KeyCode::X) {
+                        // Some hotkeys might modify code (like paste, undo, comment, etc.)
+                        if matches!(key_code, KeyCode::V | KeyCode::Z | KeyCode::Y | KeyCode::X | KeyCode::Slash | KeyCode::C | KeyCode::U) {
                             code_modified = true;
                         }
                         hotkey_handled = true;
@@ -2214,8 +1966,9 @@ async fn run_command_test_mode(enable_all_logs: bool) {
                 level_number: None,
                 boolean_param: None,
                 message: None,
+                robot: None,
             };
-            last_result = execute_function(&mut game, call);
+            last_result = execute_function(&mut game, call).to_string();
         }
 
         button_y += button_height + button_spacing;
@@ -2228,8 +1981,9 @@ async fn run_command_test_mode(enable_all_logs: bool) {
                 level_number: None,
                 boolean_param: None,
                 message: None,
+                robot: None,
             };
-            last_result = execute_function(&mut game, call);
+            last_result = execute_function(&mut game, call).to_string();
         }
 
         button_y += button_height + button_spacing;
@@ -2242,8 +1996,9 @@ async fn run_command_test_mode(enable_all_logs: bool) {
                 level_number: None,
                 boolean_param: None,
                 message: None,
+                robot: None,
             };
-            last_result = execute_function(&mut game, call);
+            last_result = execute_function(&mut game, call).to_string();
         }
 
         button_y += button_height + button_spacing;
@@ -2256,8 +2011,9 @@ async fn run_command_test_mode(enable_all_logs: bool) {
                 level_number: None,
                 boolean_param: None,
                 message: None,
+                robot: None,
             };
-            last_result = execute_function(&mut game, call);
+            last_result = execute_function(&mut game, call).to_string();
         }
 
         // Scan section
@@ -2274,8 +2030,9 @@ async fn run_command_test_mode(enable_all_logs: bool) {
                 level_number: None,
                 boolean_param: None,
                 message: None,
+                robot: None,
             };
-            last_result = execute_function(&mut game, call);
+            last_result = execute_function(&mut game, call).to_string();
         }
 
         button_y += button_height + button_spacing;
@@ -2288,8 +2045,9 @@ async fn run_command_test_mode(enable_all_logs: bool) {
                 level_number: None,
                 boolean_param: None,
                 message: None,
+                robot: None,
             };
-            last_result = execute_function(&mut game, call);
+            last_result = execute_function(&mut game, call).to_string();
         }
 
         // Grab section
@@ -2306,8 +2064,9 @@ async fn run_command_test_mode(enable_all_logs: bool) {
                 level_number: None,
                 boolean_param: None,
                 message: None,
+                robot: None,
             };
-            last_result = execute_function(&mut game, call);
+            last_result = execute_function(&mut game, call).to_string();
         }
 
         // Laser section (if available)
@@ -2325,8 +2084,9 @@ async fn run_command_test_mode(enable_all_logs: bool) {
                     level_number: None,
                     boolean_param: None,
                     message: None,
+                    robot: None,
                 };
-                last_result = execute_function(&mut game, call);
+                last_result = execute_function(&mut game, call).to_string();
             }
         }
 
@@ -2398,22 +2158,282 @@ fn draw_button(text: &str, rect: (f32, f32, f32, f32), mouse_x: f32, mouse_y: f3
     let text_y = y + (height + text_dims.height) / 2.0;
     draw_text(text, text_x, text_y, text_size, WHITE);
 
-    is_clicked
-}
+    is_clicked
+}
+
+// Main function for desktop
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    // Parse command line arguments (subcommands, with the old flags still accepted)
+    let cli = cli::Cli::parse();
+    let log_spec = match game_log::LogSpec::parse(&cli.log_spec()) {
+        Ok(spec) => spec,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(2);
+        }
+    };
+    let command = cli.into_command();
+
+    // Debug as the default level also turns on the extra startup checks and test output
+    let enable_all_logs = log_spec.default_level() >= log::LevelFilter::Debug;
+    game_log::init(log_spec);
+
+    // Commands that don't need a window run before macroquad opens one,
+    // so they also work in CI and over SSH
+    match command {
+        cli::Command::TestErrorSystem => {
+            info!("Testing enhanced error system");
+            test_enhanced_errors::test_error_system();
+        }
+        cli::Command::CheckCode { code } => {
+            let result = check_code_manually(&code);
+            println!("{}", result);
+        }
+        cli::Command::ValidateLevel { paths } => {
+            if !run_validate_levels(&paths) {
+                std::process::exit(1);
+            }
+        }
+        cli::Command::EditorScenario { paths } => {
+            if !editor_scenarios::run_scenarios(&paths) {
+                std::process::exit(1);
+            }
+        }
+        #[cfg(feature = "steam")]
+        cli::Command::WorkshopUpload { pack, title, description } => {
+            match steam::upload_pack(&pack, &title, &description) {
+                Ok(id) => println!("Published {} as Workshop item {}", pack.display(), id),
+                Err(e) => {
+                    eprintln!("❌ Failed to publish {}: {}", pack.display(), e);
+                    std::process::exit(1);
+                }
+            }
+            steam::shutdown();
+        }
+        cli::Command::Serve { port, dir } => {
+            if let Err(e) = cli::serve_directory(&dir, port) {
+                eprintln!("❌ Failed to serve {} on port {}: {}", dir.display(), port, e);
+                std::process::exit(1);
+            }
+        }
+        cli::Command::Bench { file, level, level_file, runs, seed, json } => {
+            // The game is only simulated, never drawn; any screen size will do
+            crash_protection::set_headless_viewport(1280.0, 800.0);
+            if let Err(e) = run_bench(&file, level, level_file.as_deref(), runs, seed, json) {
+                eprintln!("❌ Benchmark failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        cli::Command::GenerateLevel { width, height, density, enemies, items, theme, seed, out } => {
+            let params = level::generator::GeneratorParams {
+                width,
+                height,
+                obstacle_density: density,
+                enemy_count: enemies,
+                item_count: items,
+                theme: level::generator::LevelTheme::parse(&theme).unwrap_or(level::generator::LevelTheme::Maze),
+            };
+            let mut rng = match seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            let config = level::generator::generate_level(&params, &mut rng);
+            match level::generator::level_to_yaml(&config) {
+                Ok(yaml) => match out {
+                    Some(path) => {
+                        if let Err(e) = std::fs::write(&path, yaml) {
+                            eprintln!("❌ Failed to write {}: {}", path.display(), e);
+                            std::process::exit(1);
+                        }
+                        println!("Generated level written to {}", path.display());
+                    }
+                    None => print!("{}", yaml),
+                },
+                Err(e) => {
+                    eprintln!("❌ Failed to serialize level: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        command => macroquad::Window::from_config(window_conf(), desktop_main(command, enable_all_logs)),
+    }
+}
+
+// Main function for WASM
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    // For WASM, macroquad handles the main function differently
+    // The actual game logic is in lib.rs
+}
+
+/// Show one program step at `step_secs`: the robot glides to its new tile while the game keeps
+/// drawing, and pausing holds playback here until it's resumed
+async fn play_program_step(game: &mut Game, from: Pos, step_secs: f64) {
+    let start = crash_protection::safe_get_time();
+    let to = game.robot.get_pos();
+    if from != to {
+        game.robot_animation = Some(gamestate::MoveAnimation { from, to, start, duration: step_secs * 0.8 });
+    }
+    loop {
+        handle_execution_controls_input(game);
+        if is_key_pressed(KeyCode::Space) {
+            game.program_paused = !game.program_paused;
+        }
+        if is_key_pressed(KeyCode::Escape) && !game.popup_system.is_showing() {
+            game.cancel_requested = true;
+        }
+        if is_key_pressed(KeyCode::W) && is_key_down(KeyCode::LeftControl) && is_key_down(KeyCode::LeftShift) {
+            game.toggle_editor_tab(EditorTab::Watch);
+        }
+        game.update_popup_system(crash_protection::safe_get_frame_time());
+        draw_main_game_view(game);
+        game.draw_popups();
+        crash_protection::safe_next_frame().await;
+        if game.cancel_requested || (!game.program_paused && crash_protection::safe_get_time() - start >= step_secs) {
+            break;
+        }
+    }
+    game.robot_animation = None;
+}
+
+/// Clicks on the execution speed buttons and the pause and stop buttons. Returns true if one was clicked.
+fn handle_execution_controls_input(game: &mut Game) -> bool {
+    if !is_mouse_button_pressed(MouseButton::Left) {
+        return false;
+    }
+    let (mouse_x, mouse_y) = crash_protection::safe_mouse_position();
+    let mouse = vec2(mouse_x, mouse_y);
+    let controls = execution_controls();
+    if game.program_running && controls.pause.contains(mouse) {
+        game.program_paused = !game.program_paused;
+        return true;
+    }
+    if game.program_running && controls.stop.contains(mouse) {
+        game.cancel_requested = true;
+        return true;
+    }
+    if let Some((speed, _)) = controls.speeds.iter().find(|(_, rect)| rect.contains(mouse)) {
+        if game.menu.settings.execution_speed != *speed {
+            game.menu.settings.execution_speed = *speed;
+            let _ = game.menu.settings.save();
+        }
+        return true;
+    }
+    false
+}
+
+/// Sandbox palette clicks pick a tool; clicks on the grid use it on the tile under the mouse.
+/// Returns true if the click was used.
+fn handle_sandbox_input(game: &mut Game) -> bool {
+    if !game.in_sandbox() || !is_mouse_button_pressed(MouseButton::Left) || game.popup_system.is_showing() {
+        return false;
+    }
+    let (mouse_x, mouse_y) = crash_protection::safe_mouse_position();
+    let mouse = vec2(mouse_x, mouse_y);
+    if let Some((_, tool)) = drawing::game_drawing::sandbox_palette().into_iter().find(|(rect, _)| rect.contains(mouse)) {
+        game.sandbox_tool = Some(tool);
+        return true;
+    }
+    let controls = drawing::camera::camera_controls();
+    if [controls.zoom_out, controls.reset, controls.zoom_in, controls.follow].iter().any(|rect| rect.contains(mouse)) {
+        return false;
+    }
+    match game.grid_camera.view(game).screen_to_tile(mouse, game) {
+        Some(pos) => game.sandbox_place(pos),
+        None => false,
+    }
+}
+
+/// Mouse-wheel zoom and drag to pan over the grid, plus the camera buttons. Returns true if a
+/// button was clicked. Zoom changes are saved as the player's preference.
+fn handle_camera_input(game: &mut Game) -> bool {
+    use drawing::camera::{camera_controls, grid_viewport, ZOOM_STEP};
+    let (mouse_x, mouse_y) = crash_protection::safe_mouse_position();
+    let mouse = vec2(mouse_x, mouse_y);
+    let controls = camera_controls();
+    let over_buttons = [controls.zoom_out, controls.reset, controls.zoom_in, controls.follow].iter().any(|rect| rect.contains(mouse));
+    let mut camera = std::mem::take(&mut game.grid_camera);
+    let mut zoom_changed = false;
+    let mut clicked = false;
+
+    if is_mouse_button_pressed(MouseButton::Left) && over_buttons {
+        clicked = true;
+        let center = grid_viewport().center();
+        if controls.zoom_out.contains(mouse) {
+            zoom_changed = camera.zoom_by(game, 1.0 / ZOOM_STEP, center);
+        } else if controls.zoom_in.contains(mouse) {
+            zoom_changed = camera.zoom_by(game, ZOOM_STEP, center);
+        } else if controls.reset.contains(mouse) {
+            zoom_changed = camera.zoom_by(game, 1.0 / camera.zoom, center);
+        } else {
+            camera.toggle_follow(game);
+        }
+    } else if grid_viewport().contains(mouse) {
+        let (_, wheel) = crash_protection::safe_mouse_wheel();
+        if wheel != 0.0 {
+            let factor = if wheel > 0.0 { ZOOM_STEP } else { 1.0 / ZOOM_STEP };
+            zoom_changed = camera.zoom_by(game, factor, mouse);
+        }
+    }
+
+    // Left drags pan unless they started on a button or the sandbox palette places things with
+    // the left button; middle and right drags always do
+    let down = is_mouse_button_down(MouseButton::Middle)
+        || is_mouse_button_down(MouseButton::Right)
+        || (is_mouse_button_down(MouseButton::Left) && !game.in_sandbox() && (camera.is_dragging() || !over_buttons));
+    camera.drag(game, mouse, down);
 
-// Main function for desktop
-#[cfg(not(target_arch = "wasm32"))]
+    if zoom_changed {
+        game.menu.settings.grid_zoom = camera.zoom;
+        let _ = game.menu.settings.save();
+    }
+    game.grid_camera = camera;
+    clicked
+}
 
-#[macroquad::main(window_conf)]
-async fn main() {
-    desktop_main().await;
+/// Advance "show me" playback and handle Esc to stop it. Returns false if no playback is running.
+fn update_solution_playback(game: &mut Game, now: f64) -> bool {
+    let Some(playback) = game.solution_playback.as_mut() else { return false };
+    if is_key_pressed(KeyCode::Escape) {
+        game.stop_solution_playback();
+        return true;
+    }
+    if let Some(call) = playback.next_call(now) {
+        let from = game.robot.get_pos();
+        let result = execute_function(game, call);
+        let to = game.robot.get_pos();
+        if from != to {
+            game.robot_animation = Some(gamestate::MoveAnimation { from, to, start: now, duration: PLAYBACK_STEP_SECS * 0.8 });
+        }
+        if let Some(playback) = game.solution_playback.as_mut() {
+            playback.last_result = Some(result.to_string());
+        }
+    }
+    true
 }
 
-// Main function for WASM
-#[cfg(target_arch = "wasm32")]
-fn main() {
-    // For WASM, macroquad handles the main function differently
-    // The actual game logic is in lib.rs
+/// Scrolling and closing the solution comparison screen. Returns false if it isn't open.
+fn handle_solution_comparison_input(game: &mut Game) -> bool {
+    let Some(comparison) = game.solution_comparison.as_mut() else { return false };
+    let visible_rows = solution_comparison_visible_rows();
+    if is_key_pressed(KeyCode::Escape) {
+        game.solution_comparison = None;
+    } else if is_key_pressed(KeyCode::Up) {
+        comparison.scroll_by(-1, visible_rows);
+    } else if is_key_pressed(KeyCode::Down) {
+        comparison.scroll_by(1, visible_rows);
+    } else if is_key_pressed(KeyCode::PageUp) {
+        comparison.scroll_by(-(visible_rows as isize), visible_rows);
+    } else if is_key_pressed(KeyCode::PageDown) {
+        comparison.scroll_by(visible_rows as isize, visible_rows);
+    } else {
+        let (_, wheel) = crash_protection::safe_mouse_wheel();
+        if wheel != 0.0 {
+            comparison.scroll_by(if wheel > 0.0 { -3 } else { 3 }, visible_rows);
+        }
+    }
+    true
 }
 
 // Helper function to cache game state when exiting
@@ -2499,9 +2519,7 @@ async fn run_test_mode(test_file: String, enable_all_logs: bool) {
     let rng = StdRng::seed_from_u64(TEST_SEED);
     let core_levels = embedded_levels::get_embedded_level_specs();
     let mut game = Game::new(core_levels, rng);
-    game.enable_coordinate_logs = enable_all_logs;
-    game.enable_key_press_logs = enable_all_logs;
-    game.current_code = test_code.clone();
+    game.current_code = test_code.clone().into();
     
     // Load level 0 for testing
     game.load_level(0);
@@ -2548,6 +2566,13 @@ async fn run_test_mode(test_file: String, enable_all_logs: bool) {
         println!("Level completed!");
     } else {
         println!("Level not completed");
+        // Full map (including unexplored tiles) to see what went wrong
+        println!("\n--- Final Map ---");
+        print!("{}", game.map_ascii(true));
+        if enable_all_logs {
+            println!("\n--- Final Map (JSON) ---");
+            println!("{}", game.map_json());
+        }
     }
     
     println!("\n=== Test Mode Complete ===");
@@ -2557,7 +2582,7 @@ async fn run_test_mode(test_file: String, enable_all_logs: bool) {
 #[cfg(not(target_arch = "wasm32"))]
 async fn execute_test_code(game: &mut Game, code: &str) -> String {
     // Extract and display print statements
-    let print_outputs = extract_print_statements_from_main(code);
+    let print_outputs = extract_print_statements_from_main(code, game.level_seed);
     
     for output in &print_outputs {
         if output.starts_with("stdout:") {
@@ -2576,55 +2601,52 @@ async fn execute_test_code(game: &mut Game, code: &str) -> String {
         }
     }
     
-    let calls = parse_rust_code_from_main(code);
+    let calls = parse_rust_code_from_main(code, game.level_seed);
     if calls.is_empty() && print_outputs.is_empty() {
         return "No valid function calls found".to_string();
     }
     
-    let mut results = Vec::new();
+    let mut outcomes = Vec::new();
     
     // Handle robot function calls
     for call in &calls {
-        let result = execute_function(game, call.clone());
-        results.push(result.clone());
+        let outcome = execute_function(game, call.clone());
         
-        println!("Robot Action: {:?} -> {}", call.function, result);
+        println!("Robot Action: {:?} -> {}", call.function, outcome);
+        print!("{}", game.map_ascii(false));
         
-        // Halt execution on blocking conditions or panic
-        if result.contains("Unknown Object Blocking Function") || 
-           result.contains("blocked by obstacle") || 
-           result.contains("Search blocked") {
-            results.push("EXECUTION HALTED! Rewrite your program to avoid obstacles.".to_string());
-            break;
-        } else if result.contains("💥 PANIC:") {
-            results.push("EXECUTION HALTED! Program panicked.".to_string());
+        // Walls and running out of energy stop the program
+        let halts = outcome.halt_message().is_some();
+        outcomes.push(outcome);
+        if halts {
             break;
         }
     }
     
     // Show what function results popup would contain
     if !calls.is_empty() {
-        let meaningful_results: Vec<String> = results
+        let mut meaningful_results: Vec<String> = outcomes
             .iter()
-            .filter(|r| !r.is_empty() && 
-                        !r.contains("executed") && 
-                        !r.contains("Print functions handled separately") &&
-                        r.as_str() != "No valid function calls found")
-            .cloned()
+            .filter(|outcome| !outcome.is_routine())
+            .map(|outcome| outcome.to_string())
             .collect();
+        meaningful_results.extend(outcomes.last().and_then(|outcome| outcome.halt_message()).map(str::to_string));
         
         if !meaningful_results.is_empty() {
             println!("Message Popup: 🤖 Robot Action Results - {}", meaningful_results.join("\n"));
         }
     }
     
+    let mut results: Vec<String> = outcomes.iter().map(|outcome| outcome.to_string()).collect();
+    results.extend(outcomes.last().and_then(|outcome| outcome.halt_message()).map(str::to_string));
     // If we only had print statements (no robot function calls), provide feedback
     if calls.is_empty() && !print_outputs.is_empty() {
         results.push("Print statements executed successfully!".to_string());
     }
     
-    // Check tutorial progress and level completion
-    game.check_tutorial_progress();
+    // Tutorial checks subscribe to the end of the run; then the level completion check
+    game.publish(GameEvent::ProgramFinished);
+    game.dispatch_events();
     game.check_end_condition();
     
     results.join("; ")
@@ -2632,9 +2654,198 @@ async fn execute_test_code(game: &mut Game, code: &str) -> String {
 
 const TEST_SEED: u64 = 0xDEADBEEF;
 
+// Validate level files for the validate-level command, returns false if any file has problems
+#[cfg(not(target_arch = "wasm32"))]
+fn run_validate_levels(paths: &[std::path::PathBuf]) -> bool {
+    let files = level::collect_level_files(paths);
+    if files.is_empty() {
+        eprintln!("❌ No .yaml, .json, .toml or .rlgpack level files found");
+        return false;
+    }
+
+    let mut failed = 0;
+    for file in &files {
+        let problems = level::validate_level_file(file);
+        if problems.is_empty() {
+            println!("✅ {}", file.display());
+        } else {
+            failed += 1;
+            println!("❌ {}", file.display());
+            for problem in &problems {
+                println!("    - {}", problem);
+            }
+        }
+    }
+
+    println!("\n{} of {} level files passed validation", files.len() - failed, files.len());
+    failed == 0
+}
+
+// Run robot code on a level and write every executed step as JSON for the export-replay command
+#[cfg(not(target_arch = "wasm32"))]
+async fn run_export_replay(code_file: &str, level_idx: usize, out: Option<&str>) -> Result<(), String> {
+    let code = std::fs::read_to_string(code_file)
+        .map_err(|e| format!("Error reading code file '{}': {}", code_file, e))?;
+
+    let rng = StdRng::seed_from_u64(TEST_SEED);
+    let core_levels = embedded_levels::get_embedded_level_specs();
+    if level_idx >= core_levels.len() {
+        return Err(format!("Level {} does not exist (max available: {})", level_idx, core_levels.len().saturating_sub(1)));
+    }
+
+    let mut game = Game::new(core_levels, rng);
+    game.level_idx = level_idx;
+    game.load_level(level_idx);
+    game.current_code = code.clone().into();
+
+    let enemy_positions = |game: &Game| -> Vec<(i32, i32)> {
+        game.grid.enemies.iter().map(|e| (e.pos.x, e.pos.y)).collect()
+    };
+
+    let start = game.robot.get_position();
+    let mut steps = vec![serde_json::json!({
+        "step": 0,
+        "action": "start",
+        "result": "",
+        "robot": [start.0, start.1],
+        "enemies": enemy_positions(&game),
+        "turns": game.turns,
+    })];
+
+    let level_seed = game.level_seed;
+    for (i, call) in parse_rust_code_from_main(&code, level_seed).into_iter().enumerate() {
+        let action = format!("{:?}", call.function);
+        let result = execute_function(&mut game, call);
+        let pos = game.robot.get_position();
+        steps.push(serde_json::json!({
+            "step": i + 1,
+            "action": action,
+            "result": result.to_string(),
+            "robot": [pos.0, pos.1],
+            "enemies": enemy_positions(&game),
+            "turns": game.turns,
+        }));
+
+        if result.halt_message().is_some() {
+            break;
+        }
+    }
+    game.check_end_condition();
+
+    let step_count = steps.len();
+    let replay = serde_json::json!({
+        "level_index": level_idx,
+        "level_name": game.levels[level_idx].name,
+        "seed": TEST_SEED,
+        "code": code,
+        "steps": steps,
+        "output": extract_print_statements_from_main(&code, level_seed),
+        "completed": game.finished,
+    });
+    let json = serde_json::to_string_pretty(&replay).map_err(|e| e.to_string())?;
+
+    match out {
+        Some(path) => {
+            std::fs::write(path, json).map_err(|e| format!("Error writing '{}': {}", path, e))?;
+            println!("Replay with {} steps written to {}", step_count, path);
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+// Run the code once per seed (the seed places random obstacles and items and drives enemies) and
+// report how many turns it takes, for checking a level's turn budget or comparing solutions
+#[cfg(not(target_arch = "wasm32"))]
+fn run_bench(code_file: &str, level_idx: usize, level_file: Option<&std::path::Path>, runs: u64, first_seed: u64, json: bool) -> Result<(), String> {
+    let code = std::fs::read_to_string(code_file)
+        .map_err(|e| format!("Error reading code file '{}': {}", code_file, e))?;
+    let (configs, level_idx) = match level_file {
+        Some(path) => (vec![YamlLevelConfig::from_file(path).map_err(|e| format!("Error loading '{}': {}", path.display(), e))?], 0),
+        None => (embedded_levels::get_embedded_learning_levels(), level_idx),
+    };
+    let Some(config) = configs.get(level_idx) else {
+        return Err(format!("Level {} does not exist (max available: {})", level_idx, configs.len().saturating_sub(1)));
+    };
+    if runs == 0 {
+        return Err("--runs must be at least 1".to_string());
+    }
+
+    let mut turns = Vec::new();
+    let mut failed_seeds = Vec::new();
+    for seed in first_seed..first_seed + runs {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let spec = config.to_level_spec(&mut rng).map_err(|e| format!("Failed to build level: {}", e))?;
+        let mut game = Game::new(vec![spec], rng);
+        game.load_level(0);
+        game.current_code = code.clone().into();
+
+        // random_int() draws from the level seed, so each run parses the program again
+        let calls = parse_rust_code_from_main(&code, game.level_seed);
+        let print_outputs = extract_print_statements_from_main(&code, game.level_seed);
+
+        // Output-based completion flags look at what the program printed
+        for output in &print_outputs {
+            if let Some(message) = output.strip_prefix("stdout: ") {
+                game.println_outputs.push(message.to_string());
+            } else if let Some(message) = output.strip_prefix("stderr: ") {
+                game.error_outputs.push(message.to_string());
+            }
+        }
+        for call in calls {
+            let result = execute_function(&mut game, call);
+            if result.halt_message().is_some() {
+                break;
+            }
+        }
+        game.check_end_condition();
+
+        if game.finished {
+            turns.push(game.turns);
+        } else {
+            failed_seeds.push(seed);
+        }
+    }
+
+    turns.sort_unstable();
+    let median = (!turns.is_empty()).then(|| turns[turns.len() / 2]);
+    let mean = (!turns.is_empty()).then(|| turns.iter().sum::<usize>() as f64 / turns.len() as f64);
+    if json {
+        let report = serde_json::json!({
+            "level": config.name,
+            "runs": runs,
+            "completed": turns.len(),
+            "failed": failed_seeds.len(),
+            "failed_seeds": failed_seeds,
+            "median_turns": median,
+            "mean_turns": mean,
+            "best_turns": turns.first(),
+            "worst_turns": turns.last(),
+            "max_turns": config.max_turns,
+        });
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?);
+        return Ok(());
+    }
+
+    println!("Benchmark: {} on {} ({} runs, seeds {}..={})", code_file, config.name, runs, first_seed, first_seed + runs - 1);
+    println!("  Completed: {}/{}", turns.len(), runs);
+    if let (Some(median), Some(mean)) = (median, mean) {
+        println!("  Turns:     median {}, mean {:.1}, best {}, worst {}", median, mean, turns[0], turns[turns.len() - 1]);
+    }
+    if let Some(max_turns) = config.max_turns.filter(|max| *max > 0) {
+        let over = turns.iter().filter(|t| **t > max_turns as usize).count();
+        println!("  Turn limit {}: exceeded in {} completed runs", max_turns, over);
+    }
+    if !failed_seeds.is_empty() {
+        let shown: Vec<String> = failed_seeds.iter().take(10).map(|s| s.to_string()).collect();
+        println!("  Failed seeds: {}{}", shown.join(", "), if failed_seeds.len() > 10 { ", ..." } else { "" });
+    }
+    Ok(())
+}
+
 // Debug mode function to test all learning level solutions
 #[cfg(not(target_arch = "wasm32"))]
-async fn run_debug_all_levels(enable_all_logs: bool) {
+async fn run_debug_all_levels() {
     println!("=== RUST ROBOT PROGRAMMING GAME - DEBUG ALL LEVELS ===");
     
     let learning_configs = crate::gamestate::types::Game::get_learning_level_configs();
@@ -2680,7 +2891,7 @@ fn main() {
     
     println!("Level 1 complete!");
 }"#;
-                test_level_solution(&config, solution, enable_all_logs).await
+                test_level_solution(&config, solution).await
             },
             1 => {
                 // Level 2: Complete solution that satisfies all 4 tasks
@@ -2740,7 +2951,7 @@ fn main() {
     scan_level();
     println!("Level 2 complete test finished!");
 }"#;
-                test_level_solution(&config, solution, enable_all_logs).await
+                test_level_solution(&config, solution).await
             },
             _ => {
                 println!("  ⚠️  No test solution available for level {}", config.level_idx);
@@ -2777,7 +2988,7 @@ fn main() {
 
 // Test a solution against a specific learning level
 #[cfg(not(target_arch = "wasm32"))]
-async fn test_level_solution(config: &crate::gamestate::types::LearningLevelConfig, solution: &str, enable_all_logs: bool) -> (bool, String) {
+async fn test_level_solution(config: &crate::gamestate::types::LearningLevelConfig, solution: &str) -> (bool, String) {
     println!("  🔄 Testing solution for level {}...", config.level_idx);
     
     // Initialize game state for this level
@@ -2789,9 +3000,7 @@ async fn test_level_solution(config: &crate::gamestate::types::LearningLevelConf
     }
     
     let mut game = Game::new(core_levels, rng);
-    game.enable_coordinate_logs = enable_all_logs;
-    game.enable_key_press_logs = enable_all_logs;
-    game.current_code = solution.to_string();
+    game.current_code = solution.into();
     
     // Load the specific level
     game.load_level(config.level_idx);
@@ -2962,9 +3171,26 @@ fn emergency_game_recovery(game: &mut Game) {
     info!("Emergency recovery completed");
 }
 
+// Startup logging shared by the play and headless test commands
+#[cfg(not(target_arch = "wasm32"))]
+fn log_startup_mode(enable_all_logs: bool) {
+    // Run autocomplete integration test at startup
+    if enable_all_logs {
+        info!("Running autocomplete integration test...");
+        test_runner::run_quick_smoke_test();
+        info!("Autocomplete test completed");
+    }
+    
+    if enable_all_logs {
+        info!("All logs enabled, including detailed coordinate tracking and debug messages");
+    } else {
+        info!("Normal logging mode - use --log debug (or e.g. --log info,keys=debug) for more detail");
+    }
+}
+
 // Desktop-specific main logic
 #[cfg(not(target_arch = "wasm32"))]
-async fn desktop_main() {
+async fn desktop_main(command: cli::Command, enable_all_logs: bool) {
     // Set up simplified crash protection only
     setup_crash_protection();
     // Temporarily disable system-level crash protection to prevent infinite loops
@@ -2975,151 +3201,78 @@ async fn desktop_main() {
         error!("Failed to initialize fonts: {}, continuing with defaults", e);
     }
     
-    // Parse command line arguments
-    let args: Vec<String> = env::args().collect();
-
-    // Check for help request
-    if args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
-        println!("Rust Steam Game - Command Line Options:");
-        println!("");
-        println!("Game Control:");
-        println!("  --start-at-level N       Start directly at level N (0-indexed)");
-        println!("                          Example: --start-at-level 5 starts at Level 6");
-        println!("");
-        println!("Testing Options:");
-        println!("  --test-learning-levels   Run automated tests for learning levels");
-        println!("  --start-level N          Start learning tests from level N");
-        println!("  --max-levels N           Test only N levels");
-        println!("  --test-code \"code\"       Test specific Rust code");
-        println!("  --test-error-system      Test the enhanced error detection system");
-        println!("  --check-code \"code\"      Check Rust code for syntax errors");
-        println!("  --editor-test            Run editor functionality tests");
-        println!("  --command-test           Run robot command tests");
-        println!("");
-        println!("Debug Options:");
-        println!("  --all-logs               Enable detailed debug logging");
-        println!("  --debug                  Enable debug mode");
-        println!("");
-        println!("Help:");
-        println!("  --help, -h               Show this help message");
-        println!("");
-        return;
-    }
-
-    let enable_all_logs = args.contains(&"--all-logs".to_string());
-    let test_mode = args.iter().position(|arg| arg == "--test-code").map(|pos| {
-        args.get(pos + 1).cloned()
-    }).flatten();
-    let debug_all_levels = args.contains(&"--debug".to_string());
-    let editor_test_mode = args.contains(&"--editor-test".to_string());
-    let command_test_mode = args.contains(&"--command-test".to_string());
-    let learning_test_mode = args.contains(&"--test-learning-levels".to_string());
-
-    // Parse direct level selection argument (--start-at-level N)
-    let start_at_level = args.iter().position(|arg| arg == "--start-at-level")
-        .and_then(|pos| args.get(pos + 1))
-        .and_then(|s| s.parse::<usize>().ok());
-
-    // Parse level skipping arguments for learning tests
-    let start_level = args.iter().position(|arg| arg == "--start-level")
-        .and_then(|pos| args.get(pos + 1))
-        .and_then(|s| s.parse::<usize>().ok())
-        .unwrap_or(0);
-
-    let max_levels = args.iter().position(|arg| arg == "--max-levels")
-        .and_then(|pos| args.get(pos + 1))
-        .and_then(|s| s.parse::<usize>().ok())
-        .unwrap_or(4);
-    
-    // Initialize logging with appropriate level based on command line args
-    let log_level = if enable_all_logs {
-        log::LevelFilter::Debug  // Show all debug logs when --all-logs is specified
-    } else {
-        log::LevelFilter::Info   // Only show info and higher by default
-    };
-    
-    env_logger::Builder::from_default_env()
-        .filter_level(log_level)
-        .init();
-
-    // Check for editor test mode early
-    if editor_test_mode {
-        info!("Starting REAL Editor Test Mode");
-        run_real_editor_test_mode(enable_all_logs).await;
-        return;
-    }
-
-    // Check for command test mode
-    if command_test_mode {
-        info!("Starting Robot Command Test Mode");
-        run_command_test_mode(enable_all_logs).await;
-        return;
-    }
-
-    // Check for error system testing
-    if args.contains(&"--test-error-system".to_string()) {
-        info!("Testing enhanced error system");
-        test_enhanced_errors::test_error_system();
-        return;
-    }
-
-    // Check for manual code checking
-    if args.contains(&"--check-code".to_string()) {
-        if let Some(code_arg) = args.iter().position(|x| x == "--check-code")
-            .and_then(|i| args.get(i + 1)) {
-            let result = check_code_manually(code_arg);
-            println!("{}", result);
+    let start_at_level = match command {
+        cli::Command::EditorTest => {
+            info!("Starting REAL Editor Test Mode");
+            run_real_editor_test_mode().await;
             return;
-        } else {
-            println!("❌ --check-code requires a code string argument");
-            println!("Example: cargo run --release -- --check-code \"fn main() {{ println!(\"test\"); }}\"");
+        }
+        cli::Command::RobotCommandTest => {
+            info!("Starting Robot Command Test Mode");
+            run_command_test_mode().await;
             return;
         }
-    }
+        cli::Command::RunLearningTests { start_level, max_levels, report_format, report_out } => {
+            let report = match report_format {
+                Some(name) => learning_test_runner::ReportFormat::parse(&name)
+                    .map(|format| learning_test_runner::ReportOptions { format, out_path: report_out }),
+                // A report path on its own defaults to JUnit, the most widely consumed format
+                None => report_out.map(|out_path| learning_test_runner::ReportOptions {
+                    format: learning_test_runner::ReportFormat::Junit,
+                    out_path: Some(out_path),
+                }),
+            };
 
-    // Check for learning levels test mode
-    if learning_test_mode {
-        if start_level > 0 || max_levels != 4 {
-            info!("Starting Learning Levels Test Mode (start: {}, max: {})", start_level, max_levels);
-            learning_test_runner::run_learning_level_tests_with_options(start_level, max_levels).await;
-        } else {
-            info!("Starting Learning Levels Test Mode");
-            learning_test_runner::run_learning_level_tests().await;
+            if start_level > 0 || max_levels != 4 {
+                info!("Starting Learning Levels Test Mode (start: {}, max: {})", start_level, max_levels);
+                learning_test_runner::run_learning_level_tests_with_options(start_level, max_levels, report).await;
+            } else {
+                info!("Starting Learning Levels Test Mode");
+                learning_test_runner::run_learning_level_tests(report).await;
+            }
+            return;
         }
-        return;
-    }
-
-    // Run autocomplete integration test at startup
-    if enable_all_logs {
-        info!("Running autocomplete integration test...");
-        test_runner::run_quick_smoke_test();
-        info!("Autocomplete test completed");
-    }
-    
-    if enable_all_logs {
-        info!("All logs enabled, including detailed coordinate tracking and debug messages");
-    } else {
-        info!("Normal logging mode - use --all-logs for detailed debug information");
-    }
-    
-    // Check if we're in test mode
-    if let Some(test_file) = test_mode {
-        info!("Running in test mode with file: {}", test_file);
-        run_test_mode(test_file, enable_all_logs).await;
-        return;
-    }
-    
-    // Check if we're in debug all levels mode
-    if debug_all_levels {
-        info!("Running debug mode - testing all learning levels");
-        run_debug_all_levels(enable_all_logs).await;
-        return;
-    }
+        cli::Command::ExportReplay { file, level, out } => {
+            if let Err(e) = run_export_replay(&file, level, out.as_deref()).await {
+                eprintln!("❌ Failed to export replay: {}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        cli::Command::Test { file } => {
+            log_startup_mode(enable_all_logs);
+            info!("Running in test mode with file: {}", file);
+            run_test_mode(file, enable_all_logs).await;
+            return;
+        }
+        cli::Command::DebugLevels => {
+            log_startup_mode(enable_all_logs);
+            info!("Running debug mode - testing all learning levels");
+            run_debug_all_levels().await;
+            return;
+        }
+        cli::Command::Play { start_at_level } => {
+            log_startup_mode(enable_all_logs);
+            start_at_level
+        }
+        // Handled in main() before the window opens
+        cli::Command::TestErrorSystem | cli::Command::CheckCode { .. } |
+        cli::Command::ValidateLevel { .. } | cli::Command::EditorScenario { .. } |
+        cli::Command::Serve { .. } | cli::Command::Bench { .. } |
+        cli::Command::GenerateLevel { .. } => return,
+        #[cfg(feature = "steam")]
+        cli::Command::WorkshopUpload { .. } => return,
+    };
     
+
     info!("Starting Rust Steam Game...");
     
     let rng = StdRng::seed_from_u64(0xC0FFEE);
     
+    // Before the loader starts, so it can pick up Workshop level packs
+    #[cfg(feature = "steam")]
+    steam::init();
+
     // Initialize progressive loader
     let mut loader = ProgressiveLoader::new();
     
@@ -3134,10 +3287,6 @@ async fn desktop_main() {
     
     let mut game = Game::new(core_levels.clone(), rng);
     
-    // Enable coordinate logs if --all-logs flag is present
-    game.enable_coordinate_logs = enable_all_logs;
-    game.enable_key_press_logs = enable_all_logs;
-    
     // Restore cached game settings if available
     if let Some(cached) = cached_settings {
         info!("Restoring cached game settings");
@@ -3154,6 +3303,18 @@ async fn desktop_main() {
         game.invalidate_font_cache();
     }
     
+    // Custom hotkeys; any conflicts among them wait on the hotkey settings page
+    if let Err(e) = game.load_hotkey_config() {
+        warn!("{}", e);
+    }
+    game.menu.hotkey_conflicts = game.hotkey_system.conflicts();
+    // The .vimrc mappings were saved with the hotkeys when Vim mode was switched on
+    let vim_mode = game.menu.settings.vim_mode;
+    game.apply_vim_mode(vim_mode, false);
+    // Autocomplete and key repeat follow the editor settings page
+    let settings = game.menu.settings.clone();
+    game.apply_menu_settings(&settings);
+
     info!("Game initialized successfully");
 
     // Handle direct level selection (--start-at-level N)
@@ -3177,12 +3338,11 @@ async fn desktop_main() {
         }
     }
 
-    // Set initial levels count in menu (use cached count if available)
+    // Set initial levels in menu (use cached count if available)
+    game.menu.set_levels(&core_levels);
     if let Some(startup_data) = cached_startup_data {
         game.menu.set_total_levels(startup_data.total_levels_count);
         info!("Using cached level count: {}", startup_data.total_levels_count);
-    } else {
-        game.menu.set_total_levels(core_levels.len());
     }
     
     // Start progressive loading in background
@@ -3191,6 +3351,9 @@ async fn desktop_main() {
     // Initialize robot code
     game.load_robot_code();
     game.file_watcher_receiver = setup_file_watcher(&game.robot_code_path);
+    if crash_protection::begin_session() {
+        game.offer_autosave_restore();
+    }
     
     // Apply saved maximize state on startup
     if game.menu.settings.maximized {
@@ -3208,9 +3371,15 @@ async fn desktop_main() {
         let delta_time = (current_time - last_time) as f32;
         last_time = current_time;
         update_crash_recovery_timer(delta_time);
+        #[cfg(feature = "steam")]
+        steam::run_callbacks();
         
         // Check for system-level crashes and reset state if needed
         if crash_protection::is_system_crash_active() {
+            // Get the editor contents to disk before anything else can go wrong
+            if let Err(e) = game.autosave.snapshot(&game.current_code) {
+                error!("Emergency autosave failed: {}", e);
+            }
             static mut SYSTEM_CRASH_TIMER: f32 = 0.0;
             unsafe {
                 SYSTEM_CRASH_TIMER += delta_time;
@@ -3221,24 +3390,37 @@ async fn desktop_main() {
                 }
             }
         }
-        // Check for progressive loading updates
-        if let Some(progress) = loader.get_latest_progress() {
+        // Check for progressive loading updates; community levels send one per batch
+        while let Some(progress) = loader.get_latest_progress() {
+            game.menu.level_load_errors.extend(progress.errors.iter().cloned());
             // Clear loading progress once complete to stop checking
             if matches!(progress.stage, LoadingStage::Complete) && progress.progress >= 1.0 {
+                // One popup for all the files that failed, once the rest have loaded
+                if !game.menu.level_load_errors.is_empty() {
+                    game.popup_system.show_level_load_errors(&game.menu.level_load_errors);
+                }
                 loading_progress = None;
             } else {
-                loading_progress = Some(progress.clone());
-            }
-            
-            // Update game with newly loaded levels when available
-            if let Some(new_levels) = loader.get_loaded_levels() {
-                info!("Updating game with {} total levels", new_levels.len());
-                game.levels = new_levels;
-                game.menu.set_total_levels(game.levels.len());
+                loading_progress = Some(progress);
             }
         }
+        // Update game with newly loaded levels when available; each list has all the levels so far
+        if let Some(new_levels) = loader.get_loaded_levels() {
+            info!("Updating game with {} total levels", new_levels.len());
+            game.levels = new_levels;
+            game.menu.set_levels(&game.levels);
+        }
         // Check for screen size changes and update menu layout if needed
         game.menu.check_screen_resize();
+
+        // Syntax-check the editor code in the background once typing pauses
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(checker) = game.rust_checker.as_mut() {
+            checker.update(&game.current_code, current_time);
+        }
+        game.autosave.update(&game.current_code, current_time);
+        // File reloads and cargo add run on the job workers; apply what they've finished
+        game.update_jobs();
         
         // Check if user manually resized window and save the new size
         let current_width = crash_protection::safe_screen_width() as i32;
@@ -3267,6 +3449,19 @@ async fn desktop_main() {
         // Handle menu input and updates
         let menu_action = game.menu.handle_input();
         game.menu.update(menu_action.clone());
+
+        // Levels installed from the community browser are playable right away
+        for path in game.menu.poll_community() {
+            let spec = YamlLevelConfig::from_yaml_file(&path).map_err(|e| e.to_string())
+                .and_then(|config| progressive_loader::community_level_spec(config, game.menu.settings.filter_community_text));
+            match spec {
+                Ok(spec) => {
+                    game.levels.push(spec);
+                    game.menu.set_levels(&game.levels);
+                }
+                Err(e) => warn!("Couldn't load installed level {}: {}", path.display(), e),
+            }
+        }
         
         // Update global font multiplier when settings change
         font_scaling::set_user_font_multiplier(game.menu.settings.font_size_multiplier);
@@ -3279,6 +3474,9 @@ async fn desktop_main() {
             MenuAction::StartGame => {
                 println!("Starting new game...");
                 // Reset to level 0 and clear robot code
+                game.stop_endless_mode();
+                game.stop_daily_challenge();
+                game.stop_sandbox();
                 game.level_idx = 0;
                 game.load_level(0);
                 reset_robot_code(&mut game);
@@ -3286,13 +3484,92 @@ async fn desktop_main() {
             MenuAction::SelectLevel(level) => {
                 println!("Loading level {}...", level);
                 // Jump to selected level and reset robot code
+                game.stop_endless_mode();
+                game.stop_daily_challenge();
+                game.stop_sandbox();
                 game.level_idx = level;
                 game.load_level(level);
                 reset_robot_code(&mut game);
             },
+            MenuAction::StartEndless => {
+                println!("Starting endless mode...");
+                game.stop_endless_mode();
+                game.stop_daily_challenge();
+                game.stop_sandbox();
+                game.start_endless_mode();
+                reset_robot_code(&mut game);
+            },
+            MenuAction::StartDailyChallenge => {
+                println!("Starting daily challenge...");
+                game.stop_endless_mode();
+                game.stop_daily_challenge();
+                game.stop_sandbox();
+                game.start_daily_challenge();
+                reset_robot_code(&mut game);
+            },
+            MenuAction::StartSandbox => {
+                println!("Starting sandbox...");
+                game.stop_endless_mode();
+                game.stop_daily_challenge();
+                game.stop_sandbox();
+                game.start_sandbox();
+                reset_robot_code(&mut game);
+            },
+            MenuAction::ResolveHotkeyConflict(conflict, keep) => {
+                game.resolve_hotkey_conflict(conflict, keep);
+            },
+            MenuAction::ResetHotkeys => {
+                game.reset_hotkeys();
+            },
+            MenuAction::CycleHotkeyPreset => {
+                let preset = game.menu.settings.hotkey_preset;
+                game.apply_hotkey_preset(preset);
+            },
+            // Levels that pin auto-grab keep it; elsewhere the robots switch right away
+            MenuAction::ToggleAutoGrab if game.levels[game.level_idx].auto_grab.is_none() => {
+                let enabled = game.menu.settings.auto_grab;
+                game.robot.set_auto_grab(enabled);
+                for robot in &mut game.robots {
+                    robot.set_auto_grab(enabled);
+                }
+            },
+            MenuAction::ToggleVimMode => {
+                let enabled = game.menu.settings.vim_mode;
+                game.apply_vim_mode(enabled, true);
+                game.menu.setup_hotkey_settings_menu();
+            },
+            // The menu already has the imported settings; the editor and hotkeys follow them here
+            MenuAction::ImportSettings => {
+                if let Err(e) = game.load_hotkey_config() {
+                    warn!("{}", e);
+                }
+                game.menu.hotkey_conflicts = game.hotkey_system.conflicts();
+                let settings = game.menu.settings.clone();
+                game.apply_vim_mode(settings.vim_mode, false);
+                game.apply_menu_settings(&settings);
+            },
+            MenuAction::OpenCacheSettings => {
+                game.menu.cache_stats = Some(loader.cache.stats());
+            },
+            MenuAction::ClearCache => {
+                loader.cache.clear();
+                loader.cache.save();
+                game.menu.cache_stats = Some(loader.cache.stats());
+            },
+            MenuAction::ToggleAutocomplete | MenuAction::CycleKeyRepeatDelay | MenuAction::CycleKeyRepeatRate => {
+                let settings = game.menu.settings.clone();
+                game.apply_menu_settings(&settings);
+            },
+            MenuAction::ToggleWordWrap => {
+                // Wrapped rows or the sideways scroll may have moved the cursor out of view
+                game.ensure_cursor_visible();
+            },
             MenuAction::Exit => {
                 // Cache game settings and state before exit
                 cache_game_state_on_exit(&mut loader.cache, &game);
+                crash_protection::end_session();
+                #[cfg(feature = "steam")]
+                steam::shutdown();
                 break;
             },
             _ => {}
@@ -3304,32 +3581,44 @@ async fn desktop_main() {
         // Draw based on current menu state
         match game.menu.state {
             MenuState::InGame => {
+                // Any input, or a program playing out, restarts the tutorial ghost's idle timer
+                if !get_keys_pressed().is_empty() || is_mouse_button_pressed(MouseButton::Left) || is_mouse_button_pressed(MouseButton::Right) || game.program_running {
+                    game.last_activity = current_time;
+                }
+
                 // Handle popup input FIRST - before any other input processing
                 let popup_action = game.handle_popup_input();
-                let popup_handled_input = popup_action != PopupAction::None;
+                // The solution comparison screen takes all input while it's open
+                let comparison_open = popup_action == PopupAction::None && handle_solution_comparison_input(&mut game);
+                // So does "show me" playback, which plays one robot action per step
+                let playback_open = popup_action == PopupAction::None && !comparison_open && update_solution_playback(&mut game, current_time);
+                let popup_handled_input = popup_action != PopupAction::None || comparison_open || playback_open;
+
+                // Popup-context hotkeys while a popup stays open
+                if game.popup_system.is_showing() {
+                    poll_hotkeys(&mut game);
+                }
 
                 // Update popup system with delta time
                 game.update_popup_system(crash_protection::safe_get_frame_time());
+                game.update_time_limit(crash_protection::safe_get_frame_time());
 
                 // Wrap main game view drawing in crash protection with focus awareness
                 crash_protection::safe_draw_operation_with_focus(|| draw_main_game_view(&mut game), "main_game_view");
 
                 // Shop functionality removed - replaced with Rust docs
 
+                if game.solution_comparison.is_some() {
+                    crash_protection::safe_draw_operation_with_focus(|| draw_solution_comparison(&game), "solution_comparison");
+                }
+
                 // Draw popups last so they appear on top - also focus protected
                 crash_protection::safe_draw_operation_with_focus(|| game.draw_popups(), "popups");
+                game.announce_task_if_changed();
 
                 // Game input handling
                 debug!("Input gating: shop_open={}, popup_handled_input={}", shop_open, popup_handled_input);
                 if !shop_open && !popup_handled_input && crash_protection::is_window_focused() {
-                    // Check for file changes
-                    if let Some(ref receiver) = game.file_watcher_receiver {
-                        if let Ok(_event) = receiver.try_recv() {
-                            game.robot_code_modified = true;
-                            game.load_robot_code(); // Reload file content
-                        }
-                    }
-                    
                     // Mouse handling
                     let (mouse_x, mouse_y) = crash_protection::safe_mouse_position_with_focus();
                     trace!("Mouse position: ({:.2}, {:.2})", mouse_x, mouse_y);
@@ -3370,24 +3659,26 @@ async fn desktop_main() {
                         game.update_window_coordinates();
                     }
                     
+                    let show_me_clicked = is_mouse_button_pressed(MouseButton::Left)
+                        && drawing::ui_drawing::show_me_button_rect(&game).is_some_and(|rect| rect.contains(vec2(mouse_x, mouse_y)));
+                    if show_me_clicked {
+                        game.start_solution_playback();
+                    }
+                    let button_clicked = handle_sandbox_input(&mut game) || handle_camera_input(&mut game) || show_me_clicked || handle_execution_controls_input(&mut game);
+
                     // Handle mouse button press - start of potential drag
-                    if is_mouse_button_pressed(MouseButton::Left) {
+                    if is_mouse_button_pressed(MouseButton::Left) && !button_clicked {
                         debug!("Left mouse button pressed at ({:.2}, {:.2}) - input allowed!", mouse_x, mouse_y);
 
-                        // Tab click handling (above sidebar area)
-                        let sidebar_x = crash_protection::safe_screen_width() * 0.5 + 16.0; // Match sidebar position
-                        let sidebar_width = crash_protection::safe_screen_width() * 0.25;
-
                         // Editor click handling (simplified - no tabs)
                         {
-                            let editor_x = sidebar_x; // Use sidebar position when Editor tab is active
-                            let editor_y = 16.0 + 100.0; // Match PADDING constant
-                            let editor_width = sidebar_width;
-                            let editor_height = crash_protection::safe_screen_height() * 0.6;
+                            let editor = drawing::layout::screen_layout().sidebar_content();
+                            let (editor_x, editor_y, editor_width, editor_height) = (editor.x, editor.y, editor.w, editor.h);
 
                             debug!("Editor bounds: x={:.2}, y={:.2}, w={:.2}, h={:.2}", editor_x, editor_y, editor_width, editor_height);
 
-                            if mouse_x >= editor_x - 10.0 && mouse_x <= editor_x + editor_width + 10.0 &&
+                            if game.editor_tab.shows_editor() &&
+                               mouse_x >= editor_x - 10.0 && mouse_x <= editor_x + editor_width + 10.0 &&
                                mouse_y >= editor_y - 10.0 && mouse_y <= editor_y + editor_height + 10.0 {
                                 debug!("Click detected in editor area, activating editor");
                                 game.code_editor_active = true;
@@ -3405,7 +3696,7 @@ async fn desktop_main() {
                                         // Set safe defaults
                                         game.cursor_position = 0;
                                         if game.current_code.is_empty() {
-                                            game.current_code = "// Start typing your Rust code here...\n".to_string();
+                                            game.current_code = "// Start typing your Rust code here...\n".into();
                                         }
                                     }
                                 }
@@ -3418,13 +3709,8 @@ async fn desktop_main() {
 
                     // Handle mouse dragging for text selection
                     if is_mouse_button_down(MouseButton::Left) && game.mouse_drag_start.is_some() {
-                        let sidebar_x = crash_protection::safe_screen_width() * 0.5 + 16.0;
-                        let sidebar_width = crash_protection::safe_screen_width() * 0.25;
-                        let editor_x = sidebar_x;
-                        let editor_y = 16.0 + 100.0;
-                        let editor_width = sidebar_width;
-                        let editor_height = crash_protection::safe_screen_height() * 0.6;
-                        let editor_bounds = (editor_x, editor_y, editor_width, editor_height);
+                        let editor = drawing::layout::screen_layout().sidebar_content();
+                        let editor_bounds = (editor.x, editor.y, editor.w, editor.h);
 
                         match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
                             game.update_mouse_drag(mouse_x, mouse_y, editor_bounds);
@@ -3445,8 +3731,26 @@ async fn desktop_main() {
                         }
                     }
 
-                    // Code editor input
-                    if game.code_editor_active {
+                    // Code editor input; the clipboard history picker and the go-to-line prompt take the keys while open
+                    if game.code_editor_active && game.clipboard_picker_open() {
+                        let _ = gamestate::cursor::read_typed_text();
+                        let mut code_modified = false;
+                        for key in get_keys_pressed() {
+                            code_modified |= game.clipboard_picker_key(key);
+                        }
+                        if code_modified {
+                            game.save_robot_code();
+                            game.update_autocomplete();
+                        }
+                    } else if game.code_editor_active && game.goto_line_open() {
+                        let typed = gamestate::cursor::read_typed_text();
+                        game.goto_line_text(&typed);
+                        for key in get_keys_pressed() {
+                            if game.goto_line_key(key) {
+                                game.update_autocomplete();
+                            }
+                        }
+                    } else if game.code_editor_active {
                         let mut code_modified = false;
                         
                         // Update key press timers
@@ -3458,17 +3762,13 @@ async fn desktop_main() {
                         let alt_held = is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt);
 
                         let mut hotkey_handled = false;
-                        for key_code in [
-                            KeyCode::Enter, KeyCode::S, KeyCode::Tab, KeyCode::Z, KeyCode::Y,
-                            KeyCode::C, KeyCode::V, KeyCode::X, KeyCode::A, KeyCode::F,
-                            KeyCode::H, KeyCode::G, KeyCode::Slash, KeyCode::D, KeyCode::K,
-                            KeyCode::GraveAccent
-                        ] {
+                        cancel_chord_on_other_keys(&mut game);
+                        for key_code in HOTKEY_KEYS {
                             if is_key_pressed(key_code) {
                                 if game.handle_hotkey(key_code, ctrl_held, shift_held, alt_held) {
                                     println!("🎹 Handled hotkey via centralized system: {:?} (ctrl:{}, shift:{}, alt:{})", key_code, ctrl_held, shift_held, alt_held);
-                                    // Some hotkeys might modify code (like paste, undo, etc.)
-                                    if matches!(key_code, KeyCode::V | KeyCode::Z | KeyCode::Y | KeyCode::X) {
+                                    // Some hotkeys might modify code (like paste, undo, comment, etc.)
+                                    if matches!(key_code, KeyCode::V | KeyCode::Z | KeyCode::Y | KeyCode::X | KeyCode::Slash | KeyCode::C | KeyCode::U | KeyCode::K | KeyCode::W) {
                                         code_modified = true;
                                     }
                                     hotkey_handled = true;
@@ -3477,8 +3777,26 @@ async fn desktop_main() {
                             }
                         }
 
-                        // Check if code execution was requested via Ctrl+Shift+Enter
-                        if game.code_execution_requested {
+                        // Vim mode: Escape leaves insert/visual mode, and outside insert mode keys are commands
+                        if is_key_pressed(KeyCode::Escape) {
+                            game.vim_escape();
+                        }
+                        let vim_commands = game.vim_intercepts_typing();
+
+                        // Check if code execution was requested via Ctrl+Shift+Enter.
+                        // Wait (without blocking the frame) for the background syntax check of this code first
+                        #[cfg(not(target_arch = "wasm32"))]
+                        let syntax_check_pending = match game.rust_checker.as_mut() {
+                            Some(checker) if game.code_execution_requested && !game.current_code.is_empty() => {
+                                checker.check_now(&game.current_code);
+                                checker.result_for(&game.current_code).is_none()
+                            }
+                            _ => false,
+                        };
+                        #[cfg(target_arch = "wasm32")]
+                        let syntax_check_pending = false;
+
+                        if game.code_execution_requested && !syntax_check_pending {
                             game.code_execution_requested = false; // Reset the flag
                             println!("🚀 Executing code via Ctrl+Shift+Enter...");
 
@@ -3497,40 +3815,42 @@ async fn desktop_main() {
                         }
 
                         // Handle character input - both initial press and continuous hold
-                        let mut current_char_pressed = None;
-                        while let Some(character) = get_char_pressed() {
-                            if character.is_ascii() && !character.is_control() && character != ' ' {
-                                current_char_pressed = Some(character);
-                                
-                                // Delete selection first if it exists
-                                if game.delete_selection() {
-                                    code_modified = true;
-                                }
-                                
-                                game.current_code.insert(game.cursor_position, character);
-                                game.cursor_position += 1;
+                        // Multi-character input in one frame is an IME commit, which isn't key-repeated
+                        let mut typed = gamestate::cursor::read_typed_text();
+                        if hotkey_handled {
+                            typed.clear(); // Alt+F and the like can arrive as typed letters too
+                        }
+                        let current_char_pressed = single_char(&typed);
+                        if vim_commands {
+                            if game.vim_handle_text(&typed) {
+                                code_modified = true;
+                            }
+                            if is_key_pressed(KeyCode::Space) && game.vim_handle_text(" ") {
                                 code_modified = true;
                             }
+                        } else if game.insert_typed_text(&typed) {
+                            code_modified = true;
                         }
                         
                         // Update character key timing
                         game.update_char_key_timing(current_char_pressed, crash_protection::safe_get_frame_time());
                         
                         // Handle continuous character repeat
-                        if game.should_repeat_char() {
-                            if let Some(character) = game.last_char_pressed {
-                                // Delete selection first if it exists
-                                if game.delete_selection() {
-                                    code_modified = true;
-                                }
-                                
-                                game.current_code.insert(game.cursor_position, character);
-                                game.cursor_position += 1;
+                        if game.should_repeat_char()
+                            && !vim_commands
+                            && let Some(character) = game.last_char_pressed
+                        {
+                            // Delete selection first if it exists
+                            if game.delete_selection() {
                                 code_modified = true;
                             }
+
+                            game.current_code.insert(game.cursor_position, character);
+                            game.cursor_position += character.len_utf8();
+                            code_modified = true;
                         }
                         
-                        if is_key_pressed(KeyCode::Enter) && !hotkey_handled {
+                        if is_key_pressed(KeyCode::Enter) && !hotkey_handled && !vim_commands {
                             // Regular enter (new line) - only if centralized system didn't handle it
                             println!("🔑 Processing regular Enter key (no hotkey handled)");
 
@@ -3539,33 +3859,38 @@ async fn desktop_main() {
                                 code_modified = true;
                             }
 
-                            // Get automatic indentation for the next line
-                            let auto_indent = get_auto_indentation(&game.current_code, game.cursor_position);
+                            // Get automatic indentation for the next line, unless turned off in the editor settings
+                            let auto_indent = if game.menu.settings.editor.auto_indent {
+                                get_auto_indentation(&game.current_code, game.cursor_position, &game.menu.settings.editor.indent_unit())
+                            } else {
+                                String::new()
+                            };
                             let newline_with_indent = format!("\n{}", auto_indent);
 
                             // Insert newline with automatic indentation
                             for ch in newline_with_indent.chars() {
                                 game.current_code.insert(game.cursor_position, ch);
-                                game.cursor_position += 1;
+                                game.cursor_position += ch.len_utf8();
                             }
                             game.ensure_cursor_visible(); // Ensure the cursor scrolls into view after newline
                             code_modified = true;
                         }
                         
                         // Handle backspace - both initial press and continuous hold
-                        if is_key_pressed(KeyCode::Backspace) || game.should_repeat_backspace() {
+                        if (is_key_pressed(KeyCode::Backspace) || game.should_repeat_backspace()) && !vim_commands {
                             // Delete selection first if it exists, otherwise delete single character
                             if game.delete_selection() {
                                 code_modified = true;
                             } else if game.cursor_position > 0 {
-                                game.cursor_position -= 1;
-                                game.current_code.remove(game.cursor_position);
+                                let previous = game.current_code.prev_grapheme_boundary(game.cursor_position);
+                                game.current_code.remove_range(previous..game.cursor_position);
+                                game.cursor_position = previous;
                                 code_modified = true;
                             }
                         }
                         
                         // Handle space - both initial press and continuous hold
-                        if is_key_pressed(KeyCode::Space) || game.should_repeat_space() {
+                        if (is_key_pressed(KeyCode::Space) || game.should_repeat_space()) && !vim_commands && !hotkey_handled {
                             // Delete selection first if it exists
                             if game.delete_selection() {
                                 code_modified = true;
@@ -3577,7 +3902,7 @@ async fn desktop_main() {
                         }
                         
                         // Handle tab key - try autocomplete first, then indentation
-                        if is_key_pressed(KeyCode::Tab) {
+                        if is_key_pressed(KeyCode::Tab) && !vim_commands {
                             // First, try to accept autocomplete suggestion
                             if game.accept_autocomplete() {
                                 // Autocomplete was accepted
@@ -3589,12 +3914,10 @@ async fn desktop_main() {
                                     code_modified = true;
                                 }
 
-                                // Insert 4 spaces for tab
-                                let tab_spaces = "    "; // 4 spaces
-                                for (i, space) in tab_spaces.chars().enumerate() {
-                                    game.current_code.insert(game.cursor_position + i, space);
-                                }
-                                game.cursor_position += tab_spaces.len();
+                                // Insert one indentation level, spaces or a tab per the editor settings
+                                let indent = game.menu.settings.editor.indent_unit();
+                                game.current_code.insert_str(game.cursor_position, &indent);
+                                game.cursor_position += indent.len();
                                 code_modified = true;
                             }
                         }
@@ -3641,7 +3964,7 @@ async fn desktop_main() {
                         
                         if is_key_pressed(KeyCode::R) && is_key_down(KeyCode::LeftControl) && is_key_down(KeyCode::LeftShift) {
                             // Reset to default code
-                            game.current_code = get_default_robot_code().to_string();
+                            game.current_code = get_default_robot_code().into();
                             game.cursor_position = 0;
                             game.code_scroll_offset = 0;
                             code_modified = true;
@@ -3652,11 +3975,95 @@ async fn desktop_main() {
                             game.save_robot_code();
                             // Update autocomplete suggestions when code changes
                             game.update_autocomplete();
+                            // Space, Tab and Backspace move the cursor without scrolling to it
+                            game.ensure_cursor_visible();
+                        }
+                    }
+
+                    // Code history: Up/Down pick an attempt, D diffs it against the editor, Enter restores it
+                    #[cfg(feature = "vcs")]
+                    let history_open = game.editor_tab == EditorTab::History;
+                    #[cfg(not(feature = "vcs"))]
+                    let history_open = false;
+                    #[cfg(feature = "vcs")]
+                    if history_open && !game.code_editor_active {
+                        if is_key_pressed(KeyCode::Enter) {
+                            game.restore_code_history();
+                        }
+                        if let Some(panel) = game.code_history.as_mut() {
+                            if is_key_pressed(KeyCode::Up) {
+                                panel.select_previous();
+                            }
+                            if is_key_pressed(KeyCode::Down) {
+                                panel.select_next();
+                            }
+                            if is_key_pressed(KeyCode::D) && !is_key_down(KeyCode::LeftControl) {
+                                panel.toggle_diff(&game.current_code);
+                            }
                         }
                     }
 
+                    // Watch panel: Up/Down step through the last run, typing + Enter pins an expression
+                    let watch_open = game.editor_tab == EditorTab::Watch && !game.code_editor_active;
+                    if watch_open {
+                        if is_key_pressed(KeyCode::Up) {
+                            game.watch.select_previous();
+                        }
+                        if is_key_pressed(KeyCode::Down) {
+                            game.watch.select_next();
+                        }
+                        let shortcut_held = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+                        let typed = gamestate::cursor::read_typed_text();
+                        if !shortcut_held {
+                            game.watch.input.push_str(&typed);
+                            if is_key_pressed(KeyCode::Space) {
+                                game.watch.input.push(' ');
+                            }
+                        }
+                        if is_key_pressed(KeyCode::Enter) {
+                            game.watch.pin_input();
+                        }
+                        if is_key_pressed(KeyCode::Backspace) {
+                            game.watch.backspace();
+                        }
+                    }
+
+                    // Game log view of the Logs tab: L cycles the level, M the module, E exports what's shown
+                    let game_log_open = game.editor_tab == EditorTab::Logs && game.log_filter == popup::LogFilter::GameLog && !game.code_editor_active;
+                    if game_log_open && !is_key_down(KeyCode::LeftControl) {
+                        if is_key_pressed(KeyCode::L) {
+                            game.game_log_view.next_level();
+                        }
+                        if is_key_pressed(KeyCode::M) {
+                            game.game_log_view.next_module();
+                        }
+                        if is_key_pressed(KeyCode::E) {
+                            game.export_game_log();
+                        }
+                    }
+
+                    // Combat log view of the Logs tab: Up/Down scroll through the turns, End goes back to the newest
+                    let combat_log_open = game.editor_tab == EditorTab::Logs && game.log_filter == popup::LogFilter::Combat && !game.code_editor_active;
+                    if combat_log_open {
+                        if is_key_pressed(KeyCode::Up) {
+                            // Roughly a line and a turn header per entry
+                            game.combat_log_scroll = (game.combat_log_scroll + 1).min(game.combat_log.entries.len() * 2);
+                        }
+                        if is_key_pressed(KeyCode::Down) {
+                            game.combat_log_scroll = game.combat_log_scroll.saturating_sub(1);
+                        }
+                        if is_key_pressed(KeyCode::End) {
+                            game.combat_log_scroll = 0;
+                        }
+                    }
+
+                    // Game-context hotkeys while the editor isn't focused
+                    if !game.code_editor_active && !history_open && !watch_open && !game_log_open && !combat_log_open {
+                        poll_hotkeys(&mut game);
+                    }
+
                     // Tutorial scrolling for when code editor is NOT active
-                    if !game.code_editor_active {
+                    if !game.code_editor_active && !history_open && !watch_open && !game_log_open && !combat_log_open {
                         // Handle Up/Down arrow keys for tutorial scrolling
                         if is_key_pressed(KeyCode::Up) {
                             if game.tutorial_scroll_offset > 0 {
@@ -3676,6 +4083,13 @@ async fn desktop_main() {
                         }
                     }
 
+                    // Ctrl+Z outside the editor rewinds the last turn
+                    if is_key_pressed(KeyCode::Z) && is_key_down(KeyCode::LeftControl) && !is_key_down(KeyCode::LeftShift) && !game.code_editor_active {
+                        game.execution_result = match game.rewind(1) {
+                            Ok(result) | Err(result) => result,
+                        };
+                    }
+
                     if is_key_pressed(KeyCode::E) && is_key_down(KeyCode::LeftControl) && is_key_down(KeyCode::LeftShift) && !game.code_editor_active {
                         // Open external editor hint
                         game.execution_result = format!("Edit {} with your preferred IDE/editor", game.robot_code_path);
@@ -3705,6 +4119,32 @@ async fn desktop_main() {
                         // Show completion instructions
                         game.show_completion_instructions();
                     }
+                    if is_key_pressed(KeyCode::G) && is_key_down(KeyCode::LeftControl) && is_key_down(KeyCode::LeftShift) {
+                        // Swap the sidebar between the code editor and the message log
+                        game.editor_tab = if game.editor_tab == EditorTab::Logs { EditorTab::Editor } else { EditorTab::Logs };
+                        game.code_editor_active = false;
+                    }
+                    #[cfg(feature = "vcs")]
+                    if is_key_pressed(KeyCode::H) && is_key_down(KeyCode::LeftControl) && is_key_down(KeyCode::LeftShift) {
+                        // Swap the sidebar between the code editor and the code history
+                        game.editor_tab = if game.editor_tab == EditorTab::History { EditorTab::Editor } else { EditorTab::History };
+                        game.code_editor_active = false;
+                    }
+                    if is_key_pressed(KeyCode::O) && is_key_down(KeyCode::LeftControl) && is_key_down(KeyCode::LeftShift) && game.levels[game.level_idx].ownership_view {
+                        // Swap the sidebar between the code editor and the ownership timeline
+                        game.toggle_editor_tab(EditorTab::Ownership);
+                    }
+                    if is_key_pressed(KeyCode::W) && is_key_down(KeyCode::LeftControl) && is_key_down(KeyCode::LeftShift) {
+                        // Swap the sidebar between the code editor and the watch panel
+                        game.toggle_editor_tab(EditorTab::Watch);
+                    }
+                    if is_key_pressed(KeyCode::F) && is_key_down(KeyCode::LeftControl) && is_key_down(KeyCode::LeftShift) && game.editor_tab == EditorTab::Logs {
+                        game.log_filter = game.log_filter.next();
+                    }
+                    if is_key_pressed(KeyCode::P) && is_key_down(KeyCode::LeftControl) && is_key_down(KeyCode::LeftShift) {
+                        // Reopen the last dismissed popup
+                        game.popup_system.reopen_last();
+                    }
                     if is_key_pressed(KeyCode::S) && is_key_down(KeyCode::LeftControl) && is_key_down(KeyCode::LeftShift) {
                         // Open settings menu from in-game
                         game.menu.open_settings_from_game();
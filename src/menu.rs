@@ -1,8 +1,15 @@
 use macroquad::prelude::*;
 use crate::font_scaling::*;
 use crate::progressive_loader::{LoadingProgress, LoadingStage};
+use crate::campaign::Campaign;
+use crate::export::Certificate;
+use crate::settings_bundle::SettingsBundle;
+use crate::level_search::{CompletionFilter, LevelInfo, LevelSearch};
+use crate::community::CommunityBrowser;
+use crate::popup::{ToastEvent, ToastSettings};
+use crate::hotkeys::HotkeyPreset;
+use crate::i18n::{tr, tr_with};
 use serde::{Serialize, Deserialize};
-use std::fs;
 use std::path::Path;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -10,7 +17,13 @@ pub enum MenuState {
     MainMenu,
     Settings,
     LevelSelect,
+    WorldMap,
     HotkeySettings,
+    NotificationSettings,
+    EditorSettings,
+    CacheSettings,
+    CommunityBrowser,
+    Stats,
     InGame,
 }
 
@@ -18,10 +31,20 @@ pub enum MenuState {
 pub enum MenuAction {
     None,
     StartGame,
+    StartEndless,
+    StartDailyChallenge,
+    StartSandbox,
     OpenSettings,
     OpenLevelSelect,
     OpenCommunityLevels,
+    OpenStats,
+    ExportCertificate,
+    RefreshCommunityLevels,
+    InstallCommunityLevel(usize), // Index into the community browser's entries
     SelectLevel(usize),
+    CycleLevelTopic,
+    CycleLevelStatus,
+    CycleLevelDifficulty,
     BackToMain,
     BackToGame,  // New action for returning to game from settings
     Exit,
@@ -36,7 +59,31 @@ pub enum MenuAction {
     DecreaseFontSize,
     ToggleAutocomplete,
     ToggleVSCodeIntegration,
+    ToggleShowMe,
+    ToggleAutoGrab,
+    CyclePalette,
+    CycleLanguage,
+    ToggleScreenReader,
+    CycleGhostDelay,
     OpenHotkeySettings,
+    ResolveHotkeyConflict(usize, usize), // Conflict index, binding of it to keep
+    ResetHotkeys,
+    ToggleVimMode,
+    CycleHotkeyPreset,
+    OpenNotificationSettings,
+    ExportSettings,
+    ImportSettings,
+    OpenCacheSettings,
+    ClearCache,
+    ToggleToast(ToastEvent),
+    OpenEditorSettings,
+    CycleTabWidth,
+    ToggleInsertSpaces,
+    ToggleAutoIndent,
+    CycleKeyRepeatDelay,
+    CycleKeyRepeatRate,
+    ToggleLineNumbers,
+    ToggleWordWrap,
     BackToSettings,
 }
 
@@ -100,6 +147,10 @@ impl MenuButton {
 pub struct PlayerProgress {
     pub max_level_unlocked: usize, // Highest level the player has reached
     pub completed_levels: Vec<bool>, // Track which levels have been completed
+    #[serde(default)]
+    pub completed_nodes: Vec<String>, // Campaign node ids the player has finished
+    #[serde(default)]
+    pub unlocked_nodes: Vec<String>, // Campaign node ids available on the world map
 }
 
 impl Default for PlayerProgress {
@@ -107,40 +158,34 @@ impl Default for PlayerProgress {
         Self {
             max_level_unlocked: 0, // Start with only level 0 unlocked
             completed_levels: Vec::new(),
+            completed_nodes: Vec::new(),
+            unlocked_nodes: Vec::new(),
         }
     }
 }
 
 impl PlayerProgress {
     const SAVE_FILE: &'static str = "player_progress.json";
+    const SCHEMA_VERSION: u32 = 1;
     
     pub fn load_or_default() -> Self {
-        if Path::new(Self::SAVE_FILE).exists() {
-            match fs::read_to_string(Self::SAVE_FILE) {
-                Ok(contents) => {
-                    match serde_json::from_str::<PlayerProgress>(&contents) {
-                        Ok(progress) => progress,
-                        Err(_) => {
-                            // If file is corrupted, create new progress and save it
-                            let default = Self::default();
-                            let _ = default.save();
-                            default
-                        }
-                    }
-                }
-                Err(_) => Self::default(),
+        // Unit tests build whole games; keep them off the player's own save file
+        if cfg!(test) {
+            return Self::default();
+        }
+        match crate::save_file::load_json(Path::new(Self::SAVE_FILE), Self::SCHEMA_VERSION) {
+            Ok(Some(progress)) => progress,
+            // No save file yet, or a corrupted one without a backup: start over and save that
+            Ok(None) | Err(_) => {
+                let default = Self::default();
+                let _ = default.save();
+                default
             }
-        } else {
-            // Create new save file
-            let default = Self::default();
-            let _ = default.save();
-            default
         }
     }
     
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let json = serde_json::to_string_pretty(self)?;
-        fs::write(Self::SAVE_FILE, json)?;
+        crate::save_file::save_json(Path::new(Self::SAVE_FILE), Self::SCHEMA_VERSION, self)?;
         Ok(())
     }
     
@@ -167,6 +212,23 @@ impl PlayerProgress {
     pub fn is_level_completed(&self, level: usize) -> bool {
         level < self.completed_levels.len() && self.completed_levels[level]
     }
+
+    /// Record a finished level on the campaign graph and unlock its successors
+    pub fn complete_campaign_level(&mut self, campaign: &Campaign, level: usize) {
+        let newly_unlocked = campaign.complete_level(level, &mut self.completed_nodes, &mut self.unlocked_nodes);
+        for id in &newly_unlocked {
+            log::info!("Campaign node unlocked: {}", id);
+        }
+        let _ = self.save();
+    }
+
+    pub fn is_node_completed(&self, id: &str) -> bool {
+        self.completed_nodes.iter().any(|n| n == id)
+    }
+
+    pub fn is_node_unlocked(&self, id: &str) -> bool {
+        self.unlocked_nodes.iter().any(|n| n == id)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -180,8 +242,159 @@ pub struct GameSettings {
     pub font_size_multiplier: f32,
     pub autocomplete_enabled: bool,
     pub vscode_integration_enabled: bool,
+    #[serde(default)]
+    pub toasts: ToastSettings, // Which minor events show as toasts instead of popups
+    #[serde(default = "default_show_me_enabled")]
+    pub show_me_enabled: bool, // "Show me" reference solution playback; off for classroom use
+    #[serde(default)]
+    pub execution_speed: ExecutionSpeed, // How fast robot programs play out on the grid
+    #[serde(default)]
+    pub palette: crate::theme::Palette, // Grid colors, including colorblind-safe and high-contrast ones
+    #[serde(default = "default_grid_zoom")]
+    pub grid_zoom: f32, // Grid camera zoom, 1.0 = normal tile size
+    #[serde(default)]
+    pub language: crate::i18n::Language, // UI language; levels can carry their own translations
+    #[serde(default)]
+    pub screen_reader: bool, // Read popups, task messages and menu focus aloud
+    #[serde(default = "default_tutorial_ghost_delay")]
+    pub tutorial_ghost_delay: f32, // Idle seconds before the tutorial ghost demonstrates the next step; 0 = off
+    #[serde(default = "default_filter_community_text")]
+    pub filter_community_text: bool, // Mask offensive words in community level texts
+    #[serde(default = "default_community_index_url")]
+    pub community_index_url: String, // JSON manifest the community browser lists levels from
+    #[serde(default)]
+    pub vim_mode: bool, // Modal Vim-style editing in the code editor
+    #[serde(default)]
+    pub hotkey_preset: HotkeyPreset, // Default (VS Code-like) or Emacs editor bindings
+    #[serde(default)]
+    pub editor: EditorSettings, // Indentation, key repeat and gutter of the code editor
+    #[serde(default)]
+    pub daily_challenge_url: String, // Optional endpoint Daily Challenge results are POSTed to
+    #[serde(default = "default_sandbox_width")]
+    pub sandbox_width: usize, // Grid size of Sandbox mode, in tiles
+    #[serde(default = "default_sandbox_height")]
+    pub sandbox_height: usize,
+    #[serde(default = "default_auto_grab")]
+    pub auto_grab: bool, // Moving onto an item picks it up; levels can pin it either way
+    #[serde(default = "default_player_name")]
+    pub player_name: String, // Name printed on the completion certificate
 }
 
+/// Pause between robot actions while a program runs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ExecutionSpeed {
+    Instant,
+    Fast,
+    #[default]
+    Normal,
+    Slow,
+}
+
+impl ExecutionSpeed {
+    pub const ALL: [ExecutionSpeed; 4] = [ExecutionSpeed::Instant, ExecutionSpeed::Fast, ExecutionSpeed::Normal, ExecutionSpeed::Slow];
+
+    /// Seconds each robot action takes on screen
+    pub fn step_seconds(&self) -> f64 {
+        match self {
+            ExecutionSpeed::Instant => 0.0,
+            ExecutionSpeed::Fast => 0.12,
+            ExecutionSpeed::Normal => 0.35,
+            ExecutionSpeed::Slow => 0.8,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExecutionSpeed::Instant => "Instant",
+            ExecutionSpeed::Fast => "Fast",
+            ExecutionSpeed::Normal => "Normal",
+            ExecutionSpeed::Slow => "Slow",
+        }
+    }
+}
+
+/// How the code editor indents and repeats keys, set on the editor settings page
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EditorSettings {
+    pub tab_width: usize,       // Columns one indentation level takes
+    pub insert_spaces: bool,    // Tab and auto-indent insert spaces rather than a tab character
+    pub auto_indent: bool,      // Enter keeps the line's indentation and indents after `{`
+    pub key_repeat_delay: f32,  // Seconds a key is held before it repeats
+    pub key_repeat_rate: f32,   // Repeats per second after that
+    pub line_numbers: bool,     // Gutter with line numbers left of the code
+    pub word_wrap: bool,        // Long lines continue on the next row instead of scrolling sideways
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        Self {
+            tab_width: 4,
+            insert_spaces: true,
+            auto_indent: true,
+            key_repeat_delay: 0.5,
+            key_repeat_rate: 20.0,
+            line_numbers: true,
+            word_wrap: false,
+        }
+    }
+}
+
+impl EditorSettings {
+    /// What Tab inserts and auto-indent adds per level
+    pub fn indent_unit(&self) -> String {
+        if self.insert_spaces {
+            " ".repeat(self.tab_width)
+        } else {
+            "\t".to_string()
+        }
+    }
+}
+
+// Choices the editor settings buttons cycle through
+const TAB_WIDTHS: [usize; 3] = [2, 4, 8];
+const KEY_REPEAT_DELAYS: [f32; 4] = [0.25, 0.4, 0.5, 0.75];
+const KEY_REPEAT_RATES: [f32; 4] = [10.0, 20.0, 30.0, 50.0];
+
+fn default_show_me_enabled() -> bool {
+    true
+}
+
+fn default_auto_grab() -> bool {
+    true
+}
+
+fn default_grid_zoom() -> f32 {
+    1.0
+}
+
+fn default_tutorial_ghost_delay() -> f32 {
+    20.0
+}
+
+fn default_filter_community_text() -> bool {
+    true
+}
+
+fn default_community_index_url() -> String {
+    crate::community::DEFAULT_INDEX_URL.to_string()
+}
+
+fn default_player_name() -> String {
+    std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_else(|_| "Player".to_string())
+}
+
+fn default_sandbox_width() -> usize {
+    40
+}
+
+fn default_sandbox_height() -> usize {
+    30
+}
+
+// Choices the settings button cycles through for the tutorial ghost
+const TUTORIAL_GHOST_DELAYS: [f32; 4] = [0.0, 10.0, 20.0, 40.0];
+
 impl Default for GameSettings {
     fn default() -> Self {
         Self {
@@ -194,40 +407,50 @@ impl Default for GameSettings {
             font_size_multiplier: 1.0,
             autocomplete_enabled: true,
             vscode_integration_enabled: true,
+            toasts: ToastSettings::default(),
+            show_me_enabled: true,
+            execution_speed: ExecutionSpeed::default(),
+            palette: crate::theme::Palette::default(),
+            grid_zoom: default_grid_zoom(),
+            language: crate::i18n::Language::default(),
+            screen_reader: false,
+            tutorial_ghost_delay: default_tutorial_ghost_delay(),
+            filter_community_text: true,
+            community_index_url: default_community_index_url(),
+            vim_mode: false,
+            hotkey_preset: HotkeyPreset::default(),
+            editor: EditorSettings::default(),
+            daily_challenge_url: String::new(),
+            sandbox_width: default_sandbox_width(),
+            sandbox_height: default_sandbox_height(),
+            auto_grab: true,
+            player_name: default_player_name(),
         }
     }
 }
 
 impl GameSettings {
     const SAVE_FILE: &'static str = "game_settings.json";
+    const SCHEMA_VERSION: u32 = 1;
     
     pub fn load_or_default() -> Self {
-        if Path::new(Self::SAVE_FILE).exists() {
-            match fs::read_to_string(Self::SAVE_FILE) {
-                Ok(contents) => {
-                    match serde_json::from_str::<GameSettings>(&contents) {
-                        Ok(settings) => settings,
-                        Err(_) => {
-                            // If file is corrupted, create new settings and save them
-                            let default = Self::default();
-                            let _ = default.save();
-                            default
-                        }
-                    }
-                }
-                Err(_) => Self::default(),
+        // Unit tests build whole games; keep them off the player's own save file
+        if cfg!(test) {
+            return Self::default();
+        }
+        match crate::save_file::load_json(Path::new(Self::SAVE_FILE), Self::SCHEMA_VERSION) {
+            Ok(Some(settings)) => settings,
+            // No save file yet, or a corrupted one without a backup: start over and save that
+            Ok(None) | Err(_) => {
+                let default = Self::default();
+                let _ = default.save();
+                default
             }
-        } else {
-            // Create new save file
-            let default = Self::default();
-            let _ = default.save();
-            default
         }
     }
     
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let json = serde_json::to_string_pretty(self)?;
-        fs::write(Self::SAVE_FILE, json)?;
+        crate::save_file::save_json(Path::new(Self::SAVE_FILE), Self::SCHEMA_VERSION, self)?;
         Ok(())
     }
     
@@ -249,6 +472,17 @@ pub struct Menu {
     pub last_screen_width: f32,
     pub last_screen_height: f32,
     pub total_levels: usize, // Total number of levels available
+    pub campaign: Option<Campaign>, // Campaign graph from campaign.yaml, replaces the flat level list
+    pub focused: Option<usize>, // Button selected with the keyboard; None until an arrow key or Tab is used
+    pub community: CommunityBrowser,
+    pub hotkey_conflicts: Vec<crate::hotkeys::HotkeyConflict>, // Shown on the hotkey settings page to be resolved
+    pub certificate_status: Option<String>, // Result of the last certificate export, shown on the Stats screen
+    pub bundle_status: Option<String>, // Result of the last settings export or import, shown on the Settings screen
+    pub cache_stats: Option<crate::cache::CacheStats>, // Filled in by the game, which owns the cache, when the cache page opens
+    pub cache_cleared: bool,
+    pub level_load_errors: Vec<String>, // Community level files the loader couldn't load, shown on the main menu
+    pub levels: Vec<LevelInfo>, // Names, tags and difficulty of the game's levels, for the level list
+    pub level_search: LevelSearch,
 }
 
 impl Menu {
@@ -263,7 +497,26 @@ impl Menu {
             last_screen_width: crate::crash_protection::safe_screen_width(),
             last_screen_height: crate::crash_protection::safe_screen_height(),
             total_levels: 0, // Will be set when game starts
+            campaign: Campaign::load_default(),
+            focused: None,
+            community: CommunityBrowser::new(),
+            hotkey_conflicts: Vec::new(),
+            certificate_status: None,
+            bundle_status: None,
+            cache_stats: None,
+            cache_cleared: false,
+            level_load_errors: Vec::new(),
+            levels: Vec::new(),
+            level_search: LevelSearch::default(),
         };
+        // Root nodes (and anything a changed campaign.yaml now allows) start unlocked
+        if let Some(campaign) = &menu.campaign
+            && !campaign.unlock_available(&menu.progress.completed_nodes, &mut menu.progress.unlocked_nodes).is_empty()
+        {
+            let _ = menu.progress.save();
+        }
+        crate::i18n::set_language(menu.settings.language);
+        crate::speech::set_enabled(menu.settings.screen_reader);
         menu.setup_main_menu();
         menu
     }
@@ -291,12 +544,12 @@ impl Menu {
         
         let screen_center_x = crate::crash_protection::safe_screen_width() / 2.0;
         let button_width = scale_size(300.0);
-        let button_height = scale_size(60.0);
-        let button_spacing = scale_size(80.0);
+        let button_height = scale_size(46.0);
+        let button_spacing = scale_size(52.0); // Tighter spacing so eight buttons fit at 720p
         let start_y = crate::crash_protection::safe_screen_height() / 2.0;
 
         self.buttons.push(MenuButton::new(
-            "Start Learning".to_string(),
+            tr("menu.start_learning"),
             screen_center_x - button_width / 2.0,
             start_y - button_spacing * 1.0,
             button_width,
@@ -305,7 +558,7 @@ impl Menu {
         ));
 
         self.buttons.push(MenuButton::new(
-            "Load Level".to_string(),
+            tr(if self.campaign.is_some() { "menu.world_map" } else { "menu.load_level" }),
             screen_center_x - button_width / 2.0,
             start_y,
             button_width,
@@ -314,29 +567,67 @@ impl Menu {
         ));
 
         self.buttons.push(MenuButton::new(
-            "Community Levels".to_string(),
+            tr("menu.endless"),
             screen_center_x - button_width / 2.0,
             start_y + button_spacing * 1.0,
             button_width,
             button_height,
-            MenuAction::OpenCommunityLevels,
+            MenuAction::StartEndless,
         ));
 
         self.buttons.push(MenuButton::new(
-            "Settings".to_string(),
+            tr("menu.daily"),
             screen_center_x - button_width / 2.0,
             start_y + button_spacing * 2.0,
             button_width,
             button_height,
-            MenuAction::OpenSettings,
+            MenuAction::StartDailyChallenge,
         ));
 
         self.buttons.push(MenuButton::new(
-            "Exit".to_string(),
+            tr("menu.sandbox"),
             screen_center_x - button_width / 2.0,
             start_y + button_spacing * 3.0,
             button_width,
             button_height,
+            MenuAction::StartSandbox,
+        ));
+
+        self.buttons.push(MenuButton::new(
+            tr("menu.community"),
+            screen_center_x - button_width / 2.0,
+            start_y + button_spacing * 4.0,
+            button_width,
+            button_height,
+            MenuAction::OpenCommunityLevels,
+        ));
+
+        // Stats and Settings share a row
+        let half_width = (button_width - scale_size(10.0)) / 2.0;
+        self.buttons.push(MenuButton::new(
+            tr("menu.stats"),
+            screen_center_x - button_width / 2.0,
+            start_y + button_spacing * 5.0,
+            half_width,
+            button_height,
+            MenuAction::OpenStats,
+        ));
+
+        self.buttons.push(MenuButton::new(
+            tr("menu.settings"),
+            screen_center_x + button_width / 2.0 - half_width,
+            start_y + button_spacing * 5.0,
+            half_width,
+            button_height,
+            MenuAction::OpenSettings,
+        ));
+
+        self.buttons.push(MenuButton::new(
+            tr("menu.exit"),
+            screen_center_x - button_width / 2.0,
+            start_y + button_spacing * 6.0,
+            button_width,
+            button_height,
             MenuAction::Exit,
         ));
     }
@@ -351,15 +642,14 @@ impl Menu {
         self.buttons.clear();
         
         let screen_center_x = crate::crash_protection::safe_screen_width() / 2.0;
-        let button_width = scale_size(400.0);
-        let button_height = scale_size(50.0);
-        let button_spacing = scale_size(70.0);
-        let start_y = crate::crash_protection::safe_screen_height() / 2.0 - scale_size(100.0);
+        let button_width = scale_size(520.0); // Wide enough for two buttons a row
+        let button_height = scale_size(40.0);
+        let button_spacing = scale_size(44.0); // Tighter spacing so thirteen rows of buttons fit at 720p
+        let start_y = crate::crash_protection::safe_screen_height() / 2.0 - scale_size(260.0);
 
         // Resolution buttons
         self.buttons.push(MenuButton::new(
-            format!("Resolution: {}x{} (Click: Next, Right-Click: Previous)", 
-                   self.settings.window_width, self.settings.window_height),
+            tr_with("settings.resolution", &[("value", &format!("{}x{}", self.settings.window_width, self.settings.window_height))]),
             screen_center_x - button_width / 2.0,
             start_y,
             button_width,
@@ -369,8 +659,7 @@ impl Menu {
 
         // Fullscreen toggle
         self.buttons.push(MenuButton::new(
-            format!("Fullscreen: {} (Click to Toggle)", 
-                   if self.settings.fullscreen { "On" } else { "Off" }),
+            tr_with("settings.fullscreen", &[("value", &tr(if self.settings.fullscreen { "settings.on" } else { "settings.off" }))]),
             screen_center_x - button_width / 2.0,
             start_y + button_spacing,
             button_width,
@@ -378,55 +667,251 @@ impl Menu {
             MenuAction::ToggleFullscreen,
         ));
 
-        // Autocomplete toggle
+        // Code editor settings button
         self.buttons.push(MenuButton::new(
-            format!("Autocomplete: {} (Click to Toggle)",
-                   if true { "On" } else { "Off" }), // TODO: Get actual autocomplete state
+            tr("settings.editor"),
             screen_center_x - button_width / 2.0,
             start_y + button_spacing * 2.0,
             button_width,
             button_height,
-            MenuAction::ToggleAutocomplete,
+            MenuAction::OpenEditorSettings,
         ));
 
-        // Font size control
+        // Reference solution playback (turned off for classroom mode)
         self.buttons.push(MenuButton::new(
-            format!("Font Size: {:.0}% (Click: +10%, Right-Click: -10%)",
-                   self.settings.font_size_multiplier * 100.0),
+            tr_with("settings.show_me", &[("value", &tr(if self.settings.show_me_enabled { "settings.on" } else { "settings.off_classroom" }))]),
             screen_center_x - button_width / 2.0,
             start_y + button_spacing * 3.0,
             button_width,
             button_height,
-            MenuAction::IncreaseFontSize,
+            MenuAction::ToggleShowMe,
         ));
 
-        // Hotkey settings button
+        // Picking up items on every move, or only on grab()
         self.buttons.push(MenuButton::new(
-            "Hotkey Settings".to_string(),
+            tr_with("settings.auto_grab", &[("value", &tr(if self.settings.auto_grab { "settings.on" } else { "settings.off" }))]),
             screen_center_x - button_width / 2.0,
             start_y + button_spacing * 4.0,
             button_width,
             button_height,
+            MenuAction::ToggleAutoGrab,
+        ));
+
+        // Grid color palette
+        self.buttons.push(MenuButton::new(
+            tr_with("settings.palette", &[("value", self.settings.palette.label())]),
+            screen_center_x - button_width / 2.0,
+            start_y + button_spacing * 5.0,
+            button_width,
+            button_height,
+            MenuAction::CyclePalette,
+        ));
+
+        // UI language
+        self.buttons.push(MenuButton::new(
+            tr_with("settings.language", &[("value", self.settings.language.native_name())]),
+            screen_center_x - button_width / 2.0,
+            start_y + button_spacing * 6.0,
+            button_width,
+            button_height,
+            MenuAction::CycleLanguage,
+        ));
+
+        // Text-to-speech for popups, tasks and menus
+        self.buttons.push(MenuButton::new(
+            tr_with("settings.screen_reader", &[("value", &tr(if self.settings.screen_reader { "settings.on" } else { "settings.off" }))]),
+            screen_center_x - button_width / 2.0,
+            start_y + button_spacing * 7.0,
+            button_width,
+            button_height,
+            MenuAction::ToggleScreenReader,
+        ));
+
+        // Idle time before the tutorial ghost shows the next step
+        let ghost = if self.settings.tutorial_ghost_delay > 0.0 {
+            tr_with("settings.ghost_after", &[("seconds", &format!("{:.0}", self.settings.tutorial_ghost_delay))])
+        } else {
+            tr("settings.off")
+        };
+        self.buttons.push(MenuButton::new(
+            tr_with("settings.ghost", &[("value", &ghost)]),
+            screen_center_x - button_width / 2.0,
+            start_y + button_spacing * 8.0,
+            button_width,
+            button_height,
+            MenuAction::CycleGhostDelay,
+        ));
+
+        // Font size control
+        self.buttons.push(MenuButton::new(
+            tr_with("settings.font_size", &[("value", &format!("{:.0}", self.settings.font_size_multiplier * 100.0))]),
+            screen_center_x - button_width / 2.0,
+            start_y + button_spacing * 9.0,
+            button_width,
+            button_height,
+            MenuAction::IncreaseFontSize,
+        ));
+
+        // Hotkey and notification settings buttons share a row
+        let half_width = (button_width - scale_size(10.0)) / 2.0;
+        self.buttons.push(MenuButton::new(
+            tr("settings.hotkeys"),
+            screen_center_x - button_width / 2.0,
+            start_y + button_spacing * 10.0,
+            half_width,
+            button_height,
             MenuAction::OpenHotkeySettings,
         ));
 
+        self.buttons.push(MenuButton::new(
+            tr("settings.notifications"),
+            screen_center_x + button_width / 2.0 - half_width,
+            start_y + button_spacing * 10.0,
+            half_width,
+            button_height,
+            MenuAction::OpenNotificationSettings,
+        ));
+
+        // Settings bundle for moving to another machine
+        self.buttons.push(MenuButton::new(
+            tr("settings.export"),
+            screen_center_x - button_width / 2.0,
+            start_y + button_spacing * 11.0,
+            half_width,
+            button_height,
+            MenuAction::ExportSettings,
+        ));
+
+        self.buttons.push(MenuButton::new(
+            tr("settings.import"),
+            screen_center_x + button_width / 2.0 - half_width,
+            start_y + button_spacing * 11.0,
+            half_width,
+            button_height,
+            MenuAction::ImportSettings,
+        ));
+
         // Back button - context-aware
         let (back_text, back_action) = if self.opened_from_game {
-            ("Back to Game".to_string(), MenuAction::BackToGame)
+            (tr("settings.back_to_game"), MenuAction::BackToGame)
         } else {
-            ("Back to Main".to_string(), MenuAction::BackToMain)
+            (tr("settings.back_to_main"), MenuAction::BackToMain)
         };
 
+        self.buttons.push(MenuButton::new(
+            tr("settings.cache"),
+            screen_center_x - button_width / 2.0,
+            start_y + button_spacing * 12.0,
+            half_width,
+            button_height,
+            MenuAction::OpenCacheSettings,
+        ));
+
         self.buttons.push(MenuButton::new(
             back_text,
+            screen_center_x + button_width / 2.0 - half_width,
+            start_y + button_spacing * 12.0,
+            half_width,
+            button_height,
+            back_action,
+        ));
+    }
+
+    /// One toggle per toast event type
+    pub fn setup_notification_settings_menu(&mut self) {
+        self.buttons.clear();
+
+        let screen_center_x = crate::crash_protection::safe_screen_width() / 2.0;
+        let button_width = scale_size(500.0);
+        let button_height = scale_size(50.0);
+        let button_spacing = scale_size(70.0);
+        let start_y = crate::crash_protection::safe_screen_height() / 2.0 - scale_size(150.0);
+
+        for (i, event) in ToastEvent::ALL.iter().enumerate() {
+            let mode = tr(if self.settings.toasts.enabled(*event) { "notifications.toast" } else { "notifications.popup" });
+            self.buttons.push(MenuButton::new(
+                tr_with("notifications.toggle", &[("event", event.label()), ("mode", &mode)]),
+                screen_center_x - button_width / 2.0,
+                start_y + button_spacing * i as f32,
+                button_width,
+                button_height,
+                MenuAction::ToggleToast(*event),
+            ));
+        }
+
+        self.buttons.push(MenuButton::new(
+            tr("settings.back_to_settings"),
             screen_center_x - button_width / 2.0,
-            start_y + button_spacing * 5.0,
+            start_y + button_spacing * ToastEvent::ALL.len() as f32,
             button_width,
             button_height,
-            back_action,
+            MenuAction::BackToSettings,
         ));
     }
 
+    /// Clear Cache and Back; the statistics are drawn by draw_cache_settings_menu
+    pub fn setup_cache_settings_menu(&mut self) {
+        self.buttons.clear();
+
+        let screen_center_x = crate::crash_protection::safe_screen_width() / 2.0;
+        let button_width = scale_size(500.0);
+        let button_height = scale_size(50.0);
+        let start_y = crate::crash_protection::safe_screen_height() / 2.0 + scale_size(100.0);
+
+        self.buttons.push(MenuButton::new(
+            tr("cache.clear"),
+            screen_center_x - button_width / 2.0,
+            start_y,
+            button_width,
+            button_height,
+            MenuAction::ClearCache,
+        ));
+
+        self.buttons.push(MenuButton::new(
+            tr("settings.back_to_settings"),
+            screen_center_x - button_width / 2.0,
+            start_y + scale_size(70.0),
+            button_width,
+            button_height,
+            MenuAction::BackToSettings,
+        ));
+    }
+
+    /// Indentation, autocomplete, key repeat and gutter options of the code editor
+    pub fn setup_editor_settings_menu(&mut self) {
+        self.buttons.clear();
+
+        let screen_center_x = crate::crash_protection::safe_screen_width() / 2.0;
+        let button_width = scale_size(500.0);
+        let button_height = scale_size(50.0);
+        let button_spacing = scale_size(70.0);
+        let start_y = crate::crash_protection::safe_screen_height() / 2.0 - scale_size(280.0);
+        let on_off = |enabled: bool| tr(if enabled { "settings.on" } else { "settings.off" });
+        let editor = &self.settings.editor;
+
+        let rows = [
+            (tr_with("editor.tab_width", &[("value", &editor.tab_width.to_string())]), MenuAction::CycleTabWidth),
+            (tr_with("editor.indent_with", &[("value", &tr(if editor.insert_spaces { "editor.spaces" } else { "editor.tabs" }))]), MenuAction::ToggleInsertSpaces),
+            (tr_with("editor.auto_indent", &[("value", &on_off(editor.auto_indent))]), MenuAction::ToggleAutoIndent),
+            (tr_with("settings.autocomplete", &[("value", &on_off(self.settings.autocomplete_enabled))]), MenuAction::ToggleAutocomplete),
+            (tr_with("editor.repeat_delay", &[("value", &format!("{:.2}", editor.key_repeat_delay))]), MenuAction::CycleKeyRepeatDelay),
+            (tr_with("editor.repeat_rate", &[("value", &format!("{:.0}", editor.key_repeat_rate))]), MenuAction::CycleKeyRepeatRate),
+            (tr_with("editor.line_numbers", &[("value", &on_off(editor.line_numbers))]), MenuAction::ToggleLineNumbers),
+            (tr_with("editor.long_lines", &[("value", &tr(if editor.word_wrap { "editor.wrap" } else { "editor.scroll" }))]), MenuAction::ToggleWordWrap),
+            (tr("settings.back_to_settings"), MenuAction::BackToSettings),
+        ];
+        for (i, (text, action)) in rows.into_iter().enumerate() {
+            self.buttons.push(MenuButton::new(
+                text,
+                screen_center_x - button_width / 2.0,
+                start_y + button_spacing * i as f32,
+                button_width,
+                button_height,
+                action,
+            ));
+        }
+    }
+
     pub fn setup_hotkey_settings_menu(&mut self) {
         self.buttons.clear();
 
@@ -447,28 +932,68 @@ impl Menu {
         ));
 
         // Display current key bindings (first few important ones)
-        let key_bindings = vec![
-            ("Tab", "Accept Autocomplete / Indent"),
-            ("Ctrl+S", "Save File"),
-            ("Ctrl+Shift+Enter", "Run Code"),
-            ("Ctrl+`", "Toggle Editor"),
-            ("Ctrl+Z", "Undo"),
-            ("Ctrl+Y", "Redo"),
-        ];
+        let key_bindings: &[(&str, &str)] = match self.settings.hotkey_preset {
+            HotkeyPreset::Default => &[
+                ("Tab", "Accept Autocomplete / Indent"),
+                ("Ctrl+S", "Save File"),
+                ("Ctrl+Shift+Enter", "Run Code"),
+                ("Ctrl+`", "Toggle Editor"),
+                ("Ctrl+Z", "Undo"),
+                ("Ctrl+Y", "Redo"),
+                ("Ctrl+K Ctrl+C", "Comment Lines"),
+                ("Ctrl+Shift+V", "Clipboard History"),
+            ],
+            HotkeyPreset::Emacs => &[
+                ("Ctrl+A / Ctrl+E", "Line Start / End"),
+                ("Ctrl+K", "Kill Line"),
+                ("Ctrl+Y", "Yank"),
+                ("Ctrl+Space", "Set Mark"),
+                ("Ctrl+W / Alt+W", "Cut / Copy Region"),
+                ("Ctrl+X Ctrl+S", "Save File"),
+                ("Ctrl+Shift+Enter", "Run Code"),
+            ],
+        };
 
-        for (i, (key, action)) in key_bindings.iter().enumerate() {
+        // Conflicting bindings take the place of the overview, one conflict at a time
+        let rows: Vec<(String, MenuAction)> = match self.hotkey_conflicts.first() {
+            Some(conflict) => {
+                let mut rows = vec![(
+                    tr_with("settings.hotkey_conflict", &[
+                        ("combo", &conflict.combo),
+                        ("total", &self.hotkey_conflicts.len().to_string()),
+                    ]),
+                    MenuAction::None,
+                )];
+                for (i, hotkey) in conflict.bindings.iter().enumerate() {
+                    rows.push((
+                        tr_with("settings.hotkey_keep", &[
+                            ("action", &format!("{:?}", hotkey.action)),
+                            ("combo", &hotkey.combo),
+                            ("context", hotkey.context.label()),
+                        ]),
+                        MenuAction::ResolveHotkeyConflict(0, i),
+                    ));
+                }
+                rows
+            }
+            None => key_bindings.iter()
+                .map(|(key, action)| (format!("{}: {}", key, action), MenuAction::None))
+                .collect(),
+        };
+
+        for (i, (text, action)) in rows.iter().enumerate() {
             self.buttons.push(MenuButton::new(
-                format!("{}: {}", key, action),
+                text.clone(),
                 screen_center_x - button_width / 2.0,
                 start_y + (i as f32 * button_spacing * 0.8),
                 button_width,
                 button_height * 0.8,
-                MenuAction::None,
+                action.clone(),
             ));
         }
 
         // Import buttons
-        let import_y = start_y + (key_bindings.len() as f32 * button_spacing * 0.8) + button_spacing;
+        let import_y = start_y + (rows.len() as f32 * button_spacing * 0.8) + button_spacing;
 
         self.buttons.push(MenuButton::new(
             "Import VSCode Keybindings".to_string(),
@@ -479,22 +1004,24 @@ impl Menu {
             MenuAction::None, // TODO: Add import actions
         ));
 
+        // Turning Vim mode on also brings in the mappings from ~/.vimrc
         self.buttons.push(MenuButton::new(
-            "Import Vim Settings".to_string(),
+            tr_with("settings.vim_mode", &[("value", &tr(if self.settings.vim_mode { "settings.on" } else { "settings.off" }))]),
             screen_center_x - button_width / 2.0,
             import_y + button_spacing,
             button_width,
             button_height,
-            MenuAction::None, // TODO: Add import actions
+            MenuAction::ToggleVimMode,
         ));
 
+        // Picking the Emacs preset also brings in the global-set-key lines of the Emacs init file
         self.buttons.push(MenuButton::new(
-            "Import Emacs Settings".to_string(),
+            tr_with("settings.hotkey_preset", &[("value", self.settings.hotkey_preset.label())]),
             screen_center_x - button_width / 2.0,
             import_y + button_spacing * 2.0,
             button_width,
             button_height,
-            MenuAction::None, // TODO: Add import actions
+            MenuAction::CycleHotkeyPreset,
         ));
 
         // Reset and back buttons
@@ -504,11 +1031,11 @@ impl Menu {
             import_y + button_spacing * 3.5,
             button_width,
             button_height,
-            MenuAction::None, // TODO: Add reset action
+            MenuAction::ResetHotkeys,
         ));
 
         self.buttons.push(MenuButton::new(
-            "Back to Settings".to_string(),
+            tr("settings.back_to_settings"),
             screen_center_x - button_width / 2.0,
             import_y + button_spacing * 4.5,
             button_width,
@@ -534,81 +1061,351 @@ impl Menu {
                 MenuState::MainMenu => self.setup_main_menu(),
                 MenuState::Settings => self.setup_settings_menu(),
                 MenuState::LevelSelect => self.setup_level_select_menu(),
+                MenuState::WorldMap => self.setup_world_map_menu(),
                 MenuState::HotkeySettings => self.setup_hotkey_settings_menu(),
+                MenuState::NotificationSettings => self.setup_notification_settings_menu(),
+                MenuState::EditorSettings => self.setup_editor_settings_menu(),
+                MenuState::CacheSettings => self.setup_cache_settings_menu(),
+                MenuState::CommunityBrowser => self.setup_community_menu(),
+                MenuState::Stats => self.setup_stats_menu(),
                 MenuState::InGame => {}, // No menu to refresh
             }
         }
     }
 
+    /// Filter buttons, one row per unlocked level the search lets through (a page at a time) and Back
     pub fn setup_level_select_menu(&mut self) {
         self.buttons.clear();
         
         let screen_center_x = crate::crash_protection::safe_screen_width() / 2.0;
-        let button_width = scale_size(300.0);
-        let button_height = scale_size(40.0);
-        let button_spacing = scale_size(45.0);
-        let buttons_per_row = 3;
-        let row_spacing = scale_size(70.0);
-        
-        let start_y = crate::crash_protection::safe_screen_height() * 0.2;
-        
-        // Create level buttons for unlocked levels
-        let mut row = 0;
-        let mut col = 0;
-        
-        for level in 0..=self.progress.max_level_unlocked {
-            if level >= self.total_levels {
-                break; // Don't show levels that don't exist
+        let screen_height = crate::crash_protection::safe_screen_height();
+        let button_width = scale_size(720.0);
+        let button_height = scale_size(36.0);
+        let row_spacing = scale_size(42.0);
+        let start_y = scale_size(215.0);
+
+        // Topic, status and difficulty filters share the top row
+        let filter_width = (button_width - scale_size(20.0)) / 3.0;
+        let all = tr("level_select.all");
+        let status = match self.level_search.completion {
+            CompletionFilter::All => all.clone(),
+            CompletionFilter::Completed => tr("level_select.completed"),
+            CompletionFilter::Uncompleted => tr("level_select.uncompleted"),
+        };
+        let filters = [
+            (tr_with("level_select.topic", &[("value", self.level_search.tag.as_ref().unwrap_or(&all))]), MenuAction::CycleLevelTopic),
+            (tr_with("level_select.status", &[("value", &status)]), MenuAction::CycleLevelStatus),
+            (tr_with("level_select.difficulty", &[("value", self.level_search.difficulty.as_ref().unwrap_or(&all))]), MenuAction::CycleLevelDifficulty),
+        ];
+        for (i, (text, action)) in filters.into_iter().enumerate() {
+            self.buttons.push(MenuButton::new(
+                text,
+                screen_center_x - button_width / 2.0 + (filter_width + scale_size(10.0)) * i as f32,
+                start_y,
+                filter_width,
+                button_height,
+                action,
+            ));
+        }
+
+        // As many rows as fit above the Back button
+        let list_y = start_y + row_spacing * 1.5;
+        let per_page = (((screen_height - list_y - scale_size(130.0)) / row_spacing) as usize).max(1);
+        let matches = self.matching_levels();
+        let pages = matches.len().div_ceil(per_page).max(1);
+        self.level_search.page = self.level_search.page.min(pages - 1);
+
+        for (row, &level) in matches.iter().skip(self.level_search.page * per_page).take(per_page).enumerate() {
+            let info = &self.levels[level];
+            let mut label = info.name.clone();
+            if self.progress.is_level_completed(level) {
+                label.push_str(" ✓");
+            }
+            let details = info.details();
+            if !details.is_empty() {
+                label = format!("{}  {}", label, details);
             }
-            
-            let x_offset = (col as f32 - (buttons_per_row as f32 - 1.0) / 2.0) * (button_width + scale_size(20.0));
-            let x = screen_center_x + x_offset;
-            let y = start_y + row as f32 * row_spacing;
-            
-            let level_name = if level < self.total_levels && level < 50 { // Reasonable upper limit
-                format!("Level {} {}", level + 1, if self.progress.is_level_completed(level) { "✓" } else { "" })
-            } else {
-                format!("Level {}", level + 1)
-            };
-            
             self.buttons.push(MenuButton::new(
-                level_name,
-                x - button_width / 2.0,
-                y,
+                label,
+                screen_center_x - button_width / 2.0,
+                list_y + row as f32 * row_spacing,
                 button_width,
                 button_height,
                 MenuAction::SelectLevel(level),
             ));
-            
-            col += 1;
-            if col >= buttons_per_row {
-                col = 0;
-                row += 1;
+        }
+        
+        self.buttons.push(MenuButton::new(
+            "Back to Main Menu".to_string(),
+            screen_center_x - scale_size(150.0),
+            list_y + per_page as f32 * row_spacing + scale_size(20.0),
+            scale_size(300.0),
+            scale_size(40.0),
+            MenuAction::BackToMain,
+        ));
+    }
+
+    /// The levels `level` requires that aren't completed yet. A requirement naming no loaded level
+    /// can't ever be met, so it's left out (the loader reports it).
+    pub fn unmet_requirements(&self, level: usize) -> Vec<&str> {
+        let Some(info) = self.levels.get(level) else { return Vec::new() };
+        info.requires.iter()
+            .filter(|required| {
+                self.levels.iter().position(|other| other.name == **required)
+                    .is_some_and(|index| !self.progress.is_level_completed(index))
+            })
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Unlocked levels the search and filters let through
+    fn matching_levels(&self) -> Vec<usize> {
+        let unlocked = (self.progress.max_level_unlocked + 1).min(self.total_levels).min(self.levels.len());
+        (0..unlocked)
+            .filter(|&level| self.level_search.matches(&self.levels[level], self.progress.is_level_completed(level)))
+            .collect()
+    }
+
+    /// Typing edits the search, Backspace deletes and PageUp/PageDown turn the page.
+    /// The focus goes to the best match so Enter opens it.
+    fn handle_level_search_input(&mut self) {
+        let mut changed = false;
+        while let Some(c) = get_char_pressed() {
+            if !c.is_control() {
+                self.level_search.query.push(c);
+                changed = true;
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) && self.level_search.query.pop().is_some() {
+            changed = true;
+        }
+        if changed {
+            self.level_search.page = 0;
+        }
+
+        if is_key_pressed(KeyCode::PageDown) {
+            self.level_search.page += 1; // Clamped to the last page by setup_level_select_menu
+            changed = true;
+        } else if is_key_pressed(KeyCode::PageUp) && self.level_search.page > 0 {
+            self.level_search.page -= 1;
+            changed = true;
+        }
+
+        if changed {
+            self.setup_level_select_menu();
+            self.focused = self.buttons.iter().position(|button| matches!(button.action, MenuAction::SelectLevel(_)));
+        }
+    }
+    
+    /// One button per campaign node, placed at its map position; locked nodes are disabled
+    pub fn setup_world_map_menu(&mut self) {
+        self.buttons.clear();
+
+        let screen_width = crate::crash_protection::safe_screen_width();
+        let screen_height = crate::crash_protection::safe_screen_height();
+        let button_width = scale_size(300.0);
+        let button_height = scale_size(40.0);
+
+        if let Some(campaign) = &self.campaign {
+            for node in &campaign.nodes {
+                // Side levels are drawn smaller so the main path stands out
+                let (width, height) = if node.optional {
+                    (button_width * 0.75, button_height * 0.8)
+                } else {
+                    (button_width * 0.85, button_height)
+                };
+                let (px, py) = campaign.layout_position(&node.id);
+                let label = format!("{}{}", node.display_name(), if self.progress.is_node_completed(&node.id) { " ✓" } else { "" });
+
+                let mut button = MenuButton::new(
+                    label,
+                    px * screen_width - width / 2.0,
+                    py * screen_height - height / 2.0,
+                    width,
+                    height,
+                    MenuAction::SelectLevel(node.level),
+                );
+                // The level's own `requires:` has to be met on top of the node's
+                button.enabled = self.progress.is_node_unlocked(&node.id)
+                    && (self.total_levels == 0 || node.level < self.total_levels)
+                    && self.unmet_requirements(node.level).is_empty();
+                self.buttons.push(button);
             }
         }
-        
-        // Add back button at the bottom
-        let back_y = start_y + (row + 2) as f32 * row_spacing;
+
+        self.buttons.push(MenuButton::new(
+            "Back to Main Menu".to_string(),
+            screen_width / 2.0 - button_width / 2.0,
+            screen_height - scale_size(110.0),
+            button_width,
+            button_height,
+            MenuAction::BackToMain,
+        ));
+    }
+
+    /// One install button per listed level, with Refresh and Back below
+    pub fn setup_community_menu(&mut self) {
+        self.buttons.clear();
+
+        let screen_center_x = crate::crash_protection::safe_screen_width() / 2.0;
+        let screen_height = crate::crash_protection::safe_screen_height();
+        let button_width = scale_size(160.0);
+        let button_height = scale_size(40.0);
+        let row_spacing = scale_size(70.0);
+        let start_y = scale_size(170.0);
+        // Leave room for the Refresh/Back row and the hint at the bottom
+        let rows = ((screen_height - start_y - scale_size(160.0)) / row_spacing).max(1.0) as usize;
+
+        for (index, entry) in self.community.entries.iter().enumerate().take(rows) {
+            let installed = entry.is_installed();
+            let mut button = MenuButton::new(
+                tr(if installed { "community.installed" } else { "community.install" }),
+                screen_center_x - scale_size(450.0),
+                start_y + index as f32 * row_spacing,
+                button_width,
+                button_height,
+                MenuAction::InstallCommunityLevel(index),
+            );
+            button.enabled = !installed && !self.community.busy;
+            self.buttons.push(button);
+        }
+
+        let wide_width = scale_size(300.0);
+        let bottom_y = screen_height - scale_size(120.0);
+        let mut refresh = MenuButton::new(
+            tr("community.refresh"),
+            screen_center_x - wide_width - scale_size(10.0),
+            bottom_y,
+            wide_width,
+            button_height,
+            MenuAction::RefreshCommunityLevels,
+        );
+        refresh.enabled = !self.community.busy;
+        self.buttons.push(refresh);
+        self.buttons.push(MenuButton::new(
+            tr("settings.back_to_main"),
+            screen_center_x + scale_size(10.0),
+            bottom_y,
+            wide_width,
+            button_height,
+            MenuAction::BackToMain,
+        ));
+    }
+
+    /// Replace the settings, hotkeys and progress with the ones in settings_bundle.json. The game
+    /// picks up the new hotkeys and editor options when it sees MenuAction::ImportSettings.
+    fn import_settings(&mut self) -> Result<(), String> {
+        let bundle = SettingsBundle::load(Path::new(crate::settings_bundle::BUNDLE_FILE))?;
+        bundle.install_hotkeys()?;
+        self.settings = bundle.settings;
+        crate::i18n::set_language(self.settings.language);
+        crate::speech::set_enabled(self.settings.screen_reader);
+        self.settings.save().map_err(|e| e.to_string())?;
+        if let Some(progress) = bundle.progress {
+            self.progress = progress;
+            self.progress.save().map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Export Certificate and Back; the numbers themselves are drawn by draw_stats_menu
+    pub fn setup_stats_menu(&mut self) {
+        self.buttons.clear();
+
+        let screen_center_x = crate::crash_protection::safe_screen_width() / 2.0;
+        let button_width = scale_size(300.0);
+        let button_height = scale_size(40.0);
+        let bottom_y = crate::crash_protection::safe_screen_height() - scale_size(160.0);
+
+        let mut export = MenuButton::new(
+            tr("stats.export"),
+            screen_center_x - button_width - scale_size(10.0),
+            bottom_y,
+            button_width,
+            button_height,
+            MenuAction::ExportCertificate,
+        );
+        export.enabled = self.levels_completed() > 0;
+        self.buttons.push(export);
         self.buttons.push(MenuButton::new(
-            "Back to Main Menu".to_string(),
-            screen_center_x - button_width / 2.0,
-            back_y,
+            tr("settings.back_to_main"),
+            screen_center_x + scale_size(10.0),
+            bottom_y,
             button_width,
             button_height,
             MenuAction::BackToMain,
         ));
     }
-    
+
+    /// Levels of the flat level list the player has finished
+    pub fn levels_completed(&self) -> usize {
+        (0..self.total_levels).filter(|&level| self.progress.is_level_completed(level)).count()
+    }
+
+    /// Every required campaign node done, or every level when there's no campaign.yaml
+    pub fn campaign_complete(&self) -> bool {
+        match &self.campaign {
+            Some(campaign) => campaign.is_finished(&self.progress.completed_nodes),
+            None => self.total_levels > 0 && self.levels_completed() == self.total_levels,
+        }
+    }
+
+    /// The certificate for the player's progress as of today
+    pub fn certificate(&self) -> Certificate {
+        Certificate {
+            name: self.settings.player_name.clone(),
+            levels_completed: self.levels_completed(),
+            total_levels: self.total_levels,
+            campaign: self.campaign.as_ref().map(|campaign| campaign.name.clone()),
+            date: crate::leaderboard::date_label(crate::leaderboard::today()),
+        }
+    }
+
+    /// Save the certificate and remember the outcome for the Stats screen
+    pub fn export_certificate(&mut self) -> Result<std::path::PathBuf, String> {
+        let result = self.certificate().save();
+        self.certificate_status = Some(match &result {
+            Ok(path) => tr_with("stats.exported", &[("path", &path.display().to_string())]),
+            Err(e) => e.clone(),
+        });
+        result
+    }
+
+    /// Pick up finished community downloads, refreshing the browser if it's showing. Returns the
+    /// level files installed since the last call.
+    pub fn poll_community(&mut self) -> Vec<std::path::PathBuf> {
+        let (installed, changed) = self.community.poll();
+        if changed && self.state == MenuState::CommunityBrowser {
+            self.setup_community_menu();
+        }
+        installed
+    }
+
     pub fn set_total_levels(&mut self, count: usize) {
         self.total_levels = count;
     }
 
+    /// Hand the menu the game's levels, for the level list
+    pub fn set_levels(&mut self, levels: &[crate::level::LevelSpec]) {
+        self.levels = levels.iter().map(LevelInfo::from_spec).collect();
+        self.set_total_levels(levels.len());
+        match self.state {
+            MenuState::LevelSelect => self.setup_level_select_menu(),
+            MenuState::WorldMap => self.setup_world_map_menu(),
+            _ => {}
+        }
+    }
+
     pub fn handle_input(&mut self) -> MenuAction {
         // Only handle input when we're actually showing a menu, not when in-game
         if self.state == MenuState::InGame {
             return MenuAction::None;
         }
 
+        if self.state == MenuState::LevelSelect {
+            self.handle_level_search_input();
+        }
+
         // Use safe mouse position to prevent crashes when window loses focus
         let (mouse_x, mouse_y) = crate::crash_protection::safe_mouse_position();
 
@@ -625,19 +1422,30 @@ impl Menu {
         if is_mouse_button_pressed(MouseButton::Right) {
             for button in &self.buttons {
                 if button.is_clicked(mouse_x, mouse_y) {
-                    // Convert increase actions to decrease actions
-                    return match button.action {
-                        MenuAction::IncreaseResolution => MenuAction::DecreaseResolution,
-                        MenuAction::IncreaseSfxVolume => MenuAction::DecreaseSfxVolume,
-                        MenuAction::IncreaseMusicVolume => MenuAction::DecreaseMusicVolume,
-                        MenuAction::IncreaseFontSize => MenuAction::DecreaseFontSize,
-                        MenuAction::ToggleFullscreen => MenuAction::ToggleFullscreen,
-                        _ => button.action.clone(),
-                    };
+                    return Self::secondary_action(&button.action);
                 }
             }
         }
 
+        // Keyboard navigation: arrows and Tab move the focus, Enter or Space presses the focused
+        // button and Left does what a right-click does
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        if is_key_pressed(KeyCode::Down) || (is_key_pressed(KeyCode::Tab) && !shift) {
+            self.move_focus(1);
+        } else if is_key_pressed(KeyCode::Up) || (is_key_pressed(KeyCode::Tab) && shift) {
+            self.move_focus(-1);
+        }
+        if let Some(button) = self.focused.and_then(|i| self.buttons.get(i)) {
+            // Space types into the level search instead
+            let space = is_key_pressed(KeyCode::Space) && self.state != MenuState::LevelSelect;
+            if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) || space || is_key_pressed(KeyCode::Right) {
+                return button.action.clone();
+            }
+            if is_key_pressed(KeyCode::Left) {
+                return Self::secondary_action(&button.action);
+            }
+        }
+
         // Handle keyboard shortcuts
         if is_key_pressed(KeyCode::Escape) {
             match self.state {
@@ -649,7 +1457,12 @@ impl Menu {
                         return MenuAction::BackToMain;
                     }
                 },
-                MenuState::HotkeySettings => return MenuAction::BackToSettings,
+                MenuState::HotkeySettings | MenuState::NotificationSettings | MenuState::EditorSettings | MenuState::CacheSettings => return MenuAction::BackToSettings,
+                // The first Escape clears the search
+                MenuState::LevelSelect if !self.level_search.query.is_empty() => {
+                    self.level_search.query.clear();
+                    self.setup_level_select_menu();
+                }
                 _ => return MenuAction::BackToMain,
             }
         }
@@ -657,20 +1470,101 @@ impl Menu {
         MenuAction::None
     }
 
+    /// What a right-click (or Left with the keyboard) on a button does: step settings backwards
+    fn secondary_action(action: &MenuAction) -> MenuAction {
+        match action {
+            MenuAction::IncreaseResolution => MenuAction::DecreaseResolution,
+            MenuAction::IncreaseSfxVolume => MenuAction::DecreaseSfxVolume,
+            MenuAction::IncreaseMusicVolume => MenuAction::DecreaseMusicVolume,
+            MenuAction::IncreaseFontSize => MenuAction::DecreaseFontSize,
+            MenuAction::ToggleFullscreen => MenuAction::ToggleFullscreen,
+            _ => action.clone(),
+        }
+    }
+
+    /// Move the keyboard focus `step` enabled buttons along, wrapping around, and read out the
+    /// newly focused button
+    fn move_focus(&mut self, step: i32) {
+        let count = self.buttons.len() as i32;
+        if count == 0 {
+            return;
+        }
+        let mut index = match self.focused {
+            Some(i) => i as i32,
+            None if step > 0 => -1,
+            None => count,
+        };
+        for _ in 0..count {
+            index = (index + step).rem_euclid(count);
+            if self.buttons[index as usize].enabled {
+                self.focused = Some(index as usize);
+                crate::speech::announce(&self.buttons[index as usize].text);
+                return;
+            }
+        }
+    }
+
     pub fn update(&mut self, action: MenuAction) {
+        let previous_state = self.state.clone();
         match action {
-            MenuAction::StartGame => {
+            MenuAction::StartGame | MenuAction::StartEndless | MenuAction::StartDailyChallenge | MenuAction::StartSandbox => {
                 self.state = MenuState::InGame;
             },
             MenuAction::OpenSettings => {
                 self.state = MenuState::Settings;
                 self.opened_from_game = false;  // Ensure flag is false when opened from main menu
+                self.bundle_status = None;
                 self.setup_settings_menu();
             },
             MenuAction::OpenLevelSelect => {
-                self.state = MenuState::LevelSelect;
+                if self.campaign.is_some() {
+                    self.state = MenuState::WorldMap;
+                    self.setup_world_map_menu();
+                } else {
+                    self.state = MenuState::LevelSelect;
+                    // Keys typed on earlier screens don't belong in the search
+                    while get_char_pressed().is_some() {}
+                    self.setup_level_select_menu();
+                }
+            },
+            MenuAction::CycleLevelTopic => {
+                self.level_search.next_tag(&self.levels);
                 self.setup_level_select_menu();
             },
+            MenuAction::CycleLevelStatus => {
+                self.level_search.completion = self.level_search.completion.next();
+                self.level_search.page = 0;
+                self.setup_level_select_menu();
+            },
+            MenuAction::CycleLevelDifficulty => {
+                self.level_search.next_difficulty(&self.levels);
+                self.setup_level_select_menu();
+            },
+            MenuAction::OpenCommunityLevels => {
+                self.state = MenuState::CommunityBrowser;
+                if self.community.entries.is_empty() && !self.community.busy {
+                    self.community.refresh(&self.settings.community_index_url, self.settings.filter_community_text);
+                }
+                self.setup_community_menu();
+            },
+            MenuAction::OpenStats => {
+                self.state = MenuState::Stats;
+                self.certificate_status = None;
+                self.setup_stats_menu();
+            },
+            MenuAction::ExportCertificate => {
+                if let Err(e) = self.export_certificate() {
+                    log::warn!("Certificate export failed: {}", e);
+                }
+            },
+            MenuAction::RefreshCommunityLevels => {
+                self.community.refresh(&self.settings.community_index_url, self.settings.filter_community_text);
+                self.setup_community_menu();
+            },
+            MenuAction::InstallCommunityLevel(index) => {
+                self.community.install(index);
+                self.setup_community_menu();
+            },
             MenuAction::SelectLevel(_) => {
                 // Level selection is handled by the main game loop
                 self.state = MenuState::InGame;
@@ -748,10 +1642,125 @@ impl Menu {
                 let _ = self.settings.save(); // Save settings when changed
                 // Menu will be refreshed at end of update method
             },
+            MenuAction::ToggleShowMe => {
+                self.settings.show_me_enabled = !self.settings.show_me_enabled;
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::ToggleAutoGrab => {
+                self.settings.auto_grab = !self.settings.auto_grab;
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::CyclePalette => {
+                self.settings.palette = self.settings.palette.next();
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::CycleLanguage => {
+                self.settings.language = self.settings.language.next();
+                crate::i18n::set_language(self.settings.language);
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::ToggleScreenReader => {
+                self.settings.screen_reader = !self.settings.screen_reader;
+                crate::speech::set_enabled(self.settings.screen_reader);
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::CycleHotkeyPreset => {
+                self.settings.hotkey_preset = self.settings.hotkey_preset.next();
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::ToggleVimMode => {
+                self.settings.vim_mode = !self.settings.vim_mode;
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::ExportSettings => {
+                let path = Path::new(crate::settings_bundle::BUNDLE_FILE);
+                let result = SettingsBundle::collect(&self.settings, &self.progress).save(path);
+                self.bundle_status = Some(match result {
+                    Ok(()) => tr_with("settings.exported", &[("path", crate::settings_bundle::BUNDLE_FILE)]),
+                    Err(e) => e,
+                });
+            },
+            MenuAction::ImportSettings => {
+                self.bundle_status = Some(match self.import_settings() {
+                    Ok(()) => tr_with("settings.imported", &[("path", crate::settings_bundle::BUNDLE_FILE)]),
+                    Err(e) => e,
+                });
+            },
+            MenuAction::CycleGhostDelay => {
+                let current = TUTORIAL_GHOST_DELAYS.iter().position(|d| *d == self.settings.tutorial_ghost_delay).unwrap_or(0);
+                self.settings.tutorial_ghost_delay = TUTORIAL_GHOST_DELAYS[(current + 1) % TUTORIAL_GHOST_DELAYS.len()];
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
             MenuAction::OpenHotkeySettings => {
                 self.state = MenuState::HotkeySettings;
                 self.setup_hotkey_settings_menu();
             },
+            MenuAction::OpenNotificationSettings => {
+                self.state = MenuState::NotificationSettings;
+                self.setup_notification_settings_menu();
+            },
+            MenuAction::OpenCacheSettings => {
+                self.state = MenuState::CacheSettings;
+                self.cache_cleared = false;
+                self.setup_cache_settings_menu();
+            },
+            // The game clears the cache and refreshes cache_stats when it sees this action
+            MenuAction::ClearCache => self.cache_cleared = true,
+            MenuAction::ToggleToast(event) => {
+                self.settings.toasts.toggle(event);
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::OpenEditorSettings => {
+                self.state = MenuState::EditorSettings;
+                self.setup_editor_settings_menu();
+            },
+            MenuAction::CycleTabWidth => {
+                let current = TAB_WIDTHS.iter().position(|w| *w == self.settings.editor.tab_width).unwrap_or(0);
+                self.settings.editor.tab_width = TAB_WIDTHS[(current + 1) % TAB_WIDTHS.len()];
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::ToggleInsertSpaces => {
+                self.settings.editor.insert_spaces = !self.settings.editor.insert_spaces;
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::ToggleAutoIndent => {
+                self.settings.editor.auto_indent = !self.settings.editor.auto_indent;
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::CycleKeyRepeatDelay => {
+                let current = KEY_REPEAT_DELAYS.iter().position(|d| *d == self.settings.editor.key_repeat_delay).unwrap_or(0);
+                self.settings.editor.key_repeat_delay = KEY_REPEAT_DELAYS[(current + 1) % KEY_REPEAT_DELAYS.len()];
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::CycleKeyRepeatRate => {
+                let current = KEY_REPEAT_RATES.iter().position(|r| *r == self.settings.editor.key_repeat_rate).unwrap_or(0);
+                self.settings.editor.key_repeat_rate = KEY_REPEAT_RATES[(current + 1) % KEY_REPEAT_RATES.len()];
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::ToggleLineNumbers => {
+                self.settings.editor.line_numbers = !self.settings.editor.line_numbers;
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
+            MenuAction::ToggleWordWrap => {
+                self.settings.editor.word_wrap = !self.settings.editor.word_wrap;
+                let _ = self.settings.save(); // Save settings when changed
+                // Menu will be refreshed at end of update method
+            },
             MenuAction::BackToSettings => {
                 self.state = MenuState::Settings;
                 self.setup_settings_menu();
@@ -759,10 +1768,18 @@ impl Menu {
             _ => {}
         }
         
+        // A different screen starts without keyboard focus
+        if self.state != previous_state {
+            self.focused = None;
+        }
+
         // Refresh menu if we're in Settings or HotkeySettings to ensure buttons stay visible
         match self.state {
             MenuState::Settings => self.setup_settings_menu(),
             MenuState::HotkeySettings => self.setup_hotkey_settings_menu(),
+            MenuState::NotificationSettings => self.setup_notification_settings_menu(),
+            MenuState::EditorSettings => self.setup_editor_settings_menu(),
+            MenuState::CacheSettings => self.setup_cache_settings_menu(),
             _ => {}
         }
     }
@@ -777,6 +1794,7 @@ impl Menu {
         match self.state {
             MenuState::MainMenu => {
                 self.draw_main_menu();
+                self.draw_level_load_errors();
                 if let Some(progress) = loading_progress {
                     // Only show loading progress if not complete
                     if !matches!(progress.stage, LoadingStage::Complete) || progress.progress < 1.0 {
@@ -786,9 +1804,27 @@ impl Menu {
             },
             MenuState::Settings => self.draw_settings_menu(),
             MenuState::LevelSelect => self.draw_level_select_menu(),
+            MenuState::WorldMap => self.draw_world_map_menu(),
             MenuState::HotkeySettings => self.draw_hotkey_settings_menu(),
+            MenuState::NotificationSettings => self.draw_notification_settings_menu(),
+            MenuState::EditorSettings => self.draw_editor_settings_menu(),
+            MenuState::CacheSettings => self.draw_cache_settings_menu(),
+            MenuState::CommunityBrowser => self.draw_community_menu(),
+            MenuState::Stats => self.draw_stats_menu(),
             MenuState::InGame => {}, // Game drawing handled elsewhere
         }
+
+        if self.state != MenuState::InGame {
+            self.draw_focus();
+        }
+    }
+
+    /// Highlight the button selected with the keyboard
+    fn draw_focus(&self) {
+        if let Some(button) = self.focused.and_then(|i| self.buttons.get(i)) {
+            let margin = scale_size(4.0);
+            draw_rectangle_lines(button.x - margin, button.y - margin, button.width + margin * 2.0, button.height + margin * 2.0, 3.0, YELLOW);
+        }
     }
 
     fn draw_main_menu(&self) {
@@ -796,7 +1832,7 @@ impl Menu {
         self.draw_background();
 
         // Draw banner
-        let banner_text = "Welcome to Robo Wars Crab Edition";
+        let banner_text = &tr("menu.banner");
         let banner_size = 48.0;
         let scaled_banner_size = scale_font_size(banner_size);
         let banner_dimensions = measure_text(banner_text, None, scaled_banner_size as u16, 1.0);
@@ -809,7 +1845,7 @@ impl Menu {
         draw_scaled_text(banner_text, banner_x, banner_y, banner_size, GOLD);
 
         // Draw subtitle
-        let subtitle = "Program your robot to explore and conquer!";
+        let subtitle = &tr("menu.subtitle");
         let subtitle_size = 20.0;
         let scaled_subtitle_size = scale_font_size(subtitle_size);
         let subtitle_dimensions = measure_text(subtitle, None, scaled_subtitle_size as u16, 1.0);
@@ -830,7 +1866,7 @@ impl Menu {
         self.draw_background();
 
         // Draw title
-        let title = "Settings";
+        let title = &tr("settings.title");
         let title_size = 36.0;
         let scaled_title_size = scale_font_size(title_size);
         let title_dimensions = measure_text(title, None, scaled_title_size as u16, 1.0);
@@ -838,7 +1874,7 @@ impl Menu {
         draw_scaled_text(title, title_x, scale_size(100.0), title_size, WHITE);
 
         // Draw instructions
-        let instructions = "Left Click: Increase/Next | Right Click: Decrease/Previous";
+        let instructions = &tr("settings.instructions");
         let inst_size = 18.0;
         let scaled_inst_size = scale_font_size(inst_size);
         let inst_dimensions = measure_text(instructions, None, scaled_inst_size as u16, 1.0);
@@ -850,9 +1886,13 @@ impl Menu {
             button.draw();
         }
 
+        if let Some(status) = &self.bundle_status {
+            draw_scaled_text(status, scale_size(50.0), crate::crash_protection::safe_screen_height() - scale_size(95.0), 16.0, YELLOW);
+        }
+
         // Draw footer notes
-        draw_scaled_text("Note: Window resolution changes require restart to take effect", scale_size(50.0), crate::crash_protection::safe_screen_height() - scale_size(70.0), 14.0, GRAY);
-        draw_scaled_text("Volume and fullscreen changes apply immediately", scale_size(50.0), crate::crash_protection::safe_screen_height() - scale_size(50.0), 14.0, GRAY);
+        draw_scaled_text(&tr("settings.note_restart"), scale_size(50.0), crate::crash_protection::safe_screen_height() - scale_size(70.0), 14.0, GRAY);
+        draw_scaled_text(&tr("settings.note_immediate"), scale_size(50.0), crate::crash_protection::safe_screen_height() - scale_size(50.0), 14.0, GRAY);
     }
 
     fn draw_level_select_menu(&self) {
@@ -875,13 +1915,90 @@ impl Menu {
         let progress_x = (crate::crash_protection::safe_screen_width() - progress_dimensions.width) / 2.0;
         draw_scaled_text(&progress_text, progress_x, scale_size(140.0), progress_size, YELLOW);
 
+        // Search box, with a cursor after the query
+        let box_width = scale_size(720.0);
+        let box_x = (crate::crash_protection::safe_screen_width() - box_width) / 2.0;
+        let box_y = scale_size(160.0);
+        draw_rectangle(box_x, box_y, box_width, scale_size(36.0), Color::new(0.1, 0.1, 0.15, 0.9));
+        draw_rectangle_lines(box_x, box_y, box_width, scale_size(36.0), 2.0, LIGHTGRAY);
+        let (search, color) = if self.level_search.query.is_empty() {
+            (tr("level_select.search_placeholder"), GRAY)
+        } else {
+            (format!("{}|", self.level_search.query), WHITE)
+        };
+        draw_scaled_text(&search, box_x + scale_size(10.0), box_y + scale_size(25.0), 20.0, color);
+
         // Draw buttons
         for button in &self.buttons {
             button.draw();
         }
 
+        let shown = self.buttons.iter().filter(|button| matches!(button.action, MenuAction::SelectLevel(_))).count();
+        let total = self.matching_levels().len();
+        let summary = if total == 0 {
+            tr("level_select.no_matches")
+        } else {
+            tr_with("level_select.showing", &[("shown", &shown.to_string()), ("total", &total.to_string()), ("page", &(self.level_search.page + 1).to_string())])
+        };
+        draw_scaled_text(&summary, box_x, scale_size(275.0), 14.0, LIGHTGRAY);
+
         // Draw instructions
-        draw_scaled_text("Select a level to jump directly to it", scale_size(50.0), crate::crash_protection::safe_screen_height() - scale_size(50.0), 14.0, GRAY);
+        draw_scaled_text(&tr("level_select.hint"), scale_size(50.0), crate::crash_protection::safe_screen_height() - scale_size(50.0), 14.0, GRAY);
+    }
+
+    fn draw_world_map_menu(&self) {
+        self.draw_background();
+
+        let Some(campaign) = &self.campaign else { return };
+        let screen_width = crate::crash_protection::safe_screen_width();
+        let screen_height = crate::crash_protection::safe_screen_height();
+
+        // Draw title
+        let title_size = 36.0;
+        let scaled_title_size = scale_font_size(title_size);
+        let title_dimensions = measure_text(&campaign.name, None, scaled_title_size as u16, 1.0);
+        draw_scaled_text(&campaign.name, (screen_width - title_dimensions.width) / 2.0, scale_size(100.0), title_size, WHITE);
+
+        // Paths from each prerequisite to the node it unlocks
+        for node in &campaign.nodes {
+            let (x, y) = campaign.layout_position(&node.id);
+            for required in &node.requires {
+                let (rx, ry) = campaign.layout_position(required);
+                let color = if self.progress.is_node_completed(required) { GREEN } else { DARKGRAY };
+                draw_line(rx * screen_width, ry * screen_height, x * screen_width, y * screen_height, 3.0, color);
+            }
+        }
+
+        // Node buttons come first in the list, in campaign order
+        for (node, button) in campaign.nodes.iter().zip(&self.buttons) {
+            button.draw();
+            let outline = if self.progress.is_node_completed(&node.id) {
+                GREEN
+            } else if self.progress.is_node_unlocked(&node.id) {
+                YELLOW
+            } else {
+                GRAY
+            };
+            draw_rectangle_lines(button.x, button.y, button.width, button.height, 4.0, outline);
+
+            let unmet = self.unmet_requirements(node.level);
+            if self.progress.is_node_unlocked(&node.id) && !unmet.is_empty() {
+                draw_scaled_text(&format!("Needs: {}", unmet.join(", ")), button.x, button.y + button.height + scale_size(16.0), 14.0, ORANGE);
+            }
+        }
+        for button in self.buttons.iter().skip(campaign.nodes.len()) {
+            button.draw();
+        }
+
+        // Progress counts only the main path; side levels are a bonus
+        let required: Vec<_> = campaign.nodes.iter().filter(|n| !n.optional).collect();
+        let done = required.iter().filter(|n| self.progress.is_node_completed(&n.id)).count();
+        let progress_text = format!("Progress: {}/{} main levels completed", done, required.len());
+        let progress_size = 18.0;
+        let progress_dimensions = measure_text(&progress_text, None, scale_font_size(progress_size) as u16, 1.0);
+        draw_scaled_text(&progress_text, (screen_width - progress_dimensions.width) / 2.0, scale_size(140.0), progress_size, YELLOW);
+
+        draw_scaled_text("Completed levels unlock the paths leading out of them - small nodes are optional side levels", scale_size(50.0), screen_height - scale_size(50.0), 14.0, GRAY);
     }
 
     fn draw_background(&self) {
@@ -924,6 +2041,28 @@ impl Menu {
         draw_circle(br_x + 15.0, br_y - 10.0, 5.0, crab_color); // Right eye
     }
     
+    /// The first few community level files that failed to load, above the version line
+    fn draw_level_load_errors(&self) {
+        const SHOWN: usize = 4;
+        if self.level_load_errors.is_empty() {
+            return;
+        }
+
+        let x = scale_size(10.0);
+        let line_height = scale_size(18.0);
+        let mut lines = vec![tr_with("menu.level_load_errors", &[("count", &self.level_load_errors.len().to_string())])];
+        lines.extend(self.level_load_errors.iter().take(SHOWN).map(|error| format!("  {}", error)));
+        if self.level_load_errors.len() > SHOWN {
+            let more = tr_with("menu.level_load_more", &[("count", &(self.level_load_errors.len() - SHOWN).to_string())]);
+            lines.push(format!("  {}", more));
+        }
+        let mut y = crate::crash_protection::safe_screen_height() - scale_size(35.0) - line_height * (lines.len() - 1) as f32;
+        for line in &lines {
+            draw_scaled_text(line, x, y, 14.0, ORANGE);
+            y += line_height;
+        }
+    }
+
     fn draw_loading_progress(&self, progress: &LoadingProgress) {
         let bar_width = scale_size(400.0);
         let bar_height = scale_size(20.0);
@@ -994,6 +2133,166 @@ impl Menu {
         }
     }
 
+    fn draw_notification_settings_menu(&self) {
+        // Draw background
+        self.draw_background();
+
+        // Draw title
+        let title = &tr("notifications.title");
+        let title_size = 36.0;
+        let scaled_title_size = scale_font_size(title_size);
+        let title_dimensions = measure_text(title, None, scaled_title_size as u16, 1.0);
+        let title_x = (crate::crash_protection::safe_screen_width() - title_dimensions.width) / 2.0;
+        draw_scaled_text(title, title_x, scale_size(100.0), title_size, WHITE);
+
+        // Draw buttons
+        for button in &self.buttons {
+            button.draw();
+        }
+
+        // Draw instructions
+        draw_scaled_text(&tr("notifications.hint"), scale_size(50.0), crate::crash_protection::safe_screen_height() - scale_size(50.0), 14.0, GRAY);
+    }
+
+    fn draw_editor_settings_menu(&self) {
+        self.draw_background();
+
+        let title = &tr("editor.title");
+        let title_size = 36.0;
+        let scaled_title_size = scale_font_size(title_size);
+        let title_dimensions = measure_text(title, None, scaled_title_size as u16, 1.0);
+        let title_x = (crate::crash_protection::safe_screen_width() - title_dimensions.width) / 2.0;
+        draw_scaled_text(title, title_x, scale_size(100.0), title_size, WHITE);
+
+        for button in &self.buttons {
+            button.draw();
+        }
+
+        draw_scaled_text(&tr("editor.hint"), scale_size(50.0), crate::crash_protection::safe_screen_height() - scale_size(50.0), 14.0, GRAY);
+    }
+
+    fn draw_cache_settings_menu(&self) {
+        use crate::cache::format_bytes;
+
+        self.draw_background();
+
+        let title = &tr("cache.title");
+        let title_size = 36.0;
+        let scaled_title_size = scale_font_size(title_size);
+        let title_dimensions = measure_text(title, None, scaled_title_size as u16, 1.0);
+        let title_x = (crate::crash_protection::safe_screen_width() - title_dimensions.width) / 2.0;
+        draw_scaled_text(title, title_x, scale_size(100.0), title_size, WHITE);
+
+        let x = crate::crash_protection::safe_screen_width() / 2.0 - scale_size(250.0);
+        let mut y = scale_size(170.0);
+        if let Some(stats) = &self.cache_stats {
+            let entries = |key: &str, (count, bytes): (usize, usize)| {
+                tr_with(key, &[("count", &count.to_string()), ("size", &format_bytes(bytes as u64))])
+            };
+            let file = match stats.file_bytes {
+                Some(bytes) => tr_with("cache.file", &[("size", &format_bytes(bytes))]),
+                None => tr("cache.no_file"),
+            };
+            let lines = [
+                entries("cache.levels", stats.levels),
+                entries("cache.font_metrics", stats.font_metrics),
+                entries("cache.assets", stats.assets),
+                tr_with("cache.total", &[("size", &format_bytes(stats.total_bytes() as u64)), ("max", &format_bytes(stats.max_bytes as u64))]),
+                tr_with("cache.evictions", &[("count", &stats.evictions.to_string())]),
+                file,
+            ];
+            for line in &lines {
+                draw_scaled_text(line, x, y, 18.0, WHITE);
+                y += scale_size(28.0);
+            }
+            if let Some(problem) = &stats.load_problem {
+                draw_scaled_text(problem, x, y, 16.0, ORANGE);
+                y += scale_size(28.0);
+            }
+        }
+        if self.cache_cleared {
+            draw_scaled_text(&tr("cache.cleared"), x, y, 16.0, YELLOW);
+        }
+
+        for button in &self.buttons {
+            button.draw();
+        }
+
+        draw_scaled_text(&tr("cache.hint"), scale_size(50.0), crate::crash_protection::safe_screen_height() - scale_size(50.0), 14.0, GRAY);
+    }
+
+    fn draw_community_menu(&self) {
+        self.draw_background();
+
+        let title = &tr("community.title");
+        let title_size = 36.0;
+        let scaled_title_size = scale_font_size(title_size);
+        let title_dimensions = measure_text(title, None, scaled_title_size as u16, 1.0);
+        let title_x = (crate::crash_protection::safe_screen_width() - title_dimensions.width) / 2.0;
+        draw_scaled_text(title, title_x, scale_size(100.0), title_size, WHITE);
+
+        let status_dimensions = measure_text(&self.community.status, None, scale_font_size(16.0) as u16, 1.0);
+        let status_x = ((crate::crash_protection::safe_screen_width() - status_dimensions.width) / 2.0).max(scale_size(20.0));
+        draw_scaled_text(&self.community.status, status_x, scale_size(140.0), 16.0, YELLOW);
+
+        // Name, ratings and description next to each install button
+        for button in &self.buttons {
+            let MenuAction::InstallCommunityLevel(index) = button.action else { continue };
+            let Some(entry) = self.community.entries.get(index) else { continue };
+            let x = button.x + button.width + scale_size(20.0);
+            draw_scaled_text(&entry.name, x, button.y + scale_size(14.0), 20.0, WHITE);
+            draw_scaled_text(&entry.summary(), x, button.y + scale_size(34.0), 14.0, YELLOW);
+            let mut details = entry.description.clone().unwrap_or_default();
+            if !entry.tags.is_empty() {
+                details = format!("{} [{}]", details, entry.tags.join(", "));
+            }
+            let details: String = details.chars().take(110).collect();
+            draw_scaled_text(&details, x, button.y + scale_size(52.0), 14.0, LIGHTGRAY);
+        }
+
+        for button in &self.buttons {
+            button.draw();
+        }
+
+        draw_scaled_text(&tr("community.hint"), scale_size(50.0), crate::crash_protection::safe_screen_height() - scale_size(50.0), 14.0, GRAY);
+    }
+
+    fn draw_stats_menu(&self) {
+        self.draw_background();
+
+        let title = &tr("stats.title");
+        let title_size = 36.0;
+        let scaled_title_size = scale_font_size(title_size);
+        let title_dimensions = measure_text(title, None, scaled_title_size as u16, 1.0);
+        let title_x = (crate::crash_protection::safe_screen_width() - title_dimensions.width) / 2.0;
+        draw_scaled_text(title, title_x, scale_size(100.0), title_size, WHITE);
+
+        let mut lines = vec![
+            (tr_with("stats.player", &[("name", &self.settings.player_name)]), WHITE),
+            (tr_with("stats.levels", &[("levels", &self.levels_completed().to_string()), ("total", &self.total_levels.to_string())]), WHITE),
+        ];
+        if let Some(campaign) = &self.campaign {
+            let required = campaign.nodes.iter().filter(|node| !node.optional);
+            let done = required.clone().filter(|node| self.progress.is_node_completed(&node.id)).count();
+            lines.push((tr_with("stats.campaign", &[("name", &campaign.name), ("done", &done.to_string()), ("total", &required.count().to_string())]), WHITE));
+        }
+        if self.campaign_complete() {
+            lines.push((tr("stats.complete"), GOLD));
+        }
+        if let Some(status) = &self.certificate_status {
+            lines.push((status.clone(), YELLOW));
+        }
+        for (i, (line, color)) in lines.iter().enumerate() {
+            draw_scaled_text(line, scale_size(120.0), scale_size(180.0) + i as f32 * scale_size(40.0), 22.0, *color);
+        }
+
+        for button in &self.buttons {
+            button.draw();
+        }
+
+        draw_scaled_text(&tr("stats.hint"), scale_size(50.0), crate::crash_protection::safe_screen_height() - scale_size(50.0), 14.0, GRAY);
+    }
+
     fn draw_hotkey_settings_menu(&self) {
         // Draw background
         self.draw_background();
@@ -0,0 +1,797 @@
+use macroquad::prelude::*;
+use crate::font_scaling::*;
+use crate::i18n::{tr, tr_with};
+use serde::{Deserialize, Serialize};
+use game_core::interpreter::ExecutionOutcome;
+use std::collections::VecDeque;
+
+pub mod toast;
+pub use toast::{ToastEvent, ToastSettings, ToastStack};
+
+// How many messages the Logs tab keeps before dropping the oldest
+const HISTORY_CAPACITY: usize = 500;
+// Popups waiting behind a manual-close popup; older ones are dropped past this
+const MAX_QUEUED_POPUPS: usize = 8;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PopupMessage {
+    pub title: String,
+    pub content: String,
+    pub popup_type: PopupType,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum PopupType {
+    Info,
+    Warning,
+    Success,
+    Tutorial,
+    Stdout,  // For println! output
+    Stderr,  // For eprintln! and error output
+    Panic,   // For panic! output
+    Congratulations, // For level completion
+    FunctionResults, // For robot function execution results
+    RestoreAutosave, // Offer to restore the editor after a crash
+    CompilerHelp(String), // Beginner explanation of a compiler error; holds the error code for "learn more"
+    Log(String),          // A record logged through the log crate; holds its level
+}
+
+/// One entry in the message history shown in the Logs tab
+#[derive(Clone, Debug)]
+pub struct GameMessage {
+    pub run: usize, // Which code execution produced it (0 = before the first run)
+    pub popup_type: PopupType,
+    pub title: String,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LogFilter {
+    All,
+    Stdout,
+    Stderr,  // eprintln! output and panics
+    Robot,   // Robot action results
+    Game,    // Level, tutorial and item messages
+    Log,     // Records logged through the log crate
+    GameLog, // The game's own log records rather than the message history
+    Combat,  // Each turn's enemy moves, hits and stuns from the last run
+}
+
+impl LogFilter {
+    pub fn matches(&self, popup_type: &PopupType) -> bool {
+        match self {
+            LogFilter::All => true,
+            LogFilter::Stdout => matches!(popup_type, PopupType::Stdout),
+            LogFilter::Stderr => matches!(popup_type, PopupType::Stderr | PopupType::Panic | PopupType::CompilerHelp(_)),
+            LogFilter::Robot => matches!(popup_type, PopupType::FunctionResults),
+            LogFilter::Game => !matches!(popup_type, PopupType::Stdout | PopupType::Stderr | PopupType::Panic | PopupType::CompilerHelp(_) | PopupType::FunctionResults | PopupType::Log(_)),
+            LogFilter::Log => matches!(popup_type, PopupType::Log(_)),
+            LogFilter::GameLog | LogFilter::Combat => false,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            LogFilter::All => LogFilter::Stdout,
+            LogFilter::Stdout => LogFilter::Stderr,
+            LogFilter::Stderr => LogFilter::Robot,
+            LogFilter::Robot => LogFilter::Game,
+            LogFilter::Game => LogFilter::Log,
+            LogFilter::Log => LogFilter::GameLog,
+            LogFilter::GameLog => LogFilter::Combat,
+            LogFilter::Combat => LogFilter::All,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogFilter::All => "All",
+            LogFilter::Stdout => "stdout",
+            LogFilter::Stderr => "stderr",
+            LogFilter::Robot => "Robot actions",
+            LogFilter::Game => "Game messages",
+            LogFilter::Log => "log records",
+            LogFilter::GameLog => "Game log",
+            LogFilter::Combat => "Combat log",
+        }
+    }
+}
+
+/// Ring buffer of every message shown as a popup, kept across code runs
+#[derive(Clone, Debug)]
+pub struct MessageHistory {
+    messages: VecDeque<GameMessage>,
+    capacity: usize,
+    current_run: usize,
+}
+
+impl MessageHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            messages: VecDeque::with_capacity(capacity),
+            capacity,
+            current_run: 0,
+        }
+    }
+
+    /// Called when the player runs their code so messages can be grouped per run
+    pub fn start_run(&mut self) {
+        self.current_run += 1;
+    }
+
+    pub fn current_run(&self) -> usize {
+        self.current_run
+    }
+
+    pub fn push(&mut self, popup_type: PopupType, title: &str, text: &str) {
+        if self.messages.len() == self.capacity {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(GameMessage {
+            run: self.current_run,
+            popup_type,
+            title: title.to_string(),
+            text: text.to_string(),
+        });
+    }
+
+    pub fn filtered(&self, filter: LogFilter) -> impl DoubleEndedIterator<Item = &GameMessage> {
+        self.messages.iter().filter(move |m| filter.matches(&m.popup_type))
+    }
+
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PopupAction {
+    None,
+    Dismissed,
+    NextLevel,
+    StayOnLevel,
+    RestoreAutosave,
+    CompareSolution,
+    LearnMore(String), // Open the full documentation of this compiler error code
+}
+
+#[derive(Clone, Debug)]
+pub struct PopupSystem {
+    pub current_popup: Option<PopupMessage>,
+    pub show_popup: bool,
+    pub popup_timer: f32,
+    pub auto_close_duration: Option<f32>, // None = manual close only
+    pub queue: VecDeque<(PopupMessage, Option<f32>)>, // Waiting behind a manual-close popup
+    pub last_popup: Option<PopupMessage>, // Most recently dismissed, for recall
+    pub history: MessageHistory,
+    pub toasts: ToastStack,
+    pub toast_settings: ToastSettings, // Synced from GameSettings every frame
+}
+
+impl PopupSystem {
+    pub fn new() -> Self {
+        Self {
+            current_popup: None,
+            show_popup: false,
+            popup_timer: 0.0,
+            auto_close_duration: None,
+            queue: VecDeque::new(),
+            last_popup: None,
+            history: MessageHistory::new(HISTORY_CAPACITY),
+            toasts: ToastStack::default(),
+            toast_settings: ToastSettings::default(),
+        }
+    }
+    
+    pub fn show_message(&mut self, title: String, content: String, popup_type: PopupType, auto_close_seconds: Option<f32>) {
+        // Only popups that would close by themselves are minor enough to become toasts
+        let event = match (&popup_type, auto_close_seconds) {
+            (_, None) => None,
+            (PopupType::FunctionResults, _) => Some(ToastEvent::RobotActions),
+            (PopupType::Success, _) => Some(ToastEvent::TaskProgress),
+            (PopupType::Info | PopupType::Warning | PopupType::Tutorial, _) => Some(ToastEvent::Notices),
+            _ => None,
+        };
+        self.show_event(event, title, content, popup_type, auto_close_seconds);
+    }
+
+    fn show_event(&mut self, event: Option<ToastEvent>, title: String, content: String, popup_type: PopupType, auto_close_seconds: Option<f32>) {
+        self.history.push(popup_type.clone(), &title, &content);
+        if event.is_some_and(|e| self.toast_settings.enabled(e)) {
+            crate::speech::announce(&format!("{}. {}", title, content));
+            self.toasts.push(title, content, popup_type);
+            return;
+        }
+
+        let popup = PopupMessage {
+            title,
+            content,
+            popup_type,
+        };
+
+        // Don't replace a popup the player still has to read; show it once that one is dismissed
+        if self.show_popup && self.auto_close_duration.is_none() && self.current_popup.is_some() {
+            if self.queue.len() == MAX_QUEUED_POPUPS {
+                self.queue.pop_front();
+            }
+            self.queue.push_back((popup, auto_close_seconds));
+            return;
+        }
+
+        self.display(popup, auto_close_seconds);
+    }
+
+    fn display(&mut self, popup: PopupMessage, auto_close_seconds: Option<f32>) {
+        crate::speech::announce(&format!("{}. {}", popup.title, popup.content));
+        self.current_popup = Some(popup);
+        self.show_popup = true;
+        self.popup_timer = 0.0;
+        self.auto_close_duration = auto_close_seconds;
+    }
+
+    /// Append to the showing (or last queued) popup of the same type instead of opening another
+    fn stack_onto(&mut self, popup_type: PopupType, line: &str) -> bool {
+        let target = match self.queue.back_mut() {
+            Some((queued, _)) => Some(queued),
+            None => self.current_popup.as_mut(),
+        };
+        match target {
+            Some(popup) if std::mem::discriminant(&popup.popup_type) == std::mem::discriminant(&popup_type) => {
+                popup.content = format!("{}\n{}", popup.content, line);
+                self.history.push(popup_type, &popup.title, line);
+                crate::speech::announce(line);
+                self.popup_timer = 0.0; // Reset timer for new message
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Add a paragraph to the showing (or last queued) popup, such as a result that belongs with it
+    pub fn append_to_last(&mut self, text: &str) {
+        let target = match self.queue.back_mut() {
+            Some((queued, _)) => Some(queued),
+            None => self.current_popup.as_mut(),
+        };
+        match target {
+            Some(popup) => {
+                popup.content = format!("{}\n\n{}", popup.content, text);
+                self.history.push(popup.popup_type.clone(), &popup.title, text);
+            }
+            None => self.show_message(tr("popup.level_information"), text.to_string(), PopupType::Info, None),
+        }
+    }
+
+    /// Show the most recently dismissed popup again
+    pub fn reopen_last(&mut self) -> bool {
+        match self.last_popup.clone() {
+            Some(popup) if !self.show_popup => {
+                // Reopened popups stay until dismissed, even if they originally auto-closed
+                self.display(popup, None);
+                true
+            }
+            _ => false,
+        }
+    }
+    
+    pub fn show_level_message(&mut self, content: String) {
+        self.show_message(
+            tr("popup.level_information"),
+            content,
+            PopupType::Info,
+            None // Manual close only for level messages
+        );
+    }
+    
+    /// The community level files that failed to load, each with its line and a hint about the field
+    pub fn show_level_load_errors(&mut self, errors: &[String]) {
+        const SHOWN: usize = 6;
+        let mut content: Vec<String> = errors.iter().take(SHOWN).cloned().collect();
+        if errors.len() > SHOWN {
+            content.push(tr_with("menu.level_load_more", &[("count", &(errors.len() - SHOWN).to_string())]));
+        }
+        content.push(tr("popup.level_load_errors_hint"));
+        self.show_message(
+            tr_with("popup.level_load_errors", &[("count", &errors.len().to_string())]),
+            content.join("\n\n"),
+            PopupType::Warning,
+            None,
+        );
+    }
+
+    pub fn show_tutorial(&mut self, content: String) {
+        self.show_message(
+            tr("popup.tutorial"),
+            content,
+            PopupType::Tutorial,
+            None
+        );
+    }
+    
+    pub fn show_item_collected(&mut self, item_name: String) {
+        self.show_event(
+            Some(ToastEvent::ItemPickup),
+            tr("popup.item_collected"),
+            tr_with("popup.item_found", &[("item", &item_name)]),
+            PopupType::Success,
+            Some(3.0) // Auto-close after 3 seconds
+        );
+    }
+    
+    pub fn show_level_complete(&mut self) {
+        self.show_message(
+            tr("popup.level_complete"),
+            tr("popup.level_complete_body"),
+            PopupType::Success,
+            None
+        );
+    }
+    
+    pub fn show_congratulations(&mut self, level_name: String, achievement: String, next_level_hint: Option<String>) {
+        let mut content = tr_with("popup.congratulations", &[("level", &level_name), ("achievement", &achievement)]);
+        if let Some(hint) = next_level_hint {
+            content = format!("{}\n\n{}", content, tr_with("popup.next_up", &[("hint", &hint)]));
+        }
+        content = format!("{}\n\n{}", content, tr("popup.continue_hint"));
+        
+        self.show_message(
+            tr("popup.congratulations_title"),
+            content,
+            PopupType::Congratulations,
+            None // Manual close only for congratulations
+        );
+    }
+    
+    pub fn show_completion_instructions(&mut self, level_name: String, instructions: String) {
+        let content = tr_with("popup.instructions", &[("level", &level_name), ("instructions", &instructions)]);
+        
+        self.show_message(
+            tr("popup.instructions_title"),
+            content,
+            PopupType::Info,
+            None // Manual close only
+        );
+    }
+    
+    pub fn show_println_output(&mut self, message: String) {
+        // Stack onto an existing stdout popup
+        if self.stack_onto(PopupType::Stdout, &message) {
+            return;
+        }
+        
+        // Create new stdout popup
+        self.show_message(
+            tr("popup.program_output"),
+            message,
+            PopupType::Stdout,
+            None // Consider this for auto close by putting in Some(#.#) that will set a timer. Right now it's not needed.
+        );
+    }
+    
+    pub fn show_eprintln_output(&mut self, message: String) {
+        // Stack onto an existing stderr popup
+        if self.stack_onto(PopupType::Stderr, &message) {
+            return;
+        }
+        
+        // Create new stderr popup
+        self.show_message(
+            tr("popup.error_output"),
+            message,
+            PopupType::Stderr,
+            None // Consider this for auto close by putting in Some(#.#) that will set a timer. Right now it's not needed.
+        );
+    }
+    
+    /// A record the player's code logged, stacked with the other records of the run
+    pub fn show_log_record(&mut self, record: &crate::code_executor::LogRecord) {
+        let line = format!("[{} {}] {}", record.level, record.target, record.message);
+        if self.stack_onto(PopupType::Log(record.level.clone()), &line) {
+            return;
+        }
+
+        self.show_message(
+            tr("popup.log_output"),
+            line,
+            PopupType::Log(record.level.clone()),
+            None
+        );
+    }
+
+    pub fn show_panic_output(&mut self, message: String) {
+        // Stack onto an existing panic popup
+        let line = tr_with("popup.program_terminated", &[("message", &message)]);
+        if self.stack_onto(PopupType::Panic, &line) {
+            return;
+        }
+        
+        // Create new panic popup
+        self.show_message(
+            tr("popup.panic"),
+            line,
+            PopupType::Panic,
+            None // Manual close for panics
+        );
+    }
+    
+    /// Results of a program run: `notes` (parser diagnostics) first, then every outcome that says
+    /// more than "done", then why the program stopped if it was halted
+    pub fn show_function_results(&mut self, notes: &[String], outcomes: &[ExecutionOutcome]) {
+        let mut meaningful_results: Vec<String> = notes.to_vec();
+        meaningful_results.extend(
+            outcomes.iter()
+                .filter(|outcome| !outcome.is_routine())
+                .map(|outcome| outcome.to_string())
+        );
+        if let Some(halt) = outcomes.last().and_then(|outcome| outcome.halt_message()) {
+            meaningful_results.push(halt.to_string());
+        }
+        
+        if meaningful_results.is_empty() {
+            return;
+        }
+        
+        // Stack onto an existing function results popup
+        if self.stack_onto(PopupType::FunctionResults, &meaningful_results.join("\n")) {
+            return;
+        }
+        
+        // Create new function results popup
+        let content = meaningful_results.join("\n");
+        self.show_message(
+            tr("popup.robot_results"),
+            content,
+            PopupType::FunctionResults,
+            Some(4.0) // Auto-close after 4 seconds for function results
+        );
+    }
+    
+    /// Explain the compiler errors the bundled catalog knows, next to the raw messages already
+    /// shown in the editor. "Learn more" goes to the first explained code.
+    pub fn show_compiler_help(&mut self, errors: &[crate::rust_checker::CompilerError]) {
+        let mut explained: Vec<&crate::error_catalog::ErrorExplanation> = Vec::new();
+        for error in errors {
+            let Some(explanation) = error.code.as_deref().and_then(crate::error_catalog::explain) else { continue };
+            if !explained.iter().any(|known| known.code == explanation.code) {
+                explained.push(explanation);
+            }
+        }
+        let Some(first) = explained.first() else { return };
+
+        let sections: Vec<String> = explained.iter().take(3).map(|explanation| {
+            let raw = errors.iter()
+                .find(|error| error.code.as_deref() == Some(explanation.code))
+                .map(|error| error.message.lines().next().unwrap_or_default())
+                .unwrap_or_default();
+            tr_with("popup.compiler_help_entry", &[
+                ("code", explanation.code),
+                ("title", explanation.title),
+                ("message", raw),
+                ("explanation", explanation.explanation),
+                ("example", explanation.example),
+            ])
+        }).collect();
+
+        self.show_message(
+            tr("popup.compiler_help"),
+            sections.join("\n\n"),
+            PopupType::CompilerHelp(first.code.to_string()),
+            None
+        );
+    }
+    
+    pub fn update(&mut self, delta_time: f32) {
+        self.toasts.update(delta_time);
+
+        if self.show_popup {
+            self.popup_timer += delta_time;
+            
+            // Auto-close if duration is set
+            if let Some(duration) = self.auto_close_duration {
+                if self.popup_timer >= duration {
+                    self.close();
+                }
+            }
+        }
+    }
+    
+    pub fn handle_input(&mut self) -> PopupAction {
+        if self.show_popup {
+            if let Some(ref popup) = self.current_popup {
+                match popup.popup_type {
+                    PopupType::Congratulations => {
+                        // Special handling for congratulations popup
+                        if is_key_pressed(KeyCode::Space) {
+                            self.close();
+                            return PopupAction::NextLevel;
+                        } else if is_key_pressed(KeyCode::Escape) {
+                            self.close();
+                            return PopupAction::StayOnLevel;
+                        } else if is_key_pressed(KeyCode::D) {
+                            self.close();
+                            return PopupAction::CompareSolution;
+                        }
+                    },
+                    PopupType::RestoreAutosave => {
+                        if is_key_pressed(KeyCode::Enter) {
+                            self.close();
+                            return PopupAction::RestoreAutosave;
+                        } else if is_key_pressed(KeyCode::Escape) {
+                            self.close();
+                            return PopupAction::Dismissed;
+                        }
+                    },
+                    PopupType::CompilerHelp(ref code) => {
+                        if is_key_pressed(KeyCode::L) {
+                            let code = code.clone();
+                            self.close();
+                            return PopupAction::LearnMore(code);
+                        } else if is_key_pressed(KeyCode::Space) || is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Escape) {
+                            self.close();
+                            return PopupAction::Dismissed;
+                        }
+                    },
+                    _ => {
+                        // Normal popup handling
+                        if is_key_pressed(KeyCode::Space) || 
+                           is_key_pressed(KeyCode::Enter) || 
+                           is_key_pressed(KeyCode::Escape) {
+                            self.close();
+                            return PopupAction::Dismissed;
+                        }
+                    }
+                }
+                
+                // Check for mouse click to dismiss
+                if is_mouse_button_pressed(MouseButton::Left) {
+                    let screen_width = crate::crash_protection::safe_screen_width();
+                    let screen_height = crate::crash_protection::safe_screen_height();
+                    let (popup_width, popup_height) = calculate_popup_dimensions(
+                        &popup.title, 
+                        &popup.content, 
+                        screen_width, 
+                        screen_height
+                    );
+                    let popup_x = (screen_width - popup_width) / 2.0;
+                    let popup_y = (screen_height - popup_height) / 2.0;
+                    
+                    // Use safe mouse position to prevent crashes when window loses focus
+                    let (mouse_x, mouse_y) = crate::crash_protection::safe_mouse_position();
+                    
+                    // Check if click is outside popup area
+                    if mouse_x < popup_x || mouse_x > popup_x + popup_width ||
+                       mouse_y < popup_y || mouse_y > popup_y + popup_height {
+                        self.close();
+                        return PopupAction::Dismissed;
+                    } else {
+                        // Click is inside popup area - dismiss the popup
+                        self.close();
+                        return PopupAction::Dismissed;
+                    }
+                }
+            } // End of if let Some(ref popup) = self.current_popup
+            
+            return PopupAction::None; // Popup is showing, consume all input
+        }
+        
+        PopupAction::None // No popup, don't consume input
+    }
+    
+    pub fn close(&mut self) {
+        if let Some(popup) = self.current_popup.take() {
+            self.last_popup = Some(popup);
+        }
+        self.show_popup = false;
+        self.popup_timer = 0.0;
+        self.auto_close_duration = None;
+
+        // Bring up the next waiting popup, if any
+        if let Some((popup, auto_close)) = self.queue.pop_front() {
+            self.display(popup, auto_close);
+        }
+    }
+    
+    pub fn is_showing(&self) -> bool {
+        self.show_popup
+    }
+    
+    pub fn draw(&self) {
+        self.toasts.draw();
+
+        if !self.show_popup {
+            return;
+        }
+        
+        let Some(ref popup) = self.current_popup else {
+            return;
+        };
+        
+        let screen_width = crate::crash_protection::safe_screen_width();
+        let screen_height = crate::crash_protection::safe_screen_height();
+        
+        // Semi-transparent overlay
+        draw_rectangle(0.0, 0.0, screen_width, screen_height, Color::new(0.0, 0.0, 0.0, 0.5));
+        
+        // Calculate dynamic popup dimensions based on content
+        let (popup_width, popup_height) = calculate_popup_dimensions(
+            &popup.title, 
+            &popup.content, 
+            screen_width, 
+            screen_height
+        );
+        let popup_x = (screen_width - popup_width) / 2.0;
+        let popup_y = (screen_height - popup_height) / 2.0;
+        
+        // Get colors based on popup type
+        let (bg_color, border_color, title_color) = match popup.popup_type {
+            PopupType::Info => (Color::new(0.2, 0.2, 0.3, 0.95), LIGHTGRAY, BLUE),
+            PopupType::Warning => (Color::new(0.3, 0.2, 0.1, 0.95), ORANGE, YELLOW),
+            PopupType::Success => (Color::new(0.1, 0.3, 0.1, 0.95), LIGHTGRAY, GREEN),
+            PopupType::Stdout => (Color::new(0.1, 0.3, 0.1, 0.95), GREEN, LIME),  // Green for println!
+            PopupType::Stderr => (Color::new(0.3, 0.1, 0.1, 0.95), RED, YELLOW),  // Red for eprintln!
+            PopupType::Panic => (Color::new(0.4, 0.1, 0.1, 0.95), RED, ORANGE),
+            PopupType::Tutorial => (Color::new(0.25, 0.15, 0.3, 0.95), PURPLE, PINK),
+            PopupType::Congratulations => (Color::new(0.1, 0.3, 0.1, 0.95), GOLD, YELLOW),
+            PopupType::FunctionResults => (Color::new(0.15, 0.25, 0.15, 0.95), GREEN, LIME),
+            PopupType::RestoreAutosave => (Color::new(0.3, 0.2, 0.1, 0.95), ORANGE, YELLOW),
+            PopupType::CompilerHelp(_) => (Color::new(0.15, 0.2, 0.3, 0.95), SKYBLUE, YELLOW),
+            PopupType::Log(_) => (Color::new(0.15, 0.15, 0.25, 0.95), SKYBLUE, WHITE),
+        };
+        
+        let scale = ScaledMeasurements::new();
+        
+        // Draw popup background
+        draw_rectangle(popup_x, popup_y, popup_width, popup_height, bg_color);
+        
+        // Draw border
+        draw_rectangle_lines(popup_x, popup_y, popup_width, popup_height, scale_size(3.0), border_color);
+        
+        // Draw title
+        let title_size = 28.0;
+        let scaled_title_size = scale_font_size(title_size);
+        let title_metrics = measure_text(&popup.title, None, scaled_title_size as u16, 1.0);
+        let title_x = popup_x + (popup_width - title_metrics.width) / 2.0;
+        let title_y = popup_y + scale_size(40.0);
+        draw_scaled_text(&popup.title, title_x, title_y, title_size, title_color);
+        
+        // Draw content
+        let content_size = 20.0;
+        let content_margin = scale_size(20.0);
+        let content_x = popup_x + content_margin;
+        let content_y = title_y + scale_size(50.0);
+        let content_width = popup_width - (content_margin * 2.0);
+        
+        // Word wrap the content
+        let wrapped_lines = wrap_text(&popup.content, content_width, scale_font_size(content_size));
+        let line_height = scale_font_size(content_size) + scale_size(5.0);
+        
+        for (i, line) in wrapped_lines.iter().enumerate() {
+            let line_y = content_y + (i as f32 * line_height);
+            draw_scaled_text(line, content_x, line_y, content_size, WHITE);
+        }
+        
+        // Draw instructions at bottom
+        let instruction_text = if let Some(duration) = self.auto_close_duration {
+            tr_with("popup.auto_closing", &[("seconds", &format!("{:.1}", duration - self.popup_timer))])
+        } else if matches!(popup.popup_type, PopupType::RestoreAutosave) {
+            tr("popup.restore_hint")
+        } else if let PopupType::CompilerHelp(code) = &popup.popup_type {
+            tr_with("popup.learn_more_hint", &[("code", code)])
+        } else {
+            tr("popup.dismiss_hint")
+        };
+        
+        let instruction_size = 16.0;
+        let scaled_instruction_size = scale_font_size(instruction_size);
+        let instruction_metrics = measure_text(&instruction_text, None, scaled_instruction_size as u16, 1.0);
+        let instruction_x = popup_x + (popup_width - instruction_metrics.width) / 2.0;
+        let instruction_y = popup_y + popup_height - scale_size(25.0);
+        draw_scaled_text(&instruction_text, instruction_x, instruction_y, instruction_size, LIGHTGRAY);
+    }
+}
+
+// Helper function to wrap text, respecting explicit newlines
+fn wrap_text(text: &str, max_width: f32, font_size: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    
+    // First split by explicit newlines
+    for paragraph in text.split('\n') {
+        if paragraph.trim().is_empty() {
+            lines.push(String::new()); // Preserve empty lines
+            continue;
+        }
+        
+        // Then wrap each paragraph
+        let words: Vec<&str> = paragraph.split_whitespace().collect();
+        let mut current_line = String::new();
+        
+        for word in words {
+            let test_line = if current_line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current_line, word)
+            };
+            
+            let test_width = measure_text(&test_line, None, font_size as u16, 1.0).width;
+            
+            if test_width <= max_width {
+                current_line = test_line;
+            } else {
+                if !current_line.is_empty() {
+                    lines.push(current_line);
+                    current_line = word.to_string();
+                } else {
+                    // Word is too long, just add it anyway
+                    lines.push(word.to_string());
+                }
+            }
+        }
+        
+        if !current_line.is_empty() {
+            lines.push(current_line);
+        }
+    }
+    
+    lines
+}
+
+// Calculate optimal popup dimensions based on content
+fn calculate_popup_dimensions(title: &str, content: &str, screen_width: f32, screen_height: f32) -> (f32, f32) {
+    let min_width = scale_size(400.0);
+    let max_width = screen_width * 0.85; // Maximum 85% of screen width
+    let min_height = scale_size(200.0);
+    let max_height = screen_height * 0.85; // Maximum 85% of screen height
+    
+    // Calculate title dimensions
+    let title_size = 28.0;
+    let scaled_title_size = scale_font_size(title_size);
+    let title_metrics = measure_text(title, None, scaled_title_size as u16, 1.0);
+    let title_width = title_metrics.width;
+    
+    // Calculate content dimensions with progressive width testing
+    let content_size = 20.0;
+    let content_margin = scale_size(20.0);
+    let line_height = scale_font_size(content_size) + scale_size(5.0);
+    
+    // Start with a reasonable width and expand if needed
+    let mut test_width = (min_width).max(title_width + scale_size(80.0)); // Title + padding
+    let mut final_width = test_width;
+    let mut final_height = min_height;
+    
+    // Test different widths to find optimal layout
+    for width_factor in [0.6, 0.7, 0.8, 0.85] {
+        test_width = (screen_width * width_factor).min(max_width);
+        let content_width = test_width - (content_margin * 2.0);
+        
+        if content_width > scale_size(300.0) { // Minimum reasonable content width
+            let wrapped_lines = wrap_text(content, content_width, scale_font_size(content_size));
+            
+            // Calculate required height
+            let title_area_height = scale_size(90.0); // Title + spacing
+            let content_area_height = wrapped_lines.len() as f32 * line_height;
+            let instruction_area_height = scale_size(60.0); // Instructions + spacing
+            
+            let required_height = title_area_height + content_area_height + instruction_area_height;
+            
+            // If this layout fits within screen bounds, use it
+            if required_height <= max_height {
+                final_width = test_width;
+                final_height = required_height.max(min_height);
+                break;
+            }
+        }
+    }
+    
+    // Ensure minimum dimensions
+    final_width = final_width.max(min_width);
+    final_height = final_height.max(min_height);
+    
+    // Ensure maximum dimensions
+    final_width = final_width.min(max_width);
+    final_height = final_height.min(max_height);
+    
+    (final_width, final_height)
+}
+
+impl Default for PopupSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
\ No newline at end of file
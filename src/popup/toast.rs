@@ -0,0 +1,172 @@
+// Small auto-dismissing notifications stacked in the bottom-right corner
+// Used instead of modal popups for minor events so they don't interrupt typing or a running program.
+
+use macroquad::prelude::*;
+use crate::font_scaling::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use super::PopupType;
+
+const TOAST_LIFETIME: f32 = 3.5;
+const FADE_IN: f32 = 0.2;
+const FADE_OUT: f32 = 0.6;
+const MAX_VISIBLE_TOASTS: usize = 5;
+
+/// Minor events that can be shown as a toast instead of a popup
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToastEvent {
+    ItemPickup,
+    RobotActions,
+    TaskProgress,
+    Notices,
+}
+
+impl ToastEvent {
+    pub const ALL: [ToastEvent; 4] = [
+        ToastEvent::ItemPickup,
+        ToastEvent::RobotActions,
+        ToastEvent::TaskProgress,
+        ToastEvent::Notices,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ToastEvent::ItemPickup => "Item pickups",
+            ToastEvent::RobotActions => "Robot action results",
+            ToastEvent::TaskProgress => "Task completed",
+            ToastEvent::Notices => "Other notices",
+        }
+    }
+}
+
+/// Which events use toasts; disabled events fall back to the old modal popup
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ToastSettings {
+    pub item_pickup: bool,
+    pub robot_actions: bool,
+    pub task_progress: bool,
+    pub notices: bool,
+}
+
+impl Default for ToastSettings {
+    fn default() -> Self {
+        Self {
+            item_pickup: true,
+            robot_actions: true,
+            task_progress: true,
+            notices: true,
+        }
+    }
+}
+
+impl ToastSettings {
+    pub fn enabled(&self, event: ToastEvent) -> bool {
+        match event {
+            ToastEvent::ItemPickup => self.item_pickup,
+            ToastEvent::RobotActions => self.robot_actions,
+            ToastEvent::TaskProgress => self.task_progress,
+            ToastEvent::Notices => self.notices,
+        }
+    }
+
+    pub fn toggle(&mut self, event: ToastEvent) {
+        let flag = match event {
+            ToastEvent::ItemPickup => &mut self.item_pickup,
+            ToastEvent::RobotActions => &mut self.robot_actions,
+            ToastEvent::TaskProgress => &mut self.task_progress,
+            ToastEvent::Notices => &mut self.notices,
+        };
+        *flag = !*flag;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Toast {
+    pub title: String,
+    pub text: String,
+    pub popup_type: PopupType,
+    pub age: f32,
+    pub slot: f32, // Animated stack position, eases toward the toast's index
+}
+
+impl Toast {
+    fn alpha(&self) -> f32 {
+        if self.age < FADE_IN {
+            self.age / FADE_IN
+        } else if self.age > TOAST_LIFETIME - FADE_OUT {
+            ((TOAST_LIFETIME - self.age) / FADE_OUT).max(0.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ToastStack {
+    pub toasts: VecDeque<Toast>,
+}
+
+impl ToastStack {
+    pub fn push(&mut self, title: String, text: String, popup_type: PopupType) {
+        // New toasts slide in from just below the bottom slot
+        self.toasts.push_back(Toast { title, text, popup_type, age: 0.0, slot: -1.0 });
+        while self.toasts.len() > MAX_VISIBLE_TOASTS {
+            self.toasts.pop_front();
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        for toast in self.toasts.iter_mut() {
+            toast.age += delta_time;
+        }
+        self.toasts.retain(|t| t.age < TOAST_LIFETIME);
+
+        // Newest toast sits at slot 0 (the bottom); older ones move up as new ones arrive
+        let count = self.toasts.len();
+        for (i, toast) in self.toasts.iter_mut().enumerate() {
+            let target = (count - 1 - i) as f32;
+            toast.slot += (target - toast.slot) * (delta_time * 12.0).min(1.0);
+        }
+    }
+
+    pub fn draw(&self) {
+        let screen_width = crate::crash_protection::safe_screen_width();
+        let screen_height = crate::crash_protection::safe_screen_height();
+        let width = scale_size(320.0);
+        let height = scale_size(56.0);
+        let margin = scale_size(16.0);
+        let spacing = scale_size(8.0);
+        let bottom = screen_height - margin - scale_size(30.0); // Stay above the controls line
+
+        for toast in &self.toasts {
+            let alpha = toast.alpha();
+            let x = screen_width - width - margin;
+            let y = bottom - height - toast.slot * (height + spacing);
+
+            let accent = match toast.popup_type {
+                PopupType::Success | PopupType::Congratulations => GREEN,
+                PopupType::FunctionResults => LIME,
+                PopupType::Warning => ORANGE,
+                PopupType::Stderr | PopupType::Panic => RED,
+                _ => SKYBLUE,
+            };
+
+            draw_rectangle(x, y, width, height, Color::new(0.08, 0.08, 0.12, 0.9 * alpha));
+            draw_rectangle(x, y, scale_size(4.0), height, Color::new(accent.r, accent.g, accent.b, alpha));
+            draw_rectangle_lines(x, y, width, height, scale_size(1.0), Color::new(0.6, 0.6, 0.6, alpha));
+
+            let text_x = x + scale_size(12.0);
+            draw_scaled_text(&toast.title, text_x, y + scale_size(20.0), 16.0, Color::new(accent.r, accent.g, accent.b, alpha));
+
+            // Toasts are one-liners; longer messages are in the Logs tab
+            let first_line = toast.text.lines().next().unwrap_or("");
+            let max_chars = ((width - scale_size(24.0)) / scale_size(7.0)) as usize;
+            let line: String = if first_line.chars().count() > max_chars {
+                first_line.chars().take(max_chars.saturating_sub(3)).collect::<String>() + "..."
+            } else {
+                first_line.to_string()
+            };
+            draw_scaled_text(&line, text_x, y + scale_size(42.0), 13.0, Color::new(1.0, 1.0, 1.0, alpha));
+        }
+    }
+}
@@ -1,10 +1,18 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::thread;
 use std::time::{Duration, Instant};
-use crate::level::LevelSpec;
+use crate::level::{LevelSpec, YamlLevelConfig};
 use crate::cache::GameCache;
 use rand::SeedableRng;
 
+// Directory community level files are loaded from, next to the executable's working directory
+pub const COMMUNITY_LEVELS_DIR: &str = "community_levels";
+
+// Community level files a loader worker parses at a time; the game gets the levels batch by batch
+const COMMUNITY_BATCH_SIZE: usize = 8;
+
 #[derive(Debug, Clone)]
 pub enum LoadingStage {
     Initialization,
@@ -22,6 +30,7 @@ pub struct LoadingProgress {
     pub progress: f32, // 0.0 to 1.0
     pub total_items: usize,
     pub completed_items: usize,
+    pub errors: Vec<String>, // Level files that failed to load since the last update, e.g. "maze.yaml: ..."
 }
 
 pub struct ProgressiveLoader {
@@ -78,6 +87,7 @@ impl ProgressiveLoader {
             progress: 0.0,
             total_items: 6, // Increased for new caching stages
             completed_items: 0,
+            errors: Vec::new(),
         });
         
         // Check if we can use cached startup data for ultra-fast loading
@@ -100,6 +110,7 @@ impl ProgressiveLoader {
             progress: 0.16,
             total_items: 6,
             completed_items: 1,
+            errors: Vec::new(),
         });
         
         // Load basic game constants and configurations
@@ -112,6 +123,7 @@ impl ProgressiveLoader {
             progress: 0.33,
             total_items: 6,
             completed_items: 2,
+            errors: Vec::new(),
         });
         
         // Load embedded learning levels first (highest priority)
@@ -129,10 +141,11 @@ impl ProgressiveLoader {
             progress: 0.5,
             total_items: 6,
             completed_items: 3,
+            errors: Vec::new(),
         });
         
-        // Load external community levels with caching
-        let community_levels = Self::load_community_levels_cached(cache);
+        // Community levels are parsed in parallel and reach the game a batch at a time
+        let community_levels = Self::load_community_levels(&progress_sender, &levels_sender, &all_levels);
         all_levels.extend(community_levels);
         log::info!("Total levels loaded: {}", all_levels.len());
         
@@ -143,6 +156,7 @@ impl ProgressiveLoader {
             progress: 0.66,
             total_items: 6,
             completed_items: 4,
+            errors: Vec::new(),
         });
         
         // Pre-cache common font sizes for better performance
@@ -155,6 +169,7 @@ impl ProgressiveLoader {
             progress: 0.83,
             total_items: 6,
             completed_items: 5,
+            errors: Vec::new(),
         });
         
         // Cache startup data for ultra-fast loading next time
@@ -167,6 +182,7 @@ impl ProgressiveLoader {
             progress: 1.0,
             total_items: 6,
             completed_items: 6,
+            errors: Vec::new(),
         });
         
         // Brief delay to show completion message before hiding
@@ -192,6 +208,7 @@ impl ProgressiveLoader {
             progress: 0.5,
             total_items: 2,
             completed_items: 1,
+            errors: Vec::new(),
         });
         
         // Load embedded levels (these are always available)
@@ -212,6 +229,7 @@ impl ProgressiveLoader {
             progress: 1.0,
             total_items: 2,
             completed_items: 2,
+            errors: Vec::new(),
         });
         
         let load_time = start_time.elapsed();
@@ -239,11 +257,139 @@ impl ProgressiveLoader {
         cache.cache_startup_data(startup_data);
     }
     
-    fn load_community_levels_cached(_cache: &mut GameCache) -> Vec<LevelSpec> {
-        // Community levels removed - only use learning levels now
-        Vec::new()
+    /// Parse the community level files on worker threads. Each time a batch finishes, the game gets
+    /// every level so far (after `loaded`) and the progress reports the files that failed.
+    fn load_community_levels(
+        progress_sender: &mpsc::Sender<LoadingProgress>,
+        levels_sender: &mpsc::Sender<Vec<LevelSpec>>,
+        loaded: &[LevelSpec],
+    ) -> Vec<LevelSpec> {
+        // Players can drop level files into community_levels/; their texts go through the
+        // community sanitizer before anything in them can reach a popup
+        let filter_words = crate::menu::GameSettings::load_or_default().filter_community_text;
+        let files = community_level_files(Path::new(COMMUNITY_LEVELS_DIR));
+        let total = files.len();
+        let batches: Vec<Vec<PathBuf>> = files.chunks(COMMUNITY_BATCH_SIZE).map(<[PathBuf]>::to_vec).collect();
+        let workers = thread::available_parallelism().map_or(2, |n| n.get()).min(batches.len());
+
+        let (batch_sender, batch_receiver) = crossbeam_channel::unbounded();
+        let (result_sender, result_receiver) = crossbeam_channel::unbounded();
+        for batch in batches.into_iter().enumerate() {
+            let _ = batch_sender.send(batch);
+        }
+        drop(batch_sender);
+
+        let mut levels = Vec::new();
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                let batch_receiver = batch_receiver.clone();
+                let result_sender = result_sender.clone();
+                scope.spawn(move || {
+                    for (index, paths) in batch_receiver {
+                        let results: Vec<Result<LevelSpec, String>> = paths.iter().map(|path| load_community_level(path, filter_words)).collect();
+                        if result_sender.send((index, results)).is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+            drop(result_sender);
+
+            // Levels keep the order of their files, so a batch that finishes early waits for the ones before it
+            let mut finished: BTreeMap<usize, Vec<LevelSpec>> = BTreeMap::new();
+            let mut next_batch = 0;
+            let mut done = 0;
+            for (index, results) in result_receiver {
+                done += results.len();
+                let (ok, failed): (Vec<_>, Vec<_>) = results.into_iter().partition(Result::is_ok);
+                finished.insert(index, ok.into_iter().flatten().collect());
+                let errors: Vec<String> = failed.into_iter().filter_map(Result::err).collect();
+                for error in &errors {
+                    log::warn!("Skipping community level {}", error);
+                }
+
+                let mut added = false;
+                while let Some(batch) = finished.remove(&next_batch) {
+                    levels.extend(batch);
+                    next_batch += 1;
+                    added = true;
+                }
+                if added {
+                    let _ = levels_sender.send(loaded.iter().chain(&levels).cloned().collect());
+                }
+                let _ = progress_sender.send(LoadingProgress {
+                    stage: LoadingStage::CommunityLevels,
+                    current_item: format!("Loaded {}/{} community level files", done, total),
+                    progress: 0.5 + 0.16 * done as f32 / total as f32,
+                    total_items: total,
+                    completed_items: done,
+                    errors,
+                });
+            }
+        });
+
+        // Packs are unpacked to disk one after another, after the plain files
+        let (packed, errors): (Vec<_>, Vec<_>) = Self::load_level_packs().into_iter()
+            .map(|(path, config)| config.and_then(|config| community_level_spec(config, filter_words)).map_err(|e| format!("{}: {}", file_name(&path), e)))
+            .partition(Result::is_ok);
+        let mut errors: Vec<String> = errors.into_iter().filter_map(Result::err).collect();
+        for error in &errors {
+            log::warn!("Skipping level pack {}", error);
+        }
+        if !packed.is_empty() {
+            levels.extend(packed.into_iter().flatten());
+            let _ = levels_sender.send(loaded.iter().chain(&levels).cloned().collect());
+        }
+
+        // A requirement naming no level can never be met; campaigns ignore it, but the author should hear about it
+        let names: Vec<&str> = loaded.iter().chain(&levels).map(|level| level.name.as_str()).collect();
+        for level in &levels {
+            for required in level.requires.iter().filter(|required| !names.contains(&required.as_str())) {
+                let error = format!("level '{}': requires unknown level '{}'", level.name, required);
+                log::warn!("{}", error);
+                errors.push(error);
+            }
+        }
+        if !errors.is_empty() {
+            let _ = progress_sender.send(LoadingProgress {
+                stage: LoadingStage::CommunityLevels,
+                current_item: "Unpacked level packs".to_string(),
+                progress: 0.66,
+                total_items: total,
+                completed_items: total,
+                errors,
+            });
+        }
+        levels
     }
-    
+
+    /// Unpack every .rlgpack in community_levels/ into community_levels/unpacked/
+    fn load_level_packs() -> Vec<(PathBuf, Result<YamlLevelConfig, String>)> {
+        use crate::level::pack::{extract_level_pack, PACK_EXTENSION};
+
+        #[cfg(feature = "steam")]
+        crate::steam::sync_workshop_packs(std::path::Path::new(COMMUNITY_LEVELS_DIR));
+
+        let mut packs: Vec<_> = std::fs::read_dir(COMMUNITY_LEVELS_DIR)
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default();
+        packs.retain(|path| path.extension().is_some_and(|ext| ext == PACK_EXTENSION));
+        packs.sort();
+
+        let unpacked = std::path::Path::new(COMMUNITY_LEVELS_DIR).join("unpacked");
+        packs.into_iter().map(|path| {
+            let config = extract_level_pack(&path, &unpacked).map(|pack| {
+                let mut config = pack.config;
+                // The pack's docs stand in for a hint if the level doesn't write its own
+                if config.hint_message.is_none() {
+                    config.hint_message = pack.docs.first().and_then(|doc| std::fs::read_to_string(doc).ok());
+                }
+                config
+            }).map_err(|e| e.to_string());
+            (path, config)
+        }).collect()
+    }
+
     fn precache_font_metrics(cache: &mut GameCache) {
         use crate::cache::FontMetrics;
         
@@ -260,7 +406,7 @@ impl ProgressiveLoader {
                     .unwrap_or_default()
                     .as_secs();
                     
-                if current_time - cached.cached_at < 86400 { // 24 hour cache
+                if current_time.saturating_sub(cached.cached_at) < 86400 { // 24 hour cache
                     continue; // Skip if fresh
                 }
             }
@@ -274,6 +420,7 @@ impl ProgressiveLoader {
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap_or_default()
                     .as_secs(),
+                last_used: 0, // Set by cache_font_metrics
             };
             
             cache.cache_font_metrics(cache_key, metrics);
@@ -288,7 +435,66 @@ impl ProgressiveLoader {
         self.progress_receiver.try_recv().ok()
     }
     
+    /// The newest level list the loader has sent, if there's one the game hasn't seen
     pub fn get_loaded_levels(&self) -> Option<Vec<LevelSpec>> {
-        self.levels_receiver.try_recv().ok()
+        self.levels_receiver.try_iter().last()
+    }
+}
+
+/// The level files (YAML, JSON or TOML) of a community level directory: the ones order.txt names,
+/// in its order, or else all of them alphabetically
+fn community_level_files(dir: &Path) -> Vec<PathBuf> {
+    let ordered: Vec<PathBuf> = std::fs::read_to_string(dir.join("order.txt"))
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|name| crate::level::find_level_file(dir, name))
+        .collect();
+    if !ordered.is_empty() {
+        return ordered;
+    }
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    files.retain(|path| crate::level::LevelFormat::from_path(path).is_some());
+    files.sort();
+    files
+}
+
+/// Parse one community level file; the error starts with the file name
+fn load_community_level(path: &Path, filter_words: bool) -> Result<LevelSpec, String> {
+    YamlLevelConfig::from_file(path)
+        .map_err(|e| e.to_string())
+        .and_then(|config| community_level_spec(config, filter_words))
+        .map_err(|e| format!("{}: {}", file_name(path), e))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned())
+}
+
+/// Build a community level after running its texts through the community sanitizer
+pub fn community_level_spec(mut config: YamlLevelConfig, filter_words: bool) -> Result<LevelSpec, String> {
+    config.sanitize_text(filter_words);
+    let mut problems = Vec::new();
+    crate::level::validation::check_metadata(&config, &mut problems);
+    if !problems.is_empty() {
+        return Err(format!("level '{}': {}", config.name, problems.join("; ")));
     }
-}
\ No newline at end of file
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0xC0FFEE);
+    match config.to_level_spec(&mut rng) {
+        Ok(mut spec) => {
+            // Evaluators are programs; a downloaded level doesn't get to run them
+            for task in &mut spec.tasks {
+                if task.task_file.as_deref().is_some_and(crate::task_plugins::is_plugin) {
+                    log::warn!("Community level '{}' task '{}' names an evaluator; ignoring it", spec.name, task.name);
+                    task.task_file = None;
+                }
+            }
+            Ok(spec)
+        }
+        Err(e) => Err(format!("level '{}': {}", config.name, e)),
+    }
+}
@@ -1,7 +1,33 @@
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+use crossbeam_channel::{unbounded, Receiver, Sender, TryRecvError};
 use serde_json::Value;
+use crate::text_buffer::TextBuffer;
+
+/// Seconds without typing before the editor code is checked in the background
+pub const CHECK_DEBOUNCE_SECS: f64 = 0.75;
+
+/// The scratch project's build output is wiped when it grows past this, and rebuilt on the next check
+pub const MAX_CHECK_CACHE_BYTES: u64 = 512 * 1024 * 1024;
+
+// Incremental, debuginfo-free dev profile: repeat checks only redo what the edit touched.
+// The empty [workspace] keeps it standalone when the game runs from inside its own checkout.
+const CHECKER_CARGO_TOML: &str = r#"[workspace]
+
+[package]
+name = "rust_game_checker"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+
+[profile.dev]
+incremental = true
+debug = 0
+"#;
 
 #[derive(Debug, Clone)]
 pub struct CompilerError {
@@ -9,6 +35,7 @@ pub struct CompilerError {
     pub column: usize,
     pub message: String,
     pub severity: ErrorSeverity,
+    pub code: Option<String>, // rustc error code like "E0308", when the compiler gave one
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -26,8 +53,13 @@ pub struct RustChecker {
 
 impl RustChecker {
     pub fn new() -> Result<Self, String> {
-        // Create a temporary directory for our Rust project
-        let temp_dir = std::env::temp_dir().join("rust_game_checker");
+        // A persistent scratch project in the cache directory, so cargo's incremental build
+        // survives between checks and between game sessions
+        // (absolute, since cargo runs inside it)
+        let temp_dir = std::env::current_dir()
+            .map_err(|e| format!("Failed to find the working directory: {}", e))?
+            .join(crate::cache::cache_dir())
+            .join("syntax_check");
         
         let checker = RustChecker {
             temp_dir,
@@ -42,23 +74,16 @@ impl RustChecker {
             return Ok(());
         }
         
-        // Create temp directory if it doesn't exist
+        // Create the project directory if it doesn't exist
         if !self.temp_dir.exists() {
             fs::create_dir_all(&self.temp_dir)
-                .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+                .map_err(|e| format!("Failed to create checker project directory: {}", e))?;
         }
         
-        // Create Cargo.toml
+        // Create Cargo.toml, or update one written by an older version of the game
         let cargo_toml_path = self.temp_dir.join("Cargo.toml");
-        if !cargo_toml_path.exists() {
-            let cargo_toml_content = r#"[package]
-name = "rust_game_checker"
-version = "0.1.0"
-edition = "2021"
-
-[dependencies]
-"#;
-            fs::write(&cargo_toml_path, cargo_toml_content)
+        if fs::read_to_string(&cargo_toml_path).ok().as_deref() != Some(CHECKER_CARGO_TOML) {
+            fs::write(&cargo_toml_path, CHECKER_CARGO_TOML)
                 .map_err(|e| format!("Failed to create Cargo.toml: {}", e))?;
         }
         
@@ -68,36 +93,89 @@ edition = "2021"
             fs::create_dir_all(&src_dir)
                 .map_err(|e| format!("Failed to create src directory: {}", e))?;
         }
+
+        self.trim_build_cache();
         
         self.project_initialized = true;
         Ok(())
     }
+
+    /// Enforce MAX_CHECK_CACHE_BYTES once per session. Incremental data piles up over many
+    /// sessions; wiping it costs one cold check.
+    fn trim_build_cache(&self) {
+        let target_dir = self.temp_dir.join("target");
+        let size = dir_size(&target_dir);
+        if size > MAX_CHECK_CACHE_BYTES {
+            log::info!("Syntax checker cache is {} MB, clearing it", size / (1024 * 1024));
+            if let Err(e) = fs::remove_dir_all(&target_dir) {
+                log::warn!("Failed to clear syntax checker cache: {}", e);
+            }
+        }
+    }
     
     pub fn check_syntax(&mut self, user_code: &str) -> Result<Vec<CompilerError>, String> {
+        let mut child = self.spawn_check(user_code)?;
+        child.wait()
+            .map_err(|e| format!("Failed to run cargo check: {}", e))?;
+        self.read_check_output()
+    }
+
+    /// Start `cargo check` on the wrapped code without waiting for it. The JSON output goes to
+    /// a file so a long-running check can't block on a full pipe.
+    fn spawn_check(&mut self, user_code: &str) -> Result<Child, String> {
         self.ensure_project()?;
 
+        // Code using serde or log needs those crates; other code keeps the manifest without dependencies
+        let dependencies = crate::code_executor::crate_dependencies(user_code);
+        let cargo_toml = CHECKER_CARGO_TOML.replace("[dependencies]\n", &format!("[dependencies]\n{}", dependencies));
+        let cargo_toml_path = self.temp_dir.join("Cargo.toml");
+        if fs::read_to_string(&cargo_toml_path).ok() != Some(cargo_toml.clone()) {
+            fs::write(&cargo_toml_path, cargo_toml)
+                .map_err(|e| format!("Failed to write Cargo.toml: {}", e))?;
+        }
+
         // Create a main.rs file with the user's code wrapped in proper structure
         let wrapped_code = self.wrap_user_code(user_code);
         let main_rs_path = self.temp_dir.join("src").join("main.rs");
 
-        fs::write(&main_rs_path, wrapped_code)
-            .map_err(|e| format!("Failed to write main.rs: {}", e))?;
+        // Leave an unchanged file alone so cargo replays its cached diagnostics instead of rechecking
+        if fs::read_to_string(&main_rs_path).ok() != Some(wrapped_code.clone()) {
+            fs::write(&main_rs_path, wrapped_code)
+                .map_err(|e| format!("Failed to write main.rs: {}", e))?;
+        }
+
+        let output = fs::File::create(self.temp_dir.join("check-output.json"))
+            .map_err(|e| format!("Failed to create check output file: {}", e))?;
 
         // Run cargo check to get compiler output
-        let output = Command::new("cargo")
-            .args(&["check", "--message-format=json"])
+        Command::new("cargo")
+            .args(["check", "--message-format=json"])
             .current_dir(&self.temp_dir)
-            .output()
-            .map_err(|e| format!("Failed to run cargo check: {}. Make sure cargo is installed.", e))?;
+            // Keep the build next to the project even if the player set up a shared target dir
+            .env("CARGO_TARGET_DIR", self.temp_dir.join("target"))
+            .env("CARGO_INCREMENTAL", "1")
+            .stdout(Stdio::from(output))
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to run cargo check: {}. Make sure cargo is installed.", e))
+    }
+
+    fn read_check_output(&self) -> Result<Vec<CompilerError>, String> {
+        let output = fs::read(self.temp_dir.join("check-output.json"))
+            .map_err(|e| format!("Failed to read cargo check output: {}", e))?;
 
         // Parse the JSON output from cargo
-        self.parse_cargo_output(&output.stdout)
+        self.parse_cargo_output(&output)
     }
 
     /// Enhanced syntax checking with detailed error reporting
     pub fn check_syntax_enhanced(&mut self, user_code: &str) -> Result<Vec<CompilerError>, String> {
-        // First try normal syntax checking
-        match self.check_syntax(user_code) {
+        let result = self.check_syntax(user_code);
+        self.enhance_result(result, user_code)
+    }
+
+    fn enhance_result(&self, result: Result<Vec<CompilerError>, String>, user_code: &str) -> Result<Vec<CompilerError>, String> {
+        match result {
             Ok(errors) => {
                 // If we get errors, also provide helpful context
                 let mut enhanced_errors = errors;
@@ -135,6 +213,7 @@ edition = "2021"
                     column: 1,
                     message: "Invalid for loop syntax. Expected 'for var in iterable', not double 'in'.".to_string(),
                     severity: ErrorSeverity::Error,
+                    code: None,
                 });
             }
 
@@ -144,6 +223,7 @@ edition = "2021"
                     column: 1,
                     message: "println! macro with format string '{}' is missing arguments.".to_string(),
                     severity: ErrorSeverity::Error,
+                    code: None,
                 });
             }
 
@@ -154,6 +234,7 @@ edition = "2021"
                     column: 1,
                     message: "Cannot find value 's' in this scope. Did you mean to call a function?".to_string(),
                     severity: ErrorSeverity::Error,
+                    code: None,
                 });
             }
 
@@ -166,6 +247,7 @@ edition = "2021"
                     column: 1,
                     message: format!("Mismatched parentheses: {} opening, {} closing.", open_parens, close_parens),
                     severity: ErrorSeverity::Error,
+                    code: None,
                 });
             }
         }
@@ -216,6 +298,46 @@ fn move_to(x: i32, y: i32) -> String {{ String::new() }}
 fn scan() -> String {{ String::new() }}
 fn grab() -> String {{ String::new() }}
 fn open_door(open: bool) -> String {{ String::new() }}
+fn teleport(x: i32, y: i32) -> String {{ String::new() }}
+struct RobotHandle;
+// robot.move_up() and robot(1).move_up(): the trait is implemented for robot() itself too
+trait RobotApi {{
+    fn move_bot(&self, direction: &str) -> String {{ String::new() }}
+    fn move_up(&self) -> String {{ String::new() }}
+    fn move_down(&self) -> String {{ String::new() }}
+    fn move_left(&self) -> String {{ String::new() }}
+    fn move_right(&self) -> String {{ String::new() }}
+    fn scan(&self, direction: &str) -> String {{ String::new() }}
+    fn scan_up(&self) -> String {{ String::new() }}
+    fn scan_down(&self) -> String {{ String::new() }}
+    fn scan_left(&self) -> String {{ String::new() }}
+    fn scan_right(&self) -> String {{ String::new() }}
+    fn scan_current(&self) -> String {{ String::new() }}
+    fn grab(&self) -> String {{ String::new() }}
+    fn open_door(&self, open: bool) -> String {{ String::new() }}
+    fn teleport(&self, x: i32, y: i32) -> String {{ String::new() }}
+    fn move_forward(&self) -> String {{ String::new() }}
+    fn rotate_left(&self) -> String {{ String::new() }}
+    fn rotate_right(&self) -> String {{ String::new() }}
+    fn print_map(&self) {{}}
+    fn rewind(&self, turns: usize) -> String {{ String::new() }}
+    fn emp(&self, radius: u32) -> String {{ String::new() }}
+    fn set_auto_grab(&self, enabled: bool) -> String {{ String::new() }}
+    fn say(&self, text: &str) -> String {{ String::new() }}
+}}
+impl RobotApi for RobotHandle {{}}
+impl<F: Fn(usize) -> RobotHandle> RobotApi for F {{}}
+fn robot(index: usize) -> RobotHandle {{ RobotHandle }}
+fn select_robot(index: usize) -> String {{ String::new() }}
+fn remaining_energy() -> u32 {{ 0 }}
+fn move_forward() -> String {{ String::new() }}
+fn rotate_left() -> String {{ String::new() }}
+fn rotate_right() -> String {{ String::new() }}
+fn print_map() {{}}
+fn rewind(turns: usize) -> String {{ String::new() }}
+fn emp(radius: u32) -> String {{ String::new() }}
+fn set_auto_grab(enabled: bool) -> String {{ String::new() }}
+fn say(text: &str) -> String {{ String::new() }}
 fn use_item(item: &str) -> String {{ String::new() }}
 fn attack() -> String {{ String::new() }}
 fn defend() -> String {{ String::new() }}
@@ -230,6 +352,7 @@ fn check_position() -> (i32, i32) {{ (0, 0) }}
 fn get_health() -> i32 {{ 100 }}
 fn get_energy() -> i32 {{ 100 }}
 fn is_blocked(direction: &str) -> bool {{ false }}
+fn random_int(min: i32, max: i32) -> i32 {{ min }}
 
 // Utility functions commonly used
 fn m(direction: &str) -> String {{ String::new() }} // Common abbreviation
@@ -244,6 +367,8 @@ mod laser {{
     pub fn aim(x: i32, y: i32) -> String {{ String::new() }}
 }}
 
+{}
+
 // Direction constants
 const UP: &str = "up";
 const DOWN: &str = "down";
@@ -256,7 +381,7 @@ const WEST: &str = "west";
 
 // User code with its own main function
 {}
-"#, user_code)
+"#, crate::code_executor::library_stubs(user_code), user_code)
         } else {
             // If no main function, wrap it like before
             format!(r#"// Comprehensive Rust syntax checker with all game functions
@@ -279,6 +404,46 @@ fn move_to(x: i32, y: i32) -> String {{ String::new() }}
 fn scan() -> String {{ String::new() }}
 fn grab() -> String {{ String::new() }}
 fn open_door(open: bool) -> String {{ String::new() }}
+fn teleport(x: i32, y: i32) -> String {{ String::new() }}
+struct RobotHandle;
+// robot.move_up() and robot(1).move_up(): the trait is implemented for robot() itself too
+trait RobotApi {{
+    fn move_bot(&self, direction: &str) -> String {{ String::new() }}
+    fn move_up(&self) -> String {{ String::new() }}
+    fn move_down(&self) -> String {{ String::new() }}
+    fn move_left(&self) -> String {{ String::new() }}
+    fn move_right(&self) -> String {{ String::new() }}
+    fn scan(&self, direction: &str) -> String {{ String::new() }}
+    fn scan_up(&self) -> String {{ String::new() }}
+    fn scan_down(&self) -> String {{ String::new() }}
+    fn scan_left(&self) -> String {{ String::new() }}
+    fn scan_right(&self) -> String {{ String::new() }}
+    fn scan_current(&self) -> String {{ String::new() }}
+    fn grab(&self) -> String {{ String::new() }}
+    fn open_door(&self, open: bool) -> String {{ String::new() }}
+    fn teleport(&self, x: i32, y: i32) -> String {{ String::new() }}
+    fn move_forward(&self) -> String {{ String::new() }}
+    fn rotate_left(&self) -> String {{ String::new() }}
+    fn rotate_right(&self) -> String {{ String::new() }}
+    fn print_map(&self) {{}}
+    fn rewind(&self, turns: usize) -> String {{ String::new() }}
+    fn emp(&self, radius: u32) -> String {{ String::new() }}
+    fn set_auto_grab(&self, enabled: bool) -> String {{ String::new() }}
+    fn say(&self, text: &str) -> String {{ String::new() }}
+}}
+impl RobotApi for RobotHandle {{}}
+impl<F: Fn(usize) -> RobotHandle> RobotApi for F {{}}
+fn robot(index: usize) -> RobotHandle {{ RobotHandle }}
+fn select_robot(index: usize) -> String {{ String::new() }}
+fn remaining_energy() -> u32 {{ 0 }}
+fn move_forward() -> String {{ String::new() }}
+fn rotate_left() -> String {{ String::new() }}
+fn rotate_right() -> String {{ String::new() }}
+fn print_map() {{}}
+fn rewind(turns: usize) -> String {{ String::new() }}
+fn emp(radius: u32) -> String {{ String::new() }}
+fn set_auto_grab(enabled: bool) -> String {{ String::new() }}
+fn say(text: &str) -> String {{ String::new() }}
 fn use_item(item: &str) -> String {{ String::new() }}
 fn attack() -> String {{ String::new() }}
 fn defend() -> String {{ String::new() }}
@@ -293,6 +458,7 @@ fn check_position() -> (i32, i32) {{ (0, 0) }}
 fn get_health() -> i32 {{ 100 }}
 fn get_energy() -> i32 {{ 100 }}
 fn is_blocked(direction: &str) -> bool {{ false }}
+fn random_int(min: i32, max: i32) -> i32 {{ min }}
 
 // Utility functions commonly used
 fn m(direction: &str) -> String {{ String::new() }} // Common abbreviation
@@ -307,6 +473,8 @@ mod laser {{
     pub fn aim(x: i32, y: i32) -> String {{ String::new() }}
 }}
 
+{}
+
 // Direction constants
 const UP: &str = "up";
 const DOWN: &str = "down";
@@ -334,7 +502,7 @@ fn main() {{
     }};
     // Explicit unit return to avoid expression issues
 }}
-"#, user_code)
+"#, crate::code_executor::library_stubs(user_code), user_code)
         }
     }
     
@@ -363,6 +531,7 @@ fn main() {{
     fn parse_compiler_message(&self, message: &Value) -> Option<CompilerError> {
         let message_text = message.get("message")?.as_str()?.to_string();
         let level = message.get("level")?.as_str()?;
+        let code = message.get("code").and_then(|code| code.get("code")).and_then(|code| code.as_str()).map(str::to_string);
         
         let severity = match level {
             "error" => ErrorSeverity::Error,
@@ -394,21 +563,185 @@ fn main() {{
             column: column_start,
             message: message_text,
             severity,
+            code,
         })
     }
     
-    pub fn cleanup(&self) -> Result<(), String> {
-        if self.temp_dir.exists() {
-            fs::remove_dir_all(&self.temp_dir)
-                .map_err(|e| format!("Failed to cleanup temp directory: {}", e))?;
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Result of a background check, for the exact code that was checked
+#[derive(Debug)]
+pub struct CheckOutcome {
+    pub code: String,
+    pub result: Result<Vec<CompilerError>, String>,
+}
+
+/// Runs the enhanced syntax check on a worker thread so neither typing nor Ctrl+Shift+Enter
+/// freezes the UI on a cold cargo build. Editor code is checked once typing pauses for
+/// CHECK_DEBOUNCE_SECS; a check still running when newer code comes in is killed.
+#[derive(Debug)]
+pub struct BackgroundChecker {
+    requests: Sender<String>,
+    results: Receiver<CheckOutcome>,
+    in_flight: Option<String>,
+    latest: Option<CheckOutcome>,
+    seen_revision: u64,
+    requested_revision: Option<u64>,
+    last_edit: f64,
+}
+
+impl BackgroundChecker {
+    pub fn new() -> Result<Self, String> {
+        let checker = RustChecker::new()?;
+        let (requests, worker_requests) = unbounded();
+        let (worker_results, results) = unbounded();
+        thread::Builder::new()
+            .name("syntax-check".to_string())
+            .spawn(move || run_checks(checker, worker_requests, worker_results))
+            .map_err(|e| format!("Failed to start syntax check thread: {}", e))?;
+
+        Ok(Self {
+            requests,
+            results,
+            in_flight: None,
+            latest: None,
+            seen_revision: 0,
+            requested_revision: None,
+            last_edit: 0.0,
+        })
+    }
+
+    /// Call every frame with the editor contents; starts a check once the code stops changing.
+    /// Edits are noticed through the buffer's revision, so the text is only read to check it.
+    pub fn update(&mut self, code: &TextBuffer, now: f64) {
+        self.collect_results();
+        if code.revision() != self.seen_revision {
+            self.seen_revision = code.revision();
+            self.last_edit = now;
+        }
+        if now - self.last_edit >= CHECK_DEBOUNCE_SECS && self.requested_revision != Some(code.revision()) {
+            self.requested_revision = Some(code.revision());
+            self.check_now(code);
+        }
+    }
+
+    /// Check `code` right away unless it's already checked or being checked
+    pub fn check_now(&mut self, code: &str) {
+        self.collect_results();
+        let done = self.latest.as_ref().is_some_and(|outcome| outcome.code == code);
+        if done || self.in_flight.as_deref() == Some(code) {
+            return;
+        }
+        if self.requests.send(code.to_string()).is_ok() {
+            self.in_flight = Some(code.to_string());
+        }
+    }
+
+    /// Finished result for exactly this code, if there is one
+    pub fn result_for<C: PartialEq<str> + ?Sized>(&mut self, code: &C) -> Option<&Result<Vec<CompilerError>, String>> {
+        self.collect_results();
+        self.latest.as_ref().filter(|outcome| *code == *outcome.code).map(|outcome| &outcome.result)
+    }
+
+    /// Block until `code` has been checked. For the headless and test modes, which have no
+    /// frame loop to wait in.
+    pub fn wait_for(&mut self, code: &str) -> Result<Vec<CompilerError>, String> {
+        self.check_now(code);
+        while self.result_for(code).is_none() {
+            if self.in_flight.as_deref() != Some(code) {
+                return Err("Syntax check was not started".to_string());
+            }
+            match self.results.recv() {
+                Ok(outcome) if outcome.code == code => {
+                    self.in_flight = None;
+                    self.latest = Some(outcome);
+                }
+                Ok(_) => {}
+                Err(_) => return Err("Syntax check thread stopped".to_string()),
+            }
+        }
+        self.result_for(code).cloned().unwrap_or_else(|| Err("Syntax check was not started".to_string()))
+    }
+
+    pub fn is_checking(&self) -> bool {
+        self.in_flight.is_some()
+    }
+
+    fn collect_results(&mut self) {
+        while let Ok(outcome) = self.results.try_recv() {
+            // Results for code that has been replaced since are stale
+            if self.in_flight.as_deref() == Some(outcome.code.as_str()) {
+                self.in_flight = None;
+                self.latest = Some(outcome);
+            }
         }
-        Ok(())
     }
 }
 
-impl Drop for RustChecker {
-    fn drop(&mut self) {
-        let _ = self.cleanup(); // Best effort cleanup
+/// Worker loop: check the newest requested code, killing cargo if newer code arrives meanwhile
+fn run_checks(mut checker: RustChecker, requests: Receiver<String>, results: Sender<CheckOutcome>) {
+    let mut next = None;
+    loop {
+        let code = match next.take() {
+            Some(code) => code,
+            None => match requests.recv() {
+                Ok(code) => code,
+                Err(_) => return, // The game shut down
+            },
+        };
+        // Only the newest of several queued requests is worth checking
+        let code = requests.try_iter().last().unwrap_or(code);
+
+        let mut child = match checker.spawn_check(&code) {
+            Ok(child) => child,
+            Err(e) => {
+                let result = checker.enhance_result(Err(e), &code);
+                let _ = results.send(CheckOutcome { code, result });
+                continue;
+            }
+        };
+
+        loop {
+            match requests.try_recv() {
+                Ok(newer) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    next = Some(newer);
+                    break;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    let _ = child.kill();
+                    return;
+                }
+                Err(TryRecvError::Empty) => {}
+            }
+
+            match child.try_wait() {
+                Ok(Some(_)) => {
+                    let result = checker.enhance_result(checker.read_check_output(), &code);
+                    let _ = results.send(CheckOutcome { code, result });
+                    break;
+                }
+                Ok(None) => thread::sleep(Duration::from_millis(20)),
+                Err(e) => {
+                    let result = checker.enhance_result(Err(format!("Failed to run cargo check: {}", e)), &code);
+                    let _ = results.send(CheckOutcome { code, result });
+                    break;
+                }
+            }
+        }
     }
 }
 
@@ -441,10 +774,14 @@ pub fn format_errors_for_display(errors: &[CompilerError]) -> String {
             ErrorSeverity::Help => "💡",
         };
 
+        let code = error.code.as_ref().map(|code| format!("[{}] ", code)).unwrap_or_default();
         result.push_str(&format!(
-            "{}. {} Line {}: {}\n",
-            i + 1, icon, error.line, error.message
+            "{}. {} Line {}: {}{}\n",
+            i + 1, icon, error.line, code, error.message
         ));
+        if let Some(explanation) = error.code.as_deref().and_then(crate::error_catalog::explain) {
+            result.push_str(&format!("   💡 {}\n", explanation.title));
+        }
 
         if error.severity == ErrorSeverity::Error {
             result.push_str("   └─ Fix this error before running your code!\n");
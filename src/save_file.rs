@@ -0,0 +1,189 @@
+// Save files: the JSON files the game keeps between sessions (settings, progress, hotkeys, the
+// cache). They're written to a temporary file that is then renamed over the old one, so a crash
+// mid-write leaves the previous file intact instead of half of a new one. Each carries a
+// `schema_version`, and every time one loads cleanly it's copied to `<file>.bak`; a file that
+// won't load is moved aside to `<file>.corrupt` and the backup restored in its place.
+//
+// Files from before versioning have no `schema_version` and load as version 0. A type that
+// changes its layout bumps its version; an older file that no longer fits is outdated rather than
+// damaged and is left alone, while files newer than the game are refused, not guessed at.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const VERSION_KEY: &str = "schema_version";
+
+/// Why a save file couldn't be loaded
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadError {
+    /// Written with an older schema_version whose layout doesn't fit this one; nothing is moved aside
+    Outdated(String),
+    /// Unreadable, not valid JSON or not what it should hold, and there was no backup to restore
+    Damaged(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Outdated(problem) | LoadError::Damaged(problem) => f.write_str(problem),
+        }
+    }
+}
+
+/// `path` with `.suffix` appended, e.g. game_settings.json.bak
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Replace `path` with `contents` all at once: write a temporary file next to it, then rename it over
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), String> {
+    let tmp = sibling(path, "tmp");
+    let written = fs::File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(contents.as_ref())?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp, path));
+    written.map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Failed to write {}: {}", path.display(), e)
+    })
+}
+
+/// Write `value` as pretty JSON tagged with `version`
+pub fn save_json<T: Serialize>(path: &Path, version: u32, value: &T) -> Result<(), String> {
+    let mut json = serde_json::to_value(value).map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    if let Value::Object(fields) = &mut json {
+        fields.insert(VERSION_KEY.to_string(), Value::from(version));
+    }
+    let text = serde_json::to_string_pretty(&json).map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    write_atomic(path, text)
+}
+
+/// Read a file saved with save_json. None when there isn't one yet; when it's damaged the last copy
+/// that loaded is restored, and only if there's none of those either is it an error. An outdated
+/// file is reported as such and left in place for the caller to replace.
+pub fn load_json<T: DeserializeOwned>(path: &Path, version: u32) -> Result<Option<T>, LoadError> {
+    let problem = match read_versioned(path, version) {
+        Ok(None) => return Ok(None),
+        Ok(Some((value, raw))) => {
+            if let Err(e) = write_atomic(&sibling(path, "bak"), raw) {
+                log::warn!("{}", e);
+            }
+            return Ok(Some(value));
+        }
+        Err(outdated @ LoadError::Outdated(_)) => return Err(outdated),
+        Err(LoadError::Damaged(problem)) => problem,
+    };
+
+    // Keep the damaged file for a bug report, out of the way of the one that replaces it
+    let corrupt = sibling(path, "corrupt");
+    let _ = fs::rename(path, &corrupt);
+    let backup = sibling(path, "bak");
+    match read_versioned::<T>(&backup, version) {
+        Ok(Some((value, raw))) => {
+            log::warn!("{}; restored the previous copy from {} (the damaged file is {})", problem, backup.display(), corrupt.display());
+            if let Err(e) = write_atomic(path, raw) {
+                log::warn!("{}", e);
+            }
+            Ok(Some(value))
+        }
+        _ => {
+            log::warn!("{}; no backup to restore (the damaged file is {})", problem, corrupt.display());
+            Err(LoadError::Damaged(problem))
+        }
+    }
+}
+
+/// The value in `path` and the bytes it was read from
+fn read_versioned<T: DeserializeOwned>(path: &Path, version: u32) -> Result<Option<(T, Vec<u8>)>, LoadError> {
+    let raw = match fs::read(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(LoadError::Damaged(format!("Couldn't read {}: {}", path.display(), e))),
+    };
+    let json: Value = serde_json::from_slice(&raw)
+        .map_err(|e| LoadError::Damaged(format!("Failed to parse {}: {}", path.display(), e)))?;
+    let found = json.get(VERSION_KEY).and_then(Value::as_u64).unwrap_or(0);
+    if found > u64::from(version) {
+        return Err(LoadError::Damaged(format!("{} is version {}, newer than this game understands ({})", path.display(), found, version)));
+    }
+    match serde_json::from_value(json) {
+        Ok(value) => Ok(Some((value, raw))),
+        Err(e) if found < u64::from(version) => {
+            Err(LoadError::Outdated(format!("{} is version {} and doesn't fit version {}: {}", path.display(), found, version, e)))
+        }
+        Err(e) => Err(LoadError::Damaged(format!("Invalid data in {}: {}", path.display(), e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Sample {
+        #[serde(default)]
+        schema_version: u32,
+        name: String,
+    }
+
+    fn sample(name: &str) -> Sample {
+        Sample { schema_version: 0, name: name.to_string() }
+    }
+
+    #[test]
+    fn a_damaged_file_is_restored_from_its_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("progress.json");
+        save_json(&path, 1, &sample("first")).unwrap();
+        // Loading cleanly is what leaves the backup behind
+        assert_eq!(load_json::<Sample>(&path, 1).unwrap().map(|s| s.name), Some("first".to_string()));
+
+        fs::write(&path, "{\"name\": \"sec").unwrap();
+        assert_eq!(load_json::<Sample>(&path, 1).unwrap().map(|s| s.name), Some("first".to_string()));
+        assert_eq!(fs::read_to_string(sibling(&path, "corrupt")).unwrap(), "{\"name\": \"sec");
+        assert_eq!(fs::read(&path).unwrap(), fs::read(sibling(&path, "bak")).unwrap());
+    }
+
+    #[test]
+    fn a_damaged_file_without_a_backup_is_moved_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("progress.json");
+        fs::write(&path, "not json").unwrap();
+
+        assert!(matches!(load_json::<Sample>(&path, 1), Err(LoadError::Damaged(_))));
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(sibling(&path, "corrupt")).unwrap(), "not json");
+    }
+
+    #[test]
+    fn an_older_schema_that_no_longer_fits_is_outdated_and_left_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("progress.json");
+        let old = "{\"schema_version\": 1, \"title\": \"first\"}";
+        fs::write(&path, old).unwrap();
+
+        assert!(matches!(load_json::<Sample>(&path, 2), Err(LoadError::Outdated(_))));
+        assert_eq!(fs::read_to_string(&path).unwrap(), old);
+        assert!(!sibling(&path, "corrupt").exists());
+
+        // An older file that still fits loads, and one from a newer game is refused
+        save_json(&path, 1, &sample("first")).unwrap();
+        assert_eq!(load_json::<Sample>(&path, 2).unwrap().map(|s| s.name), Some("first".to_string()));
+        save_json(&path, 3, &sample("later")).unwrap();
+        fs::remove_file(sibling(&path, "bak")).unwrap();
+        assert!(matches!(load_json::<Sample>(&path, 2), Err(LoadError::Damaged(_))));
+    }
+}
@@ -0,0 +1,72 @@
+// Settings bundle: game_settings.json (editor options included), hotkeys_config.json and the
+// player's progress in one JSON file, for moving a setup to another machine. Settings > Export
+// writes settings_bundle.json next to the game and Import reads it back.
+//
+// Bundles carry a schema version and older ones are migrated when they're imported:
+//   1: a bare game_settings.json, the way settings were copied around before bundles existed
+//   2: {version, settings, hotkeys, progress}
+
+use crate::hotkeys::HotkeyConfig;
+use crate::menu::{GameSettings, PlayerProgress};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+pub const BUNDLE_FILE: &str = "settings_bundle.json";
+pub const BUNDLE_VERSION: u64 = 2;
+
+const HOTKEYS_FILE: &str = "hotkeys_config.json";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SettingsBundle {
+    pub version: u64,
+    pub settings: GameSettings,
+    #[serde(default)]
+    pub hotkeys: Option<HotkeyConfig>, // None keeps the bindings already on this machine
+    #[serde(default)]
+    pub progress: Option<PlayerProgress>,
+}
+
+impl SettingsBundle {
+    /// Everything the game has saved right now
+    pub fn collect(settings: &GameSettings, progress: &PlayerProgress) -> Self {
+        let hotkeys = fs::read_to_string(HOTKEYS_FILE).ok().and_then(|content| serde_json::from_str(&content).ok());
+        Self {
+            version: BUNDLE_VERSION,
+            settings: settings.clone(),
+            hotkeys,
+            progress: Some(progress.clone()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        crate::save_file::write_atomic(path, json)
+    }
+
+    /// Read a bundle of any known version, migrating it to the current one
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let value: Value = serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        serde_json::from_value(migrate(value)?).map_err(|e| format!("Invalid settings in {}: {}", path.display(), e))
+    }
+
+    /// Write the hotkeys of the bundle to hotkeys_config.json; settings and progress are the menu's
+    pub fn install_hotkeys(&self) -> Result<(), String> {
+        let Some(hotkeys) = &self.hotkeys else { return Ok(()) };
+        crate::save_file::save_json(Path::new(HOTKEYS_FILE), crate::hotkeys::CONFIG_VERSION, hotkeys)
+    }
+}
+
+/// Bring a bundle of an older schema version up to BUNDLE_VERSION
+fn migrate(mut value: Value) -> Result<Value, String> {
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(1);
+    if version > BUNDLE_VERSION {
+        return Err(format!("The settings bundle is version {}, newer than this game understands ({})", version, BUNDLE_VERSION));
+    }
+    if version < 2 {
+        value = serde_json::json!({ "version": 2, "settings": value });
+    }
+    Ok(value)
+}
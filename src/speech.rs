@@ -0,0 +1,97 @@
+// Text-to-speech for the accessibility mode: popups, task messages, execution results and the
+// focused menu button are read aloud when "Screen Reader" is on in Settings.
+//
+// Desktop builds speak through the platform engine with the `tts` feature (SAPI, AVFoundation,
+// speech-dispatcher); without it announcements only go to the log. The web build uses the
+// browser's SpeechSynthesis.
+
+use std::cell::RefCell;
+
+thread_local! {
+    static SPEAKER: RefCell<Speaker> = RefCell::new(Speaker::default());
+}
+
+#[derive(Default)]
+struct Speaker {
+    enabled: bool,
+    last: String, // Skip repeating the same text, e.g. a popup that's redrawn or re-shown
+    #[cfg(all(feature = "tts", not(target_arch = "wasm32")))]
+    engine: Option<tts::Tts>,
+}
+
+impl Speaker {
+    #[cfg(all(feature = "tts", not(target_arch = "wasm32")))]
+    fn speak(&mut self, text: &str) {
+        if self.engine.is_none() {
+            match tts::Tts::default() {
+                Ok(engine) => self.engine = Some(engine),
+                Err(e) => {
+                    log::warn!("Text-to-speech is unavailable: {}", e);
+                    self.enabled = false;
+                    return;
+                }
+            }
+        }
+        if let Some(engine) = self.engine.as_mut() {
+            if let Err(e) = engine.speak(text, true) {
+                log::warn!("Text-to-speech failed: {}", e);
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn speak(&mut self, text: &str) {
+        let Some(synthesis) = web_sys::window().and_then(|window| window.speech_synthesis().ok()) else {
+            return;
+        };
+        if let Ok(utterance) = web_sys::SpeechSynthesisUtterance::new_with_text(text) {
+            utterance.set_lang(crate::i18n::current_language().code());
+            synthesis.cancel();
+            synthesis.speak(&utterance);
+        }
+    }
+
+    #[cfg(not(any(feature = "tts", target_arch = "wasm32")))]
+    fn speak(&mut self, text: &str) {
+        log::info!("[speech] {}", text);
+    }
+}
+
+pub fn set_enabled(enabled: bool) {
+    SPEAKER.with(|speaker| {
+        let mut speaker = speaker.borrow_mut();
+        speaker.enabled = enabled;
+        speaker.last.clear();
+    });
+}
+
+pub fn is_enabled() -> bool {
+    SPEAKER.with(|speaker| speaker.borrow().enabled)
+}
+
+/// Read `text` aloud, interrupting whatever is being read. Does nothing while the mode is off.
+pub fn announce(text: &str) {
+    SPEAKER.with(|speaker| {
+        let mut speaker = speaker.borrow_mut();
+        if !speaker.enabled {
+            return;
+        }
+        let text = spoken_form(text);
+        if text.is_empty() || text == speaker.last {
+            return;
+        }
+        speaker.speak(&text);
+        speaker.last = text;
+    });
+}
+
+/// Drop markdown markup and emoji, which speech engines either skip or spell out, and put the
+/// rest on one line
+fn spoken_form(text: &str) -> String {
+    text.chars()
+        .filter(|c| !matches!(c, '#' | '*' | '`') && (c.is_ascii() || c.is_alphanumeric() || c.is_whitespace()))
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,281 @@
+// Steam integration for Steam builds (`--features steam`): level packs shared through the Workshop
+// and the game's level achievements mirrored to Steam achievements.
+//
+// This talks to the Steamworks SDK's flat C API (SDK 1.57) in steam_api, which ships next to the
+// executable together with steam_appid.txt. When Steam isn't running, init() fails and every other
+// call does nothing, so a Steam build still starts outside Steam.
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+type SteamApiCall = u64;
+type PublishedFileId = u64;
+type UgcUpdateHandle = u64;
+
+const RESULT_OK: i32 = 1;
+const WORKSHOP_FILE_TYPE_COMMUNITY: i32 = 0;
+const ITEM_STATE_INSTALLED: u32 = 4;
+// Callback ids of the call results the Workshop upload waits for
+const CREATE_ITEM_RESULT: i32 = 3403;
+const SUBMIT_ITEM_UPDATE_RESULT: i32 = 3404;
+
+// steam_api.h packs callback structs with pack(8) on Windows and pack(4) everywhere else, so on
+// Linux and macOS a u64 after an i32 sits at offset 4, not 8. The fields are packed there and only
+// ever read by value.
+#[cfg_attr(windows, repr(C))]
+#[cfg_attr(not(windows), repr(C, packed(4)))]
+#[derive(Clone, Copy)]
+struct CreateItemResult {
+    result: i32,
+    published_file_id: PublishedFileId,
+    needs_legal_agreement: bool,
+}
+
+#[cfg_attr(windows, repr(C))]
+#[cfg_attr(not(windows), repr(C, packed(4)))]
+#[derive(Clone, Copy)]
+struct SubmitItemUpdateResult {
+    result: i32,
+    needs_legal_agreement: bool,
+    published_file_id: PublishedFileId,
+}
+
+// Offsets and sizes of CreateItemResult_t and SubmitItemUpdateResult_t in the SDK headers
+#[cfg(not(windows))]
+const _: () = {
+    assert!(std::mem::offset_of!(CreateItemResult, published_file_id) == 4);
+    assert!(std::mem::offset_of!(CreateItemResult, needs_legal_agreement) == 12);
+    assert!(std::mem::size_of::<CreateItemResult>() == 16);
+    assert!(std::mem::offset_of!(SubmitItemUpdateResult, needs_legal_agreement) == 4);
+    assert!(std::mem::offset_of!(SubmitItemUpdateResult, published_file_id) == 8);
+    assert!(std::mem::size_of::<SubmitItemUpdateResult>() == 16);
+};
+#[cfg(windows)]
+const _: () = {
+    assert!(std::mem::offset_of!(CreateItemResult, published_file_id) == 8);
+    assert!(std::mem::offset_of!(CreateItemResult, needs_legal_agreement) == 16);
+    assert!(std::mem::size_of::<CreateItemResult>() == 24);
+    assert!(std::mem::offset_of!(SubmitItemUpdateResult, needs_legal_agreement) == 4);
+    assert!(std::mem::offset_of!(SubmitItemUpdateResult, published_file_id) == 8);
+    assert!(std::mem::size_of::<SubmitItemUpdateResult>() == 16);
+};
+
+#[cfg_attr(all(windows, target_pointer_width = "64"), link(name = "steam_api64"))]
+#[cfg_attr(not(all(windows, target_pointer_width = "64")), link(name = "steam_api"))]
+unsafe extern "C" {
+    fn SteamAPI_Init() -> bool;
+    fn SteamAPI_Shutdown();
+    fn SteamAPI_RunCallbacks();
+
+    fn SteamAPI_SteamUserStats_v012() -> *mut c_void;
+    fn SteamAPI_ISteamUserStats_RequestCurrentStats(stats: *mut c_void) -> bool;
+    fn SteamAPI_ISteamUserStats_SetAchievement(stats: *mut c_void, name: *const c_char) -> bool;
+    fn SteamAPI_ISteamUserStats_StoreStats(stats: *mut c_void) -> bool;
+
+    fn SteamAPI_SteamUtils_v010() -> *mut c_void;
+    fn SteamAPI_ISteamUtils_GetAppID(utils: *mut c_void) -> u32;
+    fn SteamAPI_ISteamUtils_IsAPICallCompleted(utils: *mut c_void, call: SteamApiCall, failed: *mut bool) -> bool;
+    fn SteamAPI_ISteamUtils_GetAPICallResult(utils: *mut c_void, call: SteamApiCall, result: *mut c_void, size: i32, callback: i32, failed: *mut bool) -> bool;
+
+    fn SteamAPI_SteamUGC_v017() -> *mut c_void;
+    fn SteamAPI_ISteamUGC_GetNumSubscribedItems(ugc: *mut c_void) -> u32;
+    fn SteamAPI_ISteamUGC_GetSubscribedItems(ugc: *mut c_void, ids: *mut PublishedFileId, max: u32) -> u32;
+    fn SteamAPI_ISteamUGC_GetItemState(ugc: *mut c_void, id: PublishedFileId) -> u32;
+    fn SteamAPI_ISteamUGC_GetItemInstallInfo(ugc: *mut c_void, id: PublishedFileId, size: *mut u64, folder: *mut c_char, folder_size: u32, timestamp: *mut u32) -> bool;
+    fn SteamAPI_ISteamUGC_CreateItem(ugc: *mut c_void, app_id: u32, file_type: i32) -> SteamApiCall;
+    fn SteamAPI_ISteamUGC_StartItemUpdate(ugc: *mut c_void, app_id: u32, id: PublishedFileId) -> UgcUpdateHandle;
+    fn SteamAPI_ISteamUGC_SetItemTitle(ugc: *mut c_void, handle: UgcUpdateHandle, title: *const c_char) -> bool;
+    fn SteamAPI_ISteamUGC_SetItemDescription(ugc: *mut c_void, handle: UgcUpdateHandle, description: *const c_char) -> bool;
+    fn SteamAPI_ISteamUGC_SetItemContent(ugc: *mut c_void, handle: UgcUpdateHandle, folder: *const c_char) -> bool;
+    fn SteamAPI_ISteamUGC_SubmitItemUpdate(ugc: *mut c_void, handle: UgcUpdateHandle, change_note: *const c_char) -> SteamApiCall;
+}
+
+static READY: AtomicBool = AtomicBool::new(false);
+
+/// Connect to the running Steam client; returns false (and leaves Steam features off) without one
+pub fn init() -> bool {
+    let ready = unsafe { SteamAPI_Init() };
+    if ready {
+        unsafe { SteamAPI_ISteamUserStats_RequestCurrentStats(SteamAPI_SteamUserStats_v012()) };
+        log::info!("Connected to Steam");
+    } else {
+        log::warn!("Steam isn't running; Workshop and Steam achievements are off");
+    }
+    READY.store(ready, Ordering::SeqCst);
+    ready
+}
+
+pub fn is_ready() -> bool {
+    READY.load(Ordering::SeqCst)
+}
+
+/// Let Steam deliver its callbacks; call once per frame
+pub fn run_callbacks() {
+    if is_ready() {
+        unsafe { SteamAPI_RunCallbacks() };
+    }
+}
+
+pub fn shutdown() {
+    if READY.swap(false, Ordering::SeqCst) {
+        unsafe { SteamAPI_Shutdown() };
+    }
+}
+
+/// Unlock the Steam achievement with API name `name` (set up in the Steamworks app admin)
+pub fn unlock_achievement(name: &str) {
+    if !is_ready() {
+        return;
+    }
+    let Ok(name) = CString::new(name) else { return };
+    unsafe {
+        let stats = SteamAPI_SteamUserStats_v012();
+        if SteamAPI_ISteamUserStats_SetAchievement(stats, name.as_ptr()) {
+            SteamAPI_ISteamUserStats_StoreStats(stats);
+        }
+    }
+}
+
+/// Copy the level packs of every installed Workshop item the player subscribes to into `dest`,
+/// named after the item so updates replace the old copy. Returns how many packs were copied.
+pub fn sync_workshop_packs(dest: &Path) -> usize {
+    if !is_ready() {
+        return 0;
+    }
+    let ugc = unsafe { SteamAPI_SteamUGC_v017() };
+    let count = unsafe { SteamAPI_ISteamUGC_GetNumSubscribedItems(ugc) };
+    let mut ids = vec![0; count as usize];
+    let count = unsafe { SteamAPI_ISteamUGC_GetSubscribedItems(ugc, ids.as_mut_ptr(), count) };
+    ids.truncate(count as usize);
+
+    let mut copied = 0;
+    for id in ids {
+        if unsafe { SteamAPI_ISteamUGC_GetItemState(ugc, id) } & ITEM_STATE_INSTALLED == 0 {
+            continue;
+        }
+        let Some(folder) = item_install_folder(ugc, id) else { continue };
+        let Some(pack) = find_pack(&folder) else {
+            log::warn!("Workshop item {} has no .{} file", id, crate::level::pack::PACK_EXTENSION);
+            continue;
+        };
+        let target = dest.join(format!("workshop_{}.{}", id, crate::level::pack::PACK_EXTENSION));
+        match std::fs::create_dir_all(dest).and_then(|_| std::fs::copy(&pack, &target)) {
+            Ok(_) => copied += 1,
+            Err(e) => log::warn!("Failed to copy Workshop item {}: {}", id, e),
+        }
+    }
+    copied
+}
+
+fn item_install_folder(ugc: *mut c_void, id: PublishedFileId) -> Option<PathBuf> {
+    let mut folder = vec![0 as c_char; 1024];
+    let (mut size, mut timestamp) = (0u64, 0u32);
+    let installed = unsafe {
+        SteamAPI_ISteamUGC_GetItemInstallInfo(ugc, id, &mut size, folder.as_mut_ptr(), folder.len() as u32, &mut timestamp)
+    };
+    if !installed {
+        return None;
+    }
+    let folder = unsafe { CStr::from_ptr(folder.as_ptr()) };
+    Some(PathBuf::from(folder.to_string_lossy().into_owned()))
+}
+
+fn find_pack(folder: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(folder).ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.extension().is_some_and(|ext| ext == crate::level::pack::PACK_EXTENSION))
+}
+
+/// Publish a level pack as a new Workshop item and return its id. Blocks until Steam answers.
+pub fn upload_pack(pack: &Path, title: &str, description: &str) -> Result<PublishedFileId, String> {
+    if !is_ready() && !init() {
+        return Err("Steam isn't running".to_string());
+    }
+    // Refuse packs the game itself would refuse to load
+    let problems = game_core::level::validate_level_file(pack);
+    if !problems.is_empty() {
+        return Err(format!("The pack doesn't pass validation:\n  - {}", problems.join("\n  - ")));
+    }
+
+    // The Workshop uploads a whole folder, so the pack gets one to itself
+    let content = tempfile::tempdir().map_err(|e| e.to_string())?;
+    let file_name = pack.file_name().ok_or("The pack has no file name")?;
+    std::fs::copy(pack, content.path().join(file_name)).map_err(|e| e.to_string())?;
+
+    let to_c = |text: &str| CString::new(text).map_err(|_| format!("'{}' contains a NUL character", text));
+    let (title, description) = (to_c(title)?, to_c(description)?);
+    let folder = to_c(&content.path().to_string_lossy())?;
+    let note = to_c("Uploaded from the game")?;
+
+    let id = unsafe {
+        let ugc = SteamAPI_SteamUGC_v017();
+        let app_id = SteamAPI_ISteamUtils_GetAppID(SteamAPI_SteamUtils_v010());
+
+        let created: CreateItemResult = wait_for_call(SteamAPI_ISteamUGC_CreateItem(ugc, app_id, WORKSHOP_FILE_TYPE_COMMUNITY), CREATE_ITEM_RESULT)?;
+        let result = created.result;
+        if result != RESULT_OK {
+            return Err(format!("Creating the Workshop item failed (Steam result {})", result));
+        }
+        if created.needs_legal_agreement {
+            log::warn!("Accept the Steam Workshop legal agreement so the item becomes visible");
+        }
+
+        let handle = SteamAPI_ISteamUGC_StartItemUpdate(ugc, app_id, created.published_file_id);
+        SteamAPI_ISteamUGC_SetItemTitle(ugc, handle, title.as_ptr());
+        SteamAPI_ISteamUGC_SetItemDescription(ugc, handle, description.as_ptr());
+        SteamAPI_ISteamUGC_SetItemContent(ugc, handle, folder.as_ptr());
+        let submitted: SubmitItemUpdateResult = wait_for_call(SteamAPI_ISteamUGC_SubmitItemUpdate(ugc, handle, note.as_ptr()), SUBMIT_ITEM_UPDATE_RESULT)?;
+        let result = submitted.result;
+        if result != RESULT_OK {
+            return Err(format!("Uploading the pack failed (Steam result {})", result));
+        }
+        created.published_file_id
+    };
+    Ok(id)
+}
+
+/// Poll a Steam API call until its result arrives. `T` must have the layout of the struct Steam
+/// documents for `callback`.
+unsafe fn wait_for_call<T>(call: SteamApiCall, callback: i32) -> Result<T, String> {
+    unsafe {
+        let utils = SteamAPI_SteamUtils_v010();
+        let mut failed = false;
+        for _ in 0..600 {
+            SteamAPI_RunCallbacks();
+            if SteamAPI_ISteamUtils_IsAPICallCompleted(utils, call, &mut failed) {
+                let mut result = std::mem::MaybeUninit::<T>::zeroed();
+                let ok = SteamAPI_ISteamUtils_GetAPICallResult(utils, call, result.as_mut_ptr().cast(), std::mem::size_of::<T>() as i32, callback, &mut failed);
+                if !ok || failed {
+                    return Err("Steam reported an error for the request".to_string());
+                }
+                return Ok(result.assume_init());
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        Err("Steam didn't answer within a minute".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_item_result_reads_the_bytes_steam_writes() {
+        // CreateItemResult_t as the SDK lays it out: k_EResultOK, item 0x0102030405060708, true.
+        // The offsets come from steam_api.h, not from our struct, so a wrong layout fails here.
+        let (id_offset, flag_offset, size) = if cfg!(windows) { (8, 16, 24) } else { (4, 12, 16) };
+        assert_eq!(std::mem::size_of::<CreateItemResult>(), size);
+        let mut bytes = vec![0u8; size];
+        bytes[..4].copy_from_slice(&RESULT_OK.to_ne_bytes());
+        bytes[id_offset..id_offset + 8].copy_from_slice(&0x0102030405060708u64.to_ne_bytes());
+        bytes[flag_offset] = 1;
+
+        let created: CreateItemResult = unsafe { std::ptr::read_unaligned(bytes.as_ptr().cast()) };
+        let (result, id, needs_agreement) = (created.result, created.published_file_id, created.needs_legal_agreement);
+        assert_eq!((result, id, needs_agreement), (RESULT_OK, 0x0102030405060708, true));
+    }
+}
@@ -0,0 +1,152 @@
+// Task evaluators from outside the binary. A YAML task can point `task_file:` at a script
+// (`script:path.py`), or with the `plugins` feature at a dynamic library (`plugin:path.so`), instead
+// of a built-in learning test; game-core's level::evaluator decides which task_files qualify. The
+// evaluator gets a TaskContext as JSON and answers with a TaskVerdict, so a new course can check its
+// own tasks without changing the game. Community levels never get to run one.
+//
+// Scripts read the context from stdin and print the verdict to stdout. Libraries export
+//     extern "C" fn task_plugin_api_version() -> u32
+//     extern "C" fn evaluate_task(context: *const c_char) -> *mut c_char
+//     extern "C" fn free_verdict(verdict: *mut c_char)
+// where the context and the verdict are NUL-terminated JSON.
+
+use crate::level::evaluator::TaskEvaluator;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Bumped whenever TaskContext or TaskVerdict change in a way old evaluators can't read
+pub const TASK_PLUGIN_API_VERSION: u32 = 1;
+
+/// An evaluator still running after this is stopped and the task counts as not passed
+const EVALUATOR_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What an evaluator gets to judge a task by
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskContext {
+    pub api_version: u32,
+    pub level: String,
+    pub task: String,
+    pub task_index: usize, // 0 for the level's first task
+    pub code: String,
+    pub stdout: Vec<String>,    // println! lines of the runs on this level so far
+    pub stderr: Vec<String>,    // eprintln! lines
+    pub game: serde_json::Value, // Robot position, turn, energy, enemies...; see Game::serde_state_document
+}
+
+/// An evaluator's answer
+#[derive(Debug, Clone, Deserialize)]
+pub struct TaskVerdict {
+    pub passed: bool,
+    #[serde(default)]
+    pub message: Option<String>, // Shown to the player, e.g. what's still missing
+}
+
+/// Whether `task_file` names an evaluator (`script:` or `plugin:`) rather than a built-in learning
+/// test or a test file
+pub fn is_plugin(task_file: &str) -> bool {
+    TaskEvaluator::parse(task_file).is_some()
+}
+
+/// Run the evaluator at `task_file` on `context`
+pub fn evaluate(task_file: &str, context: &TaskContext) -> Result<TaskVerdict, String> {
+    let evaluator = TaskEvaluator::parse(task_file)
+        .ok_or_else(|| format!("'{}' doesn't name a script: or plugin: evaluator", task_file))??;
+    let input = serde_json::to_string(context).map_err(|e| format!("Failed to write task context: {}", e))?;
+    let output = match evaluator {
+        TaskEvaluator::Script { path, interpreter } => run_script(interpreter, path, &input)?,
+        TaskEvaluator::Library(path) => evaluate_library(path, &input)?,
+    };
+    serde_json::from_str(output.trim())
+        .map_err(|e| format!("{} didn't answer with a verdict: {}", task_file, e))
+}
+
+fn run_script(interpreter: &str, path: &Path, input: &str) -> Result<String, String> {
+    let mut child = Command::new(interpreter)
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start {} with {}: {}", path.display(), interpreter, e))?;
+
+    // The pipes are fed and drained on their own threads, so a script that writes more than a pipe
+    // buffer holds (or never reads its context) can't block on us while we wait for it
+    if let Some(mut stdin) = child.stdin.take() {
+        let input = input.to_string();
+        // An evaluator that doesn't read its context closes the pipe early; that's fine
+        std::thread::spawn(move || { let _ = stdin.write_all(input.as_bytes()); });
+    }
+    let stdout = child.stdout.take().map(drain);
+    let stderr = child.stderr.take().map(drain);
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() > EVALUATOR_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("{} took longer than {}s", path.display(), EVALUATOR_TIMEOUT.as_secs()));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(20)),
+            Err(e) => return Err(format!("Failed to wait for {}: {}", path.display(), e)),
+        }
+    };
+
+    let collect = |reader: Option<JoinHandle<String>>| reader.and_then(|reader| reader.join().ok()).unwrap_or_default();
+    let (stdout, stderr) = (collect(stdout), collect(stderr));
+    if !status.success() {
+        return Err(format!("{} failed: {}", path.display(), stderr.trim()));
+    }
+    Ok(stdout)
+}
+
+/// Read a pipe to the end on a new thread
+fn drain(mut pipe: impl Read + Send + 'static) -> JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = pipe.read_to_end(&mut bytes);
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+#[cfg(feature = "plugins")]
+fn evaluate_library(path: &Path, input: &str) -> Result<String, String> {
+    use std::ffi::{c_char, CStr, CString};
+
+    let describe = |e: libloading::Error| format!("{}: {}", path.display(), e);
+    let input = CString::new(input).map_err(|e| format!("Task context contains a NUL byte: {}", e))?;
+
+    // Loading a library runs its code; task libraries are trusted like the level files naming them.
+    // A bare file name would be looked up on the system library path, so it's made explicit.
+    unsafe {
+        let library = libloading::Library::new(Path::new(".").join(path)).map_err(describe)?;
+        let api_version: libloading::Symbol<unsafe extern "C" fn() -> u32> =
+            library.get(b"task_plugin_api_version\0").map_err(describe)?;
+        let version = api_version();
+        if version != TASK_PLUGIN_API_VERSION {
+            return Err(format!("{} was written for task plugin API {}, the game speaks {}", path.display(), version, TASK_PLUGIN_API_VERSION));
+        }
+        let evaluate_task: libloading::Symbol<unsafe extern "C" fn(*const c_char) -> *mut c_char> =
+            library.get(b"evaluate_task\0").map_err(describe)?;
+        let free_verdict: libloading::Symbol<unsafe extern "C" fn(*mut c_char)> =
+            library.get(b"free_verdict\0").map_err(describe)?;
+
+        let verdict = evaluate_task(input.as_ptr());
+        if verdict.is_null() {
+            return Err(format!("{} returned no verdict", path.display()));
+        }
+        let text = CStr::from_ptr(verdict).to_string_lossy().into_owned();
+        free_verdict(verdict);
+        Ok(text)
+    }
+}
+
+#[cfg(not(feature = "plugins"))]
+fn evaluate_library(path: &Path, _input: &str) -> Result<String, String> {
+    Err(format!("{} is a dynamic library; loading those needs the game built with --features plugins", path.display()))
+}
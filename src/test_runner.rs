@@ -137,7 +137,7 @@ fn test_game_integration() {
     println!("  ℹ️  VSCode integration available: {}", vscode_available);
 
     // Test autocomplete update
-    game.current_code = "fn test() {}\nle".to_string();
+    game.current_code = "fn test() {}\nle".into();
     game.cursor_position = game.current_code.len();
     game.update_autocomplete();
 
@@ -165,14 +165,53 @@ fn test_hotkey_system() {
         println!("  ✅ Ctrl+S binding found: {:?}", action);
     }
 
+    // Test chords: the first stroke waits, the second finishes it
+    use crate::hotkeys::{HotkeyContext, HotkeyInput};
+    assert!(matches!(hotkey_system.process_input(KeyCode::K, true, false, false, HotkeyContext::Editor), HotkeyInput::Pending(_)));
+    let input = hotkey_system.process_input(KeyCode::U, true, false, false, HotkeyContext::Editor);
+    assert_eq!(input, HotkeyInput::Action(crate::hotkeys::EditorAction::Uncomment));
+    println!("  ✅ Ctrl+K Ctrl+U chord works");
+
     // Test custom binding
     hotkey_system.set_binding("Ctrl+K".to_string(), crate::hotkeys::EditorAction::Comment);
     let action = hotkey_system.get_action_for_input(KeyCode::K, true, false, false);
     assert!(action.is_some());
     println!("  ✅ Custom binding set and retrieved");
 
+    // Test conflicts: Ctrl+K from above now cuts off the Ctrl+K chords
+    let conflicts = hotkey_system.conflicts();
+    assert!(conflicts.iter().any(|conflict| conflict.bindings.iter().any(|hotkey| hotkey.combo == "Ctrl+K")));
+    hotkey_system.set_binding_in(HotkeyContext::Game, "Ctrl+D".to_string(), crate::hotkeys::EditorAction::RunCode);
+    assert!(!hotkey_system.conflicts().iter().any(|conflict| conflict.combo == "Ctrl+D"), "Editor and game bindings shouldn't clash");
+    hotkey_system.resolve_conflict(&conflicts[0], 0);
+    assert!(hotkey_system.conflicts().len() < conflicts.len());
+    println!("  ✅ Conflicts detected and resolved: {}", conflicts.len());
+
+    // Test importing .vimrc mappings; plain-key mappings stay Vim mode's own
+    let vimrc = std::env::temp_dir().join("robo_grid_test_vimrc");
+    std::fs::write(&vimrc, "set number\nnnoremap <silent> <C-s> :w<CR>\ninoremap <C-z> <Esc>u\nnmap <C-k><C-d> dd\nnnoremap Y y$\n").unwrap();
+    let imported = hotkey_system.import_vim_config(&vimrc.to_string_lossy()).unwrap();
+    let _ = std::fs::remove_file(&vimrc);
+    assert_eq!(imported, 3);
+    assert_eq!(hotkey_system.get_action_for_input(KeyCode::S, true, false, false), Some(crate::hotkeys::EditorAction::SaveFile));
+    println!("  ✅ Vim mappings imported: {}", imported);
+
+    // Test the Emacs preset and importing an Emacs init file
+    hotkey_system.apply_preset(crate::hotkeys::HotkeyPreset::Emacs);
+    assert!(hotkey_system.conflicts().is_empty(), "The Emacs preset shouldn't conflict");
+    assert_eq!(hotkey_system.get_action_for_input(KeyCode::A, true, false, false), Some(crate::hotkeys::EditorAction::MoveCursorLineStart));
+    assert_eq!(hotkey_system.get_action_for_input(KeyCode::K, true, false, false), Some(crate::hotkeys::EditorAction::KillLine));
+    let init_file = std::env::temp_dir().join("robo_grid_test_init.el");
+    std::fs::write(&init_file, "(setq inhibit-startup-screen t)\n(global-set-key (kbd \"C-c g\") 'goto-line)\n(global-set-key (kbd \"M-<f5>\") #'save-buffer)\n").unwrap();
+    let imported = hotkey_system.import_emacs_config(&init_file.to_string_lossy()).unwrap();
+    let _ = std::fs::remove_file(&init_file);
+    assert_eq!(imported, 2);
+    assert_eq!(hotkey_system.get_action_for_input(KeyCode::F5, false, false, true), Some(crate::hotkeys::EditorAction::SaveFile));
+    println!("  ✅ Emacs preset works, init file bindings imported: {}", imported);
+
     // Test reset to defaults
     hotkey_system.reset_to_defaults();
+    assert!(hotkey_system.conflicts().is_empty(), "Default bindings shouldn't conflict");
     let action = hotkey_system.get_action_for_input(KeyCode::Z, true, false, false);
     if let Some(action) = action {
         println!("  ✅ Reset to defaults works: Ctrl+Z = {:?}", action);
@@ -241,6 +280,15 @@ pub fn run_quick_smoke_test() {
         scanner_at: None,
         blockers: vec![],
         doors: vec![],
+        locked_doors: vec![],
+        switches: vec![],
+        gates: vec![],
+        emitters: vec![],
+        terrain: vec![],
+        spawners: vec![],
+        flanking: false,
+        win_condition: None,
+        real_time_limit_secs: None,
         enemies: vec![],
         items: vec![],
         tasks: vec![],
@@ -256,6 +304,23 @@ pub fn run_quick_smoke_test() {
         achievement_message: None,
         next_level_hint: None,
         completion_message: None,
+        energy: None,
+        crates: vec![],
+        plates: vec![],
+        conveyors: vec![],
+        spikes: vec![],
+        robots: vec![],
+        mirrors: vec![],
+        message_i18n: Default::default(),
+        ownership_view: false,
+        seed: None,
+        allowed_functions: None,
+        auto_grab: None,
+        tags: Vec::new(),
+        difficulty: None,
+        author: None,
+        version: None,
+        requires: Vec::new(),
     };
     let levels = vec![minimal_level];
     let rng = StdRng::from_seed([0; 32]);
@@ -0,0 +1,292 @@
+// Text storage for the code editor.
+//
+// Edits go into a rope, so typing stays cheap however long the file gets. Code that runs every
+// frame (drawing, the syntax check and autosave timers) reads lines, chunks and the revision
+// straight from the rope. Parsers and tutorial checks still want one &str, so the buffer also
+// derefs to str; that string is only rebuilt the first time it's read after an edit.
+//
+// Positions are byte offsets, like the cursor and selection in Game. Every method snaps them
+// to a char boundary first, so cursor math can never land inside a multi-byte character.
+// Columns and cursor steps count graphemes (what the player sees as one character), so an
+// accented letter or emoji made of several code points is moved over and deleted as a whole.
+
+use ropey::Rope;
+use std::cell::OnceCell;
+use std::fmt;
+use std::ops::{Deref, Range};
+use std::sync::atomic::{AtomicU64, Ordering};
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Clone, Default)]
+pub struct TextBuffer {
+    rope: Rope,
+    text: OnceCell<String>,
+    revision: u64,
+}
+
+impl TextBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.text.get_or_init(|| self.rope.to_string())
+    }
+
+    fn edited(&mut self) {
+        self.text = OnceCell::new();
+        self.revision = next_revision();
+    }
+
+    /// Changes with every edit and differs between buffers made separately, so an unchanged
+    /// revision means unchanged text without reading any of it
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn len(&self) -> usize {
+        self.rope.len_bytes()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rope.len_bytes() == 0
+    }
+
+    /// The text in the pieces the rope stores it in
+    pub fn chunks(&self) -> impl Iterator<Item = &str> {
+        self.rope.chunks()
+    }
+
+    // --- Edits ---
+
+    pub fn insert(&mut self, byte: usize, ch: char) {
+        let char_idx = self.char_at(byte);
+        self.rope.insert_char(char_idx, ch);
+        self.edited();
+    }
+
+    pub fn insert_str(&mut self, byte: usize, text: &str) {
+        let char_idx = self.char_at(byte);
+        self.rope.insert(char_idx, text);
+        self.edited();
+    }
+
+    pub fn push(&mut self, ch: char) {
+        self.insert(self.len(), ch);
+    }
+
+    pub fn remove_range(&mut self, range: Range<usize>) {
+        let start = self.char_at(range.start);
+        let end = self.char_at(range.end).max(start);
+        self.rope.remove(start..end);
+        self.edited();
+    }
+
+    // --- Char boundaries ---
+
+    fn char_at(&self, byte: usize) -> usize {
+        self.rope.byte_to_char(byte.min(self.rope.len_bytes()))
+    }
+
+    /// `byte`, moved back to the start of the character it falls in
+    pub fn floor_char_boundary(&self, byte: usize) -> usize {
+        self.rope.char_to_byte(self.char_at(byte))
+    }
+
+    /// Start of the grapheme before `byte`
+    pub fn prev_grapheme_boundary(&self, byte: usize) -> usize {
+        self.grapheme_boundary(byte, false)
+    }
+
+    /// End of the grapheme that starts at `byte`
+    pub fn next_grapheme_boundary(&self, byte: usize) -> usize {
+        self.grapheme_boundary(byte, true)
+    }
+
+    fn grapheme_boundary(&self, byte: usize, forward: bool) -> usize {
+        let byte = self.floor_char_boundary(byte);
+        // Graphemes never span lines, except "\r\n" which ends its line, so searching the
+        // line the step happens in is enough
+        let line = if forward { self.line_of(byte) } else { self.line_of(byte.saturating_sub(1)) };
+        let line_start = self.rope.line_to_byte(line);
+        let text = self.rope.line(line).to_string();
+        let mut cursor = GraphemeCursor::new(byte - line_start, text.len(), true);
+        let found = if forward { cursor.next_boundary(&text, 0) } else { cursor.prev_boundary(&text, 0) };
+        match found {
+            Ok(Some(boundary)) => line_start + boundary,
+            _ => byte,
+        }
+    }
+
+    // --- Lines and columns (columns count graphemes, not bytes) ---
+
+    /// Number of lines, counting an empty line after a trailing newline
+    pub fn line_count(&self) -> usize {
+        self.rope.len_lines()
+    }
+
+    pub fn line_of(&self, byte: usize) -> usize {
+        self.rope.byte_to_line(byte.min(self.rope.len_bytes()))
+    }
+
+    /// Text of `line` without its line break
+    pub fn line_text(&self, line: usize) -> String {
+        if line >= self.rope.len_lines() {
+            return String::new();
+        }
+        let mut text = self.rope.line(line).to_string();
+        if text.ends_with('\n') {
+            text.pop();
+            if text.ends_with('\r') {
+                text.pop();
+            }
+        }
+        text
+    }
+
+    /// The graphemes on `line`, one per editor column
+    pub fn line_graphemes(&self, line: usize) -> Vec<String> {
+        self.line_text(line).graphemes(true).map(str::to_string).collect()
+    }
+
+    /// Columns on `line`, not counting its line break
+    pub fn line_len(&self, line: usize) -> usize {
+        self.line_text(line).graphemes(true).count()
+    }
+
+    /// Line and column of `byte`
+    pub fn line_col(&self, byte: usize) -> (usize, usize) {
+        let byte = self.floor_char_boundary(byte);
+        let line = self.line_of(byte);
+        let text = self.line_text(line);
+        let offset = (byte - self.rope.line_to_byte(line)).min(text.len());
+        (line, text[..offset].graphemes(true).count())
+    }
+
+    /// Byte offset of a line and column, clamped to the end of that line
+    pub fn byte_at(&self, line: usize, col: usize) -> usize {
+        if line >= self.rope.len_lines() {
+            return self.rope.len_bytes();
+        }
+        let offset: usize = self.line_text(line).graphemes(true).take(col).map(str::len).sum();
+        self.rope.line_to_byte(line) + offset
+    }
+}
+
+impl Deref for TextBuffer {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for TextBuffer {
+    fn from(text: &str) -> Self {
+        Self { rope: Rope::from_str(text), text: OnceCell::new(), revision: next_revision() }
+    }
+}
+
+impl From<String> for TextBuffer {
+    fn from(text: String) -> Self {
+        let buffer = Self::from(text.as_str());
+        let _ = buffer.text.set(text);
+        buffer
+    }
+}
+
+impl PartialEq for TextBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.rope == other.rope
+    }
+}
+
+impl PartialEq<str> for TextBuffer {
+    fn eq(&self, other: &str) -> bool {
+        let mut rest = other;
+        self.len() == other.len() && self.chunks().all(|chunk| rest.strip_prefix(chunk).map(|after| rest = after).is_some())
+    }
+}
+
+impl fmt::Display for TextBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+impl fmt::Debug for TextBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_keep_the_string_view_in_sync() {
+        let mut buffer = TextBuffer::from("fn main() {\n}\n");
+        buffer.insert_str(12, "    move_bot(\"right\");\n");
+        buffer.remove_range(0..3);
+        let revision = buffer.revision();
+        buffer.push('x');
+        assert_ne!(buffer.revision(), revision);
+        assert_eq!(&*buffer, "main() {\n    move_bot(\"right\");\n}\nx");
+        assert!(buffer == *"main() {\n    move_bot(\"right\");\n}\nx");
+        assert!(buffer != *"main() {\n    move_bot(\"right\");\n}\ny");
+        assert_eq!(buffer.to_string(), buffer.as_str());
+    }
+
+    #[test]
+    fn positions_snap_to_char_boundaries() {
+        // 'é' is two bytes, '🤖' four
+        let mut buffer = TextBuffer::from("é🤖a");
+        assert_eq!(buffer.next_grapheme_boundary(0), 2);
+        assert_eq!(buffer.next_grapheme_boundary(2), 6);
+        assert_eq!(buffer.prev_grapheme_boundary(6), 2);
+        assert_eq!(buffer.floor_char_boundary(4), 2);
+
+        // Removing from "inside" the robot removes the whole robot
+        buffer.remove_range(3..6);
+        buffer.insert(1, 'ß');
+        assert_eq!(&*buffer, "ßéa");
+    }
+
+    #[test]
+    fn cursor_steps_over_whole_graphemes() {
+        // "e" + combining acute accent, then a flag made of two regional indicators
+        let text = "e\u{301}🇯🇵\nx";
+        let buffer = TextBuffer::from(text);
+        let flag_start = "e\u{301}".len();
+        let newline = flag_start + "🇯🇵".len();
+        assert_eq!(buffer.next_grapheme_boundary(0), flag_start);
+        assert_eq!(buffer.next_grapheme_boundary(flag_start), newline);
+        assert_eq!(buffer.prev_grapheme_boundary(newline), flag_start);
+        assert_eq!(buffer.prev_grapheme_boundary(newline + 1), newline);
+        assert_eq!(buffer.next_grapheme_boundary(text.len()), text.len());
+        assert_eq!(buffer.prev_grapheme_boundary(0), 0);
+        assert_eq!(buffer.line_len(0), 2);
+        assert_eq!(buffer.line_col(newline), (0, 2));
+    }
+
+    #[test]
+    fn line_and_column_use_graphemes() {
+        let buffer = TextBuffer::from("let s = \"ü\";\nscan();\r\n");
+        assert_eq!(buffer.line_count(), 3);
+        assert_eq!(buffer.line_len(0), 12);
+        assert_eq!(buffer.line_len(1), 7);
+
+        let end_of_first = buffer.byte_at(0, 99);
+        assert_eq!(end_of_first, "let s = \"ü\";".len());
+        assert_eq!(buffer.line_col(end_of_first), (0, 12));
+        assert_eq!(buffer.line_col(buffer.byte_at(1, 4)), (1, 4));
+        assert_eq!(buffer.line_of(end_of_first + 1), 1);
+    }
+}
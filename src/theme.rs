@@ -0,0 +1,164 @@
+// Color palettes for the grid, chosen in Settings. Besides the classic colors there's a
+// colorblind-safe palette (Okabe-Ito colors) and a high-contrast one.
+//
+// Enemy types used to be told apart by color alone, so every palette but Classic also marks
+// each type with its own letter.
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::grid::Enemy;
+use crate::level::EnemyDirection;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Palette {
+    #[default]
+    Classic,
+    ColorblindSafe,
+    HighContrast,
+}
+
+/// Which kind of enemy this is, as far as drawing it goes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnemyKind {
+    Chasing,   // Chase enemy that has spotted the robot
+    Searching, // Chase enemy that hasn't
+    Random,
+    Diagonal,
+    Circular,
+    Spiral,
+    Custom,    // Pattern loaded from a file
+    Unknown,
+    Horizontal,
+    Vertical,
+}
+
+impl EnemyKind {
+    pub fn of(enemy: &Enemy) -> Self {
+        match enemy.movement_pattern.as_deref() {
+            Some("chase") | Some("chase_smart") => {
+                let chasing = enemy.movement_data.get("is_chasing").and_then(|v| v.as_bool()).unwrap_or(true);
+                if chasing { EnemyKind::Chasing } else { EnemyKind::Searching }
+            }
+            Some("random") => EnemyKind::Random,
+            Some("diagonal") => EnemyKind::Diagonal,
+            Some("circular") => EnemyKind::Circular,
+            Some("spiral") => EnemyKind::Spiral,
+            Some(pattern) if pattern.starts_with("file:") => EnemyKind::Custom,
+            Some(_) => EnemyKind::Unknown,
+            None => match enemy.direction {
+                EnemyDirection::Horizontal => EnemyKind::Horizontal,
+                EnemyDirection::Vertical => EnemyKind::Vertical,
+            },
+        }
+    }
+
+    /// Letter drawn on the enemy so the kind doesn't depend on color
+    pub fn glyph(&self) -> &'static str {
+        match self {
+            EnemyKind::Chasing => "C",
+            EnemyKind::Searching => "S",
+            EnemyKind::Random => "R",
+            EnemyKind::Diagonal => "X",
+            EnemyKind::Circular => "O",
+            EnemyKind::Spiral => "@",
+            EnemyKind::Custom => "F",
+            EnemyKind::Unknown => "?",
+            EnemyKind::Horizontal => "H",
+            EnemyKind::Vertical => "V",
+        }
+    }
+}
+
+/// Colors for everything on the grid that isn't an enemy
+#[derive(Clone, Copy, Debug)]
+pub struct GridColors {
+    pub unexplored: Color,
+    pub floor: Color,
+    pub obstacle: Color,
+    pub item: Color,
+    pub robot: Color,
+}
+
+// Okabe-Ito palette, distinguishable with every common form of color blindness
+const OI_ORANGE: Color = Color::new(0.90, 0.62, 0.0, 1.0);
+const OI_SKY_BLUE: Color = Color::new(0.34, 0.71, 0.91, 1.0);
+const OI_GREEN: Color = Color::new(0.0, 0.62, 0.45, 1.0);
+const OI_YELLOW: Color = Color::new(0.94, 0.89, 0.26, 1.0);
+const OI_BLUE: Color = Color::new(0.0, 0.45, 0.70, 1.0);
+const OI_VERMILLION: Color = Color::new(0.84, 0.37, 0.0, 1.0);
+const OI_PURPLE: Color = Color::new(0.80, 0.47, 0.65, 1.0);
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [Palette::Classic, Palette::ColorblindSafe, Palette::HighContrast];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Palette::Classic => "Classic",
+            Palette::ColorblindSafe => "Colorblind-Safe",
+            Palette::HighContrast => "High Contrast",
+        }
+    }
+
+    /// The palette after this one, wrapping around
+    pub fn next(&self) -> Palette {
+        let index = Palette::ALL.iter().position(|p| p == self).unwrap_or(0);
+        Palette::ALL[(index + 1) % Palette::ALL.len()]
+    }
+
+    pub fn grid_colors(&self) -> GridColors {
+        match self {
+            Palette::Classic => GridColors { unexplored: BLACK, floor: GREEN, obstacle: WHITE, item: WHITE, robot: SKYBLUE },
+            Palette::ColorblindSafe => GridColors {
+                unexplored: BLACK,
+                floor: Color::new(0.35, 0.35, 0.38, 1.0),
+                obstacle: WHITE,
+                item: OI_YELLOW,
+                robot: OI_SKY_BLUE,
+            },
+            Palette::HighContrast => GridColors { unexplored: BLACK, floor: Color::new(0.15, 0.15, 0.15, 1.0), obstacle: WHITE, item: YELLOW, robot: WHITE },
+        }
+    }
+
+    pub fn enemy_color(&self, kind: EnemyKind) -> Color {
+        match self {
+            Palette::Classic => match kind {
+                EnemyKind::Chasing => ORANGE,
+                EnemyKind::Searching => BLUE,
+                EnemyKind::Random => MAGENTA,
+                EnemyKind::Diagonal => YELLOW,
+                EnemyKind::Circular => LIME,
+                EnemyKind::Spiral => PINK,
+                EnemyKind::Custom => PURPLE,
+                EnemyKind::Unknown => RED,
+                EnemyKind::Horizontal => GREEN,
+                EnemyKind::Vertical => DARKBLUE,
+            },
+            // Seven colors for ten kinds; the glyph tells the ones sharing a color apart
+            Palette::ColorblindSafe => match kind {
+                EnemyKind::Chasing => OI_VERMILLION,
+                EnemyKind::Searching => OI_BLUE,
+                EnemyKind::Random => OI_PURPLE,
+                EnemyKind::Diagonal => OI_YELLOW,
+                EnemyKind::Circular => OI_GREEN,
+                EnemyKind::Spiral => OI_ORANGE,
+                EnemyKind::Custom => OI_PURPLE,
+                EnemyKind::Unknown => WHITE,
+                EnemyKind::Horizontal => OI_SKY_BLUE,
+                EnemyKind::Vertical => OI_ORANGE,
+            },
+            Palette::HighContrast => match kind {
+                EnemyKind::Chasing | EnemyKind::Unknown => Color::new(1.0, 0.3, 0.3, 1.0),
+                EnemyKind::Searching | EnemyKind::Horizontal | EnemyKind::Vertical => Color::new(0.3, 0.9, 1.0, 1.0),
+                _ => Color::new(1.0, 0.5, 1.0, 1.0),
+            },
+        }
+    }
+
+    /// Letter for an enemy; Classic keeps the plain "E"
+    pub fn enemy_glyph(&self, kind: EnemyKind) -> &'static str {
+        match self {
+            Palette::Classic => "E",
+            _ => kind.glyph(),
+        }
+    }
+}
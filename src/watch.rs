@@ -0,0 +1,64 @@
+// Watch tab: the variables the program had when each robot action ran, so players can step
+// through a run and see how their data changed turn by turn. Pinned expressions are worked out
+// again at every step.
+
+use game_core::ast::Environment;
+
+#[derive(Clone, Debug, Default)]
+pub struct WatchPanel {
+    pub environments: Vec<Environment>, // One per robot action of the last run
+    pub step: usize,                    // Action whose variables are shown
+    pub pins: Vec<String>,              // Expressions pinned by the player
+    pub input: String,                  // Expression being typed
+}
+
+impl WatchPanel {
+    /// Start showing a new run; pins are kept
+    pub fn start_run(&mut self, environments: Vec<Environment>) {
+        self.environments = environments;
+        self.step = 0;
+    }
+
+    pub fn current(&self) -> Option<&Environment> {
+        self.environments.get(self.step)
+    }
+
+    pub fn select_previous(&mut self) {
+        self.step = self.step.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self) {
+        if self.step + 1 < self.environments.len() {
+            self.step += 1;
+        }
+    }
+
+    /// Pin the typed expression
+    pub fn pin_input(&mut self) {
+        let expression = self.input.trim().to_string();
+        if !expression.is_empty() && !self.pins.contains(&expression) {
+            self.pins.push(expression);
+        }
+        self.input.clear();
+    }
+
+    /// Backspace edits the typed expression, and removes the last pin once it's empty
+    pub fn backspace(&mut self) {
+        if self.input.pop().is_none() {
+            self.pins.pop();
+        }
+    }
+
+    /// Every pin with its value at the current step
+    pub fn pinned_values(&self) -> Vec<(String, String)> {
+        self.pins.iter()
+            .map(|pin| {
+                let value = match self.current() {
+                    Some(environment) => environment.evaluate(pin).unwrap_or_else(|e| e),
+                    None => "?".to_string(),
+                };
+                (pin.clone(), value)
+            })
+            .collect()
+    }
+}